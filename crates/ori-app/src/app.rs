@@ -1,25 +1,32 @@
-use std::{any::Any, collections::HashMap};
+use std::{
+    any::Any,
+    collections::{HashMap, HashSet},
+    time::Duration,
+};
 
 use instant::Instant;
 use ori_core::{
-    canvas::{Canvas, Color},
+    canvas::{Canvas, Color, ColorSpace, Damage},
     command::{CommandProxy, CommandReceiver},
     context::{BaseCx, BuildCx, Contexts, DrawCx, EventCx, LayoutCx, RebuildCx},
     event::{
         Code, Event, FocusTarget, Ime, Key, KeyPressed, KeyReleased, Modifiers, PointerButton,
         PointerId, PointerLeft, PointerMoved, PointerPressed, PointerReleased, PointerScrolled,
-        RequestFocus, RequestFocusNext, RequestFocusPrev, WindowCloseRequested, WindowMaximized,
-        WindowResized, WindowScaled,
+        PointerTool, RequestFocus, RequestFocusNext, RequestFocusPrev, ScrollDelta,
+        WindowCloseRequested, WindowFocusChanged, WindowMaximized, WindowResized, WindowScaled,
     },
-    layout::{Point, Size, Space, Vector},
+    layout::{Point, Rect, Size, Space, Vector},
     log::trace,
     style::{Styles, Theme},
     view::{any, AnyState, BoxedView, View, ViewState},
     views::opaque,
-    window::{Cursor, Window, WindowId, WindowSizing, WindowSnapshot, WindowUpdate},
+    window::{Cursor, Popup, Window, WindowId, WindowSizing, WindowSnapshot, WindowUpdate},
 };
 
-use crate::{AppBuilder, AppCommand, AppDelegate, AppRequest, DelegateCx, UiBuilder};
+use crate::{
+    record::{AppInput, Recorder, Replay},
+    AppBuilder, AppCommand, AppDelegate, AppRequest, ControlFlow, DelegateCx, UiBuilder,
+};
 
 /// Information needed to render a window.
 pub struct WindowRenderState {
@@ -29,8 +36,21 @@ pub struct WindowRenderState {
     /// The size of the window.
     pub logical_size: Size,
 
+    /// The scale of the user interface, the canvas should be rendered with the scale of the
+    /// window multiplied by this.
+    pub ui_scale: f32,
+
     /// The clear color of the window.
     pub clear_color: Color,
+
+    /// The color space to render the window in.
+    pub color_space: ColorSpace,
+
+    /// The region of the window that changed since the last frame, in logical coordinates.
+    ///
+    /// Only the damaged region needs to be rendered if the previous frame is still presented
+    /// outside of it, otherwise the whole window must be rendered.
+    pub damage: Damage,
 }
 
 pub(crate) struct WindowState<T> {
@@ -44,9 +64,14 @@ pub(crate) struct WindowState<T> {
     window: Window,
     snapshot: WindowSnapshot,
     animate: Option<Instant>,
+    last_frame: Option<Instant>,
 }
 
 impl<T> WindowState<T> {
+    pub(crate) fn window(&self) -> &Window {
+        &self.window
+    }
+
     fn rebuild(&mut self, data: &mut T, base: &mut BaseCx) {
         let t = Instant::now();
 
@@ -106,7 +131,7 @@ impl<T> WindowState<T> {
         // we need to calculate the max size of the window
         // depending on the sizing of the window
         let max_size = match self.window.sizing {
-            WindowSizing::Fixed => self.window.size,
+            WindowSizing::Fixed => self.window.content_size(),
             WindowSizing::Content => Size::INFINITY,
         };
 
@@ -126,7 +151,7 @@ impl<T> WindowState<T> {
                 ori_core::log::warn!("Window content size is non-finite.");
             }
 
-            self.window.size = size;
+            self.window.size = size * self.window.ui_scale;
         }
 
         trace!(
@@ -136,9 +161,10 @@ impl<T> WindowState<T> {
         );
     }
 
-    fn draw(&mut self, data: &mut T, base: &mut BaseCx) {
+    fn draw(&mut self, data: &mut T, base: &mut BaseCx) -> Damage {
         let t = Instant::now();
 
+        let root_damaged = self.view_state.is_damaged();
         self.view_state.mark_drawn();
 
         self.canvas.clear();
@@ -146,14 +172,34 @@ impl<T> WindowState<T> {
         let mut cx = DrawCx::new(base, &mut self.view_state, &mut self.canvas);
 
         cx.insert_context(self.window.clone());
+        cx.insert_context(Damage::None);
         self.view.draw(&mut self.state, &mut cx, data);
+        let mut damage = cx.remove_context().unwrap_or(Damage::Full);
         self.window = cx.remove_context().expect("Window context missing");
 
+        // if the draw wasn't requested by a view inside a pod, we don't know what changed
+        if root_damaged || damage == Damage::None {
+            damage.invalidate();
+        }
+
         trace!(
             window = ?self.window.id(),
             elapsed = ?t.elapsed(),
             "Window draw"
         );
+
+        damage
+    }
+
+    // how long to wait before drawing the next frame, to keep to `Window::max_fps`
+    fn frame_delay(&self) -> Duration {
+        match (self.window.max_fps, self.last_frame) {
+            (Some(max_fps), Some(last_frame)) if max_fps > 0 => {
+                let interval = Duration::from_secs_f64(1.0 / max_fps as f64);
+                interval.saturating_sub(last_frame.elapsed())
+            }
+            _ => Duration::ZERO,
+        }
     }
 
     fn animate(&mut self, animate: Instant) -> Vec<AppRequest<T>> {
@@ -174,11 +220,54 @@ pub struct App<T> {
     /// The contexts of the application.
     pub contexts: Contexts,
 
+    pub(crate) app_id: String,
     pub(crate) windows: HashMap<WindowId, WindowState<T>>,
+    // the index of every open window, in the order windows were added, see `AppInput`
+    pub(crate) window_indices: HashMap<WindowId, usize>,
+    pub(crate) windows_added: usize,
     pub(crate) modifiers: Modifiers,
+    pub(crate) held_keys: HashSet<(WindowId, HeldKey)>,
+    pub(crate) left_pointers: HashSet<(WindowId, PointerId)>,
+    // the windows showing popups drawn by views, with their parent and index, see `DrawCx::popup`
+    pub(crate) view_popups: HashMap<WindowId, (WindowId, usize)>,
+    pub(crate) monitors: Vec<Rect>,
+    pub(crate) suspended: bool,
     pub(crate) delegates: Vec<Box<dyn AppDelegate<T>>>,
     pub(crate) receiver: CommandReceiver,
     pub(crate) requests: Vec<AppRequest<T>>,
+    pub(crate) recorder: Option<Recorder>,
+    pub(crate) replay: Option<Replay>,
+    pub(crate) quit_timeout: Duration,
+    pub(crate) quit: QuitState,
+    pub(crate) needs_rebuild: bool,
+}
+
+// the progress of quitting, the platform is only asked to quit once every delegate has
+// cleaned up, or the timeout runs out
+pub(crate) enum QuitState {
+    Running,
+    CleaningUp { pending: usize, deadline: Instant },
+    Quit,
+}
+
+// sent when a future returned by `AppDelegate::quit_requested` completes
+struct QuitCleanupDone;
+
+// a key held down, by its physical code when the platform reports one, as the logical key can
+// change while the key is held, eg. when shift is released before it
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum HeldKey {
+    Code(Code),
+    Key(Key),
+}
+
+impl HeldKey {
+    fn new(key: Key, code: Option<Code>) -> Self {
+        match code {
+            Some(code) => HeldKey::Code(code),
+            None => HeldKey::Key(key),
+        }
+    }
 }
 
 impl<T> App<T> {
@@ -187,6 +276,70 @@ impl<T> App<T> {
         AppBuilder::new()
     }
 
+    /// Get the id of the application, see [`AppBuilder::app_id`].
+    pub fn app_id(&self) -> &str {
+        &self.app_id
+    }
+
+    /// Check if the application is suspended, see [`App::suspend`].
+    pub fn is_suspended(&self) -> bool {
+        self.suspended
+    }
+
+    /// The application was suspended, eg. it was moved to the background on mobile.
+    ///
+    /// This calls [`AppDelegate::suspended`] and sends [`Event::Suspended`] to every window,
+    /// suspending an application that is already suspended does nothing.
+    pub fn suspend(&mut self, data: &mut T) {
+        if self.suspended {
+            return;
+        }
+
+        self.suspended = true;
+        self.lifecycle_event(data, Event::Suspended);
+    }
+
+    /// The application was resumed after being suspended, see [`App::suspend`].
+    ///
+    /// This calls [`AppDelegate::resumed`] and sends [`Event::Resumed`] to every window.
+    pub fn resume(&mut self, data: &mut T) {
+        if !self.suspended {
+            return;
+        }
+
+        self.suspended = false;
+        self.lifecycle_event(data, Event::Resumed);
+    }
+
+    fn lifecycle_event(&mut self, data: &mut T, event: Event) {
+        let mut rebuild = false;
+        let mut base = BaseCx::new(&mut self.contexts, &mut self.proxy);
+
+        for delegate in &mut self.delegates {
+            let mut cx =
+                DelegateCx::new(&mut base, &self.windows, &mut self.requests, &mut rebuild);
+
+            match event {
+                Event::Suspended => delegate.suspended(&mut cx, data),
+                _ => delegate.resumed(&mut cx, data),
+            }
+        }
+
+        // the delegates have been notified through their hooks, so the event only goes to
+        // the windows
+        for window_state in self.windows.values_mut() {
+            let mut base = BaseCx::new(&mut self.contexts, &mut self.proxy);
+            let _ = window_state.event(data, &mut base, &mut rebuild, &event);
+        }
+
+        if rebuild {
+            self.request_rebuild();
+        }
+
+        self.handle_commands(data);
+        self.handle_window_requests();
+    }
+
     /// A window was requested to be closed.
     ///
     /// Returns `true` if the window was closed, i.e. the event was not handled.
@@ -196,11 +349,11 @@ impl<T> App<T> {
         let handled = self.window_event(data, window_id, &event);
 
         if !handled {
-            self.remove_window(window_id);
+            self.remove_window(data, window_id);
             self.requests.push(AppRequest::CloseWindow(window_id));
 
             if self.windows.is_empty() {
-                self.requests.push(AppRequest::Quit);
+                self.quit(data);
             }
         }
 
@@ -215,6 +368,12 @@ impl<T> App<T> {
         width: u32,
         height: u32,
     ) -> bool {
+        self.record(window_id, |window| AppInput::WindowResized {
+            window,
+            width,
+            height,
+        });
+
         if let Some(window_state) = self.windows.get_mut(&window_id) {
             window_state.view_state.request_layout();
             window_state.window.size = Size::new(width as f32, height as f32);
@@ -232,15 +391,36 @@ impl<T> App<T> {
 
     /// A window was scaled.
     pub fn window_scaled(&mut self, data: &mut T, window_id: WindowId, scale: f32) -> bool {
-        if let Some(window_state) = self.windows.get_mut(&window_id) {
-            window_state.view_state.request_layout();
-            window_state.window.scale = scale;
-            window_state.snapshot.scale = scale;
-        }
+        let Some(window_state) = self.windows.get_mut(&window_id) else {
+            return false;
+        };
+
+        window_state.view_state.request_layout();
+        window_state.window.scale = scale;
+        window_state.snapshot.scale = scale;
+
+        // the event carries the same scale as the one sent by `App::set_ui_scale`
+        let event = Event::WindowScaled(WindowScaled {
+            window: window_id,
+            scale_factor: window_state.window.render_scale(),
+        });
+
+        self.window_event(data, window_id, &event)
+    }
+
+    /// Set the scale of the user interface of a window, see [`Window::ui_scale`].
+    pub fn set_ui_scale(&mut self, data: &mut T, window_id: WindowId, ui_scale: f32) -> bool {
+        let Some(window_state) = self.windows.get_mut(&window_id) else {
+            return false;
+        };
+
+        window_state.view_state.request_layout();
+        window_state.window.set_ui_scale(ui_scale);
+        window_state.snapshot.ui_scale = window_state.window.ui_scale;
 
         let event = Event::WindowScaled(WindowScaled {
             window: window_id,
-            scale_factor: scale,
+            scale_factor: window_state.window.render_scale(),
         });
 
         self.window_event(data, window_id, &event)
@@ -262,6 +442,25 @@ impl<T> App<T> {
         self.window_event(data, window_id, &event)
     }
 
+    /// The focus state of a window changed.
+    pub fn window_focused(&mut self, data: &mut T, window_id: WindowId, focused: bool) -> bool {
+        if let Some(window_state) = self.windows.get_mut(&window_id) {
+            window_state.window.focused = focused;
+        }
+
+        // keys released while the window isn't focused are never reported
+        if !focused {
+            self.held_keys.retain(|&(id, _)| id != window_id);
+        }
+
+        let event = Event::WindowFocusChanged(WindowFocusChanged {
+            window: window_id,
+            focused,
+        });
+
+        self.window_event(data, window_id, &event)
+    }
+
     /// The decorations state of a window changed.
     pub fn window_decorated(&mut self, data: &mut T, window_id: WindowId, decorated: bool) -> bool {
         if let Some(window_state) = self.windows.get_mut(&window_id) {
@@ -283,18 +482,41 @@ impl<T> App<T> {
         pointer_id: PointerId,
         position: Point,
     ) -> bool {
+        if let Some((parent, offset)) = self.view_popup_offset(window_id) {
+            return self.pointer_moved(data, parent, pointer_id, position + offset);
+        }
+
+        self.record(window_id, |window| AppInput::PointerMoved {
+            window,
+            pointer: pointer_id,
+            position,
+        });
+
+        self.flush_rebuild(data);
+
+        // the pointer is back in the window
+        self.left_pointers.remove(&(window_id, pointer_id));
+
         let Some(window_state) = self.windows.get_mut(&window_id) else {
             return false;
         };
 
+        // the content is laid out in a space scaled by the ui scale
+        let position = position / window_state.window.ui_scale;
+
         let delta = window_state.window.move_pointer(pointer_id, position);
         self.update_hovered(window_id);
 
+        let (pressure, tilt, tool) = self.pointer_stylus_state(window_id, pointer_id);
+
         let event = Event::PointerMoved(PointerMoved {
             id: pointer_id,
             modifiers: self.modifiers,
             position,
             delta,
+            pressure,
+            tilt,
+            tool,
         });
 
         self.window_event(data, window_id, &event)
@@ -307,31 +529,104 @@ impl<T> App<T> {
         window_id: WindowId,
         pointer_id: PointerId,
     ) -> bool {
+        if let Some((parent, _)) = self.view_popup_offset(window_id) {
+            return self.pointer_left(data, parent, pointer_id);
+        }
+
+        self.record(window_id, |window| AppInput::PointerLeft {
+            window,
+            pointer: pointer_id,
+        });
+
         let Some(window_state) = self.windows.get_mut(&window_id) else {
             return false;
         };
 
-        window_state.window.remove_pointer(pointer_id);
+        // a captured pointer keeps being tracked, since the capturing view still follows it,
+        // until it's released
+        match window_state.window.pointer_capture(pointer_id) {
+            Some(_) => {
+                self.left_pointers.insert((window_id, pointer_id));
+            }
+            None => window_state.window.remove_pointer(pointer_id),
+        }
 
         let event = Event::PointerLeft(PointerLeft { id: pointer_id });
 
         self.window_event(data, window_id, &event)
     }
 
+    /// The pressure, tilt or tool of a pointer changed.
+    ///
+    /// This doesn't emit an event, the new state is reported by the next pointer event.
+    pub fn pointer_stylus(
+        &mut self,
+        window_id: WindowId,
+        pointer_id: PointerId,
+        pressure: f32,
+        tilt: Vector,
+        tool: PointerTool,
+    ) {
+        self.record(window_id, |window| AppInput::PointerStylus {
+            window,
+            pointer: pointer_id,
+            pressure,
+            tilt,
+            tool,
+        });
+
+        let Some(window_state) = self.windows.get_mut(&window_id) else {
+            return;
+        };
+
+        if let Some(pointer) = window_state.window.get_pointer_mut(pointer_id) {
+            pointer.pressure = pressure;
+            pointer.tilt = tilt;
+            pointer.tool = tool;
+        }
+    }
+
     fn pointer_position(&self, window_id: WindowId, pointer_id: PointerId) -> Option<Point> {
         let window = self.get_window(window_id)?;
         let pointer = window.get_pointer(pointer_id)?;
         Some(pointer.position)
     }
 
+    fn pointer_stylus_state(
+        &self,
+        window_id: WindowId,
+        pointer_id: PointerId,
+    ) -> (f32, Vector, PointerTool) {
+        let pointer = self
+            .get_window(window_id)
+            .and_then(|window| window.get_pointer(pointer_id));
+
+        match pointer {
+            Some(pointer) => (pointer.pressure, pointer.tilt, pointer.tool),
+            None => (1.0, Vector::ZERO, PointerTool::Mouse),
+        }
+    }
+
     /// A pointer scrolled.
     pub fn pointer_scrolled(
         &mut self,
         data: &mut T,
         window_id: WindowId,
         pointer_id: PointerId,
-        delta: Vector,
+        delta: impl Into<ScrollDelta>,
     ) -> bool {
+        let delta = delta.into();
+
+        if let Some((parent, _)) = self.view_popup_offset(window_id) {
+            return self.pointer_scrolled(data, parent, pointer_id, delta);
+        }
+
+        self.record(window_id, |window| AppInput::PointerScrolled {
+            window,
+            pointer: pointer_id,
+            delta,
+        });
+
         let position = self
             .pointer_position(window_id, pointer_id)
             .unwrap_or(Point::ZERO);
@@ -355,11 +650,37 @@ impl<T> App<T> {
         button: PointerButton,
         pressed: bool,
     ) -> bool {
+        if let Some((parent, _)) = self.view_popup_offset(window_id) {
+            return self.pointer_button(data, parent, pointer_id, button, pressed);
+        }
+
+        self.record(window_id, |window| AppInput::PointerButton {
+            window,
+            pointer: pointer_id,
+            button,
+            pressed,
+        });
+
         let position = self
             .pointer_position(window_id, pointer_id)
             .unwrap_or(Point::ZERO);
 
+        let (pressure, tilt, tool) = self.pointer_stylus_state(window_id, pointer_id);
+
+        self.flush_rebuild(data);
+
         if pressed {
+            // a popup that grabs the pointer also sees presses outside of it, which close it
+            let popup = self.get_window(window_id).and_then(|window| {
+                let rect = Rect::min_size(Point::ZERO, window.content_size());
+                window.popup.filter(|_| !rect.contains(position))
+            });
+
+            match popup {
+                Some(popup) => self.dismiss_popups(popup.parent),
+                None => self.dismiss_popups(window_id),
+            }
+
             if let Some(window_state) = self.windows.get_mut(&window_id) {
                 window_state.window.press_pointer(pointer_id, button);
             }
@@ -369,6 +690,9 @@ impl<T> App<T> {
                 modifiers: self.modifiers,
                 position,
                 button,
+                pressure,
+                tilt,
+                tool,
             });
 
             self.window_event(data, window_id, &event)
@@ -383,9 +707,26 @@ impl<T> App<T> {
                 clicked,
                 position,
                 button,
+                pressure,
+                tilt,
+                tool,
             });
 
-            self.window_event(data, window_id, &event)
+            let handled = self.window_event(data, window_id, &event);
+
+            // the capture ends with the release, after the capturing view has seen it
+            if let Some(window_state) = self.windows.get_mut(&window_id) {
+                if let Some(pointer) = window_state.window.get_pointer_mut(pointer_id) {
+                    pointer.capture = None;
+                }
+
+                // a pointer that left while captured is only removed now
+                if self.left_pointers.remove(&(window_id, pointer_id)) {
+                    window_state.window.remove_pointer(pointer_id);
+                }
+            }
+
+            handled
         }
     }
 
@@ -399,17 +740,34 @@ impl<T> App<T> {
         text: Option<String>,
         pressed: bool,
     ) -> bool {
+        self.record(window_id, |window| AppInput::KeyboardKey {
+            window,
+            key,
+            code,
+            text: text.clone(),
+            pressed,
+        });
+
+        self.flush_rebuild(data);
+
         if pressed {
+            // a press of a key that is already held down is an automatic repeat
+            let repeat = !self.held_keys.insert((window_id, HeldKey::new(key, code)));
+
             let event = Event::KeyPressed(KeyPressed {
                 key,
                 code,
                 text,
                 modifiers: self.modifiers,
+                repeat,
             });
 
             let mut handled = self.window_event(data, window_id, &event);
 
-            if let (Some(window), Key::Tab) = (self.windows.get(&window_id), key) {
+            // tab moves the focus unless a view handled it, eg. ctrl+tab switching tabs
+            let moves_focus = key == Key::Tab && !handled;
+
+            if let (Some(window), true) = (self.windows.get(&window_id), moves_focus) {
                 let event = match window.view_state.has_focused() {
                     true if self.modifiers.shift => Event::FocusPrev,
                     false if self.modifiers.shift => Event::FocusGiven(FocusTarget::Prev),
@@ -421,22 +779,54 @@ impl<T> App<T> {
                 handled |= self.window_event(data, window_id, &event);
             }
 
+            if !handled {
+                handled = self.delegate_unhandled_event(data, &event);
+            }
+
+            // ctrl with plus, minus or zero zooms the user interface, like in a browser
+            if !handled && self.modifiers.ctrl {
+                if let Some(ui_scale) = self.zoomed_ui_scale(window_id, key) {
+                    self.set_ui_scale(data, window_id, ui_scale);
+                    handled = true;
+                }
+            }
+
             handled
         } else {
+            self.held_keys.remove(&(window_id, HeldKey::new(key, code)));
+
             let event = Event::KeyReleased(KeyReleased {
                 key,
                 code,
                 modifiers: self.modifiers,
             });
 
-            self.window_event(data, window_id, &event)
+            let handled = self.window_event(data, window_id, &event);
+
+            if !handled {
+                return self.delegate_unhandled_event(data, &event);
+            }
+
+            handled
         }
     }
 
     /// The modifiers changed.
     pub fn modifiers_changed(&mut self, modifiers: Modifiers) {
+        self.record_modifiers(modifiers);
         self.modifiers = modifiers;
     }
+
+    fn zoomed_ui_scale(&self, window_id: WindowId, key: Key) -> Option<f32> {
+        let ui_scale = self.windows.get(&window_id)?.window.ui_scale;
+
+        match key {
+            Key::Character('+' | '=') => Some(ui_scale * 1.1),
+            Key::Character('-') => Some(ui_scale / 1.1),
+            Key::Character('0') => Some(1.0),
+            _ => None,
+        }
+    }
 }
 
 impl<T> App<T> {
@@ -467,14 +857,167 @@ impl<T> App<T> {
             window,
             snapshot,
             animate: None,
+            last_frame: None,
         };
 
         self.windows.insert(window_id, window_state);
+        self.window_indices.insert(window_id, self.windows_added);
+        self.windows_added += 1;
     }
 
     /// Remove a window from the application.
-    pub fn remove_window(&mut self, window_id: WindowId) {
-        self.windows.remove(&window_id);
+    ///
+    /// This drops the view tree and the canvas of the window, releasing every image they
+    /// reference, renderers free the textures of images that are no longer referenced when
+    /// they are cleaned. Removing a window that doesn't exist does nothing.
+    ///
+    /// The popups of the window are closed along with it, see [`Window::popup`]. The placement
+    /// of the window is saved first, see [`AppDelegate::save_window_placement`].
+    pub fn remove_window(&mut self, data: &mut T, window_id: WindowId) {
+        self.save_window_placement(data, window_id);
+        self.drop_window(window_id);
+    }
+
+    // remove a window without saving its placement, popups are never saved
+    fn drop_window(&mut self, window_id: WindowId) {
+        let view_popup = self.view_popups.remove(&window_id);
+
+        if self.windows.remove(&window_id).is_none() && view_popup.is_none() {
+            return;
+        }
+
+        self.window_indices.remove(&window_id);
+        self.held_keys.retain(|&(id, _)| id != window_id);
+        self.left_pointers.retain(|&(id, _)| id != window_id);
+
+        let mut popups: Vec<_> = (self.windows.values())
+            .filter(|w| w.window.popup.is_some_and(|p| p.parent == window_id))
+            .map(|w| w.window.id())
+            .collect();
+
+        // view popups that the platform hasn't opened yet aren't windows of the app
+        for (&id, &(parent, _)) in &self.view_popups {
+            if parent == window_id && !popups.contains(&id) {
+                popups.push(id);
+            }
+        }
+
+        for popup in popups {
+            self.close_popup(popup);
+        }
+    }
+
+    // close a popup and its own popups, unless it was already closed along with another
+    fn close_popup(&mut self, popup: WindowId) {
+        if self.windows.contains_key(&popup) || self.view_popups.contains_key(&popup) {
+            self.drop_window(popup);
+            self.requests.push(AppRequest::CloseWindow(popup));
+        }
+    }
+
+    // close every popup that `window_id` isn't, and isn't a popup of
+    //
+    // popups drawn by views are left to the views, they're closed when they aren't drawn anymore
+    fn dismiss_popups(&mut self, window_id: WindowId) {
+        let mut keep = Vec::new();
+        let mut current = Some(window_id);
+
+        while let Some(id) = current {
+            keep.push(id);
+            current = (self.get_window(id)).and_then(|w| w.popup.map(|p| p.parent));
+        }
+
+        let popups: Vec<_> = (self.windows.values())
+            .map(|w| w.window.id())
+            .filter(|id| !keep.contains(id) && !self.view_popups.contains_key(id))
+            .filter(|&id| self.get_window(id).is_some_and(Window::is_popup))
+            .collect();
+
+        for popup in popups {
+            self.close_popup(popup);
+        }
+    }
+
+    // the parent of a view popup window, and the offset of the popup in the parent, in the
+    // logical pixels of the platform
+    fn view_popup_offset(&self, window_id: WindowId) -> Option<(WindowId, Vector)> {
+        let &(parent, _) = self.view_popups.get(&window_id)?;
+        let popup = self.get_window(window_id)?.popup?;
+        let ui_scale = self.get_window(parent)?.ui_scale;
+
+        Some((parent, popup.position.to_vector() * ui_scale))
+    }
+
+    // open, move and close the windows showing the popups `window_id` drew
+    fn sync_view_popups(&mut self, window_id: WindowId) {
+        let Some(window_state) = self.windows.get(&window_id) else {
+            return;
+        };
+
+        let ui_scale = window_state.window.ui_scale;
+        let popups: Vec<_> = (window_state.canvas.popups().iter())
+            .map(|popup| (popup.rect, popup.grab))
+            .collect();
+
+        let mut open: Vec<_> = (self.view_popups.iter())
+            .filter(|(_, &(parent, _))| parent == window_id)
+            .map(|(&id, &(_, index))| (index, id))
+            .collect();
+
+        for (index, &(rect, grab)) in popups.iter().enumerate() {
+            let popup = Popup {
+                parent: window_id,
+                position: rect.min,
+                grab,
+            };
+
+            if let Some(&(_, id)) = open.iter().find(|(i, _)| *i == index) {
+                if let Some(window) = self.get_window_mut(id) {
+                    window.popup = Some(popup);
+                    window.size = rect.size() * ui_scale;
+                    window.ui_scale = ui_scale;
+                }
+
+                self.requests.push(AppRequest::RequestRedraw(id));
+                continue;
+            }
+
+            let mut window = Window::new()
+                .popup(window_id, rect.min)
+                .sizing(WindowSizing::Fixed)
+                .ui_scale(ui_scale);
+
+            window.popup = Some(popup);
+            window.size = rect.size() * ui_scale;
+
+            self.view_popups.insert(window.id(), (window_id, index));
+            open.push((index, window.id()));
+
+            let ui: UiBuilder<T> = Box::new(|_| any(()));
+            self.requests.push(AppRequest::OpenWindow(window, ui));
+        }
+
+        for (index, id) in open {
+            if index >= popups.len() {
+                self.close_popup(id);
+            }
+        }
+    }
+
+    // draw a view popup window, with the canvas its parent drew for it
+    fn draw_view_popup(&self, window_id: WindowId) -> Option<WindowRenderState> {
+        let &(parent, index) = self.view_popups.get(&window_id)?;
+        let window = self.get_window(window_id)?;
+        let parent = self.windows.get(&parent)?;
+
+        Some(WindowRenderState {
+            canvas: parent.canvas.popups().get(index)?.canvas.clone(),
+            logical_size: window.size,
+            ui_scale: window.ui_scale,
+            clear_color: Color::TRANSPARENT,
+            color_space: parent.window.color_space,
+            damage: Damage::Full,
+        })
     }
 
     /// Get a window by id.
@@ -492,9 +1035,83 @@ impl<T> App<T> {
         self.contexts.insert(context);
     }
 
+    /// Get how long the event loop should wait for events.
+    ///
+    /// When no window is animating, or needs to be laid out or drawn, this is
+    /// [`ControlFlow::Wait`], and the event loop should block until an event arrives or the
+    /// [`CommandWaker`](ori_core::command::CommandWaker) wakes it, instead of polling.
+    pub fn control_flow(&self) -> ControlFlow {
+        if !self.requests.is_empty() {
+            return ControlFlow::Poll;
+        }
+
+        let timeout = self.windows.values().filter_map(|window_state| {
+            let view_state = &window_state.view_state;
+
+            let animating = window_state.animate.is_some()
+                || view_state.needs_animate()
+                || view_state.needs_layout()
+                || view_state.needs_draw();
+
+            match window_state.window.max_fps {
+                Some(_) if animating => Some(window_state.frame_delay()),
+                None if animating => Some(ControlFlow::FRAME),
+                _ => None,
+            }
+        });
+
+        let quit_deadline = match self.quit {
+            QuitState::CleaningUp { deadline, .. } => {
+                Some(deadline.saturating_duration_since(Instant::now()))
+            }
+            _ => None,
+        };
+
+        match timeout
+            .chain(self.replay_delay())
+            .chain(quit_deadline)
+            .min()
+        {
+            Some(timeout) => ControlFlow::WaitFor(timeout),
+            None => ControlFlow::Wait,
+        }
+    }
+
+    /// Get how long to wait before drawing a window, to keep to its [`Window::max_fps`].
+    ///
+    /// This is [`Duration::ZERO`] when the window has no frame rate limit, or when the next
+    /// frame is due. Event loops should keep redraws pending until then.
+    pub fn frame_delay(&self, window_id: WindowId) -> Duration {
+        match self.windows.get(&window_id) {
+            Some(window_state) => window_state.frame_delay(),
+            None => Duration::ZERO,
+        }
+    }
+
+    /// Set the rectangles of the connected monitors, in logical pixels.
+    ///
+    /// Windows opened at a position that isn't on any of them are moved onto the first, eg. a
+    /// window restored onto a monitor that has been disconnected, see [`WindowPlacement::clamp`].
+    pub fn set_monitors(&mut self, monitors: Vec<Rect>) {
+        self.monitors = monitors;
+    }
+
     /// Take all pending requests.
+    ///
+    /// Windows that are requested to be opened are moved onto a monitor, see
+    /// [`App::set_monitors`].
     pub fn take_requests(&mut self) -> impl Iterator<Item = AppRequest<T>> {
-        std::mem::take(&mut self.requests).into_iter()
+        let mut requests = std::mem::take(&mut self.requests);
+
+        for request in &mut requests {
+            if let AppRequest::OpenWindow(window, _) = request {
+                let mut placement = window.placement();
+                placement.clamp(&self.monitors);
+                window.position = placement.position;
+            }
+        }
+
+        requests.into_iter()
     }
 
     fn handle_app_command(&mut self, data: &mut T, command: AppCommand) {
@@ -509,16 +1126,59 @@ impl<T> App<T> {
             AppCommand::DragWindow(window_id) => {
                 self.requests.push(AppRequest::DragWindow(window_id));
             }
-            AppCommand::Quit => {
-                self.requests.push(AppRequest::Quit);
+            AppCommand::SetProgress(window_id, progress) => {
+                if let Some(window) = self.get_window_mut(window_id) {
+                    window.set_progress(progress);
+                }
+
+                // commands might not be followed by an event, so send the update now
+                self.handle_window_requests();
             }
-        }
-    }
+            AppCommand::SetUrgent(window_id, urgent) => {
+                if let Some(window) = self.get_window_mut(window_id) {
+                    window.urgent = urgent;
+                }
 
-    /// Handle all pending commands.
+                self.handle_window_requests();
+            }
+            AppCommand::RequestAttention(window_id) => {
+                let update = WindowUpdate::RequestAttention;
+                self.requests
+                    .push(AppRequest::UpdateWindow(window_id, update));
+            }
+            AppCommand::SetAllWorkspaces(window_id, all_workspaces) => {
+                if let Some(window) = self.get_window_mut(window_id) {
+                    window.all_workspaces = all_workspaces;
+                }
+
+                self.handle_window_requests();
+            }
+            AppCommand::SetInputRegion(window_id, input_region) => {
+                if let Some(window) = self.get_window_mut(window_id) {
+                    window.input_region = input_region;
+                }
+
+                self.handle_window_requests();
+            }
+            AppCommand::SetUiScale(window_id, ui_scale) => {
+                self.set_ui_scale(data, window_id, ui_scale);
+                self.handle_window_requests();
+            }
+            AppCommand::Quit => {
+                self.quit(data);
+            }
+        }
+    }
+
+    /// Handle all pending commands.
     pub fn handle_commands(&mut self, data: &mut T) {
         while let Some(command) = self.receiver.try_recv() {
             // if the command is an AppCommand we handle it here
+            if command.is::<QuitCleanupDone>() {
+                self.quit_cleanup_done();
+                continue;
+            }
+
             if command.is::<AppCommand>() {
                 let app_command = command.to_any().downcast().unwrap();
                 self.handle_app_command(data, *app_command);
@@ -527,8 +1187,12 @@ impl<T> App<T> {
             }
 
             if let Some(&RequestFocus(window, view)) = command.get() {
-                self.window_event(data, window, &Event::FocusWanted);
-                self.window_event(data, window, &Event::FocusGiven(FocusTarget::View(view)));
+                self.dispatch_window_event(data, window, &Event::FocusWanted);
+                self.dispatch_window_event(
+                    data,
+                    window,
+                    &Event::FocusGiven(FocusTarget::View(view)),
+                );
 
                 continue;
             }
@@ -537,10 +1201,14 @@ impl<T> App<T> {
                 if let Some(window_state) = self.windows.get_mut(&window) {
                     match window_state.view_state.has_focused() {
                         true => {
-                            self.window_event(data, window, &Event::FocusNext);
+                            self.dispatch_window_event(data, window, &Event::FocusNext);
                         }
                         false => {
-                            self.window_event(data, window, &Event::FocusGiven(FocusTarget::Next));
+                            self.dispatch_window_event(
+                                data,
+                                window,
+                                &Event::FocusGiven(FocusTarget::Next),
+                            );
                         }
                     }
                 }
@@ -552,10 +1220,14 @@ impl<T> App<T> {
                 if let Some(window_state) = self.windows.get_mut(&window) {
                     match window_state.view_state.has_focused() {
                         true => {
-                            self.window_event(data, window, &Event::FocusPrev);
+                            self.dispatch_window_event(data, window, &Event::FocusPrev);
                         }
                         false => {
-                            self.window_event(data, window, &Event::FocusGiven(FocusTarget::Prev));
+                            self.dispatch_window_event(
+                                data,
+                                window,
+                                &Event::FocusGiven(FocusTarget::Prev),
+                            );
                         }
                     }
                 }
@@ -563,7 +1235,8 @@ impl<T> App<T> {
                 continue;
             }
 
-            self.event(data, &Event::Command(command));
+            // other commands are broadcast to every window
+            self.dispatch_event(data, &Event::Command(command));
         }
     }
 
@@ -593,31 +1266,143 @@ impl<T> App<T> {
         let mut base = BaseCx::new(&mut self.contexts, &mut self.proxy);
 
         for delegate in &mut self.delegates {
-            let mut cx = DelegateCx::new(&mut base, &mut self.requests, &mut rebuild);
+            let mut cx =
+                DelegateCx::new(&mut base, &self.windows, &mut self.requests, &mut rebuild);
 
             delegate.init(&mut cx, data);
         }
 
         if rebuild {
-            self.rebuild(data);
-            self.handle_window_requests();
+            self.request_rebuild();
         }
     }
 
     /// The application is idle.
+    ///
+    /// Inputs replayed with [`App::replay`] are fed into the application here, when they are
+    /// due.
     pub fn idle(&mut self, data: &mut T) {
+        self.replay_inputs(data);
+
+        if let QuitState::CleaningUp { deadline, .. } = self.quit {
+            if Instant::now() >= deadline {
+                ori_core::log::warn!("Cleaning up before quitting timed out.");
+                self.finish_quit();
+            }
+        }
+
         let mut rebuild = false;
         let mut base = BaseCx::new(&mut self.contexts, &mut self.proxy);
 
         for delegate in &mut self.delegates {
-            let mut cx = DelegateCx::new(&mut base, &mut self.requests, &mut rebuild);
+            let mut cx =
+                DelegateCx::new(&mut base, &self.windows, &mut self.requests, &mut rebuild);
 
             delegate.idle(&mut cx, data);
         }
 
         if rebuild {
-            self.rebuild(data);
-            self.handle_window_requests();
+            self.request_rebuild();
+        }
+    }
+
+    /// Request the application to quit.
+    ///
+    /// This calls [`AppDelegate::quit_requested`], and [`AppRequest::Quit`] is only requested
+    /// once the futures it returned have completed, or [`AppBuilder::quit_timeout`] has passed.
+    /// Requesting to quit while already quitting does nothing.
+    pub fn quit(&mut self, data: &mut T) {
+        if !matches!(self.quit, QuitState::Running) {
+            return;
+        }
+
+        // the windows stay open until the platform quits, so they're saved as they are now,
+        // popups are placed by the application and aren't saved
+        let mut window_ids: Vec<_> = (self.windows.values())
+            .filter(|w| !w.window.is_popup())
+            .map(|w| w.window.id())
+            .collect();
+        window_ids.sort_by_key(|id| self.window_indices.get(id));
+
+        for window_id in window_ids {
+            self.save_window_placement(data, window_id);
+        }
+
+        let mut rebuild = false;
+        let mut base = BaseCx::new(&mut self.contexts, &mut self.proxy);
+        let mut cleanups = Vec::new();
+
+        for delegate in &mut self.delegates {
+            let mut cx =
+                DelegateCx::new(&mut base, &self.windows, &mut self.requests, &mut rebuild);
+
+            cleanups.extend(delegate.quit_requested(&mut cx, data));
+        }
+
+        if rebuild {
+            self.request_rebuild();
+        }
+
+        if cleanups.is_empty() {
+            self.finish_quit();
+            return;
+        }
+
+        self.quit = QuitState::CleaningUp {
+            pending: cleanups.len(),
+            deadline: Instant::now() + self.quit_timeout,
+        };
+
+        for cleanup in cleanups {
+            self.proxy.cmd_async(async move {
+                cleanup.await;
+                QuitCleanupDone
+            });
+        }
+    }
+
+    /// Check whether the application is quitting, see [`App::quit`].
+    pub fn is_quitting(&self) -> bool {
+        !matches!(self.quit, QuitState::Running)
+    }
+
+    fn quit_cleanup_done(&mut self) {
+        if let QuitState::CleaningUp {
+            ref mut pending, ..
+        } = self.quit
+        {
+            *pending -= 1;
+
+            if *pending == 0 {
+                self.finish_quit();
+            }
+        }
+    }
+
+    fn finish_quit(&mut self) {
+        self.quit = QuitState::Quit;
+        self.requests.push(AppRequest::Quit);
+    }
+
+    fn save_window_placement(&mut self, data: &mut T, window_id: WindowId) {
+        let Some(window_state) = self.windows.get(&window_id) else {
+            return;
+        };
+
+        let placement = window_state.window.placement();
+
+        let mut rebuild = false;
+        let mut base = BaseCx::new(&mut self.contexts, &mut self.proxy);
+
+        for delegate in &mut self.delegates {
+            let mut cx =
+                DelegateCx::new(&mut base, &self.windows, &mut self.requests, &mut rebuild);
+
+            delegate.save_window_placement(&mut cx, data, window_id, &placement);
+        }
+
+        if rebuild {
+            self.request_rebuild();
         }
     }
 
@@ -626,7 +1411,8 @@ impl<T> App<T> {
         let mut base = BaseCx::new(&mut self.contexts, &mut self.proxy);
 
         for delegate in &mut self.delegates {
-            let mut cx = DelegateCx::new(&mut base, &mut self.requests, &mut rebuild);
+            let mut cx =
+                DelegateCx::new(&mut base, &self.windows, &mut self.requests, &mut rebuild);
 
             if delegate.event(&mut cx, data, event) {
                 rebuild = true;
@@ -635,12 +1421,38 @@ impl<T> App<T> {
         }
 
         if rebuild {
-            self.rebuild(data);
+            self.request_rebuild();
         }
 
         false
     }
 
+    fn delegate_unhandled_event(&mut self, data: &mut T, event: &Event) -> bool {
+        let mut handled = false;
+        let mut rebuild = false;
+        let mut base = BaseCx::new(&mut self.contexts, &mut self.proxy);
+
+        for delegate in &mut self.delegates {
+            let mut cx =
+                DelegateCx::new(&mut base, &self.windows, &mut self.requests, &mut rebuild);
+
+            if delegate.unhandled_event(&mut cx, data, event) {
+                handled = true;
+                rebuild = true;
+                break;
+            }
+        }
+
+        if rebuild {
+            self.request_rebuild();
+        }
+
+        self.handle_commands(data);
+        self.handle_window_requests();
+
+        handled
+    }
+
     fn handle_window_requests(&mut self) {
         for window_state in self.windows.values_mut() {
             let id = window_state.window.id();
@@ -649,6 +1461,12 @@ impl<T> App<T> {
             window_state.snapshot = window_state.window.snapshot();
 
             for update in updates {
+                // the ui scale was changed through the window context, the content has to be
+                // notified, which is done by `App::set_ui_scale`
+                if let WindowUpdate::UiScale(ui_scale) = update {
+                    self.proxy.cmd(AppCommand::SetUiScale(id, ui_scale));
+                }
+
                 self.requests.push(AppRequest::UpdateWindow(id, update));
             }
 
@@ -676,8 +1494,34 @@ impl<T> App<T> {
         }
     }
 
+    /// Request a rebuild of all windows, before the next window is drawn.
+    ///
+    /// This is called whenever any view or delegate requests a rebuild, however many changes
+    /// are made before the next frame, the windows are only rebuilt once.
+    pub fn request_rebuild(&mut self) {
+        if self.needs_rebuild {
+            return;
+        }
+
+        self.needs_rebuild = true;
+
+        for &window_id in self.windows.keys() {
+            self.requests.push(AppRequest::RequestRedraw(window_id));
+        }
+    }
+
+    /// Check whether a rebuild has been requested, see [`App::request_rebuild`].
+    pub fn needs_rebuild(&self) -> bool {
+        self.needs_rebuild
+    }
+
     /// Rebuild all windows.
+    ///
+    /// Since all windows share the same data, changes made in one window are reflected in
+    /// every other. This is usually deferred with [`App::request_rebuild`].
     pub fn rebuild(&mut self, data: &mut T) {
+        self.needs_rebuild = false;
+
         let mut base = BaseCx::new(&mut self.contexts, &mut self.proxy);
 
         for window_state in self.windows.values_mut() {
@@ -685,10 +1529,27 @@ impl<T> App<T> {
         }
     }
 
+    // rebuilds are deferred to the next frame, but an event arriving before then must not be
+    // handled by a view tree built from stale data
+    fn flush_rebuild(&mut self, data: &mut T) {
+        if self.needs_rebuild {
+            self.rebuild(data);
+            self.handle_window_requests();
+        }
+    }
+
     /// Handle an event for the entire application.
     ///
-    /// Returns true if the event was handled by a delegate.
+    /// Any pending rebuild is done first, so the event is handled by views built from the
+    /// current data. Returns true if the event was handled by a delegate.
     pub fn event(&mut self, data: &mut T, event: &Event) -> bool {
+        self.flush_rebuild(data);
+        self.dispatch_event(data, event)
+    }
+
+    // commands are dispatched without a rebuild, so many changes made by commands in one
+    // frame are still rebuilt once
+    fn dispatch_event(&mut self, data: &mut T, event: &Event) -> bool {
         trace!(event = ?event, "Event");
 
         // we need to animate the window before handling the event
@@ -710,7 +1571,7 @@ impl<T> App<T> {
 
         // rebuild the view tree if requested
         if rebuild {
-            self.rebuild(data);
+            self.request_rebuild();
         }
 
         // update the window state after handling the event
@@ -728,8 +1589,14 @@ impl<T> App<T> {
 
     /// Handle an event for a single window.
     ///
-    /// Returns true if the event was handled by a delegate.
+    /// Any pending rebuild is done first, like in [`App::event`]. Returns true if the event was
+    /// handled by a delegate.
     pub fn window_event(&mut self, data: &mut T, window_id: WindowId, event: &Event) -> bool {
+        self.flush_rebuild(data);
+        self.dispatch_window_event(data, window_id, event)
+    }
+
+    fn dispatch_window_event(&mut self, data: &mut T, window_id: WindowId, event: &Event) -> bool {
         trace!(event = ?event, window = ?window_id, "Window event");
 
         // we need to animate the window before handling the event
@@ -752,13 +1619,16 @@ impl<T> App<T> {
 
         // rebuild the view tree if requested
         if rebuild {
-            self.rebuild(data);
+            self.request_rebuild();
         }
 
-        // update the window state after handling the event
-        if let Some(window_state) = self.windows.get_mut(&window_id) {
-            let requests = window_state.animate(animate);
-            self.requests.extend(requests);
+        // update the window state after handling the event, a rebuild affects
+        // every window so they all need to be updated
+        for window_state in self.windows.values_mut() {
+            if rebuild || window_state.window.id() == window_id {
+                let requests = window_state.animate(animate);
+                self.requests.extend(requests);
+            }
         }
 
         if let Some(styles) = self.contexts.get_mut::<Styles>() {
@@ -800,6 +1670,14 @@ impl<T> App<T> {
     pub fn draw_window(&mut self, data: &mut T, window_id: WindowId) -> Option<WindowRenderState> {
         trace!(window = ?window_id, "Draw window");
 
+        // popups drawn by views are drawn with their parent
+        if self.view_popups.contains_key(&window_id) {
+            return self.draw_view_popup(window_id);
+        }
+
+        // rebuilds requested since the last frame are done at once, before anything else
+        self.flush_rebuild(data);
+
         // animate the window before drawing it
         //
         // this will send an Animate event if needed
@@ -811,23 +1689,37 @@ impl<T> App<T> {
         // and is set here so the time is as accurate as possible
         let animate = Instant::now();
         let window_state = self.windows.get_mut(&window_id)?;
+        window_state.last_frame = Some(animate);
 
         let mut base = BaseCx::new(&mut self.contexts, &mut self.proxy);
 
+        // the whole window is damaged unless only the views that requested a draw changed,
+        // which isn't the case after a layout, or if the platform asked for a redraw
+        let mut damage = Damage::Full;
+        let needs_layout = window_state.view_state.needs_layout();
+
         // layout if needed
-        if window_state.view_state.needs_layout() {
+        if needs_layout {
             window_state.layout(data, &mut base);
         }
 
         // draw if needed
         if window_state.view_state.needs_draw() {
-            window_state.draw(data, &mut base);
+            let drawn = window_state.draw(data, &mut base);
+
+            if !needs_layout {
+                damage = drawn;
+            }
 
             // since hover state is determined by the scene, and since draw modifies the scene,
             // we must update the hover state, and send an UpdateHovered event if needed
             if self.update_hovered(window_id) {
                 self.window_event(data, window_id, &Event::Notify);
             }
+
+            if self.get_window(window_id).is_some_and(|w| w.popup_windows) {
+                self.sync_view_popups(window_id);
+            }
         }
 
         let window_state = self.windows.get_mut(&window_id)?;
@@ -853,10 +1745,1429 @@ impl<T> App<T> {
             }
         };
 
+        // without popup windows, popups are drawn over the content
+        let mut canvas = window_state.canvas.clone();
+
+        if !window_state.window.popup_windows {
+            canvas.flatten_popups();
+        }
+
         Some(WindowRenderState {
-            canvas: window_state.canvas.clone(),
+            canvas,
             logical_size: window_state.window.size,
+            ui_scale: window_state.window.ui_scale,
             clear_color,
+            color_space: window_state.window.color_space,
+            damage,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        future::Future,
+        path::PathBuf,
+        pin::Pin,
+        sync::{Arc, Mutex},
+        task::{Context, Poll, Waker},
+    };
+
+    use ori_core::{
+        command::{Canceled, Command, CommandRequest, CommandResponder, CommandWaker},
+        dialog::{FileDialog, FileDialogKind},
+        image::Image,
+        layout::{Rect, Size},
+        text::MonoFonts,
+        view::any,
+        views::{
+            button, center, live_region, on_build, on_click, on_event, painter, rotate_degrees,
+            size, text,
+        },
+        window::{Announcement, WindowPlacement},
+    };
+
+    use crate::{QuitCleanup, RecordedInput, ReplayTiming};
+
+    use super::*;
+
+    // build an app with a waker that does nothing, laying out text with `MonoFonts`
+    fn build_app<T>(builder: AppBuilder<T>) -> App<T> {
+        builder.build(CommandWaker::new(|| {}), Box::new(MonoFonts))
+    }
+
+    // add `window` to `app` showing `ui`, and get its id
+    fn open_window<T>(
+        app: &mut App<T>,
+        data: &mut T,
+        window: Window,
+        ui: impl FnMut(&mut T) -> BoxedView<T> + 'static,
+    ) -> WindowId {
+        let window_id = window.id();
+        app.add_window(data, Box::new(ui), window);
+        window_id
+    }
+
+    struct Unhandled;
+
+    impl AppDelegate<Vec<Key>> for Unhandled {
+        fn event(&mut self, _: &mut DelegateCx<Vec<Key>>, _: &mut Vec<Key>, _: &Event) -> bool {
+            false
+        }
+
+        fn unhandled_event(
+            &mut self,
+            _cx: &mut DelegateCx<Vec<Key>>,
+            data: &mut Vec<Key>,
+            event: &Event,
+        ) -> bool {
+            if let Event::KeyPressed(e) = event {
+                data.push(e.key);
+            }
+
+            true
+        }
+    }
+
+    #[test]
+    fn unhandled_key_reaches_delegate() {
+        let mut app = build_app(AppBuilder::new().delegate(Unhandled));
+
+        let mut data = Vec::new();
+        let window_id = open_window(&mut app, &mut data, Window::new(), |_| any(()));
+
+        let key = Key::Character('f');
+        let handled = app.keyboard_key(&mut data, window_id, key, None, None, true);
+
+        assert!(handled);
+        assert_eq!(data, vec![key]);
+    }
+
+    #[test]
+    fn held_keys_repeat_until_released() {
+        let mut app = build_app(AppBuilder::new());
+
+        // record whether every press is a repeat
+        let ui = |_: &mut Vec<bool>| {
+            any(on_event((), |_, repeats: &mut Vec<bool>, event| {
+                if let Event::KeyPressed(e) = event {
+                    repeats.push(e.repeat);
+                }
+
+                false
+            }))
+        };
+
+        let mut repeats = Vec::new();
+        let window_id = open_window(&mut app, &mut repeats, Window::new(), ui);
+
+        let key = |app: &mut App<Vec<bool>>, repeats: &mut Vec<bool>, c, pressed| {
+            let key = Key::Character(c);
+            app.keyboard_key(repeats, window_id, key, Some(Code::A), None, pressed);
+        };
+
+        // the key changes when shift is pressed while it's held, the physical key doesn't
+        key(&mut app, &mut repeats, 'a', true);
+        key(&mut app, &mut repeats, 'a', true);
+        key(&mut app, &mut repeats, 'A', true);
+        key(&mut app, &mut repeats, 'A', false);
+        key(&mut app, &mut repeats, 'a', true);
+
+        // releases while the window isn't focused are never reported
+        app.window_focused(&mut repeats, window_id, false);
+        key(&mut app, &mut repeats, 'a', true);
+        key(&mut app, &mut repeats, 'a', true);
+
+        assert_eq!(repeats, [false, true, true, false, false, true]);
+
+        app.remove_window(&mut repeats, window_id);
+        assert!(app.held_keys.is_empty());
+    }
+
+    #[test]
+    fn handled_tab_does_not_move_focus() {
+        let mut app = build_app(AppBuilder::new());
+
+        // the view handles ctrl+tab, like tabs switching to the next tab
+        let ui = |_: &mut Vec<&str>| {
+            on_event((), |_, log: &mut Vec<&str>, event| match event {
+                Event::KeyPressed(e) if e.key == Key::Tab => {
+                    log.push("tab");
+                    e.modifiers.ctrl
+                }
+                Event::FocusGiven(_) => {
+                    log.push("focus");
+                    false
+                }
+                _ => false,
+            })
+        };
+
+        let mut log = Vec::new();
+        let window_id = open_window(&mut app, &mut log, Window::new(), move |data| any(ui(data)));
+
+        app.modifiers_changed(Modifiers::CTRL);
+        app.keyboard_key(&mut log, window_id, Key::Tab, None, None, true);
+        app.keyboard_key(&mut log, window_id, Key::Tab, None, None, false);
+        assert_eq!(log, ["tab"]);
+
+        app.modifiers_changed(Modifiers::NONE);
+        app.keyboard_key(&mut log, window_id, Key::Tab, None, None, true);
+        assert_eq!(log, ["tab", "tab", "focus"]);
+    }
+
+    #[test]
+    fn ui_scale_divides_layout_size() {
+        let mut app = build_app(AppBuilder::new());
+
+        // a painter fills the space it is given
+        let ui = |_: &mut ()| any(painter(|_, _| {}));
+        let window_id = open_window(&mut app, &mut (), Window::new().size(800, 600), ui);
+
+        app.draw_window(&mut (), window_id);
+        let size = app.windows[&window_id].view_state.size();
+        assert_eq!(size, Size::new(800.0, 600.0));
+
+        app.set_ui_scale(&mut (), window_id, 2.0);
+
+        let state = app.draw_window(&mut (), window_id).unwrap();
+        let size = app.windows[&window_id].view_state.size();
+        assert_eq!(size, Size::new(400.0, 300.0));
+        assert_eq!(state.logical_size, Size::new(800.0, 600.0));
+        assert_eq!(state.ui_scale, 2.0);
+    }
+
+    struct RecordScale;
+
+    impl AppDelegate<Vec<f32>> for RecordScale {
+        fn event(
+            &mut self,
+            _: &mut DelegateCx<Vec<f32>>,
+            data: &mut Vec<f32>,
+            event: &Event,
+        ) -> bool {
+            if let Event::WindowScaled(scaled) = event {
+                data.push(scaled.scale_factor);
+            }
+
+            false
+        }
+    }
+
+    struct RecordScrolls;
+
+    impl AppDelegate<Vec<PointerScrolled>> for RecordScrolls {
+        fn event(
+            &mut self,
+            _: &mut DelegateCx<Vec<PointerScrolled>>,
+            data: &mut Vec<PointerScrolled>,
+            event: &Event,
+        ) -> bool {
+            if let Event::PointerScrolled(scrolled) = event {
+                data.push(scrolled.clone());
+            }
+
+            false
+        }
+    }
+
+    #[test]
+    fn scroll_deltas_keep_their_unit() {
+        let mut app = build_app(AppBuilder::new().delegate(RecordScrolls));
+
+        let mut data = Vec::new();
+        let window_id = open_window(&mut app, &mut data, Window::new(), |_| any(()));
+
+        // a plain vector is a delta in lines, like before deltas had a unit
+        let pointer = PointerId::from_u64(0);
+        app.pointer_scrolled(&mut data, window_id, pointer, Vector::NEG_Y);
+
+        let pixels = ScrollDelta::Pixels(Vector::new(0.0, -5.0));
+        app.pointer_scrolled(&mut data, window_id, pointer, pixels);
+
+        assert_eq!(data[0].scroll_delta(), Vector::NEG_Y);
+        assert_eq!(data[0].delta, ScrollDelta::Lines(Vector::NEG_Y));
+        assert_eq!(data[1].scroll_delta(), Vector::new(0.0, -5.0));
+        assert_eq!(data[1].delta, pixels);
+    }
+
+    #[test]
+    fn window_scaled_carries_render_scale() {
+        let mut app = build_app(AppBuilder::new().delegate(RecordScale));
+
+        let mut data = Vec::new();
+        let window_id = open_window(&mut app, &mut data, Window::new(), |_| any(()));
+
+        app.set_ui_scale(&mut data, window_id, 2.0);
+        app.window_scaled(&mut data, window_id, 1.5);
+
+        // both events include the ui scale
+        assert_eq!(data, [2.0, 3.0]);
+    }
+
+    struct SavePlacement;
+
+    impl AppDelegate<Vec<WindowPlacement>> for SavePlacement {
+        fn event(
+            &mut self,
+            _cx: &mut DelegateCx<Vec<WindowPlacement>>,
+            _data: &mut Vec<WindowPlacement>,
+            _event: &Event,
+        ) -> bool {
+            false
+        }
+
+        fn save_window_placement(
+            &mut self,
+            _cx: &mut DelegateCx<Vec<WindowPlacement>>,
+            data: &mut Vec<WindowPlacement>,
+            _window_id: WindowId,
+            placement: &WindowPlacement,
+        ) {
+            data.push(*placement);
+        }
+    }
+
+    #[test]
+    fn closing_window_saves_placement() {
+        let mut app = build_app(AppBuilder::new().delegate(SavePlacement));
+
+        let mut data = Vec::new();
+        let window_id = open_window(
+            &mut app,
+            &mut data,
+            Window::new().size(640, 480).maximized(true),
+            |_| any(()),
+        );
+
+        assert!(app.close_requested(&mut data, window_id));
+
+        let placement = WindowPlacement {
+            size: Size::new(640.0, 480.0),
+            position: None,
+            maximized: true,
+        };
+
+        assert_eq!(data, vec![placement]);
+
+        let restored = Window::new().restore(placement);
+        assert_eq!(restored.placement(), placement);
+    }
+
+    #[test]
+    fn removing_windows_and_quitting_saves_placements() {
+        let mut app = build_app(AppBuilder::new().delegate(SavePlacement));
+
+        let mut data = Vec::new();
+
+        let removed_id = open_window(&mut app, &mut data, Window::new().size(300, 200), |_| {
+            any(())
+        });
+
+        let open_id = open_window(
+            &mut app,
+            &mut data,
+            Window::new().size(640, 480).position(10.0, 20.0),
+            |_| any(()),
+        );
+
+        // popups aren't saved
+        let popup = Window::new().popup(open_id, Point::ZERO);
+        open_window(&mut app, &mut data, popup, |_| any(()));
+
+        app.remove_window(&mut data, removed_id);
+        app.quit(&mut data);
+
+        let sizes: Vec<_> = data.iter().map(|placement| placement.size).collect();
+        assert_eq!(sizes, [Size::new(300.0, 200.0), Size::new(640.0, 480.0)]);
+        assert_eq!(data[1].position, Some(Point::new(10.0, 20.0)));
+    }
+
+    #[test]
+    fn restored_windows_are_moved_onto_a_monitor() {
+        let mut app = build_app(AppBuilder::new());
+        app.set_monitors(vec![
+            Rect::min_size(Point::ZERO, Size::new(1920.0, 1080.0)),
+            Rect::min_size(Point::new(1920.0, 0.0), Size::new(1280.0, 1024.0)),
+        ]);
+
+        let placement = |x, y| WindowPlacement {
+            size: Size::new(800.0, 600.0),
+            position: Some(Point::new(x, y)),
+            maximized: false,
+        };
+
+        // on the second monitor, and on a monitor that has been disconnected
+        let kept = Window::new().restore(placement(2000.0, 100.0));
+        let moved = Window::new().restore(placement(4000.0, 2000.0));
+        app.requests
+            .push(AppRequest::OpenWindow(kept, Box::new(|_: &mut ()| any(()))));
+        app.requests.push(AppRequest::OpenWindow(
+            moved,
+            Box::new(|_: &mut ()| any(())),
+        ));
+
+        let positions: Vec<_> = (app.take_requests())
+            .filter_map(|request| match request {
+                AppRequest::OpenWindow(window, _) => window.position,
+                _ => None,
+            })
+            .collect();
+
+        let expected = [Point::new(2000.0, 100.0), Point::new(1120.0, 480.0)];
+        assert_eq!(positions, expected);
+    }
+
+    fn closed_windows<T>(app: &mut App<T>) -> Vec<WindowId> {
+        (app.take_requests())
+            .filter_map(|request| match request {
+                AppRequest::CloseWindow(id) => Some(id),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn popups_close_on_outside_press() {
+        let mut app = build_app(AppBuilder::new());
+
+        let parent_id = open_window(&mut app, &mut (), Window::new().size(400, 300), |_| any(()));
+
+        let open_popup = |app: &mut App<()>, parent_id| {
+            open_window(
+                app,
+                &mut (),
+                Window::new()
+                    .size(100, 50)
+                    .popup(parent_id, Point::new(380.0, 20.0)),
+                |_| any(()),
+            )
+        };
+
+        let popup_id = open_popup(&mut app, parent_id);
+        let nested_id = open_popup(&mut app, popup_id);
+
+        // pressing inside the popup closes only the popups opened from it
+        app.pointer_button(
+            &mut (),
+            popup_id,
+            PointerId::from_hash(&0),
+            PointerButton::Primary,
+            true,
+        );
+        assert_eq!(closed_windows(&mut app), vec![nested_id]);
+        assert!(app.get_window(popup_id).is_some());
+
+        app.pointer_button(
+            &mut (),
+            parent_id,
+            PointerId::from_hash(&0),
+            PointerButton::Primary,
+            true,
+        );
+        assert_eq!(closed_windows(&mut app), vec![popup_id]);
+        assert!(app.get_window(popup_id).is_none());
+
+        // closing the parent closes its popups
+        let popup_id = open_popup(&mut app, parent_id);
+        app.remove_window(&mut (), parent_id);
+        assert_eq!(closed_windows(&mut app), vec![popup_id]);
+        assert!(app.windows.is_empty());
+    }
+
+    #[test]
+    fn nested_popups_close_once() {
+        let mut app = build_app(AppBuilder::new());
+
+        let parent_id = open_window(&mut app, &mut (), Window::new().size(400, 300), |_| any(()));
+
+        let open_popup = |app: &mut App<()>, parent_id| {
+            open_window(
+                app,
+                &mut (),
+                Window::new()
+                    .size(100, 50)
+                    .popup(parent_id, Point::new(20.0, 20.0)),
+                |_| any(()),
+            )
+        };
+
+        let popup_id = open_popup(&mut app, parent_id);
+        let nested_id = open_popup(&mut app, popup_id);
+
+        let pointer = PointerId::from_hash(&0);
+        app.pointer_button(&mut (), parent_id, pointer, PointerButton::Primary, true);
+
+        let mut closed = closed_windows(&mut app);
+        closed.sort_by_key(|&id| id != popup_id);
+        assert_eq!(closed, vec![popup_id, nested_id]);
+
+        // the grab delivers presses outside the popup to it, which closes it
+        let popup_id = open_popup(&mut app, parent_id);
+        let nested_id = open_popup(&mut app, popup_id);
+
+        app.pointer_moved(&mut (), nested_id, pointer, Point::new(-10.0, 70.0));
+        app.pointer_button(&mut (), nested_id, pointer, PointerButton::Primary, true);
+        assert_eq!(closed_windows(&mut app), vec![nested_id]);
+        assert!(app.get_window(popup_id).is_some());
+    }
+
+    // a popup is drawn while the data is true
+    fn view_popup_ui() -> impl View<bool> {
+        painter(|cx, open: &mut bool| {
+            if *open {
+                let rect = Rect::min_size(Point::new(380.0, 20.0), Size::new(100.0, 50.0));
+
+                cx.popup(rect, true, |cx| {
+                    cx.fill_rect(Rect::min_size(Point::ZERO, rect.size()), Color::RED);
+                });
+            }
         })
     }
+
+    #[test]
+    fn view_popups_are_shown_in_popup_windows() {
+        let mut app = build_app(AppBuilder::new());
+
+        let mut open = true;
+        let mut parent = Window::new().size(400, 300).ui_scale(2.0);
+        parent.popup_windows = true;
+        let parent_id = open_window(&mut app, &mut open, parent, |_| any(view_popup_ui()));
+        app.take_requests().for_each(drop);
+
+        let state = app.draw_window(&mut open, parent_id).unwrap();
+        assert!(state.canvas.primitives().next().is_none());
+
+        let opened: Vec<_> = (app.take_requests())
+            .filter_map(|request| match request {
+                AppRequest::OpenWindow(window, ui) => Some((window, ui)),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(opened.len(), 1);
+        let (window, ui) = opened.into_iter().next().unwrap();
+        let popup_id = window.id();
+
+        assert_eq!(window.size, Size::new(200.0, 100.0));
+        assert_eq!(
+            window.popup.map(|p| p.position),
+            Some(Point::new(380.0, 20.0))
+        );
+
+        // the platform opens the window, which shows the popup the parent drew
+        app.add_window(&mut open, ui, window);
+
+        let state = app.draw_window(&mut open, popup_id).unwrap();
+        assert_eq!(state.clear_color, Color::TRANSPARENT);
+        assert_eq!(state.canvas.primitives().count(), 1);
+
+        // pointer input on the popup is delivered to the parent
+        let pointer = PointerId::from_hash(&0);
+        app.pointer_moved(&mut open, popup_id, pointer, Point::new(10.0, 10.0));
+        let position = app.pointer_position(parent_id, pointer);
+        assert_eq!(position, Some(Point::new(385.0, 25.0)));
+
+        // the popup window is closed when the popup isn't drawn anymore
+        open = false;
+        app.windows
+            .get_mut(&parent_id)
+            .unwrap()
+            .view_state
+            .request_draw();
+        app.draw_window(&mut open, parent_id);
+
+        assert_eq!(closed_windows(&mut app), vec![popup_id]);
+        assert!(app.get_window(popup_id).is_none());
+    }
+
+    #[test]
+    fn view_popups_are_drawn_over_the_content_without_popup_windows() {
+        let mut app = build_app(AppBuilder::new());
+
+        let mut open = true;
+        let window_id = open_window(&mut app, &mut open, Window::new().size(400, 300), |_| {
+            any(view_popup_ui())
+        });
+
+        let state = app.draw_window(&mut open, window_id).unwrap();
+        assert!(state.canvas.popups().is_empty());
+        assert_eq!(state.canvas.primitives().count(), 1);
+
+        let opened = (app.take_requests()).any(|r| matches!(r, AppRequest::OpenWindow(..)));
+        assert!(!opened);
+    }
+
+    #[derive(Default)]
+    struct Lifecycle {
+        calls: Vec<&'static str>,
+    }
+
+    struct RecordLifecycle;
+
+    impl AppDelegate<Lifecycle> for RecordLifecycle {
+        fn event(&mut self, _: &mut DelegateCx<Lifecycle>, _: &mut Lifecycle, _: &Event) -> bool {
+            false
+        }
+
+        fn suspended(&mut self, _cx: &mut DelegateCx<Lifecycle>, data: &mut Lifecycle) {
+            data.calls.push("delegate suspended");
+        }
+
+        fn resumed(&mut self, _cx: &mut DelegateCx<Lifecycle>, data: &mut Lifecycle) {
+            data.calls.push("delegate resumed");
+        }
+    }
+
+    #[test]
+    fn suspend_and_resume_in_order() {
+        let mut app = build_app(AppBuilder::new().delegate(RecordLifecycle));
+
+        let ui = |_: &mut Lifecycle| {
+            let view = on_event((), |_, data: &mut Lifecycle, event| {
+                match event {
+                    Event::Suspended => data.calls.push("view suspended"),
+                    Event::Resumed => data.calls.push("view resumed"),
+                    _ => {}
+                }
+
+                false
+            });
+
+            any(view)
+        };
+
+        let mut data = Lifecycle::default();
+        open_window(&mut app, &mut data, Window::new(), ui);
+
+        app.suspend(&mut data);
+        app.suspend(&mut data);
+        assert!(app.is_suspended());
+
+        app.resume(&mut data);
+        assert!(!app.is_suspended());
+
+        let expected = [
+            "delegate suspended",
+            "view suspended",
+            "delegate resumed",
+            "view resumed",
+        ];
+
+        assert_eq!(data.calls, expected);
+    }
+
+    #[test]
+    fn removed_windows_release_resources() {
+        let mut app = build_app(AppBuilder::new());
+
+        let image = Image::new(vec![255; 4 * 4 * 4], 4, 4);
+        let weak = image.downgrade();
+
+        for _ in 0..100 {
+            let image = image.clone();
+            let ui = move |_: &mut ()| any(image.clone());
+            let window_id = open_window(&mut app, &mut (), Window::new().size(100, 100), ui);
+
+            app.draw_window(&mut (), window_id);
+            app.remove_window(&mut (), window_id);
+        }
+
+        assert!(app.windows.is_empty());
+        assert!(app.window_indices.is_empty());
+
+        // only our own reference to the image is left
+        assert_eq!(weak.strong_count(), 1);
+        drop(image);
+        assert_eq!(weak.strong_count(), 0);
+    }
+
+    #[test]
+    fn idle_app_waits_without_timeout() {
+        let mut app = build_app(AppBuilder::new());
+
+        let ui = |_: &mut ()| any(painter(|_, _| {}));
+        let window_id = open_window(&mut app, &mut (), Window::new().size(100, 100), ui);
+
+        // the new window hasn't been drawn yet
+        assert!(app.control_flow().timeout().is_some());
+
+        app.draw_window(&mut (), window_id);
+        app.take_requests().for_each(drop);
+        app.idle(&mut ());
+
+        assert_eq!(app.control_flow(), ControlFlow::Wait);
+        assert_eq!(app.control_flow().timeout(), None);
+    }
+
+    #[test]
+    fn max_fps_paces_animation_frames() {
+        let mut app = build_app(AppBuilder::new());
+
+        // a view that animates forever
+        let ui = |_: &mut ()| {
+            let view = on_event((), |cx, _, _| {
+                cx.animate();
+                false
+            });
+
+            any(on_build(view, |cx, _| cx.animate()))
+        };
+        let window_id = open_window(
+            &mut app,
+            &mut (),
+            Window::new().size(100, 100).max_fps(Some(30)),
+            ui,
+        );
+
+        let mut frames = Vec::new();
+
+        for _ in 0..6 {
+            // wait the way event loops do, and only draw once the frame is due
+            while !app.frame_delay(window_id).is_zero() {
+                let timeout = app.control_flow().timeout();
+                assert!(timeout.is_some_and(|t| t <= Duration::from_millis(34)));
+                std::thread::sleep(timeout.unwrap());
+            }
+
+            app.draw_window(&mut (), window_id);
+            app.take_requests().for_each(drop);
+            frames.push(Instant::now());
+
+            // right after drawing, the next frame isn't due yet
+            let delay = app.frame_delay(window_id);
+            assert!(delay > Duration::from_millis(20), "{:?}", delay);
+            assert!(app.control_flow().timeout() <= Some(delay));
+        }
+
+        for frame in frames.windows(2) {
+            let spacing = frame[1] - frame[0];
+
+            assert!(spacing >= Duration::from_millis(30), "{:?}", spacing);
+            assert!(spacing <= Duration::from_millis(80), "{:?}", spacing);
+        }
+    }
+
+    #[test]
+    fn all_workspaces_updates_the_window() {
+        let mut app = build_app(AppBuilder::new());
+
+        let window_id = open_window(&mut app, &mut (), Window::new(), |_| any(()));
+        app.take_requests().for_each(drop);
+
+        app.handle_app_command(&mut (), AppCommand::SetAllWorkspaces(window_id, true));
+
+        let updates: Vec<_> = (app.take_requests())
+            .filter_map(|request| match request {
+                AppRequest::UpdateWindow(id, update) if id == window_id => Some(update),
+                _ => None,
+            })
+            .collect();
+
+        assert!(matches!(updates[..], [WindowUpdate::AllWorkspaces(true)]));
+        assert!(app.get_window(window_id).unwrap().all_workspaces);
+    }
+
+    #[test]
+    fn progress_and_urgency_update_the_window() {
+        let mut app = build_app(AppBuilder::new());
+
+        let window_id = open_window(&mut app, &mut (), Window::new(), |_| any(()));
+        app.take_requests().for_each(drop);
+
+        let updates = |app: &mut App<()>, command| {
+            app.handle_app_command(&mut (), command);
+
+            (app.take_requests())
+                .filter_map(|request| match request {
+                    AppRequest::UpdateWindow(id, update) if id == window_id => Some(update),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        };
+
+        // the progress is clamped, and setting it again sends nothing
+        let progress = AppCommand::SetProgress(window_id, Some(1.5));
+        assert_eq!(
+            updates(&mut app, progress),
+            [WindowUpdate::Progress(Some(1.0))]
+        );
+        assert_eq!(app.get_window(window_id).unwrap().progress, Some(1.0));
+
+        let progress = AppCommand::SetProgress(window_id, Some(1.0));
+        assert_eq!(updates(&mut app, progress), []);
+
+        let progress = AppCommand::SetProgress(window_id, None);
+        assert_eq!(updates(&mut app, progress), [WindowUpdate::Progress(None)]);
+
+        let urgent = AppCommand::SetUrgent(window_id, true);
+        assert_eq!(updates(&mut app, urgent), [WindowUpdate::Urgent(true)]);
+        assert!(app.get_window(window_id).unwrap().urgent);
+
+        let attention = AppCommand::RequestAttention(window_id);
+        assert_eq!(
+            updates(&mut app, attention),
+            [WindowUpdate::RequestAttention]
+        );
+    }
+
+    #[test]
+    fn empty_input_region_makes_window_click_through() {
+        let mut app = build_app(AppBuilder::new());
+
+        let window_id = open_window(&mut app, &mut (), Window::new(), |_| any(()));
+        app.take_requests().for_each(drop);
+
+        let command = AppCommand::SetInputRegion(window_id, Some(Vec::new()));
+        app.handle_app_command(&mut (), command);
+
+        let updates: Vec<_> = (app.take_requests())
+            .filter_map(|request| match request {
+                AppRequest::UpdateWindow(id, update) if id == window_id => Some(update),
+                _ => None,
+            })
+            .collect();
+
+        // an empty region, rather than `None`, so no part of the window receives input
+        assert_eq!(updates, [WindowUpdate::InputRegion(Some(Vec::new()))]);
+        assert_eq!(
+            app.get_window(window_id).unwrap().input_region,
+            Some(Vec::new())
+        );
+
+        // and the initial updates of a click-through window set the region
+        let updates = Window::new().click_through().updates();
+        assert!(updates.contains(&WindowUpdate::InputRegion(Some(Vec::new()))));
+    }
+
+    struct Config {
+        name: &'static str,
+    }
+
+    #[test]
+    fn views_read_injected_contexts() {
+        let mut app = build_app(AppBuilder::new().context(Config { name: "ori" }));
+
+        let view = |_: &mut Option<&'static str>| {
+            on_build((), |cx, name: &mut Option<&'static str>| {
+                *name = cx.get_context::<Config>().map(|config| config.name);
+            })
+        };
+
+        let mut data = None;
+        open_window(&mut app, &mut data, Window::new(), move |data| {
+            any(view(data))
+        });
+
+        assert_eq!(data, Some("ori"));
+    }
+
+    fn click(app: &mut App<u32>, clicks: &mut u32, window_id: WindowId, position: Point) {
+        let pointer_id = PointerId::from_hash(&0);
+
+        app.pointer_moved(clicks, window_id, pointer_id, position);
+        app.pointer_button(clicks, window_id, pointer_id, PointerButton::Primary, true);
+        app.pointer_button(clicks, window_id, pointer_id, PointerButton::Primary, false);
+    }
+
+    #[test]
+    fn rotated_button_hit_testing() {
+        let mut app = build_app(AppBuilder::new());
+
+        // a 100x100 button rotated into a diamond, centered in the window
+        let ui = |_: &mut u32| {
+            let button = button(size(100.0, ()));
+            let button = on_click(button, |_, clicks: &mut u32| *clicks += 1);
+            center(rotate_degrees(45.0, button))
+        };
+
+        let mut clicks = 0;
+        let window_id = open_window(
+            &mut app,
+            &mut clicks,
+            Window::new().size(200, 200),
+            move |data| any(ui(data)),
+        );
+        app.draw_window(&mut clicks, window_id);
+
+        // inside the diamond, but outside the unrotated layout rect
+        click(&mut app, &mut clicks, window_id, Point::new(100.0, 35.0));
+        assert_eq!(clicks, 1);
+
+        // inside the unrotated layout rect, but outside the diamond
+        click(&mut app, &mut clicks, window_id, Point::new(55.0, 55.0));
+        click(&mut app, &mut clicks, window_id, Point::new(145.0, 145.0));
+        assert_eq!(clicks, 1);
+
+        click(&mut app, &mut clicks, window_id, Point::new(100.0, 100.0));
+        assert_eq!(clicks, 2);
+    }
+
+    #[test]
+    fn pointer_released_outside_window_is_removed() {
+        let mut app = build_app(AppBuilder::new());
+
+        // a view that captures every pointer pressed on it
+        let ui = |_: &mut u32| {
+            on_event(size(100.0, ()), |cx, _, event| {
+                if let Event::PointerPressed(e) = event {
+                    cx.capture_pointer(e.id);
+                }
+
+                false
+            })
+        };
+
+        let mut data = 0;
+        let window_id = open_window(
+            &mut app,
+            &mut data,
+            Window::new().size(100, 100),
+            move |data| any(ui(data)),
+        );
+        app.draw_window(&mut data, window_id);
+
+        let pointer_id = PointerId::from_hash(&0);
+        let has_pointer = |app: &App<u32>| app.get_window(window_id).unwrap().pointers().len() == 1;
+
+        let press = |app: &mut App<u32>, data: &mut u32, pressed| {
+            app.pointer_button(data, window_id, pointer_id, PointerButton::Primary, pressed);
+        };
+
+        // dragged out of the window, and released there
+        app.pointer_moved(&mut data, window_id, pointer_id, Point::new(50.0, 50.0));
+        press(&mut app, &mut data, true);
+        app.pointer_left(&mut data, window_id, pointer_id);
+        assert!(has_pointer(&app));
+
+        press(&mut app, &mut data, false);
+        assert!(!has_pointer(&app));
+
+        // dragged out of the window, and back in before the release
+        app.pointer_moved(&mut data, window_id, pointer_id, Point::new(50.0, 50.0));
+        press(&mut app, &mut data, true);
+        app.pointer_left(&mut data, window_id, pointer_id);
+        app.pointer_moved(&mut data, window_id, pointer_id, Point::new(60.0, 50.0));
+
+        press(&mut app, &mut data, false);
+        assert!(has_pointer(&app));
+    }
+
+    #[test]
+    fn request_resolves_with_the_handler_response() {
+        let mut app = build_app(AppBuilder::new().handle_request(
+            |_, data: &mut Vec<u32>, request: &u32| {
+                data.push(*request);
+                request * 2
+            },
+        ));
+
+        let response = Arc::new(Mutex::new(None));
+        let mut data = Vec::new();
+
+        let future = app.proxy.cmd_request::<u32, u32>(21);
+        app.proxy.spawn_async({
+            let response = response.clone();
+
+            async move {
+                *response.lock().unwrap() = Some(future.await);
+            }
+        });
+
+        app.handle_commands(&mut data);
+
+        assert_eq!(data, vec![21]);
+        assert_eq!(*response.lock().unwrap(), Some(Ok(42)));
+    }
+
+    #[test]
+    fn file_dialog_is_answered_later() {
+        type Responders = Arc<Mutex<Vec<CommandResponder<Option<PathBuf>>>>>;
+
+        // answers dialogs once the test responds, like the shell does when the dialog closes
+        struct Dialogs(Responders);
+
+        impl AppDelegate<()> for Dialogs {
+            fn event(&mut self, _cx: &mut DelegateCx<()>, _data: &mut (), event: &Event) -> bool {
+                if let Some(request) = event.cmd::<CommandRequest<FileDialog, Option<PathBuf>>>() {
+                    assert_eq!(request.request().kind, FileDialogKind::Save);
+                    self.0.lock().unwrap().push(request.responder());
+                }
+
+                false
+            }
+        }
+
+        let responders = Responders::default();
+
+        let mut app = build_app(
+            AppBuilder::new()
+                .delegate(Dialogs(responders.clone()))
+                .handle_request(|_, _: &mut (), _: &FileDialog| Some(PathBuf::from("ignored"))),
+        );
+
+        let response = Arc::new(Mutex::new(None));
+
+        let mut base = BaseCx::new(&mut app.contexts, &mut app.proxy);
+        let future = base.save_file_dialog(FileDialog::new().file_name("notes.txt"));
+        app.proxy.spawn_async({
+            let response = response.clone();
+
+            async move {
+                *response.lock().unwrap() = Some(future.await);
+            }
+        });
+
+        app.handle_commands(&mut ());
+        assert_eq!(*response.lock().unwrap(), None);
+
+        let responder = responders.lock().unwrap().pop().unwrap();
+        responder.respond(Some(PathBuf::from("notes.txt")));
+
+        app.handle_commands(&mut ());
+        assert_eq!(
+            *response.lock().unwrap(),
+            Some(Some(PathBuf::from("notes.txt")))
+        );
+    }
+
+    #[test]
+    fn unhandled_request_is_canceled() {
+        let mut app = build_app(AppBuilder::new());
+
+        let response = Arc::new(Mutex::new(None));
+
+        let future = app.proxy.cmd_request::<u32, u32>(21);
+        app.proxy.spawn_async({
+            let response = response.clone();
+
+            async move {
+                *response.lock().unwrap() = Some(future.await);
+            }
+        });
+
+        app.handle_commands(&mut ());
+        app.handle_commands(&mut ());
+        assert_eq!(*response.lock().unwrap(), Some(Err(Canceled)));
+    }
+
+    #[test]
+    fn dropped_responder_cancels_request() {
+        let (request, mut response) = CommandRequest::<u32, u32>::new(21);
+        let responder = request.responder();
+        drop(request);
+
+        let waker = Waker::noop();
+        let mut cx = Context::from_waker(waker);
+        assert!(Pin::new(&mut response).poll(&mut cx).is_pending());
+
+        drop(responder);
+        assert_eq!(
+            Pin::new(&mut response).poll(&mut cx),
+            Poll::Ready(Err(Canceled))
+        );
+    }
+
+    #[test]
+    fn unanswered_file_dialog_resolves_with_none() {
+        let mut app = build_app(AppBuilder::new());
+
+        let response = Arc::new(Mutex::new(None));
+
+        let mut base = BaseCx::new(&mut app.contexts, &mut app.proxy);
+        let future = base.open_file_dialog(FileDialog::new());
+        app.proxy.spawn_async({
+            let response = response.clone();
+
+            async move {
+                *response.lock().unwrap() = Some(future.await);
+            }
+        });
+
+        app.handle_commands(&mut ());
+        app.handle_commands(&mut ());
+        assert_eq!(*response.lock().unwrap(), Some(None));
+    }
+
+    #[test]
+    fn replayed_recording_reproduces_data() {
+        // clicks on the button and typed text are logged in the data
+        fn ui(_: &mut Vec<String>) -> BoxedView<Vec<String>> {
+            let button = on_click(button(size(100.0, ())), |_, log: &mut Vec<String>| {
+                log.push(String::from("click"));
+            });
+
+            let view = on_event(button, |_, log: &mut Vec<String>, event| {
+                if let Event::KeyPressed(KeyPressed {
+                    text: Some(ref text),
+                    ..
+                }) = event
+                {
+                    log.push(text.clone());
+                }
+
+                false
+            });
+
+            any(view)
+        }
+
+        fn session(app: &mut App<Vec<String>>, log: &mut Vec<String>, window_id: WindowId) {
+            let pointer_id = PointerId::from_u64(0);
+
+            app.pointer_moved(log, window_id, pointer_id, Point::new(50.0, 50.0));
+            app.pointer_button(log, window_id, pointer_id, PointerButton::Primary, true);
+            app.pointer_button(log, window_id, pointer_id, PointerButton::Primary, false);
+
+            for c in ['h', 'i'] {
+                let key = Key::Character(c);
+                app.keyboard_key(log, window_id, key, None, Some(c.to_string()), true);
+                app.keyboard_key(log, window_id, key, None, None, false);
+            }
+
+            // outside the button
+            app.pointer_moved(log, window_id, pointer_id, Point::new(150.0, 150.0));
+            app.pointer_button(log, window_id, pointer_id, PointerButton::Primary, true);
+            app.pointer_button(log, window_id, pointer_id, PointerButton::Primary, false);
+        }
+
+        let recording = Arc::new(Mutex::new(Vec::new()));
+
+        let mut app = build_app(AppBuilder::new().record_input({
+            let recording = recording.clone();
+            move |input: &RecordedInput| recording.lock().unwrap().push(input.clone())
+        }));
+
+        let mut log = Vec::new();
+        let window_id = open_window(&mut app, &mut log, Window::new().size(200, 200), ui);
+        app.draw_window(&mut log, window_id);
+
+        session(&mut app, &mut log, window_id);
+        assert_eq!(log, ["click", "h", "i"]);
+
+        // replayed into a new application, where the window has another id
+        let inputs = recording.lock().unwrap().clone();
+        assert_eq!(inputs.len(), 10);
+
+        let mut replayed = build_app(AppBuilder::new().replay(inputs, ReplayTiming::Immediate));
+
+        let mut replayed_log = Vec::new();
+        let replayed_id = open_window(
+            &mut replayed,
+            &mut replayed_log,
+            Window::new().size(200, 200),
+            ui,
+        );
+        replayed.draw_window(&mut replayed_log, replayed_id);
+
+        assert!(replayed.is_replaying());
+        assert_eq!(
+            replayed.control_flow(),
+            ControlFlow::WaitFor(Duration::ZERO)
+        );
+
+        replayed.idle(&mut replayed_log);
+
+        assert!(!replayed.is_replaying());
+        assert_eq!(replayed_log, log);
+    }
+
+    #[test]
+    fn recorded_windows_keep_their_index_when_others_close() {
+        let recording = Arc::new(Mutex::new(Vec::new()));
+
+        let mut app = build_app(AppBuilder::new().record_input({
+            let recording = recording.clone();
+            move |input: &RecordedInput| recording.lock().unwrap().push(input.clone())
+        }));
+
+        let first_id = open_window(&mut app, &mut (), Window::new(), |_: &mut ()| any(()));
+
+        let second_id = open_window(&mut app, &mut (), Window::new(), |_: &mut ()| any(()));
+
+        app.remove_window(&mut (), first_id);
+        assert_eq!(app.window_indices.len(), 1);
+
+        let pointer_id = PointerId::from_u64(0);
+        app.pointer_moved(&mut (), second_id, pointer_id, Point::new(10.0, 10.0));
+
+        let inputs = recording.lock().unwrap().clone();
+        assert_eq!(inputs.len(), 1);
+        assert_eq!(inputs[0].input.window(), Some(1));
+    }
+
+    #[test]
+    fn quit_waits_for_cleanup_or_timeout() {
+        type Requests = Arc<Mutex<Vec<CommandRequest<(), ()>>>>;
+
+        // each quit is deferred until the test answers the request, like a pending save
+        struct Save(Requests);
+
+        impl AppDelegate<()> for Save {
+            fn event(&mut self, _: &mut DelegateCx<()>, _: &mut (), _: &Event) -> bool {
+                false
+            }
+
+            fn quit_requested(
+                &mut self,
+                _: &mut DelegateCx<()>,
+                _: &mut (),
+            ) -> Option<QuitCleanup> {
+                let (request, response) = CommandRequest::new(());
+                self.0.lock().unwrap().push(request);
+                Some(Box::pin(async move {
+                    let _ = response.await;
+                }))
+            }
+        }
+
+        let quits = |app: &mut App<()>| {
+            let requests = app.take_requests();
+            requests.filter(|r| matches!(r, AppRequest::Quit)).count()
+        };
+
+        let requests = Requests::default();
+        let mut app = build_app(AppBuilder::new().delegate(Save(requests.clone())));
+
+        app.proxy.cmd(AppCommand::Quit);
+        app.handle_commands(&mut ());
+        app.idle(&mut ());
+        assert!(app.is_quitting());
+        assert_eq!(quits(&mut app), 0);
+
+        // the event loop wakes up in time for the timeout
+        assert!(app.control_flow().timeout().is_some());
+
+        // quitting again while cleaning up doesn't start another cleanup
+        app.proxy.cmd(AppCommand::Quit);
+        app.handle_commands(&mut ());
+        assert_eq!(requests.lock().unwrap().len(), 1);
+
+        requests.lock().unwrap().pop().unwrap().respond(());
+        app.handle_commands(&mut ());
+        assert_eq!(quits(&mut app), 1);
+
+        // a cleanup that never completes is cut short by the timeout
+        let requests = Requests::default();
+        let mut app = build_app(
+            AppBuilder::new()
+                .delegate(Save(requests.clone()))
+                .quit_timeout(Duration::ZERO),
+        );
+
+        app.proxy.cmd(AppCommand::Quit);
+        app.handle_commands(&mut ());
+        assert_eq!(quits(&mut app), 0);
+
+        app.idle(&mut ());
+        assert_eq!(quits(&mut app), 1);
+    }
+
+    #[test]
+    fn many_changes_in_one_frame_rebuild_once() {
+        struct Bump;
+
+        // each bump changes the data and requests a rebuild, like a changed signal
+        struct Bumper;
+
+        impl AppDelegate<u32> for Bumper {
+            fn event(&mut self, cx: &mut DelegateCx<u32>, data: &mut u32, event: &Event) -> bool {
+                if event.cmd::<Bump>().is_none() {
+                    return false;
+                }
+
+                *data += 1;
+                cx.rebuild();
+                true
+            }
+        }
+
+        let mut app = build_app(AppBuilder::new().delegate(Bumper));
+
+        // the data each time the ui is built
+        let builds = Arc::new(Mutex::new(Vec::new()));
+
+        let ui = {
+            let builds = builds.clone();
+            move |data: &mut u32| {
+                builds.lock().unwrap().push(*data);
+                any(painter(|_, _| {}))
+            }
+        };
+
+        let mut data = 0;
+        let window_id = open_window(&mut app, &mut data, Window::new().size(100, 100), ui);
+        app.draw_window(&mut data, window_id);
+        app.take_requests().for_each(drop);
+
+        for _ in 0..10 {
+            app.proxy.cmd(Bump);
+        }
+
+        app.handle_commands(&mut data);
+        assert_eq!(data, 10);
+        assert_eq!(*builds.lock().unwrap(), [0]);
+
+        // the platform is asked to draw the window, which rebuilds it once
+        assert!(app.needs_rebuild());
+        assert!(app
+            .take_requests()
+            .any(|r| matches!(r, AppRequest::RequestRedraw(_))));
+
+        app.draw_window(&mut data, window_id);
+        assert_eq!(*builds.lock().unwrap(), [0, 10]);
+        assert!(!app.needs_rebuild());
+    }
+
+    #[test]
+    fn events_between_frames_see_rebuilt_tree() {
+        struct Bump;
+
+        let mut app = build_app(AppBuilder::new());
+
+        // the data the ui was built with, each time a bump is handled
+        let seen = Arc::new(Mutex::new(Vec::new()));
+
+        let ui = {
+            let seen = seen.clone();
+            move |data: &mut u32| {
+                let built = *data;
+                let seen = seen.clone();
+
+                any(on_event(painter(|_, _| {}), move |cx, data, event| {
+                    if event.cmd::<Bump>().is_none() {
+                        return false;
+                    }
+
+                    seen.lock().unwrap().push(built);
+                    *data += 1;
+                    cx.rebuild();
+                    true
+                }))
+            }
+        };
+
+        let mut data = 0;
+        let window_id = open_window(&mut app, &mut data, Window::new().size(100, 100), ui);
+        app.draw_window(&mut data, window_id);
+
+        // two events arrive before the next frame is drawn
+        let bump = Event::Command(Command::new(Bump));
+        app.window_event(&mut data, window_id, &bump);
+        app.window_event(&mut data, window_id, &bump);
+
+        assert_eq!(data, 2);
+        assert_eq!(*seen.lock().unwrap(), [0, 1]);
+    }
+
+    #[test]
+    fn animating_widget_damages_only_its_bounds() {
+        let mut app = build_app(AppBuilder::new());
+
+        // a large static view, and a small spinner that redraws every frame
+        let ui = |_: &mut ()| {
+            let background = painter(|cx, _| cx.fill_rect(cx.rect(), Color::BLUE));
+
+            let spinner = painter(|cx, _| cx.fill_rect(cx.rect(), Color::RED));
+            let spinner = on_event(size(20.0, spinner), |cx, _, event| {
+                if let Event::Animate(_) = event {
+                    cx.animate();
+                    cx.draw();
+                }
+
+                false
+            });
+            let spinner = on_build(spinner, |cx, _| cx.animate());
+
+            any(ori_core::vstack![
+                size(Size::new(400.0, 200.0), background),
+                spinner
+            ])
+        };
+
+        let window_id = open_window(&mut app, &mut (), Window::new().size(400, 300), ui);
+
+        // the first frame is laid out, so everything is damaged
+        let state = app.draw_window(&mut (), window_id).unwrap();
+        assert_eq!(state.damage, Damage::Full);
+
+        for _ in 0..3 {
+            let state = app.draw_window(&mut (), window_id).unwrap();
+
+            let Damage::Rect(rect) = state.damage else {
+                panic!("expected a damaged rect, got {:?}", state.damage);
+            };
+
+            assert_eq!(rect.size(), Size::all(20.0));
+            assert_eq!(rect.min.y, 200.0);
+            assert!(rect.area() < 400.0 * 300.0);
+        }
+    }
+
+    #[test]
+    fn animating_overlay_damages_its_window_bounds() {
+        let mut app = build_app(AppBuilder::new());
+
+        // a view below the middle of the window, that draws an overlay in the top left corner
+        let ui = |_: &mut ()| {
+            let popup = painter(|cx, _| {
+                cx.overlay(0, |cx| {
+                    let rect = Rect::min_size(Point::ZERO, Size::all(20.0));
+                    cx.fill_rect(rect, Color::RED);
+                });
+            });
+
+            let popup = on_event(size(20.0, popup), |cx, _, event| {
+                if let Event::Animate(_) = event {
+                    cx.animate();
+                    cx.draw();
+                }
+
+                false
+            });
+            let popup = on_build(popup, |cx, _| cx.animate());
+
+            any(ori_core::vstack![size(Size::new(400.0, 200.0), ()), popup])
+        };
+
+        let window_id = open_window(&mut app, &mut (), Window::new().size(400, 300), ui);
+        app.draw_window(&mut (), window_id);
+
+        let state = app.draw_window(&mut (), window_id).unwrap();
+        let expected = Rect::min_size(Point::ZERO, Size::all(20.0));
+        assert_eq!(state.damage, Damage::Rect(expected));
+    }
+
+    #[test]
+    fn live_region_announcement_reaches_delegates() {
+        type Data = (u32, Vec<String>);
+
+        // stands in for an accessibility backend, passing messages on to a screen reader
+        struct ScreenReader;
+
+        impl AppDelegate<Data> for ScreenReader {
+            fn event(&mut self, _: &mut DelegateCx<Data>, data: &mut Data, event: &Event) -> bool {
+                if let Some(announcement) = event.cmd::<Announcement>() {
+                    data.1.push(announcement.message.clone());
+                }
+
+                false
+            }
+        }
+
+        let mut app = build_app(AppBuilder::new().delegate(ScreenReader));
+
+        let mut data: Data = (3, Vec::new());
+        let ui = |data: &mut Data| any(live_region(format!("{} left", data.0), text("")));
+        open_window(&mut app, &mut data, Window::new(), ui);
+
+        app.handle_commands(&mut data);
+        assert!(data.1.is_empty());
+
+        data.0 = 2;
+        app.rebuild(&mut data);
+        app.handle_commands(&mut data);
+
+        assert_eq!(data.1, ["2 left"]);
+    }
 }
@@ -1,19 +1,23 @@
-use std::{fmt::Debug, io, path::Path, str::FromStr};
+use std::{any::Any, fmt::Debug, io, path::Path, str::FromStr, time::Duration};
 
 use ori_core::{
-    canvas::{BorderRadius, BorderWidth},
+    canvas::{BorderRadius, BorderWidth, Shadow},
     command::{CommandProxy, CommandWaker},
     context::Contexts,
     layout::{Align, Justify},
     style::{Styles, Theme},
     text::{
-        include_font, FontFamily, FontSource, FontStretch, FontStyle, FontWeight, Fonts, TextAlign,
-        TextWrap,
+        include_font, FontFamily, FontSource, FontStretch, FontStyle, FontWeight, Fonts,
+        GlyphAtlasConfig, GlyphRenderConfig, TextAlign, TextWrap, WhiteSpace,
     },
     window::Window,
 };
 
-use crate::{App, AppDelegate, AppRequest, IntoUiBuilder};
+use crate::{
+    app::QuitState,
+    record::{Recorder, Replay},
+    App, AppDelegate, AppRequest, DelegateCx, IntoUiBuilder, RequestHandler,
+};
 
 /// A builder for an [`App`].
 pub struct AppBuilder<T> {
@@ -21,6 +25,13 @@ pub struct AppBuilder<T> {
     requests: Vec<AppRequest<T>>,
     styles: Styles,
     fonts: Vec<FontSource<'static>>,
+    atlas: Option<GlyphAtlasConfig>,
+    glyph_rendering: Option<GlyphRenderConfig>,
+    app_id: Option<String>,
+    contexts: Contexts,
+    pub(crate) recorder: Option<Recorder>,
+    pub(crate) replay: Option<Replay>,
+    quit_timeout: Duration,
 }
 
 impl<T> Default for AppBuilder<T> {
@@ -41,12 +52,15 @@ impl<T> AppBuilder<T> {
         styles.add_conversion::<f32, _>(BorderRadius::from);
         styles.add_conversion::<[f32; 4], _>(BorderRadius::from);
 
+        styles.add_conversion::<Shadow, Vec<Shadow>>(Vec::from);
+
         styles.add_conversion::<String, _>(FontFamily::from);
         styles.add_conversion::<String, _>(FontWeight::from);
         styles.add_conversion::<String, _>(FontStretch::from);
         styles.add_conversion::<String, _>(FontStyle::from);
         styles.add_conversion::<String, _>(TextAlign::from);
         styles.add_conversion::<String, _>(TextWrap::from);
+        styles.add_conversion::<String, _>(WhiteSpace::from);
 
         styles.add_conversion::<String, _>(Align::from);
         styles.add_conversion::<String, _>(Justify::from);
@@ -56,15 +70,54 @@ impl<T> AppBuilder<T> {
             requests: Vec::new(),
             styles,
             fonts: vec![include_font!("font")],
+            atlas: None,
+            glyph_rendering: None,
+            app_id: None,
+            contexts: Contexts::new(),
+            recorder: None,
+            replay: None,
+            quit_timeout: Duration::from_secs(5),
         }
     }
 
+    /// Set the id of the application.
+    ///
+    /// This is used by the desktop to group windows and find their icons, eg. the `WM_CLASS`
+    /// on X11 and the `app_id` on Wayland. Defaults to the name of the executable.
+    pub fn app_id(mut self, app_id: impl Into<String>) -> Self {
+        self.app_id = Some(app_id.into());
+        self
+    }
+
+    /// Set how long delegates get to clean up before the application quits anyway.
+    ///
+    /// See [`AppDelegate::quit_requested`]. Defaults to 5 seconds.
+    pub fn quit_timeout(mut self, timeout: Duration) -> Self {
+        self.quit_timeout = timeout;
+        self
+    }
+
     /// Add a delegate to the application.
     pub fn delegate(mut self, delegate: impl AppDelegate<T> + 'static) -> Self {
         self.delegates.push(Box::new(delegate));
         self
     }
 
+    /// Add a handler for requests sent with
+    /// [`BaseCx::cmd_request`](ori_core::context::BaseCx::cmd_request).
+    ///
+    /// The value returned by `handler` resolves the future of the sender.
+    pub fn handle_request<Req, Resp>(
+        self,
+        handler: impl FnMut(&mut DelegateCx<T>, &mut T, &Req) -> Resp + 'static,
+    ) -> Self
+    where
+        Req: Any + Send,
+        Resp: Any + Send,
+    {
+        self.delegate(RequestHandler::new(handler))
+    }
+
     /// Add a style to the application.
     pub fn style<L>(mut self, styles: L) -> Self
     where
@@ -79,12 +132,57 @@ impl<T> AppBuilder<T> {
         self
     }
 
+    /// Register the default styles of a component, see [`Styles::register_defaults`].
+    ///
+    /// Unlike [`AppBuilder::style`], the defaults are overridden by every other style.
+    pub fn style_defaults(mut self, defaults: impl Into<Styles>) -> Self {
+        self.styles.register_defaults(defaults);
+        self
+    }
+
     /// Add a font to the application.
     pub fn font(mut self, font: impl Into<FontSource<'static>>) -> Self {
         self.fonts.push(font.into());
         self
     }
 
+    /// Set the configuration of the glyph atlas.
+    pub fn glyph_atlas(mut self, config: GlyphAtlasConfig) -> Self {
+        self.atlas = Some(config);
+        self
+    }
+
+    /// Set how glyphs are rasterized.
+    pub fn glyph_rendering(mut self, config: GlyphRenderConfig) -> Self {
+        self.glyph_rendering = Some(config);
+        self
+    }
+
+    /// Add a context to the application, replacing any context of the same type.
+    ///
+    /// Contexts are services shared by every view, eg. the configuration of the application,
+    /// which views can read with [`BaseCx::get_context`](ori_core::context::BaseCx::get_context)
+    /// from any of their contexts.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use ori_core::views::*;
+    /// # use ori_app::AppBuilder;
+    /// struct Config {
+    ///     compact: bool,
+    /// }
+    ///
+    /// let app = AppBuilder::<bool>::new().context(Config { compact: true });
+    ///
+    /// let ui = on_build(text("Hello, world!"), |cx, compact: &mut bool| {
+    ///     *compact = cx.get_context::<Config>().is_some_and(|config| config.compact);
+    /// });
+    /// ```
+    pub fn context<C: Any>(mut self, context: C) -> Self {
+        self.contexts.insert(context);
+        self
+    }
+
     /// Add a window to the application.
     pub fn window<V, P>(
         mut self,
@@ -102,24 +200,56 @@ impl<T> AppBuilder<T> {
             fonts.load(font, None);
         }
 
+        if let Some(config) = self.atlas {
+            fonts.set_atlas_config(config);
+        }
+
+        if let Some(config) = self.glyph_rendering {
+            fonts.set_render_config(config);
+        }
+
         let (proxy, receiver) = CommandProxy::new(waker);
 
-        let mut contexts = Contexts::new();
+        let mut contexts = self.contexts;
         contexts.insert(self.styles);
         contexts.insert(fonts);
 
         App {
+            app_id: self.app_id.unwrap_or_else(default_app_id),
             windows: Default::default(),
+            window_indices: Default::default(),
+            windows_added: 0,
             modifiers: Default::default(),
+            held_keys: Default::default(),
+            left_pointers: Default::default(),
+            view_popups: Default::default(),
+            monitors: Vec::new(),
+            suspended: false,
             delegates: self.delegates,
             proxy,
             receiver,
             requests: self.requests,
             contexts,
+            recorder: self.recorder,
+            replay: self.replay,
+            quit_timeout: self.quit_timeout,
+            quit: QuitState::Running,
+            needs_rebuild: false,
         }
     }
 }
 
+// the name of the executable, eg. `todos` for `target/debug/todos`
+fn default_app_id() -> String {
+    let exe = std::env::current_exe().ok();
+    let name = exe.as_deref().and_then(Path::file_stem);
+
+    match name.and_then(|name| name.to_str()) {
+        Some(name) => name.to_string(),
+        None => String::from("ori"),
+    }
+}
+
 /// A trait for loading styles.
 pub trait LoadStyle {
     /// The error type.
@@ -1,4 +1,5 @@
 use ori_core::{
+    layout::Rect,
     view::{BoxedView, View},
     window::{Window, WindowId},
 };
@@ -27,6 +28,25 @@ pub enum AppCommand {
     /// Drag a window.
     DragWindow(WindowId),
 
+    /// Set the progress shown in the taskbar for a window, see [`Window::progress`].
+    SetProgress(WindowId, Option<f32>),
+
+    /// Set whether a window requests the attention of the user, see [`Window::urgent`].
+    SetUrgent(WindowId, bool),
+
+    /// Request the attention of the user once for a window, see
+    /// [`WindowUpdate::RequestAttention`](ori_core::window::WindowUpdate::RequestAttention).
+    RequestAttention(WindowId),
+
+    /// Set whether a window is visible on all workspaces, see [`Window::all_workspaces`].
+    SetAllWorkspaces(WindowId, bool),
+
+    /// Set the region of a window that receives pointer input, see [`Window::input_region`].
+    SetInputRegion(WindowId, Option<Vec<Rect>>),
+
+    /// Set the scale of the user interface of a window, see [`Window::ui_scale`].
+    SetUiScale(WindowId, f32),
+
     /// Quit the application.
     Quit,
 }
@@ -0,0 +1,31 @@
+use std::time::Duration;
+
+/// How long an event loop should wait for events, see [`App::control_flow`].
+///
+/// [`App::control_flow`]: crate::App::control_flow
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ControlFlow {
+    /// There is pending work, the event loop shouldn't wait at all.
+    Poll,
+
+    /// Nothing is scheduled, the event loop should block until an event arrives, or until the
+    /// [`CommandWaker`](ori_core::command::CommandWaker) wakes it.
+    Wait,
+
+    /// Something is animating, the event loop should wait at most the duration.
+    WaitFor(Duration),
+}
+
+impl ControlFlow {
+    /// The time between frames while animating.
+    pub const FRAME: Duration = Duration::from_micros(1_000_000 / 60);
+
+    /// Get the timeout to wait for events with, `None` means waiting indefinitely.
+    pub fn timeout(self) -> Option<Duration> {
+        match self {
+            ControlFlow::Poll => Some(Duration::ZERO),
+            ControlFlow::Wait => None,
+            ControlFlow::WaitFor(duration) => Some(duration),
+        }
+    }
+}
@@ -1,17 +1,33 @@
-use std::ops::{Deref, DerefMut};
+use std::{
+    any::Any,
+    collections::HashMap,
+    future::Future,
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+    pin::Pin,
+};
 
 use ori_core::{
+    command::CommandRequest,
     context::BaseCx,
     event::Event,
+    layout::Point,
     view::{any, AnyView},
-    window::{Window, WindowId},
+    window::{Window, WindowId, WindowPlacement},
 };
 
-use crate::{AppRequest, UiBuilder};
+use crate::{app::WindowState, AppCommand, AppRequest, UiBuilder};
+
+/// A future cleaning up before the application quits, see [`AppDelegate::quit_requested`].
+pub type QuitCleanup = Pin<Box<dyn Future<Output = ()> + Send>>;
 
 /// The context passed to the [`Delegate`] trait.
+///
+/// The context dereferences to [`BaseCx`], which gives access to the contexts of the
+/// application, eg. the clipboard through [`BaseCx::clipboard`].
 pub struct DelegateCx<'a, 'b, T> {
     base: &'a mut BaseCx<'b>,
+    windows: &'a HashMap<WindowId, WindowState<T>>,
     requests: &'a mut Vec<AppRequest<T>>,
     rebuild: &'a mut bool,
 }
@@ -33,24 +49,41 @@ impl<T> DerefMut for DelegateCx<'_, '_, T> {
 impl<'a, 'b, T> DelegateCx<'a, 'b, T> {
     pub(crate) fn new(
         base: &'a mut BaseCx<'b>,
+        windows: &'a HashMap<WindowId, WindowState<T>>,
         requests: &'a mut Vec<AppRequest<T>>,
         rebuild: &'a mut bool,
     ) -> Self {
         Self {
             base,
+            windows,
             requests,
             rebuild,
         }
     }
 
     /// Request a rebuild of the view tree.
+    ///
+    /// This rebuilds the view tree of every window.
     pub fn rebuild(&mut self) {
         *self.rebuild = true;
     }
 
+    /// Get the position of the cursor in a window.
+    ///
+    /// The position is in the coordinate space of the content of the window, and is `None`
+    /// if the window doesn't exist, or if no pointer is over it.
+    pub fn cursor_position(&self, window_id: WindowId) -> Option<Point> {
+        let window_state = self.windows.get(&window_id)?;
+        let pointer = window_state.window().pointers().first()?;
+        Some(pointer.position)
+    }
+
     /// Quit the application.
+    ///
+    /// The application quits once every delegate has cleaned up, see
+    /// [`AppDelegate::quit_requested`].
     pub fn quit(&mut self) {
-        self.requests.push(AppRequest::Quit);
+        self.base.cmd(AppCommand::Quit);
     }
 
     /// Add a window to the application.
@@ -83,4 +116,94 @@ pub trait AppDelegate<T> {
 
     /// Handle an event.
     fn event(&mut self, cx: &mut DelegateCx<T>, data: &mut T, event: &Event) -> bool;
+
+    /// Called when a window is closed or removed, and for every open window when the
+    /// application quits, with the placement it had. Popups are not saved.
+    ///
+    /// This is useful for remembering the size of a window between runs of the application,
+    /// the placement can be restored with [`Window::restore`].
+    fn save_window_placement(
+        &mut self,
+        cx: &mut DelegateCx<T>,
+        data: &mut T,
+        window_id: WindowId,
+        placement: &WindowPlacement,
+    ) {
+        let _ = (cx, data, window_id, placement);
+    }
+
+    /// Called when the application is suspended, eg. when it's moved to the background on
+    /// mobile.
+    ///
+    /// This is a good time to pause work like game loops or network polling, and to release
+    /// resources that can be recreated later.
+    fn suspended(&mut self, cx: &mut DelegateCx<T>, data: &mut T) {
+        let _ = (cx, data);
+    }
+
+    /// Called when the application is resumed after being suspended.
+    fn resumed(&mut self, cx: &mut DelegateCx<T>, data: &mut T) {
+        let _ = (cx, data);
+    }
+
+    /// Called when the application is requested to quit, before it quits.
+    ///
+    /// Returning a future defers quitting until it completes, which is useful for saving
+    /// state or finishing network writes. The future is polled with the commands of the
+    /// application, and the application quits anyway when the timeout set with
+    /// [`AppBuilder::quit_timeout`] runs out.
+    ///
+    /// [`AppBuilder::quit_timeout`]: crate::AppBuilder::quit_timeout
+    fn quit_requested(&mut self, cx: &mut DelegateCx<T>, data: &mut T) -> Option<QuitCleanup> {
+        let _ = (cx, data);
+        None
+    }
+
+    /// Handle a keyboard event that wasn't handled by any view.
+    ///
+    /// This is useful for application-wide shortcuts and menu mnemonics.
+    fn unhandled_event(&mut self, cx: &mut DelegateCx<T>, data: &mut T, event: &Event) -> bool {
+        let _ = (cx, data, event);
+        false
+    }
+}
+
+/// A delegate answering [`CommandRequest`]s of type `Req`, see [`AppBuilder::handle_request`].
+///
+/// [`AppBuilder::handle_request`]: crate::AppBuilder::handle_request
+pub struct RequestHandler<F, Req> {
+    handler: F,
+    marker: PhantomData<fn(&Req)>,
+}
+
+impl<F, Req> RequestHandler<F, Req> {
+    /// Create a new [`RequestHandler`].
+    pub fn new(handler: F) -> Self {
+        Self {
+            handler,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T, F, Req, Resp> AppDelegate<T> for RequestHandler<F, Req>
+where
+    F: FnMut(&mut DelegateCx<T>, &mut T, &Req) -> Resp,
+    Req: Any,
+    Resp: Any,
+{
+    fn event(&mut self, cx: &mut DelegateCx<T>, data: &mut T, event: &Event) -> bool {
+        let Some(request) = event.cmd::<CommandRequest<Req, Resp>>() else {
+            return false;
+        };
+
+        if request.is_responded() {
+            return false;
+        }
+
+        let response = (self.handler)(cx, data, request.request());
+        request.respond(response);
+
+        true
+    }
 }
@@ -6,13 +6,17 @@
 mod app;
 mod builder;
 mod command;
+mod control_flow;
 mod delegate;
+mod record;
 mod request;
 
 pub use app::*;
 pub use builder::*;
 pub use command::*;
+pub use control_flow::*;
 pub use delegate::*;
+pub use record::*;
 pub use request::*;
 
 use ori_core::view::{AnyView, BoxedView};
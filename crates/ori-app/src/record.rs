@@ -0,0 +1,356 @@
+use std::{collections::VecDeque, time::Duration};
+
+use instant::Instant;
+use ori_core::{
+    event::{Code, Key, Modifiers, PointerButton, PointerId, PointerTool, ScrollDelta},
+    layout::{Point, Vector},
+    window::WindowId,
+};
+
+use crate::{App, AppBuilder};
+
+/// An input to an [`App`], see [`App::input`].
+///
+/// Windows are referred to by their index in the order they were added to the application,
+/// which unlike their [`WindowId`] is the same every time the application runs.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AppInput {
+    /// A window was resized, see [`App::window_resized`].
+    WindowResized {
+        /// The index of the window.
+        window: usize,
+        /// The new width of the window.
+        width: u32,
+        /// The new height of the window.
+        height: u32,
+    },
+
+    /// A pointer moved, see [`App::pointer_moved`].
+    PointerMoved {
+        /// The index of the window.
+        window: usize,
+        /// The pointer that moved.
+        pointer: PointerId,
+        /// The new position of the pointer.
+        position: Point,
+    },
+
+    /// A pointer left a window, see [`App::pointer_left`].
+    PointerLeft {
+        /// The index of the window.
+        window: usize,
+        /// The pointer that left.
+        pointer: PointerId,
+    },
+
+    /// The stylus state of a pointer changed, see [`App::pointer_stylus`].
+    PointerStylus {
+        /// The index of the window.
+        window: usize,
+        /// The pointer that changed.
+        pointer: PointerId,
+        /// The pressure of the pointer.
+        pressure: f32,
+        /// The tilt of the pointer.
+        tilt: Vector,
+        /// The tool of the pointer.
+        tool: PointerTool,
+    },
+
+    /// A pointer scrolled, see [`App::pointer_scrolled`].
+    PointerScrolled {
+        /// The index of the window.
+        window: usize,
+        /// The pointer that scrolled.
+        pointer: PointerId,
+        /// The scroll delta.
+        delta: ScrollDelta,
+    },
+
+    /// A pointer button was pressed or released, see [`App::pointer_button`].
+    PointerButton {
+        /// The index of the window.
+        window: usize,
+        /// The pointer of the button.
+        pointer: PointerId,
+        /// The button.
+        button: PointerButton,
+        /// Whether the button was pressed.
+        pressed: bool,
+    },
+
+    /// A keyboard key was pressed or released, see [`App::keyboard_key`].
+    KeyboardKey {
+        /// The index of the window.
+        window: usize,
+        /// The key.
+        key: Key,
+        /// The physical code of the key.
+        code: Option<Code>,
+        /// The text of the key.
+        text: Option<String>,
+        /// Whether the key was pressed.
+        pressed: bool,
+    },
+
+    /// The modifiers changed, see [`App::modifiers_changed`].
+    ModifiersChanged {
+        /// The new modifiers.
+        modifiers: Modifiers,
+    },
+}
+
+impl AppInput {
+    /// Get the index of the window the input is for, `None` if it's for every window.
+    pub fn window(&self) -> Option<usize> {
+        match *self {
+            AppInput::WindowResized { window, .. }
+            | AppInput::PointerMoved { window, .. }
+            | AppInput::PointerLeft { window, .. }
+            | AppInput::PointerStylus { window, .. }
+            | AppInput::PointerScrolled { window, .. }
+            | AppInput::PointerButton { window, .. }
+            | AppInput::KeyboardKey { window, .. } => Some(window),
+            AppInput::ModifiersChanged { .. } => None,
+        }
+    }
+}
+
+/// An [`AppInput`] recorded with [`AppBuilder::record_input`].
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RecordedInput {
+    /// The time since the recording started.
+    pub time: Duration,
+
+    /// The input.
+    pub input: AppInput,
+}
+
+/// When recorded inputs are replayed, see [`AppBuilder::replay`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ReplayTiming {
+    /// Replay the inputs at the time they were recorded.
+    #[default]
+    Recorded,
+
+    /// Replay the inputs as fast as possible.
+    Immediate,
+}
+
+pub(crate) struct Recorder {
+    start: Instant,
+    sink: Box<dyn FnMut(&RecordedInput)>,
+}
+
+impl Recorder {
+    pub(crate) fn new(sink: impl FnMut(&RecordedInput) + 'static) -> Self {
+        Self {
+            start: Instant::now(),
+            sink: Box::new(sink),
+        }
+    }
+
+    fn record(&mut self, input: AppInput) {
+        let input = RecordedInput {
+            time: self.start.elapsed(),
+            input,
+        };
+
+        (self.sink)(&input);
+    }
+}
+
+pub(crate) struct Replay {
+    inputs: VecDeque<RecordedInput>,
+    timing: ReplayTiming,
+
+    // the clock starts when the first input is due, after the windows have been opened
+    start: Option<Instant>,
+}
+
+impl Replay {
+    pub(crate) fn new(
+        inputs: impl IntoIterator<Item = RecordedInput>,
+        timing: ReplayTiming,
+    ) -> Self {
+        Self {
+            inputs: inputs.into_iter().collect(),
+            timing,
+            start: None,
+        }
+    }
+
+    // how long until the next input is due, `None` when every input has been replayed
+    fn delay(&self) -> Option<Duration> {
+        let next = self.inputs.front()?;
+
+        match (self.timing, self.start) {
+            (ReplayTiming::Recorded, Some(start)) => {
+                Some(next.time.saturating_sub(start.elapsed()))
+            }
+            _ => Some(Duration::ZERO),
+        }
+    }
+
+    fn next_due(&mut self) -> Option<AppInput> {
+        let start = *self.start.get_or_insert_with(Instant::now);
+        let next = self.inputs.front()?;
+
+        if self.timing == ReplayTiming::Recorded && next.time > start.elapsed() {
+            return None;
+        }
+
+        self.inputs.pop_front().map(|input| input.input)
+    }
+}
+
+impl<T> AppBuilder<T> {
+    /// Record every input to the application, passing them to `sink` as they arrive.
+    ///
+    /// The recorded inputs can be fed back into an application with [`AppBuilder::replay`],
+    /// with the `serde` feature they can be serialized, eg. to reproduce a bug in a test.
+    pub fn record_input(mut self, sink: impl FnMut(&RecordedInput) + 'static) -> Self {
+        self.recorder = Some(Recorder::new(sink));
+        self
+    }
+
+    /// Replay inputs recorded with [`AppBuilder::record_input`].
+    ///
+    /// The inputs are replayed when the application is idle, after its windows have been
+    /// opened, either at the time they were recorded or as fast as possible.
+    pub fn replay(
+        mut self,
+        inputs: impl IntoIterator<Item = RecordedInput>,
+        timing: ReplayTiming,
+    ) -> Self {
+        self.replay = Some(Replay::new(inputs, timing));
+        self
+    }
+}
+
+impl<T> App<T> {
+    /// Start recording every input to the application, see [`AppBuilder::record_input`].
+    pub fn record_input(&mut self, sink: impl FnMut(&RecordedInput) + 'static) {
+        self.recorder = Some(Recorder::new(sink));
+    }
+
+    /// Stop recording inputs.
+    pub fn stop_recording(&mut self) {
+        self.recorder = None;
+    }
+
+    /// Check whether inputs are being recorded.
+    pub fn is_recording(&self) -> bool {
+        self.recorder.is_some()
+    }
+
+    /// Replay recorded inputs, see [`AppBuilder::replay`].
+    pub fn replay(
+        &mut self,
+        inputs: impl IntoIterator<Item = RecordedInput>,
+        timing: ReplayTiming,
+    ) {
+        self.replay = Some(Replay::new(inputs, timing));
+    }
+
+    /// Check whether there are recorded inputs left to replay.
+    pub fn is_replaying(&self) -> bool {
+        self.replay.is_some()
+    }
+
+    /// Handle an input, by calling the method of the application it corresponds to.
+    ///
+    /// Returns `false` if the window of the input doesn't exist, otherwise what the method
+    /// returns.
+    pub fn input(&mut self, data: &mut T, input: &AppInput) -> bool {
+        if let AppInput::ModifiersChanged { modifiers } = *input {
+            self.modifiers_changed(modifiers);
+            return false;
+        }
+
+        let window_id = (self.window_indices.iter())
+            .find(|&(_, &window)| Some(window) == input.window())
+            .map(|(&window_id, _)| window_id);
+
+        let Some(window_id) = window_id else {
+            return false;
+        };
+
+        match *input {
+            AppInput::WindowResized { width, height, .. } => {
+                self.window_resized(data, window_id, width, height)
+            }
+            AppInput::PointerMoved {
+                pointer, position, ..
+            } => self.pointer_moved(data, window_id, pointer, position),
+            AppInput::PointerLeft { pointer, .. } => self.pointer_left(data, window_id, pointer),
+            AppInput::PointerStylus {
+                pointer,
+                pressure,
+                tilt,
+                tool,
+                ..
+            } => {
+                self.pointer_stylus(window_id, pointer, pressure, tilt, tool);
+                false
+            }
+            AppInput::PointerScrolled { pointer, delta, .. } => {
+                self.pointer_scrolled(data, window_id, pointer, delta)
+            }
+            AppInput::PointerButton {
+                pointer,
+                button,
+                pressed,
+                ..
+            } => self.pointer_button(data, window_id, pointer, button, pressed),
+            AppInput::KeyboardKey {
+                key,
+                code,
+                ref text,
+                pressed,
+                ..
+            } => self.keyboard_key(data, window_id, key, code, text.clone(), pressed),
+            // handled above, since it isn't for a window
+            AppInput::ModifiersChanged { .. } => false,
+        }
+    }
+
+    // record an input to `window_id`, built from the index of the window
+    pub(crate) fn record(&mut self, window_id: WindowId, input: impl FnOnce(usize) -> AppInput) {
+        let Some(ref mut recorder) = self.recorder else {
+            return;
+        };
+
+        if let Some(&window) = self.window_indices.get(&window_id) {
+            recorder.record(input(window));
+        }
+    }
+
+    pub(crate) fn record_modifiers(&mut self, modifiers: Modifiers) {
+        if let Some(ref mut recorder) = self.recorder {
+            recorder.record(AppInput::ModifiersChanged { modifiers });
+        }
+    }
+
+    // how long until the next replayed input is due
+    pub(crate) fn replay_delay(&self) -> Option<Duration> {
+        self.replay.as_ref().and_then(Replay::delay)
+    }
+
+    // feed the replayed inputs that are due into the application
+    pub(crate) fn replay_inputs(&mut self, data: &mut T) {
+        while let Some(input) = self.replay.as_mut().and_then(Replay::next_due) {
+            self.input(data, &input);
+        }
+
+        if self
+            .replay
+            .as_ref()
+            .is_some_and(|replay| replay.inputs.is_empty())
+        {
+            self.replay = None;
+        }
+    }
+}
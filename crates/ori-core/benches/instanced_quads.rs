@@ -0,0 +1,67 @@
+//! Compares recording 10k quads as individual rectangles against a single instanced draw.
+//!
+//! This only measures building the canvas, the `instanced_quads` bench of ori-skia measures
+//! rendering it.
+//!
+//! Run with `cargo bench -p ori-core --bench instanced_quads`.
+
+use std::{hint::black_box, time::Instant};
+
+use ori_core::{
+    canvas::{Canvas, Color, QuadInstance},
+    layout::{Point, Rect, Size},
+};
+
+const QUADS: usize = 10_000;
+const ITERATIONS: u32 = 100;
+
+fn quads() -> Vec<QuadInstance> {
+    (0..QUADS)
+        .map(|i| {
+            let x = (i % 100) as f32 * 12.0;
+            let y = (i / 100) as f32 * 12.0;
+
+            let rect = Rect::min_size(Point::new(x, y), Size::all(10.0));
+            let color = Color::hsl(i as f32 * 0.036, 0.8, 0.5);
+
+            QuadInstance::rect(rect, color)
+        })
+        .collect()
+}
+
+fn bench(name: &str, mut f: impl FnMut() -> Canvas) {
+    let start = Instant::now();
+
+    for _ in 0..ITERATIONS {
+        black_box(f());
+    }
+
+    let elapsed = start.elapsed() / ITERATIONS;
+    let primitives = f().primitives().count();
+
+    println!(
+        "{name:>12}: {:>10.3?} per frame, {primitives} primitives",
+        elapsed
+    );
+}
+
+fn main() {
+    let instances = quads();
+
+    bench("individual", || {
+        let mut canvas = Canvas::new();
+
+        for instance in &instances {
+            let [min, _, max, _] = instance.corners();
+            canvas.rect(Rect::new(min, max), instance.color);
+        }
+
+        canvas
+    });
+
+    bench("instanced", || {
+        let mut canvas = Canvas::new();
+        canvas.draw_quads_instanced(&instances);
+        canvas
+    });
+}
@@ -0,0 +1,58 @@
+//! Compares laying out a 10 deep tree of nested containers with an unchanged space, which is
+//! answered from the layout cache, against a space that changes every frame.
+//!
+//! Run with `cargo bench -p ori-core --bench nested_layout`.
+
+use std::{hint::black_box, time::Instant};
+
+use ori_core::{
+    command::{CommandProxy, CommandWaker},
+    context::{BaseCx, BuildCx, Contexts, LayoutCx},
+    layout::{Size, Space},
+    style::Styles,
+    view::{Pod, View, ViewState},
+    views::{container, pad, size, vstack},
+    window::Window,
+};
+
+const ITERATIONS: u32 = 1000;
+
+fn level(content: impl View) -> impl View {
+    container(pad(4.0, vstack![size(20.0, ()), content, size(20.0, ())]))
+}
+
+fn tree() -> Pod<impl View> {
+    let view = level(level(level(level(level(size(20.0, ()))))));
+    Pod::new(level(level(level(level(level(view))))))
+}
+
+fn bench(name: &str, mut space: impl FnMut(u32) -> Space) {
+    let mut contexts = Contexts::new();
+    contexts.insert(Window::new());
+    contexts.insert(Styles::new());
+
+    let (mut proxy, _receiver) = CommandProxy::new(CommandWaker::new(|| {}));
+    let mut base = BaseCx::new(&mut contexts, &mut proxy);
+    let mut view_state = ViewState::default();
+
+    let mut view = tree();
+    let mut state = view.build(&mut BuildCx::new(&mut base, &mut view_state), &mut ());
+
+    let start = Instant::now();
+
+    for i in 0..ITERATIONS {
+        let mut cx = LayoutCx::new(&mut base, &mut view_state);
+        black_box(view.layout(&mut state, &mut cx, &mut (), space(i)));
+    }
+
+    let elapsed = start.elapsed() / ITERATIONS;
+    println!("{name:>12}: {elapsed:>10.3?} per layout");
+}
+
+fn main() {
+    bench("cached", |_| Space::new(Size::ZERO, Size::all(800.0)));
+
+    bench("uncached", |i| {
+        Space::new(Size::ZERO, Size::all(800.0 + (i % 2) as f32))
+    });
+}
@@ -0,0 +1,130 @@
+//! Compares laying out an unchanged label every frame with fonts that cache laid out text in a
+//! `TextLayoutCache`, against fonts that shape the label again every frame.
+//!
+//! Run with `cargo bench -p ori-core --bench text_layout`.
+
+use std::{hint::black_box, time::Instant};
+
+use ori_core::{
+    command::{CommandProxy, CommandWaker},
+    context::{BaseCx, BuildCx, Contexts, LayoutCx},
+    layout::{Point, Rect, Size, Space},
+    style::Styles,
+    text::{
+        FontSource, Fonts, GlyphCluster, Paragraph, TextDirection, TextLayoutCache, TextLayoutLine,
+    },
+    view::{View, ViewState},
+    views::text,
+    window::Window,
+};
+
+const FRAMES: u32 = 1000;
+
+const LABEL: &str = "The quick brown fox jumps over the lazy dog, again and again and again.";
+
+// stands in for a real shaper, with a bit of work per character
+struct BenchFonts {
+    cache: Option<TextLayoutCache>,
+    shaped: u32,
+}
+
+impl BenchFonts {
+    fn shape(&mut self, paragraph: &Paragraph) -> Vec<TextLayoutLine> {
+        self.shaped += 1;
+
+        let mut advance = 0.0;
+        let mut glyphs = Vec::new();
+
+        for (i, c) in paragraph.text().char_indices() {
+            let width = (0..64).fold(c as u32 as f32, |w, _| black_box(w.sqrt() + 8.0));
+            let min = Point::new(advance, 0.0);
+
+            glyphs.push(GlyphCluster {
+                bounds: Rect::min_size(min, Size::new(width, 20.0)),
+                range: i..i + c.len_utf8(),
+                direction: TextDirection::Ltr,
+            });
+
+            advance += width;
+        }
+
+        vec![TextLayoutLine {
+            ascent: 16.0,
+            descent: 4.0,
+            left: 0.0,
+            width: advance,
+            height: 20.0,
+            baseline: 16.0,
+            range: 0..paragraph.text().len(),
+            glyphs,
+        }]
+    }
+}
+
+impl Fonts for BenchFonts {
+    fn load(&mut self, _source: FontSource<'_>, _name: Option<&str>) {}
+
+    fn layout(&mut self, paragraph: &Paragraph, width: f32) -> Vec<TextLayoutLine> {
+        if let Some(lines) = self.cache.as_mut().and_then(|c| c.lines(paragraph, width)) {
+            return lines;
+        }
+
+        let lines = self.shape(paragraph);
+
+        if let Some(ref mut cache) = self.cache {
+            cache.insert_lines(paragraph, width, lines.clone());
+        }
+
+        lines
+    }
+
+    fn measure(&mut self, paragraph: &Paragraph, width: f32) -> Size {
+        if let Some(size) = self.cache.as_mut().and_then(|c| c.size(paragraph, width)) {
+            return size;
+        }
+
+        let line = self.shape(paragraph).remove(0);
+        let size = Size::new(line.width, line.height);
+
+        if let Some(ref mut cache) = self.cache {
+            cache.insert_size(paragraph, width, size);
+        }
+
+        size
+    }
+}
+
+fn bench(name: &str, cache: Option<TextLayoutCache>) {
+    let mut contexts = Contexts::new();
+    contexts.insert(Window::new());
+    contexts.insert(Styles::new());
+    contexts.insert::<Box<dyn Fonts>>(Box::new(BenchFonts { cache, shaped: 0 }));
+
+    let (mut proxy, _receiver) = CommandProxy::new(CommandWaker::new(|| {}));
+    let mut base = BaseCx::new(&mut contexts, &mut proxy);
+    let mut view_state = ViewState::default();
+
+    let mut view = text(LABEL);
+    let mut state = view.build(&mut BuildCx::new(&mut base, &mut view_state), &mut ());
+
+    let start = Instant::now();
+
+    // the label is laid out every frame, without the layout cache of a pod in between
+    for _ in 0..FRAMES {
+        let mut cx = LayoutCx::new(&mut base, &mut view_state);
+        let space = Space::new(Size::ZERO, Size::all(800.0));
+        black_box(view.layout(&mut state, &mut cx, &mut (), space));
+    }
+
+    let elapsed = start.elapsed() / FRAMES;
+
+    let fonts = base.fonts().downcast_ref::<BenchFonts>().unwrap();
+    let shaped = fonts.shaped;
+
+    println!("{name:>12}: {elapsed:>10.3?} per frame, shaped {shaped} times in {FRAMES} frames");
+}
+
+fn main() {
+    bench("cached", Some(TextLayoutCache::default()));
+    bench("uncached", None);
+}
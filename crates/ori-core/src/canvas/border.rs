@@ -1,4 +1,6 @@
-use crate::style::Styled;
+use crate::{image::Image, style::Styled};
+
+use super::{Color, ConicGradient, Paint, Pattern};
 
 /// Radi of the corners on a rounded rectangle.
 #[derive(Clone, Copy, Debug, Default, PartialEq)]
@@ -236,3 +238,166 @@ impl From<f32> for Styled<BorderWidth> {
         Self::Value(BorderWidth::from(x))
     }
 }
+
+/// An edge of the border of a rectangle.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BorderEdge {
+    /// The top edge.
+    Top,
+    /// The right edge.
+    Right,
+    /// The bottom edge.
+    Bottom,
+    /// The left edge.
+    Left,
+}
+
+impl BorderEdge {
+    /// All the edges, going clockwise from the top.
+    pub const ALL: [Self; 4] = [Self::Top, Self::Right, Self::Bottom, Self::Left];
+}
+
+/// The border color of a rounded rectangle.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BorderColor {
+    /// The top border color.
+    pub top: Color,
+    /// The right border color.
+    pub right: Color,
+    /// The bottom border color.
+    pub bottom: Color,
+    /// The left border color.
+    pub left: Color,
+}
+
+impl BorderColor {
+    /// Create a new [`BorderColor`].
+    pub const fn new(top: Color, right: Color, bottom: Color, left: Color) -> Self {
+        Self {
+            top,
+            right,
+            bottom,
+            left,
+        }
+    }
+
+    /// Create a new [`BorderColor`] with the same color on all borders.
+    pub const fn all(color: Color) -> Self {
+        Self {
+            top: color,
+            right: color,
+            bottom: color,
+            left: color,
+        }
+    }
+
+    /// Get the color shared by all borders, if there is one.
+    pub fn uniform(&self) -> Option<Color> {
+        let uniform = self.top == self.right && self.top == self.bottom && self.top == self.left;
+        uniform.then_some(self.top)
+    }
+}
+
+impl From<(Color, Color, Color, Color)> for BorderColor {
+    fn from((top, right, bottom, left): (Color, Color, Color, Color)) -> Self {
+        Self::new(top, right, bottom, left)
+    }
+}
+
+impl From<[Color; 4]> for BorderColor {
+    fn from([top, right, bottom, left]: [Color; 4]) -> Self {
+        Self::new(top, right, bottom, left)
+    }
+}
+
+impl From<Color> for BorderColor {
+    fn from(color: Color) -> Self {
+        Self::all(color)
+    }
+}
+
+impl From<BorderColor> for [Color; 4] {
+    fn from(color: BorderColor) -> Self {
+        [color.top, color.right, color.bottom, color.left]
+    }
+}
+
+impl From<(Color, Color, Color, Color)> for Styled<BorderColor> {
+    fn from(x: (Color, Color, Color, Color)) -> Self {
+        Self::Value(BorderColor::from(x))
+    }
+}
+
+impl From<[Color; 4]> for Styled<BorderColor> {
+    fn from(x: [Color; 4]) -> Self {
+        Self::Value(BorderColor::from(x))
+    }
+}
+
+/// The paint of the border of a rounded rectangle, see
+/// [`DrawCx::quad`](crate::context::DrawCx::quad).
+///
+/// A border is either filled with a single [`Paint`], eg. a gradient, or with a color per edge.
+#[derive(Clone, Debug, PartialEq)]
+pub enum BorderPaint {
+    /// A paint shared by all the edges.
+    Uniform(Paint),
+
+    /// A color per edge.
+    Edges(BorderColor),
+}
+
+impl Default for BorderPaint {
+    fn default() -> Self {
+        Self::Edges(BorderColor::default())
+    }
+}
+
+impl From<Paint> for BorderPaint {
+    fn from(paint: Paint) -> Self {
+        Self::Uniform(paint)
+    }
+}
+
+impl From<Color> for BorderPaint {
+    fn from(color: Color) -> Self {
+        Self::Edges(BorderColor::all(color))
+    }
+}
+
+impl From<Image> for BorderPaint {
+    fn from(image: Image) -> Self {
+        Self::Uniform(Paint::from(image))
+    }
+}
+
+impl From<Pattern> for BorderPaint {
+    fn from(pattern: Pattern) -> Self {
+        Self::Uniform(Paint::from(pattern))
+    }
+}
+
+impl From<ConicGradient> for BorderPaint {
+    fn from(gradient: ConicGradient) -> Self {
+        Self::Uniform(Paint::from(gradient))
+    }
+}
+
+impl From<BorderColor> for BorderPaint {
+    fn from(color: BorderColor) -> Self {
+        Self::Edges(color)
+    }
+}
+
+impl From<(Color, Color, Color, Color)> for BorderPaint {
+    fn from(colors: (Color, Color, Color, Color)) -> Self {
+        Self::Edges(BorderColor::from(colors))
+    }
+}
+
+impl From<[Color; 4]> for BorderPaint {
+    fn from(colors: [Color; 4]) -> Self {
+        Self::Edges(BorderColor::from(colors))
+    }
+}
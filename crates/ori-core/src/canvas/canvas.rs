@@ -1,18 +1,19 @@
 use std::{
     collections::BTreeMap,
+    f32::consts::SQRT_2,
     hash::{Hash, Hasher},
     mem,
     sync::Arc,
 };
 
 use crate::{
-    image::Image,
-    layout::{Affine, Point, Rect, Vector},
+    image::{Image, ImageData, ImageHandle, TextureId},
+    layout::{Affine, Point, Rect, Size, Vector},
     text::Paragraph,
     view::ViewId,
 };
 
-use super::{Color, Curve, Stroke};
+use super::{BorderRadius, Color, ColorMatrix, ConicGradient, Curve, Mesh, QuadInstance, Stroke};
 
 /// A pattern that can be used to fill a shape.
 #[derive(Clone, Debug, PartialEq)]
@@ -53,10 +54,13 @@ pub enum Shader {
 
     /// A pattern.
     Pattern(Pattern),
+
+    /// A conic gradient.
+    ConicGradient(ConicGradient),
 }
 
 /// Ways to blend two colors.
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
 pub enum BlendMode {
     /// Replaces the destination with zero.
     Clear,
@@ -68,10 +72,26 @@ pub enum BlendMode {
     Destination,
 
     /// Source over destination.
+    #[default]
     SourceOver,
 
     /// Destination over source.
     DestinationOver,
+
+    /// Multiplies the source and destination, darkening the result.
+    Multiply,
+
+    /// Inverse of multiplying the inverted source and destination, lightening the result.
+    Screen,
+
+    /// Multiplies or screens depending on the destination.
+    Overlay,
+
+    /// Keeps the darker of the source and destination.
+    Darken,
+
+    /// Keeps the lighter of the source and destination.
+    Lighten,
 }
 
 /// Ways to anti-alias a shape.
@@ -137,6 +157,15 @@ impl From<Pattern> for Paint {
     }
 }
 
+impl From<ConicGradient> for Paint {
+    fn from(value: ConicGradient) -> Self {
+        Self {
+            shader: Shader::ConicGradient(value),
+            ..Default::default()
+        }
+    }
+}
+
 /// Rule determining if a point is inside a shape.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum FillRule {
@@ -201,6 +230,33 @@ pub enum Primitive {
         paint: Paint,
     },
 
+    /// A mesh of triangles colored by their vertices.
+    Mesh {
+        /// The mesh to draw.
+        mesh: Arc<Mesh>,
+    },
+
+    /// A filled rectangle with rounded corners, and a stroke inside its edges.
+    ///
+    /// Backends draw rounded rectangles analytically, so the edges are antialiased without
+    /// multisampling.
+    RoundedRect {
+        /// The rectangle to draw.
+        rect: Rect,
+
+        /// The radii of the corners.
+        radius: BorderRadius,
+
+        /// The paint to fill the rectangle with.
+        fill: Paint,
+
+        /// The width of the stroke, drawn inside the edges of the rectangle.
+        stroke_width: f32,
+
+        /// The color of the stroke.
+        stroke: Color,
+    },
+
     /// A paragraph on rich text.
     Paragraph {
         /// The paragraph to draw.
@@ -224,25 +280,101 @@ pub enum Primitive {
         /// The mask of the layer.
         mask: Option<Mask>,
 
+        /// The blend mode used to composite the layer.
+        blend: BlendMode,
+
+        /// The color filter applied to the layer.
+        filter: Option<ColorMatrix>,
+
         /// The view of the layer.
         view: Option<ViewId>,
     },
+
+    /// Primitives drawn to an off-screen texture instead of the canvas.
+    ///
+    /// The texture can be drawn by images with the same [`TextureId`], see
+    /// [`Canvas::render_to_texture`].
+    ///
+    /// Renderers draw every off-screen primitive before anything else, so the texture is ready
+    /// wherever it's sampled on the canvas.
+    Offscreen {
+        /// The primitives drawn to the texture.
+        primitives: Arc<Vec<Primitive>>,
+
+        /// The texture to draw to.
+        texture: TextureId,
+
+        /// The width of the texture in logical pixels, the texture is allocated in physical
+        /// pixels by the renderer.
+        width: u32,
+
+        /// The height of the texture in logical pixels.
+        height: u32,
+    },
 }
 
 impl Primitive {
     /// Count the number of primitives.
     pub fn count(&self) -> usize {
         match self {
-            Primitive::Fill { .. } | Primitive::Stroke { .. } | Primitive::Paragraph { .. } => 1,
-            Primitive::Layer { primitives, .. } => primitives.iter().map(Self::count).sum(),
+            Primitive::Fill { .. }
+            | Primitive::Stroke { .. }
+            | Primitive::Mesh { .. }
+            | Primitive::RoundedRect { .. }
+            | Primitive::Paragraph { .. } => 1,
+            Primitive::Layer { primitives, .. } | Primitive::Offscreen { primitives, .. } => {
+                primitives.iter().map(Self::count).sum()
+            }
+        }
+    }
+
+    /// Get the bounds of what the primitive draws on the canvas, this may be larger than the
+    /// area actually drawn.
+    ///
+    /// Returns `None` if the primitive doesn't draw on the canvas.
+    pub fn bounds(&self) -> Option<Rect> {
+        match self {
+            Primitive::Fill { curve, .. } => Some(curve.bounds()),
+            Primitive::Stroke { curve, stroke, .. } => {
+                // miter joins and square caps reach past half the width of the stroke
+                let reach = stroke.width / 2.0 * stroke.miter.max(SQRT_2);
+                Some(curve.bounds().expand(reach))
+            }
+            Primitive::Mesh { mesh } => Some(mesh.bounds()),
+            Primitive::RoundedRect { rect, .. } => Some(*rect),
+            Primitive::Paragraph { bounds, rect, .. } => Some(bounds.union(*rect)),
+            Primitive::Layer {
+                primitives,
+                transform,
+                ..
+            } => {
+                let bounds = primitives.iter().filter_map(Self::bounds);
+                let bounds = bounds.reduce(Rect::union)?;
+                Some(bounds.transform(*transform))
+            }
+            Primitive::Offscreen { .. } => None,
         }
     }
 }
 
+/// A popup drawn on a [`Canvas`], see [`Canvas::popup`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct CanvasPopup {
+    /// The rect of the popup, in the coordinates of the window.
+    pub rect: Rect,
+
+    /// Whether the popup grabs the pointer, see [`Popup::grab`](crate::window::Popup::grab).
+    pub grab: bool,
+
+    /// What is drawn in the popup, relative to the top-left corner of `rect`.
+    pub canvas: Canvas,
+}
+
 /// A canvas that can be drawn on.
 #[derive(Clone, Debug, PartialEq)]
 pub struct Canvas {
     overlays: BTreeMap<i32, Arc<Vec<Primitive>>>,
+    popups: Vec<CanvasPopup>,
     primitives: Arc<Vec<Primitive>>,
 }
 
@@ -257,19 +389,58 @@ impl Canvas {
     pub fn new() -> Self {
         Self {
             overlays: BTreeMap::new(),
+            popups: Vec::new(),
             primitives: Arc::new(Vec::new()),
         }
     }
 
-    /// Get the primitives of the canvas.
+    /// Get the primitives of the canvas, including overlays but not popups.
     pub fn primitives(&self) -> impl Iterator<Item = &Primitive> + '_ {
         let overlays = self.overlays.values().flat_map(|p| p.iter());
         self.primitives.iter().chain(overlays)
     }
 
+    /// Get the bounds of what is drawn on the canvas itself, not counting overlays.
+    ///
+    /// These are in the coordinates of the canvas, while overlays are drawn in the
+    /// coordinates of the window, see [`Canvas::overlay_bounds`].
+    pub fn content_bounds(&self) -> Option<Rect> {
+        (self.primitives.iter())
+            .filter_map(Primitive::bounds)
+            .reduce(Rect::union)
+    }
+
+    /// Get the bounds of the overlays and popups drawn on the canvas, in window coordinates.
+    pub fn overlay_bounds(&self) -> Option<Rect> {
+        let overlays = (self.overlays.values())
+            .flat_map(|primitives| primitives.iter())
+            .filter_map(Primitive::bounds);
+
+        let popups = self.popups.iter().map(|popup| popup.rect);
+        overlays.chain(popups).reduce(Rect::union)
+    }
+
+    /// Get the popups drawn on the canvas, in the order they were drawn.
+    pub fn popups(&self) -> &[CanvasPopup] {
+        &self.popups
+    }
+
+    /// Draw the popups of the canvas over everything else, as if they were overlays.
+    ///
+    /// This is how popups are shown when the platform can't open windows for them, see
+    /// [`Window::popup_windows`](crate::window::Window::popup_windows).
+    pub fn flatten_popups(&mut self) {
+        for popup in mem::take(&mut self.popups) {
+            self.overlay(i32::MAX, |canvas| {
+                canvas.translated(popup.rect.offset(), |canvas| *canvas = popup.canvas);
+            });
+        }
+    }
+
     /// Clear the canvas.
     pub fn clear(&mut self) {
         self.overlays.clear();
+        self.popups.clear();
         Arc::make_mut(&mut self.primitives).clear();
     }
 
@@ -320,6 +491,41 @@ impl Canvas {
         });
     }
 
+    /// Draw a mesh.
+    pub fn mesh(&mut self, mesh: impl Into<Arc<Mesh>>) {
+        let primitives = Arc::make_mut(&mut self.primitives);
+        primitives.push(Primitive::Mesh { mesh: mesh.into() });
+    }
+
+    /// Draw a rectangle with rounded corners, filled with `fill` and stroked inside its edges.
+    ///
+    /// Unlike filling a rounded rectangle curve, backends draw this analytically, so the corners
+    /// are crisp and antialiased regardless of multisampling.
+    pub fn draw_rounded_rect(
+        &mut self,
+        rect: Rect,
+        radius: impl Into<BorderRadius>,
+        fill: impl Into<Paint>,
+        stroke_width: f32,
+        stroke: Color,
+    ) {
+        let primitives = Arc::make_mut(&mut self.primitives);
+        primitives.push(Primitive::RoundedRect {
+            rect,
+            radius: radius.into(),
+            fill: fill.into(),
+            stroke_width,
+            stroke,
+        });
+    }
+
+    /// Draw many instances of a quad, see [`Mesh::quads`].
+    pub fn draw_quads_instanced(&mut self, instances: &[QuadInstance]) {
+        if !instances.is_empty() {
+            self.mesh(Mesh::quads(instances));
+        }
+    }
+
     /// Draw a paragraph.
     pub fn paragraph(&mut self, paragraph: Paragraph, rect: Rect, bounds: Rect) {
         let primitives = Arc::make_mut(&mut self.primitives);
@@ -333,7 +539,38 @@ impl Canvas {
 
     /// Draw a canvas.
     pub fn draw_canvas(&mut self, canvas: Canvas) {
-        self.layer(Affine::IDENTITY, None, None, |ca| *ca = canvas);
+        self.layer(Affine::IDENTITY, None, None, BlendMode::SourceOver, |ca| {
+            *ca = canvas
+        });
+    }
+
+    /// Draw to an off-screen texture of `size`, in logical pixels, and get a handle to the
+    /// texture.
+    ///
+    /// The image of the handle can be drawn like any other image, eg. with a [`Pattern`], and
+    /// contains what `f` drew once the canvas is rendered. The image has no pixels of its own,
+    /// the renderer creates the texture when the canvas is rendered, in physical pixels so it
+    /// stays sharp when scaled. Overlays drawn by `f` are drawn on top of the texture, not the
+    /// canvas. The texture is freed by the renderer when the image isn't drawn anymore.
+    pub fn render_to_texture(&mut self, size: Size, f: impl FnOnce(&mut Self)) -> ImageHandle {
+        let mut target = Canvas::new();
+        f(&mut target);
+
+        let width = f32::max(size.width.ceil(), 1.0) as u32;
+        let height = f32::max(size.height.ceil(), 1.0) as u32;
+        let handle = ImageHandle::new(ImageData::unallocated(width, height));
+
+        self.popups.append(&mut target.popups);
+
+        let primitives = Arc::make_mut(&mut self.primitives);
+        primitives.push(Primitive::Offscreen {
+            primitives: Arc::new(target.primitives().cloned().collect()),
+            texture: handle.texture_id(),
+            width,
+            height,
+        });
+
+        handle
     }
 
     /// Draw an overlay.
@@ -348,6 +585,8 @@ impl Canvas {
             primitives.extend(others);
         }
 
+        self.popups.append(&mut overlay.popups);
+
         let other = mem::take(Arc::make_mut(&mut overlay.primitives));
         let primitives = Arc::make_mut(self.overlays.entry(index).or_default());
         primitives.extend(other);
@@ -355,12 +594,45 @@ impl Canvas {
         result
     }
 
+    /// Draw a popup at `rect`, in the coordinates of the window.
+    ///
+    /// Popups are drawn over everything else, and the platform might show them in windows of
+    /// their own so they can extend beyond the window, see
+    /// [`Window::popup_windows`](crate::window::Window::popup_windows). `f` draws relative to
+    /// the top-left corner of `rect`, and hit testing doesn't reach past the rect.
+    pub fn popup<T>(&mut self, rect: Rect, grab: bool, f: impl FnOnce(&mut Self) -> T) -> T {
+        let mut canvas = Canvas::new();
+
+        let result = f(&mut canvas);
+
+        // popups drawn inside the popup are placed in the window as well
+        let popups = mem::take(&mut canvas.popups);
+
+        self.popups.push(CanvasPopup { rect, grab, canvas });
+        self.popups.extend(popups);
+
+        result
+    }
+
     /// Draw a layer.
     pub fn layer<T>(
         &mut self,
         transform: Affine,
         mask: Option<Mask>,
         view: Option<ViewId>,
+        blend: BlendMode,
+        f: impl FnOnce(&mut Self) -> T,
+    ) -> T {
+        self.layer_inner(transform, mask, view, blend, None, f)
+    }
+
+    fn layer_inner<T>(
+        &mut self,
+        transform: Affine,
+        mask: Option<Mask>,
+        view: Option<ViewId>,
+        blend: BlendMode,
+        filter: Option<ColorMatrix>,
         f: impl FnOnce(&mut Self) -> T,
     ) -> T {
         let mut layer = Canvas::new();
@@ -373,11 +645,15 @@ impl Canvas {
             primitives.extend(other);
         }
 
+        self.popups.append(&mut layer.popups);
+
         let primitives = Arc::make_mut(&mut self.primitives);
         primitives.push(Primitive::Layer {
             primitives: layer.primitives,
             transform,
             mask,
+            blend,
+            filter,
             view,
         });
 
@@ -386,7 +662,7 @@ impl Canvas {
 
     /// Draw a layer with a transformation.
     pub fn transformed<T>(&mut self, transform: Affine, f: impl FnOnce(&mut Self) -> T) -> T {
-        self.layer(transform, None, None, f)
+        self.layer(transform, None, None, BlendMode::SourceOver, f)
     }
 
     /// Draw a layer with a translation.
@@ -406,12 +682,47 @@ impl Canvas {
 
     /// Draw a layer with a mask.
     pub fn masked<T>(&mut self, mask: Mask, f: impl FnOnce(&mut Self) -> T) -> T {
-        self.layer(Affine::IDENTITY, Some(mask), None, f)
+        self.layer(Affine::IDENTITY, Some(mask), None, BlendMode::SourceOver, f)
+    }
+
+    /// Draw a layer composited with a blend mode.
+    pub fn blended<T>(&mut self, blend: BlendMode, f: impl FnOnce(&mut Self) -> T) -> T {
+        self.layer(Affine::IDENTITY, None, None, blend, f)
+    }
+
+    /// Draw a layer with its colors transformed by a [`ColorMatrix`].
+    pub fn filtered<T>(&mut self, filter: ColorMatrix, f: impl FnOnce(&mut Self) -> T) -> T {
+        let blend = BlendMode::SourceOver;
+        self.layer_inner(Affine::IDENTITY, None, None, blend, Some(filter), f)
     }
 
     /// Draw a layer with a view.
     pub fn hoverable<T>(&mut self, view: ViewId, f: impl FnOnce(&mut Self) -> T) -> T {
-        self.layer(Affine::IDENTITY, None, Some(view), f)
+        self.layer(Affine::IDENTITY, None, Some(view), BlendMode::SourceOver, f)
+    }
+
+    /// Draw a layer that is ignored by [`Canvas::view_at`], even where views inside of it are
+    /// hoverable.
+    pub fn non_hoverable<T>(&mut self, f: impl FnOnce(&mut Self) -> T) -> T {
+        fn clear_views(primitives: &mut [Primitive]) {
+            for primitive in primitives {
+                if let Primitive::Layer {
+                    primitives, view, ..
+                } = primitive
+                {
+                    *view = None;
+                    clear_views(Arc::make_mut(primitives).as_mut_slice());
+                }
+            }
+        }
+
+        let result = self.layer(Affine::IDENTITY, None, None, BlendMode::SourceOver, f);
+
+        if let Some(layer) = Arc::make_mut(&mut self.primitives).last_mut() {
+            clear_views(std::slice::from_mut(layer));
+        }
+
+        result
     }
 
     /// Get the view at a point.
@@ -438,12 +749,33 @@ impl Canvas {
                         }
 
                         let mut stroked = Curve::new();
-                        stroked.stroke_curve(curve, *stroke);
+                        stroked.stroke_curve(curve, stroke);
 
                         if stroked.contains(point, FillRule::NonZero) {
                             return view;
                         }
                     }
+                    Primitive::Mesh { mesh } => {
+                        if view.is_none() {
+                            continue;
+                        }
+
+                        if mesh.contains(point) {
+                            return view;
+                        }
+                    }
+                    Primitive::RoundedRect { rect, radius, .. } => {
+                        if view.is_none() || !rect.contains(point) {
+                            continue;
+                        }
+
+                        let mut curve = Curve::new();
+                        curve.push_rect_with_radius(*rect, *radius);
+
+                        if curve.contains(point, FillRule::NonZero) {
+                            return view;
+                        }
+                    }
                     Primitive::Paragraph { bounds, .. } => {
                         if view.is_none() {
                             continue;
@@ -458,6 +790,7 @@ impl Canvas {
                         transform,
                         mask,
                         view: layer_view,
+                        ..
                     } => {
                         let point = transform.inverse() * point;
 
@@ -476,12 +809,20 @@ impl Canvas {
                             return view;
                         }
                     }
+                    Primitive::Offscreen { .. } => {}
                 }
             }
 
             None
         }
 
+        // popups hide what is beneath them, even where they draw nothing
+        for popup in self.popups.iter().rev() {
+            if popup.rect.contains(point) {
+                return popup.canvas.view_at(point - popup.rect.offset());
+            }
+        }
+
         for primitives in self.overlays.values().rev() {
             if let Some(view) = recurse(primitives, None, point) {
                 return Some(view);
@@ -4,6 +4,8 @@ use std::{
     ops::{Add, AddAssign, Deref, Mul},
 };
 
+use super::BlendMode;
+
 /// Create a new color, with the given `red`, `green` and `blue` components.
 pub fn rgb(r: f32, g: f32, b: f32) -> Color {
     Color::rgb(r, g, b)
@@ -79,7 +81,59 @@ pub fn hex(hex: &str) -> Color {
     Color::hex(hex)
 }
 
+/// The color space a window is rendered in, see `Window::color_space`.
+///
+/// Colors are always specified in sRGB, and converted to the color space of the window by
+/// the renderer. Colors outside the sRGB gamut, eg. those created with [`Color::display_p3`],
+/// have components below `0.0` or above `1.0`, and are clamped when rendered in sRGB.
+///
+/// The Skia renderer supports [`ColorSpace::Srgb`] and [`ColorSpace::DisplayP3`], the latter
+/// only where the driver supports Display P3 surfaces, otherwise the window falls back to sRGB.
+/// The surfaces of the shell backends are 8 bit, so [`ColorSpace::ExtendedSrgb`] is rendered
+/// as sRGB.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ColorSpace {
+    /// Standard dynamic range sRGB, the default.
+    #[default]
+    Srgb,
+
+    /// The wide gamut Display P3 color space, with the transfer function of sRGB.
+    DisplayP3,
+
+    /// Extended range sRGB, where components above `1.0` are brighter than white.
+    ///
+    /// This requires a surface with floating point components.
+    ExtendedSrgb,
+}
+
+impl ColorSpace {
+    /// Check if the color space covers colors outside of the sRGB gamut.
+    pub fn is_wide_gamut(self) -> bool {
+        matches!(self, Self::DisplayP3 | Self::ExtendedSrgb)
+    }
+}
+
+/// The color space colors are interpolated in, see [`Color::mix_in`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ColorInterpolation {
+    /// The perceptually uniform oklab color space, the default.
+    ///
+    /// Mixing complementary colors in oklab keeps the midpoint saturated.
+    #[default]
+    Oklab,
+
+    /// The sRGB color space, mixing the components directly.
+    ///
+    /// Mixing complementary colors in sRGB passes through dull, darker colors.
+    Srgb,
+}
+
 /// A color with red, green, blue and alpha components.
+///
+/// Components are usually in the range `0.0..=1.0`, but may exceed it for colors outside of
+/// the sRGB gamut or brighter than white, see [`ColorSpace`].
 #[repr(C)]
 #[derive(Copy, Clone, Debug, Default, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -842,14 +896,45 @@ impl Color {
         l
     }
 
+    /// Get the relative luminance, as defined by WCAG.
+    ///
+    /// See <https://www.w3.org/TR/WCAG21/#dfn-relative-luminance>.
+    pub fn relative_luminance(self) -> f32 {
+        let [r, g, b, _] = self.to_srgb();
+        0.2126 * r + 0.7152 * g + 0.0722 * b
+    }
+
+    /// Get the contrast ratio between two colors, as defined by WCAG.
+    ///
+    /// The ratio ranges from `1.0` to `21.0`.
+    ///
+    /// See <https://www.w3.org/TR/WCAG21/#dfn-contrast-ratio>.
+    pub fn contrast_ratio(self, other: Self) -> f32 {
+        let a = self.relative_luminance();
+        let b = other.relative_luminance();
+
+        (f32::max(a, b) + 0.05) / (f32::min(a, b) + 0.05)
+    }
+
     /// Linearly interpolate between two colors.
     ///
     /// This uses a fractor `t` between `0.0` and `1.0`.
     /// Where `0.0` is `self` and `1.0` is `other`.
     ///
-    /// Note that this is a linear interpolation in the oklab color space.
-    /// If rgb interpolation is required use `mix_rgb`.
+    /// Note that this is a linear interpolation in the oklab color space, see
+    /// [`Color::mix_oklab`]. If rgb interpolation is required use `mix_rgb`.
     pub fn mix(self, other: Self, t: f32) -> Self {
+        self.mix_oklab(other, t)
+    }
+
+    /// Linearly interpolate between two colors in the oklab color space.
+    ///
+    /// This uses a fractor `t` between `0.0` and `1.0`.
+    /// Where `0.0` is `self` and `1.0` is `other`.
+    ///
+    /// Oklab is perceptually uniform, so gradients between saturated colors don't pass through
+    /// muddy, darker colors like they do in sRGB.
+    pub fn mix_oklab(self, other: Self, t: f32) -> Self {
         let (al, aa, ab, aalpha) = self.to_oklaba();
         let (bl, ba, bb, balpha) = other.to_oklaba();
 
@@ -873,6 +958,17 @@ impl Color {
         self * (1.0 - t) + other * t
     }
 
+    /// Linearly interpolate between two colors in the given color space.
+    ///
+    /// This is [`Color::mix`] for [`ColorInterpolation::Oklab`], and [`Color::mix_rgb`] for
+    /// [`ColorInterpolation::Srgb`].
+    pub fn mix_in(self, other: Self, t: f32, interpolation: ColorInterpolation) -> Self {
+        match interpolation {
+            ColorInterpolation::Oklab => self.mix(other, t),
+            ColorInterpolation::Srgb => self.mix_rgb(other, t),
+        }
+    }
+
     /// Saturates the color by given `amount`.
     pub fn saturate(self, amount: f32) -> Self {
         let (h, s, l, alpha) = self.to_okhsla();
@@ -897,6 +993,58 @@ impl Color {
         Self::okhsla(h, s, l - amount, alpha)
     }
 
+    /// Composite `self` on top of `dst` with the given blend `mode`.
+    ///
+    /// This follows the compositing rules used by the renderers, and can be used to
+    /// predict the result of drawing with a [`BlendMode`].
+    pub fn blend(self, dst: Self, mode: BlendMode) -> Self {
+        fn over(src: Color, dst: Color) -> Color {
+            let a = src.a + dst.a * (1.0 - src.a);
+
+            if a == 0.0 {
+                return Color::TRANSPARENT;
+            }
+
+            let channel = |s: f32, d: f32| (s * src.a + d * dst.a * (1.0 - src.a)) / a;
+
+            Color::rgba(
+                channel(src.r, dst.r),
+                channel(src.g, dst.g),
+                channel(src.b, dst.b),
+                a,
+            )
+        }
+
+        fn mix(src: Color, dst: Color, f: impl Fn(f32, f32) -> f32) -> Color {
+            let channel = |s: f32, d: f32| s * (1.0 - dst.a) + f(s, d) * dst.a;
+
+            let blended = Color::rgba(
+                channel(src.r, dst.r),
+                channel(src.g, dst.g),
+                channel(src.b, dst.b),
+                src.a,
+            );
+
+            over(blended, dst)
+        }
+
+        match mode {
+            BlendMode::Clear => Self::TRANSPARENT,
+            BlendMode::Source => self,
+            BlendMode::Destination => dst,
+            BlendMode::SourceOver => over(self, dst),
+            BlendMode::DestinationOver => over(dst, self),
+            BlendMode::Multiply => mix(self, dst, |s, d| s * d),
+            BlendMode::Screen => mix(self, dst, |s, d| s + d - s * d),
+            BlendMode::Overlay => mix(self, dst, |s, d| match d <= 0.5 {
+                true => 2.0 * s * d,
+                false => 1.0 - 2.0 * (1.0 - s) * (1.0 - d),
+            }),
+            BlendMode::Darken => mix(self, dst, f32::min),
+            BlendMode::Lighten => mix(self, dst, f32::max),
+        }
+    }
+
     /// Fade the color by the given `amount`.
     pub fn fade(self, amount: f32) -> Self {
         Self::rgba(self.r, self.g, self.b, self.a * amount)
@@ -924,6 +1072,86 @@ impl Color {
         ]
     }
 
+    /// Create a color from linear sRGB, the inverse of [`Color::to_srgb`].
+    pub fn from_srgb([r, g, b, a]: [f32; 4]) -> Self {
+        Self::rgba(
+            Self::from_linear(r),
+            Self::from_linear(g),
+            Self::from_linear(b),
+            a,
+        )
+    }
+
+    /// Composite `self` on top of `dst` with an anti-aliasing `coverage`, eg. the coverage of a
+    /// glyph at a pixel.
+    ///
+    /// Coverage is a fraction of the area of the pixel, so it's blended in linear light.
+    /// Blending the sRGB encoded components directly makes anti-aliased edges of dark text on
+    /// light backgrounds too heavy, and those of light text on dark backgrounds too thin.
+    pub fn blend_coverage(self, dst: Self, coverage: f32) -> Self {
+        let t = f32::clamp(coverage * self.a, 0.0, 1.0);
+
+        let [sr, sg, sb, _] = self.to_srgb();
+        let [dr, dg, db, da] = dst.to_srgb();
+
+        let channel = |s: f32, d: f32| s * t + d * (1.0 - t);
+
+        Self::from_srgb([
+            channel(sr, dr),
+            channel(sg, dg),
+            channel(sb, db),
+            t + da * (1.0 - t),
+        ])
+    }
+
+    /// Create a color from Display P3 components, see [`ColorSpace::DisplayP3`].
+    ///
+    /// Colors outside of the sRGB gamut have components below `0.0` or above `1.0`.
+    pub fn display_p3a(r: f32, g: f32, b: f32, a: f32) -> Self {
+        let [r, g, b] = [r, g, b].map(Self::to_linear_extended);
+
+        let sr = 1.224_940_2 * r - 0.224_940_4 * g;
+        let sg = -0.042_056_955 * r + 1.042_057 * g;
+        let sb = -0.019_637_555 * r - 0.078_636_05 * g + 1.098_273_5 * b;
+
+        let [r, g, b] = [sr, sg, sb].map(Self::from_linear_extended);
+        Self::rgba(r, g, b, a)
+    }
+
+    /// Create a color from Display P3 components, see [`Color::display_p3a`].
+    pub fn display_p3(r: f32, g: f32, b: f32) -> Self {
+        Self::display_p3a(r, g, b, 1.0)
+    }
+
+    /// Convert the color to Display P3 components, the inverse of [`Color::display_p3a`].
+    pub fn to_display_p3(self) -> [f32; 4] {
+        let [r, g, b] = [self.r, self.g, self.b].map(Self::to_linear_extended);
+
+        let pr = 0.822_461_97 * r + 0.177_538_03 * g;
+        let pg = 0.033_194_2 * r + 0.966_805_8 * g;
+        let pb = 0.017_082_632 * r + 0.072_397_44 * g + 0.910_519_96 * b;
+
+        let [r, g, b] = [pr, pg, pb].map(Self::from_linear_extended);
+        [r, g, b, self.a]
+    }
+
+    /// Check if any component of the color is outside of the range `0.0..=1.0`, see
+    /// [`ColorSpace`].
+    pub fn is_extended(self) -> bool {
+        [self.r, self.g, self.b]
+            .iter()
+            .any(|c| !(0.0..=1.0).contains(c))
+    }
+
+    // the transfer functions of sRGB extended to negative values by mirroring them
+    fn to_linear_extended(x: f32) -> f32 {
+        f32::copysign(Self::to_linear(x.abs()), x)
+    }
+
+    fn from_linear_extended(x: f32) -> f32 {
+        f32::copysign(Self::from_linear(x.abs()), x)
+    }
+
     /// Convert the color to linear sRGB.
     pub fn to_rgba8(self) -> [u8; 4] {
         [
@@ -1117,12 +1345,58 @@ mod tests {
         assert_eq!(color, Color::MAGENTA);
     }
 
+    #[test]
+    fn srgb_round_trip() {
+        let color = Color::rgba(0.2, 0.5, 0.8, 0.6);
+        let [r, g, b, a] = Color::from_srgb(color.to_srgb()).into();
+
+        assert!((r - 0.2).abs() < 1e-4);
+        assert!((g - 0.5).abs() < 1e-4);
+        assert!((b - 0.8).abs() < 1e-4);
+        assert_eq!(a, 0.6);
+    }
+
+    #[test]
+    fn coverage_is_gamma_correct() {
+        // an edge pixel of black text on white, half covered by the glyph, reflects half the
+        // light, which is sRGB 188 and not the 128 of naive blending
+        let edge = Color::BLACK.blend_coverage(Color::WHITE, 0.5);
+        assert!(edge.r8().abs_diff(188) <= 1, "{}", edge.r8());
+
+        // and white text on black is lighter than the naive blend too
+        let edge = Color::WHITE.blend_coverage(Color::BLACK, 0.25);
+        assert!(edge.r8().abs_diff(137) <= 1, "{}", edge.r8());
+
+        let full = Color::RED.blend_coverage(Color::BLUE, 1.0);
+        assert!((full.r - 1.0).abs() < 1e-4 && full.b.abs() < 1e-4);
+
+        let none = Color::RED.blend_coverage(Color::BLUE, 0.0);
+        assert!(none.r.abs() < 1e-4 && (none.b - 1.0).abs() < 1e-4);
+    }
+
     #[test]
     fn hex_display() {
         let display = DisplayHex::new(0xa0, 0xb2, 0xcb, 0xd6);
         assert_eq!(display.as_ref(), "#a0b2cbd6");
     }
 
+    #[test]
+    fn blend_multiply() {
+        let src = Color::rgb(0.5, 0.4, 1.0);
+        let dst = Color::rgb(0.2, 1.0, 0.5);
+        let color = src.blend(dst, BlendMode::Multiply);
+        assert!(f32::abs(color.r - 0.1) < 0.0001);
+        assert!(f32::abs(color.g - 0.4) < 0.0001);
+        assert!(f32::abs(color.b - 0.5) < 0.0001);
+        assert_eq!(color.a, 1.0);
+    }
+
+    #[test]
+    fn contrast_ratio() {
+        let ratio = Color::BLACK.contrast_ratio(Color::WHITE);
+        assert!(f32::abs(ratio - 21.0) < 0.0001);
+    }
+
     #[test]
     fn okhsl_inverse() {
         let color = Color::rgb(0.05, 0.15, 0.20);
@@ -1142,4 +1416,40 @@ mod tests {
         assert!(f32::abs(color.g - color2.g) < 0.0001);
         assert!(f32::abs(color.b - color2.b) < 0.0001);
     }
+
+    #[test]
+    fn oklch_inverse() {
+        let color = Color::rgba(0.8, 0.2, 0.6, 0.5);
+        let (l, c, h, a) = color.to_oklcha();
+        let color2 = Color::oklcha(l, c, h, a);
+        assert!(f32::abs(color.r - color2.r) < 0.0001);
+        assert!(f32::abs(color.g - color2.g) < 0.0001);
+        assert!(f32::abs(color.b - color2.b) < 0.0001);
+        assert!(f32::abs(color.a - color2.a) < 0.0001);
+
+        // the midpoint of complementary colors isn't darkened when mixed in oklab
+        let mid = Color::rgb(1.0, 0.0, 0.0).mix_oklab(Color::rgb(0.0, 1.0, 1.0), 0.5);
+        let dull = Color::rgb(1.0, 0.0, 0.0).mix_rgb(Color::rgb(0.0, 1.0, 1.0), 0.5);
+        assert!(mid.to_oklch().0 > dull.to_oklch().0);
+    }
+
+    #[test]
+    fn display_p3_round_trip() {
+        // white is the same in both color spaces
+        let white = Color::display_p3(1.0, 1.0, 1.0);
+        assert!(f32::abs(white.r - 1.0) < 0.0001);
+        assert!(f32::abs(white.g - 1.0) < 0.0001);
+        assert!(f32::abs(white.b - 1.0) < 0.0001);
+        assert!(!white.is_extended());
+
+        // pure P3 red is outside of the sRGB gamut
+        let red = Color::display_p3(1.0, 0.0, 0.0);
+        assert!(red.is_extended());
+        assert!(red.r > 1.0 && red.g < 0.0);
+
+        let [r, g, b, _] = red.to_display_p3();
+        assert!(f32::abs(r - 1.0) < 0.0001);
+        assert!(f32::abs(g) < 0.0001);
+        assert!(f32::abs(b) < 0.0001);
+    }
 }
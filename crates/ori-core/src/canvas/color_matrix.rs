@@ -0,0 +1,161 @@
+use super::Color;
+
+/// A matrix transforming the colors of a layer, see [`Canvas::filtered`](super::Canvas::filtered).
+///
+/// The matrix has 4 rows of 5 columns, one row for each of the red, green, blue and alpha
+/// components of the result. The first 4 columns are multiplied with the components of the
+/// color, and the last column is added, eg. the red component is
+/// `m[0] * r + m[1] * g + m[2] * b + m[3] * a + m[4]`. Colors are transformed without
+/// premultiplied alpha, with components in the range `0.0..=1.0`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ColorMatrix {
+    /// The components of the matrix, row by row.
+    pub matrix: [f32; 20],
+}
+
+impl Default for ColorMatrix {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl ColorMatrix {
+    /// The identity matrix, that leaves colors unchanged.
+    pub const IDENTITY: Self = Self::new([
+        1.0, 0.0, 0.0, 0.0, 0.0, //
+        0.0, 1.0, 0.0, 0.0, 0.0, //
+        0.0, 0.0, 1.0, 0.0, 0.0, //
+        0.0, 0.0, 0.0, 1.0, 0.0, //
+    ]);
+
+    /// Create a new [`ColorMatrix`] from its components, row by row.
+    pub const fn new(matrix: [f32; 20]) -> Self {
+        Self { matrix }
+    }
+
+    /// Remove the color, keeping the luminance, eg. for disabled content.
+    ///
+    /// The luminance is `0.2126 * r + 0.7152 * g + 0.0722 * b`.
+    pub const fn grayscale() -> Self {
+        let [r, g, b] = [0.2126, 0.7152, 0.0722];
+
+        Self::new([
+            r, g, b, 0.0, 0.0, //
+            r, g, b, 0.0, 0.0, //
+            r, g, b, 0.0, 0.0, //
+            0.0, 0.0, 0.0, 1.0, 0.0, //
+        ])
+    }
+
+    /// Give colors a warm brown tone, like an old photograph.
+    pub const fn sepia() -> Self {
+        Self::new([
+            0.393, 0.769, 0.189, 0.0, 0.0, //
+            0.349, 0.686, 0.168, 0.0, 0.0, //
+            0.272, 0.534, 0.131, 0.0, 0.0, //
+            0.0, 0.0, 0.0, 1.0, 0.0, //
+        ])
+    }
+
+    /// Multiply colors with `color`, eg. to color a white icon.
+    pub const fn tint(color: Color) -> Self {
+        Self::new([
+            color.r, 0.0, 0.0, 0.0, 0.0, //
+            0.0, color.g, 0.0, 0.0, 0.0, //
+            0.0, 0.0, color.b, 0.0, 0.0, //
+            0.0, 0.0, 0.0, color.a, 0.0, //
+        ])
+    }
+
+    /// Scale the alpha of colors, `0.0` is transparent and `1.0` leaves colors unchanged.
+    pub const fn opacity(opacity: f32) -> Self {
+        Self::new([
+            1.0, 0.0, 0.0, 0.0, 0.0, //
+            0.0, 1.0, 0.0, 0.0, 0.0, //
+            0.0, 0.0, 1.0, 0.0, 0.0, //
+            0.0, 0.0, 0.0, opacity, 0.0, //
+        ])
+    }
+
+    /// Scale the brightness of colors, `0.0` is black and `1.0` leaves colors unchanged.
+    pub const fn brightness(amount: f32) -> Self {
+        Self::new([
+            amount, 0.0, 0.0, 0.0, 0.0, //
+            0.0, amount, 0.0, 0.0, 0.0, //
+            0.0, 0.0, amount, 0.0, 0.0, //
+            0.0, 0.0, 0.0, 1.0, 0.0, //
+        ])
+    }
+
+    /// Scale the contrast of colors, `0.0` is gray and `1.0` leaves colors unchanged.
+    pub const fn contrast(amount: f32) -> Self {
+        let offset = 0.5 - 0.5 * amount;
+
+        Self::new([
+            amount, 0.0, 0.0, 0.0, offset, //
+            0.0, amount, 0.0, 0.0, offset, //
+            0.0, 0.0, amount, 0.0, offset, //
+            0.0, 0.0, 0.0, 1.0, 0.0, //
+        ])
+    }
+
+    /// Get a matrix that applies `self` and then `other`.
+    pub fn then(self, other: Self) -> Self {
+        let a = &self.matrix;
+        let b = &other.matrix;
+
+        let mut matrix = [0.0; 20];
+
+        for row in 0..4 {
+            for column in 0..5 {
+                let mut value = (0..4)
+                    .map(|i| b[row * 5 + i] * a[i * 5 + column])
+                    .sum::<f32>();
+
+                if column == 4 {
+                    value += b[row * 5 + 4];
+                }
+
+                matrix[row * 5 + column] = value;
+            }
+        }
+
+        Self::new(matrix)
+    }
+
+    /// Transform a color with the matrix, the result is clamped to the range `0.0..=1.0`.
+    pub fn apply(&self, color: Color) -> Color {
+        let m = &self.matrix;
+        let c = [color.r, color.g, color.b, color.a];
+
+        let row = |row: usize| {
+            let m = &m[row * 5..row * 5 + 5];
+            let value = m[0] * c[0] + m[1] * c[1] + m[2] * c[2] + m[3] * c[3] + m[4];
+            value.clamp(0.0, 1.0)
+        };
+
+        Color::rgba(row(0), row(1), row(2), row(3))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grayscale_red_is_its_luminance() {
+        let gray = ColorMatrix::grayscale().apply(Color::RED);
+        assert_eq!(gray, Color::rgb(0.2126, 0.2126, 0.2126));
+    }
+
+    #[test]
+    fn then_applies_both() {
+        let matrix = ColorMatrix::grayscale().then(ColorMatrix::tint(Color::BLUE));
+        let color = matrix.apply(Color::WHITE);
+
+        let expected =
+            ColorMatrix::tint(Color::BLUE).apply(ColorMatrix::grayscale().apply(Color::WHITE));
+        assert!((color.b - expected.b).abs() < 1e-6);
+        assert_eq!((color.r, color.g), (0.0, 0.0));
+    }
+}
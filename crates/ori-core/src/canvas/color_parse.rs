@@ -0,0 +1,326 @@
+use std::{
+    error::Error,
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+use super::Color;
+
+/// An error returned when a [`Color`] can't be parsed from a string.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ColorParseError {
+    /// The string is empty.
+    Empty,
+
+    /// The string starts with `#`, but isn't a valid hex color.
+    InvalidHex(String),
+
+    /// The string is a function, but not one of `rgb`, `rgba`, `hsl` or `hsla`.
+    UnknownFunction(String),
+
+    /// The arguments of a function are invalid.
+    InvalidArguments(String),
+
+    /// The string isn't a named CSS color.
+    UnknownName(String),
+}
+
+impl Display for ColorParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ColorParseError::Empty => write!(f, "empty color"),
+            ColorParseError::InvalidHex(hex) => write!(f, "invalid hex color: {}", hex),
+            ColorParseError::UnknownFunction(name) => write!(f, "unknown color function: {}", name),
+            ColorParseError::InvalidArguments(color) => {
+                write!(f, "invalid color function arguments: {}", color)
+            }
+            ColorParseError::UnknownName(name) => write!(f, "unknown color: {}", name),
+        }
+    }
+}
+
+impl Error for ColorParseError {}
+
+impl FromStr for Color {
+    type Err = ColorParseError;
+
+    /// Parse a color the way CSS does, eg. `#6495ed`, `rgb(100, 149, 237)`,
+    /// `hsl(219deg 79% 66% / 50%)` or `cornflowerblue`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+
+        if s.is_empty() {
+            return Err(ColorParseError::Empty);
+        }
+
+        if s.starts_with('#') {
+            return Color::try_hex(s).ok_or_else(|| ColorParseError::InvalidHex(s.to_string()));
+        }
+
+        if let Some((name, args)) = s.strip_suffix(')').and_then(|s| s.split_once('(')) {
+            let name = name.trim().to_ascii_lowercase();
+
+            if !matches!(name.as_str(), "rgb" | "rgba" | "hsl" | "hsla") {
+                return Err(ColorParseError::UnknownFunction(name));
+            }
+
+            return parse_function(&name, args)
+                .ok_or_else(|| ColorParseError::InvalidArguments(s.to_string()));
+        }
+
+        Color::named(s).ok_or_else(|| ColorParseError::UnknownName(s.to_string()))
+    }
+}
+
+impl Color {
+    /// Get one of the 148 named CSS colors, or `transparent`, ignoring case.
+    ///
+    /// See <https://www.w3.org/TR/css-color-4/#named-colors>.
+    pub fn named(name: &str) -> Option<Self> {
+        let name = name.to_ascii_lowercase();
+
+        if name == "transparent" {
+            return Some(Color::TRANSPARENT);
+        }
+
+        let index = NAMED_COLORS
+            .binary_search_by(|(named, _)| named.cmp(&name.as_str()))
+            .ok()?;
+
+        let [_, r, g, b] = NAMED_COLORS[index].1.to_be_bytes();
+        Some(Color::rgba8(r, g, b, 255))
+    }
+}
+
+// parse a color function, eg. `rgb(...)`, or `None` if the arguments are invalid
+fn parse_function(name: &str, args: &str) -> Option<Color> {
+    // both the legacy `rgb(1, 2, 3, 0.5)` and modern `rgb(1 2 3 / 50%)` syntax
+    let args = args.split(|c: char| c == ',' || c == '/' || c.is_whitespace());
+    let args: Vec<&str> = args.filter(|arg| !arg.is_empty()).collect();
+
+    let alpha = match args.len() {
+        3 => 1.0,
+        4 => parse_component(args[3], 1.0)?,
+        _ => return None,
+    };
+
+    match name {
+        "rgb" | "rgba" => Some(Color::rgba(
+            parse_component(args[0], 255.0)?,
+            parse_component(args[1], 255.0)?,
+            parse_component(args[2], 255.0)?,
+            alpha,
+        )),
+        _ => {
+            let hue = args[0].strip_suffix("deg").unwrap_or(args[0]);
+            let hue = hue.parse::<f32>().ok()?.rem_euclid(360.0);
+
+            // saturation and lightness are percentages, with or without the `%`
+            Some(Color::hsla(
+                hue,
+                parse_component(args[1], 100.0)?,
+                parse_component(args[2], 100.0)?,
+                alpha,
+            ))
+        }
+    }
+}
+
+// parse a number in `0.0..=max` or a percentage, as a fraction clamped to `0.0..=1.0`
+fn parse_component(arg: &str, max: f32) -> Option<f32> {
+    let value = match arg.strip_suffix('%') {
+        Some(percent) => percent.parse::<f32>().ok()? / 100.0,
+        None => arg.parse::<f32>().ok()? / max,
+    };
+
+    value.is_finite().then(|| value.clamp(0.0, 1.0))
+}
+
+// the named CSS colors, sorted by name so they can be binary searched
+const NAMED_COLORS: [(&str, u32); 148] = [
+    ("aliceblue", 0xf0f8ff),
+    ("antiquewhite", 0xfaebd7),
+    ("aqua", 0x00ffff),
+    ("aquamarine", 0x7fffd4),
+    ("azure", 0xf0ffff),
+    ("beige", 0xf5f5dc),
+    ("bisque", 0xffe4c4),
+    ("black", 0x000000),
+    ("blanchedalmond", 0xffebcd),
+    ("blue", 0x0000ff),
+    ("blueviolet", 0x8a2be2),
+    ("brown", 0xa52a2a),
+    ("burlywood", 0xdeb887),
+    ("cadetblue", 0x5f9ea0),
+    ("chartreuse", 0x7fff00),
+    ("chocolate", 0xd2691e),
+    ("coral", 0xff7f50),
+    ("cornflowerblue", 0x6495ed),
+    ("cornsilk", 0xfff8dc),
+    ("crimson", 0xdc143c),
+    ("cyan", 0x00ffff),
+    ("darkblue", 0x00008b),
+    ("darkcyan", 0x008b8b),
+    ("darkgoldenrod", 0xb8860b),
+    ("darkgray", 0xa9a9a9),
+    ("darkgreen", 0x006400),
+    ("darkgrey", 0xa9a9a9),
+    ("darkkhaki", 0xbdb76b),
+    ("darkmagenta", 0x8b008b),
+    ("darkolivegreen", 0x556b2f),
+    ("darkorange", 0xff8c00),
+    ("darkorchid", 0x9932cc),
+    ("darkred", 0x8b0000),
+    ("darksalmon", 0xe9967a),
+    ("darkseagreen", 0x8fbc8f),
+    ("darkslateblue", 0x483d8b),
+    ("darkslategray", 0x2f4f4f),
+    ("darkslategrey", 0x2f4f4f),
+    ("darkturquoise", 0x00ced1),
+    ("darkviolet", 0x9400d3),
+    ("deeppink", 0xff1493),
+    ("deepskyblue", 0x00bfff),
+    ("dimgray", 0x696969),
+    ("dimgrey", 0x696969),
+    ("dodgerblue", 0x1e90ff),
+    ("firebrick", 0xb22222),
+    ("floralwhite", 0xfffaf0),
+    ("forestgreen", 0x228b22),
+    ("fuchsia", 0xff00ff),
+    ("gainsboro", 0xdcdcdc),
+    ("ghostwhite", 0xf8f8ff),
+    ("gold", 0xffd700),
+    ("goldenrod", 0xdaa520),
+    ("gray", 0x808080),
+    ("green", 0x008000),
+    ("greenyellow", 0xadff2f),
+    ("grey", 0x808080),
+    ("honeydew", 0xf0fff0),
+    ("hotpink", 0xff69b4),
+    ("indianred", 0xcd5c5c),
+    ("indigo", 0x4b0082),
+    ("ivory", 0xfffff0),
+    ("khaki", 0xf0e68c),
+    ("lavender", 0xe6e6fa),
+    ("lavenderblush", 0xfff0f5),
+    ("lawngreen", 0x7cfc00),
+    ("lemonchiffon", 0xfffacd),
+    ("lightblue", 0xadd8e6),
+    ("lightcoral", 0xf08080),
+    ("lightcyan", 0xe0ffff),
+    ("lightgoldenrodyellow", 0xfafad2),
+    ("lightgray", 0xd3d3d3),
+    ("lightgreen", 0x90ee90),
+    ("lightgrey", 0xd3d3d3),
+    ("lightpink", 0xffb6c1),
+    ("lightsalmon", 0xffa07a),
+    ("lightseagreen", 0x20b2aa),
+    ("lightskyblue", 0x87cefa),
+    ("lightslategray", 0x778899),
+    ("lightslategrey", 0x778899),
+    ("lightsteelblue", 0xb0c4de),
+    ("lightyellow", 0xffffe0),
+    ("lime", 0x00ff00),
+    ("limegreen", 0x32cd32),
+    ("linen", 0xfaf0e6),
+    ("magenta", 0xff00ff),
+    ("maroon", 0x800000),
+    ("mediumaquamarine", 0x66cdaa),
+    ("mediumblue", 0x0000cd),
+    ("mediumorchid", 0xba55d3),
+    ("mediumpurple", 0x9370db),
+    ("mediumseagreen", 0x3cb371),
+    ("mediumslateblue", 0x7b68ee),
+    ("mediumspringgreen", 0x00fa9a),
+    ("mediumturquoise", 0x48d1cc),
+    ("mediumvioletred", 0xc71585),
+    ("midnightblue", 0x191970),
+    ("mintcream", 0xf5fffa),
+    ("mistyrose", 0xffe4e1),
+    ("moccasin", 0xffe4b5),
+    ("navajowhite", 0xffdead),
+    ("navy", 0x000080),
+    ("oldlace", 0xfdf5e6),
+    ("olive", 0x808000),
+    ("olivedrab", 0x6b8e23),
+    ("orange", 0xffa500),
+    ("orangered", 0xff4500),
+    ("orchid", 0xda70d6),
+    ("palegoldenrod", 0xeee8aa),
+    ("palegreen", 0x98fb98),
+    ("paleturquoise", 0xafeeee),
+    ("palevioletred", 0xdb7093),
+    ("papayawhip", 0xffefd5),
+    ("peachpuff", 0xffdab9),
+    ("peru", 0xcd853f),
+    ("pink", 0xffc0cb),
+    ("plum", 0xdda0dd),
+    ("powderblue", 0xb0e0e6),
+    ("purple", 0x800080),
+    ("rebeccapurple", 0x663399),
+    ("red", 0xff0000),
+    ("rosybrown", 0xbc8f8f),
+    ("royalblue", 0x4169e1),
+    ("saddlebrown", 0x8b4513),
+    ("salmon", 0xfa8072),
+    ("sandybrown", 0xf4a460),
+    ("seagreen", 0x2e8b57),
+    ("seashell", 0xfff5ee),
+    ("sienna", 0xa0522d),
+    ("silver", 0xc0c0c0),
+    ("skyblue", 0x87ceeb),
+    ("slateblue", 0x6a5acd),
+    ("slategray", 0x708090),
+    ("slategrey", 0x708090),
+    ("snow", 0xfffafa),
+    ("springgreen", 0x00ff7f),
+    ("steelblue", 0x4682b4),
+    ("tan", 0xd2b48c),
+    ("teal", 0x008080),
+    ("thistle", 0xd8bfd8),
+    ("tomato", 0xff6347),
+    ("turquoise", 0x40e0d0),
+    ("violet", 0xee82ee),
+    ("wheat", 0xf5deb3),
+    ("white", 0xffffff),
+    ("whitesmoke", 0xf5f5f5),
+    ("yellow", 0xffff00),
+    ("yellowgreen", 0x9acd32),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_css_colors() {
+        let cornflower = Color::rgba8(100, 149, 237, 255);
+
+        assert_eq!("#6495ed".parse(), Ok(cornflower));
+        assert_eq!("rgb(100, 149, 237)".parse(), Ok(cornflower));
+        assert_eq!("rgb(100 149 237 / 100%)".parse(), Ok(cornflower));
+        assert_eq!("CornflowerBlue".parse(), Ok(cornflower));
+        assert_eq!("rebeccapurple".parse(), Ok(Color::rgba8(102, 51, 153, 255)));
+
+        let red: Color = "hsla(360deg, 100%, 50%, 0.5)".parse().unwrap();
+        assert_eq!(red, Color::rgba(1.0, 0.0, 0.0, 0.5));
+
+        assert_eq!("".parse::<Color>(), Err(ColorParseError::Empty));
+        assert!(matches!(
+            "#12345".parse::<Color>(),
+            Err(ColorParseError::InvalidHex(_))
+        ));
+        assert!(matches!(
+            "lab(50 20 30)".parse::<Color>(),
+            Err(ColorParseError::UnknownFunction(_))
+        ));
+        assert!(matches!(
+            "rgb(1, 2)".parse::<Color>(),
+            Err(ColorParseError::InvalidArguments(_))
+        ));
+        assert!(matches!(
+            "notacolor".parse::<Color>(),
+            Err(ColorParseError::UnknownName(_))
+        ));
+    }
+}
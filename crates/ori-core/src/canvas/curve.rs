@@ -1,8 +1,8 @@
-use std::f32::consts::{PI, SQRT_2};
+use std::{cell::Cell, f32::consts::SQRT_2};
 
 use crate::layout::{Affine, Point, Rect, Size, Vector};
 
-use super::{BorderRadius, BorderWidth, FillRule, Stroke};
+use super::{BorderEdge, BorderRadius, BorderWidth, FillRule, Stroke};
 
 /// A verb that describes the type of curve.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
@@ -104,6 +104,25 @@ impl Curve {
         self.bounds
     }
 
+    /// Check if the curve is exactly a rectangle, as created by [`Curve::rect`].
+    ///
+    /// Renderers can clip to rectangles without rasterizing the curve.
+    pub fn is_rect(&self) -> bool {
+        self.verbs.len() == 5 && *self == Curve::rect(self.bounds)
+    }
+
+    /// Check if the curve is exactly an oval, as created by [`Curve::ellipse`] and
+    /// [`Curve::circle`].
+    ///
+    /// Renderers can clip to ovals without rasterizing the curve.
+    pub fn is_oval(&self) -> bool {
+        let oval = Curve::ellipse(self.bounds);
+
+        // the quads approximating the oval are rounded slightly differently for every oval
+        let mut points = self.points.iter().zip(&oval.points);
+        self.verbs == oval.verbs && points.all(|(a, b)| a.distance(*b) < 0.01)
+    }
+
     /// Clear the curve, retaining the allocated memory for reuse.
     pub fn clear(&mut self) {
         self.verbs.clear();
@@ -224,6 +243,72 @@ impl Curve {
         self.bounds = self.bounds.transform(transform);
     }
 
+    /// Check if the curve has the same segments as `other`, and can be interpolated with
+    /// [`Curve::lerp`] point by point.
+    pub fn is_compatible(&self, other: &Curve) -> bool {
+        self.verbs == other.verbs
+    }
+
+    /// Linearly interpolate between the curve and `other`, eg. to morph one icon into another.
+    ///
+    /// The points are interpolated one by one, which requires both curves to have the same
+    /// segments, see [`Curve::is_compatible`]. Curves that don't are first converted with
+    /// [`Curve::normalize`], and if that isn't possible, either because the curves have a
+    /// different number of contours, or only one of them is closed, the result snaps from
+    /// `self` to `other` at `t = 0.5`.
+    pub fn lerp(&self, other: &Curve, t: f32) -> Curve {
+        if self.is_compatible(other) {
+            let mut curve = Curve::new();
+            curve.verbs = self.verbs.clone();
+
+            for (a, b) in self.points.iter().zip(&other.points) {
+                curve.push_point(a.lerp(*b, t));
+            }
+
+            return curve;
+        }
+
+        match Curve::normalize(self, other) {
+            Some((a, b)) => a.lerp(&b, t),
+            None if t < 0.5 => self.clone(),
+            None => other.clone(),
+        }
+    }
+
+    /// Convert two curves so they have the same segments, and can be interpolated point by
+    /// point with [`Curve::lerp`], without changing their shapes.
+    ///
+    /// Every segment is converted to a cubic bezier curve, including the line drawn by closing
+    /// a contour, and the longest segments of the contour with fewer segments are split in half
+    /// until both contours have the same number of segments.
+    ///
+    /// Returns `None` if the curves have a different number of contours, or if a contour is
+    /// closed in one curve but not in the other.
+    pub fn normalize(a: &Curve, b: &Curve) -> Option<(Curve, Curve)> {
+        let mut a_contours = Contour::all(a);
+        let mut b_contours = Contour::all(b);
+
+        if a_contours.len() != b_contours.len() {
+            return None;
+        }
+
+        for (a, b) in a_contours.iter_mut().zip(&mut b_contours) {
+            if a.closed != b.closed {
+                return None;
+            }
+
+            while a.cubics.len() < b.cubics.len() {
+                a.split_longest();
+            }
+
+            while b.cubics.len() < a.cubics.len() {
+                b.split_longest();
+            }
+        }
+
+        Some((Contour::curve(&a_contours), Contour::curve(&b_contours)))
+    }
+
     /// Get an iterator over the curve segments.
     pub fn iter(&self) -> CurveIter {
         CurveIter {
@@ -240,14 +325,196 @@ impl Curve {
         }
 
         match rule {
-            FillRule::NonZero => self.contains_even_odd(point),
-            FillRule::EvenOdd => self.contains_even_odd(point),
+            FillRule::NonZero => self.winding(point) != 0,
+            FillRule::EvenOdd => self.winding(point) % 2 != 0,
+        }
+    }
+
+    /// Get the distance from `point` to the closest point on the curve.
+    ///
+    /// Returns [`f32::INFINITY`] if the curve is empty.
+    pub fn distance_to(&self, point: Point) -> f32 {
+        match self.nearest_point(point) {
+            Some((nearest, _)) => nearest.distance(point),
+            None => f32::INFINITY,
         }
     }
 
-    /// Stroke the `curve` with the given `stroke`.
-    pub fn stroke_curve(&mut self, curve: &Curve, stroke: Stroke) {
-        self.stroke_impl(curve, stroke);
+    /// Get the closest point on the curve to `point`.
+    ///
+    /// Returns the closest point and the parameter `t` of that point, in the range `0.0..=1.0`,
+    /// along the segment it lies on. Returns `None` if the curve has no segments.
+    pub fn nearest_point(&self, point: Point) -> Option<(Point, f32)> {
+        let mut nearest = None;
+        let mut best = f32::INFINITY;
+
+        self.flatten_lines(Self::FLATTEN_TOLERANCE, |line| {
+            let (t, closest) = line.project(point);
+            let distance = closest.distance(point);
+
+            if distance < best {
+                best = distance;
+                nearest = Some((closest, line.t0 + (line.t1 - line.t0) * t));
+            }
+        });
+
+        nearest
+    }
+
+    /// Flatten the curve into points connected by lines, that stay within `tolerance` of the
+    /// curve.
+    ///
+    /// Each segment is split into as many lines as its curvature needs, so straight parts stay
+    /// a single line. Every contour starts with the point it moved to, use [`Curve::iter`] to
+    /// tell contours apart. Tolerances that aren't positive are clamped to a small positive
+    /// value.
+    pub fn flatten(&self, tolerance: f32) -> impl Iterator<Item = Point> {
+        let tolerance = tolerance.max(Self::MIN_FLATTEN_TOLERANCE);
+
+        let mut points = Vec::new();
+
+        self.flatten_lines(tolerance, |line| {
+            if line.first {
+                points.push(line.start);
+            }
+
+            points.push(line.end);
+        });
+
+        points.into_iter()
+    }
+
+    /// Get the length of the curve, the sum of the lengths of all its contours.
+    pub fn length(&self) -> f32 {
+        let mut length = 0.0;
+
+        self.flatten_lines(Self::FLATTEN_TOLERANCE, |line| {
+            length += line.start.distance(line.end);
+        });
+
+        length
+    }
+
+    /// Get the point `distance` along the curve, and the direction of the curve at that point.
+    ///
+    /// The distance is measured like [`Curve::length`] and clamped to the length of the curve.
+    /// Returns `None` if the curve has no segments.
+    pub fn sample_at(&self, distance: f32) -> Option<(Point, Vector)> {
+        let mut remaining = distance.max(0.0);
+        let mut sample = None;
+        let mut done = false;
+
+        self.flatten_lines(Self::FLATTEN_TOLERANCE, |line| {
+            if done {
+                return;
+            }
+
+            let length = line.start.distance(line.end);
+
+            // lines without a length have no direction, they only count if nothing else does
+            if length == 0.0 {
+                sample.get_or_insert((line.start, Vector::ZERO));
+                return;
+            }
+
+            let direction = (line.end - line.start) / length;
+            let t = f32::min(remaining, length);
+            sample = Some((line.start + direction * t, direction));
+
+            // stop at the first line that reaches the distance
+            remaining -= length;
+            done = remaining <= 0.0;
+        });
+
+        sample
+    }
+
+    /// Stroke the `curve` with the given `stroke`, following its dash pattern.
+    pub fn stroke_curve(&mut self, curve: &Curve, stroke: &Stroke) {
+        match stroke.dash {
+            Some(ref pattern) => {
+                let dashed = curve.dash(pattern, stroke.dash_offset);
+                self.stroke_impl(&dashed, stroke);
+            }
+            None => self.stroke_impl(curve, stroke),
+        }
+    }
+
+    // split the curve into dashes, see `Stroke::dash`, curves are flattened into lines so the
+    // dashed curve only contains lines
+    //
+    // returns a copy of the curve if the pattern is empty, has negative lengths or sums to zero
+    pub(crate) fn dash(&self, pattern: &[f32], offset: f32) -> Curve {
+        let is_valid = pattern
+            .iter()
+            .all(|length| length.is_finite() && *length >= 0.0);
+        let total: f32 = pattern.iter().sum();
+
+        if pattern.is_empty() || !is_valid || total <= 0.0 || !offset.is_finite() {
+            return self.clone();
+        }
+
+        let pattern = match pattern.len() % 2 {
+            0 => pattern.to_vec(),
+            _ => pattern.repeat(2),
+        };
+
+        let total: f32 = pattern.iter().sum();
+
+        // find the dash `offset` units into the pattern, and how much of it is left
+        let start = || {
+            let mut index = 0;
+            let mut distance = offset.rem_euclid(total);
+
+            while distance >= pattern[index] {
+                distance -= pattern[index];
+                index = (index + 1) % pattern.len();
+            }
+
+            (index, pattern[index] - distance)
+        };
+
+        let mut dashed = Curve::new();
+        let (mut index, mut remaining) = start();
+        let mut pen_down = false;
+
+        self.flatten_lines(Self::FLATTEN_TOLERANCE, |line| {
+            // a new contour restarts the pattern
+            if line.first {
+                (index, remaining) = start();
+                pen_down = false;
+            }
+
+            let mut point = line.start;
+            let mut length = line.start.distance(line.end);
+            let direction = (line.end - line.start).normalize();
+
+            while length > 0.0 {
+                let step = f32::min(remaining, length);
+                let next = point + direction * step;
+
+                if index % 2 == 0 {
+                    if !pen_down {
+                        dashed.move_to(point);
+                        pen_down = true;
+                    }
+
+                    dashed.line_to(next);
+                }
+
+                point = next;
+                length -= step;
+                remaining -= step;
+
+                if remaining <= 0.0 {
+                    index = (index + 1) % pattern.len();
+                    remaining = pattern[index];
+                    pen_down = false;
+                }
+            }
+        });
+
+        dashed
     }
 
     pub(crate) fn append_reverse(&mut self, curve: &Curve) {
@@ -337,9 +604,10 @@ impl Curve {
         self.close();
     }
 
-    fn border_data(radius: BorderRadius, width: BorderWidth, index: usize) -> [f32; 3] {
+    fn border_data(radius: BorderRadius, width: BorderWidth, edge: BorderEdge) -> [f32; 3] {
         let radius: [f32; 4] = radius.into();
         let width: [f32; 4] = width.into();
+        let index = edge as usize;
 
         let radius = radius[index];
         let start = width[(index + 3) % 4];
@@ -350,176 +618,126 @@ impl Curve {
 
     /// Push the border of a rectangle with rounded corners to the curve.
     pub fn push_rect_with_borders(&mut self, rect: Rect, radius: BorderRadius, width: BorderWidth) {
+        for edge in BorderEdge::ALL {
+            self.push_rect_border_edge(rect, radius, width, edge);
+        }
+    }
+
+    /// Push a single edge of the border of a rectangle with rounded corners to the curve.
+    ///
+    /// Each edge includes the corner leading into it, going clockwise, eg. the top edge
+    /// includes the top left corner.
+    pub fn push_rect_border_edge(
+        &mut self,
+        rect: Rect,
+        radius: BorderRadius,
+        width: BorderWidth,
+        edge: BorderEdge,
+    ) {
         let tl = rect.top_left();
         let tr = rect.top_right();
         let br = rect.bottom_right();
         let bl = rect.bottom_left();
 
-        let [r, s, e] = Self::border_data(radius, width, 0);
-
-        if s > 0.0 || e > 0.0 {
-            self.move_to(tl + Vector::new(0.0, r));
-            self.cubic_to(
-                tl + Vector::new(0.0, r * 0.45),
-                tl + Vector::new(r * 0.45, 0.0),
-                tl + Vector::new(r, 0.0),
-            );
-
-            if e > 0.0 {
-                self.line_to(tr + Vector::new(-r, 0.0));
-                self.line_to(tr + Vector::new(-r, e));
-            }
-
-            self.line_to(tl + Vector::new(r, e));
-
-            self.cubic_to(
-                tl + Vector::new((r + s) * 0.45, e),
-                tl + Vector::new(s, (r + e) * 0.45),
-                tl + Vector::new(s, r),
-            );
+        let [r, s, e] = Self::border_data(radius, width, edge);
 
-            self.close();
+        if s <= 0.0 && e <= 0.0 {
+            return;
         }
 
-        let [r, s, e] = Self::border_data(radius, width, 1);
-
-        if s > 0.0 || e > 0.0 {
-            self.move_to(tr + Vector::new(-r, 0.0));
-            self.cubic_to(
-                tr + Vector::new(-r * 0.45, 0.0),
-                tr + Vector::new(0.0, r * 0.45),
-                tr + Vector::new(0.0, r),
-            );
-
-            if e > 0.0 {
-                self.line_to(br + Vector::new(0.0, -r));
-                self.line_to(br + Vector::new(-e, -r));
-            }
-
-            self.line_to(tr + Vector::new(-e, r));
-
-            self.cubic_to(
-                tr + Vector::new(-e, (r + s) * 0.45),
-                tr + Vector::new(-(r + e) * 0.45, s),
-                tr + Vector::new(-r, s),
-            );
-
-            self.close();
-        }
+        match edge {
+            BorderEdge::Top => {
+                self.move_to(tl + Vector::new(0.0, r));
+                self.cubic_to(
+                    tl + Vector::new(0.0, r * 0.45),
+                    tl + Vector::new(r * 0.45, 0.0),
+                    tl + Vector::new(r, 0.0),
+                );
+
+                if e > 0.0 {
+                    self.line_to(tr + Vector::new(-r, 0.0));
+                    self.line_to(tr + Vector::new(-r, e));
+                }
 
-        let [r, s, e] = Self::border_data(radius, width, 2);
+                self.line_to(tl + Vector::new(r, e));
 
-        if s > 0.0 || e > 0.0 {
-            self.move_to(br + Vector::new(0.0, -r));
-            self.cubic_to(
-                br + Vector::new(0.0, -r * 0.45),
-                br + Vector::new(-r * 0.45, 0.0),
-                br + Vector::new(-r, 0.0),
-            );
+                self.cubic_to(
+                    tl + Vector::new((r + s) * 0.45, e),
+                    tl + Vector::new(s, (r + e) * 0.45),
+                    tl + Vector::new(s, r),
+                );
 
-            if e > 0.0 {
-                self.line_to(bl + Vector::new(r, 0.0));
-                self.line_to(bl + Vector::new(r, -e));
+                self.close();
             }
+            BorderEdge::Right => {
+                self.move_to(tr + Vector::new(-r, 0.0));
+                self.cubic_to(
+                    tr + Vector::new(-r * 0.45, 0.0),
+                    tr + Vector::new(0.0, r * 0.45),
+                    tr + Vector::new(0.0, r),
+                );
+
+                if e > 0.0 {
+                    self.line_to(br + Vector::new(0.0, -r));
+                    self.line_to(br + Vector::new(-e, -r));
+                }
 
-            self.line_to(br + Vector::new(-r, -e));
-
-            self.cubic_to(
-                br + Vector::new(-(r + s) * 0.45, -e),
-                br + Vector::new(-s, -(r + e) * 0.45),
-                br + Vector::new(-s, -r),
-            );
-
-            self.close();
-        }
-
-        let [r, s, e] = Self::border_data(radius, width, 3);
+                self.line_to(tr + Vector::new(-e, r));
 
-        if s > 0.0 || e > 0.0 {
-            self.move_to(bl + Vector::new(r, 0.0));
-            self.cubic_to(
-                bl + Vector::new(r * 0.45, 0.0),
-                bl + Vector::new(0.0, -r * 0.45),
-                bl + Vector::new(0.0, -r),
-            );
+                self.cubic_to(
+                    tr + Vector::new(-e, (r + s) * 0.45),
+                    tr + Vector::new(-(r + e) * 0.45, s),
+                    tr + Vector::new(-r, s),
+                );
 
-            if e > 0.0 {
-                self.line_to(tl + Vector::new(0.0, r));
-                self.line_to(tl + Vector::new(e, r));
+                self.close();
             }
+            BorderEdge::Bottom => {
+                self.move_to(br + Vector::new(0.0, -r));
+                self.cubic_to(
+                    br + Vector::new(0.0, -r * 0.45),
+                    br + Vector::new(-r * 0.45, 0.0),
+                    br + Vector::new(-r, 0.0),
+                );
+
+                if e > 0.0 {
+                    self.line_to(bl + Vector::new(r, 0.0));
+                    self.line_to(bl + Vector::new(r, -e));
+                }
 
-            self.line_to(bl + Vector::new(e, -r));
-
-            self.cubic_to(
-                bl + Vector::new(e, -(r + s) * 0.45),
-                bl + Vector::new((r + e) * 0.45, -s),
-                bl + Vector::new(r, -s),
-            );
-
-            self.close();
-        }
-    }
-
-    pub(crate) fn square_roots(a: f32, b: f32, c: f32) -> [f32; 2] {
-        if a.abs() < 1e-6 {
-            return [-c / b, f32::NAN];
-        }
-
-        let discriminant = b * b - 4.0 * a * c;
-
-        if discriminant < 0.0 {
-            return [f32::NAN, f32::NAN];
-        }
-
-        let sqrt = discriminant.sqrt();
-
-        let x1 = (-b + sqrt) / (2.0 * a);
-        let x2 = (-b - sqrt) / (2.0 * a);
-
-        [x1, x2]
-    }
-
-    pub(crate) fn cube_roots(a: f32, b: f32, c: f32, d: f32) -> [f32; 3] {
-        if a.abs() < 1e-6 {
-            let [x1, x2] = Self::square_roots(b, c, d);
-            return [x1, x2, f32::NAN];
-        }
-
-        let a_inv = 1.0 / a;
-        let a = b * a_inv;
-        let b = c * a_inv;
-        let c = d * a_inv;
-
-        let q = (a * a - b * 3.0) / 9.0;
-        let r = (2.0 * a * a * a - 9.0 * a * b + 27.0 * c) / 54.0;
-
-        let q3 = q * q * q;
-        let r2_minus_q3 = r * r - q3;
-        let adiv3 = a / 3.0;
+                self.line_to(br + Vector::new(-r, -e));
 
-        if r2_minus_q3 < 0.0 {
-            // we have 3 real roots
-            let theta = (r / q3.sqrt()).acos();
-            let neg2_root_q = -2.0 * q.sqrt();
+                self.cubic_to(
+                    br + Vector::new(-(r + s) * 0.45, -e),
+                    br + Vector::new(-s, -(r + e) * 0.45),
+                    br + Vector::new(-s, -r),
+                );
 
-            let x1 = neg2_root_q * (theta / 3.0).cos() - adiv3;
-            let x2 = neg2_root_q * ((theta + 2.0 * PI) / 3.0).cos() - adiv3;
-            let x3 = neg2_root_q * ((theta - 2.0 * PI) / 3.0).cos() - adiv3;
+                self.close();
+            }
+            BorderEdge::Left => {
+                self.move_to(bl + Vector::new(r, 0.0));
+                self.cubic_to(
+                    bl + Vector::new(r * 0.45, 0.0),
+                    bl + Vector::new(0.0, -r * 0.45),
+                    bl + Vector::new(0.0, -r),
+                );
+
+                if e > 0.0 {
+                    self.line_to(tl + Vector::new(0.0, r));
+                    self.line_to(tl + Vector::new(e, r));
+                }
 
-            [x1, x2, x3]
-        } else {
-            // we have 1 real root
-            let mut a = (r.abs() + r2_minus_q3.sqrt()).cbrt();
+                self.line_to(bl + Vector::new(e, -r));
 
-            if r < 0.0 {
-                a = -a;
-            }
+                self.cubic_to(
+                    bl + Vector::new(e, -(r + s) * 0.45),
+                    bl + Vector::new((r + e) * 0.45, -s),
+                    bl + Vector::new(r, -s),
+                );
 
-            if a != 0.0 {
-                a += q / a;
+                self.close();
             }
-
-            [a - adiv3, f32::NAN, f32::NAN]
         }
     }
 
@@ -539,96 +757,186 @@ impl Curve {
         )
     }
 
-    // check if the curve contains a point using the even-odd rule
+    // the maximum distance between the flattened lines and the true curve
+    const FLATTEN_TOLERANCE: f32 = 0.1;
+
+    // the smallest tolerance `Curve::flatten` accepts
+    const MIN_FLATTEN_TOLERANCE: f32 = 0.001;
+
+    // the most lines a single segment is flattened into, segments that need more are
+    // flattened less precisely, so huge or degenerate segments can't stall hit testing
+    const MAX_FLATTEN_STEPS: usize = 1024;
+
+    // flatten the curve into line segments
     //
-    // this works by counting the number of times a ray starting at the `point`
-    // and extending to the right along the x-axis intersects the curve
-    fn contains_even_odd(&self, p: Point) -> bool {
-        let mut crossings = 0;
-        let mut s = *self.points.last().unwrap();
+    // `Close` emits a line back to the start of the contour, contours that aren't closed
+    // explicitly are left open
+    fn flatten_lines(&self, tolerance: f32, mut f: impl FnMut(FlatLine)) {
+        let mut start = Point::ZERO;
+        let mut s = Point::ZERO;
+
+        // the first line after a move starts a contour, even if the move didn't go anywhere
+        let first = Cell::new(true);
+        let mut f = |line: FlatLine| {
+            f(FlatLine {
+                first: first.replace(false),
+                ..line
+            })
+        };
 
         for segment in self.iter() {
             match segment {
                 CurveSegment::Move(e) => {
+                    first.set(true);
+                    start = e;
                     s = e;
                 }
                 CurveSegment::Line(e) => {
-                    crossings += Self::line_intersection_count(s, e, p);
+                    f(FlatLine::new(s, e, 0.0, 1.0));
                     s = e;
                 }
                 CurveSegment::Quad(c0, e) => {
-                    crossings += Self::quad_intersection_count(s, c0, e, p);
+                    let dd = ((s - c0) + (e - c0)).length();
+                    let n = Self::flatten_steps(dd * 0.25, tolerance);
+
+                    let mut prev = s;
+
+                    for i in 1..=n {
+                        let t = i as f32 / n as f32;
+                        let next = Point::new(
+                            Self::quadratic_bezier(s.x, c0.x, e.x, t),
+                            Self::quadratic_bezier(s.y, c0.y, e.y, t),
+                        );
+
+                        let t0 = (i - 1) as f32 / n as f32;
+                        f(FlatLine::new(prev, next, t0, t));
+                        prev = next;
+                    }
+
                     s = e;
                 }
                 CurveSegment::Cubic(c0, c1, e) => {
-                    crossings += Self::cubic_intersection_count(s, c0, c1, e, p);
+                    let dd0 = ((s - c0) + (c1 - c0)).length();
+                    let dd1 = ((c0 - c1) + (e - c1)).length();
+                    let n = Self::flatten_steps(f32::max(dd0, dd1) * 0.75, tolerance);
+
+                    let mut prev = s;
+
+                    for i in 1..=n {
+                        let t = i as f32 / n as f32;
+                        let next = Point::new(
+                            Self::cubic_bezier(s.x, c0.x, c1.x, e.x, t),
+                            Self::cubic_bezier(s.y, c0.y, c1.y, e.y, t),
+                        );
+
+                        let t0 = (i - 1) as f32 / n as f32;
+                        f(FlatLine::new(prev, next, t0, t));
+                        prev = next;
+                    }
+
                     s = e;
                 }
-                CurveSegment::Close => {}
+                CurveSegment::Close => {
+                    f(FlatLine::new(s, start, 0.0, 1.0));
+                    s = start;
+                }
             }
         }
-
-        crossings % 2 == 1
     }
 
-    fn line_intersection_count(s: Point, e: Point, p: Point) -> usize {
-        let a = e.y - s.y;
-        let b = s.y - p.y;
+    // the number of lines needed to flatten a segment within `tolerance`, given the maximum
+    // deviation of a single line from the curve
+    //
+    // segments with non-finite points are a single line
+    fn flatten_steps(deviation: f32, tolerance: f32) -> usize {
+        let steps = f32::sqrt(deviation / tolerance).ceil();
 
-        let t = -b / a;
+        match steps.is_finite() {
+            true => (steps as usize).clamp(1, Self::MAX_FLATTEN_STEPS),
+            false => 1,
+        }
+    }
 
-        let is_on_curve = (0.0..=1.0).contains(&t);
-        let is_right = Self::lerps(s.x, e.x, t) >= p.x;
+    // compute the winding number of the curve around a point
+    //
+    // contours are implicitly closed, as they are when filled
+    fn winding(&self, p: Point) -> i32 {
+        let mut winding = 0;
 
-        (is_on_curve && is_right) as usize
-    }
+        let mut start = None;
+        let mut last = Point::ZERO;
 
-    pub(crate) fn quad_intersections(s: Point, c0: Point, e: Point, p: Point) -> [f32; 2] {
-        let a = s.y - 2.0 * c0.y + e.y;
-        let b = 2.0 * (c0.y - s.y);
-        let c = s.y - p.y;
+        let mut edge = |a: Point, b: Point| {
+            let side = (b - a).cross(p - a);
 
-        Self::square_roots(a, b, c)
-    }
+            if a.y <= p.y {
+                if b.y > p.y && side > 0.0 {
+                    winding += 1;
+                }
+            } else if b.y <= p.y && side < 0.0 {
+                winding -= 1;
+            }
+        };
 
-    fn quad_intersection_count(s: Point, c0: Point, e: Point, p: Point) -> usize {
-        let [t1, t2] = Self::quad_intersections(s, c0, e, p);
+        self.flatten_lines(Self::FLATTEN_TOLERANCE, |line| {
+            // a new contour was started, close the previous one
+            if line.first {
+                if let Some(first) = start {
+                    edge(last, first);
+                }
 
-        let is_valid = |t: f32| {
-            let is_on_curve = (0.0..=1.0).contains(&t);
-            let is_right = Self::quadratic_bezier(s.x, c0.x, e.x, t) >= p.x;
+                start = Some(line.start);
+            }
 
-            is_on_curve && is_right
-        };
+            edge(line.start, line.end);
+            last = line.end;
+        });
+
+        if let Some(first) = start {
+            edge(last, first);
+        }
 
-        is_valid(t1) as usize + is_valid(t2) as usize
+        winding
     }
+}
 
-    pub(crate) fn cubic_intersections(
-        s: Point,
-        c0: Point,
-        c1: Point,
-        e: Point,
-        p: Point,
-    ) -> [f32; 3] {
-        let a = -s.y + 3.0 * c0.y - 3.0 * c1.y + e.y;
-        let b = 3.0 * (s.y - 2.0 * c0.y + c1.y);
-        let c = 3.0 * (c0.y - s.y);
-        let d = s.y - p.y;
+// a line produced by flattening a curve segment, covering `t0..=t1` of the segment
+//
+// `first` is set by `Curve::flatten_lines` on the first line of every contour
+#[derive(Clone, Copy, Debug)]
+struct FlatLine {
+    start: Point,
+    end: Point,
+    t0: f32,
+    t1: f32,
+    first: bool,
+}
 
-        Self::cube_roots(a, b, c, d)
+impl FlatLine {
+    fn new(start: Point, end: Point, t0: f32, t1: f32) -> Self {
+        Self {
+            start,
+            end,
+            t0,
+            t1,
+            first: false,
+        }
     }
 
-    fn cubic_intersection_count(s: Point, c0: Point, c1: Point, e: Point, p: Point) -> usize {
-        let is_valid = |t: f32| {
-            let is_on_curve = (0.0..=1.0).contains(&t);
-            let is_right = Self::cubic_bezier(s.x, c0.x, c1.x, e.x, t) >= p.x;
-            is_on_curve && is_right
-        };
+    // project a point onto the line, returning the parameter along the line
+    // and the projected point
+    fn project(self, point: Point) -> (f32, Point) {
+        let direction = self.end - self.start;
+        let length_squared = direction.length_squared();
+
+        if length_squared == 0.0 {
+            return (0.0, self.start);
+        }
 
-        let [t1, t2, t3] = Self::cubic_intersections(s, c0, c1, e, p);
+        let t = (point - self.start).dot(direction) / length_squared;
+        let t = t.clamp(0.0, 1.0);
 
-        is_valid(t1) as usize + is_valid(t2) as usize + is_valid(t3) as usize
+        (t, self.start + direction * t)
     }
 }
 
@@ -771,6 +1079,127 @@ impl Conic {
     }
 }
 
+// a contour of a curve where every segment is a cubic bezier curve, used to morph curves
+#[derive(Clone, Debug)]
+struct Contour {
+    start: Point,
+    cubics: Vec<[Point; 3]>,
+    closed: bool,
+}
+
+impl Contour {
+    fn all(curve: &Curve) -> Vec<Contour> {
+        let mut contours = Vec::<Contour>::new();
+
+        for segment in curve {
+            if let CurveSegment::Move(point) = segment {
+                contours.push(Contour::new(point));
+                continue;
+            }
+
+            let start = match contours.last() {
+                Some(contour) if !contour.closed => contour.end(),
+                Some(contour) => contour.start,
+                None => Point::ZERO,
+            };
+
+            // segments without a move start a new contour where the previous one ended
+            if contours.last().is_none_or(|contour| contour.closed) {
+                contours.push(Contour::new(start));
+            }
+
+            let contour = contours.last_mut().unwrap();
+
+            match segment {
+                CurveSegment::Move(_) => unreachable!(),
+                CurveSegment::Line(p) => contour.cubics.push(line(start, p)),
+                CurveSegment::Quad(c, p) => {
+                    // a quadratic curve is exactly a cubic curve with these control points
+                    let a = start.lerp(c, 2.0 / 3.0);
+                    let b = p.lerp(c, 2.0 / 3.0);
+                    contour.cubics.push([a, b, p]);
+                }
+                CurveSegment::Cubic(a, b, p) => contour.cubics.push([a, b, p]),
+                CurveSegment::Close => {
+                    if start != contour.start {
+                        contour.cubics.push(line(start, contour.start));
+                    }
+
+                    contour.closed = true;
+                }
+            }
+        }
+
+        // a lone move has nothing to morph
+        contours.retain(|contour| !contour.cubics.is_empty());
+        contours
+    }
+
+    fn new(start: Point) -> Self {
+        Self {
+            start,
+            cubics: Vec::new(),
+            closed: false,
+        }
+    }
+
+    fn end(&self) -> Point {
+        self.cubics.last().map_or(self.start, |cubic| cubic[2])
+    }
+
+    // split the longest segment in half, which doesn't change the shape of the contour
+    fn split_longest(&mut self) {
+        let mut start = self.start;
+        let mut longest = (0, start, 0.0);
+
+        for (i, [a, b, p]) in self.cubics.iter().enumerate() {
+            let length = start.distance(*a) + a.distance(*b) + b.distance(*p);
+
+            if length > longest.2 || i == 0 {
+                longest = (i, start, length);
+            }
+
+            start = *p;
+        }
+
+        let (index, start, _) = longest;
+        let [a, b, p] = self.cubics[index];
+
+        let ab = a.lerp(b, 0.5);
+        let sa = start.lerp(a, 0.5);
+        let bp = b.lerp(p, 0.5);
+        let left = sa.lerp(ab, 0.5);
+        let right = ab.lerp(bp, 0.5);
+        let mid = left.lerp(right, 0.5);
+
+        self.cubics[index] = [sa, left, mid];
+        self.cubics.insert(index + 1, [right, bp, p]);
+    }
+
+    fn curve(contours: &[Contour]) -> Curve {
+        let mut curve = Curve::new();
+
+        for contour in contours {
+            curve.move_to(contour.start);
+
+            for [a, b, p] in &contour.cubics {
+                curve.cubic_to(*a, *b, *p);
+            }
+
+            if contour.closed {
+                curve.close();
+            }
+        }
+
+        curve
+    }
+}
+
+// a line as a cubic bezier curve
+fn line(start: Point, end: Point) -> [Point; 3] {
+    [start.lerp(end, 1.0 / 3.0), start.lerp(end, 2.0 / 3.0), end]
+}
+
 impl<'a> IntoIterator for &'a Curve {
     type Item = CurveSegment;
     type IntoIter = CurveIter<'a>;
@@ -856,3 +1285,375 @@ impl Iterator for CurveIter<'_> {
         (remaining, Some(remaining))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rects_and_ovals_are_recognized() {
+        let rect = Rect::min_size(Point::new(10.0, 20.0), Size::new(30.0, 40.0));
+
+        assert!(Curve::rect(rect).is_rect());
+        assert!(Curve::ellipse(rect).is_oval());
+        assert!(Curve::circle(Point::new(3.3, 7.1), 12.7).is_oval());
+
+        assert!(!Curve::rect(rect).is_oval());
+        assert!(!Curve::ellipse(rect).is_rect());
+
+        let mut rounded = Curve::new();
+        rounded.push_rect_with_radius(rect, BorderRadius::all(4.0));
+        assert!(!rounded.is_rect() && !rounded.is_oval());
+
+        // a rectangle with another shape in the same curve isn't a rectangle
+        let mut two = Curve::rect(rect);
+        two.push_rect(rect);
+        assert!(!two.is_rect());
+    }
+
+    #[test]
+    fn border_edges_cover_their_side() {
+        let rect = Rect::min_size(Point::ZERO, Size::all(20.0));
+        let width = BorderWidth::new(2.0, 3.0, 4.0, 5.0);
+
+        // the middle of each edge of the border
+        let points = [
+            Point::new(10.0, 1.0),
+            Point::new(18.5, 10.0),
+            Point::new(10.0, 18.0),
+            Point::new(2.5, 10.0),
+        ];
+
+        for (i, edge) in BorderEdge::ALL.into_iter().enumerate() {
+            let mut curve = Curve::new();
+            curve.push_rect_border_edge(rect, BorderRadius::all(0.0), width, edge);
+
+            for (j, point) in points.into_iter().enumerate() {
+                assert_eq!(
+                    curve.contains(point, FillRule::NonZero),
+                    i == j,
+                    "{:?}",
+                    edge
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn circle_contains_center() {
+        let curve = Curve::circle(Point::new(50.0, 50.0), 20.0);
+
+        assert!(curve.contains(Point::new(50.0, 50.0), FillRule::NonZero));
+        assert!(curve.contains(Point::new(50.0, 50.0), FillRule::EvenOdd));
+
+        assert!(!curve.contains(Point::new(200.0, 50.0), FillRule::NonZero));
+        assert!(!curve.contains(Point::new(200.0, 50.0), FillRule::EvenOdd));
+    }
+
+    #[test]
+    fn circle_distance() {
+        let curve = Curve::circle(Point::new(50.0, 50.0), 20.0);
+
+        let distance = curve.distance_to(Point::new(100.0, 50.0));
+        assert!((distance - 30.0).abs() < 0.5);
+
+        let (nearest, t) = curve.nearest_point(Point::new(50.0, 0.0)).unwrap();
+        assert!(nearest.distance(Point::new(50.0, 30.0)) < 0.5);
+        assert!((0.0..=1.0).contains(&t));
+    }
+
+    #[test]
+    fn dashed_line() {
+        let mut line = Curve::new();
+        line.move_to(Point::new(0.0, 0.0));
+        line.line_to(Point::new(100.0, 0.0));
+
+        let dashes = |curve: &Curve| {
+            let moves = curve.iter().filter(|s| matches!(s, CurveSegment::Move(_)));
+            moves.count()
+        };
+
+        let dashed = line.dash(&[10.0, 10.0], 0.0);
+        assert_eq!(dashes(&dashed), 5);
+        assert_eq!(
+            dashed.bounds(),
+            Rect::new(Point::ZERO, Point::new(90.0, 0.0))
+        );
+
+        // shifting the pattern by half a dash splits the first one
+        let dashed = line.dash(&[10.0, 10.0], 5.0);
+        assert_eq!(dashes(&dashed), 6);
+
+        assert_eq!(line.dash(&[], 0.0), line);
+
+        // a contour starting where the last one ended restarts the pattern
+        let mut contours = Curve::new();
+        contours.move_to(Point::ZERO);
+        contours.line_to(Point::new(15.0, 0.0));
+        contours.move_to(Point::new(15.0, 0.0));
+        contours.line_to(Point::new(30.0, 0.0));
+
+        let dashed = contours.dash(&[10.0, 10.0], 0.0);
+        assert_eq!(dashes(&dashed), 2);
+        assert_eq!(
+            dashed.bounds(),
+            Rect::new(Point::ZERO, Point::new(25.0, 0.0))
+        );
+    }
+
+    #[test]
+    fn strokes_follow_their_dash_pattern() {
+        let mut line = Curve::new();
+        line.move_to(Point::new(0.0, 0.0));
+        line.line_to(Point::new(100.0, 0.0));
+
+        let mut stroke = Stroke {
+            width: 4.0,
+            dash: Some(vec![10.0, 10.0]),
+            ..Default::default()
+        };
+
+        let mut stroked = Curve::new();
+        stroked.stroke_curve(&line, &stroke);
+        assert!(stroked.contains(Point::new(5.0, 0.0), FillRule::NonZero));
+        assert!(!stroked.contains(Point::new(15.0, 0.0), FillRule::NonZero));
+
+        // the offset moves the dashes along the line
+        stroke.dash_offset = 10.0;
+
+        let mut stroked = Curve::new();
+        stroked.stroke_curve(&line, &stroke);
+        assert!(!stroked.contains(Point::new(5.0, 0.0), FillRule::NonZero));
+        assert!(stroked.contains(Point::new(15.0, 0.0), FillRule::NonZero));
+    }
+
+    fn triangle(a: Point, b: Point, c: Point) -> Curve {
+        let mut curve = Curve::new();
+        curve.move_to(a);
+        curve.line_to(b);
+        curve.line_to(c);
+        curve.close();
+        curve
+    }
+
+    #[test]
+    fn lerp_triangles() {
+        let a = triangle(Point::ZERO, Point::new(10.0, 0.0), Point::new(0.0, 10.0));
+        let b = triangle(
+            Point::all(10.0),
+            Point::new(30.0, 10.0),
+            Point::new(10.0, 30.0),
+        );
+
+        for t in [0.0, 0.25, 0.5, 1.0] {
+            assert_eq!(a.lerp(&a, t), a);
+        }
+
+        let mid = triangle(
+            Point::all(5.0),
+            Point::new(20.0, 5.0),
+            Point::new(5.0, 20.0),
+        );
+        assert_eq!(a.lerp(&b, 0.5), mid);
+    }
+
+    #[test]
+    fn contours_are_closed_when_the_next_starts_at_their_end() {
+        // a triangle below the diagonal, and a square starting at its last corner
+        let mut curve = Curve::new();
+        curve.move_to(Point::ZERO);
+        curve.line_to(Point::new(10.0, 0.0));
+        curve.line_to(Point::all(10.0));
+        curve.move_to(Point::all(10.0));
+        curve.line_to(Point::new(20.0, 10.0));
+        curve.line_to(Point::all(20.0));
+        curve.line_to(Point::new(10.0, 20.0));
+
+        assert!(curve.contains(Point::new(8.0, 2.0), FillRule::NonZero));
+        assert!(curve.contains(Point::all(15.0), FillRule::NonZero));
+
+        // above the diagonal, which closes the triangle
+        assert!(!curve.contains(Point::new(3.0, 5.0), FillRule::NonZero));
+    }
+
+    #[test]
+    fn normalize_triangle_and_rect() {
+        let a = triangle(Point::ZERO, Point::new(10.0, 0.0), Point::new(0.0, 10.0));
+        let b = Curve::rect(Rect::new(Point::ZERO, Point::all(10.0)));
+
+        let (a, b) = Curve::normalize(&a, &b).unwrap();
+        assert!(a.is_compatible(&b));
+
+        // the shapes are unchanged
+        assert!(a.contains(Point::new(2.0, 2.0), FillRule::NonZero));
+        assert!(!a.contains(Point::new(8.0, 8.0), FillRule::NonZero));
+        assert!(b.contains(Point::new(8.0, 8.0), FillRule::NonZero));
+
+        let open = Curve::circle(Point::ZERO, 5.0);
+        let mut line = Curve::new();
+        line.move_to(Point::ZERO);
+        line.line_to(Point::all(10.0));
+
+        assert!(Curve::normalize(&open, &line).is_none());
+    }
+
+    #[test]
+    fn flattened_cubic_stays_within_tolerance() {
+        let [s, a, b, e] = [
+            Point::new(0.0, 0.0),
+            Point::new(0.0, 100.0),
+            Point::new(100.0, 100.0),
+            Point::new(100.0, -50.0),
+        ];
+
+        let mut curve = Curve::new();
+        curve.move_to(s);
+        curve.cubic_to(a, b, e);
+
+        let cubic = |t: f32| {
+            let u = 1.0 - t;
+            let [s, a, b, e] = [s, a, b, e].map(Point::to_vector);
+            (s * u * u * u + a * 3.0 * u * u * t + b * 3.0 * u * t * t + e * t * t * t).to_point()
+        };
+
+        for tolerance in [1.0, 0.25, 0.01] {
+            let points: Vec<_> = curve.flatten(tolerance).collect();
+            assert_eq!(points.first(), Some(&s));
+            assert_eq!(points.last(), Some(&e));
+
+            // every point on the curve is close to one of the lines
+            for i in 0..=1000 {
+                let point = cubic(i as f32 / 1000.0);
+
+                let distance = (points.windows(2))
+                    .map(|line| {
+                        let (_, closest) = FlatLine::new(line[0], line[1], 0.0, 1.0).project(point);
+                        closest.distance(point)
+                    })
+                    .fold(f32::INFINITY, f32::min);
+
+                assert!(distance <= tolerance + 1e-3, "{distance} > {tolerance}");
+            }
+        }
+
+        // a looser tolerance needs fewer lines
+        assert!(curve.flatten(1.0).count() < curve.flatten(0.01).count());
+    }
+
+    #[test]
+    fn extreme_curves_are_flattened_into_few_lines() {
+        let mut curve = Curve::new();
+        curve.move_to(Point::new(0.0, 0.0));
+        curve.cubic_to(
+            Point::new(1e15, -1e15),
+            Point::new(-1e15, 1e15),
+            Point::new(10.0, 0.0),
+        );
+        curve.close();
+
+        // the curve would need billions of lines, the step count is capped instead
+        let count = curve.flatten(0.001).count();
+        assert!(count <= Curve::MAX_FLATTEN_STEPS + 2, "{count}");
+        assert!(count > Curve::MAX_FLATTEN_STEPS, "{count}");
+
+        // and hit testing finishes
+        let _ = curve.contains(Point::new(5.0, 0.0), FillRule::NonZero);
+    }
+
+    #[test]
+    fn large_curves_are_flattened_within_tolerance() {
+        let [s, c, e] = [
+            Point::new(0.0, 0.0),
+            Point::new(5000.0, 10000.0),
+            Point::new(10000.0, 0.0),
+        ];
+
+        let mut curve = Curve::new();
+        curve.move_to(s);
+        curve.quad_to(c, e);
+
+        let quad = |t: f32| {
+            let u = 1.0 - t;
+            let [s, c, e] = [s, c, e].map(Point::to_vector);
+            (s * u * u + c * 2.0 * u * t + e * t * t).to_point()
+        };
+
+        // this needs more lines than a cap of a few hundred would allow
+        let tolerance = 0.01;
+        let points: Vec<_> = curve.flatten(tolerance).collect();
+        assert!(points.len() > 257);
+
+        for i in 0..=1000 {
+            let point = quad(i as f32 / 1000.0);
+
+            let distance = (points.windows(2))
+                .map(|line| {
+                    let (_, closest) = FlatLine::new(line[0], line[1], 0.0, 1.0).project(point);
+                    closest.distance(point)
+                })
+                .fold(f32::INFINITY, f32::min);
+
+            // f32 points this far from the origin are only precise to about a thousandth
+            assert!(distance <= tolerance + 2e-3, "{distance} > {tolerance}");
+        }
+    }
+
+    #[test]
+    fn contours_starting_where_the_last_ended_are_split() {
+        let mut curve = Curve::new();
+        curve.move_to(Point::ZERO);
+        curve.line_to(Point::new(10.0, 0.0));
+        curve.move_to(Point::new(10.0, 0.0));
+        curve.line_to(Point::new(10.0, 10.0));
+
+        // both contours start with the point they moved to
+        let points: Vec<_> = curve.flatten(0.1).collect();
+        assert_eq!(
+            points,
+            [
+                Point::ZERO,
+                Point::new(10.0, 0.0),
+                Point::new(10.0, 0.0),
+                Point::new(10.0, 10.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn sample_along_lines() {
+        let mut curve = Curve::new();
+        curve.move_to(Point::ZERO);
+        curve.line_to(Point::new(30.0, 40.0));
+        curve.line_to(Point::new(30.0, 90.0));
+
+        assert_eq!(curve.length(), 100.0);
+
+        let (point, direction) = curve.sample_at(25.0).unwrap();
+        assert!(point.distance(Point::new(15.0, 20.0)) < 1e-4);
+        assert!((direction - Vector::new(0.6, 0.8)).length() < 1e-4);
+
+        let (point, direction) = curve.sample_at(75.0).unwrap();
+        assert!(point.distance(Point::new(30.0, 65.0)) < 1e-4);
+        assert!((direction - Vector::Y).length() < 1e-4);
+
+        // distances past the ends are clamped
+        assert_eq!(curve.sample_at(-10.0).unwrap().0, Point::ZERO);
+        assert_eq!(curve.sample_at(500.0).unwrap().0, Point::new(30.0, 90.0));
+
+        assert!(Curve::new().sample_at(0.0).is_none());
+
+        let circle = Curve::circle(Point::ZERO, 10.0);
+        assert!((circle.length() - std::f32::consts::TAU * 10.0).abs() < 0.1);
+    }
+
+    #[test]
+    fn nested_rects_winding() {
+        let mut curve = Curve::new();
+        curve.push_rect(Rect::new(Point::ZERO, Point::new(100.0, 100.0)));
+        curve.push_rect(Rect::new(Point::new(25.0, 25.0), Point::new(75.0, 75.0)));
+
+        let center = Point::new(50.0, 50.0);
+        assert!(curve.contains(center, FillRule::NonZero));
+        assert!(!curve.contains(center, FillRule::EvenOdd));
+    }
+}
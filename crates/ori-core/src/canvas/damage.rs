@@ -0,0 +1,70 @@
+use crate::layout::Rect;
+
+/// The region of a window that changed since the last frame.
+///
+/// Views that request a draw add the bounds of what they drew in the last frame and what they
+/// draw now, renderers can then limit drawing to the damaged region. Whenever it isn't known
+/// exactly what changed, eg. after a layout, the whole window is damaged.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Damage {
+    /// Nothing has been damaged.
+    #[default]
+    None,
+
+    /// The region inside a rectangle has been damaged.
+    Rect(Rect),
+
+    /// The whole window has been damaged.
+    Full,
+}
+
+impl Damage {
+    /// Add a rectangle to the damaged region.
+    ///
+    /// Rectangles that aren't finite damage the whole window.
+    pub fn add(&mut self, rect: Rect) {
+        if !rect.min.is_finite() || !rect.max.is_finite() {
+            *self = Damage::Full;
+            return;
+        }
+
+        if rect.area() <= 0.0 {
+            return;
+        }
+
+        *self = match *self {
+            Damage::None => Damage::Rect(rect),
+            Damage::Rect(damage) => Damage::Rect(damage.union(rect)),
+            Damage::Full => Damage::Full,
+        };
+    }
+
+    /// Add another damaged region to the damaged region.
+    pub fn extend(&mut self, other: Damage) {
+        match other {
+            Damage::None => {}
+            Damage::Rect(rect) => self.add(rect),
+            Damage::Full => *self = Damage::Full,
+        }
+    }
+
+    /// Damage the whole window.
+    pub fn invalidate(&mut self) {
+        *self = Damage::Full;
+    }
+
+    /// Check if the whole window has been damaged.
+    pub fn is_full(&self) -> bool {
+        matches!(self, Damage::Full)
+    }
+
+    /// Get the damaged rectangle inside `bounds`, `bounds` is returned if the whole window has
+    /// been damaged, and `None` if nothing has.
+    pub fn rect(&self, bounds: Rect) -> Option<Rect> {
+        match self {
+            Damage::None => None,
+            Damage::Rect(rect) => rect.try_intersection(bounds),
+            Damage::Full => Some(bounds),
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use std::{
+    f32::consts::TAU,
+    hash::{Hash, Hasher},
+};
+
+use crate::layout::Point;
+
+use super::Color;
+
+/// A color stop of a gradient.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GradientStop {
+    /// The offset of the stop, in the range `0.0..=1.0`.
+    pub offset: f32,
+
+    /// The color of the stop.
+    pub color: Color,
+}
+
+impl GradientStop {
+    /// Create a new gradient stop.
+    pub fn new(offset: f32, color: Color) -> Self {
+        Self { offset, color }
+    }
+}
+
+impl From<(f32, Color)> for GradientStop {
+    fn from((offset, color): (f32, Color)) -> Self {
+        Self::new(offset, color)
+    }
+}
+
+impl Hash for GradientStop {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.offset.to_bits().hash(state);
+        self.color.hash(state);
+    }
+}
+
+/// A gradient that sweeps around a center point, also known as an angular gradient.
+///
+/// An offset of `0.0` starts at `angle`, and the stops are swept clockwise for a full turn.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConicGradient {
+    /// The center of the gradient.
+    pub center: Point,
+
+    /// The angle the gradient starts at, in radians.
+    pub angle: f32,
+
+    /// The color stops of the gradient, sorted by offset.
+    pub stops: Vec<GradientStop>,
+}
+
+impl ConicGradient {
+    /// Create a new conic gradient starting at an angle of `0.0`.
+    pub fn new(center: Point, stops: impl IntoIterator<Item = impl Into<GradientStop>>) -> Self {
+        Self {
+            center,
+            angle: 0.0,
+            stops: stops.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// Set the angle the gradient starts at, in radians.
+    pub fn angle(mut self, angle: f32) -> Self {
+        self.angle = angle;
+        self
+    }
+
+    /// Get the color of the gradient at a `point`.
+    pub fn color_at(&self, point: Point) -> Color {
+        let delta = point - self.center;
+        let angle = f32::atan2(delta.y, delta.x) - self.angle;
+
+        self.color_at_offset(angle.rem_euclid(TAU) / TAU)
+    }
+
+    /// Get the color of the gradient at an `offset` in the range `0.0..=1.0`.
+    pub fn color_at_offset(&self, offset: f32) -> Color {
+        let (Some(first), Some(last)) = (self.stops.first(), self.stops.last()) else {
+            return Color::TRANSPARENT;
+        };
+
+        if offset <= first.offset {
+            return first.color;
+        }
+
+        for pair in self.stops.windows(2) {
+            let [a, b] = [pair[0], pair[1]];
+
+            if offset <= b.offset {
+                let t = (offset - a.offset) / (b.offset - a.offset).max(f32::EPSILON);
+                return a.color.mix_rgb(b.color, t);
+            }
+        }
+
+        last.color
+    }
+}
+
+impl Hash for ConicGradient {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.center.hash(state);
+        self.angle.to_bits().hash(state);
+        self.stops.hash(state);
+    }
+}
@@ -0,0 +1,256 @@
+use std::hash::{Hash, Hasher};
+
+use crate::layout::{Affine, Point, Rect, Vector};
+
+use super::Color;
+
+/// A vertex of a [`Mesh`].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Vertex {
+    /// The position of the vertex.
+    pub position: Point,
+
+    /// The color of the vertex.
+    pub color: Color,
+}
+
+impl Vertex {
+    /// Create a new vertex.
+    pub fn new(position: Point, color: Color) -> Self {
+        Self { position, color }
+    }
+}
+
+impl Hash for Vertex {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.position.hash(state);
+        self.color.hash(state);
+    }
+}
+
+/// An instance of a quad, see [`Mesh::quads`].
+///
+/// The quad is the unit square from `(0, 0)` to `(1, 1)`, placed by the `transform`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct QuadInstance {
+    /// The transformation of the unit square.
+    pub transform: Affine,
+
+    /// The color of the quad.
+    pub color: Color,
+}
+
+impl QuadInstance {
+    /// Create a new quad instance.
+    pub fn new(transform: Affine, color: Color) -> Self {
+        Self { transform, color }
+    }
+
+    /// Create a quad instance covering `rect`.
+    pub fn rect(rect: Rect, color: Color) -> Self {
+        let transform = Affine::translate(rect.min.to_vector())
+            * Affine::scale(Vector::new(rect.width(), rect.height()));
+
+        Self::new(transform, color)
+    }
+
+    /// Get the corners of the quad.
+    pub fn corners(&self) -> [Point; 4] {
+        [
+            self.transform * Point::new(0.0, 0.0),
+            self.transform * Point::new(1.0, 0.0),
+            self.transform * Point::new(1.0, 1.0),
+            self.transform * Point::new(0.0, 1.0),
+        ]
+    }
+}
+
+/// A mesh of triangles.
+///
+/// The colors of the vertices are interpolated linearly in the sRGB color space over each
+/// triangle, so a triangle with three different colors fades between them.
+#[derive(Clone, Debug, Default, PartialEq, Hash)]
+pub struct Mesh {
+    /// The vertices of the mesh.
+    pub vertices: Vec<Vertex>,
+
+    /// The indices of the mesh, every three indices form a triangle.
+    pub indices: Vec<u32>,
+}
+
+impl Mesh {
+    /// Create a new empty mesh.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a mesh with a single triangle.
+    pub fn triangle(a: Vertex, b: Vertex, c: Vertex) -> Self {
+        let mut mesh = Self::new();
+        mesh.push_triangle(a, b, c);
+        mesh
+    }
+
+    /// Create a mesh with many instances of a quad.
+    ///
+    /// All the quads are drawn with a single draw call, which is much faster than drawing
+    /// each of them as a separate rectangle, eg. for particles or a grid of icons.
+    pub fn quads(instances: &[QuadInstance]) -> Self {
+        let mut mesh = Self {
+            vertices: Vec::with_capacity(instances.len() * 4),
+            indices: Vec::with_capacity(instances.len() * 6),
+        };
+
+        for instance in instances {
+            mesh.push_quad(instance);
+        }
+
+        mesh
+    }
+
+    /// Add a quad to the mesh.
+    pub fn push_quad(&mut self, instance: &QuadInstance) {
+        let index = self.vertices.len() as u32;
+
+        let corners = instance.corners();
+        let vertices = corners.map(|corner| Vertex::new(corner, instance.color));
+
+        self.vertices.extend(vertices);
+        self.indices.extend([index, index + 1, index + 2]);
+        self.indices.extend([index, index + 2, index + 3]);
+    }
+
+    /// Add a triangle to the mesh.
+    pub fn push_triangle(&mut self, a: Vertex, b: Vertex, c: Vertex) {
+        let index = self.vertices.len() as u32;
+
+        self.vertices.extend([a, b, c]);
+        self.indices.extend([index, index + 1, index + 2]);
+    }
+
+    /// Get an iterator over the triangles of the mesh.
+    ///
+    /// Triangles with indices outside of `vertices` are skipped.
+    pub fn triangles(&self) -> impl DoubleEndedIterator<Item = [Vertex; 3]> + '_ {
+        self.indices.chunks_exact(3).filter_map(|triangle| {
+            Some([
+                *self.vertices.get(triangle[0] as usize)?,
+                *self.vertices.get(triangle[1] as usize)?,
+                *self.vertices.get(triangle[2] as usize)?,
+            ])
+        })
+    }
+
+    /// Get the bounds of the mesh.
+    pub fn bounds(&self) -> Rect {
+        let mut vertices = self.vertices.iter();
+
+        let Some(first) = vertices.next() else {
+            return Rect::ZERO;
+        };
+
+        let (min, max) = vertices.fold((first.position, first.position), |(min, max), v| {
+            (min.min(v.position), max.max(v.position))
+        });
+
+        Rect::new(min, max)
+    }
+
+    /// Check if the mesh contains a `point`.
+    pub fn contains(&self, point: Point) -> bool {
+        self.triangles()
+            .any(|triangle| barycentric(triangle, point).is_some())
+    }
+
+    /// Get the color of the mesh at a `point`.
+    ///
+    /// If triangles overlap, the color of the last one is returned, as it is drawn on top.
+    pub fn sample(&self, point: Point) -> Option<Color> {
+        let triangle = self.triangles().rev().find_map(|triangle| {
+            let weights = barycentric(triangle, point)?;
+            Some((triangle, weights))
+        });
+
+        let ([a, b, c], [u, v, w]) = triangle?;
+        Some(a.color * u + b.color * v + c.color * w)
+    }
+}
+
+// the barycentric coordinates of `point` in `triangle`, if `point` is inside it
+fn barycentric([a, b, c]: [Vertex; 3], point: Point) -> Option<[f32; 3]> {
+    let ab = b.position - a.position;
+    let ac = c.position - a.position;
+    let ap = point - a.position;
+
+    let det = ab.x * ac.y - ab.y * ac.x;
+
+    if det.abs() <= f32::EPSILON {
+        return None;
+    }
+
+    let v = (ap.x * ac.y - ap.y * ac.x) / det;
+    let w = (ab.x * ap.y - ab.y * ap.x) / det;
+    let u = 1.0 - v - w;
+
+    let inside = u >= 0.0 && v >= 0.0 && w >= 0.0;
+    inside.then_some([u, v, w])
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::layout::Size;
+
+    use super::*;
+
+    #[test]
+    fn sample_triangle_center() {
+        let mesh = Mesh::triangle(
+            Vertex::new(Point::new(0.0, 0.0), Color::RED),
+            Vertex::new(Point::new(30.0, 0.0), Color::GREEN),
+            Vertex::new(Point::new(0.0, 30.0), Color::BLUE),
+        );
+
+        let center = mesh.sample(Point::new(10.0, 10.0)).unwrap();
+        let average = (Color::RED + Color::GREEN + Color::BLUE) * (1.0 / 3.0);
+
+        assert!((center.r - average.r).abs() < 1e-4);
+        assert!((center.g - average.g).abs() < 1e-4);
+        assert!((center.b - average.b).abs() < 1e-4);
+        assert!((center.a - 1.0).abs() < 1e-4);
+
+        assert_eq!(mesh.sample(Point::new(25.0, 25.0)), None);
+    }
+
+    #[test]
+    fn quads_cover_their_instances() {
+        let mesh = Mesh::quads(&[
+            QuadInstance::rect(Rect::min_size(Point::ZERO, Size::all(10.0)), Color::RED),
+            QuadInstance::rect(
+                Rect::min_size(Point::all(20.0), Size::all(10.0)),
+                Color::BLUE,
+            ),
+        ]);
+
+        assert_eq!(mesh.vertices.len(), 8);
+        assert_eq!(mesh.indices.len(), 12);
+
+        assert_eq!(mesh.sample(Point::all(5.0)), Some(Color::RED));
+        assert_eq!(mesh.sample(Point::all(25.0)), Some(Color::BLUE));
+        assert_eq!(mesh.sample(Point::all(15.0)), None);
+    }
+
+    #[test]
+    fn out_of_range_indices_are_skipped() {
+        let mut mesh = Mesh::triangle(
+            Vertex::new(Point::new(0.0, 0.0), Color::RED),
+            Vertex::new(Point::new(30.0, 0.0), Color::RED),
+            Vertex::new(Point::new(0.0, 30.0), Color::RED),
+        );
+
+        mesh.indices.extend([0, 2, 3]);
+
+        assert_eq!(mesh.triangles().count(), 1);
+        assert_eq!(mesh.sample(Point::new(10.0, 10.0)), Some(Color::RED));
+        assert!(!mesh.contains(Point::new(25.0, 25.0)));
+    }
+}
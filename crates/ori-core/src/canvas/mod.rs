@@ -3,11 +3,23 @@
 mod border;
 mod canvas;
 mod color;
+mod color_matrix;
+mod color_parse;
 mod curve;
+mod damage;
+mod gradient;
+mod mesh;
+mod shadow;
 mod stroke;
 
 pub use border::*;
 pub use canvas::*;
 pub use color::*;
+pub use color_matrix::*;
+pub use color_parse::*;
 pub use curve::*;
+pub use damage::*;
+pub use gradient::*;
+pub use mesh::*;
+pub use shadow::*;
 pub use stroke::*;
@@ -0,0 +1,44 @@
+use crate::layout::Vector;
+
+use super::Color;
+
+/// A shadow cast by a box, eg. `box-shadow: 0 1px 2px black`.
+///
+/// Shadows, and lists of them, can be read from styles with a `Style<Vec<Shadow>>`, but none of
+/// the built-in views draw them yet, the canvas has no blur to draw them with.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Shadow {
+    /// The offset of the shadow.
+    pub offset: Vector,
+    /// The blur radius of the shadow.
+    pub blur: f32,
+    /// The distance the shadow is expanded by.
+    pub spread: f32,
+    /// The color of the shadow.
+    pub color: Color,
+}
+
+impl Shadow {
+    /// Create a new [`Shadow`].
+    pub const fn new(offset: Vector, blur: f32, color: Color) -> Self {
+        Self {
+            offset,
+            blur,
+            spread: 0.0,
+            color,
+        }
+    }
+
+    /// Set the spread of the shadow.
+    pub const fn spread(mut self, spread: f32) -> Self {
+        self.spread = spread;
+        self
+    }
+}
+
+impl From<Shadow> for Vec<Shadow> {
+    fn from(shadow: Shadow) -> Self {
+        vec![shadow]
+    }
+}
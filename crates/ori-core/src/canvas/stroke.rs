@@ -34,7 +34,7 @@ pub enum StrokeJoin {
 }
 
 /// Properties of a stroke.
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct Stroke {
     /// The width of the stroke.
     pub width: f32,
@@ -47,6 +47,21 @@ pub struct Stroke {
 
     /// The join of the stroke.
     pub join: StrokeJoin,
+
+    /// The dash pattern of the stroke, alternating on and off lengths, `None` draws a solid
+    /// stroke.
+    ///
+    /// The pattern restarts at the start of every contour. Patterns with an odd number of
+    /// lengths are repeated twice, like in svg, and patterns with negative lengths or that sum
+    /// to zero are ignored. Dotted lines can be drawn with short dashes and
+    /// [`StrokeCap::Round`].
+    pub dash: Option<Vec<f32>>,
+
+    /// How far into the dash pattern the stroke starts.
+    ///
+    /// Advancing it every frame, eg. by `speed * dt` on
+    /// [`Event::Animate`](crate::event::Event::Animate), makes the dashes march along the curve.
+    pub dash_offset: f32,
 }
 
 impl Default for Stroke {
@@ -56,6 +71,8 @@ impl Default for Stroke {
             miter: 4.0,
             cap: StrokeCap::Butt,
             join: StrokeJoin::Miter,
+            dash: None,
+            dash_offset: 0.0,
         }
     }
 }
@@ -75,6 +92,14 @@ impl Hash for Stroke {
         self.miter.to_bits().hash(state);
         self.cap.hash(state);
         self.join.hash(state);
+
+        if let Some(ref dash) = self.dash {
+            for length in dash {
+                length.to_bits().hash(state);
+            }
+        }
+
+        self.dash_offset.to_bits().hash(state);
     }
 }
 
@@ -242,7 +267,7 @@ impl Curve {
         [p01, p012, center, p123, p23]
     }
 
-    fn stroke_line_cap(&mut self, p: Point, n: Vector, t: Vector, stroke: Stroke) {
+    fn stroke_line_cap(&mut self, p: Point, n: Vector, t: Vector, stroke: &Stroke) {
         let r = stroke.width / 2.0;
 
         match stroke.cap {
@@ -351,7 +376,7 @@ impl Curve {
         p1: Point,
         n0: Option<Vector>,
         r: f32,
-        stroke: Stroke,
+        stroke: &Stroke,
         first: &mut Option<(Point, Vector)>,
     ) -> (Point, Vector) {
         let n1 = line_normal(p0, p1);
@@ -374,7 +399,7 @@ impl Curve {
         (p1, n1)
     }
 
-    pub(super) fn stroke_impl(&mut self, curve: &Curve, stroke: Stroke) {
+    pub(super) fn stroke_impl(&mut self, curve: &Curve, stroke: &Stroke) {
         if stroke.width <= 0.0 {
             return;
         }
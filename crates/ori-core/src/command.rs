@@ -1,18 +1,21 @@
 //! A channel for sending commands to the user interface.
 
 use std::{
-    any::{Any, TypeId},
-    cell::UnsafeCell,
-    fmt::Debug,
+    any::{self, Any, TypeId},
+    cell::{Cell, UnsafeCell},
+    error::Error,
+    fmt::{self, Debug, Display},
     future::Future,
     mem::ManuallyDrop,
     pin::Pin,
-    sync::Arc,
-    task::{Context, RawWaker, RawWakerVTable, Waker},
+    sync::{Arc, Mutex, MutexGuard},
+    task::{Context, Poll, RawWaker, RawWakerVTable, Waker},
 };
 
 use crossbeam_channel::{Receiver, Sender};
 
+use crate::view::ViewId;
+
 /// A waker for the event loop, triggered when a command is sent.
 #[derive(Clone)]
 pub struct CommandWaker(Arc<dyn Fn() + Send + Sync>);
@@ -46,6 +49,8 @@ pub struct Command {
     type_id: TypeId,
     data: Box<dyn Any + Send>,
     name: &'static str,
+    target: Option<ViewId>,
+    reached: Cell<bool>,
 }
 
 impl Command {
@@ -55,12 +60,41 @@ impl Command {
             type_id: TypeId::of::<T>(),
             data: Box::new(command),
             name: std::any::type_name::<T>(),
+            target: None,
+            reached: Cell::new(false),
+        }
+    }
+
+    /// Create a new command targeted at a single view.
+    ///
+    /// See [`Command::target`] for more information.
+    pub fn targeted<T: Any + Send>(target: ViewId, command: T) -> Self {
+        Self {
+            target: Some(target),
+            ..Self::new(command)
         }
     }
 
+    /// Get the view the command is targeted at, if any.
+    ///
+    /// A targeted command is only delivered to the view with the [`ViewId`] `target`, and
+    /// is hidden from every other view and delegate, ie. [`Command::is`] returns `false`.
+    pub fn target(&self) -> Option<ViewId> {
+        self.target
+    }
+
+    pub(crate) fn is_reached(&self) -> bool {
+        self.reached.get()
+    }
+
+    pub(crate) fn set_reached(&self, reached: bool) {
+        self.reached.set(reached);
+    }
+
     /// Check whether the command is of type `T`.
     pub fn is<T: Any>(&self) -> bool {
-        self.type_id == TypeId::of::<T>()
+        let is_visible = self.target.is_none() || self.reached.get();
+        is_visible && self.type_id == TypeId::of::<T>()
     }
 
     /// Try to downcast the command to `T`.
@@ -147,6 +181,210 @@ impl CommandProxy {
             proxy.cmd(future.await);
         });
     }
+
+    /// Send a request, returning a future that resolves with the response.
+    ///
+    /// The request is sent as a [`CommandRequest`], and is answered by whoever handles it with
+    /// [`CommandRequest::respond`], usually a delegate of the application. The future resolves
+    /// with [`Canceled`] if the request is dropped without being answered.
+    pub fn cmd_request<Req, Resp>(&self, request: Req) -> CommandResponse<Resp>
+    where
+        Req: Any + Send,
+        Resp: Any + Send,
+    {
+        let (request, response) = CommandRequest::new(request);
+        self.cmd(request);
+        response
+    }
+}
+
+struct Reply<T> {
+    value: Option<T>,
+    responded: bool,
+    resolved: bool,
+    canceled: bool,
+    waker: Option<Waker>,
+}
+
+/// A command that expects a response, see [`CommandProxy::cmd_request`].
+///
+/// The request is read with [`CommandRequest::request`], and answered with
+/// [`CommandRequest::respond`].
+pub struct CommandRequest<Req, Resp> {
+    request: Req,
+    reply: Arc<Mutex<Reply<Resp>>>,
+}
+
+impl<Req, Resp> CommandRequest<Req, Resp> {
+    /// Create a new request, and the future that resolves with its response.
+    pub fn new(request: Req) -> (Self, CommandResponse<Resp>) {
+        let reply = Arc::new(Mutex::new(Reply {
+            value: None,
+            responded: false,
+            resolved: false,
+            canceled: false,
+            waker: None,
+        }));
+
+        let response = CommandResponse {
+            reply: reply.clone(),
+        };
+
+        (Self { request, reply }, response)
+    }
+
+    /// Get the request.
+    pub fn request(&self) -> &Req {
+        &self.request
+    }
+
+    /// Check whether the request has been responded to.
+    pub fn is_responded(&self) -> bool {
+        self.lock().responded
+    }
+
+    /// Respond to the request, resolving the [`CommandResponse`] of the sender.
+    ///
+    /// Only the first response is used, later ones are ignored.
+    pub fn respond(&self, response: Resp) {
+        let mut reply = self.lock();
+
+        if reply.responded {
+            tracing::warn!("request `{}` responded to twice", any::type_name::<Req>());
+            return;
+        }
+
+        reply.responded = true;
+        reply.resolve(response);
+    }
+
+    /// Take a [`CommandResponder`], to respond to the request later, eg. when a future
+    /// completes.
+    ///
+    /// This marks the request as responded to, so other handlers leave it alone.
+    pub fn responder(&self) -> CommandResponder<Resp> {
+        self.lock().responded = true;
+
+        CommandResponder {
+            reply: self.reply.clone(),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Reply<Resp>> {
+        self.reply.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+impl<Req, Resp> Drop for CommandRequest<Req, Resp> {
+    fn drop(&mut self) {
+        let mut reply = self.lock();
+
+        if !reply.responded {
+            reply.cancel();
+        }
+    }
+}
+
+impl<T> Reply<T> {
+    fn resolve(&mut self, value: T) {
+        self.value = Some(value);
+        self.resolved = true;
+        self.wake();
+    }
+
+    fn cancel(&mut self) {
+        self.canceled = true;
+        self.wake();
+    }
+
+    fn wake(&mut self) {
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+/// Responds to a [`CommandRequest`] after it was handled, see [`CommandRequest::responder`].
+pub struct CommandResponder<T> {
+    reply: Arc<Mutex<Reply<T>>>,
+}
+
+impl<T> CommandResponder<T> {
+    /// Respond to the request, resolving the [`CommandResponse`] of the sender.
+    pub fn respond(self, response: T) {
+        let mut reply = self.reply.lock().unwrap_or_else(|err| err.into_inner());
+        reply.resolve(response);
+    }
+}
+
+impl<T> Drop for CommandResponder<T> {
+    fn drop(&mut self) {
+        let mut reply = self.reply.lock().unwrap_or_else(|err| err.into_inner());
+
+        if !reply.resolved {
+            reply.cancel();
+        }
+    }
+}
+
+impl<T> Debug for CommandResponder<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CommandResponder").finish()
+    }
+}
+
+impl<Req: Debug, Resp> Debug for CommandRequest<Req, Resp> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("CommandRequest")
+            .field(&self.request)
+            .finish()
+    }
+}
+
+/// The error a [`CommandResponse`] resolves with when its request was dropped without being
+/// answered, eg. when no handler took it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Canceled;
+
+impl Display for Canceled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("request was dropped without a response")
+    }
+}
+
+impl Error for Canceled {}
+
+/// A future that resolves with the response to a [`CommandRequest`].
+///
+/// Resolves with [`Canceled`] if the request, or its [`CommandResponder`], is dropped without
+/// responding.
+pub struct CommandResponse<T> {
+    reply: Arc<Mutex<Reply<T>>>,
+}
+
+impl<T> Future for CommandResponse<T> {
+    type Output = Result<T, Canceled>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut reply = self.reply.lock().unwrap_or_else(|err| err.into_inner());
+
+        if let Some(value) = reply.value.take() {
+            return Poll::Ready(Ok(value));
+        }
+
+        if reply.canceled {
+            return Poll::Ready(Err(Canceled));
+        }
+
+        reply.waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+impl<T> Debug for CommandResponse<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CommandResponse").finish()
+    }
 }
 
 impl Debug for CommandProxy {
@@ -5,9 +5,12 @@ use std::{
 
 use crate::{
     clipboard::Clipboard,
-    command::{Command, CommandProxy},
+    command::{Command, CommandProxy, CommandRequest, CommandResponse},
+    dialog::{FileDialog, FileDialogKind, FileDialogResponse},
     layout::Size,
     text::{Fonts, Paragraph, TextLayoutLine},
+    view::ViewId,
+    window::{Announcement, Politeness},
 };
 
 use super::Contexts;
@@ -50,10 +53,33 @@ impl<'a> BaseCx<'a> {
     }
 
     /// Emit a command.
+    ///
+    /// The command is sent to the delegates and, if not handled, to every window.
     pub fn cmd<T: Any + Send>(&mut self, command: T) {
         self.proxy.cmd_silent(Command::new(command));
     }
 
+    /// Emit a command targeted at a single view.
+    ///
+    /// The command is only delivered to the view with the id `view`, which can be set with
+    /// [`with_id`](crate::views::with_id).
+    pub fn send_to<T: Any + Send>(&mut self, view: ViewId, command: T) {
+        self.proxy.cmd_silent(Command::targeted(view, command));
+    }
+
+    /// Emit an [`Announcement`] of a message for screen readers, eg. `"Saved"` or
+    /// `"3 items left"`.
+    ///
+    /// This only emits the command, which is read by nothing but the delegates of the
+    /// application, see [`Announcement`]. See [`live_region`](crate::views::live_region) for
+    /// emitting it when a message changes.
+    pub fn announce(&mut self, message: impl Into<String>, politeness: Politeness) {
+        self.cmd(Announcement {
+            message: message.into(),
+            politeness,
+        });
+    }
+
     /// Spawn a future.
     pub fn spawn_async(&mut self, future: impl Future<Output = ()> + Send + 'static) {
         self.proxy.spawn_async(future);
@@ -64,6 +90,42 @@ impl<'a> BaseCx<'a> {
         self.proxy.cmd_async(future);
     }
 
+    /// Send a request, returning a future that resolves with the response.
+    ///
+    /// See [`CommandProxy::cmd_request`] for more information.
+    pub fn cmd_request<Req, Resp>(&mut self, request: Req) -> CommandResponse<Resp>
+    where
+        Req: Any + Send,
+        Resp: Any + Send,
+    {
+        let (request, response) = CommandRequest::new(request);
+        self.cmd(request);
+        response
+    }
+
+    /// Open a native dialog picking a file to open, returning a future that resolves with the
+    /// picked path, or `None` if the dialog was cancelled.
+    ///
+    /// The dialog is answered by the shell when its `file-dialog` feature is enabled, otherwise
+    /// the future resolves with `None`, see [`FileDialog`].
+    pub fn open_file_dialog(&mut self, dialog: FileDialog) -> FileDialogResponse {
+        FileDialogResponse::new(self.cmd_request(FileDialog {
+            kind: FileDialogKind::Open,
+            ..dialog
+        }))
+    }
+
+    /// Open a native dialog picking a file to save to, returning a future that resolves with
+    /// the picked path, or `None` if the dialog was cancelled.
+    ///
+    /// See [`BaseCx::open_file_dialog`] for more information.
+    pub fn save_file_dialog(&mut self, dialog: FileDialog) -> FileDialogResponse {
+        FileDialogResponse::new(self.cmd_request(FileDialog {
+            kind: FileDialogKind::Save,
+            ..dialog
+        }))
+    }
+
     /// Get a reference to the [`Contexts`].
     pub fn contexts(&self) -> &Contexts {
         self.contexts
@@ -90,6 +152,9 @@ impl<'a> BaseCx<'a> {
     }
 
     /// Get a context.
+    ///
+    /// Contexts can be provided by the application, eg. with `AppBuilder::context`, which is
+    /// how views read shared services like the configuration of the application.
     pub fn get_context<T: Any>(&self) -> Option<&T> {
         self.contexts.get::<T>()
     }
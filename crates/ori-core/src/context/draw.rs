@@ -4,13 +4,17 @@ use std::{
 };
 
 use crate::{
-    canvas::{BorderRadius, BorderWidth, Canvas, Curve, FillRule, Mask, Paint, Stroke},
+    canvas::{
+        BlendMode, BorderColor, BorderEdge, BorderPaint, BorderRadius, BorderWidth, Canvas, Color,
+        ColorMatrix, Curve, FillRule, Mask, Mesh, Paint, QuadInstance, Stroke,
+    },
+    image::ImageHandle,
     layout::{Affine, Point, Rect, Size, Vector},
     text::{FontAttributes, Paragraph, TextAlign, TextWrap},
     view::ViewState,
 };
 
-use super::BaseCx;
+use super::{BaseCx, BuildCx, LayoutCx};
 
 /// A context for drawing the view tree.
 pub struct DrawCx<'a, 'b> {
@@ -19,6 +23,7 @@ pub struct DrawCx<'a, 'b> {
     pub(crate) transform: Affine,
     pub(crate) canvas: &'a mut Canvas,
     pub(crate) visible: Rect,
+    pub(crate) snap: bool,
 }
 
 impl<'b> Deref for DrawCx<'_, 'b> {
@@ -50,6 +55,7 @@ impl<'a, 'b> DrawCx<'a, 'b> {
             transform: Affine::IDENTITY,
             canvas,
             visible: Self::EVERYTHING,
+            snap: false,
         }
     }
 
@@ -61,9 +67,20 @@ impl<'a, 'b> DrawCx<'a, 'b> {
             transform: self.transform,
             canvas: self.canvas,
             visible: self.visible,
+            snap: self.snap,
         }
     }
 
+    /// Get a build context.
+    pub fn as_build_cx(&mut self) -> BuildCx<'_, 'b> {
+        BuildCx::new(self.base, self.view_state)
+    }
+
+    /// Get a layout context.
+    pub fn as_layout_cx(&mut self) -> LayoutCx<'_, 'b> {
+        LayoutCx::new(self.base, self.view_state)
+    }
+
     /// Check if a rect is visible.
     pub fn is_visible(&self, rect: Rect) -> bool {
         self.visible.intersects(rect)
@@ -84,6 +101,44 @@ impl<'a, 'b> DrawCx<'a, 'b> {
         Rect::min_size(Point::ZERO, self.size())
     }
 
+    /// Get whether drawing is snapped to physical pixels, see [`DrawCx::snapped`].
+    pub fn is_snapping(&self) -> bool {
+        self.snap
+    }
+
+    /// Draw with quads and text snapped to the physical pixel grid, or not.
+    ///
+    /// Edges that fall between physical pixels are blurred by anti-aliasing, snapping keeps
+    /// thin lines, like 1 pixel borders, crisp.
+    pub fn snapped<T>(&mut self, snap: bool, f: impl FnOnce(&mut DrawCx<'_, 'b>) -> T) -> T {
+        let mut cx = self.child();
+        cx.snap = snap;
+        f(&mut cx)
+    }
+
+    /// Snap a point in local space to the nearest physical pixel.
+    ///
+    /// Points are only snapped when the transform of the view doesn't rotate or skew, as the
+    /// pixel grid isn't aligned with the local space otherwise.
+    pub fn snap_point(&self, point: Point) -> Point {
+        let matrix = self.transform.matrix;
+
+        if matrix.x.y != 0.0 || matrix.y.x != 0.0 {
+            return point;
+        }
+
+        let scale = self.window().render_scale();
+        let physical = (self.transform * point) * scale;
+        let snapped = physical.round() / scale;
+
+        self.transform.inverse() * snapped
+    }
+
+    /// Snap the corners of a rectangle in local space to the nearest physical pixels.
+    pub fn snap_rect(&self, rect: Rect) -> Rect {
+        Rect::new(self.snap_point(rect.min), self.snap_point(rect.max))
+    }
+
     /// Get the canvas.
     pub fn canvas(&mut self) -> &mut Canvas {
         self.canvas
@@ -127,6 +182,26 @@ impl<'a, 'b> DrawCx<'a, 'b> {
         self.canvas.stroke(curve, stroke, paint.into());
     }
 
+    /// Draw a mesh.
+    pub fn mesh(&mut self, mesh: Mesh) {
+        if !self.is_visible(mesh.bounds()) {
+            return;
+        }
+
+        self.canvas.mesh(mesh);
+    }
+
+    /// Draw many instances of a quad with a single draw call, see [`Mesh::quads`].
+    pub fn draw_quads_instanced(&mut self, instances: &[QuadInstance]) {
+        let mesh = Mesh::quads(instances);
+
+        if !self.is_visible(mesh.bounds()) {
+            return;
+        }
+
+        self.canvas.mesh(mesh);
+    }
+
     /// Draw some text.
     pub fn text(&mut self, text: impl Display, rect: Rect, font: FontAttributes) {
         let mut paragraph = Paragraph::new(1.2, TextAlign::Center, TextWrap::Word);
@@ -136,6 +211,11 @@ impl<'a, 'b> DrawCx<'a, 'b> {
 
     /// Draw a paragraph.
     pub fn paragraph(&mut self, paragraph: &Paragraph, rect: Rect) {
+        let rect = match self.snap {
+            true => rect + (self.snap_point(rect.min) - rect.min),
+            false => rect,
+        };
+
         let lines = self.fonts().layout(paragraph, rect.width());
 
         let mut bounds: Option<Rect> = None;
@@ -157,27 +237,87 @@ impl<'a, 'b> DrawCx<'a, 'b> {
     }
 
     /// Draw a rectangle with rounded corners and a border.
+    ///
+    /// The border is filled with any [`Paint`], or a color per edge, see [`BorderPaint`]. The
+    /// width of each edge can differ too, see [`BorderWidth`].
     pub fn quad(
         &mut self,
         rect: Rect,
         paint: impl Into<Paint>,
         border_radius: impl Into<BorderRadius>,
         border_width: impl Into<BorderWidth>,
-        border_paint: impl Into<Paint>,
+        border_paint: impl Into<BorderPaint>,
     ) {
         let radius = border_radius.into();
         let width = border_width.into();
-        let rect = rect.round();
+
+        let rect = match self.snap {
+            true => self.snap_rect(rect),
+            false => rect.round(),
+        };
 
         let mut curve = Curve::new();
         curve.push_rect_with_radius(rect, radius);
 
         self.fill(curve, FillRule::NonZero, paint);
 
+        // borders of a single paint are filled at once, to avoid seams between the edges
+        let paint = match border_paint.into() {
+            BorderPaint::Uniform(paint) => paint,
+            BorderPaint::Edges(color) => match color.uniform() {
+                Some(color) => Paint::from(color),
+                None => return self.fill_border_edges(rect, radius, width, color),
+            },
+        };
+
         let mut curve = Curve::new();
         curve.push_rect_with_borders(rect, radius, width);
 
-        self.fill(curve, FillRule::NonZero, border_paint);
+        self.fill(curve, FillRule::NonZero, paint);
+    }
+
+    fn fill_border_edges(
+        &mut self,
+        rect: Rect,
+        radius: BorderRadius,
+        width: BorderWidth,
+        color: BorderColor,
+    ) {
+        let colors: [Color; 4] = color.into();
+
+        for (edge, color) in BorderEdge::ALL.into_iter().zip(colors) {
+            let mut curve = Curve::new();
+            curve.push_rect_border_edge(rect, radius, width, edge);
+
+            if !curve.is_empty() {
+                self.fill(curve, FillRule::NonZero, color);
+            }
+        }
+    }
+
+    /// Draw a filled rectangle with rounded corners, and a stroke inside its edges.
+    ///
+    /// This is drawn analytically by the backend, see [`Canvas::draw_rounded_rect`], which
+    /// makes it a crisp building block for cards, badges and the like. Use [`DrawCx::quad`]
+    /// for borders with a different width or color on each edge.
+    pub fn rounded_rect(
+        &mut self,
+        rect: Rect,
+        radius: impl Into<BorderRadius>,
+        fill: impl Into<Paint>,
+        stroke_width: f32,
+        stroke: Color,
+    ) {
+        let rect = match self.snap {
+            true => self.snap_rect(rect),
+            false => rect.round(),
+        };
+
+        if !self.is_visible(rect) {
+            return;
+        }
+
+        (self.canvas).draw_rounded_rect(rect, radius, fill, stroke_width, stroke);
     }
 
     /// Draw a canvas.
@@ -185,6 +325,49 @@ impl<'a, 'b> DrawCx<'a, 'b> {
         self.canvas.draw_canvas(canvas);
     }
 
+    /// Record the drawing done by `f` into a new canvas, without drawing it.
+    ///
+    /// Everything is considered visible while recording, the returned canvas can later be
+    /// replayed with [`DrawCx::draw_canvas`].
+    pub fn record(&mut self, f: impl FnOnce(&mut DrawCx<'_, 'b>)) -> Canvas {
+        let mut canvas = Canvas::new();
+
+        let mut cx = DrawCx {
+            base: self.base,
+            view_state: self.view_state,
+            transform: self.transform,
+            canvas: &mut canvas,
+            visible: Self::EVERYTHING,
+            snap: self.snap,
+        };
+
+        f(&mut cx);
+
+        canvas
+    }
+
+    /// Draw to an off-screen texture of `size`, see [`Canvas::render_to_texture`].
+    ///
+    /// The drawing done by `f` starts at the top left corner of the texture.
+    pub fn render_to_texture(
+        &mut self,
+        size: Size,
+        f: impl FnOnce(&mut DrawCx<'_, 'b>),
+    ) -> ImageHandle {
+        self.canvas.render_to_texture(size, |canvas| {
+            let mut cx = DrawCx {
+                base: self.base,
+                view_state: self.view_state,
+                transform: Affine::IDENTITY,
+                canvas,
+                visible: Self::EVERYTHING,
+                snap: self.snap,
+            };
+
+            f(&mut cx);
+        })
+    }
+
     /// Draw an overlay, at `index`.
     pub fn overlay<T>(&mut self, index: i32, f: impl FnOnce(&mut DrawCx<'_, 'b>) -> T) -> T {
         self.canvas.overlay(index, |canvas| {
@@ -194,6 +377,64 @@ impl<'a, 'b> DrawCx<'a, 'b> {
                 transform: Affine::IDENTITY,
                 canvas,
                 visible: Self::EVERYTHING,
+                snap: self.snap,
+            };
+
+            f(&mut cx)
+        })
+    }
+
+    /// Draw a popup at `rect`, in the coordinates of the window, see [`Canvas::popup`].
+    ///
+    /// `f` draws relative to the top-left corner of `rect`. When the platform supports it the
+    /// popup is shown in a window of its own, that can extend beyond the window, and pointer
+    /// input on it is delivered to the views of the window, see
+    /// [`Window::popup_windows`](crate::window::Window::popup_windows). Popups that `grab` the
+    /// pointer see presses anywhere outside of them, so menus can close. Keep the rect inside
+    /// [`DrawCx::popup_bounds`].
+    pub fn popup<T>(
+        &mut self,
+        rect: Rect,
+        grab: bool,
+        f: impl FnOnce(&mut DrawCx<'_, 'b>) -> T,
+    ) -> T {
+        self.canvas.popup(rect, grab, |canvas| {
+            let mut cx = DrawCx {
+                base: self.base,
+                view_state: self.view_state,
+                transform: Affine::IDENTITY,
+                canvas,
+                visible: Self::EVERYTHING,
+                snap: self.snap,
+            };
+
+            f(&mut cx)
+        })
+    }
+
+    /// Get the rect popups can be placed in, in the coordinates of the window.
+    ///
+    /// This is the window itself, unless popups are shown in windows of their own, see
+    /// [`Window::popup_windows`](crate::window::Window::popup_windows).
+    pub fn popup_bounds(&self) -> Rect {
+        let window = self.window();
+
+        match window.popup_windows {
+            true => Self::EVERYTHING,
+            false => Rect::min_size(Point::ZERO, window.content_size()),
+        }
+    }
+
+    /// Draw a layer that is never hovered, see [`Canvas::non_hoverable`].
+    pub fn non_hoverable<T>(&mut self, f: impl FnOnce(&mut DrawCx<'_, 'b>) -> T) -> T {
+        self.canvas.non_hoverable(|canvas| {
+            let mut cx = DrawCx {
+                base: self.base,
+                view_state: self.view_state,
+                transform: self.transform,
+                canvas,
+                visible: self.visible,
+                snap: self.snap,
             };
 
             f(&mut cx)
@@ -209,6 +450,7 @@ impl<'a, 'b> DrawCx<'a, 'b> {
                 transform: self.transform,
                 canvas,
                 visible: Self::EVERYTHING,
+                snap: self.snap,
             };
 
             f(&mut cx)
@@ -223,13 +465,14 @@ impl<'a, 'b> DrawCx<'a, 'b> {
     ) -> T {
         let visible = self.visible.transform(transform.inverse());
 
-        self.canvas.layer(transform, None, None, |canvas| {
+        (self.canvas).layer(transform, None, None, BlendMode::SourceOver, |canvas| {
             let mut cx = DrawCx {
                 base: self.base,
                 view_state: self.view_state,
                 transform: self.transform * transform,
                 canvas,
                 visible,
+                snap: self.snap,
             };
 
             f(&mut cx)
@@ -264,16 +507,169 @@ impl<'a, 'b> DrawCx<'a, 'b> {
         let mask = mask.into();
         let visible = self.visible.intersection(mask.curve.bounds());
 
-        (self.canvas).layer(Affine::IDENTITY, Some(mask), None, |canvas| {
+        (self.canvas).layer(
+            Affine::IDENTITY,
+            Some(mask),
+            None,
+            BlendMode::SourceOver,
+            |canvas| {
+                let mut cx = DrawCx {
+                    base: self.base,
+                    view_state: self.view_state,
+                    transform: self.transform,
+                    canvas,
+                    visible,
+                    snap: self.snap,
+                };
+
+                f(&mut cx)
+            },
+        )
+    }
+
+    /// Draw a layer composited with a blend mode.
+    pub fn blended<T>(&mut self, blend: BlendMode, f: impl FnOnce(&mut DrawCx<'_, 'b>) -> T) -> T {
+        let visible = self.visible;
+
+        (self.canvas).layer(Affine::IDENTITY, None, None, blend, |canvas| {
+            let mut cx = DrawCx {
+                base: self.base,
+                view_state: self.view_state,
+                transform: self.transform,
+                canvas,
+                visible,
+                snap: self.snap,
+            };
+
+            f(&mut cx)
+        })
+    }
+
+    /// Draw a layer with its colors transformed by a [`ColorMatrix`].
+    pub fn filtered<T>(
+        &mut self,
+        filter: ColorMatrix,
+        f: impl FnOnce(&mut DrawCx<'_, 'b>) -> T,
+    ) -> T {
+        let visible = self.visible;
+
+        self.canvas.filtered(filter, |canvas| {
             let mut cx = DrawCx {
                 base: self.base,
                 view_state: self.view_state,
                 transform: self.transform,
                 canvas,
                 visible,
+                snap: self.snap,
             };
 
             f(&mut cx)
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        canvas::{ConicGradient, Primitive, Shader},
+        layout::Space,
+        view::ViewId,
+        views::{painter, testing::ViewTester},
+    };
+
+    use super::*;
+
+    #[test]
+    fn top_border_only_colors_top_edge() {
+        let mut view = painter(|cx, _| {
+            let rect = Rect::min_size(Point::ZERO, Size::all(20.0));
+            let border_color = [Color::RED, Color::BLUE, Color::BLUE, Color::BLUE];
+            cx.quad(
+                rect,
+                Color::TRANSPARENT,
+                0.0,
+                [4.0, 0.0, 0.0, 0.0],
+                border_color,
+            );
+        });
+
+        let mut tester = ViewTester::new(&mut view, &mut ());
+        tester.layout(&mut view, &mut (), Space::from_size(Size::all(20.0)));
+        let canvas = tester.draw(&mut view, &mut ());
+
+        // the colors of the border drawn at `point`
+        let colors_at = |point: Point| {
+            let mut colors = Vec::new();
+
+            for primitive in canvas.primitives() {
+                if let Primitive::Fill { curve, fill, paint } = primitive {
+                    let Shader::Solid(color) = paint.shader else {
+                        continue;
+                    };
+
+                    if color != Color::TRANSPARENT && curve.contains(point, *fill) {
+                        colors.push(color);
+                    }
+                }
+            }
+
+            colors
+        };
+
+        assert_eq!(colors_at(Point::new(10.0, 2.0)), [Color::RED]);
+        assert_eq!(colors_at(Point::new(1.0, 10.0)), []);
+        assert_eq!(colors_at(Point::new(19.0, 10.0)), []);
+        assert_eq!(colors_at(Point::new(10.0, 19.0)), []);
+        assert_eq!(colors_at(Point::new(10.0, 10.0)), []);
+    }
+
+    #[test]
+    fn border_paint_fills_whole_border() {
+        let gradient =
+            ConicGradient::new(Point::all(10.0), [(0.0, Color::RED), (1.0, Color::BLUE)]);
+
+        let mut view = painter({
+            let gradient = gradient.clone();
+
+            move |cx, _| {
+                let rect = Rect::min_size(Point::ZERO, Size::all(20.0));
+                cx.quad(rect, Color::TRANSPARENT, 4.0, 2.0, gradient.clone());
+            }
+        });
+
+        let mut tester = ViewTester::new(&mut view, &mut ());
+        tester.layout(&mut view, &mut (), Space::from_size(Size::all(20.0)));
+        let canvas = tester.draw(&mut view, &mut ());
+
+        // the border is a single curve, filled with the gradient
+        let shaders = (canvas.primitives())
+            .filter_map(|primitive| match primitive {
+                Primitive::Fill { paint, .. } => Some(paint.shader.clone()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            shaders,
+            [
+                Shader::Solid(Color::TRANSPARENT),
+                Shader::ConicGradient(gradient)
+            ],
+        );
+    }
+
+    #[test]
+    fn rounded_rect_hit_test_excludes_corners() {
+        let id = ViewId::new();
+        let mut canvas = Canvas::new();
+
+        canvas.hoverable(id, |canvas| {
+            let rect = Rect::min_size(Point::ZERO, Size::all(20.0));
+            canvas.draw_rounded_rect(rect, 8.0, Color::RED, 0.0, Color::TRANSPARENT);
+        });
+
+        assert_eq!(canvas.view_at(Point::new(0.5, 0.5)), None);
+        assert_eq!(canvas.view_at(Point::new(2.5, 2.5)), Some(id));
+        assert_eq!(canvas.view_at(Point::new(10.0, 10.0)), Some(id));
+    }
+}
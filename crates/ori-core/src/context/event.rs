@@ -1,6 +1,7 @@
 use std::ops::{Deref, DerefMut};
 
 use crate::{
+    event::PointerId,
     layout::{Affine, Point, Rect, Size},
     view::{ViewFlags, ViewState},
 };
@@ -92,10 +93,41 @@ impl<'a, 'b> EventCx<'a, 'b> {
     }
 
     /// Request a rebuild of the view tree.
+    ///
+    /// This rebuilds the view tree of every window, not just the window the event was sent to.
     pub fn rebuild(&mut self) {
         *self.rebuild = true;
     }
 
+    /// Capture the pointer with `pointer_id`.
+    ///
+    /// Until the capture is released, moves and releases of the pointer are only delivered to
+    /// this view and its ancestors, wherever the pointer is, eg. so a slider keeps following
+    /// the pointer when it's dragged past the end. The capture is released when a button of
+    /// the pointer is released, or with [`EventCx::release_pointer_capture`].
+    pub fn capture_pointer(&mut self, pointer_id: PointerId) {
+        let id = self.id();
+
+        if let Some(pointer) = self.window_mut().get_pointer_mut(pointer_id) {
+            pointer.capture = Some(id);
+            self.view_state.set_capturing(true);
+        }
+    }
+
+    /// Release the capture of the pointer with `pointer_id`, if it's captured by this view.
+    pub fn release_pointer_capture(&mut self, pointer_id: PointerId) {
+        let id = self.id();
+
+        if let Some(pointer) = self.window_mut().get_pointer_mut(pointer_id) {
+            if pointer.capture == Some(id) {
+                pointer.capture = None;
+            }
+        }
+
+        let capturing = self.window().is_capturing(id);
+        self.view_state.set_capturing(capturing);
+    }
+
     /// Get whether the view was hovered last call.
     pub fn was_hovered(&self) -> bool {
         self.view_state.prev_flags.contains(ViewFlags::HOVERED)
@@ -231,4 +231,11 @@ impl_context! {BuildCx<'_, '_>, RebuildCx<'_, '_>, EventCx<'_, '_> {
     pub fn set_class(&mut self, class: impl Into<String>) {
         self.view_state.set_class(class.into());
     }
+
+    /// Set the id of the view.
+    ///
+    /// This is used to address the view with targeted commands, or to focus it.
+    pub fn set_id(&mut self, id: ViewId) {
+        self.view_state.set_id(id);
+    }
 }}
@@ -0,0 +1,130 @@
+//! Native file dialogs.
+
+use std::{
+    fmt::Debug,
+    future::Future,
+    path::PathBuf,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use crate::command::CommandResponse;
+
+/// Whether a [`FileDialog`] picks a file to open or to save to.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum FileDialogKind {
+    /// Pick an existing file to open.
+    #[default]
+    Open,
+
+    /// Pick a file to save to, which may not exist yet.
+    Save,
+}
+
+/// A filter restricting which files can be picked in a [`FileDialog`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct FileFilter {
+    /// The name of the filter shown in the dialog, eg. `Images`.
+    pub name: String,
+
+    /// The extensions of the files matching the filter, without the leading dot.
+    pub extensions: Vec<String>,
+}
+
+impl FileFilter {
+    /// Create a new [`FileFilter`].
+    pub fn new(name: impl ToString, extensions: &[&str]) -> Self {
+        Self {
+            name: name.to_string(),
+            extensions: extensions.iter().map(ToString::to_string).collect(),
+        }
+    }
+}
+
+/// A native file dialog.
+///
+/// Dialogs are opened with [`BaseCx::open_file_dialog`] and [`BaseCx::save_file_dialog`],
+/// which send the dialog as a [`CommandRequest`] that is answered by the shell. When nothing
+/// answers the dialog, eg. when the `file-dialog` feature of the shell is disabled, the
+/// [`FileDialogResponse`] resolves with `None`, as if the dialog was cancelled.
+///
+/// [`BaseCx::open_file_dialog`]: crate::context::BaseCx::open_file_dialog
+/// [`BaseCx::save_file_dialog`]: crate::context::BaseCx::save_file_dialog
+/// [`CommandRequest`]: crate::command::CommandRequest
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FileDialog {
+    /// Whether the dialog picks a file to open or to save to.
+    pub kind: FileDialogKind,
+
+    /// The title of the dialog.
+    pub title: Option<String>,
+
+    /// The directory the dialog starts in.
+    pub directory: Option<PathBuf>,
+
+    /// The file name initially entered in the dialog, only used when saving.
+    pub file_name: Option<String>,
+
+    /// The filters restricting which files can be picked.
+    pub filters: Vec<FileFilter>,
+}
+
+impl FileDialog {
+    /// Create a new [`FileDialog`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the title of the dialog.
+    pub fn title(mut self, title: impl ToString) -> Self {
+        self.title = Some(title.to_string());
+        self
+    }
+
+    /// Set the directory the dialog starts in.
+    pub fn directory(mut self, directory: impl Into<PathBuf>) -> Self {
+        self.directory = Some(directory.into());
+        self
+    }
+
+    /// Set the file name initially entered in the dialog, only used when saving.
+    pub fn file_name(mut self, file_name: impl ToString) -> Self {
+        self.file_name = Some(file_name.to_string());
+        self
+    }
+
+    /// Add a filter restricting which files can be picked, eg.
+    /// `filter("Images", &["png", "jpg"])`.
+    pub fn filter(mut self, name: impl ToString, extensions: &[&str]) -> Self {
+        self.filters.push(FileFilter::new(name, extensions));
+        self
+    }
+}
+
+/// A future that resolves with the path picked in a [`FileDialog`].
+///
+/// Resolves with `None` if the dialog was cancelled, or if nothing answered it.
+pub struct FileDialogResponse {
+    response: CommandResponse<Option<PathBuf>>,
+}
+
+impl FileDialogResponse {
+    pub(crate) fn new(response: CommandResponse<Option<PathBuf>>) -> Self {
+        Self { response }
+    }
+}
+
+impl Future for FileDialogResponse {
+    type Output = Option<PathBuf>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let response = Pin::new(&mut self.response);
+        response.poll(cx).map(|path| path.ok().flatten())
+    }
+}
+
+impl Debug for FileDialogResponse {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FileDialogResponse").finish()
+    }
+}
@@ -4,7 +4,8 @@ use crate::{command::Command, view::ViewId, window::WindowId};
 
 use super::{
     IsKey, KeyPressed, KeyReleased, PointerLeft, PointerMoved, PointerPressed, PointerReleased,
-    PointerScrolled, WindowCloseRequested, WindowMaximized, WindowResized, WindowScaled,
+    PointerScrolled, WindowCloseRequested, WindowFocusChanged, WindowMaximized, WindowResized,
+    WindowScaled,
 };
 
 /// A request to focus a view.
@@ -45,6 +46,9 @@ pub enum Event {
     /// The window was maximized.
     WindowMaximized(WindowMaximized),
 
+    /// The window gained or lost focus.
+    WindowFocusChanged(WindowFocusChanged),
+
     /// The window requested to be close.
     WindowCloseRequested(WindowCloseRequested),
 
@@ -86,6 +90,14 @@ pub enum Event {
     /// An animation frame has passed.
     Animate(f32),
 
+    /// The application was suspended, eg. it was moved to the background on mobile.
+    ///
+    /// This is a good time to pause timers and animations, and to release resources.
+    Suspended,
+
+    /// The application was resumed after being [`Event::Suspended`].
+    Resumed,
+
     /// A command was sent.
     Command(Command),
 
@@ -17,6 +17,12 @@ pub struct Ime {
 
     /// How the IME should capitalize text.
     pub capitalize: Capitalize,
+
+    /// Whether the text is a password.
+    ///
+    /// The [`Ime::text`] of a password is masked, and IMEs shouldn't suggest or learn words
+    /// from it.
+    pub password: bool,
 }
 
 /// Input Method Editor (IME) capitalization.
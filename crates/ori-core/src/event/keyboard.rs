@@ -50,6 +50,12 @@ pub struct KeyPressed {
 
     /// The modifiers that were active.
     pub modifiers: Modifiers,
+
+    /// Whether the press was repeated automatically, because the key is held down.
+    ///
+    /// Repeats are useful for navigation and deletion, but shortcuts should usually only
+    /// fire on the first press.
+    pub repeat: bool,
 }
 
 impl KeyPressed {
@@ -194,6 +200,12 @@ impl Key {
     }
 }
 
+impl From<char> for Key {
+    fn from(value: char) -> Self {
+        Self::Character(value)
+    }
+}
+
 /// A keyboard key-code.
 #[allow(missing_docs)]
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
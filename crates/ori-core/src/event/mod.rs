@@ -5,6 +5,7 @@ mod ime;
 mod keyboard;
 mod modifiers;
 mod pointer;
+mod shortcut;
 mod window;
 
 pub use event::*;
@@ -12,4 +13,5 @@ pub use ime::*;
 pub use keyboard::*;
 pub use modifiers::*;
 pub use pointer::*;
+pub use shortcut::*;
 pub use window::*;
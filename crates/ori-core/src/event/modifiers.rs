@@ -1,5 +1,8 @@
+use std::ops::BitOr;
+
 /// Modifiers for keyboard and pointer events.
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Modifiers {
     /// Whether the shift key is held down.
     pub shift: bool,
@@ -12,8 +15,102 @@ pub struct Modifiers {
 }
 
 impl Modifiers {
+    /// No modifiers.
+    pub const NONE: Self = Self {
+        shift: false,
+        ctrl: false,
+        alt: false,
+        meta: false,
+    };
+
+    /// The shift modifier.
+    pub const SHIFT: Self = Self {
+        shift: true,
+        ..Self::NONE
+    };
+
+    /// The control modifier.
+    pub const CTRL: Self = Self {
+        ctrl: true,
+        ..Self::NONE
+    };
+
+    /// The alt modifier.
+    pub const ALT: Self = Self {
+        alt: true,
+        ..Self::NONE
+    };
+
+    /// The meta modifier, this is the command key on macOS.
+    pub const META: Self = Self {
+        meta: true,
+        ..Self::NONE
+    };
+
+    /// The primary accelerator modifier of the current platform, see [`Accelerator::NATIVE`].
+    pub const COMMAND: Self = Accelerator::NATIVE.modifiers();
+
     /// Check if any modifiers are held down.
     pub fn any(&self) -> bool {
         self.shift || self.ctrl || self.alt || self.meta
     }
+
+    /// Combine the modifiers held down in either `self` or `other`.
+    pub const fn with(self, other: Self) -> Self {
+        Self {
+            shift: self.shift || other.shift,
+            ctrl: self.ctrl || other.ctrl,
+            alt: self.alt || other.alt,
+            meta: self.meta || other.meta,
+        }
+    }
+
+    /// Check if the primary accelerator modifier of the current platform is held down.
+    ///
+    /// This is the command key on macOS, and control everywhere else.
+    pub fn command(&self) -> bool {
+        self.command_for(Accelerator::NATIVE)
+    }
+
+    /// Check if the primary accelerator modifier of `accelerator` is held down.
+    pub fn command_for(&self, accelerator: Accelerator) -> bool {
+        match accelerator {
+            Accelerator::Ctrl => self.ctrl,
+            Accelerator::Meta => self.meta,
+        }
+    }
+}
+
+impl BitOr for Modifiers {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        self.with(rhs)
+    }
+}
+
+/// The modifier used as the primary accelerator for shortcuts, eg. copy and paste.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Accelerator {
+    /// The control key, used on Linux and Windows.
+    Ctrl,
+
+    /// The meta key, which is the command key on macOS.
+    Meta,
+}
+
+impl Accelerator {
+    /// The accelerator of the platform being compiled for.
+    pub const NATIVE: Self = match cfg!(any(target_os = "macos", target_os = "ios")) {
+        true => Self::Meta,
+        false => Self::Ctrl,
+    };
+
+    /// Get the modifiers of the accelerator.
+    pub const fn modifiers(self) -> Modifiers {
+        match self {
+            Self::Ctrl => Modifiers::CTRL,
+            Self::Meta => Modifiers::META,
+        }
+    }
 }
@@ -6,6 +6,7 @@ use super::Modifiers;
 
 /// A unique pointer id.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PointerId {
     id: u64,
 }
@@ -34,6 +35,7 @@ impl PointerId {
 
 /// A pointer button.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PointerButton {
     /// The primary button, usually the left mouse button or the touch screen.
     Primary,
@@ -68,8 +70,30 @@ impl PointerButton {
     }
 }
 
+/// The kind of tool a pointer is.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PointerTool {
+    /// A mouse, or any pointer the backend can't identify.
+    #[default]
+    Mouse,
+
+    /// The tip of a pen or stylus.
+    Pen,
+
+    /// The eraser end of a pen or stylus.
+    Eraser,
+
+    /// A finger on a touch screen.
+    Touch,
+}
+
 /// A pointer was moved.
-#[derive(Clone, Debug, PartialEq, Hash)]
+///
+/// The `pressure`, `tilt` and `tool` fields are only populated by backends with stylus
+/// support, currently Android and X11 with XInput2, other backends report `1.0`,
+/// [`Vector::ZERO`] and [`PointerTool::Mouse`].
+#[derive(Clone, Debug, PartialEq)]
 pub struct PointerMoved {
     /// The unique id of the pointer.
     pub id: PointerId,
@@ -80,6 +104,17 @@ pub struct PointerMoved {
     /// The delta of the pointer.
     pub delta: Vector,
 
+    /// The pressure of the pointer, in the range `0.0..=1.0`.
+    ///
+    /// This is `1.0` for pointers without pressure information.
+    pub pressure: f32,
+
+    /// The tilt of the pointer in radians, along the x and y axes.
+    pub tilt: Vector,
+
+    /// The tool of the pointer.
+    pub tool: PointerTool,
+
     /// The modifiers of the pointer.
     pub modifiers: Modifiers,
 }
@@ -92,7 +127,9 @@ pub struct PointerLeft {
 }
 
 /// A pointer button was pressed.
-#[derive(Clone, Debug, PartialEq, Hash)]
+///
+/// See [`PointerMoved`] for which backends populate `pressure`, `tilt` and `tool`.
+#[derive(Clone, Debug, PartialEq)]
 pub struct PointerPressed {
     /// The unique id of the pointer.
     pub id: PointerId,
@@ -103,12 +140,25 @@ pub struct PointerPressed {
     /// The button of the pointer.
     pub button: PointerButton,
 
+    /// The pressure of the pointer, in the range `0.0..=1.0`.
+    ///
+    /// This is `1.0` for pointers without pressure information.
+    pub pressure: f32,
+
+    /// The tilt of the pointer in radians, along the x and y axes.
+    pub tilt: Vector,
+
+    /// The tool of the pointer.
+    pub tool: PointerTool,
+
     /// The modifiers of the pointer.
     pub modifiers: Modifiers,
 }
 
 /// A pointer button was released.
-#[derive(Clone, Debug, PartialEq, Hash)]
+///
+/// See [`PointerMoved`] for which backends populate `pressure`, `tilt` and `tool`.
+#[derive(Clone, Debug, PartialEq)]
 pub struct PointerReleased {
     /// The unique id of the pointer.
     pub id: PointerId,
@@ -122,10 +172,66 @@ pub struct PointerReleased {
     /// The button of the pointer.
     pub button: PointerButton,
 
+    /// The pressure of the pointer, in the range `0.0..=1.0`.
+    ///
+    /// This is `1.0` for pointers without pressure information.
+    pub pressure: f32,
+
+    /// The tilt of the pointer in radians, along the x and y axes.
+    pub tilt: Vector,
+
+    /// The tool of the pointer.
+    pub tool: PointerTool,
+
     /// The modifiers of the pointer.
     pub modifiers: Modifiers,
 }
 
+/// The amount a pointer wheel was scrolled, and the unit it was scrolled in.
+#[derive(Clone, Copy, Debug, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ScrollDelta {
+    /// A delta in lines, eg. from the clicks of a mouse wheel.
+    Lines(Vector),
+
+    /// A delta in pixels, eg. from a trackpad.
+    Pixels(Vector),
+}
+
+impl Default for ScrollDelta {
+    fn default() -> Self {
+        Self::Lines(Vector::ZERO)
+    }
+}
+
+impl ScrollDelta {
+    /// Get the delta, in whichever unit it was scrolled in.
+    pub fn vector(self) -> Vector {
+        match self {
+            Self::Lines(delta) | Self::Pixels(delta) => delta,
+        }
+    }
+
+    /// Get the delta in pixels, where a line is `line_height` pixels.
+    pub fn to_pixels(self, line_height: f32) -> Vector {
+        match self {
+            Self::Lines(delta) => delta * line_height,
+            Self::Pixels(delta) => delta,
+        }
+    }
+
+    /// Check whether the delta is in lines.
+    pub fn is_lines(self) -> bool {
+        matches!(self, Self::Lines(_))
+    }
+}
+
+impl From<Vector> for ScrollDelta {
+    fn from(delta: Vector) -> Self {
+        Self::Lines(delta)
+    }
+}
+
 /// A pointer wheel was scrolled.
 #[derive(Clone, Debug, PartialEq, Hash)]
 pub struct PointerScrolled {
@@ -135,9 +241,56 @@ pub struct PointerScrolled {
     /// The position of the pointer.
     pub position: Point,
 
-    /// The delta of the pointer.
-    pub delta: Vector,
+    /// The delta of the pointer, and the unit it was scrolled in.
+    ///
+    /// This is a delta in lines for mouse wheels and in pixels for trackpads, use
+    /// [`ScrollDelta::to_pixels`] to scroll by the same distance with both.
+    pub delta: ScrollDelta,
 
     /// The modifiers of the pointer.
     pub modifiers: Modifiers,
 }
+
+impl PointerScrolled {
+    /// Get the delta of the pointer, in whichever unit it was scrolled in.
+    pub fn scroll_delta(&self) -> Vector {
+        self.delta.vector()
+    }
+}
+
+impl Hash for PointerMoved {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+        self.position.hash(state);
+        self.delta.hash(state);
+        self.pressure.to_bits().hash(state);
+        self.tilt.hash(state);
+        self.tool.hash(state);
+        self.modifiers.hash(state);
+    }
+}
+
+impl Hash for PointerPressed {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+        self.position.hash(state);
+        self.button.hash(state);
+        self.pressure.to_bits().hash(state);
+        self.tilt.hash(state);
+        self.tool.hash(state);
+        self.modifiers.hash(state);
+    }
+}
+
+impl Hash for PointerReleased {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+        self.position.hash(state);
+        self.clicked.hash(state);
+        self.button.hash(state);
+        self.pressure.to_bits().hash(state);
+        self.tilt.hash(state);
+        self.tool.hash(state);
+        self.modifiers.hash(state);
+    }
+}
@@ -0,0 +1,161 @@
+use super::{Accelerator, Code, IsKey, Key, KeyPressed, Modifiers};
+
+/// The key of a [`Shortcut`], either a logical [`Key`] or a physical [`Code`].
+///
+/// A logical key depends on the keyboard layout, eg. `Key::Character('z')` is the key labeled
+/// Z. A physical code is the position of the key on the keyboard regardless of the layout, eg.
+/// `Code::Z` is the key next to left shift, which is labeled `;` on a Dvorak layout.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ShortcutKey {
+    /// A logical key, depending on the keyboard layout.
+    Key(Key),
+
+    /// A physical key, independent of the keyboard layout.
+    Code(Code),
+}
+
+impl From<Key> for ShortcutKey {
+    fn from(key: Key) -> Self {
+        Self::Key(key)
+    }
+}
+
+impl From<char> for ShortcutKey {
+    fn from(key: char) -> Self {
+        Self::Key(Key::Character(key))
+    }
+}
+
+impl From<Code> for ShortcutKey {
+    fn from(code: Code) -> Self {
+        Self::Code(code)
+    }
+}
+
+impl IsKey for ShortcutKey {
+    fn is(&self, key: Key, code: Option<Code>) -> bool {
+        match self {
+            ShortcutKey::Key(this) => this.is(key, code),
+            ShortcutKey::Code(this) => this.is(key, code),
+        }
+    }
+}
+
+/// A keyboard shortcut, a key pressed together with a set of modifiers.
+///
+/// Shortcuts that use [`Modifiers::COMMAND`] bind to command on macOS and control on other
+/// platforms, eg. `Shortcut::new('s', Modifiers::COMMAND)` is Cmd+S on macOS and Ctrl+S on
+/// Linux.
+///
+/// Shortcuts created from a [`Code`] match the position of the key, so they stay in the same
+/// place with every keyboard layout, eg. `Shortcut::command(Code::Z)`. Text input always uses
+/// the logical [`Key`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Shortcut {
+    /// The key of the shortcut.
+    pub key: ShortcutKey,
+
+    /// The modifiers that must be held down, no other modifiers may be held.
+    pub modifiers: Modifiers,
+}
+
+impl Shortcut {
+    /// Create a new [`Shortcut`].
+    pub fn new(key: impl Into<ShortcutKey>, modifiers: Modifiers) -> Self {
+        Self {
+            key: key.into(),
+            modifiers,
+        }
+    }
+
+    /// Create a new [`Shortcut`] using the primary accelerator of the current platform.
+    pub fn command(key: impl Into<ShortcutKey>) -> Self {
+        Self::new(key, Modifiers::COMMAND)
+    }
+
+    /// Create a new [`Shortcut`] using the primary accelerator of `accelerator`.
+    ///
+    /// This is useful for applications that let the user choose their shortcut convention.
+    pub fn command_for(key: impl Into<ShortcutKey>, accelerator: Accelerator) -> Self {
+        Self::new(key, accelerator.modifiers())
+    }
+
+    /// Add `modifiers` to the shortcut.
+    pub fn with(mut self, modifiers: Modifiers) -> Self {
+        self.modifiers = self.modifiers.with(modifiers);
+        self
+    }
+
+    /// Check if the shortcut matches a pressed logical key, physical code and modifiers.
+    pub fn matches(&self, key: Key, code: Option<Code>, modifiers: Modifiers) -> bool {
+        self.key.is(key, code) && self.modifiers == modifiers
+    }
+
+    /// Check if the shortcut was pressed.
+    pub fn is_pressed(&self, event: &KeyPressed) -> bool {
+        self.matches(event.key, event.code, event.modifiers)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn command_follows_platform_convention() {
+        let save = Shortcut::command_for('s', Accelerator::Ctrl);
+        assert!(save.matches(Key::Character('s'), None, Modifiers::CTRL));
+        assert!(!save.matches(Key::Character('s'), None, Modifiers::META));
+
+        let save = Shortcut::command_for('s', Accelerator::Meta);
+        assert!(save.matches(Key::Character('s'), None, Modifiers::META));
+        assert!(!save.matches(Key::Character('s'), None, Modifiers::CTRL));
+
+        assert!(Modifiers::CTRL.command_for(Accelerator::Ctrl));
+        assert!(!Modifiers::CTRL.command_for(Accelerator::Meta));
+        assert!(Modifiers::META.command_for(Accelerator::Meta));
+
+        let native = Shortcut::command('s');
+        assert!(native.matches(Key::Character('s'), None, Accelerator::NATIVE.modifiers()));
+        assert!(Modifiers::COMMAND.command());
+    }
+
+    #[test]
+    fn extra_modifiers_do_not_match() {
+        let save_as = Shortcut::command_for('s', Accelerator::Ctrl).with(Modifiers::SHIFT);
+
+        assert!(save_as.matches(
+            Key::Character('s'),
+            None,
+            Modifiers::CTRL | Modifiers::SHIFT
+        ));
+        assert!(!save_as.matches(Key::Character('s'), None, Modifiers::CTRL));
+    }
+
+    #[test]
+    fn code_shortcut_ignores_layout() {
+        let undo = Shortcut::command_for(Code::Z, Accelerator::Ctrl);
+
+        // on a dvorak layout the physical z key types a semicolon
+        let dvorak = KeyPressed {
+            key: Key::Character(';'),
+            code: Some(Code::Z),
+            text: Some(String::from(";")),
+            modifiers: Modifiers::CTRL,
+            repeat: false,
+        };
+
+        assert!(undo.is_pressed(&dvorak));
+
+        // while the key labeled z is somewhere else
+        let labeled_z = KeyPressed {
+            key: Key::Character('z'),
+            code: Some(Code::Slash),
+            text: Some(String::from("z")),
+            ..dvorak.clone()
+        };
+
+        assert!(!undo.is_pressed(&labeled_z));
+        assert!(Shortcut::command_for('z', Accelerator::Ctrl).is_pressed(&labeled_z));
+    }
+}
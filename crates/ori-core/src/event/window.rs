@@ -35,7 +35,11 @@ pub struct WindowScaled {
     /// The window that was scaled.
     pub window: WindowId,
 
-    /// The new scale factor of the window.
+    /// The new render scale of the window, the product of [`Window::scale`] and
+    /// [`Window::ui_scale`].
+    ///
+    /// [`Window::scale`]: crate::window::Window::scale
+    /// [`Window::ui_scale`]: crate::window::Window::ui_scale
     pub scale_factor: f32,
 }
 
@@ -48,3 +52,13 @@ pub struct WindowMaximized {
     /// Whether the window is maximized or not.
     pub maximized: bool,
 }
+
+/// Event emitted when a window gains or loses focus.
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Hash)]
+pub struct WindowFocusChanged {
+    /// The window that gained or lost focus.
+    pub window: WindowId,
+
+    /// Whether the window is focused or not.
+    pub focused: bool,
+}
@@ -2,19 +2,56 @@ use std::{
     fmt::Debug,
     hash::{Hash, Hasher},
     ops::{Deref, DerefMut},
+    time::Duration,
 };
 
 use crate::layout::Size;
 
 use super::ImageId;
 
+/// A frame of an animated image.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct ImageFrame {
+    data: Vec<u8>,
+    delay: Duration,
+}
+
+impl ImageFrame {
+    /// Create a new frame, shown for `delay` before the next one.
+    pub fn new(data: Vec<u8>, delay: Duration) -> Self {
+        Self { data, delay }
+    }
+
+    /// Get the pixels of the frame.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Get how long the frame is shown before the next one.
+    pub fn delay(&self) -> Duration {
+        self.delay
+    }
+}
+
+impl Debug for ImageFrame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ImageFrame")
+            .field("delay", &self.delay)
+            .finish()
+    }
+}
+
 /// Image data.
+///
+/// Images have one or more [frames](ImageFrame), the pixels of an image are the pixels of its
+/// first frame.
 #[derive(Clone, PartialEq, Eq, Hash)]
 pub struct ImageData {
-    data: Vec<u8>,
+    frames: Vec<ImageFrame>,
     width: u32,
     height: u32,
     filter: bool,
+    loop_count: Option<u32>,
 }
 
 impl Default for ImageData {
@@ -29,27 +66,101 @@ impl ImageData {
     /// # Panics
     /// - If `pixels.len()` is not equal to `width * height * 4`.
     pub fn new(data: Vec<u8>, width: u32, height: u32) -> Self {
-        assert_eq!(data.len() as u32, width * height * 4);
+        Self::from_frames(vec![ImageFrame::new(data, Duration::ZERO)], width, height)
+    }
+
+    /// Create new image data from the frames of an animation.
+    ///
+    /// # Panics
+    /// - If `frames` is empty.
+    /// - If the pixels of a frame are not `width * height * 4` long.
+    pub fn from_frames(frames: Vec<ImageFrame>, width: u32, height: u32) -> Self {
+        assert!(!frames.is_empty(), "an image needs at least one frame");
+
+        for frame in &frames {
+            assert_eq!(frame.data.len() as u32, width * height * 4);
+        }
 
         Self {
-            data,
+            frames,
             width,
             height,
             filter: true,
+            loop_count: None,
+        }
+    }
+
+    // the data of an image without pixels, drawn to by the renderer, see
+    // `Canvas::render_to_texture`
+    pub(crate) fn unallocated(width: u32, height: u32) -> Self {
+        Self {
+            frames: vec![ImageFrame::new(Vec::new(), Duration::ZERO)],
+            width,
+            height,
+            filter: true,
+            loop_count: None,
         }
     }
 
     /// Try to load image data from a file.
+    ///
+    /// Animated GIF and PNG images are loaded with all of their frames, and the number of
+    /// times they're played, see [`ImageData::loop_count`].
     #[cfg(feature = "image")]
     pub fn try_load_data(data: Vec<u8>) -> image::ImageResult<Self> {
-        let data = image::load_from_memory(&data)?;
+        use image::{
+            codecs::{gif::GifDecoder, png::PngDecoder},
+            metadata::LoopCount,
+            AnimationDecoder, Frames, ImageFormat,
+        };
 
-        Ok(Self {
-            data: data.to_rgba8().into_raw(),
-            width: data.width(),
-            height: data.height(),
-            filter: true,
-        })
+        // the loop count is read from the netscape extension of gifs and `num_plays` of apngs
+        fn animation<'a>(decoder: impl AnimationDecoder<'a>) -> (Frames<'a>, Option<u32>) {
+            let loop_count = match decoder.loop_count() {
+                LoopCount::Infinite => None,
+                LoopCount::Finite(count) => Some(count.get()),
+            };
+
+            (decoder.into_frames(), loop_count)
+        }
+
+        let cursor = || std::io::Cursor::new(data.as_slice());
+
+        let animation = match image::guess_format(&data)? {
+            ImageFormat::Gif => Some(animation(GifDecoder::new(cursor())?)),
+            ImageFormat::Png => {
+                let decoder = PngDecoder::new(cursor())?;
+
+                match decoder.is_apng()? {
+                    true => Some(animation(decoder.apng()?)),
+                    false => None,
+                }
+            }
+            _ => None,
+        };
+
+        if let Some((frames, loop_count)) = animation {
+            let frames = frames.collect_frames()?;
+
+            if let Some(first) = frames.first() {
+                let (width, height) = first.buffer().dimensions();
+
+                let frames = frames.into_iter().map(|frame| {
+                    let delay = Duration::from(frame.delay());
+                    ImageFrame::new(frame.into_buffer().into_raw(), delay)
+                });
+
+                let mut image = Self::from_frames(frames.collect(), width, height);
+                image.loop_count = loop_count;
+
+                return Ok(image);
+            }
+        }
+
+        let image = image::load_from_memory(&data)?;
+        let (width, height) = (image.width(), image.height());
+
+        Ok(Self::new(image.to_rgba8().into_raw(), width, height))
     }
 
     /// Load image data from a file.
@@ -67,14 +178,7 @@ impl ImageData {
     /// Try to load an image from a file.
     #[cfg(feature = "image")]
     pub fn try_load(path: impl AsRef<std::path::Path>) -> image::ImageResult<Self> {
-        let data = image::open(path)?;
-
-        Ok(Self {
-            data: data.to_rgba8().into_raw(),
-            width: data.width(),
-            height: data.height(),
-            filter: true,
-        })
+        Self::try_load_data(std::fs::read(path)?)
     }
 
     /// Load an image from a file.
@@ -107,30 +211,71 @@ impl ImageData {
     /// Get a pixel.
     pub fn get_pixel(&self, x: u32, y: u32) -> [u8; 4] {
         let i = (y * self.width + x) as usize * 4;
-        let r = self.data[i];
-        let g = self.data[i + 1];
-        let b = self.data[i + 2];
-        let a = self.data[i + 3];
+        let data = self.data();
+        let r = data[i];
+        let g = data[i + 1];
+        let b = data[i + 2];
+        let a = data[i + 3];
         [r, g, b, a]
     }
 
     /// Set a pixel.
     pub fn set_pixel(&mut self, x: u32, y: u32, pixel: [u8; 4]) {
         let i = (y * self.width + x) as usize * 4;
-        self.data[i] = pixel[0];
-        self.data[i + 1] = pixel[1];
-        self.data[i + 2] = pixel[2];
-        self.data[i + 3] = pixel[3];
+        let data = self.data_mut();
+        data[i] = pixel[0];
+        data[i + 1] = pixel[1];
+        data[i + 2] = pixel[2];
+        data[i + 3] = pixel[3];
     }
 
     /// Get the pixels.
+    ///
+    /// Images rendered by the renderer have no pixels, see
+    /// [`Canvas::render_to_texture`](crate::canvas::Canvas::render_to_texture).
     pub fn data(&self) -> &[u8] {
-        &self.data
+        &self.frames[0].data
     }
 
     /// Get the pixels mutably.
     pub fn data_mut(&mut self) -> &mut [u8] {
-        &mut self.data
+        &mut self.frames[0].data
+    }
+
+    /// Get the frames of the image, still images have a single frame.
+    pub fn frames(&self) -> &[ImageFrame] {
+        &self.frames
+    }
+
+    /// Check whether the image has more than one frame.
+    pub fn is_animated(&self) -> bool {
+        self.frames.len() > 1
+    }
+
+    /// Copy a single frame into new image data.
+    ///
+    /// # Panics
+    /// - If `index` is out of bounds.
+    pub fn to_frame(&self, index: usize) -> Self {
+        let frame = ImageFrame::new(self.frames[index].data.clone(), Duration::ZERO);
+
+        Self {
+            frames: vec![frame],
+            loop_count: None,
+            ..*self
+        }
+    }
+
+    /// Get the number of times an animated image is played, `None` if it loops forever.
+    pub fn loop_count(&self) -> Option<u32> {
+        self.loop_count
+    }
+
+    /// Set the number of times an animated image is played, `None` to loop forever.
+    ///
+    /// Once the last loop is done, the last frame stays shown.
+    pub fn set_loop_count(&mut self, loop_count: Option<u32>) {
+        self.loop_count = loop_count;
     }
 
     /// Get the filter mode.
@@ -167,6 +312,8 @@ impl Debug for ImageData {
             .field("width", &self.width)
             .field("height", &self.height)
             .field("filter", &self.filter)
+            .field("frames", &self.frames.len())
+            .field("loop_count", &self.loop_count)
             .finish()
     }
 }
@@ -175,12 +322,49 @@ impl Deref for ImageData {
     type Target = [u8];
 
     fn deref(&self) -> &Self::Target {
-        &self.data
+        self.data()
     }
 }
 
 impl DerefMut for ImageData {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.data
+        self.data_mut()
+    }
+}
+
+#[cfg(all(test, feature = "image"))]
+mod tests {
+    use super::*;
+
+    // a 2x1 gif with a red and a blue frame, shown for 100ms each and played 3 times
+    const ANIMATED_GIF: &[u8] = &[
+        b'G', b'I', b'F', b'8', b'9', b'a', // header
+        0x02, 0x00, 0x01, 0x00, 0xF0, 0x00, 0x00, // 2x1, with a 2 color table
+        0xFF, 0x00, 0x00, 0x00, 0x00, 0xFF, // red and blue
+        0x21, 0xFF, 0x0B, b'N', b'E', b'T', b'S', b'C', b'A', b'P', b'E', b'2', b'.', b'0', 0x03,
+        0x01, 0x03, 0x00, 0x00, // loop count 3
+        0x21, 0xF9, 0x04, 0x04, 0x0A, 0x00, 0x00, 0x00, // 10/100s delay
+        0x2C, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x01, 0x00, 0x00, // first frame
+        0x02, 0x02, 0x04, 0x0A, 0x00, // red red
+        0x21, 0xF9, 0x04, 0x04, 0x0A, 0x00, 0x00, 0x00, // 10/100s delay
+        0x2C, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x01, 0x00, 0x00, // second frame
+        0x02, 0x02, 0x4C, 0x0A, 0x00, // blue blue
+        0x3B, // trailer
+    ];
+
+    #[test]
+    fn animated_gifs_load_their_frames_and_loop_count() {
+        let image = ImageData::try_load_data(ANIMATED_GIF.to_vec()).unwrap();
+
+        assert_eq!(image.size(), Size::new(2.0, 1.0));
+        assert_eq!(image.loop_count(), Some(3));
+
+        let frames = image.frames();
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].data(), [255, 0, 0, 255, 255, 0, 0, 255]);
+        assert_eq!(frames[1].data(), [0, 0, 255, 255, 0, 0, 255, 255]);
+        assert!(frames
+            .iter()
+            .all(|f| f.delay() == Duration::from_millis(100)));
     }
 }
@@ -0,0 +1,84 @@
+use super::{Image, ImageData, TextureId};
+
+/// A handle to an image that is updated in place.
+///
+/// Every distinct [`Image`] is uploaded to a new texture by the renderer, which is wasteful
+/// for images that change every frame, eg. video or camera feeds. The image of a handle is
+/// tied to a [`TextureId`], and as long as the size of the image doesn't change, the
+/// renderer re-uploads the pixels into the same texture.
+#[derive(Clone, Debug)]
+pub struct ImageHandle {
+    texture: TextureId,
+    image: Image,
+}
+
+impl Default for ImageHandle {
+    fn default() -> Self {
+        Self::new(ImageData::default())
+    }
+}
+
+impl ImageHandle {
+    /// Create a new [`ImageHandle`].
+    pub fn new(data: impl Into<Image>) -> Self {
+        let texture = TextureId::new();
+
+        let mut image = data.into();
+        image.texture = Some(texture);
+
+        Self { texture, image }
+    }
+
+    /// Get the [`TextureId`] of the handle.
+    pub fn texture_id(&self) -> TextureId {
+        self.texture
+    }
+
+    /// Get the current image.
+    pub fn image(&self) -> &Image {
+        &self.image
+    }
+
+    /// Update the pixels of the image.
+    ///
+    /// If the size of `data` matches the current image the texture is reused, otherwise a
+    /// new texture is allocated. Returns `true` if the texture was reused.
+    pub fn update(&mut self, data: &ImageData) -> bool {
+        let reuse = self.image.width() == data.width() && self.image.height() == data.height();
+
+        if !reuse {
+            self.texture = TextureId::new();
+            self.image.texture = Some(self.texture);
+        }
+
+        // this reuses the allocation of the pixels, if the image isn't shared
+        self.image.modify(|image| image.clone_from(data));
+
+        reuse
+    }
+}
+
+impl From<ImageHandle> for Image {
+    fn from(handle: ImageHandle) -> Self {
+        handle.image
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_size_reuses_texture() {
+        let mut handle = ImageHandle::new(ImageData::new(vec![0; 16], 2, 2));
+        let texture = handle.texture_id();
+        let id = handle.image().id();
+
+        assert!(handle.update(&ImageData::new(vec![255; 16], 2, 2)));
+        assert_eq!(handle.texture_id(), texture);
+        assert_ne!(handle.image().id(), id);
+
+        assert!(!handle.update(&ImageData::new(vec![255; 36], 3, 3)));
+        assert_ne!(handle.texture_id(), texture);
+    }
+}
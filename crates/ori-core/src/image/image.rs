@@ -7,7 +7,7 @@ use std::{
 
 use crate::canvas::Color;
 
-use super::ImageData;
+use super::{ImageData, TextureId};
 
 /// Include an image.
 ///
@@ -48,6 +48,7 @@ pub struct ImageId {
 pub struct Image {
     id: ImageId,
     data: Arc<ImageData>,
+    pub(crate) texture: Option<TextureId>,
 }
 
 impl Default for Image {
@@ -100,6 +101,16 @@ impl Image {
         self.id
     }
 
+    /// Get the texture the image is uploaded to, if it is managed by an [`ImageHandle`].
+    ///
+    /// Images with the same texture share the same GPU resource, which is updated in place
+    /// when the [`ImageId`] changes.
+    ///
+    /// [`ImageHandle`]: super::ImageHandle
+    pub fn texture(&self) -> Option<TextureId> {
+        self.texture
+    }
+
     /// Modify the image data.
     pub fn modify(&mut self, f: impl FnOnce(&mut ImageData)) {
         f(Arc::make_mut(&mut self.data));
@@ -155,7 +166,11 @@ impl From<ImageData> for Image {
         let id = value.compute_id();
         let data = Arc::new(value);
 
-        Self { id, data }
+        Self {
+            id,
+            data,
+            texture: None,
+        }
     }
 }
 
@@ -1,9 +1,15 @@
 //! Image data and processing.
 
 mod data;
+mod handle;
 mod image;
+#[cfg(feature = "svg")]
+mod svg;
 mod texture;
 
 pub use self::image::*;
 pub use data::*;
+pub use handle::*;
+#[cfg(feature = "svg")]
+pub use svg::*;
 pub use texture::*;
@@ -0,0 +1,185 @@
+use std::{
+    collections::HashMap,
+    error::Error,
+    fmt::{self, Debug, Display},
+    io,
+    sync::{Arc, Mutex},
+};
+
+use resvg::{tiny_skia, usvg};
+
+use crate::layout::Size;
+
+use super::{Image, ImageData};
+
+/// An error that occurred while loading an [`Svg`].
+#[derive(Debug)]
+pub enum SvgError {
+    /// The file could not be read.
+    Io(io::Error),
+
+    /// The svg could not be parsed.
+    Parse(usvg::Error),
+}
+
+impl Display for SvgError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SvgError::Io(err) => Display::fmt(err, f),
+            SvgError::Parse(err) => Display::fmt(err, f),
+        }
+    }
+}
+
+impl Error for SvgError {}
+
+/// A vector image, that is rasterized at the size it's drawn at.
+///
+/// Unlike an [`Image`] an svg stays crisp at any scale, the rasterized images are cached by
+/// pixel size and shared between clones of the svg.
+#[derive(Clone)]
+pub struct Svg {
+    tree: Arc<usvg::Tree>,
+    cache: Arc<Mutex<HashMap<(u32, u32), Image>>>,
+}
+
+impl Svg {
+    // the number of rasterized sizes kept, eg. while a window is resized
+    const CACHE_SIZE: usize = 8;
+
+    /// Try to parse an svg from its source.
+    pub fn try_load_data(data: &[u8]) -> Result<Self, SvgError> {
+        let tree = usvg::Tree::from_data(data, &usvg::Options::default());
+        let tree = tree.map_err(SvgError::Parse)?;
+
+        Ok(Self {
+            tree: Arc::new(tree),
+            cache: Arc::default(),
+        })
+    }
+
+    /// Parse an svg from its source.
+    pub fn load_data(data: &[u8]) -> Self {
+        match Self::try_load_data(data) {
+            Ok(svg) => svg,
+            Err(err) => {
+                tracing::error!("Failed to load svg: {}", err);
+                Self::default()
+            }
+        }
+    }
+
+    /// Try to load an svg from a file.
+    pub fn try_load(path: impl AsRef<std::path::Path>) -> Result<Self, SvgError> {
+        let data = std::fs::read(path).map_err(SvgError::Io)?;
+        Self::try_load_data(&data)
+    }
+
+    /// Load an svg from a file.
+    pub fn load(path: impl AsRef<std::path::Path>) -> Self {
+        match Self::try_load(path.as_ref()) {
+            Ok(svg) => svg,
+            Err(err) => {
+                tracing::error!("Failed to load svg: {}: {}", path.as_ref().display(), err);
+                Self::default()
+            }
+        }
+    }
+
+    /// Get the size of the svg, as given by its `width` and `height`.
+    pub fn size(&self) -> Size {
+        let size = self.tree.size();
+        Size::new(size.width(), size.height())
+    }
+
+    /// Rasterize the svg to fill `size`, at `scale` pixels per unit.
+    ///
+    /// The size of the image is `size * scale` rounded up to whole pixels. Images are cached, so
+    /// rasterizing at the same size again is cheap.
+    pub fn rasterize(&self, size: Size, scale: f32) -> Image {
+        let width = f32::max((size.width * scale).ceil(), 1.0) as u32;
+        let height = f32::max((size.height * scale).ceil(), 1.0) as u32;
+
+        let mut cache = self.cache.lock().unwrap_or_else(|err| err.into_inner());
+
+        if let Some(image) = cache.get(&(width, height)) {
+            return image.clone();
+        }
+
+        if cache.len() >= Self::CACHE_SIZE {
+            cache.clear();
+        }
+
+        let image = self.render(width, height);
+        cache.insert((width, height), image.clone());
+
+        image
+    }
+
+    fn render(&self, width: u32, height: u32) -> Image {
+        let Some(mut pixmap) = tiny_skia::Pixmap::new(width, height) else {
+            return Image::default();
+        };
+
+        let size = self.size();
+        let transform = tiny_skia::Transform::from_scale(
+            width as f32 / size.width,
+            height as f32 / size.height,
+        );
+
+        resvg::render(&self.tree, transform, &mut pixmap.as_mut());
+
+        // tiny-skia premultiplies the alpha, images are stored without
+        let mut data = Vec::with_capacity(width as usize * height as usize * 4);
+
+        for pixel in pixmap.pixels() {
+            let color = pixel.demultiply();
+            data.extend([color.red(), color.green(), color.blue(), color.alpha()]);
+        }
+
+        Image::from(ImageData::new(data, width, height))
+    }
+}
+
+impl Default for Svg {
+    fn default() -> Self {
+        let svg = br#"<svg xmlns="http://www.w3.org/2000/svg" width="1" height="1"/>"#;
+        Self::try_load_data(svg).unwrap()
+    }
+}
+
+impl Debug for Svg {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Svg").field("size", &self.size()).finish()
+    }
+}
+
+impl PartialEq for Svg {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.tree, &other.tree)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SQUARE: &[u8] = br##"
+        <svg xmlns="http://www.w3.org/2000/svg" width="16" height="12">
+            <rect width="16" height="12" fill="#ff0000"/>
+        </svg>
+    "##;
+
+    #[test]
+    fn rasterize_at_double_scale() {
+        let svg = Svg::try_load_data(SQUARE).unwrap();
+        assert_eq!(svg.size(), Size::new(16.0, 12.0));
+
+        let image = svg.rasterize(svg.size(), 2.0);
+        assert_eq!((image.width(), image.height()), (32, 24));
+        assert_eq!(image.get_pixel(16, 12), [255, 0, 0, 255]);
+
+        // the second rasterization at the same size is cached
+        assert_eq!(svg.rasterize(svg.size(), 2.0).id(), image.id());
+    }
+}
@@ -1,6 +1,10 @@
 use super::{Size, Vector};
 
 /// Alignment of content inside a container.
+///
+/// The alignment is a fractional anchor, `0.0` aligns the content to the left or top of the
+/// container and `1.0` to the right or bottom, any value in between positions the content
+/// proportionally, eg. `(0.5, 0.2)` centers it horizontally a fifth of the way down.
 #[repr(C)]
 #[derive(Copy, Clone, Debug, Default, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -56,3 +60,15 @@ impl From<[f32; 2]> for Alignment {
         Self::new(x, y)
     }
 }
+
+impl From<Vector> for Alignment {
+    fn from(vector: Vector) -> Self {
+        Self::new(vector.x, vector.y)
+    }
+}
+
+impl From<Alignment> for Vector {
+    fn from(alignment: Alignment) -> Self {
+        Vector::new(alignment.x, alignment.y)
+    }
+}
@@ -63,6 +63,21 @@ impl Rect {
         }
     }
 
+    /// Translate the rectangle so that it lies within the given rectangle.
+    ///
+    /// If the rectangle is larger than `other`, it will be aligned with the bottom right.
+    pub fn shift_inside(self, other: impl Into<Self>) -> Self {
+        let other = other.into();
+
+        let tl_delta = other.top_left() - self.top_left();
+        let br_delta = self.bottom_right() - other.bottom_right();
+
+        let mut offset = Vector::max(tl_delta, Vector::ZERO);
+        offset -= Vector::max(br_delta + offset, Vector::ZERO);
+
+        self + offset
+    }
+
     /// Get the offset of the rectangle.
     pub fn offset(self) -> Vector {
         self.min.to_vector()
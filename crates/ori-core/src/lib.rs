@@ -7,6 +7,7 @@ pub mod canvas;
 pub mod clipboard;
 pub mod command;
 pub mod context;
+pub mod dialog;
 pub mod event;
 pub mod image;
 pub mod layout;
@@ -8,9 +8,13 @@ use std::{
     sync::Arc,
 };
 
-use crate::canvas::Color;
+use crate::{
+    canvas::{Color, ColorInterpolation, Shadow},
+    layout::Vector,
+    transition::{Easing, Transition},
+};
 
-use super::{Style, Styled, Styles};
+use super::{Style, StyleEntry, StyleVar, Styled, Styles};
 
 impl FromStr for Styles {
     type Err = ParseError;
@@ -39,7 +43,7 @@ impl Display for ParseError {
 
 impl Error for ParseError {}
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 enum TokenKind<'a> {
     Ident(&'a str),
     String(&'a str),
@@ -50,20 +54,23 @@ enum TokenKind<'a> {
     Colon,
     Dot,
     Comma,
+    Greater,
     OpenBrace,
     OpenBracket,
+    OpenParen,
     CloseBrace,
     CloseBracket,
+    CloseParen,
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 #[allow(dead_code)]
 struct Token<'a> {
     kind: TokenKind<'a>,
     span: Range<usize>,
 }
 
-fn tokenize(s: &str) -> Result<impl ExactSizeIterator<Item = Token>, ParseError> {
+fn tokenize(s: &str) -> Result<impl ExactSizeIterator<Item = Token> + Clone, ParseError> {
     // i want a generator here, it's absense is making me sad
 
     let mut chars = s
@@ -84,7 +91,10 @@ fn tokenize(s: &str) -> Result<impl ExactSizeIterator<Item = Token>, ParseError>
             continue;
         }
 
-        if is_ident_start(c) {
+        // variables are identifiers starting with `--`, eg. `--accent`
+        let is_variable = c == '-' && chars.peek().is_some_and(|&(c, _)| c == '-');
+
+        if is_ident_start(c) || is_variable {
             let mut hi = lo + c.len_utf8();
 
             while let Some(&(c, idx)) = chars.peek() {
@@ -132,12 +142,13 @@ fn tokenize(s: &str) -> Result<impl ExactSizeIterator<Item = Token>, ParseError>
         if c == '#' {
             let mut hi = lo + c.len_utf8();
 
-            for (c, idx) in chars.by_ref() {
+            while let Some(&(c, idx)) = chars.peek() {
                 if !c.is_ascii_hexdigit() {
                     break;
                 }
 
                 hi = idx + c.len_utf8();
+                chars.next();
             }
 
             let color = Color::hex(&s[lo + 1..hi]);
@@ -198,17 +209,20 @@ fn get_symbol(c: char) -> Option<TokenKind<'static>> {
         ':' => Some(TokenKind::Colon),
         '.' => Some(TokenKind::Dot),
         ',' => Some(TokenKind::Comma),
+        '>' => Some(TokenKind::Greater),
         '{' => Some(TokenKind::OpenBrace),
         '}' => Some(TokenKind::CloseBrace),
         '[' => Some(TokenKind::OpenBracket),
         ']' => Some(TokenKind::CloseBracket),
+        '(' => Some(TokenKind::OpenParen),
+        ')' => Some(TokenKind::CloseParen),
         _ => None,
     }
 }
 
 fn parse<'a, I>(tokens: I) -> Result<Styles, ParseError>
 where
-    I: Iterator<Item = Token<'a>>,
+    I: Iterator<Item = Token<'a>> + Clone,
 {
     let mut tokens = tokens.peekable();
     let mut styles = Styles::default();
@@ -226,20 +240,46 @@ fn parse_item<'a, I>(
     styles: &mut Styles,
 ) -> Result<(), ParseError>
 where
-    I: Iterator<Item = Token<'a>>,
+    I: Iterator<Item = Token<'a>> + Clone,
 {
-    let name = expect_ident(next(tokens)?)?;
+    let name = parse_class(tokens)?;
+
+    // the transition shorthand, eg. `transition: color 0.2s ease-out, border-color 0.1s,`
+    if name == "transition" && is(tokens, TokenKind::Colon) {
+        next(tokens)?;
+        return parse_transitions(tokens, key, styles);
+    }
 
     let mut key = match key.is_empty() {
         true => name.to_string(),
         false => format!("{}.{}", key, name),
     };
 
-    while is(tokens, TokenKind::Dot) {
-        next(tokens)?;
-        let ident = expect_ident(next(tokens)?)?;
-        key.push('.');
-        key.push_str(ident);
+    // selectors, eg. `sidebar button` matches buttons anywhere inside a sidebar, and
+    // `toolbar > button` only buttons whose closest ancestor with a class is a toolbar
+    loop {
+        let token = tokens.peek().map(|token| &token.kind);
+
+        match token {
+            Some(TokenKind::Dot) => {
+                next(tokens)?;
+                let ident = expect_ident(next(tokens)?)?;
+                key.push('.');
+                key.push_str(ident);
+            }
+            Some(TokenKind::Greater) => {
+                next(tokens)?;
+                let ident = parse_class(tokens)?;
+                key.push_str(".>");
+                key.push_str(ident);
+            }
+            Some(TokenKind::Ident(ident)) => {
+                key.push('.');
+                key.push_str(ident);
+                next(tokens)?;
+            }
+            _ => break,
+        }
     }
 
     let token = next(tokens)?;
@@ -276,33 +316,320 @@ where
     }
 }
 
-fn parse_value<'a, I>(
+fn parse_transitions<'a, I>(
     tokens: &mut Peekable<I>,
-) -> Result<Styled<Arc<dyn Any + Send + Sync>>, ParseError>
+    key: &str,
+    styles: &mut Styles,
+) -> Result<(), ParseError>
+where
+    I: Iterator<Item = Token<'a>> + Clone,
+{
+    loop {
+        let property = expect_ident(next(tokens)?)?;
+        let transition = parse_transition(tokens)?;
+
+        let key = match key.is_empty() {
+            true => format!("{}.transition", property),
+            false => format!("{}.{}.transition", key, property),
+        };
+
+        styles.insert_entry(&key, Styled::Value(Arc::new(transition)));
+
+        if !is(tokens, TokenKind::Comma) {
+            return Err(ParseError {
+                message: "expected ','".to_string(),
+            });
+        }
+
+        next(tokens)?;
+
+        // the list continues if the next item is a property followed by a duration
+        let mut ahead = tokens.clone();
+        let is_property = matches!(
+            ahead.next(),
+            Some(Token {
+                kind: TokenKind::Ident(_),
+                ..
+            })
+        );
+        let is_duration = matches!(
+            ahead.peek(),
+            Some(Token {
+                kind: TokenKind::Number(_),
+                ..
+            })
+        );
+
+        if !(is_property && is_duration) {
+            return Ok(());
+        }
+    }
+}
+
+fn parse_transition<'a, I>(tokens: &mut Peekable<I>) -> Result<Transition, ParseError>
 where
     I: Iterator<Item = Token<'a>>,
 {
     let token = next(tokens)?;
 
-    match token.kind {
-        TokenKind::String(s) => Ok(Styled::Value(Arc::new(s.to_string()))),
-        TokenKind::Number(n) => Ok(Styled::Value(Arc::new(n))),
-        TokenKind::True => Ok(Styled::Value(Arc::new(true))),
-        TokenKind::False => Ok(Styled::Value(Arc::new(false))),
-        TokenKind::Color(color) => Ok(Styled::Value(Arc::new(color))),
+    let TokenKind::Number(mut duration) = token.kind else {
+        return Err(ParseError {
+            message: format!("expected duration, found {:?}", token.kind),
+        });
+    };
+
+    let mut easing = Easing::Ease;
+    let mut interpolation = ColorInterpolation::Oklab;
+
+    while let Some(Token {
+        kind: TokenKind::Ident(ident),
+        ..
+    }) = tokens.peek()
+    {
+        match *ident {
+            "s" => {}
+            "ms" => duration /= 1000.0,
+            "linear" => easing = Easing::Linear,
+            "ease" | "ease-in-out" => easing = Easing::Ease,
+            "ease-in" => easing = Easing::EaseIn,
+            "ease-out" => easing = Easing::EaseOut,
+            "oklab" => interpolation = ColorInterpolation::Oklab,
+            "srgb" => interpolation = ColorInterpolation::Srgb,
+            ident => {
+                return Err(ParseError {
+                    message: format!("unknown transition parameter: {:?}", ident),
+                });
+            }
+        }
 
-        TokenKind::Ident(ident) => {
-            let mut key = String::from(ident);
+        next(tokens)?;
+    }
 
-            while is(tokens, TokenKind::Dot) {
-                next(tokens)?;
-                let ident = expect_ident(next(tokens)?)?;
-                key.push('.');
-                key.push_str(ident);
+    Ok(Transition {
+        duration,
+        easing,
+        interpolation,
+    })
+}
+
+// a value parsed from a style sheet, before it is stored as a `dyn Any`
+#[derive(Clone, Debug, PartialEq)]
+enum StyleValue {
+    String(String),
+    Number(f32),
+    Bool(bool),
+    Color(Color),
+    Vec2([f32; 2]),
+    Vec4([f32; 4]),
+    Shadow(Shadow),
+    List(Vec<StyleValue>),
+}
+
+impl StyleValue {
+    fn into_any(self) -> Result<Arc<dyn Any + Send + Sync>, ParseError> {
+        Ok(match self {
+            StyleValue::String(s) => Arc::new(s),
+            StyleValue::Number(n) => Arc::new(n),
+            StyleValue::Bool(b) => Arc::new(b),
+            StyleValue::Color(color) => Arc::new(color),
+            StyleValue::Vec2(v) => Arc::new(v),
+            StyleValue::Vec4(v) => Arc::new(v),
+            StyleValue::Shadow(shadow) => Arc::new(shadow),
+
+            // lists are stored as a `Vec` of the type of their first item
+            StyleValue::List(items) => match items.first() {
+                Some(StyleValue::String(_)) => Arc::new(Vec::<String>::from_list(items)?),
+                Some(StyleValue::Number(_)) => Arc::new(Vec::<f32>::from_list(items)?),
+                Some(StyleValue::Bool(_)) => Arc::new(Vec::<bool>::from_list(items)?),
+                Some(StyleValue::Color(_)) => Arc::new(Vec::<Color>::from_list(items)?),
+                Some(StyleValue::Vec2(_)) => Arc::new(Vec::<[f32; 2]>::from_list(items)?),
+                Some(StyleValue::Vec4(_)) => Arc::new(Vec::<[f32; 4]>::from_list(items)?),
+                Some(StyleValue::Shadow(_)) => Arc::new(Vec::<Shadow>::from_list(items)?),
+                Some(StyleValue::List(_)) | None => {
+                    return Err(ParseError {
+                        message: "lists cannot be nested or empty".to_string(),
+                    });
+                }
+            },
+        })
+    }
+}
+
+// conversion from a parsed [`StyleValue`] to a concrete type
+trait FromStyleValue: Sized {
+    const NAME: &'static str;
+
+    fn from_style_value(value: StyleValue) -> Option<Self>;
+}
+
+macro_rules! impl_from_style_value {
+    ($($variant:ident => $ty:ty, $name:literal;)*) => {$(
+        impl FromStyleValue for $ty {
+            const NAME: &'static str = $name;
+
+            fn from_style_value(value: StyleValue) -> Option<Self> {
+                match value {
+                    StyleValue::$variant(value) => Some(value),
+                    _ => None,
+                }
             }
+        }
+    )*};
+}
+
+impl_from_style_value! {
+    String => String, "string";
+    Number => f32, "number";
+    Bool => bool, "boolean";
+    Color => Color, "color";
+    Vec2 => [f32; 2], "2 numbers";
+    Vec4 => [f32; 4], "4 numbers";
+    Shadow => Shadow, "shadow";
+}
+
+trait FromStyleList: Sized {
+    fn from_list(items: Vec<StyleValue>) -> Result<Self, ParseError>;
+}
+
+impl<T: FromStyleValue> FromStyleList for Vec<T> {
+    fn from_list(items: Vec<StyleValue>) -> Result<Self, ParseError> {
+        let mut list = Vec::with_capacity(items.len());
+
+        for item in items {
+            let message = format!("expected {} in list, found {:?}", T::NAME, item);
+
+            match T::from_style_value(item) {
+                Some(item) => list.push(item),
+                None => return Err(ParseError { message }),
+            }
+        }
+
+        Ok(list)
+    }
+}
+
+fn parse_value<'a, I>(tokens: &mut Peekable<I>) -> Result<StyleEntry, ParseError>
+where
+    I: Iterator<Item = Token<'a>> + Clone,
+{
+    let mut ahead = tokens.clone();
+    let is_var = ahead
+        .next()
+        .is_some_and(|token| token.kind == TokenKind::Ident("var"));
+
+    if is_var && is(&mut ahead, TokenKind::OpenParen) {
+        return parse_var(tokens);
+    }
+
+    if let Some(Token {
+        kind: TokenKind::Ident(ident),
+        ..
+    }) = tokens.peek()
+    {
+        let mut key = String::from(*ident);
+        next(tokens)?;
+
+        while is(tokens, TokenKind::Dot) {
+            next(tokens)?;
+            let ident = expect_ident(next(tokens)?)?;
+            key.push('.');
+            key.push_str(ident);
+        }
+
+        return Ok(Styled::Style(Style::from_string(key)));
+    }
+
+    let mut items = vec![parse_scalar(tokens)?];
+
+    // the list continues if the next item is a value and not a property,
+    // eg. `box-shadow: 0 1px 2px black, 0 2px 4px gray,`
+    loop {
+        let mut ahead = tokens.clone();
+
+        let is_comma = matches!(ahead.next(), Some(token) if token.kind == TokenKind::Comma);
+        let is_value = matches!(
+            ahead.peek().map(|token| &token.kind),
+            Some(
+                TokenKind::String(_)
+                    | TokenKind::Number(_)
+                    | TokenKind::True
+                    | TokenKind::False
+                    | TokenKind::Color(_)
+                    | TokenKind::OpenBracket
+            )
+        );
+
+        if !(is_comma && is_value) {
+            break;
+        }
+
+        next(tokens)?;
+        items.push(parse_scalar(tokens)?);
+    }
+
+    let value = match items.len() {
+        1 => items.pop().unwrap(),
+        _ => StyleValue::List(items),
+    };
+
+    Ok(Styled::Value(value.into_any()?))
+}
+
+// a variable reference with an optional fallback, eg. `var(--accent, #3b82f6)`
+fn parse_var<'a, I>(tokens: &mut Peekable<I>) -> Result<StyleEntry, ParseError>
+where
+    I: Iterator<Item = Token<'a>> + Clone,
+{
+    next(tokens)?;
+    next(tokens)?;
+
+    let name = expect_ident(next(tokens)?)?;
 
-            Ok(Styled::Style(Style::from_string(key)))
+    if !name.starts_with("--") {
+        return Err(ParseError {
+            message: format!(
+                "expected variable name starting with '--', found {:?}",
+                name
+            ),
+        });
+    }
+
+    let fallback = match is(tokens, TokenKind::Comma) {
+        true => {
+            next(tokens)?;
+            Some(parse_value(tokens)?)
         }
+        false => None,
+    };
+
+    if !is(tokens, TokenKind::CloseParen) {
+        return Err(ParseError {
+            message: "expected ')'".to_string(),
+        });
+    }
+
+    next(tokens)?;
+
+    let var = StyleVar {
+        style: Style::from_string(name.to_string()),
+        fallback,
+    };
+
+    Ok(Styled::Value(Arc::new(var)))
+}
+
+fn parse_scalar<'a, I>(tokens: &mut Peekable<I>) -> Result<StyleValue, ParseError>
+where
+    I: Iterator<Item = Token<'a>>,
+{
+    let token = next(tokens)?;
+
+    match token.kind {
+        TokenKind::String(s) => Ok(StyleValue::String(s.to_string())),
+        TokenKind::Number(n) => parse_numbers(tokens, n),
+        TokenKind::True => Ok(StyleValue::Bool(true)),
+        TokenKind::False => Ok(StyleValue::Bool(false)),
+        TokenKind::Color(color) => Ok(StyleValue::Color(color)),
 
         TokenKind::OpenBracket => {
             let mut v = Vec::new();
@@ -327,8 +654,8 @@ where
             next(tokens)?;
 
             match v.len() {
-                2 => Ok(Styled::Value(Arc::new([v[0], v[1]]))),
-                4 => Ok(Styled::Value(Arc::new([v[0], v[1], v[2], v[3]]))),
+                2 => Ok(StyleValue::Vec2([v[0], v[1]])),
+                4 => Ok(StyleValue::Vec4([v[0], v[1], v[2], v[3]])),
                 _ => Err(ParseError {
                     message: format!("expected 2 or 4 numbers, found {}", v.len()),
                 }),
@@ -341,6 +668,74 @@ where
     }
 }
 
+// parse a number, or a space separated shadow, eg. `0 1px 2px black`
+fn parse_numbers<'a, I>(tokens: &mut Peekable<I>, first: f32) -> Result<StyleValue, ParseError>
+where
+    I: Iterator<Item = Token<'a>>,
+{
+    let mut numbers = vec![first];
+    skip_px(tokens)?;
+
+    while let Some(Token {
+        kind: TokenKind::Number(n),
+        ..
+    }) = tokens.peek()
+    {
+        numbers.push(*n);
+        next(tokens)?;
+        skip_px(tokens)?;
+    }
+
+    if numbers.len() == 1 {
+        return Ok(StyleValue::Number(first));
+    }
+
+    let token = next(tokens)?;
+
+    let color = match token.kind {
+        TokenKind::Color(color) => color,
+        TokenKind::Ident(ident) => Color::named(ident).ok_or_else(|| ParseError {
+            message: format!("unknown color: {:?}", ident),
+        })?,
+        _ => {
+            return Err(ParseError {
+                message: format!("expected shadow color, found {:?}", token.kind),
+            });
+        }
+    };
+
+    match numbers[..] {
+        [x, y] => Ok(StyleValue::Shadow(Shadow::new(
+            Vector::new(x, y),
+            0.0,
+            color,
+        ))),
+        [x, y, blur] => Ok(StyleValue::Shadow(Shadow::new(
+            Vector::new(x, y),
+            blur,
+            color,
+        ))),
+        [x, y, blur, spread] => {
+            let shadow = Shadow::new(Vector::new(x, y), blur, color).spread(spread);
+            Ok(StyleValue::Shadow(shadow))
+        }
+        _ => Err(ParseError {
+            message: format!("expected 2 to 4 shadow lengths, found {}", numbers.len()),
+        }),
+    }
+}
+
+fn skip_px<'a, I>(tokens: &mut Peekable<I>) -> Result<(), ParseError>
+where
+    I: Iterator<Item = Token<'a>>,
+{
+    if is(tokens, TokenKind::Ident("px")) {
+        next(tokens)?;
+    }
+
+    Ok(())
+}
+
 fn is<'a, I>(tokens: &mut Peekable<I>, kind: TokenKind) -> bool
 where
     I: Iterator<Item = Token<'a>>,
@@ -357,6 +752,18 @@ where
     })
 }
 
+// a class in a selector, the leading `.` is optional, eg. `.sidebar` and `sidebar` are the same
+fn parse_class<'a, I>(tokens: &mut Peekable<I>) -> Result<&'a str, ParseError>
+where
+    I: Iterator<Item = Token<'a>>,
+{
+    if is(tokens, TokenKind::Dot) {
+        next(tokens)?;
+    }
+
+    expect_ident(next(tokens)?)
+}
+
 fn expect_ident(token: Token<'_>) -> Result<&str, ParseError> {
     match token.kind {
         TokenKind::Ident(ident) => Ok(ident),
@@ -365,3 +772,145 @@ fn expect_ident(token: Token<'_>) -> Result<&str, ParseError> {
         }),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hex_color_keeps_following_token() {
+        let kinds = tokenize("#ff0000,}")
+            .unwrap()
+            .map(|token| token.kind)
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            kinds,
+            [
+                TokenKind::Color(Color::rgb(1.0, 0.0, 0.0)),
+                TokenKind::Comma,
+                TokenKind::CloseBrace,
+            ],
+        );
+    }
+
+    #[test]
+    fn transition_shorthand() {
+        let styles: Styles = r#"
+            button {
+                transition: background-color 0.2s ease-out srgb, border-color 100ms,
+                color: #ff0000,
+            }
+        "#
+        .parse()
+        .unwrap();
+
+        let background = Style::<Color>::new("button.background-color");
+        let border = Style::<Color>::new("button.border-color");
+        let color = Style::<Color>::new("button.color");
+
+        assert_eq!(
+            styles.get_transition(&background),
+            Some(Transition {
+                duration: 0.2,
+                easing: Easing::EaseOut,
+                interpolation: ColorInterpolation::Srgb,
+            }),
+        );
+
+        assert_eq!(styles.get_transition(&border), Some(Transition::ease(0.1)));
+        assert_eq!(styles.get_transition(&color), None);
+        assert_eq!(styles.get(&color), Some(Color::rgb(1.0, 0.0, 0.0)));
+    }
+
+    #[test]
+    fn shadow_list() {
+        let styles: Styles = r#"
+            button {
+                box-shadow: 0 1px 2px black, 0 2px 4px gray,
+                border-radius: 4,
+            }
+        "#
+        .parse()
+        .unwrap();
+
+        let shadows = Style::<Vec<Shadow>>::new("button.box-shadow");
+        let radius = Style::<f32>::new("button.border-radius");
+
+        assert_eq!(
+            styles.get(&shadows),
+            Some(vec![
+                Shadow::new(Vector::new(0.0, 1.0), 2.0, Color::BLACK),
+                Shadow::new(Vector::new(0.0, 2.0), 4.0, Color::hex("#808080")),
+            ]),
+        );
+
+        assert_eq!(styles.get(&radius), Some(4.0));
+    }
+
+    #[test]
+    fn descendant_and_child_selectors() {
+        let mut styles: Styles = r#"
+            button {
+                color: #0000ff,
+            }
+
+            .sidebar button {
+                color: #ff0000,
+            }
+
+            toolbar > button {
+                color: #00ff00,
+            }
+        "#
+        .parse()
+        .unwrap();
+
+        let color = Style::<Color>::new("button.color");
+
+        let red = Some(Color::rgb(1.0, 0.0, 0.0));
+        let green = Some(Color::rgb(0.0, 1.0, 0.0));
+        let blue = Some(Color::rgb(0.0, 0.0, 1.0));
+
+        assert_eq!(styles.get(&color), blue);
+        assert_eq!(styles.with_class("card", |s| s.get(&color)), blue);
+
+        // the sidebar doesn't have to be the direct parent
+        let nested = styles.with_class("sidebar", |s| s.with_class("card", |s| s.get(&color)));
+        assert_eq!(nested, red);
+
+        assert_eq!(styles.with_class("toolbar", |s| s.get(&color)), green);
+
+        let nested = styles.with_class("toolbar", |s| s.with_class("card", |s| s.get(&color)));
+        assert_eq!(nested, blue);
+    }
+
+    #[test]
+    fn variables() {
+        let styles: Styles = r#"
+            --accent: #0000ff,
+
+            button {
+                color: var(--accent),
+                border-color: var(--border, #ff0000),
+            }
+
+            sidebar {
+                --accent: #00ff00,
+            }
+        "#
+        .parse()
+        .unwrap();
+
+        let color = Style::<Color>::new("button.color");
+        let border = Style::<Color>::new("button.border-color");
+
+        assert_eq!(styles.get(&color), Some(Color::rgb(0.0, 0.0, 1.0)));
+        assert_eq!(styles.get(&border), Some(Color::rgb(1.0, 0.0, 0.0)));
+
+        // variables are resolved with the classes of the reader
+        let mut styles = styles;
+        let sidebar = styles.with_class("sidebar", |s| s.get(&color));
+        assert_eq!(sidebar, Some(Color::rgb(0.0, 1.0, 0.0)));
+    }
+}
@@ -1,6 +1,7 @@
 use std::{
     any::{Any, TypeId},
     borrow::Cow,
+    cell::RefCell,
     collections::HashMap,
     fmt::Debug,
     hash::{BuildHasherDefault, Hasher},
@@ -12,6 +13,8 @@ use std::{
 
 use seahash::SeaHasher;
 
+use crate::transition::Transition;
+
 #[repr(transparent)]
 #[derive(Clone, Copy)]
 struct StylesHasher(u64);
@@ -48,6 +51,9 @@ pub struct Styles {
     /// The root style set.
     root: StyleSet,
 
+    /// The default styles of components, used when the root sets nothing.
+    defaults: StyleSet,
+
     /// The set of style converters.
     converters: HashMap<(TypeId, TypeId), StyleConverter>,
 
@@ -57,13 +63,17 @@ pub struct Styles {
 
 impl Debug for Styles {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("Styles").field("root", &self.root).finish()
+        f.debug_struct("Styles")
+            .field("root", &self.root)
+            .field("defaults", &self.defaults)
+            .finish()
     }
 }
 
 #[derive(Clone, Debug, Default)]
 struct StyleSet {
     classes: HashMap<u64, StyleSet, BuildStyleHasher>,
+    children: HashMap<u64, StyleSet, BuildStyleHasher>,
     styles: HashMap<u64, StyleEntry, BuildStyleHasher>,
 }
 
@@ -73,12 +83,22 @@ impl StyleSet {
             self.classes.entry(key).or_default().extend(value);
         }
 
+        for (key, value) in other.children {
+            self.children.entry(key).or_default().extend(value);
+        }
+
         self.styles.extend(other.styles);
     }
 }
 
+// a reference to a variable with a fallback, eg. `var(--accent, #3b82f6)`
+pub(crate) struct StyleVar {
+    pub(crate) style: Style<()>,
+    pub(crate) fallback: Option<StyleEntry>,
+}
+
 type BuildStyleHasher = BuildHasherDefault<StylesHasher>;
-type StyleEntry = Styled<Arc<dyn Any + Send + Sync>>;
+pub(crate) type StyleEntry = Styled<Arc<dyn Any + Send + Sync>>;
 type StyleConverter = Arc<dyn Fn(Arc<dyn Any + Send + Sync>) -> Arc<dyn Any + Send + Sync>>;
 type StyleKey = (u64, TypeId);
 type CacheEntry = Option<Arc<dyn Any + Send + Sync>>;
@@ -90,8 +110,10 @@ impl Styles {
             stack: Vec::new(),
             root: StyleSet {
                 classes: HashMap::default(),
+                children: HashMap::default(),
                 styles: HashMap::default(),
             },
+            defaults: StyleSet::default(),
             converters: HashMap::default(),
             cache: Mutex::new(HashMap::default()),
         }
@@ -129,6 +151,16 @@ impl Styles {
         self.stack.pop().is_some()
     }
 
+    /// Get the number of classes on the stack.
+    pub(crate) fn class_depth(&self) -> usize {
+        self.stack.len()
+    }
+
+    /// Pop classes from the stack until there are `depth` left.
+    pub(crate) fn truncate_classes(&mut self, depth: usize) {
+        self.stack.truncate(depth);
+    }
+
     /// Run a closure within a context of a class.
     pub fn with_class<T>(&mut self, class: &str, f: impl FnOnce(&mut Self) -> T) -> T {
         let class = hash_style_key(class.as_bytes());
@@ -141,6 +173,15 @@ impl Styles {
     }
 
     /// Insert a style into the styles.
+    ///
+    /// The classes of the key match any ancestor, eg. `sidebar.button.color` applies to
+    /// buttons anywhere inside a `sidebar`. A class prefixed with `>` only matches if it's the
+    /// class of the closest ancestor with a class, eg. `toolbar.>button.color`.
+    ///
+    /// Styles can refer to other styles with [`Styled::Style`], which is how variables like
+    /// `--accent` are referenced, eg. `styles.insert(button_color, style("--accent"))`. The
+    /// reference is resolved when the style is read, with the classes of the reader, so a
+    /// variable can be overridden for a part of the user interface, eg. `sidebar.--accent`.
     pub fn insert<T>(&mut self, style: Style<T>, value: impl Into<Styled<T>>)
     where
         T: Clone + Send + Sync + 'static,
@@ -159,14 +200,18 @@ impl Styles {
     }
 
     pub(crate) fn insert_entry(&mut self, key: &str, entry: StyleEntry) {
-        let mut classes = key.split('.').map(str::as_bytes).map(hash_style_key);
+        let mut classes = key.split('.');
 
-        let last = classes.next_back().unwrap();
+        let last = hash_style_key(classes.next_back().unwrap().as_bytes());
 
         let mut current = &mut self.root;
 
         for class in classes {
-            current = current.classes.entry(class).or_default();
+            current = match class.strip_prefix('>') {
+                Some(child) => current.children.entry(hash_style_key(child.as_bytes())),
+                None => current.classes.entry(hash_style_key(class.as_bytes())),
+            }
+            .or_default();
         }
 
         current.styles.insert(last, entry);
@@ -187,10 +232,37 @@ impl Styles {
         let other = other.into();
 
         self.root.extend(other.root);
+        self.defaults.extend(other.defaults);
         self.converters.extend(other.converters);
         let _ = self.cache.get_mut().map(HashMap::clear);
     }
 
+    /// Register the default styles of a component.
+    ///
+    /// Defaults are only used when nothing else sets the style, so they can be overridden by
+    /// stylesheets no matter the order they're added in. This lets components outside of this
+    /// crate be themed like the built-in ones, by reading their styles with [`Styled::style`]
+    /// and registering the defaults when the application is built.
+    ///
+    /// Keys are named `<component>.<property>`, in kebab-case, eg. `button.color` or
+    /// `button.border-radius`, and can be declared as constants, eg.
+    /// `const COLOR: Style<Color> = Style::new("button.color")`. Defaults can refer to the
+    /// theme, eg. `style(Theme::PRIMARY)`, so they follow it when it changes.
+    pub fn register_defaults(&mut self, defaults: impl Into<Styles>) {
+        let defaults = defaults.into();
+
+        self.defaults.extend(defaults.root);
+        self.defaults.extend(defaults.defaults);
+        self.converters.extend(defaults.converters);
+        let _ = self.cache.get_mut().map(HashMap::clear);
+    }
+
+    /// Register the default styles of a component, see [`Styles::register_defaults`].
+    pub fn with_defaults(mut self, defaults: impl Into<Styles>) -> Self {
+        self.register_defaults(defaults);
+        self
+    }
+
     /// Get a value from the styles.
     #[inline(always)]
     pub fn get<T>(&self, style: &Style<T>) -> Option<T>
@@ -240,18 +312,84 @@ impl Styles {
         Some(style)
     }
 
+    /// Get an inherited value from the styles.
+    ///
+    /// Inherited properties are looked up on the classes on the stack, from the closest
+    /// ancestor to the furthest, eg. `container { text-color: red }` sets the color of every
+    /// text inside a container that doesn't set its own. `property` is the name of the
+    /// property, without any classes, eg. `text-color` or `font-size`.
+    pub fn get_inherited<T>(&self, property: &Style<T>) -> Option<T>
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        // inherited values are cached apart from the styles of the same key
+        const INHERITED: u64 = hash_style_key(b"inherited");
+
+        let stack_hash = hash_style_key_u64(self.stack.as_slice());
+        let key = (property.hash ^ stack_hash ^ INHERITED, TypeId::of::<T>());
+
+        if let Some(entry) = self.cache.lock().unwrap().get(&key) {
+            let entry = entry.as_ref()?;
+            let style = entry.downcast_ref::<T>()?;
+
+            return Some(style.clone());
+        }
+
+        let entry = (1..=self.stack.len()).rev().find_map(|depth| {
+            let (&class, outer) = self.stack[..depth].split_last()?;
+
+            // the class of the ancestor has to set the property itself, the outer classes
+            // only narrow down where it applies, like in any other style
+            let classes = outer
+                .iter()
+                .map(|&class| (class, false))
+                .chain([(class, true), (property.hash, true)])
+                .collect::<Vec<_>>();
+
+            self.lookup(&classes)
+        });
+
+        let value = entry.and_then(|entry| self.resolve::<T>(entry));
+
+        let cache_entry: CacheEntry = value.clone().map(|value| Arc::new(value) as _);
+        self.cache.lock().unwrap().insert(key, cache_entry);
+
+        value
+    }
+
     fn get_inner<T>(&self, classes: &[(u64, bool)]) -> Option<T>
     where
         T: Clone + Send + Sync + 'static,
     {
-        let entry = Self::get_uncached(&self.root, classes.iter().copied())?;
+        let entry = self.lookup(classes)?;
+        self.resolve(entry)
+    }
+
+    // the registered defaults are only used when the root doesn't set the style
+    fn lookup(&self, classes: &[(u64, bool)]) -> Option<&StyleEntry> {
+        let classes = classes.iter().copied();
 
+        Self::get_uncached(&self.root, classes.clone(), false)
+            .or_else(|| Self::get_uncached(&self.defaults, classes, false))
+    }
+
+    fn resolve<T>(&self, entry: &StyleEntry) -> Option<T>
+    where
+        T: Clone + Send + Sync + 'static,
+    {
         match entry {
             Styled::Value(value) => {
                 if let Some(value) = value.downcast_ref::<T>() {
                     return Some(value.clone());
                 }
 
+                if let Some(var) = value.downcast_ref::<StyleVar>() {
+                    return match self.get_referenced(var.style.cast()) {
+                        Some(value) => Some(value),
+                        None => self.resolve(var.fallback.as_ref()?),
+                    };
+                }
+
                 let signature = (value.as_ref().type_id(), TypeId::of::<T>());
 
                 match self.converters.get(&signature) {
@@ -270,11 +408,49 @@ impl Styles {
                     }
                 }
             }
-            Styled::Style(style) => self.get(style.cast()),
+            Styled::Style(style) => self.get_referenced(style.cast()),
             Styled::Computed(..) => todo!(),
         }
     }
 
+    // get a style referred to by another, a style that refers to itself, directly or through
+    // other styles, eg. `--a: var(--a)`, is treated as unset
+    fn get_referenced<T>(&self, style: &Style<T>) -> Option<T>
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        thread_local! {
+            static RESOLVING: RefCell<Vec<u64>> = const { RefCell::new(Vec::new()) };
+        }
+
+        // pops the style when dropped, so a panic while resolving it doesn't leave it behind
+        struct Resolving;
+
+        impl Drop for Resolving {
+            fn drop(&mut self) {
+                RESOLVING.with_borrow_mut(Vec::pop);
+            }
+        }
+
+        let is_cycle = RESOLVING.with_borrow_mut(|resolving| {
+            let is_cycle = resolving.contains(&style.hash);
+
+            if !is_cycle {
+                resolving.push(style.hash);
+            }
+
+            is_cycle
+        });
+
+        if is_cycle {
+            tracing::warn!("style '{}' refers to itself", style.key);
+            return None;
+        }
+
+        let _resolving = Resolving;
+        self.get(style)
+    }
+
     /// Get a value from the styles.
     #[inline(always)]
     pub fn get_or<T>(&self, default: T, style: &Style<T>) -> T
@@ -294,9 +470,31 @@ impl Styles {
         self.get(style).unwrap_or_else(default)
     }
 
+    /// Get the transition of a style.
+    ///
+    /// Transitions are stored under `<key>.transition`, usually by the `transition` shorthand,
+    /// eg. `button { transition: color 0.2s ease-out, }` sets the transition of `button.color`.
+    pub fn get_transition<T: ?Sized>(&self, style: &Style<T>) -> Option<Transition> {
+        let key = format!("{}.transition", style.key);
+        self.get(&Style::from_string(key))
+    }
+
+    /// Get the transition of a styled property, see [`Styles::get_transition`].
+    ///
+    /// Returns `None` if `property` isn't a style key, or no transition is set for it.
+    pub fn get_property_transition<T>(&self, property: &Styled<T>) -> Option<Transition> {
+        match property {
+            Styled::Style(style) => self.get_transition(style),
+            _ => None,
+        }
+    }
+
+    // `adjacent` is whether the class of `style_set` was the previous class, child sets only
+    // match if no class was skipped since then
     fn get_uncached(
         style_set: &StyleSet,
         mut classes: impl ExactSizeIterator<Item = (u64, bool)> + Clone,
+        adjacent: bool,
     ) -> Option<&StyleEntry> {
         let (class, required) = classes.next()?;
 
@@ -304,8 +502,16 @@ impl Styles {
             return style_set.styles.get(&class);
         }
 
+        if adjacent {
+            if let Some(next_set) = style_set.children.get(&class) {
+                if let Some(entry) = Self::get_uncached(next_set, classes.clone(), true) {
+                    return Some(entry);
+                }
+            }
+        }
+
         if let Some(next_set) = style_set.classes.get(&class) {
-            if let Some(entry) = Self::get_uncached(next_set, classes.clone()) {
+            if let Some(entry) = Self::get_uncached(next_set, classes.clone(), true) {
                 return Some(entry);
             }
         }
@@ -314,7 +520,7 @@ impl Styles {
             return None;
         }
 
-        Self::get_uncached(style_set, classes)
+        Self::get_uncached(style_set, classes, false)
     }
 }
 
@@ -323,6 +529,7 @@ impl Clone for Styles {
         Self {
             stack: self.stack.clone(),
             root: self.root.clone(),
+            defaults: self.defaults.clone(),
             converters: self.converters.clone(),
             cache: Mutex::new(HashMap::default()),
         }
@@ -544,3 +751,118 @@ const fn hash_style_key_u64(bytes: &[u64]) -> u64 {
 
     hash
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        canvas::Color,
+        layout::Space,
+        view::View,
+        views::{hstack, painter, size, testing::ViewTester},
+    };
+
+    use super::*;
+
+    // a view that records the color of `key`, every time it's drawn
+    fn swatch(key: &'static str, color: &Arc<Mutex<Option<Color>>>) -> impl View {
+        let color = color.clone();
+
+        size(
+            10.0,
+            painter(move |cx, _| {
+                *color.lock().unwrap() = cx.styles().get(&Style::new(key));
+            }),
+        )
+    }
+
+    #[test]
+    fn changing_a_variable_updates_every_reader() {
+        let button = Arc::new(Mutex::new(None));
+        let card = Arc::new(Mutex::new(None));
+
+        let mut view = hstack![
+            swatch("button.color", &button),
+            swatch("card.border-color", &card),
+        ];
+
+        let mut tester = ViewTester::new(&mut view, &mut ());
+
+        let styles: Styles = r#"
+            --accent: #0000ff,
+            button { color: var(--accent), }
+            card { border-color: var(--accent), }
+        "#
+        .parse()
+        .unwrap();
+
+        tester.contexts.insert(styles);
+
+        tester.layout(&mut view, &mut (), Space::UNBOUNDED);
+        tester.draw(&mut view, &mut ());
+        assert_eq!(*button.lock().unwrap(), Some(Color::rgb(0.0, 0.0, 1.0)));
+        assert_eq!(*card.lock().unwrap(), Some(Color::rgb(0.0, 0.0, 1.0)));
+
+        let styles = tester.contexts.get_mut::<Styles>().unwrap();
+        styles.insert(Style::new("--accent"), Color::rgb(1.0, 0.0, 0.0));
+
+        tester.draw(&mut view, &mut ());
+        assert_eq!(*button.lock().unwrap(), Some(Color::rgb(1.0, 0.0, 0.0)));
+        assert_eq!(*card.lock().unwrap(), Some(Color::rgb(1.0, 0.0, 0.0)));
+    }
+
+    #[test]
+    fn cyclic_variables_are_unset() {
+        let styles: Styles = r#"
+            --a: var(--a),
+            --b: var(--c),
+            --c: var(--b),
+
+            button {
+                color: var(--a),
+                border-color: var(--b, #ff0000),
+            }
+        "#
+        .parse()
+        .unwrap();
+
+        let color = Style::<Color>::new("button.color");
+        let border = Style::<Color>::new("button.border-color");
+
+        // the cycle is unset, so the default of the reader is used
+        assert_eq!(styles.get(&color), None);
+        assert_eq!(styles.get_or(Color::BLUE, &color), Color::BLUE);
+        assert_eq!(styles.get(&border), Some(Color::rgb(1.0, 0.0, 0.0)));
+    }
+
+    #[test]
+    fn registered_defaults_are_overridden_by_stylesheets() {
+        const COLOR: Style<Color> = Style::new("gauge.color");
+        const WIDTH: Style<f32> = Style::new("gauge.width");
+
+        let defaults = || {
+            let theme = Styles::new().with(Style::new("--accent"), Color::BLUE);
+            let gauge = Styles::new()
+                .with(COLOR, style("--accent"))
+                .with(WIDTH, 4.0);
+
+            (theme, gauge)
+        };
+
+        // nothing set by the user, the defaults follow the theme
+        let (mut styles, gauge) = defaults();
+        styles.register_defaults(gauge);
+        assert_eq!(styles.get(&COLOR), Some(Color::BLUE));
+        assert_eq!(styles.get(&WIDTH), Some(4.0));
+
+        // a stylesheet overrides the defaults, whether it's added after them or before
+        let user: Styles = "gauge { color: #ff0000, }".parse().unwrap();
+        styles.extend(user.clone());
+        assert_eq!(styles.get(&COLOR), Some(Color::rgb(1.0, 0.0, 0.0)));
+        assert_eq!(styles.get(&WIDTH), Some(4.0));
+
+        let (mut styles, gauge) = defaults();
+        styles.extend(user);
+        styles.register_defaults(gauge);
+        assert_eq!(styles.get(&COLOR), Some(Color::rgb(1.0, 0.0, 0.0)));
+    }
+}
@@ -2,6 +2,18 @@ use crate::canvas::Color;
 
 use super::{Style, Styles};
 
+/// Whether a theme is light or dark.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ColorScheme {
+    /// A light theme, with dark text on a light background.
+    Light,
+
+    /// A dark theme, with light text on a dark background.
+    #[default]
+    Dark,
+}
+
 /// A theme.
 #[derive(Clone, Copy, Debug)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -74,6 +86,72 @@ impl Theme {
             info: Color::hex("#639ff7"),
         }
     }
+
+    /// Generate a theme from a single `seed` color.
+    ///
+    /// The hue and chroma of the seed are used for the primary color, the secondary and
+    /// accent colors are derived by rotating the hue in the oklab color space. The
+    /// background, surface and outline are tinted by the seed, and the lightness of every
+    /// color is chosen by the `scheme`.
+    pub fn from_seed(seed: Color, scheme: ColorScheme) -> Self {
+        let (_, chroma, hue) = seed.to_oklch();
+
+        // keep some chroma, so the theme stays tinted by grayish seeds
+        let chroma = chroma.clamp(0.04, 0.2);
+
+        let role = |l: f32, c: f32, h: f32| gamut_oklch(l, c, (h + 360.0) % 360.0);
+
+        match scheme {
+            ColorScheme::Light => Self {
+                background: role(0.99, chroma * 0.04, hue),
+                surface: role(0.95, chroma * 0.1, hue),
+                outline: role(0.87, chroma * 0.1, hue),
+                contrast: role(0.18, chroma * 0.1, hue),
+                primary: role(0.55, chroma, hue),
+                secondary: role(0.6, chroma * 0.7, hue + 60.0),
+                accent: role(0.58, chroma, hue + 180.0),
+                danger: role(0.58, 0.2, 25.0),
+                success: role(0.7, 0.16, 150.0),
+                warning: role(0.85, 0.16, 95.0),
+                info: role(0.58, 0.14, 245.0),
+            },
+            ColorScheme::Dark => Self {
+                background: role(0.22, chroma * 0.06, hue),
+                surface: role(0.26, chroma * 0.1, hue),
+                outline: role(0.42, chroma * 0.1, hue),
+                contrast: role(0.97, chroma * 0.04, hue),
+                primary: role(0.72, chroma, hue),
+                secondary: role(0.72, chroma * 0.7, hue + 60.0),
+                accent: role(0.78, chroma, hue + 180.0),
+                danger: role(0.68, 0.18, 25.0),
+                success: role(0.85, 0.16, 140.0),
+                warning: role(0.9, 0.14, 100.0),
+                info: role(0.7, 0.13, 255.0),
+            },
+        }
+    }
+}
+
+// reduce the chroma of an oklch color until it fits in the sRGB gamut
+fn gamut_oklch(l: f32, mut c: f32, h: f32) -> Color {
+    loop {
+        let color = Color::oklch(l, c, h);
+
+        let in_gamut = [color.r, color.g, color.b]
+            .into_iter()
+            .all(|x| (0.0..=1.0).contains(&x));
+
+        if in_gamut || c <= 0.001 {
+            return Color::rgba(
+                color.r.clamp(0.0, 1.0),
+                color.g.clamp(0.0, 1.0),
+                color.b.clamp(0.0, 1.0),
+                1.0,
+            );
+        }
+
+        c *= 0.9;
+    }
 }
 
 impl From<Theme> for Styles {
@@ -169,3 +247,31 @@ impl Theme {
     pub const INFO: Style<Color> = Style::new("theme.info");
     pub const INFO_LOW: Style<Color> = Style::new("theme.info_low");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_seed_contrast() {
+        let seeds = [
+            Color::hex("#1c71d8"),
+            Color::hex("#f6d32d"),
+            Color::hex("#e01b24"),
+            Color::hex("#808080"),
+        ];
+
+        for seed in seeds {
+            for scheme in [ColorScheme::Light, ColorScheme::Dark] {
+                let theme = Theme::from_seed(seed, scheme);
+
+                // WCAG AA for normal text
+                let ratio = theme.contrast.contrast_ratio(theme.background);
+                assert!(ratio >= 4.5, "{:?} {:?}: {}", seed, scheme, ratio);
+
+                let ratio = theme.contrast.contrast_ratio(theme.surface);
+                assert!(ratio >= 4.5, "{:?} {:?}: {}", seed, scheme, ratio);
+            }
+        }
+    }
+}
@@ -1,4 +1,7 @@
-use std::hash::{Hash, Hasher};
+use std::{
+    borrow::Cow,
+    hash::{Hash, Hasher},
+};
 
 use smol_str::SmolStr;
 
@@ -123,6 +126,12 @@ impl From<String> for FontWeight {
     }
 }
 
+impl From<FontWeight> for f32 {
+    fn from(weight: FontWeight) -> Self {
+        weight.0 as f32
+    }
+}
+
 /// A font stretch.
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
 pub enum FontStretch {
@@ -209,6 +218,58 @@ impl From<String> for FontStyle {
     }
 }
 
+/// A position on a variation axis of a variable font, eg. `wght` for weight.
+///
+/// Variable fonts contain a continuous range of styles in a single file, a light and a bold
+/// weight can be picked from the same font, along with every weight in between.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FontVariation {
+    /// The four byte tag of the axis, eg. `*b"wght"`.
+    pub tag: [u8; 4],
+
+    /// The value on the axis.
+    pub value: f32,
+}
+
+impl FontVariation {
+    /// The weight axis, in the range `1.0..=1000.0`.
+    pub const WEIGHT: [u8; 4] = *b"wght";
+
+    /// The width axis, in percent of the normal width.
+    pub const WIDTH: [u8; 4] = *b"wdth";
+
+    /// The slant axis, in degrees.
+    pub const SLANT: [u8; 4] = *b"slnt";
+
+    /// Create a new [`FontVariation`].
+    ///
+    /// Tags shorter than four bytes are padded with spaces, as in the OpenType specification.
+    pub fn new(tag: &str, value: f32) -> Self {
+        let mut bytes = [b' '; 4];
+
+        for (byte, tag) in bytes.iter_mut().zip(tag.bytes()) {
+            *byte = tag;
+        }
+
+        Self { tag: bytes, value }
+    }
+
+    /// Get the tag as a string.
+    pub fn tag_str(&self) -> &str {
+        std::str::from_utf8(&self.tag).unwrap_or("")
+    }
+}
+
+impl Eq for FontVariation {}
+
+impl Hash for FontVariation {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.tag.hash(state);
+        self.value.to_bits().hash(state);
+    }
+}
+
 /// Alignment of a section of text.
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -283,6 +344,82 @@ impl From<String> for TextWrap {
     }
 }
 
+/// Handling of whitespace in a section of text.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum WhiteSpace {
+    /// Collapse every run of whitespace, including newlines, into a single space.
+    Normal,
+
+    /// Preserve whitespace, never wrapping lines.
+    Pre,
+
+    /// Preserve whitespace, wrapping lines as usual.
+    #[default]
+    PreWrap,
+}
+
+impl WhiteSpace {
+    /// Apply the whitespace handling to `text`.
+    ///
+    /// Preserved tabs are kept in the text, and advance to the next tab stop when the text is
+    /// laid out, see [`Paragraph::tab_size`](super::Paragraph::tab_size).
+    pub fn apply(self, text: &str) -> Cow<'_, str> {
+        match self {
+            Self::Normal => collapse_white_space(text),
+            Self::Pre | Self::PreWrap => Cow::Borrowed(text),
+        }
+    }
+
+    /// Get the wrapping used for text with this whitespace handling.
+    pub fn wrap(self, wrap: TextWrap) -> TextWrap {
+        match self {
+            Self::Pre => TextWrap::None,
+            Self::Normal | Self::PreWrap => wrap,
+        }
+    }
+}
+
+fn collapse_white_space(text: &str) -> Cow<'_, str> {
+    let mut collapsed = String::with_capacity(text.len());
+    let mut last_was_space = false;
+
+    for c in text.chars() {
+        if c.is_whitespace() {
+            if !last_was_space {
+                collapsed.push(' ');
+            }
+
+            last_was_space = true;
+        } else {
+            collapsed.push(c);
+            last_was_space = false;
+        }
+    }
+
+    match collapsed == text {
+        true => Cow::Borrowed(text),
+        false => Cow::Owned(collapsed),
+    }
+}
+
+impl From<&str> for WhiteSpace {
+    fn from(white_space: &str) -> Self {
+        match white_space {
+            "normal" => Self::Normal,
+            "pre" => Self::Pre,
+            "pre-wrap" => Self::PreWrap,
+            _ => Self::PreWrap,
+        }
+    }
+}
+
+impl From<String> for WhiteSpace {
+    fn from(white_space: String) -> Self {
+        Self::from(white_space.as_str())
+    }
+}
+
 /// Attributes of a section of text.
 #[derive(Clone, Debug, PartialEq)]
 pub struct FontAttributes {
@@ -306,6 +443,9 @@ pub struct FontAttributes {
 
     /// The color of the font.
     pub color: Color,
+
+    /// The positions on the variation axes of the font, only used by variable fonts.
+    pub variations: Vec<FontVariation>,
 }
 
 impl Default for FontAttributes {
@@ -318,6 +458,7 @@ impl Default for FontAttributes {
             style: FontStyle::Normal,
             ligatures: true,
             color: Color::BLACK,
+            variations: Vec::new(),
         }
     }
 }
@@ -333,5 +474,6 @@ impl Hash for FontAttributes {
         self.style.hash(state);
         self.ligatures.hash(state);
         self.color.hash(state);
+        self.variations.hash(state);
     }
 }
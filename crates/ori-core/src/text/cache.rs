@@ -0,0 +1,257 @@
+use std::{collections::HashMap, hash::BuildHasherDefault};
+
+use seahash::SeaHasher;
+
+use crate::layout::Size;
+
+use super::{Paragraph, TextLayoutLine};
+
+/// A cache of laid out text, for implementors of [`Fonts`](super::Fonts).
+///
+/// Shaping is the expensive part of laying out text, and static labels are laid out with the
+/// same paragraph and width every frame. Entries are keyed on the whole [`Paragraph`], its
+/// text, attributes, line height, alignment and wrapping, and the max width, so anything that
+/// affects shaping misses the cache. When full, the least recently used entry is evicted.
+#[derive(Clone, Debug)]
+pub struct TextLayoutCache {
+    entries: HashMap<TextLayoutKey, TextLayoutEntry, BuildHasherDefault<SeaHasher>>,
+    capacity: usize,
+    clock: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct TextLayoutKey {
+    paragraph: Paragraph,
+    width: u32,
+}
+
+impl TextLayoutKey {
+    fn new(paragraph: &Paragraph, width: f32) -> Self {
+        Self {
+            paragraph: paragraph.clone(),
+            // -0.0 and 0.0 lay out the same
+            width: (width + 0.0).to_bits(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+struct TextLayoutEntry {
+    lines: Option<Vec<TextLayoutLine>>,
+    size: Option<Size>,
+    used: u64,
+}
+
+impl Default for TextLayoutCache {
+    fn default() -> Self {
+        Self::new(256)
+    }
+}
+
+impl TextLayoutCache {
+    /// Create a new cache holding at most `capacity` laid out paragraphs.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: HashMap::default(),
+            capacity: capacity.max(1),
+            clock: 0,
+        }
+    }
+
+    /// Get the maximum number of cached paragraphs.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Get the number of cached paragraphs.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Check whether the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Remove every cached layout, eg. when the loaded fonts change.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Get the cached lines of `paragraph` laid out with `width`.
+    pub fn lines(&mut self, paragraph: &Paragraph, width: f32) -> Option<Vec<TextLayoutLine>> {
+        let key = TextLayoutKey::new(paragraph, width);
+        self.get(&key)?.lines.clone()
+    }
+
+    /// Get the cached size of `paragraph` measured with `width`.
+    pub fn size(&mut self, paragraph: &Paragraph, width: f32) -> Option<Size> {
+        let key = TextLayoutKey::new(paragraph, width);
+        self.get(&key)?.size
+    }
+
+    /// Cache the lines of `paragraph` laid out with `width`.
+    pub fn insert_lines(&mut self, paragraph: &Paragraph, width: f32, lines: Vec<TextLayoutLine>) {
+        self.entry(TextLayoutKey::new(paragraph, width)).lines = Some(lines);
+    }
+
+    /// Cache the size of `paragraph` measured with `width`.
+    pub fn insert_size(&mut self, paragraph: &Paragraph, width: f32, size: Size) {
+        self.entry(TextLayoutKey::new(paragraph, width)).size = Some(size);
+    }
+
+    fn get(&mut self, key: &TextLayoutKey) -> Option<&TextLayoutEntry> {
+        self.clock += 1;
+
+        let entry = self.entries.get_mut(key)?;
+        entry.used = self.clock;
+        Some(entry)
+    }
+
+    fn entry(&mut self, key: TextLayoutKey) -> &mut TextLayoutEntry {
+        self.clock += 1;
+
+        if !self.entries.contains_key(&key) && self.entries.len() >= self.capacity {
+            self.evict();
+        }
+
+        let entry = self.entries.entry(key).or_default();
+        entry.used = self.clock;
+        entry
+    }
+
+    // evict the least recently used entry, the cache is small enough that a scan is cheaper
+    // than keeping the entries ordered on every hit
+    fn evict(&mut self) {
+        let oldest = (self.entries.iter())
+            .min_by_key(|(_, entry)| entry.used)
+            .map(|(key, _)| key.clone());
+
+        if let Some(oldest) = oldest {
+            self.entries.remove(&oldest);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        layout::{Point, Rect},
+        text::{FontAttributes, GlyphCluster, TextAlign, TextDirection, TextWrap},
+    };
+
+    use super::*;
+
+    // lays out every character as a 10 by 20 glyph on a single line, counting the calls
+    fn shape(shaped: &mut u32, paragraph: &Paragraph) -> Vec<TextLayoutLine> {
+        *shaped += 1;
+
+        let glyphs = (paragraph.text().char_indices())
+            .map(|(i, c)| GlyphCluster {
+                bounds: Rect::min_size(Point::new(i as f32 * 10.0, 0.0), Size::new(10.0, 20.0)),
+                range: i..i + c.len_utf8(),
+                direction: TextDirection::Ltr,
+            })
+            .collect::<Vec<_>>();
+
+        vec![TextLayoutLine {
+            ascent: 16.0,
+            descent: 4.0,
+            left: 0.0,
+            width: glyphs.len() as f32 * 10.0,
+            height: 20.0,
+            baseline: 16.0,
+            range: 0..paragraph.text().len(),
+            glyphs,
+        }]
+    }
+
+    fn layout(
+        cache: &mut TextLayoutCache,
+        shaped: &mut u32,
+        paragraph: &Paragraph,
+        width: f32,
+    ) -> Vec<TextLayoutLine> {
+        if let Some(lines) = cache.lines(paragraph, width) {
+            return lines;
+        }
+
+        let lines = shape(shaped, paragraph);
+        cache.insert_lines(paragraph, width, lines.clone());
+        lines
+    }
+
+    fn paragraph(text: &str, attrs: FontAttributes) -> Paragraph {
+        let mut paragraph = Paragraph::new(1.2, TextAlign::Start, TextWrap::Word);
+        paragraph.set_text(text, attrs);
+        paragraph
+    }
+
+    fn bounds(lines: &[TextLayoutLine]) -> Vec<(Rect, Vec<Rect>)> {
+        let glyphs = |line: &TextLayoutLine| line.glyphs.iter().map(|g| g.bounds).collect();
+        lines.iter().map(|l| (l.bounds(), glyphs(l))).collect()
+    }
+
+    #[test]
+    fn cached_layout_matches_shaping_and_misses_on_changes() {
+        let mut cache = TextLayoutCache::new(8);
+        let mut shaped = 0;
+
+        let label = paragraph("label", FontAttributes::default());
+        let fresh = shape(&mut 0, &label);
+
+        // the same label every frame is shaped once, and laid out the same as when shaped
+        for _ in 0..10 {
+            let lines = layout(&mut cache, &mut shaped, &label, 100.0);
+            assert_eq!(bounds(&lines), bounds(&fresh));
+        }
+
+        assert_eq!(shaped, 1);
+
+        // rebuilding the paragraph with the same contents still hits
+        let rebuilt = paragraph("label", FontAttributes::default());
+        layout(&mut cache, &mut shaped, &rebuilt, 100.0);
+        assert_eq!(shaped, 1);
+
+        // the width, text, attributes and line height are all part of the key
+        let bigger = FontAttributes {
+            size: 20.0,
+            ..Default::default()
+        };
+
+        let other = paragraph("other", FontAttributes::default());
+
+        let mut taller = label.clone();
+        taller.line_height = 2.0;
+
+        layout(&mut cache, &mut shaped, &label, 50.0);
+        layout(&mut cache, &mut shaped, &other, 100.0);
+        layout(&mut cache, &mut shaped, &paragraph("label", bigger), 100.0);
+        layout(&mut cache, &mut shaped, &taller, 100.0);
+        assert_eq!(shaped, 5);
+    }
+
+    #[test]
+    fn least_recently_used_is_evicted() {
+        let mut cache = TextLayoutCache::new(2);
+        let mut shaped = 0;
+
+        let a = paragraph("a", Default::default());
+        let b = paragraph("b", Default::default());
+        let c = paragraph("c", Default::default());
+
+        layout(&mut cache, &mut shaped, &a, 100.0);
+        layout(&mut cache, &mut shaped, &b, 100.0);
+
+        // `a` was used more recently than `b`, so `c` evicts `b`
+        layout(&mut cache, &mut shaped, &a, 100.0);
+        layout(&mut cache, &mut shaped, &c, 100.0);
+        assert_eq!((cache.len(), shaped), (2, 3));
+
+        layout(&mut cache, &mut shaped, &a, 100.0);
+        assert_eq!(shaped, 3);
+
+        layout(&mut cache, &mut shaped, &b, 100.0);
+        assert_eq!(shaped, 4);
+    }
+}
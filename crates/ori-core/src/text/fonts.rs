@@ -3,7 +3,10 @@ use std::{
     ops::Range,
 };
 
-use crate::layout::{Point, Rect, Size};
+use crate::{
+    canvas::AntiAlias,
+    layout::{Point, Rect, Size},
+};
 
 use super::{FontSource, Paragraph};
 
@@ -20,6 +23,34 @@ pub trait Fonts: Any {
 
     /// Measure the given paragraph with the given max width.
     fn measure(&mut self, paragraph: &Paragraph, width: f32) -> Size;
+
+    /// Set the configuration of the glyph atlas.
+    ///
+    /// When the atlas is full, the least recently used glyphs are evicted and
+    /// rasterized again the next time they are drawn.
+    fn set_atlas_config(&mut self, config: GlyphAtlasConfig) {
+        let _ = config;
+    }
+
+    /// Get the configuration of the glyph atlas.
+    fn atlas_config(&self) -> GlyphAtlasConfig {
+        GlyphAtlasConfig::default()
+    }
+
+    /// Get the current occupancy of the glyph cache the atlas is filled from.
+    fn glyph_cache_stats(&self) -> GlyphCacheStats {
+        GlyphCacheStats::default()
+    }
+
+    /// Set how glyphs are rasterized.
+    fn set_render_config(&mut self, config: GlyphRenderConfig) {
+        let _ = config;
+    }
+
+    /// Get how glyphs are rasterized.
+    fn render_config(&self) -> GlyphRenderConfig {
+        GlyphRenderConfig::default()
+    }
 }
 
 impl dyn Fonts {
@@ -48,6 +79,84 @@ impl dyn Fonts {
     }
 }
 
+/// Configuration of the glyph atlas used to render text.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct GlyphAtlasConfig {
+    /// The maximum width and height of a single atlas texture in pixels.
+    pub max_texture_size: u32,
+
+    /// Whether the atlas may use more than one texture, before it starts evicting glyphs.
+    ///
+    /// The number of textures is decided by the renderer, skia uses at most four.
+    pub multiple_pages: bool,
+
+    /// The maximum number of bytes of rasterized glyphs to keep cached in memory, the atlas
+    /// textures are filled from this cache.
+    pub cache_size: usize,
+}
+
+impl Default for GlyphAtlasConfig {
+    fn default() -> Self {
+        Self {
+            max_texture_size: 2048,
+            multiple_pages: true,
+            cache_size: 2 * 1024 * 1024,
+        }
+    }
+}
+
+/// Configuration of how glyphs are rasterized.
+///
+/// Color glyphs, like emoji, are always rasterized in color.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct GlyphRenderConfig {
+    /// Whether glyph outlines are hinted, fitting them to the pixel grid.
+    ///
+    /// Hinting makes small text sharper, at the cost of distorting the shapes of the glyphs,
+    /// which is noticeable when text is scaled or animated.
+    pub hinting: bool,
+
+    /// How the edges of glyphs are anti-aliased, [`AntiAlias::None`] draws every pixel of a
+    /// glyph either fully covered or empty, eg. for pixel fonts.
+    pub anti_alias: AntiAlias,
+}
+
+impl Default for GlyphRenderConfig {
+    fn default() -> Self {
+        Self {
+            hinting: true,
+            anti_alias: AntiAlias::Full,
+        }
+    }
+}
+
+/// Occupancy statistics of the cache of rasterized glyphs, see [`GlyphAtlasConfig::cache_size`].
+///
+/// The atlas textures are filled from this cache, their own occupancy is managed by the
+/// renderer and isn't reported.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct GlyphCacheStats {
+    /// The number of bytes used by rasterized glyphs.
+    pub used: usize,
+
+    /// The maximum number of bytes available for rasterized glyphs.
+    pub capacity: usize,
+
+    /// The number of cached glyph entries.
+    pub entries: usize,
+}
+
+impl GlyphCacheStats {
+    /// Get the fraction of the cache that is in use, in the range `0.0..=1.0`.
+    pub fn occupancy(&self) -> f32 {
+        if self.capacity == 0 {
+            return 0.0;
+        }
+
+        f32::min(self.used as f32 / self.capacity as f32, 1.0)
+    }
+}
+
 /// A line of text layout.
 #[derive(Clone, Debug)]
 pub struct TextLayoutLine {
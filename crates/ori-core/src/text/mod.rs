@@ -1,11 +1,17 @@
 //! Text rendering and layouting.
 
 mod attributes;
+mod cache;
 mod fonts;
+#[cfg(any(test, feature = "testing"))]
+mod mono;
 mod paragraph;
 mod source;
 
 pub use attributes::*;
+pub use cache::*;
 pub use fonts::*;
+#[cfg(any(test, feature = "testing"))]
+pub use mono::*;
 pub use paragraph::*;
 pub use source::*;
@@ -0,0 +1,83 @@
+use crate::layout::{Point, Rect, Size};
+
+use super::{FontSource, Fonts, GlyphCluster, Paragraph, TextDirection, TextLayoutLine};
+
+/// A monospace [`Fonts`] that doesn't load any fonts, and wraps at any character.
+///
+/// Every character is [`MonoFonts::ADVANCE`] wide and every line is [`MonoFonts::HEIGHT`]
+/// tall, which makes text layout predictable where no real fonts are available, like in tests.
+/// Tabs advance to the next tab stop, see [`Paragraph::tab_stops`].
+///
+/// Only available in the tests of `ori-core`, or with its `testing` feature.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MonoFonts;
+
+impl MonoFonts {
+    /// The advance of every character.
+    pub const ADVANCE: f32 = 8.0;
+
+    /// The height of every line.
+    pub const HEIGHT: f32 = 20.0;
+}
+
+impl Fonts for MonoFonts {
+    fn load(&mut self, _source: FontSource<'_>, _name: Option<&str>) {}
+
+    fn layout(&mut self, paragraph: &Paragraph, width: f32) -> Vec<TextLayoutLine> {
+        let mut lines = Vec::<TextLayoutLine>::new();
+        let mut tab_stops = paragraph.tab_stops().peekable();
+
+        for (index, c) in paragraph.text().char_indices() {
+            let range = index..index + c.len_utf8();
+
+            let advance = match tab_stops.next_if(|&(tab, _)| tab == index) {
+                Some((_, columns)) => columns as f32 * Self::ADVANCE,
+                None => Self::ADVANCE,
+            };
+
+            let fits = match lines.last() {
+                Some(line) => line.width + advance <= width,
+                None => false,
+            };
+
+            if !fits {
+                let top = lines.len() as f32 * Self::HEIGHT;
+
+                lines.push(TextLayoutLine {
+                    ascent: Self::HEIGHT,
+                    descent: 0.0,
+                    left: 0.0,
+                    width: 0.0,
+                    height: Self::HEIGHT,
+                    baseline: top + Self::HEIGHT,
+                    range: range.start..range.start,
+                    glyphs: Vec::new(),
+                });
+            }
+
+            let line = lines.last_mut().unwrap();
+            let top = line.baseline - Self::HEIGHT;
+
+            line.glyphs.push(GlyphCluster {
+                bounds: Rect::min_size(
+                    Point::new(line.width, top),
+                    Size::new(advance, Self::HEIGHT),
+                ),
+                range: range.clone(),
+                direction: TextDirection::Ltr,
+            });
+
+            line.width += advance;
+            line.range.end = range.end;
+        }
+
+        lines
+    }
+
+    fn measure(&mut self, paragraph: &Paragraph, width: f32) -> Size {
+        let lines = self.layout(paragraph, width);
+        let width = lines.iter().map(|line| line.width).fold(0.0, f32::max);
+
+        Size::new(width, lines.len() as f32 * Self::HEIGHT)
+    }
+}
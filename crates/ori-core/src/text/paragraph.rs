@@ -20,6 +20,10 @@ pub struct Paragraph {
     /// The text wrapping mode.
     pub wrap: TextWrap,
 
+    /// The number of columns between tab stops, or `None` to shape tabs like any other
+    /// character, see [`Paragraph::tab_stops`].
+    pub tab_size: Option<usize>,
+
     text: SmolStr,
     segments: SmallVec<[Segment; 1]>,
 }
@@ -31,6 +35,7 @@ impl Paragraph {
             line_height,
             align,
             wrap,
+            tab_size: None,
             text: SmolStr::default(),
             segments: SmallVec::new(),
         }
@@ -50,8 +55,10 @@ impl Paragraph {
 
     /// Push a new segment of text with the given [`FontAttributes`] to the paragraph.
     pub fn push_text(&mut self, text: impl Display, attrs: FontAttributes) {
+        let start = self.text.len();
         self.text = format_smolstr!("{}{}", self.text, text);
         self.segments.push(Segment {
+            start,
             end: self.text.len(),
             attrs,
         });
@@ -62,24 +69,47 @@ impl Paragraph {
         &self.text
     }
 
+    /// Get an iterator over the tabs of the paragraph, and the number of columns each of them
+    /// advances to reach the next tab stop.
+    ///
+    /// Columns are counted in characters from the start of the line, which for monospace fonts
+    /// is the same as counting in multiples of the space advance. Empty when there is no
+    /// [`Paragraph::tab_size`].
+    pub fn tab_stops(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.tab_size.into_iter().flat_map(move |tab_size| {
+            let tab_size = tab_size.max(1);
+            let mut column = 0;
+
+            (self.text.char_indices()).filter_map(move |(index, c)| match c {
+                '\t' => {
+                    let columns = tab_size - column % tab_size;
+                    column += columns;
+                    Some((index, columns))
+                }
+                '\n' => {
+                    column = 0;
+                    None
+                }
+                _ => {
+                    column += 1;
+                    None
+                }
+            })
+        })
+    }
+
     /// Get an iterator over the segments of the paragraph.
     pub fn iter(&self) -> impl DoubleEndedIterator<Item = (&str, &FontAttributes)> {
-        self.segments.iter().map(|segment| {
-            let start = segment.end - self.text.len();
-            let end = segment.end;
-            let text = &self.text[start..end];
-            (text, &segment.attrs)
-        })
+        (self.segments.iter())
+            .map(|segment| (&self.text[segment.start..segment.end], &segment.attrs))
     }
 
     /// Get an iterator over the segments of the paragraph mutably.
     pub fn iter_mut(&mut self) -> impl DoubleEndedIterator<Item = (&str, &mut FontAttributes)> {
         let text = &self.text;
 
-        self.segments.iter_mut().map(|segment| {
-            let start = segment.end - self.text.len();
-            let end = segment.end;
-            let text = &text[start..end];
+        (self.segments.iter_mut()).map(|segment| {
+            let text = &text[segment.start..segment.end];
             (text, &mut segment.attrs)
         })
     }
@@ -92,6 +122,7 @@ impl Hash for Paragraph {
         self.line_height.to_bits().hash(state);
         self.align.hash(state);
         self.wrap.hash(state);
+        self.tab_size.hash(state);
         self.text.hash(state);
         self.segments.hash(state);
     }
@@ -102,6 +133,25 @@ impl Hash for Paragraph {
 /// the range of the text that the segment covers.
 #[derive(Clone, Debug, PartialEq, Hash)]
 struct Segment {
+    start: usize,
     end: usize,
     attrs: FontAttributes,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn segments_cover_their_own_text() {
+        let mut paragraph = Paragraph::new(1.0, TextAlign::Start, TextWrap::Word);
+        paragraph.push_text("ab", FontAttributes::default());
+        paragraph.push_text("\tc", FontAttributes::default());
+
+        let texts: Vec<_> = paragraph.iter().map(|(text, _)| text).collect();
+        assert_eq!(texts, ["ab", "\tc"]);
+
+        paragraph.tab_size = Some(4);
+        assert_eq!(paragraph.tab_stops().collect::<Vec<_>>(), [(2, 2)]);
+    }
+}
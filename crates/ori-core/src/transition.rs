@@ -1,5 +1,7 @@
 //! Transition utilities.
 
+use crate::canvas::{Color, ColorInterpolation};
+
 /// Create a linear transition with the given `duration`.
 pub fn linear(duration: f32) -> Transition {
     Transition::linear(duration)
@@ -20,6 +22,12 @@ pub enum Easing {
 
     /// An ease transition curve.
     Ease,
+
+    /// An ease in transition curve, starting slow and ending fast.
+    EaseIn,
+
+    /// An ease out transition curve, starting fast and ending slow.
+    EaseOut,
 }
 
 impl Easing {
@@ -28,6 +36,8 @@ impl Easing {
         match self {
             Easing::Linear => t,
             Easing::Ease => t * t * (3.0 - 2.0 * t),
+            Easing::EaseIn => t * t,
+            Easing::EaseOut => t * (2.0 - t),
         }
     }
 }
@@ -40,6 +50,9 @@ pub struct Transition {
     pub duration: f32,
     /// The easing curve.
     pub easing: Easing,
+    /// The color space colors are interpolated in, see [`Transition::mix`].
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub interpolation: ColorInterpolation,
 }
 
 impl Default for Transition {
@@ -54,6 +67,7 @@ impl Transition {
         Self {
             duration,
             easing: Easing::Linear,
+            interpolation: ColorInterpolation::Oklab,
         }
     }
 
@@ -62,9 +76,16 @@ impl Transition {
         Self {
             duration,
             easing: Easing::Ease,
+            interpolation: ColorInterpolation::Oklab,
         }
     }
 
+    /// Set the color space colors are interpolated in.
+    pub fn interpolation(mut self, interpolation: ColorInterpolation) -> Self {
+        self.interpolation = interpolation;
+        self
+    }
+
     /// Step the transition.
     pub fn step(&self, t: &mut f32, on: bool, dt: f32) -> bool {
         let sign = if on { 1.0 } else { -1.0 };
@@ -93,4 +114,34 @@ impl Transition {
     pub fn get(&self, t: f32) -> f32 {
         self.easing.evaluate(t)
     }
+
+    /// Interpolate between the colors `from` and `to` at `t`.
+    ///
+    /// The easing is applied to `t`, and the colors are mixed in the color space of
+    /// [`Transition::interpolation`].
+    pub fn mix(&self, from: Color, to: Color, t: f32) -> Color {
+        from.mix_in(to, self.get(t), self.interpolation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chroma(color: Color) -> f32 {
+        let (_, a, b) = color.to_oklab();
+        f32::sqrt(a * a + b * b)
+    }
+
+    #[test]
+    fn oklab_midpoint_is_more_saturated_than_srgb() {
+        let oklab = Transition::linear(1.0);
+        let srgb = Transition::linear(1.0).interpolation(ColorInterpolation::Srgb);
+
+        let oklab = oklab.mix(Color::RED, Color::GREEN, 0.5);
+        let srgb = srgb.mix(Color::RED, Color::GREEN, 0.5);
+
+        assert_eq!(srgb, Color::rgb(0.5, 0.5, 0.0));
+        assert!(chroma(oklab) > chroma(srgb));
+    }
 }
@@ -3,7 +3,7 @@ use std::any::Any;
 use crate::{
     context::{BuildCx, DrawCx, EventCx, LayoutCx, RebuildCx},
     event::Event,
-    layout::{Size, Space},
+    layout::{Axis, Size, Space},
 };
 
 use super::View;
@@ -80,6 +80,16 @@ pub trait AnyView<T> {
         space: Space,
     ) -> Size;
 
+    /// Calculate the intrinsic size.
+    fn dyn_intrinsic_size(
+        &mut self,
+        state: &mut AnyState,
+        cx: &mut LayoutCx,
+        data: &mut T,
+        axis: Axis,
+        cross_extent: f32,
+    ) -> f32;
+
     /// Draw the view.
     fn dyn_draw(&mut self, state: &mut AnyState, cx: &mut DrawCx, data: &mut T);
 }
@@ -141,6 +151,20 @@ where
         }
     }
 
+    fn dyn_intrinsic_size(
+        &mut self,
+        state: &mut AnyState,
+        cx: &mut LayoutCx,
+        data: &mut T,
+        axis: Axis,
+        cross_extent: f32,
+    ) -> f32 {
+        match state.downcast_mut::<V::State>() {
+            Some(state) => self.intrinsic_size(state, cx, data, axis, cross_extent),
+            None => 0.0,
+        }
+    }
+
     fn dyn_draw(&mut self, state: &mut AnyState, cx: &mut DrawCx, data: &mut T) {
         match state.downcast_mut::<V::State>() {
             Some(state) => self.draw(state, cx, data),
@@ -180,6 +204,17 @@ impl<T> View<T> for BoxedView<T> {
         self.as_mut().dyn_layout(state, cx, data, space)
     }
 
+    fn intrinsic_size(
+        &mut self,
+        state: &mut Self::State,
+        cx: &mut LayoutCx,
+        data: &mut T,
+        axis: Axis,
+        cross_extent: f32,
+    ) -> f32 {
+        (self.as_mut()).dyn_intrinsic_size(state, cx, data, axis, cross_extent)
+    }
+
     fn draw(&mut self, state: &mut Self::State, cx: &mut DrawCx, data: &mut T) {
         self.as_mut().dyn_draw(state, cx, data);
     }
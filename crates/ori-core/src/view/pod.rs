@@ -1,14 +1,17 @@
-use std::ops::{Deref, DerefMut};
+use std::{
+    mem,
+    ops::{Deref, DerefMut},
+};
 
 use crate::{
-    canvas::Canvas,
+    canvas::{Canvas, ColorMatrix, Damage},
     context::{BuildCx, DrawCx, EventCx, LayoutCx, RebuildCx},
     event::{Event, FocusTarget},
-    layout::{Rect, Size, Space},
+    layout::{Axis, Rect, Size, Space},
     style::{hash_style_key, Styles},
 };
 
-use super::{View, ViewState};
+use super::{View, ViewId, ViewState};
 
 /// The state of a [`Pod`].
 pub struct State<T, V: View<T> + ?Sized> {
@@ -168,6 +171,35 @@ impl<V> Pod<V> {
             view_state.mark_animated();
         }
 
+        // events of a captured pointer are only delivered to the capturing view and its
+        // ancestors, and can't be handled by views before it
+        if let Some(capture) = Self::pointer_capture(cx, event) {
+            if view_state.id() != capture && !view_state.has_capturing() {
+                cx.view_state.propagate(view_state);
+
+                return false;
+            }
+        }
+
+        // targeted commands are only delivered to their target, and not to its content
+        if let Event::Command(command) = event {
+            if let Some(target) = command.target() {
+                if command.is_reached() {
+                    cx.view_state.propagate(view_state);
+
+                    return false;
+                }
+
+                if view_state.id() == target {
+                    command.set_reached(true);
+                    let handled = Self::event_with_inner(view_state, cx, event, f);
+                    command.set_reached(false);
+
+                    return handled;
+                }
+            }
+        }
+
         if event.wants_focus() && view_state.is_focused() {
             view_state.set_focused(false);
             Self::event_with_inner(view_state, cx, &Event::Notify, f);
@@ -202,6 +234,7 @@ impl<V> Pod<V> {
         f: impl FnOnce(&mut EventCx, &Event) -> bool,
     ) -> bool {
         view_state.set_hovered(cx.window().is_hovered(view_state.id()));
+        view_state.set_capturing(cx.window().is_capturing(view_state.id()));
         view_state.prepare();
 
         if let Some(class) = cx.view_state.class() {
@@ -226,12 +259,33 @@ impl<V> Pod<V> {
         handled
     }
 
+    // the view that captured the pointer of a move or release
+    fn pointer_capture(cx: &EventCx, event: &Event) -> Option<ViewId> {
+        let pointer = match event {
+            Event::PointerMoved(e) => e.id,
+            Event::PointerReleased(e) => e.id,
+            _ => return None,
+        };
+
+        cx.window().pointer_capture(pointer)
+    }
+
     /// Call a closure with the [`LayoutCx`] provided by a pod.
+    ///
+    /// The result is cached in the `view_state`, and the closure is only called again when
+    /// the `space` changes, or a layout of the view or one of its descendants is requested.
     pub(crate) fn layout_with(
         view_state: &mut ViewState,
         cx: &mut LayoutCx,
+        space: Space,
         f: impl FnOnce(&mut LayoutCx) -> Size,
     ) -> Size {
+        if let Some(size) = view_state.cached_layout(space) {
+            // the layout may have been done while measuring, which keeps the old size
+            view_state.size = size;
+            return size;
+        }
+
         view_state.mark_layed_out();
 
         if let Some(class) = cx.view_state.class() {
@@ -242,7 +296,9 @@ impl<V> Pod<V> {
         let mut new_cx = cx.child();
         new_cx.view_state = view_state;
 
-        view_state.size = f(&mut new_cx);
+        let size = f(&mut new_cx);
+        view_state.size = Self::checked_size(size);
+        view_state.cache_layout(space, view_state.size);
 
         if cx.view_state.class().is_some() {
             cx.context_mut::<Styles>().pop_class();
@@ -251,12 +307,82 @@ impl<V> Pod<V> {
         view_state.size
     }
 
+    /// Call a closure computing an intrinsic size with the [`LayoutCx`] provided by a pod.
+    ///
+    /// The result is cached in the `view_state`, until a layout of the view is requested.
+    pub(crate) fn intrinsic_with(
+        view_state: &mut ViewState,
+        cx: &mut LayoutCx,
+        axis: Axis,
+        cross_extent: f32,
+        f: impl FnOnce(&mut LayoutCx) -> f32,
+    ) -> f32 {
+        if let Some(size) = view_state.cached_intrinsic_size(axis, cross_extent) {
+            return size;
+        }
+
+        if let Some(class) = cx.view_state.class() {
+            let hash = hash_style_key(class.as_bytes());
+            cx.context_mut::<Styles>().push_class_hash(hash);
+        }
+
+        // measuring might lay out the content, which must not change the size of the view
+        // until it's laid out by the container, see `layout_with`
+        let size = view_state.size;
+
+        let mut new_cx = cx.child();
+        new_cx.view_state = view_state;
+
+        let intrinsic = f(&mut new_cx);
+        view_state.size = size;
+
+        if cx.view_state.class().is_some() {
+            cx.context_mut::<Styles>().pop_class();
+        }
+
+        let intrinsic = match intrinsic.is_finite() {
+            true => intrinsic,
+            false => 0.0,
+        };
+
+        view_state.cache_intrinsic_size(axis, cross_extent, intrinsic);
+        intrinsic
+    }
+
+    /// Reject non-finite sizes returned by a view's layout.
+    ///
+    /// A `NaN` size is always a bug and panics in debug builds, infinite sizes are
+    /// logged. In both cases the offending components are clamped to zero.
+    fn checked_size(size: Size) -> Size {
+        if size.is_finite() {
+            return size;
+        }
+
+        let view = std::any::type_name::<V>();
+
+        debug_assert!(
+            !size.width.is_nan() && !size.height.is_nan(),
+            "view `{}` returned a NaN size from layout: {:?}",
+            view,
+            size,
+        );
+
+        crate::log::warn!(
+            "view `{}` returned a non-finite size from layout: {:?}, clamping to zero",
+            view,
+            size,
+        );
+
+        size.finite_or_zero()
+    }
+
     /// Call a closure with the [`DrawCx`] provided by a pod.
     pub(crate) fn draw_with(
         view_state: &mut ViewState,
         cx: &mut DrawCx,
         f: impl FnOnce(&mut DrawCx),
     ) {
+        let damaged = view_state.is_damaged();
         view_state.mark_drawn();
 
         if let Some(class) = cx.view_state.class() {
@@ -268,15 +394,43 @@ impl<V> Pod<V> {
         let mut new_cx = cx.child();
         new_cx.view_state = view_state;
 
-        // draw the content
+        // draw the content, in a layer faded as a unit if the view isn't opaque
+        let opacity = new_cx.view_state.opacity;
+
         new_cx.transformed(new_cx.view_state.transform, |cx| {
-            f(cx);
+            match opacity < 1.0 {
+                true => cx.filtered(ColorMatrix::opacity(opacity), f),
+                false => f(cx),
+            }
+
+            if damaged {
+                Self::damage(cx);
+            }
         });
 
         if cx.view_state.class().is_some() {
             cx.context_mut::<Styles>().pop_class();
         }
     }
+
+    // both where the view drew in the last frame and where it draws now have changed
+    fn damage(cx: &mut DrawCx) {
+        let content = (cx.canvas.content_bounds()).map(|bounds| bounds.transform(cx.transform));
+
+        // overlays are already drawn in window coordinates
+        let bounds = match (content, cx.canvas.overlay_bounds()) {
+            (Some(content), Some(overlay)) => Some(content.union(overlay)),
+            (content, overlay) => content.or(overlay),
+        };
+
+        let prev_bounds = mem::replace(&mut cx.view_state.drawn_bounds, bounds);
+
+        if let Some(damage) = cx.get_context_mut::<Damage>() {
+            for rect in prev_bounds.iter().chain(&bounds) {
+                damage.add(*rect);
+            }
+        }
+    }
 }
 
 impl<V> From<V> for Pod<V> {
@@ -338,11 +492,24 @@ impl<T, V: View<T>> View<T> for Pod<V> {
         data: &mut T,
         space: Space,
     ) -> Size {
-        Self::layout_with(&mut state.view_state, cx, |cx| {
+        Self::layout_with(&mut state.view_state, cx, space, |cx| {
             (self.view).layout(&mut state.content, cx, data, space)
         })
     }
 
+    fn intrinsic_size(
+        &mut self,
+        state: &mut Self::State,
+        cx: &mut LayoutCx,
+        data: &mut T,
+        axis: Axis,
+        cross_extent: f32,
+    ) -> f32 {
+        Self::intrinsic_with(&mut state.view_state, cx, axis, cross_extent, |cx| {
+            (self.view).intrinsic_size(&mut state.content, cx, data, axis, cross_extent)
+        })
+    }
+
     fn draw(&mut self, state: &mut Self::State, cx: &mut DrawCx, data: &mut T) {
         // we need to check if the view needs to be drawn here
         // since the flag gets cleared in draw function
@@ -370,3 +537,167 @@ impl<T, V: View<T>> View<T> for Pod<V> {
         });
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        command::Command,
+        views::{
+            pad,
+            testing::{save_layout, test_layout, SavedLayouts, ViewTester},
+            vstack,
+        },
+    };
+
+    use super::*;
+
+    struct NanView;
+
+    impl View<()> for NanView {
+        type State = ();
+
+        fn build(&mut self, _cx: &mut BuildCx, _data: &mut ()) -> Self::State {}
+
+        fn rebuild(&mut self, _: &mut (), _: &mut RebuildCx, _: &mut (), _old: &Self) {}
+
+        fn event(&mut self, _: &mut (), _: &mut EventCx, _: &mut (), _: &Event) -> bool {
+            false
+        }
+
+        fn layout(&mut self, _: &mut (), _: &mut LayoutCx, _: &mut (), _: Space) -> Size {
+            Size::all(f32::NAN)
+        }
+
+        fn draw(&mut self, _: &mut (), _: &mut DrawCx, _: &mut ()) {}
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "NaN size")]
+    fn nan_size_panics() {
+        let mut view = Pod::new(NanView);
+        let mut tester = ViewTester::new(&mut view, &mut ());
+        tester.layout(&mut view, &mut (), Space::UNBOUNDED);
+    }
+
+    #[test]
+    fn infinite_size_clamped() {
+        let size = Size::new(f32::INFINITY, 10.0);
+        assert_eq!(Pod::<NanView>::checked_size(size), Size::new(0.0, 10.0));
+    }
+
+    struct Invalidate;
+
+    // counts its layouts in the data, and requests a layout on `Invalidate`
+    struct Counted;
+
+    impl View<u32> for Counted {
+        type State = ();
+
+        fn build(&mut self, _cx: &mut BuildCx, _data: &mut u32) -> Self::State {}
+
+        fn rebuild(&mut self, _: &mut (), _: &mut RebuildCx, _: &mut u32, _old: &Self) {}
+
+        fn event(&mut self, _: &mut (), cx: &mut EventCx, _: &mut u32, event: &Event) -> bool {
+            if event.is_cmd::<Invalidate>() {
+                cx.layout();
+            }
+
+            false
+        }
+
+        fn layout(
+            &mut self,
+            _: &mut (),
+            _: &mut LayoutCx,
+            layouts: &mut u32,
+            space: Space,
+        ) -> Size {
+            *layouts += 1;
+            space.fit(Size::all(10.0))
+        }
+
+        fn draw(&mut self, _: &mut (), _: &mut DrawCx, _: &mut u32) {}
+    }
+
+    fn tree() -> Pod<impl View<u32>> {
+        Pod::new(pad(
+            4.0,
+            vstack![
+                save_layout(pad(4.0, Counted), "inner"),
+                save_layout(Counted, "leaf")
+            ],
+        ))
+    }
+
+    #[test]
+    fn cached_layout_matches_uncached() {
+        let space = Space::new(Size::ZERO, Size::all(100.0));
+
+        let mut view = tree();
+        let mut layouts = 0;
+
+        let mut tester = ViewTester::new(&mut view, &mut layouts);
+        let size = tester.layout(&mut view, &mut layouts, space);
+        let first = layouts;
+
+        // the same space is answered from the cache, without laying out the counted views
+        assert_eq!(tester.layout(&mut view, &mut layouts, space), size);
+        assert_eq!(layouts, first);
+
+        tester.event(&mut view, &mut layouts, &Event::Notify);
+        let cached = tester.contexts.get::<SavedLayouts>().unwrap().clone();
+
+        let uncached = test_layout(&mut tree(), &mut 0, space);
+        assert_eq!(cached, uncached);
+    }
+
+    #[test]
+    fn requested_layout_busts_ancestor_caches() {
+        let space = Space::new(Size::ZERO, Size::all(100.0));
+
+        let mut view = tree();
+        let mut layouts = 0;
+
+        let mut tester = ViewTester::new(&mut view, &mut layouts);
+        tester.layout(&mut view, &mut layouts, space);
+        let first = layouts;
+
+        let invalidate = Event::Command(Command::new(Invalidate));
+        tester.event(&mut view, &mut layouts, &invalidate);
+
+        tester.layout(&mut view, &mut layouts, space);
+        assert!(layouts > first);
+
+        // a different space lays out again too
+        let second = layouts;
+        tester.layout(
+            &mut view,
+            &mut layouts,
+            Space::new(Size::ZERO, Size::all(50.0)),
+        );
+        assert!(layouts > second);
+    }
+
+    #[test]
+    fn measured_layout_is_reused() {
+        let space = Space::new(Size::ZERO, Size::all(100.0));
+
+        let mut view = Pod::new(Counted);
+        let mut layouts = 0;
+
+        let mut tester = ViewTester::new(&mut view, &mut layouts);
+        let width = tester.intrinsic_size(&mut view, &mut layouts, Axis::Horizontal, f32::INFINITY);
+        assert_eq!((width, layouts), (10.0, 1));
+
+        // laying out in the space it was measured in reuses the measurement
+        tester.layout(&mut view, &mut layouts, Space::UNBOUNDED);
+        assert_eq!(layouts, 1);
+
+        // but measuring replaces the layout done in any other space
+        tester.layout(&mut view, &mut layouts, space);
+        tester.intrinsic_size(&mut view, &mut layouts, Axis::Vertical, 50.0);
+        tester.layout(&mut view, &mut layouts, space);
+        assert_eq!(layouts, 4);
+    }
+}
@@ -3,11 +3,54 @@ use std::ops::{Deref, DerefMut};
 use crate::{
     context::{BuildCx, DrawCx, EventCx, LayoutCx, RebuildCx},
     event::{Event, FocusTarget},
-    layout::{Size, Space},
+    layout::{Axis, Size, Space},
 };
 
 use super::{Pod, View, ViewState};
 
+pub use crate::children;
+
+/// Create a [`ViewSeq`] from a mix of single views and spread iterators.
+///
+/// Single views are written as usual, while iterators of views are prefixed with `..`,
+/// and are collected into a [`Vec`]. When no iterators are spread, this produces a
+/// plain tuple, the same as the stack macros always have.
+///
+/// ```ignore
+/// let rows = data.items.iter().map(|item| text(item));
+/// vstack![text("header"), ..rows, text("footer")]
+/// ```
+///
+/// Each single view and each spread is its own segment, and views are identified by
+/// their position within their segment. This means that a spread growing or shrinking
+/// will never cause the state of the views around it to be mixed up, but views
+/// inside a spread are still matched by index, so reordering items in a spread will
+/// move state between them. Keyed reconciliation of the items in a spread
+/// belongs to the [`Vec`] segment, and is unaffected by the surrounding views.
+#[macro_export]
+macro_rules! children {
+    (@[$($single:expr,)*]) => {
+        ($($single,)*)
+    };
+    (@[$($single:expr,)*] .. $spread:expr $(, $($rest:tt)*)?) => {
+        $crate::view::Chain(
+            ($($single,)*),
+            $crate::view::Chain(
+                ::std::iter::Iterator::collect::<::std::vec::Vec<_>>(
+                    ::std::iter::IntoIterator::into_iter($spread),
+                ),
+                $crate::children!(@[] $($($rest)*)?),
+            ),
+        )
+    };
+    (@[$($single:expr,)*] $child:expr $(, $($rest:tt)*)?) => {
+        $crate::children!(@[$($single,)* $child,] $($($rest)*)?)
+    };
+    ($($child:tt)*) => {
+        $crate::children!(@[] $($child)*)
+    };
+}
+
 /// A sequence of views.
 #[allow(clippy::len_without_is_empty)]
 pub trait ViewSeq<T> {
@@ -21,7 +64,17 @@ pub trait ViewSeq<T> {
     fn build(&mut self, cx: &mut BuildCx, data: &mut T) -> (Self::State, Vec<ViewState>);
 
     /// Rebuild the sequence state.
-    fn rebuild(&mut self, state: &mut Self::State, cx: &mut BuildCx, data: &mut T, old: &Self);
+    ///
+    /// `view_states` contains the view states of the old sequence, and must be updated to
+    /// match the length of the new sequence.
+    fn rebuild(
+        &mut self,
+        state: &mut Self::State,
+        view_states: &mut Vec<ViewState>,
+        cx: &mut BuildCx,
+        data: &mut T,
+        old: &Self,
+    );
 
     /// Rebuild the nth view.
     fn rebuild_nth(
@@ -53,6 +106,20 @@ pub trait ViewSeq<T> {
         space: Space,
     ) -> Size;
 
+    /// Get the intrinsic size of the nth view, see [`View::intrinsic_size`].
+    fn intrinsic_size_nth(
+        &mut self,
+        n: usize,
+        state: &mut Self::State,
+        cx: &mut LayoutCx,
+        data: &mut T,
+        axis: Axis,
+        cross_extent: f32,
+    ) -> f32 {
+        let space = Space::new(Size::ZERO, axis.pack(f32::INFINITY, cross_extent));
+        axis.major(self.layout_nth(n, state, cx, data, space))
+    }
+
     /// Draw the nth view.
     fn draw_nth(&mut self, n: usize, state: &mut Self::State, cx: &mut DrawCx, data: &mut T);
 }
@@ -77,12 +144,24 @@ impl<T, V: View<T>> ViewSeq<T> for Vec<V> {
         (states, view_states)
     }
 
-    fn rebuild(&mut self, state: &mut Self::State, cx: &mut BuildCx, data: &mut T, _old: &Self) {
+    fn rebuild(
+        &mut self,
+        state: &mut Self::State,
+        view_states: &mut Vec<ViewState>,
+        cx: &mut BuildCx,
+        data: &mut T,
+        _old: &Self,
+    ) {
         if self.len() < state.len() {
             state.truncate(self.len());
+            view_states.truncate(self.len());
         } else {
+            view_states.truncate(state.len());
+
             for item in self.iter_mut().skip(state.len()) {
-                state.push(item.build(cx, data));
+                let (state_item, view_state) = Pod::<V>::build_with(cx, |cx| item.build(cx, data));
+                view_states.push(view_state);
+                state.push(state_item);
             }
         }
     }
@@ -122,6 +201,18 @@ impl<T, V: View<T>> ViewSeq<T> for Vec<V> {
         self[n].layout(&mut state[n], cx, data, space)
     }
 
+    fn intrinsic_size_nth(
+        &mut self,
+        n: usize,
+        state: &mut Self::State,
+        cx: &mut LayoutCx,
+        data: &mut T,
+        axis: Axis,
+        cross_extent: f32,
+    ) -> f32 {
+        self[n].intrinsic_size(&mut state[n], cx, data, axis, cross_extent)
+    }
+
     fn draw_nth(&mut self, n: usize, state: &mut Self::State, cx: &mut DrawCx, data: &mut T) {
         self[n].draw(&mut state[n], cx, data);
     }
@@ -138,7 +229,14 @@ impl<T> ViewSeq<T> for () {
         ((), Vec::new())
     }
 
-    fn rebuild(&mut self, _state: &mut Self::State, _cx: &mut BuildCx, _data: &mut T, _old: &Self) {
+    fn rebuild(
+        &mut self,
+        _state: &mut Self::State,
+        _view_states: &mut Vec<ViewState>,
+        _cx: &mut BuildCx,
+        _data: &mut T,
+        _old: &Self,
+    ) {
     }
 
     fn rebuild_nth(
@@ -203,6 +301,7 @@ macro_rules! impl_tuple {
             fn rebuild(
                 &mut self,
                 _state: &mut Self::State,
+                _view_states: &mut Vec<ViewState>,
                 _cx: &mut BuildCx,
                 _data: &mut T,
                 _old: &Self,
@@ -251,6 +350,24 @@ macro_rules! impl_tuple {
                 }
             }
 
+            fn intrinsic_size_nth(
+                &mut self,
+                n: usize,
+                state: &mut Self::State,
+                cx: &mut LayoutCx,
+                data: &mut T,
+                axis: Axis,
+                cross_extent: f32,
+            ) -> f32 {
+                match n {
+                    $($index => {
+                        let state = &mut state.$index;
+                        self.$index.intrinsic_size(state, cx, data, axis, cross_extent)
+                    })*
+                    _ => 0.0,
+                }
+            }
+
             fn draw_nth(
                 &mut self,
                 n: usize,
@@ -293,6 +410,120 @@ impl_tuple!(A B C D E F G H I J K L M N O P Q R S U V W; 0 1 2 3 4 5 6 7 8 9 10
 impl_tuple!(A B C D E F G H I J K L M N O P Q R S U V W X; 0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22);
 impl_tuple!(A B C D E F G H I J K L M N O P Q R S U V W X Z; 0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23);
 
+/// Two [`ViewSeq`]s chained together, see [`children!`].
+///
+/// The views of the first sequence are identified separately from the views of the
+/// second, so a change in the length of one doesn't affect the other.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Chain<A, B>(pub A, pub B);
+
+impl<T, A: ViewSeq<T>, B: ViewSeq<T>> ViewSeq<T> for Chain<A, B> {
+    type State = (A::State, B::State);
+
+    fn len(&self) -> usize {
+        self.0.len() + self.1.len()
+    }
+
+    fn build(&mut self, cx: &mut BuildCx, data: &mut T) -> (Self::State, Vec<ViewState>) {
+        let (a, mut view_states) = self.0.build(cx, data);
+        let (b, b_view_states) = self.1.build(cx, data);
+        view_states.extend(b_view_states);
+
+        ((a, b), view_states)
+    }
+
+    fn rebuild(
+        &mut self,
+        (a, b): &mut Self::State,
+        view_states: &mut Vec<ViewState>,
+        cx: &mut BuildCx,
+        data: &mut T,
+        old: &Self,
+    ) {
+        let split = usize::min(old.0.len(), view_states.len());
+        let mut b_view_states = view_states.split_off(split);
+
+        (self.0).rebuild(a, view_states, cx, data, &old.0);
+        (self.1).rebuild(b, &mut b_view_states, cx, data, &old.1);
+
+        view_states.append(&mut b_view_states);
+    }
+
+    fn rebuild_nth(
+        &mut self,
+        n: usize,
+        (a, b): &mut Self::State,
+        cx: &mut RebuildCx,
+        data: &mut T,
+        old: &Self,
+    ) {
+        let len = self.0.len();
+
+        match n < len {
+            true => self.0.rebuild_nth(n, a, cx, data, &old.0),
+            false => self.1.rebuild_nth(n - len, b, cx, data, &old.1),
+        }
+    }
+
+    fn event_nth(
+        &mut self,
+        n: usize,
+        (a, b): &mut Self::State,
+        cx: &mut EventCx,
+        data: &mut T,
+        event: &Event,
+    ) -> bool {
+        let len = self.0.len();
+
+        match n < len {
+            true => self.0.event_nth(n, a, cx, data, event),
+            false => self.1.event_nth(n - len, b, cx, data, event),
+        }
+    }
+
+    fn layout_nth(
+        &mut self,
+        n: usize,
+        (a, b): &mut Self::State,
+        cx: &mut LayoutCx,
+        data: &mut T,
+        space: Space,
+    ) -> Size {
+        let len = self.0.len();
+
+        match n < len {
+            true => self.0.layout_nth(n, a, cx, data, space),
+            false => self.1.layout_nth(n - len, b, cx, data, space),
+        }
+    }
+
+    fn intrinsic_size_nth(
+        &mut self,
+        n: usize,
+        (a, b): &mut Self::State,
+        cx: &mut LayoutCx,
+        data: &mut T,
+        axis: Axis,
+        cross_extent: f32,
+    ) -> f32 {
+        let len = self.0.len();
+
+        match n < len {
+            true => (self.0).intrinsic_size_nth(n, a, cx, data, axis, cross_extent),
+            false => (self.1).intrinsic_size_nth(n - len, b, cx, data, axis, cross_extent),
+        }
+    }
+
+    fn draw_nth(&mut self, n: usize, (a, b): &mut Self::State, cx: &mut DrawCx, data: &mut T) {
+        let len = self.0.len();
+
+        match n < len {
+            true => self.0.draw_nth(n, a, cx, data),
+            false => self.1.draw_nth(n - len, b, cx, data),
+        }
+    }
+}
+
 /// The state of a [`PodSeq`].
 pub struct SeqState<T, V: ViewSeq<T>> {
     content: V::State,
@@ -399,9 +630,10 @@ impl<V> PodSeq<V> {
     ) where
         V: ViewSeq<T>,
     {
-        (state.view_state).resize_with(self.views.len(), ViewState::default);
+        let view_states = &mut state.view_state;
+        (self.views).rebuild(&mut state.content, view_states, cx, data, &old.views);
 
-        (self.views).rebuild(&mut state.content, cx, data, &old.views);
+        (state.view_state).resize_with(self.views.len(), ViewState::default);
     }
 
     /// Rebuild the nth view.
@@ -573,11 +805,32 @@ impl<V> PodSeq<V> {
     where
         V: ViewSeq<T>,
     {
-        Pod::<V>::layout_with(&mut state.view_state[n], cx, |cx| {
+        Pod::<V>::layout_with(&mut state.view_state[n], cx, space, |cx| {
             (self.views).layout_nth(n, &mut state.content, cx, data, space)
         })
     }
 
+    /// Get the intrinsic size of the nth view, see [`View::intrinsic_size`].
+    pub fn intrinsic_size_nth<T>(
+        &mut self,
+        n: usize,
+        state: &mut SeqState<T, V>,
+        cx: &mut LayoutCx,
+        data: &mut T,
+        axis: Axis,
+        cross_extent: f32,
+    ) -> f32
+    where
+        V: ViewSeq<T>,
+    {
+        let view_state = &mut state.view_state[n];
+
+        Pod::<V>::intrinsic_with(view_state, cx, axis, cross_extent, |cx| {
+            let content = &mut state.content;
+            (self.views).intrinsic_size_nth(n, content, cx, data, axis, cross_extent)
+        })
+    }
+
     /// Draw the nth view.
     pub fn draw_nth<T>(
         &mut self,
@@ -597,3 +850,74 @@ impl<V> PodSeq<V> {
         });
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        command::{CommandProxy, CommandWaker},
+        context::{BaseCx, Contexts},
+        layout::Rect,
+        views::{
+            size,
+            testing::{save_layout, test_layout},
+            vstack,
+        },
+    };
+
+    use super::*;
+
+    #[test]
+    fn spreads_are_laid_out_in_order() {
+        let items = (0..3).map(|i| save_layout(size([10.0, 10.0], ()), format!("item {i}")));
+
+        let mut view = vstack![
+            save_layout(size([10.0, 5.0], ()), "header"),
+            ..items,
+            save_layout(size([10.0, 5.0], ()), "footer"),
+        ]
+        .gap(0.0);
+
+        assert_eq!(view.content.len::<()>(), 5);
+
+        let layouts = test_layout(&mut view, &mut (), Space::UNBOUNDED);
+
+        assert_eq!(layouts["header"], Rect::from([0.0, 0.0, 10.0, 5.0]));
+        assert_eq!(layouts["item 0"], Rect::from([0.0, 5.0, 10.0, 15.0]));
+        assert_eq!(layouts["item 2"], Rect::from([0.0, 25.0, 10.0, 35.0]));
+        assert_eq!(layouts["footer"], Rect::from([0.0, 35.0, 10.0, 40.0]));
+    }
+
+    #[test]
+    fn rebuilding_a_spread_keeps_the_views_after_it() {
+        let children = |n: usize| {
+            let items = (0..n).map(|_| size([10.0, 10.0], ()));
+            children![size([10.0, 5.0], ()), ..items, size([10.0, 5.0], ())]
+        };
+
+        let mut contexts = Contexts::new();
+        let (mut proxy, _rx) = CommandProxy::new(CommandWaker::new(|| {}));
+        let mut base_cx = BaseCx::new(&mut contexts, &mut proxy);
+        let mut view_state = ViewState::default();
+        let mut cx = BuildCx::new(&mut base_cx, &mut view_state);
+
+        let mut old = children(3);
+        let (mut state, mut view_states) = old.build(&mut cx, &mut ());
+        assert_eq!((ViewSeq::<()>::len(&old), view_states.len()), (5, 5));
+
+        let header = view_states[0].id();
+        let footer = view_states[4].id();
+
+        // the spread shrinks, and then grows past its old length
+        for n in [1, 4] {
+            let mut new = children(n);
+            new.rebuild(&mut state, &mut view_states, &mut cx, &mut (), &old);
+
+            assert_eq!(ViewSeq::<()>::len(&new), n + 2);
+            assert_eq!(view_states.len(), n + 2);
+            assert_eq!(view_states[0].id(), header);
+            assert_eq!(view_states[n + 1].id(), footer);
+
+            old = new;
+        }
+    }
+}
@@ -7,7 +7,7 @@ use std::{
 
 use crate::{
     event::Ime,
-    layout::{Affine, Point, Rect, Size, Vector},
+    layout::{Affine, Axis, Point, Rect, Size, Space, Vector},
     window::Cursor,
 };
 
@@ -30,7 +30,7 @@ bitflags::bitflags! {
 bitflags::bitflags! {
     /// Flags that indicate state of a view.
     #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
-    pub struct ViewFlags: u8 {
+    pub struct ViewFlags: u16 {
         /// The view is hovered.
         const HOVERED = 1 << 0;
 
@@ -52,17 +52,32 @@ bitflags::bitflags! {
         /// The view is focusable.
         const FOCUSABLE = 1 << 6;
 
+        /// The view has captured a pointer.
+        const CAPTURING = 1 << 7;
+
+        /// The view has a child that has captured a pointer.
+        const HAS_CAPTURING = 1 << 8;
+
         /// Equivalent to `Self::HOVERED | Self::FOCUSED | Self::ACTIVE`.
         const IS = Self::HOVERED.bits() | Self::FOCUSED.bits() | Self::ACTIVE.bits();
 
-        /// Equivalent to `Self::HAS_HOVERED | Self::HAS_FOCUSED | Self::HAS_ACTIVE`.
-        const HAS = Self::HAS_HOVERED.bits() | Self::HAS_FOCUSED.bits() | Self::HAS_ACTIVE.bits();
+        /// Equivalent to `Self::HAS_HOVERED | Self::HAS_FOCUSED | Self::HAS_ACTIVE | Self::HAS_CAPTURING`.
+        const HAS = Self::HAS_HOVERED.bits()
+            | Self::HAS_FOCUSED.bits()
+            | Self::HAS_ACTIVE.bits()
+            | Self::HAS_CAPTURING.bits();
     }
 }
 
 impl ViewFlags {
     fn has(self) -> Self {
-        (self & Self::HAS) | Self::from_bits_retain((self & Self::IS).bits() << 3)
+        let mut has = (self & Self::HAS) | Self::from_bits_retain((self & Self::IS).bits() << 3);
+
+        if self.contains(Self::CAPTURING) {
+            has |= Self::HAS_CAPTURING;
+        }
+
+        has
     }
 }
 
@@ -120,6 +135,8 @@ pub struct ViewState {
     pub(crate) prev_flags: ViewFlags,
     pub(crate) flags: ViewFlags,
     pub(crate) update: Update,
+    pub(crate) damaged: bool,
+    pub(crate) drawn_bounds: Option<Rect>,
 
     /* properties */
     pub(crate) properties: Properties,
@@ -130,6 +147,9 @@ pub struct ViewState {
     /* layout */
     pub(crate) size: Size,
     pub(crate) transform: Affine,
+    pub(crate) opacity: f32,
+    pub(crate) intrinsic: Vec<(Axis, f32, f32)>,
+    pub(crate) layout: Option<(Space, Size)>,
 
     /* cursor */
     pub(crate) cursor: Option<Cursor>,
@@ -147,6 +167,8 @@ impl Default for ViewState {
 }
 
 impl ViewState {
+    const INTRINSIC_CACHE_SIZE: usize = 4;
+
     /// Create a new [`ViewState`] with the given [`ViewId`].
     pub fn new(id: ViewId) -> Self {
         Self {
@@ -156,6 +178,9 @@ impl ViewState {
             prev_flags: ViewFlags::default(),
             flags: ViewFlags::default(),
             update: Update::LAYOUT | Update::DRAW,
+            // the bounds of a view are recorded the first time it's drawn
+            damaged: true,
+            drawn_bounds: None,
 
             /* properties */
             properties: Properties::new(),
@@ -166,6 +191,9 @@ impl ViewState {
             /* layout */
             size: Size::ZERO,
             transform: Affine::IDENTITY,
+            opacity: 1.0,
+            intrinsic: Vec::new(),
+            layout: None,
 
             /* cursor */
             cursor: None,
@@ -188,6 +216,10 @@ impl ViewState {
 
     /// Propagate the state of a child view.
     pub fn propagate(&mut self, child: &mut Self) {
+        if child.needs_layout() {
+            self.intrinsic.clear();
+        }
+
         self.update |= child.update;
         self.flags |= child.flags.has();
         self.inherited_cursor = self.cursor().or(child.cursor());
@@ -199,6 +231,11 @@ impl ViewState {
         self.id
     }
 
+    /// Set the id of the view.
+    pub fn set_id(&mut self, id: ViewId) {
+        self.id = id;
+    }
+
     /// Get whether the view is hovered.
     pub fn is_hovered(&self) -> bool {
         self.flags.contains(ViewFlags::HOVERED)
@@ -229,6 +266,16 @@ impl ViewState {
         self.flags.set(ViewFlags::ACTIVE, active);
     }
 
+    /// Get whether the view has captured a pointer.
+    pub fn is_capturing(&self) -> bool {
+        self.flags.contains(ViewFlags::CAPTURING)
+    }
+
+    /// Set whether the view has captured a pointer.
+    pub fn set_capturing(&mut self, capturing: bool) {
+        self.flags.set(ViewFlags::CAPTURING, capturing);
+    }
+
     /// Get whether the view has a hovered child.
     pub fn has_hovered(&self) -> bool {
         let flags = self.flags & (ViewFlags::HOVERED | ViewFlags::HAS_HOVERED);
@@ -247,6 +294,12 @@ impl ViewState {
         flags != ViewFlags::empty()
     }
 
+    /// Get whether the view or a child has captured a pointer.
+    pub fn has_capturing(&self) -> bool {
+        let flags = self.flags & (ViewFlags::CAPTURING | ViewFlags::HAS_CAPTURING);
+        flags != ViewFlags::empty()
+    }
+
     /// Get whether the view is focusable.
     pub fn is_focusable(&self) -> bool {
         self.flags.contains(ViewFlags::FOCUSABLE)
@@ -317,6 +370,42 @@ impl ViewState {
         self.size
     }
 
+    /// Get a cached intrinsic size of the view, see [`View::intrinsic_size`].
+    ///
+    /// [`View::intrinsic_size`]: super::View::intrinsic_size
+    pub fn cached_intrinsic_size(&self, axis: Axis, cross_extent: f32) -> Option<f32> {
+        (self.intrinsic.iter())
+            .find(|(a, cross, _)| *a == axis && *cross == cross_extent)
+            .map(|(_, _, size)| *size)
+    }
+
+    /// Cache an intrinsic size of the view, until a layout is requested.
+    pub fn cache_intrinsic_size(&mut self, axis: Axis, cross_extent: f32, size: f32) {
+        // containers usually ask for a few different extents, so only the latest are kept
+        if self.intrinsic.len() >= Self::INTRINSIC_CACHE_SIZE {
+            self.intrinsic.remove(0);
+        }
+
+        self.intrinsic.push((axis, cross_extent, size));
+    }
+
+    /// Get the size the view was laid out with in `space`, if the view hasn't requested a
+    /// layout since.
+    pub fn cached_layout(&self, space: Space) -> Option<Size> {
+        if self.needs_layout() {
+            return None;
+        }
+
+        (self.layout)
+            .filter(|(cached, _)| *cached == space)
+            .map(|(_, size)| size)
+    }
+
+    /// Cache the size the view was laid out with in `space`, until a layout is requested.
+    pub fn cache_layout(&mut self, space: Space, size: Size) {
+        self.layout = Some((space, size));
+    }
+
     /// Get the rect of the view in local coordinates.
     pub fn rect(&self) -> Rect {
         Rect::min_size(Point::ZERO, self.size)
@@ -332,6 +421,19 @@ impl ViewState {
         self.transform = transform;
     }
 
+    /// Get the opacity of the view.
+    pub fn opacity(&self) -> f32 {
+        self.opacity
+    }
+
+    /// Set the opacity of the view, clamped to `0.0..=1.0`.
+    ///
+    /// Everything the view draws is composited as a unit with the opacity, so overlapping
+    /// content doesn't show through itself.
+    pub fn set_opacity(&mut self, opacity: f32) {
+        self.opacity = opacity.clamp(0.0, 1.0);
+    }
+
     /// Translate the transform of the view.
     pub fn translate(&mut self, translation: Vector) {
         self.transform = Affine::translate(translation);
@@ -339,12 +441,17 @@ impl ViewState {
 
     /// Request a layout of the view tree.
     pub fn request_layout(&mut self) {
+        self.intrinsic.clear();
         self.update |= Update::LAYOUT | Update::DRAW;
     }
 
     /// Request a draw of the view tree.
+    ///
+    /// Unlike [`Update::DRAW`], which is propagated to the ancestors of the view, only the
+    /// view itself is marked as damaged, see [`Damage`](crate::canvas::Damage).
     pub fn request_draw(&mut self) {
         self.update |= Update::DRAW;
+        self.damaged = true;
     }
 
     /// Request an animation frame of the view tree.
@@ -362,6 +469,11 @@ impl ViewState {
         self.update.contains(Update::DRAW)
     }
 
+    /// Get whether the view requested a draw itself, since it was last drawn.
+    pub fn is_damaged(&self) -> bool {
+        self.damaged
+    }
+
     /// Get whether the view needs an animation frame.
     pub fn needs_animate(&self) -> bool {
         self.update.contains(Update::ANIMATE)
@@ -376,9 +488,10 @@ impl ViewState {
 
     /// Mark the view as drawn.
     ///
-    /// This will remove the [`Update::DRAW`] flag.
+    /// This will remove the [`Update::DRAW`] flag, and the view is no longer damaged.
     pub fn mark_drawn(&mut self) {
         self.update.remove(Update::DRAW);
+        self.damaged = false;
     }
 
     /// Mark the view as animated.
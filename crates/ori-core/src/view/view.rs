@@ -1,7 +1,7 @@
 use crate::{
     context::{BuildCx, DrawCx, EventCx, LayoutCx, RebuildCx},
     event::Event,
-    layout::{Size, Space},
+    layout::{Axis, Size, Space},
 };
 
 /// A single UI component.
@@ -34,6 +34,15 @@ use crate::{
 ///     contents and the compute it's own size based on the contents' size(s).
 /// - [`View::draw`] is called when the view needs to be drawn.
 ///
+/// During layout a container can ask its contents for their natural size along an axis,
+/// before committing space to them, with [`View::intrinsic_size`]. The default implementation
+/// lays the view out in a loose space, and records that as the latest layout of the view, so a
+/// container laying the view out in the same space afterwards reuses it instead of laying the
+/// view out again. Views like [`Text`] override it to measure themselves without laying out.
+/// A [`Pod`] caches the intrinsic sizes of its content until a layout of it is requested, so a
+/// container can ask as often as it needs during a layout, without the content being measured
+/// more than once.
+///
 /// For examples see the implementation of views like [`Button`] or [`Checkbox`].
 ///
 /// [`BaseCx::cmd`]: crate::context::BaseCx::cmd
@@ -43,6 +52,8 @@ use crate::{
 /// [`Rebuild`]: crate::rebuild::Rebuild
 /// [`Button`]: crate::views::Button
 /// [`Checkbox`]: crate::views::Checkbox
+/// [`Text`]: crate::views::Text
+/// [`Pod`]: super::Pod
 pub trait View<T: ?Sized = ()> {
     /// The state of the view, see top-level documentation for more information.
     type State;
@@ -73,6 +84,31 @@ pub trait View<T: ?Sized = ()> {
         space: Space,
     ) -> Size;
 
+    /// Get the intrinsic size of the view along an `axis`, see top-level documentation for
+    /// more information.
+    ///
+    /// `cross_extent` is the space available on the other axis, and may be infinite.
+    #[must_use]
+    fn intrinsic_size(
+        &mut self,
+        state: &mut Self::State,
+        cx: &mut LayoutCx,
+        data: &mut T,
+        axis: Axis,
+        cross_extent: f32,
+    ) -> f32 {
+        let space = Space::new(Size::ZERO, axis.pack(f32::INFINITY, cross_extent));
+
+        cx.view_state.mark_layed_out();
+        let size = self.layout(state, cx, data, space);
+
+        // the view is now laid out in `space`, a pod laid out in it next won't do it again,
+        // and one laid out in the space it was laid out in before must not skip it
+        cx.view_state.layout = size.is_finite().then_some((space, size));
+
+        axis.major(size)
+    }
+
     /// Draw the view, see top-level documentation for more information.
     fn draw(&mut self, state: &mut Self::State, cx: &mut DrawCx, data: &mut T);
 }
@@ -125,6 +161,22 @@ impl<T, V: View<T>> View<T> for Option<V> {
         }
     }
 
+    fn intrinsic_size(
+        &mut self,
+        state: &mut Self::State,
+        cx: &mut LayoutCx,
+        data: &mut T,
+        axis: Axis,
+        cross_extent: f32,
+    ) -> f32 {
+        match self {
+            Some(view) => {
+                view.intrinsic_size(state.as_mut().unwrap(), cx, data, axis, cross_extent)
+            }
+            None => 0.0,
+        }
+    }
+
     fn draw(&mut self, state: &mut Self::State, cx: &mut DrawCx, data: &mut T) {
         if let Some(view) = self {
             view.draw(state.as_mut().unwrap(), cx, data);
@@ -220,6 +272,21 @@ impl<T, V: View<T>, E: View<T>> View<T> for Result<V, E> {
         }
     }
 
+    fn intrinsic_size(
+        &mut self,
+        state: &mut Self::State,
+        cx: &mut LayoutCx,
+        data: &mut T,
+        axis: Axis,
+        cross_extent: f32,
+    ) -> f32 {
+        match (self, state) {
+            (Ok(view), Ok(state)) => view.intrinsic_size(state, cx, data, axis, cross_extent),
+            (Err(view), Err(state)) => view.intrinsic_size(state, cx, data, axis, cross_extent),
+            _ => 0.0,
+        }
+    }
+
     fn draw(&mut self, state: &mut Self::State, cx: &mut DrawCx, data: &mut T) {
         match (self, state) {
             (Ok(view), Ok(state)) => view.draw(state, cx, data),
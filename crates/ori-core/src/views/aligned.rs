@@ -9,6 +9,9 @@ use crate::{
 };
 
 /// Create a new [`Aligned`] view.
+///
+/// The alignment is either one of the named [`Alignment`] constants, or a fractional anchor
+/// like `(0.5, 0.2)`.
 pub fn align<V>(alignment: impl Into<Alignment>, view: V) -> Aligned<V> {
     Aligned::new(alignment.into(), view)
 }
@@ -59,6 +62,9 @@ pub fn bottom_right<V>(view: V) -> Aligned<V> {
 }
 
 /// A view that aligns its content.
+///
+/// The content is laid out at its natural size, with the constraints loosened, and positioned
+/// in the available space by the [`Alignment`].
 #[example(name = "align", width = 400, height = 300)]
 #[derive(Rebuild)]
 pub struct Aligned<V> {
@@ -126,3 +132,45 @@ impl<T, V: View<T>> View<T> for Aligned<V> {
         self.content.draw(state, cx, data);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        layout::Vector,
+        views::{size, testing::ViewTester},
+    };
+
+    use super::*;
+
+    #[test]
+    fn named_anchors_place_content_in_space() {
+        let anchors = [
+            (Alignment::TOP_LEFT, Vector::new(0.0, 0.0)),
+            (Alignment::TOP, Vector::new(40.0, 0.0)),
+            (Alignment::TOP_RIGHT, Vector::new(80.0, 0.0)),
+            (Alignment::LEFT, Vector::new(0.0, 45.0)),
+            (Alignment::CENTER, Vector::new(40.0, 45.0)),
+            (Alignment::RIGHT, Vector::new(80.0, 45.0)),
+            (Alignment::BOTTOM_LEFT, Vector::new(0.0, 90.0)),
+            (Alignment::BOTTOM, Vector::new(40.0, 90.0)),
+            (Alignment::BOTTOM_RIGHT, Vector::new(80.0, 90.0)),
+        ];
+
+        for (alignment, offset) in anchors {
+            // a 20 by 10 child in a 100 by 100 box
+            let mut view = align(alignment, size((20.0, 10.0), ()));
+
+            let mut tester = ViewTester::new(&mut view, &mut ());
+            let size = tester.layout(&mut view, &mut (), Space::new(Size::ZERO, Size::all(100.0)));
+
+            assert_eq!(size, Size::all(100.0));
+            assert_eq!(tester.state.size(), Size::new(20.0, 10.0));
+            assert_eq!(
+                tester.state.transform().translation,
+                offset,
+                "{:?}",
+                alignment
+            );
+        }
+    }
+}
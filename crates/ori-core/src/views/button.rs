@@ -37,8 +37,11 @@ pub struct Button<V> {
     pub fancy: Styled<f32>,
 
     /// The transition of the button.
+    ///
+    /// Can also be set for the `color` with the `transition` shorthand, eg.
+    /// `button { transition: color 0.2s, }`.
     #[rebuild(draw)]
-    #[styled(default = Transition::ease(0.1))]
+    #[styled(default = Transition::ease(0.1), transition_of = color)]
     pub transition: Styled<Transition>,
 
     /// The color of the button.
@@ -166,10 +169,11 @@ impl<T, V: View<T>> View<T> for Button<V> {
             let dim = state.style.color.darken(0.025);
             let bright = state.style.color.lighten(0.05);
 
-            let hovered = state.style.transition.get(state.hovered);
-            let active = state.style.transition.get(state.active);
+            let transition = state.style.transition;
+            let active = transition.get(state.active);
 
-            let face = state.style.color.mix(bright, hovered).mix(dim, active);
+            let face = transition.mix(state.style.color, bright, state.hovered);
+            let face = transition.mix(face, dim, state.active);
 
             if cx.is_focused() {
                 cx.quad(
@@ -194,7 +198,7 @@ impl<T, V: View<T>> View<T> for Button<V> {
                 return;
             }
 
-            let base = dim.mix(dark, 1.0 - active);
+            let base = transition.mix(dark, dim, state.active);
 
             cx.quad(
                 cx.rect(),
@@ -220,3 +224,76 @@ impl<T, V: View<T>> View<T> for Button<V> {
         });
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        canvas::{ColorInterpolation, Primitive, Shader},
+        style::Styles,
+        views::testing::ViewTester,
+    };
+
+    use super::*;
+
+    // the color of the face of a button, half way through the hover transition
+    fn half_hovered_face(transition: Transition) -> Color {
+        let mut view = button(()).color(Color::RED).transition(transition);
+        let mut tester = ViewTester::new(&mut view, &mut ());
+        tester.layout(&mut view, &mut (), Space::UNBOUNDED);
+        tester.state.0.hovered = 0.5;
+
+        fn first_fill<'a>(mut primitives: impl Iterator<Item = &'a Primitive>) -> Option<Color> {
+            primitives.find_map(|primitive| match primitive {
+                Primitive::Fill { paint, .. } | Primitive::RoundedRect { fill: paint, .. } => {
+                    match paint.shader {
+                        Shader::Solid(color) => Some(color),
+                        _ => None,
+                    }
+                }
+                Primitive::Layer { primitives, .. } => first_fill(primitives.iter()),
+                _ => None,
+            })
+        }
+
+        let canvas = tester.draw(&mut view, &mut ());
+        first_fill(canvas.primitives()).unwrap()
+    }
+
+    #[test]
+    fn hover_is_mixed_in_the_color_space_of_the_transition() {
+        let bright = Color::RED.lighten(0.05);
+
+        // mixing in oklab round trips through it, which isn't exact
+        let close = |a: Color, b: Color| {
+            let [a, b] = [a, b].map(|c| [c.r, c.g, c.b, c.a]);
+            a.iter().zip(b).all(|(a, b)| (a - b).abs() < 1e-4)
+        };
+
+        let srgb = Transition::linear(1.0).interpolation(ColorInterpolation::Srgb);
+        let srgb_face = Color::RED.mix_in(bright, 0.5, ColorInterpolation::Srgb);
+        assert!(close(half_hovered_face(srgb), srgb_face));
+
+        let oklab = Transition::linear(1.0);
+        let oklab_face = Color::RED.mix_in(bright, 0.5, ColorInterpolation::Oklab);
+        assert!(close(half_hovered_face(oklab), oklab_face));
+        assert!(!close(srgb_face, oklab_face));
+    }
+
+    #[test]
+    fn transition_shorthand_sets_transition_of_color() {
+        let styles: Styles = "button { transition: color 0.3s linear, }".parse().unwrap();
+
+        let style = ButtonStyle::styled(&button(()), &styles);
+        assert_eq!(style.transition, Transition::linear(0.3));
+
+        // a transition set on the button itself is kept
+        let view = button(()).transition(Transition::ease(0.5));
+        let style = ButtonStyle::styled(&view, &styles);
+        assert_eq!(style.transition, Transition::ease(0.5));
+
+        // as are the transitions of other properties
+        let styles: Styles = "button { transition: border-color 0.3s, }".parse().unwrap();
+        let style = ButtonStyle::styled(&button(()), &styles);
+        assert_eq!(style.transition, Transition::ease(0.1));
+    }
+}
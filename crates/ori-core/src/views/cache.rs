@@ -0,0 +1,167 @@
+use crate::{
+    canvas::Canvas,
+    context::{BuildCx, DrawCx, EventCx, LayoutCx, RebuildCx},
+    event::Event,
+    layout::{Size, Space},
+    view::{Pod, State, View},
+};
+
+/// Create a new [`Cache`] view.
+pub fn cache<V>(content: V) -> Cache<V> {
+    Cache::new(content)
+}
+
+/// A view that records the drawing of its content, and replays it until the content
+/// needs to be drawn again.
+///
+/// Every [`Pod`] already caches its drawing, but that cache is invalidated whenever the
+/// visible area changes. The recording of a [`Cache`] includes the entire content, so it can
+/// be replayed at any offset, eg. when the content is moved by a scroll view.
+pub struct Cache<V> {
+    /// The content.
+    pub content: Pod<V>,
+}
+
+impl<V> Cache<V> {
+    /// Create a new [`Cache`] view.
+    pub fn new(content: V) -> Self {
+        Self {
+            content: Pod::new(content),
+        }
+    }
+}
+
+#[doc(hidden)]
+pub struct CacheState<T, V: View<T>> {
+    content: State<T, V>,
+    recording: Option<Canvas>,
+    size: Size,
+}
+
+impl<T, V: View<T>> View<T> for Cache<V> {
+    type State = CacheState<T, V>;
+
+    fn build(&mut self, cx: &mut BuildCx, data: &mut T) -> Self::State {
+        CacheState {
+            content: self.content.build(cx, data),
+            recording: None,
+            size: Size::ZERO,
+        }
+    }
+
+    fn rebuild(&mut self, state: &mut Self::State, cx: &mut RebuildCx, data: &mut T, old: &Self) {
+        (self.content).rebuild(&mut state.content, cx, data, &old.content);
+    }
+
+    fn event(
+        &mut self,
+        state: &mut Self::State,
+        cx: &mut EventCx,
+        data: &mut T,
+        event: &Event,
+    ) -> bool {
+        self.content.event(&mut state.content, cx, data, event)
+    }
+
+    fn layout(
+        &mut self,
+        state: &mut Self::State,
+        cx: &mut LayoutCx,
+        data: &mut T,
+        space: Space,
+    ) -> Size {
+        self.content.layout(&mut state.content, cx, data, space)
+    }
+
+    fn draw(&mut self, state: &mut Self::State, cx: &mut DrawCx, data: &mut T) {
+        // the recording is stale if anything in the content requested a draw,
+        // or if the content was resized
+        let is_stale = state.content.needs_draw() || state.size != cx.size();
+
+        if let (Some(recording), false) = (&state.recording, is_stale) {
+            cx.draw_canvas(recording.clone());
+            return;
+        }
+
+        let recording = cx.record(|cx| {
+            self.content.draw(&mut state.content, cx, data);
+        });
+
+        cx.draw_canvas(recording.clone());
+
+        state.recording = Some(recording);
+        state.size = cx.size();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use crate::{canvas::Color, views::testing::ViewTester};
+
+    use super::*;
+
+    struct CountDraws {
+        color: Color,
+        draws: Arc<AtomicUsize>,
+    }
+
+    impl View for CountDraws {
+        type State = ();
+
+        fn build(&mut self, _cx: &mut BuildCx, _data: &mut ()) -> Self::State {}
+
+        fn rebuild(&mut self, _: &mut (), cx: &mut RebuildCx, _: &mut (), old: &Self) {
+            if self.color != old.color {
+                cx.draw();
+            }
+        }
+
+        fn event(&mut self, _: &mut (), _: &mut EventCx, _: &mut (), _: &Event) -> bool {
+            false
+        }
+
+        fn layout(&mut self, _: &mut (), _: &mut LayoutCx, _: &mut (), _: Space) -> Size {
+            Size::all(10.0)
+        }
+
+        fn draw(&mut self, _: &mut (), cx: &mut DrawCx, _: &mut ()) {
+            self.draws.fetch_add(1, Ordering::SeqCst);
+            cx.fill_rect(cx.rect(), self.color);
+        }
+    }
+
+    #[test]
+    fn replay_until_invalidated() {
+        let draws = Arc::new(AtomicUsize::new(0));
+
+        let mut view = cache(CountDraws {
+            color: Color::RED,
+            draws: draws.clone(),
+        });
+
+        let mut tester = ViewTester::new(&mut view, &mut ());
+        tester.layout(&mut view, &mut (), Space::UNBOUNDED);
+
+        let first = tester.draw(&mut view, &mut ());
+        let second = tester.draw(&mut view, &mut ());
+
+        assert_eq!(draws.load(Ordering::SeqCst), 1);
+        assert_eq!(first, second);
+
+        let mut new_view = cache(CountDraws {
+            color: Color::BLUE,
+            draws: draws.clone(),
+        });
+
+        tester.rebuild(&mut new_view, &mut (), &view);
+        let third = tester.draw(&mut new_view, &mut ());
+
+        assert_eq!(draws.load(Ordering::SeqCst), 2);
+        assert_ne!(second, third);
+    }
+}
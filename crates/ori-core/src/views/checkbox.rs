@@ -27,8 +27,11 @@ pub struct Checkbox {
     pub checked: bool,
 
     /// The transition of the checkbox.
+    ///
+    /// Can also be set for the `border-color` with the `transition` shorthand, eg.
+    /// `checkbox { transition: border-color 0.2s, }`.
     #[rebuild(draw)]
-    #[styled(default = Transition::ease(0.1))]
+    #[styled(default = Transition::ease(0.1), transition_of = border_color)]
     pub transition: Styled<Transition>,
 
     /// The size of the checkbox.
@@ -149,7 +152,7 @@ impl<T> View<T> for Checkbox {
 
             let border_color = match cx.is_focused() {
                 true => cx.styles().get_or(Color::BLUE, &Theme::INFO),
-                false => style.border_color.mix(bright, style.transition.get(*t)),
+                false => style.transition.mix(style.border_color, bright, *t),
             };
 
             cx.quad(
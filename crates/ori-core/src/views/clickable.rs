@@ -4,8 +4,8 @@ use ori_macro::Build;
 
 use crate::{
     context::{BuildCx, DrawCx, EventCx, LayoutCx, RebuildCx},
-    event::{Event, Key, PointerButton},
-    layout::{Size, Space},
+    event::{Event, Key, PointerButton, PointerId},
+    layout::{Point, Size, Space},
     rebuild::Rebuild,
     view::{Pod, State, View},
 };
@@ -37,6 +37,45 @@ where
     Clickable::new(content, ClickEvent::Click, on_click)
 }
 
+/// Create a new [`Clickable`], that calls `on_long_press` when pressed and held.
+///
+/// This is the touch equivalent of a right click, and pairs well with a context menu.
+pub fn on_long_press<T, V, F>(content: V, on_long_press: F) -> Clickable<T, V, F>
+where
+    V: View<T>,
+    F: FnMut(&mut EventCx, &mut T) + 'static,
+{
+    Clickable::new(content, ClickEvent::LongPress, on_long_press)
+}
+
+/// Create a new [`Clickable`], that calls `on_double_click` when clicked twice in a row.
+///
+/// The second click must follow the first within [`Clickable::double_click_interval`], without
+/// the pointer moving more than [`DOUBLE_CLICK_SLOP`] in between.
+pub fn on_double_click<T, V, F>(content: V, on_double_click: F) -> Clickable<T, V, F>
+where
+    V: View<T>,
+    F: FnMut(&mut EventCx, &mut T) + 'static,
+{
+    Clickable::new(content, ClickEvent::DoubleClick, on_double_click)
+}
+
+/// Create a new [`Clickable`], that calls `on_right_click` when clicked with the
+/// [secondary](PointerButton::Secondary) button.
+pub fn on_right_click<T, V, F>(content: V, on_right_click: F) -> Clickable<T, V, F>
+where
+    V: View<T>,
+    F: FnMut(&mut EventCx, &mut T) + 'static,
+{
+    Clickable::new(content, ClickEvent::RightClick, on_right_click)
+}
+
+/// The distance a pointer can move before a long press is cancelled.
+pub const LONG_PRESS_SLOP: f32 = 8.0;
+
+/// The distance a pointer can move between the clicks of a double click.
+pub const DOUBLE_CLICK_SLOP: f32 = 4.0;
+
 /// A click event.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum ClickEvent {
@@ -48,6 +87,15 @@ pub enum ClickEvent {
 
     /// The click event.
     Click,
+
+    /// The long press event, fired when a press is held without moving.
+    LongPress,
+
+    /// The double click event, fired on the second of two clicks in quick succession.
+    DoubleClick,
+
+    /// The right click event, fired when clicked with the secondary button.
+    RightClick,
 }
 
 /// A click handler.
@@ -67,12 +115,23 @@ where
 
     /// The button to listen for.
     ///
-    /// If `Some` the callbacks will only be called when this button is pressed.
+    /// If `Some` the callbacks will only be called when this button is pressed. This is
+    /// ignored for [`ClickEvent::RightClick`], which always listens for the secondary button.
     pub button: Option<PointerButton>,
 
     /// The event to listen for.
     pub event: ClickEvent,
 
+    /// The time in seconds a press must be held to be a long press.
+    ///
+    /// Defaults to `0.5`.
+    pub long_press_delay: f32,
+
+    /// The time in seconds the second click of a double click must follow the first within.
+    ///
+    /// Defaults to `0.4`.
+    pub double_click_interval: f32,
+
     /// The callback.
     #[build(ignore)]
     pub callback: F,
@@ -92,28 +151,60 @@ where
             descendants: true,
             button: None,
             event,
+            long_press_delay: 0.5,
+            double_click_interval: 0.4,
             callback,
             marker: PhantomData,
         }
     }
 
     fn is_button(&self, button: PointerButton) -> bool {
-        self.button.map_or(true, |b| b == button)
+        match self.event {
+            ClickEvent::RightClick => button == PointerButton::Secondary,
+            _ => self.button.map_or(true, |b| b == button),
+        }
     }
 }
 
+#[doc(hidden)]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ClickableState {
+    long_press: Option<LongPress>,
+    last_click: Option<LastClick>,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct LongPress {
+    pointer: PointerId,
+    origin: Point,
+    held: f32,
+}
+
+// the first click of a possible double click
+#[derive(Clone, Copy, Debug)]
+struct LastClick {
+    origin: Point,
+    elapsed: f32,
+}
+
 impl<T, V, F> View<T> for Clickable<T, V, F>
 where
     V: View<T>,
     F: FnMut(&mut EventCx, &mut T) + 'static,
 {
-    type State = State<T, V>;
+    type State = (ClickableState, State<T, V>);
 
     fn build(&mut self, cx: &mut BuildCx, data: &mut T) -> Self::State {
-        self.content.build(cx, data)
+        (ClickableState::default(), self.content.build(cx, data))
     }
 
-    fn rebuild(&mut self, content: &mut Self::State, cx: &mut RebuildCx, data: &mut T, old: &Self) {
+    fn rebuild(
+        &mut self,
+        (_state, content): &mut Self::State,
+        cx: &mut RebuildCx,
+        data: &mut T,
+        old: &Self,
+    ) {
         Rebuild::rebuild(self, cx, old);
 
         self.content.rebuild(content, cx, data, &old.content);
@@ -121,7 +212,7 @@ where
 
     fn event(
         &mut self,
-        content: &mut Self::State,
+        (state, content): &mut Self::State,
         cx: &mut EventCx,
         data: &mut T,
         event: &Event,
@@ -136,6 +227,16 @@ where
                     handled = true;
                 }
 
+                if self.event == ClickEvent::LongPress {
+                    state.long_press = Some(LongPress {
+                        pointer: e.id,
+                        origin: e.position,
+                        held: 0.0,
+                    });
+
+                    cx.animate();
+                }
+
                 content.set_active(true);
             }
 
@@ -149,14 +250,75 @@ where
                     handled = true;
                 }
 
-                if e.clicked && self.event == ClickEvent::Click {
+                if e.clicked && matches!(self.event, ClickEvent::Click | ClickEvent::RightClick) {
                     (self.callback)(cx, data);
                     handled = true;
                 }
 
+                if e.clicked && self.event == ClickEvent::DoubleClick {
+                    match state.last_click.take() {
+                        Some(last) if last.origin.distance(e.position) <= DOUBLE_CLICK_SLOP => {
+                            (self.callback)(cx, data);
+                            handled = true;
+                        }
+                        _ => {
+                            state.last_click = Some(LastClick {
+                                origin: e.position,
+                                elapsed: 0.0,
+                            });
+
+                            cx.animate();
+                        }
+                    }
+                }
+
+                state.long_press = None;
                 content.set_active(false);
             }
 
+            Event::PointerMoved(e) => {
+                // moving too far turns the press into a drag, and cancels the long press
+                if let Some(long_press) = state.long_press {
+                    let moved = long_press.origin.distance(e.position) > LONG_PRESS_SLOP;
+
+                    if long_press.pointer == e.id && moved {
+                        state.long_press = None;
+                    }
+                }
+
+                // moving away between clicks starts over
+                if let Some(last_click) = state.last_click {
+                    if last_click.origin.distance(e.position) > DOUBLE_CLICK_SLOP {
+                        state.last_click = None;
+                    }
+                }
+            }
+
+            Event::Animate(dt) => {
+                if let Some(ref mut last_click) = state.last_click {
+                    last_click.elapsed += dt;
+
+                    if last_click.elapsed > self.double_click_interval {
+                        state.last_click = None;
+                    } else {
+                        cx.animate();
+                    }
+                }
+
+                if let Some(ref mut long_press) = state.long_press {
+                    long_press.held += dt;
+
+                    if long_press.held >= self.long_press_delay {
+                        state.long_press = None;
+
+                        (self.callback)(cx, data);
+                        handled = true;
+                    } else {
+                        cx.animate();
+                    }
+                }
+            }
+
             Event::KeyPressed(e) if content.is_focused() => {
                 if e.is_key(Key::Enter) || e.is_key(' ') {
                     if matches!(self.event, ClickEvent::Press | ClickEvent::Click) {
@@ -187,7 +349,7 @@ where
 
     fn layout(
         &mut self,
-        content: &mut Self::State,
+        (_state, content): &mut Self::State,
         cx: &mut LayoutCx,
         data: &mut T,
         space: Space,
@@ -195,7 +357,114 @@ where
         self.content.layout(content, cx, data, space)
     }
 
-    fn draw(&mut self, content: &mut Self::State, cx: &mut DrawCx, data: &mut T) {
+    fn draw(&mut self, (_state, content): &mut Self::State, cx: &mut DrawCx, data: &mut T) {
         self.content.draw(content, cx, data);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::views::{size, testing::ViewTester};
+
+    use super::*;
+
+    #[test]
+    fn held_press_fires_long_press_once() {
+        let mut view = on_long_press(size(10.0, ()), |_, presses: &mut u32| *presses += 1);
+        let mut presses = 0;
+
+        let mut tester = ViewTester::new(&mut view, &mut presses);
+        tester.state.1.set_hovered(true);
+
+        let position = Point::new(5.0, 5.0);
+        tester.press(&mut view, &mut presses, position, PointerButton::Primary);
+
+        for _ in 0..10 {
+            tester.event(&mut view, &mut presses, &Event::Animate(0.1));
+        }
+
+        assert_eq!(presses, 1);
+    }
+
+    // click with `button` at `position`
+    fn click<V: View<u32>>(
+        tester: &mut ViewTester<u32, V>,
+        view: &mut V,
+        clicks: &mut u32,
+        button: PointerButton,
+        position: Point,
+    ) {
+        tester.press(view, clicks, position, button);
+        tester.release(view, clicks, position, button);
+    }
+
+    #[test]
+    fn double_click_within_interval_and_slop() {
+        let mut view = on_double_click(size(10.0, ()), |_, clicks: &mut u32| *clicks += 1);
+        let mut clicks = 0;
+
+        let mut tester = ViewTester::new(&mut view, &mut clicks);
+        tester.state.1.set_hovered(true);
+        tester.hover(tester.state.1.id(), Point::ZERO);
+
+        let primary = PointerButton::Primary;
+        let origin = Point::new(5.0, 5.0);
+
+        // a single click isn't a double click
+        click(&mut tester, &mut view, &mut clicks, primary, origin);
+        assert_eq!(clicks, 0);
+
+        click(
+            &mut tester,
+            &mut view,
+            &mut clicks,
+            primary,
+            Point::new(6.0, 5.0),
+        );
+        assert_eq!(clicks, 1);
+
+        // the second click comes too late
+        click(&mut tester, &mut view, &mut clicks, primary, origin);
+        tester.event(&mut view, &mut clicks, &Event::Animate(0.5));
+        click(&mut tester, &mut view, &mut clicks, primary, origin);
+        assert_eq!(clicks, 1);
+
+        // the pointer moved away between the clicks
+        tester.move_to(&mut view, &mut clicks, Point::new(9.0, 9.0));
+        click(&mut tester, &mut view, &mut clicks, primary, origin);
+        assert_eq!(clicks, 1);
+
+        click(&mut tester, &mut view, &mut clicks, primary, origin);
+        assert_eq!(clicks, 2);
+    }
+
+    #[test]
+    fn right_click_only_fires_for_secondary_button() {
+        let mut view = on_right_click(size(10.0, ()), |_, clicks: &mut u32| *clicks += 1);
+        let mut clicks = 0;
+
+        let mut tester = ViewTester::new(&mut view, &mut clicks);
+        tester.state.1.set_hovered(true);
+        tester.hover(tester.state.1.id(), Point::ZERO);
+
+        let origin = Point::new(5.0, 5.0);
+
+        click(
+            &mut tester,
+            &mut view,
+            &mut clicks,
+            PointerButton::Primary,
+            origin,
+        );
+        assert_eq!(clicks, 0);
+
+        click(
+            &mut tester,
+            &mut view,
+            &mut clicks,
+            PointerButton::Secondary,
+            origin,
+        );
+        assert_eq!(clicks, 1);
+    }
+}
@@ -0,0 +1,190 @@
+use crate::{
+    canvas::{BorderRadius, Curve, FillRule, Mask},
+    context::{BuildCx, DrawCx, EventCx, LayoutCx, RebuildCx},
+    event::Event,
+    layout::{Rect, Size, Space},
+    rebuild::Rebuild,
+    view::{Pod, State, View},
+};
+
+/// Create a new [`Clip`] view.
+pub fn clip<V>(shape: impl Into<ClipShape>, view: V) -> Clip<V> {
+    Clip::new(shape, view)
+}
+
+/// Create a new [`Clip`] view, clipping the content to a circle.
+pub fn clip_circle<V>(view: V) -> Clip<V> {
+    Clip::new(ClipShape::Circle, view)
+}
+
+/// The shape a [`Clip`] view clips its content to.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ClipShape {
+    /// The largest circle centered in the view.
+    Circle,
+
+    /// The ellipse filling the view.
+    Ellipse,
+
+    /// The rectangle of the view with rounded corners.
+    Rounded(BorderRadius),
+
+    /// An arbitrary mask, in the local coordinates of the view.
+    Mask(Mask),
+}
+
+impl ClipShape {
+    /// Get the mask of the shape for a view occupying `rect`.
+    pub fn mask(&self, rect: Rect) -> Mask {
+        match self {
+            ClipShape::Circle => {
+                let radius = rect.width().min(rect.height()) / 2.0;
+                Mask::new(Curve::circle(rect.center(), radius), FillRule::NonZero)
+            }
+            ClipShape::Ellipse => Mask::new(Curve::ellipse(rect), FillRule::NonZero),
+            ClipShape::Rounded(radius) => {
+                let mut curve = Curve::new();
+                curve.push_rect_with_radius(rect, *radius);
+                Mask::new(curve, FillRule::NonZero)
+            }
+            ClipShape::Mask(mask) => mask.clone(),
+        }
+    }
+}
+
+impl From<Mask> for ClipShape {
+    fn from(value: Mask) -> Self {
+        Self::Mask(value)
+    }
+}
+
+impl From<Curve> for ClipShape {
+    fn from(value: Curve) -> Self {
+        Self::Mask(Mask::new(value, FillRule::NonZero))
+    }
+}
+
+impl From<BorderRadius> for ClipShape {
+    fn from(value: BorderRadius) -> Self {
+        Self::Rounded(value)
+    }
+}
+
+/// A view that clips its content to a shape.
+///
+/// Anything the content draws outside the fill region of the shape is not visible, eg.
+/// `clip_circle(image)` draws a circular avatar.
+#[derive(Rebuild)]
+pub struct Clip<V> {
+    /// The content.
+    pub content: Pod<V>,
+
+    /// The shape to clip to.
+    #[rebuild(draw)]
+    pub shape: ClipShape,
+}
+
+impl<V> Clip<V> {
+    /// Create a new [`Clip`] view.
+    pub fn new(shape: impl Into<ClipShape>, content: V) -> Self {
+        Self {
+            content: Pod::new(content),
+            shape: shape.into(),
+        }
+    }
+}
+
+impl<T, V: View<T>> View<T> for Clip<V> {
+    type State = State<T, V>;
+
+    fn build(&mut self, cx: &mut BuildCx, data: &mut T) -> Self::State {
+        self.content.build(cx, data)
+    }
+
+    fn rebuild(&mut self, state: &mut Self::State, cx: &mut RebuildCx, data: &mut T, old: &Self) {
+        Rebuild::rebuild(self, cx, old);
+
+        self.content.rebuild(state, cx, data, &old.content);
+    }
+
+    fn event(
+        &mut self,
+        state: &mut Self::State,
+        cx: &mut EventCx,
+        data: &mut T,
+        event: &Event,
+    ) -> bool {
+        self.content.event(state, cx, data, event)
+    }
+
+    fn layout(
+        &mut self,
+        state: &mut Self::State,
+        cx: &mut LayoutCx,
+        data: &mut T,
+        space: Space,
+    ) -> Size {
+        self.content.layout(state, cx, data, space)
+    }
+
+    fn draw(&mut self, state: &mut Self::State, cx: &mut DrawCx, data: &mut T) {
+        let mask = self.shape.mask(cx.rect());
+
+        cx.masked(mask, |cx| {
+            self.content.draw(state, cx, data);
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        canvas::{Canvas, Color, Primitive},
+        layout::{Affine, Point},
+        views::{painter, size, testing::ViewTester},
+    };
+
+    use super::*;
+
+    // whether any fill covers `point`, taking the masks of the layers into account
+    fn is_drawn(primitives: &[Primitive], transform: Affine, point: Point) -> bool {
+        primitives.iter().any(|primitive| match primitive {
+            Primitive::Fill { curve, fill, .. } => curve.contains(transform * point, *fill),
+            Primitive::Layer {
+                primitives,
+                transform: layer,
+                mask,
+                ..
+            } => {
+                let inside = match mask {
+                    Some(mask) => mask.curve.contains(transform * point, mask.fill),
+                    None => true,
+                };
+
+                let transform = layer.inverse() * transform;
+                inside && is_drawn(primitives, transform, point)
+            }
+            _ => false,
+        })
+    }
+
+    fn drawn_at(canvas: &Canvas, point: Point) -> bool {
+        let primitives: Vec<_> = canvas.primitives().cloned().collect();
+        is_drawn(&primitives, Affine::IDENTITY, point)
+    }
+
+    #[test]
+    fn circle_hides_corners() {
+        let fill = painter(|cx, _| cx.fill_rect(cx.rect(), Color::RED));
+        let mut view = clip_circle(size(100.0, fill));
+
+        let mut tester = ViewTester::new(&mut view, &mut ());
+        tester.layout(&mut view, &mut (), Space::UNBOUNDED);
+        let canvas = tester.draw(&mut view, &mut ());
+
+        assert!(drawn_at(&canvas, Point::new(50.0, 50.0)));
+        assert!(drawn_at(&canvas, Point::new(50.0, 5.0)));
+        assert!(!drawn_at(&canvas, Point::new(5.0, 5.0)));
+        assert!(!drawn_at(&canvas, Point::new(95.0, 95.0)));
+    }
+}
@@ -29,6 +29,11 @@ pub fn background<V>(background: impl Into<Styled<Color>>, view: V) -> Container
 }
 
 /// A container view.
+///
+/// Text inside a container inherits the `text-color`, `font-size`, `font-family` and
+/// `text-align` styles set on it, eg. `container { text-color: #ff0000 }`. The text color is
+/// inherited from `text-color`, not `color`, since views like buttons use `color` for their
+/// own fill, which the text drawn on top of it shouldn't take.
 #[example(name = "container", width = 400, height = 300)]
 #[derive(Styled, Build, Rebuild)]
 pub struct Container<V> {
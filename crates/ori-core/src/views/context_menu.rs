@@ -0,0 +1,867 @@
+use std::f32::consts::SQRT_2;
+
+use ori_macro::Styled;
+use smol_str::SmolStr;
+
+use crate::{
+    canvas::{BorderRadius, BorderWidth, Color, Curve, FillRule},
+    context::{BuildCx, DrawCx, EventCx, LayoutCx, RebuildCx},
+    event::{Event, Key, PointerButton},
+    layout::{pt, Padding, Point, Rect, Size, Space, Vector},
+    style::{Styled, Theme},
+    text::{FontAttributes, FontFamily, Paragraph, TextAlign, TextWrap},
+    view::{Pod, State, View},
+};
+
+/// Create a new [`ContextMenu`] view.
+pub fn context_menu<T, V>(content: V, items: Vec<MenuItem<T>>) -> ContextMenu<T, V> {
+    ContextMenu::new(content, items)
+}
+
+/// A callback called when a [`MenuItem`] is activated.
+pub type MenuCallback<T> = Box<dyn FnMut(&mut EventCx, &mut T)>;
+
+/// An item in a [`ContextMenu`].
+pub enum MenuItem<T> {
+    /// An item that can be activated, or that opens a submenu.
+    Item {
+        /// The label of the item.
+        label: SmolStr,
+
+        /// The icon of the item, drawn as text before the label.
+        icon: Option<SmolStr>,
+
+        /// The shortcut hint of the item, drawn after the label.
+        shortcut: Option<SmolStr>,
+
+        /// The items of the submenu, if empty the item has no submenu.
+        items: Vec<MenuItem<T>>,
+
+        /// The callback called when the item is activated.
+        on_activate: Option<MenuCallback<T>>,
+    },
+
+    /// A separator between items.
+    Separator,
+}
+
+impl<T> MenuItem<T> {
+    /// Create a new item calling `on_activate` when activated.
+    pub fn new(
+        label: impl Into<SmolStr>,
+        on_activate: impl FnMut(&mut EventCx, &mut T) + 'static,
+    ) -> Self {
+        Self::Item {
+            label: label.into(),
+            icon: None,
+            shortcut: None,
+            items: Vec::new(),
+            on_activate: Some(Box::new(on_activate)),
+        }
+    }
+
+    /// Create a new item that opens a submenu.
+    pub fn submenu(label: impl Into<SmolStr>, items: Vec<MenuItem<T>>) -> Self {
+        Self::Item {
+            label: label.into(),
+            icon: None,
+            shortcut: None,
+            items,
+            on_activate: None,
+        }
+    }
+
+    /// Create a new separator.
+    pub fn separator() -> Self {
+        Self::Separator
+    }
+
+    /// Set the icon of the item.
+    pub fn icon(mut self, new_icon: impl Into<SmolStr>) -> Self {
+        if let Self::Item { ref mut icon, .. } = self {
+            *icon = Some(new_icon.into());
+        }
+
+        self
+    }
+
+    /// Set the shortcut hint of the item.
+    pub fn shortcut(mut self, new_shortcut: impl Into<SmolStr>) -> Self {
+        if let Self::Item {
+            ref mut shortcut, ..
+        } = self
+        {
+            *shortcut = Some(new_shortcut.into());
+        }
+
+        self
+    }
+
+    /// Check if the item is a separator.
+    pub fn is_separator(&self) -> bool {
+        matches!(self, Self::Separator)
+    }
+
+    fn submenu_items(&self) -> &[MenuItem<T>] {
+        match self {
+            Self::Item { items, .. } => items,
+            Self::Separator => &[],
+        }
+    }
+
+    fn has_submenu(&self) -> bool {
+        !self.submenu_items().is_empty()
+    }
+}
+
+/// A view that opens a menu when the content is right-clicked.
+///
+/// The menu supports keyboard navigation, separators and nested submenus,
+/// and is closed when clicking outside of it or pressing `Escape`.
+///
+/// Can be styled using the [`ContextMenuStyle`].
+#[derive(Styled)]
+pub struct ContextMenu<T, V> {
+    /// The content.
+    pub content: Pod<V>,
+
+    /// The items of the menu.
+    pub items: Vec<MenuItem<T>>,
+
+    /// The delay before a submenu opens when hovered.
+    #[styled(default = 0.2)]
+    pub submenu_delay: Styled<f32>,
+
+    /// The padding of the items.
+    #[styled(default = Padding::new(4.0, 8.0, 4.0, 8.0))]
+    pub padding: Styled<Padding>,
+
+    /// The minimum width of a menu.
+    #[styled(default = 120.0)]
+    pub min_width: Styled<f32>,
+
+    /// The font size of the items.
+    #[styled(default = pt(12.0))]
+    pub font_size: Styled<f32>,
+
+    /// The font family of the items.
+    #[styled(default)]
+    pub font_family: Styled<FontFamily>,
+
+    /// The color of the items.
+    #[styled(default -> Theme::CONTRAST or Color::BLACK)]
+    pub color: Styled<Color>,
+
+    /// The color of the shortcut hints.
+    #[styled(default -> Theme::CONTRAST_LOW or Color::grayscale(0.4))]
+    pub shortcut_color: Styled<Color>,
+
+    /// The color of the highlighted item.
+    #[styled(default -> Theme::PRIMARY_LOW or Color::grayscale(0.8))]
+    pub highlight: Styled<Color>,
+
+    /// The color of the separators.
+    #[styled(default -> Theme::OUTLINE or Color::BLACK)]
+    pub separator_color: Styled<Color>,
+
+    /// The background color of the menu.
+    #[styled(default -> Theme::SURFACE_HIGHER or Color::WHITE)]
+    pub background: Styled<Color>,
+
+    /// The border radius of the menu.
+    #[styled(default = BorderRadius::all(4.0))]
+    pub border_radius: Styled<BorderRadius>,
+
+    /// The border width of the menu.
+    #[styled(default = BorderWidth::all(1.0))]
+    pub border_width: Styled<BorderWidth>,
+
+    /// The border color of the menu.
+    #[styled(default -> Theme::OUTLINE or Color::BLACK)]
+    pub border_color: Styled<Color>,
+}
+
+impl<T, V> ContextMenu<T, V> {
+    /// Create a new context menu view.
+    pub fn new(content: V, items: Vec<MenuItem<T>>) -> Self {
+        Self {
+            content: Pod::new(content),
+            items,
+            submenu_delay: Styled::style("context-menu.submenu-delay"),
+            padding: Styled::style("context-menu.padding"),
+            min_width: Styled::style("context-menu.min-width"),
+            font_size: Styled::style("context-menu.font-size"),
+            font_family: Styled::style("context-menu.font-family"),
+            color: Styled::style("context-menu.color"),
+            shortcut_color: Styled::style("context-menu.shortcut-color"),
+            highlight: Styled::style("context-menu.highlight"),
+            separator_color: Styled::style("context-menu.separator-color"),
+            background: Styled::style("context-menu.background"),
+            border_radius: Styled::style("context-menu.border-radius"),
+            border_width: Styled::style("context-menu.border-width"),
+            border_color: Styled::style("context-menu.border-color"),
+        }
+    }
+
+    /// Get the items of the menu at `level`, following the open submenus in `path`.
+    fn level_items(&self, path: &[usize], level: usize) -> &[MenuItem<T>] {
+        let mut items = self.items.as_slice();
+
+        for &index in &path[..level] {
+            items = items[index].submenu_items();
+        }
+
+        items
+    }
+
+    fn item_mut(&mut self, path: &[usize], index: usize) -> Option<&mut MenuItem<T>> {
+        let mut items = &mut self.items;
+
+        for &i in path {
+            match items.get_mut(i)? {
+                MenuItem::Item {
+                    items: ref mut submenu,
+                    ..
+                } => items = submenu,
+                MenuItem::Separator => return None,
+            }
+        }
+
+        items.get_mut(index)
+    }
+}
+
+/// The layout of a single open menu, computed when drawn.
+#[derive(Clone, Debug, Default)]
+struct MenuLayout {
+    rect: Rect,
+    items: Vec<Rect>,
+}
+
+#[doc(hidden)]
+pub struct ContextMenuState {
+    open: bool,
+    pressed: bool,
+    position: Point,
+    path: Vec<usize>,
+    highlight: Vec<Option<usize>>,
+    pending: Option<(usize, Option<usize>)>,
+    timer: f32,
+    menus: Vec<MenuLayout>,
+    style: ContextMenuStyle,
+}
+
+impl ContextMenuState {
+    fn open(&mut self, position: Point) {
+        self.open = true;
+        self.pressed = false;
+        self.position = position;
+        self.path.clear();
+        self.highlight = vec![None];
+        self.pending = None;
+        self.menus.clear();
+    }
+
+    fn close(&mut self) {
+        self.open = false;
+        self.pressed = false;
+        self.path.clear();
+        self.highlight.clear();
+        self.pending = None;
+        self.menus.clear();
+    }
+
+    fn hit(&self, point: Point) -> Option<(usize, Option<usize>)> {
+        for (level, menu) in self.menus.iter().enumerate().rev() {
+            if !menu.rect.contains(point) {
+                continue;
+            }
+
+            let index = menu.items.iter().position(|rect| rect.contains(point));
+            return Some((level, index));
+        }
+
+        None
+    }
+
+    // set the open submenu at `level`, closing any deeper menus
+    fn set_submenu(&mut self, level: usize, index: Option<usize>) {
+        self.path.truncate(level);
+        self.highlight.truncate(level + 1);
+        self.menus.truncate(level + 1);
+
+        if let Some(index) = index {
+            self.path.push(index);
+            self.highlight.push(None);
+        }
+    }
+}
+
+impl<T, V: View<T>> View<T> for ContextMenu<T, V> {
+    type State = (ContextMenuState, State<T, V>);
+
+    fn build(&mut self, cx: &mut BuildCx, data: &mut T) -> Self::State {
+        cx.set_class("context-menu");
+
+        let state = ContextMenuState {
+            open: false,
+            pressed: false,
+            position: Point::ZERO,
+            path: Vec::new(),
+            highlight: Vec::new(),
+            pending: None,
+            timer: 0.0,
+            menus: Vec::new(),
+            style: ContextMenuStyle::styled(self, cx.styles()),
+        };
+
+        (state, self.content.build(cx, data))
+    }
+
+    fn rebuild(
+        &mut self,
+        (state, content): &mut Self::State,
+        cx: &mut RebuildCx,
+        data: &mut T,
+        old: &Self,
+    ) {
+        state.style = ContextMenuStyle::styled(self, cx.styles());
+
+        // the items might have changed, make sure the open path is still valid
+        if state.open {
+            let valid = (0..state.path.len()).all(|level| {
+                let items = self.level_items(&state.path, level);
+                items
+                    .get(state.path[level])
+                    .is_some_and(MenuItem::has_submenu)
+            });
+
+            if !valid {
+                state.close();
+            }
+
+            // the highlighted items and the items of the laid out menus might be out of
+            // range now, or be separators
+            for level in 0..state.highlight.len() {
+                let items = self.level_items(&state.path, level);
+
+                let is_item = |index: usize| items.get(index).is_some_and(|i| !i.is_separator());
+                if !state.highlight[level].is_some_and(is_item) {
+                    state.highlight[level] = None;
+                }
+
+                if let Some(menu) = state.menus.get_mut(level) {
+                    menu.items.truncate(items.len());
+                }
+            }
+
+            cx.draw();
+        }
+
+        self.content.rebuild(content, cx, data, &old.content);
+    }
+
+    fn event(
+        &mut self,
+        (state, content): &mut Self::State,
+        cx: &mut EventCx,
+        data: &mut T,
+        event: &Event,
+    ) -> bool {
+        if !state.open {
+            if let Event::PointerPressed(e) = event {
+                if e.button == PointerButton::Secondary && content.has_hovered() {
+                    state.open(e.position);
+                    cx.draw();
+
+                    let _ = self.content.event(content, cx, data, &Event::Notify);
+                    return true;
+                }
+            }
+
+            return self.content.event(content, cx, data, event);
+        }
+
+        let handled = match event {
+            Event::PointerMoved(e) => {
+                if let Some((level, Some(index))) = state.hit(e.position) {
+                    let items = self.level_items(&state.path, level);
+
+                    // separators can't be highlighted
+                    let highlight = (!items[index].is_separator()).then_some(index);
+
+                    if state.highlight[level] != highlight {
+                        state.highlight[level] = highlight;
+                        cx.draw();
+                    }
+
+                    let submenu = items[index].has_submenu().then_some(index);
+
+                    // open or close submenus after a delay, so that moving the pointer
+                    // diagonally towards a submenu doesn't close it
+                    if state.path.get(level).copied() != submenu
+                        && state.pending != Some((level, submenu))
+                    {
+                        state.pending = Some((level, submenu));
+                        state.timer = 0.0;
+                        cx.animate();
+                    }
+                }
+
+                true
+            }
+            Event::Animate(dt) => {
+                if let Some((level, index)) = state.pending {
+                    state.timer += dt / state.style.submenu_delay.max(f32::EPSILON);
+
+                    if state.timer >= 1.0 {
+                        state.pending = None;
+                        state.set_submenu(level, index);
+                        cx.draw();
+                    } else {
+                        cx.animate();
+                    }
+                }
+
+                false
+            }
+            Event::PointerPressed(e) => {
+                match state.hit(e.position) {
+                    Some(_) => state.pressed = true,
+                    None => {
+                        state.close();
+                        cx.draw();
+                    }
+                }
+
+                true
+            }
+            Event::PointerReleased(e) => {
+                if let (true, Some((level, Some(index)))) = (state.pressed, state.hit(e.position)) {
+                    self.activate(state, cx, data, level, index);
+                }
+
+                state.pressed = false;
+
+                true
+            }
+            Event::KeyPressed(e) => {
+                let level = state.path.len();
+
+                if e.is_key(Key::Escape) {
+                    state.close();
+                    cx.draw();
+                } else if e.is_key(Key::Down) || e.is_key(Key::Up) {
+                    let items = self.level_items(&state.path, level);
+                    let forward = e.is_key(Key::Down);
+
+                    state.highlight[level] = step(items, state.highlight[level], forward);
+                    cx.draw();
+                } else if e.is_key(Key::Left) {
+                    if level > 0 {
+                        state.set_submenu(level - 1, None);
+                        cx.draw();
+                    }
+                } else if e.is_key(Key::Right) || e.is_key(Key::Enter) || e.is_key(' ') {
+                    if let Some(index) = state.highlight[level] {
+                        let items = self.level_items(&state.path, level);
+
+                        if items[index].has_submenu() {
+                            state.set_submenu(level, Some(index));
+                            state.highlight[level + 1] =
+                                step(items[index].submenu_items(), None, true);
+                            cx.draw();
+                        } else if !e.is_key(Key::Right) {
+                            self.activate(state, cx, data, level, index);
+                        }
+                    }
+                }
+
+                true
+            }
+            Event::KeyReleased(_) => true,
+            Event::WindowResized(_) => {
+                state.close();
+                cx.draw();
+
+                false
+            }
+            _ => false,
+        };
+
+        self.content.event_maybe(handled, content, cx, data, event)
+    }
+
+    fn layout(
+        &mut self,
+        (_state, content): &mut Self::State,
+        cx: &mut LayoutCx,
+        data: &mut T,
+        space: Space,
+    ) -> Size {
+        self.content.layout(content, cx, data, space)
+    }
+
+    fn draw(&mut self, (state, content): &mut Self::State, cx: &mut DrawCx, data: &mut T) {
+        self.content.draw(content, cx, data);
+
+        if !state.open {
+            return;
+        }
+
+        let bounds = cx.popup_bounds();
+        let style = &state.style;
+
+        let attributes = |color| FontAttributes {
+            size: style.font_size,
+            family: style.font_family.clone(),
+            color,
+            ..Default::default()
+        };
+
+        let mut menus = Vec::with_capacity(state.path.len() + 1);
+
+        for level in 0..=state.path.len() {
+            let items = self.level_items(&state.path, level);
+
+            let mut paragraphs = Vec::with_capacity(items.len());
+            let mut label_width = 0.0f32;
+            let mut shortcut_width = 0.0f32;
+            let mut has_icon = false;
+
+            for item in items {
+                let MenuItem::Item {
+                    label,
+                    icon,
+                    shortcut,
+                    ..
+                } = item
+                else {
+                    paragraphs.push(None);
+                    continue;
+                };
+
+                let mut label_paragraph = Paragraph::new(1.2, TextAlign::Start, TextWrap::None);
+                label_paragraph.set_text(label, attributes(style.color));
+                let label_size = cx.fonts().measure(&label_paragraph, f32::INFINITY);
+                label_width = label_width.max(label_size.width);
+
+                let icon = icon.as_ref().map(|icon| {
+                    let mut paragraph = Paragraph::new(1.2, TextAlign::Center, TextWrap::None);
+                    paragraph.set_text(icon, attributes(style.color));
+                    paragraph
+                });
+
+                has_icon |= icon.is_some();
+
+                let shortcut = shortcut.as_ref().map(|shortcut| {
+                    let mut paragraph = Paragraph::new(1.2, TextAlign::End, TextWrap::None);
+                    paragraph.set_text(shortcut, attributes(style.shortcut_color));
+                    paragraph
+                });
+
+                if let Some(ref shortcut) = shortcut {
+                    let size = cx.fonts().measure(shortcut, f32::INFINITY);
+                    shortcut_width = shortcut_width.max(size.width + style.font_size * 2.0);
+                }
+
+                paragraphs.push(Some((label_paragraph, label_size.height, icon, shortcut)));
+            }
+
+            let icon_width = if has_icon { style.font_size * 1.5 } else { 0.0 };
+            let arrow_width = style.font_size;
+
+            let content_width = icon_width + label_width + shortcut_width + arrow_width;
+            let width = f32::max(content_width + style.padding.size().width, style.min_width);
+
+            // compute the rects of the items relative to the top-left of the menu
+            let mut items_rects = Vec::with_capacity(items.len());
+            let mut height = 0.0;
+
+            for paragraph in &paragraphs {
+                let item_height = match paragraph {
+                    Some((_, text_height, ..)) => text_height + style.padding.size().height,
+                    None => style.padding.top + style.padding.bottom + 1.0,
+                };
+
+                let rect = Rect::min_size(Point::new(0.0, height), Size::new(width, item_height));
+                items_rects.push(rect);
+                height += item_height;
+            }
+
+            // submenus are placed next to their parent item, or on the left side of the
+            // parent menu if there is no room for popups on the right
+            let size = Size::new(width, height);
+            let rect = match level {
+                0 => Rect::min_size(state.position, size),
+                _ => {
+                    let parent: &MenuLayout = &menus[level - 1];
+                    let item = parent.items[state.path[level - 1]];
+
+                    let right = Rect::min_size(Point::new(parent.rect.max.x, item.min.y), size);
+
+                    if right.max.x > bounds.max.x {
+                        right - Vector::new(parent.rect.width() + width, 0.0)
+                    } else {
+                        right
+                    }
+                }
+            };
+
+            let rect = rect.shift_inside(bounds);
+
+            let layout = MenuLayout {
+                rect,
+                items: items_rects
+                    .iter()
+                    .map(|item| *item + rect.offset())
+                    .collect(),
+            };
+
+            cx.popup(rect, true, |cx| {
+                cx.hoverable(|cx| {
+                    cx.quad(
+                        Rect::min_size(Point::ZERO, size),
+                        style.background,
+                        style.border_radius,
+                        style.border_width,
+                        style.border_color,
+                    );
+
+                    for (index, (paragraph, item_rect)) in
+                        paragraphs.iter().zip(&items_rects).enumerate()
+                    {
+                        let Some((label, text_height, icon, shortcut)) = paragraph else {
+                            let y = item_rect.center().y.round();
+                            let line = Rect::new(
+                                Point::new(item_rect.min.x + style.padding.left, y),
+                                Point::new(item_rect.max.x - style.padding.right, y + 1.0),
+                            );
+
+                            cx.fill_rect(line, style.separator_color);
+                            continue;
+                        };
+
+                        if state.highlight[level] == Some(index) {
+                            cx.quad(
+                                item_rect.shrink(1.0),
+                                style.highlight,
+                                style.border_radius,
+                                BorderWidth::ZERO,
+                                Color::TRANSPARENT,
+                            );
+                        }
+
+                        let text_min = item_rect.min + style.padding.offset();
+                        let text_size = Size::new(label_width, *text_height);
+
+                        if let Some(icon) = icon {
+                            let icon_size = Size::new(icon_width, *text_height);
+                            cx.paragraph(icon, Rect::min_size(text_min, icon_size));
+                        }
+
+                        let label_min = text_min + Vector::new(icon_width, 0.0);
+                        cx.paragraph(label, Rect::min_size(label_min, text_size));
+
+                        if let Some(shortcut) = shortcut {
+                            let max = Point::new(
+                                item_rect.max.x - style.padding.right - arrow_width,
+                                text_min.y + text_height,
+                            );
+
+                            let size = Size::new(shortcut_width, *text_height);
+                            cx.paragraph(shortcut, Rect::max_size(max, size));
+                        }
+
+                        if items[index].has_submenu() {
+                            let center = Point::new(
+                                item_rect.max.x - style.padding.right - arrow_width / 2.0,
+                                item_rect.center().y,
+                            );
+
+                            cx.fill(arrow(center, arrow_width), FillRule::NonZero, style.color);
+                        }
+                    }
+                });
+            });
+
+            menus.push(layout);
+        }
+
+        state.menus = menus;
+    }
+}
+
+impl<T, V> ContextMenu<T, V> {
+    fn activate(
+        &mut self,
+        state: &mut ContextMenuState,
+        cx: &mut EventCx,
+        data: &mut T,
+        level: usize,
+        index: usize,
+    ) {
+        let path = state.path[..level].to_vec();
+
+        match self.item_mut(&path, index) {
+            Some(MenuItem::Item { items, .. }) if !items.is_empty() => {
+                state.pending = None;
+                state.set_submenu(level, Some(index));
+            }
+            Some(MenuItem::Item { on_activate, .. }) => {
+                state.close();
+
+                if let Some(on_activate) = on_activate {
+                    on_activate(cx, data);
+                }
+            }
+            Some(MenuItem::Separator) | None => return,
+        }
+
+        cx.draw();
+    }
+}
+
+// find the next item that isn't a separator, wrapping around
+fn step<T>(items: &[MenuItem<T>], current: Option<usize>, forward: bool) -> Option<usize> {
+    let len = items.len();
+
+    if len == 0 {
+        return None;
+    }
+
+    let mut index = match (current, forward) {
+        (Some(index), true) => (index + 1) % len,
+        (Some(index), false) => (index + len - 1) % len,
+        (None, true) => 0,
+        (None, false) => len - 1,
+    };
+
+    for _ in 0..len {
+        if !items[index].is_separator() {
+            return Some(index);
+        }
+
+        index = match forward {
+            true => (index + 1) % len,
+            false => (index + len - 1) % len,
+        };
+    }
+
+    None
+}
+
+fn arrow(center: Point, size: f32) -> Curve {
+    let d = size * 0.2;
+
+    let mut curve = Curve::new();
+    curve.move_to(center + Vector::new(-d, -d * SQRT_2));
+    curve.line_to(center + Vector::new(d * SQRT_2, 0.0));
+    curve.line_to(center + Vector::new(-d, d * SQRT_2));
+    curve.close();
+
+    curve
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        event::{KeyPressed, Modifiers},
+        views::testing::ViewTester,
+    };
+
+    use super::*;
+
+    fn key_pressed(key: Key) -> Event {
+        Event::KeyPressed(KeyPressed {
+            key,
+            code: None,
+            text: None,
+            modifiers: Modifiers::default(),
+            repeat: false,
+        })
+    }
+
+    fn menu(items: Vec<MenuItem<Vec<&'static str>>>) -> ContextMenu<Vec<&'static str>, ()> {
+        context_menu((), items)
+    }
+
+    fn item(label: &'static str) -> MenuItem<Vec<&'static str>> {
+        MenuItem::new(label, move |_, log: &mut Vec<&'static str>| log.push(label))
+    }
+
+    #[test]
+    fn keyboard_navigation_skips_separators() {
+        let mut log = Vec::new();
+        let mut view = menu(vec![
+            item("cut"),
+            MenuItem::separator(),
+            MenuItem::submenu("more", vec![item("first"), item("second")]),
+            item("paste"),
+        ]);
+
+        let mut tester = ViewTester::new(&mut view, &mut log);
+        tester.state.0.open(Point::ZERO);
+
+        let mut press = |tester: &mut ViewTester<_, _>, view: &mut _, key| {
+            tester.event(view, &mut log, &key_pressed(key));
+        };
+
+        press(&mut tester, &mut view, Key::Down);
+        assert_eq!(tester.state.0.highlight, [Some(0)]);
+
+        press(&mut tester, &mut view, Key::Down);
+        assert_eq!(tester.state.0.highlight, [Some(2)]);
+
+        // right opens the submenu and highlights its first item
+        press(&mut tester, &mut view, Key::Right);
+        assert_eq!(tester.state.0.path, [2]);
+        assert_eq!(tester.state.0.highlight, [Some(2), Some(0)]);
+
+        press(&mut tester, &mut view, Key::Up);
+        assert_eq!(tester.state.0.highlight, [Some(2), Some(1)]);
+
+        press(&mut tester, &mut view, Key::Left);
+        assert!(tester.state.0.path.is_empty());
+
+        // up wraps around from the first item
+        press(&mut tester, &mut view, Key::Up);
+        assert_eq!(tester.state.0.highlight, [Some(0)]);
+
+        press(&mut tester, &mut view, Key::Up);
+        assert_eq!(tester.state.0.highlight, [Some(3)]);
+
+        press(&mut tester, &mut view, Key::Enter);
+        assert!(!tester.state.0.open);
+        assert_eq!(log, ["paste"]);
+    }
+
+    #[test]
+    fn rebuild_with_fewer_items_clears_highlight() {
+        let mut log = Vec::new();
+        let mut view = menu(vec![item("cut"), item("copy"), item("paste")]);
+
+        let mut tester = ViewTester::new(&mut view, &mut log);
+        tester.state.0.open(Point::ZERO);
+
+        tester.event(&mut view, &mut log, &key_pressed(Key::Up));
+        assert_eq!(tester.state.0.highlight, [Some(2)]);
+
+        // the highlighted item is gone, and the new second item is a separator
+        let mut new_view = menu(vec![item("undo"), MenuItem::separator()]);
+        tester.rebuild(&mut new_view, &mut log, &view);
+        assert!(tester.state.0.open);
+        assert_eq!(tester.state.0.highlight, [None]);
+
+        // activating doesn't reach past the items
+        tester.event(&mut new_view, &mut log, &key_pressed(Key::Enter));
+        assert!(log.is_empty());
+
+        tester.event(&mut new_view, &mut log, &key_pressed(Key::Down));
+        tester.event(&mut new_view, &mut log, &key_pressed(Key::Down));
+        assert_eq!(tester.state.0.highlight, [Some(0)]);
+
+        tester.event(&mut new_view, &mut log, &key_pressed(Key::Enter));
+        assert_eq!(log, ["undo"]);
+    }
+}
@@ -0,0 +1,151 @@
+use crate::{
+    context::{BuildCx, DrawCx, EventCx, LayoutCx, RebuildCx},
+    event::Event,
+    layout::{Size, Space},
+    view::View,
+};
+
+/// Create a new [`Crisp`] view.
+pub fn crisp<V>(content: V) -> Crisp<V> {
+    Crisp::new(content)
+}
+
+/// A view that snaps the quads and text of its content to physical pixels.
+///
+/// Edges at fractional positions are blurred by anti-aliasing, which is especially visible on
+/// thin lines like 1 pixel borders and separators. See [`DrawCx::snapped`].
+pub struct Crisp<V> {
+    /// The content.
+    pub content: V,
+
+    /// Whether snapping is enabled.
+    pub snap: bool,
+}
+
+impl<V> Crisp<V> {
+    /// Create a new [`Crisp`] view.
+    pub fn new(content: V) -> Self {
+        Self {
+            content,
+            snap: true,
+        }
+    }
+
+    /// Set whether snapping is enabled.
+    pub fn snap(mut self, snap: bool) -> Self {
+        self.snap = snap;
+        self
+    }
+}
+
+impl<T, V: View<T>> View<T> for Crisp<V> {
+    type State = V::State;
+
+    fn build(&mut self, cx: &mut BuildCx, data: &mut T) -> Self::State {
+        self.content.build(cx, data)
+    }
+
+    fn rebuild(&mut self, state: &mut Self::State, cx: &mut RebuildCx, data: &mut T, old: &Self) {
+        if self.snap != old.snap {
+            cx.draw();
+        }
+
+        self.content.rebuild(state, cx, data, &old.content);
+    }
+
+    fn event(
+        &mut self,
+        state: &mut Self::State,
+        cx: &mut EventCx,
+        data: &mut T,
+        event: &Event,
+    ) -> bool {
+        self.content.event(state, cx, data, event)
+    }
+
+    fn layout(
+        &mut self,
+        state: &mut Self::State,
+        cx: &mut LayoutCx,
+        data: &mut T,
+        space: Space,
+    ) -> Size {
+        self.content.layout(state, cx, data, space)
+    }
+
+    fn draw(&mut self, state: &mut Self::State, cx: &mut DrawCx, data: &mut T) {
+        cx.snapped(self.snap, |cx| {
+            self.content.draw(state, cx, data);
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        canvas::{Color, Primitive},
+        layout::{Affine, Point, Rect, Vector},
+        views::{painter, size, testing::ViewTester},
+        window::Window,
+    };
+
+    use super::*;
+
+    // find the bounds of the first filled curve, and the transform it's drawn with
+    fn find_fill<'a>(
+        primitives: impl Iterator<Item = &'a Primitive>,
+        transform: Affine,
+    ) -> Option<(Rect, Affine)> {
+        for primitive in primitives {
+            match primitive {
+                Primitive::Fill { curve, .. } => return Some((curve.bounds(), transform)),
+                Primitive::Layer {
+                    primitives,
+                    transform: layer,
+                    ..
+                } => {
+                    if let Some(found) = find_fill(primitives.iter(), transform * *layer) {
+                        return Some(found);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        None
+    }
+
+    #[test]
+    fn border_snaps_to_physical_pixels() {
+        // a 1 pixel border at a fractional offset
+        let border = painter(|cx, _| {
+            cx.translated(Vector::new(10.3, 0.4), |cx| {
+                let rect = Rect::min_size(Point::ZERO, Size::new(50.0, 1.0));
+                cx.quad(rect, Color::BLACK, 0.0, 0.0, Color::TRANSPARENT);
+            });
+        });
+        let mut view = crisp(size(100.0, border));
+
+        let mut tester = ViewTester::new(&mut view, &mut ());
+        tester.contexts.get_mut::<Window>().unwrap().scale = 1.5;
+        tester.layout(&mut view, &mut (), Space::UNBOUNDED);
+
+        let canvas = tester.draw(&mut view, &mut ());
+        let (bounds, transform) = find_fill(canvas.primitives(), Affine::IDENTITY).unwrap();
+
+        for corner in [bounds.min, bounds.max] {
+            let physical = (transform * corner) * 1.5;
+
+            assert!(
+                (physical.x - physical.x.round()).abs() < 1e-3,
+                "{:?}",
+                physical
+            );
+            assert!(
+                (physical.y - physical.y.round()).abs() < 1e-3,
+                "{:?}",
+                physical
+            );
+        }
+    }
+}
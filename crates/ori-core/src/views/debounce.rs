@@ -0,0 +1,303 @@
+use std::time::Duration;
+
+use crate::{
+    context::{BuildCx, DrawCx, EventCx, LayoutCx, RebuildCx},
+    event::Event,
+    layout::{Size, Space},
+    view::{Pod, State, View},
+};
+
+/// Create a new [`Debounce`] view.
+///
+/// `on_settle` is called once `value` has stopped changing for `delay`.
+pub fn debounce<T, V, A>(
+    content: V,
+    delay: Duration,
+    value: A,
+    on_settle: impl FnMut(&mut EventCx, &mut T, &A) + 'static,
+) -> Debounce<T, V, A> {
+    Debounce::new(content, delay, value, on_settle)
+}
+
+/// Create a new [`Throttle`] view.
+///
+/// `on_change` is called when `value` changes, but at most once per `interval`.
+pub fn throttle<T, V, A>(
+    content: V,
+    interval: Duration,
+    value: A,
+    on_change: impl FnMut(&mut EventCx, &mut T, &A) + 'static,
+) -> Throttle<T, V, A> {
+    Throttle::new(content, interval, value, on_change)
+}
+
+/// A view that calls a handler after a value has stopped changing for a delay.
+///
+/// This is useful for expensive work driven by input, eg. a live search where `value` is the
+/// query written by a [`TextInput`](super::TextInput), and the search should only be started
+/// once the user stops typing.
+///
+/// The value is compared every time the view is rebuilt, and the handler is called with the
+/// latest value. The value the view is built with never calls the handler.
+pub struct Debounce<T, V, A> {
+    /// The content.
+    pub content: Pod<V>,
+
+    /// The time the value has to stay unchanged.
+    pub delay: Duration,
+
+    /// The value being watched.
+    pub value: A,
+
+    #[allow(clippy::type_complexity)]
+    on_settle: Box<dyn FnMut(&mut EventCx, &mut T, &A)>,
+}
+
+impl<T, V, A> Debounce<T, V, A> {
+    /// Create a new [`Debounce`] view.
+    pub fn new(
+        content: V,
+        delay: Duration,
+        value: A,
+        on_settle: impl FnMut(&mut EventCx, &mut T, &A) + 'static,
+    ) -> Self {
+        Self {
+            content: Pod::new(content),
+            delay,
+            value,
+            on_settle: Box::new(on_settle),
+        }
+    }
+}
+
+#[doc(hidden)]
+pub struct DebounceState<A> {
+    value: A,
+    pending: bool,
+    elapsed: f32,
+}
+
+impl<T, V: View<T>, A: Clone + PartialEq> View<T> for Debounce<T, V, A> {
+    type State = (DebounceState<A>, State<T, V>);
+
+    fn build(&mut self, cx: &mut BuildCx, data: &mut T) -> Self::State {
+        let state = DebounceState {
+            value: self.value.clone(),
+            pending: false,
+            elapsed: 0.0,
+        };
+
+        (state, self.content.build(cx, data))
+    }
+
+    fn rebuild(
+        &mut self,
+        (state, content): &mut Self::State,
+        cx: &mut RebuildCx,
+        data: &mut T,
+        old: &Self,
+    ) {
+        if self.value != state.value {
+            // every change restarts the delay
+            state.value = self.value.clone();
+            state.pending = true;
+            state.elapsed = 0.0;
+            cx.animate();
+        }
+
+        (self.content).rebuild(content, cx, data, &old.content);
+    }
+
+    fn event(
+        &mut self,
+        (state, content): &mut Self::State,
+        cx: &mut EventCx,
+        data: &mut T,
+        event: &Event,
+    ) -> bool {
+        let handled = self.content.event(content, cx, data, event);
+
+        if let (Event::Animate(dt), true) = (event, state.pending) {
+            state.elapsed += dt;
+
+            if state.elapsed >= self.delay.as_secs_f32() {
+                state.pending = false;
+                (self.on_settle)(cx, data, &state.value);
+            } else {
+                cx.animate();
+            }
+        }
+
+        handled
+    }
+
+    fn layout(
+        &mut self,
+        (_, content): &mut Self::State,
+        cx: &mut LayoutCx,
+        data: &mut T,
+        space: Space,
+    ) -> Size {
+        self.content.layout(content, cx, data, space)
+    }
+
+    fn draw(&mut self, (_, content): &mut Self::State, cx: &mut DrawCx, data: &mut T) {
+        self.content.draw(content, cx, data);
+    }
+}
+
+/// A view that calls a handler when a value changes, at most once per interval.
+///
+/// The first change calls the handler right away, changes made within the interval after that
+/// are collapsed into a single call with the latest value once the interval has passed.
+///
+/// The value is compared every time the view is rebuilt. The value the view is built with
+/// never calls the handler.
+pub struct Throttle<T, V, A> {
+    /// The content.
+    pub content: Pod<V>,
+
+    /// The minimum time between calls to the handler.
+    pub interval: Duration,
+
+    /// The value being watched.
+    pub value: A,
+
+    #[allow(clippy::type_complexity)]
+    on_change: Box<dyn FnMut(&mut EventCx, &mut T, &A)>,
+}
+
+impl<T, V, A> Throttle<T, V, A> {
+    /// Create a new [`Throttle`] view.
+    pub fn new(
+        content: V,
+        interval: Duration,
+        value: A,
+        on_change: impl FnMut(&mut EventCx, &mut T, &A) + 'static,
+    ) -> Self {
+        Self {
+            content: Pod::new(content),
+            interval,
+            value,
+            on_change: Box::new(on_change),
+        }
+    }
+}
+
+#[doc(hidden)]
+pub struct ThrottleState<A> {
+    value: A,
+    pending: bool,
+    since_call: f32,
+}
+
+impl<T, V: View<T>, A: Clone + PartialEq> View<T> for Throttle<T, V, A> {
+    type State = (ThrottleState<A>, State<T, V>);
+
+    fn build(&mut self, cx: &mut BuildCx, data: &mut T) -> Self::State {
+        let state = ThrottleState {
+            value: self.value.clone(),
+            pending: false,
+            since_call: f32::INFINITY,
+        };
+
+        (state, self.content.build(cx, data))
+    }
+
+    fn rebuild(
+        &mut self,
+        (state, content): &mut Self::State,
+        cx: &mut RebuildCx,
+        data: &mut T,
+        old: &Self,
+    ) {
+        if self.value != state.value {
+            state.value = self.value.clone();
+            state.pending = true;
+            cx.animate();
+        }
+
+        (self.content).rebuild(content, cx, data, &old.content);
+    }
+
+    fn event(
+        &mut self,
+        (state, content): &mut Self::State,
+        cx: &mut EventCx,
+        data: &mut T,
+        event: &Event,
+    ) -> bool {
+        let handled = self.content.event(content, cx, data, event);
+
+        if let Event::Animate(dt) = event {
+            let interval = self.interval.as_secs_f32();
+            state.since_call += dt;
+
+            if state.pending && state.since_call >= interval {
+                state.pending = false;
+                state.since_call = 0.0;
+                (self.on_change)(cx, data, &state.value);
+            }
+
+            // keep counting until the interval has passed, so the next change is on time
+            if state.pending || state.since_call < interval {
+                cx.animate();
+            }
+        }
+
+        handled
+    }
+
+    fn layout(
+        &mut self,
+        (_, content): &mut Self::State,
+        cx: &mut LayoutCx,
+        data: &mut T,
+        space: Space,
+    ) -> Size {
+        self.content.layout(content, cx, data, space)
+    }
+
+    fn draw(&mut self, (_, content): &mut Self::State, cx: &mut DrawCx, data: &mut T) {
+        self.content.draw(content, cx, data);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use crate::views::testing::ViewTester;
+
+    use super::*;
+
+    #[test]
+    fn rapid_changes_collapse_into_one_call() {
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let delay = Duration::from_millis(300);
+
+        let search = |query: &str| {
+            let calls = calls.clone();
+            debounce((), delay, query.to_string(), move |_, _, query| {
+                calls.lock().unwrap().push(query.clone());
+            })
+        };
+
+        let mut view = search("");
+        let mut tester = ViewTester::new(&mut view, &mut ());
+
+        for query in ["o", "or", "ori"] {
+            let mut new_view = search(query);
+            tester.rebuild(&mut new_view, &mut (), &view);
+            tester.event(&mut new_view, &mut (), &Event::Animate(0.1));
+            view = new_view;
+        }
+
+        assert!(calls.lock().unwrap().is_empty());
+
+        tester.event(&mut view, &mut (), &Event::Animate(0.25));
+        tester.event(&mut view, &mut (), &Event::Animate(0.25));
+
+        assert_eq!(*calls.lock().unwrap(), ["ori"]);
+    }
+}
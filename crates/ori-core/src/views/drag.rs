@@ -0,0 +1,568 @@
+use std::any::Any;
+
+use ori_macro::{Build, Styled};
+
+use crate::{
+    canvas::{BorderRadius, BorderWidth, Color},
+    context::{BuildCx, DrawCx, EventCx, LayoutCx, RebuildCx},
+    event::{Event, PointerButton, PointerId},
+    layout::{Point, Size, Space},
+    rebuild::Rebuild,
+    style::{Styled, Theme},
+    view::{Pod, State, View, ViewId},
+    window::Cursor,
+};
+
+/// Create a new [`Draggable`], that carries `payload` when dragged.
+pub fn draggable<V, P>(payload: P, content: V) -> Draggable<V, P>
+where
+    P: Clone + 'static,
+{
+    Draggable::new(payload, content)
+}
+
+/// Create a new [`DropTarget`], that accepts payloads of type `P`.
+pub fn drop_target<P, T, V>(content: V) -> DropTarget<T, V, P>
+where
+    V: View<T>,
+    P: 'static,
+{
+    DropTarget::new(content)
+}
+
+/// Create a new [`DragPreview`], that follows the pointer while something is dragged.
+pub fn drag_preview<V>(content: V) -> DragPreview<V> {
+    DragPreview::new(content)
+}
+
+/// The distance a pointer must move before a press becomes a drag.
+pub const DRAG_THRESHOLD: f32 = 4.0;
+
+/// The state of a drag between views, shared as a context.
+///
+/// A [`Draggable`] puts its payload here when a drag starts, and a [`DropTarget`] under the
+/// pointer takes it when the pointer is released.
+#[derive(Default)]
+pub struct DragState {
+    payload: Option<Box<dyn Any>>,
+    source: Option<ViewId>,
+    position: Point,
+    released: bool,
+}
+
+impl DragState {
+    /// Get whether something is being dragged.
+    pub fn is_dragging(&self) -> bool {
+        self.payload.is_some() && !self.released
+    }
+
+    /// Get the payload being dragged, if it's a `P`.
+    pub fn payload<P: Any>(&self) -> Option<&P> {
+        self.payload.as_ref()?.downcast_ref()
+    }
+
+    /// Take the payload being dragged, if it's a `P`.
+    pub fn take<P: Any>(&mut self) -> Option<P> {
+        match self.payload.take()?.downcast() {
+            Ok(payload) => Some(*payload),
+            Err(payload) => {
+                self.payload = Some(payload);
+                None
+            }
+        }
+    }
+
+    /// Get the view the drag was started from.
+    pub fn source(&self) -> Option<ViewId> {
+        self.source
+    }
+
+    /// Get the position of the pointer, in window space.
+    pub fn position(&self) -> Point {
+        self.position
+    }
+
+    fn clear(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// A view that can be dragged onto a [`DropTarget`], carrying a payload.
+#[derive(Build, Rebuild)]
+pub struct Draggable<V, P> {
+    /// The content.
+    pub content: Pod<V>,
+
+    /// The payload carried by the drag.
+    #[build(ignore)]
+    pub payload: P,
+
+    /// The button that starts a drag.
+    ///
+    /// Defaults to [`PointerButton::Primary`].
+    pub button: PointerButton,
+}
+
+impl<V, P> Draggable<V, P> {
+    /// Create a new [`Draggable`].
+    pub fn new(payload: P, content: V) -> Self {
+        Self {
+            content: Pod::new(content),
+            payload,
+            button: PointerButton::Primary,
+        }
+    }
+}
+
+#[doc(hidden)]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DraggableState {
+    press: Option<(PointerId, Point)>,
+    dragging: bool,
+}
+
+impl<T, V, P> View<T> for Draggable<V, P>
+where
+    V: View<T>,
+    P: Clone + 'static,
+{
+    type State = (DraggableState, State<T, V>);
+
+    fn build(&mut self, cx: &mut BuildCx, data: &mut T) -> Self::State {
+        (DraggableState::default(), self.content.build(cx, data))
+    }
+
+    fn rebuild(
+        &mut self,
+        (_state, content): &mut Self::State,
+        cx: &mut RebuildCx,
+        data: &mut T,
+        old: &Self,
+    ) {
+        Rebuild::rebuild(self, cx, old);
+
+        self.content.rebuild(content, cx, data, &old.content);
+    }
+
+    fn event(
+        &mut self,
+        (state, content): &mut Self::State,
+        cx: &mut EventCx,
+        data: &mut T,
+        event: &Event,
+    ) -> bool {
+        let is_hovered = content.is_hovered() || content.has_hovered();
+
+        // drag events aren't handled, since the drop targets need to see them too
+        match event {
+            Event::PointerPressed(e) if is_hovered && e.button == self.button => {
+                state.press = Some((e.id, e.position));
+            }
+
+            Event::PointerMoved(e) => {
+                if let Some((pointer, origin)) = state.press {
+                    let moved = origin.distance(e.position) > DRAG_THRESHOLD;
+
+                    if pointer == e.id && moved && !state.dragging {
+                        let id = cx.id();
+                        let drag = cx.context_or_default::<DragState>();
+                        drag.payload = Some(Box::new(self.payload.clone()));
+                        drag.source = Some(id);
+                        drag.released = false;
+
+                        state.dragging = true;
+                        cx.set_cursor(Some(Cursor::Grabbing));
+                    }
+
+                    if pointer == e.id && state.dragging {
+                        cx.context_or_default::<DragState>().position = e.position;
+                        cx.draw();
+                    }
+                }
+            }
+
+            Event::PointerReleased(e) if state.press.is_some_and(|(id, _)| id == e.id) => {
+                state.press = None;
+
+                if state.dragging {
+                    state.dragging = false;
+                    cx.set_cursor(None);
+
+                    // the drag is cleared on the next frame, so drop targets after this view
+                    // still see the payload
+                    cx.context_or_default::<DragState>().released = true;
+                    cx.animate();
+                    cx.draw();
+                }
+            }
+
+            Event::Animate(_) => {
+                let id = cx.id();
+
+                if let Some(drag) = cx.get_context_mut::<DragState>() {
+                    if drag.source == Some(id) && drag.released {
+                        drag.clear();
+                    }
+                }
+            }
+
+            _ => {}
+        }
+
+        self.content.event(content, cx, data, event)
+    }
+
+    fn layout(
+        &mut self,
+        (_state, content): &mut Self::State,
+        cx: &mut LayoutCx,
+        data: &mut T,
+        space: Space,
+    ) -> Size {
+        self.content.layout(content, cx, data, space)
+    }
+
+    fn draw(&mut self, (_state, content): &mut Self::State, cx: &mut DrawCx, data: &mut T) {
+        self.content.draw(content, cx, data);
+    }
+}
+
+/// A view that accepts payloads of type `P` dragged from a [`Draggable`].
+///
+/// The target is highlighted while a matching payload is dragged over it.
+///
+/// Can be styled using the [`DropTargetStyle`].
+#[derive(Styled, Build, Rebuild)]
+pub struct DropTarget<T, V, P> {
+    /// The content.
+    pub content: Pod<V>,
+
+    /// The callback for when a payload is dropped on the target.
+    #[build(ignore)]
+    #[allow(clippy::type_complexity)]
+    pub on_drop: Option<Box<dyn FnMut(&mut EventCx, &mut T, P)>>,
+
+    /// The color of the highlight.
+    #[rebuild(draw)]
+    #[styled(default -> Theme::PRIMARY or Color::BLUE)]
+    pub highlight: Styled<Color>,
+
+    /// The border radius of the highlight.
+    #[rebuild(draw)]
+    #[styled(default = BorderRadius::all(4.0))]
+    pub border_radius: Styled<BorderRadius>,
+
+    /// The border width of the highlight.
+    #[rebuild(draw)]
+    #[styled(default = BorderWidth::all(2.0))]
+    pub border_width: Styled<BorderWidth>,
+}
+
+impl<T, V, P> DropTarget<T, V, P> {
+    /// Create a new [`DropTarget`].
+    pub fn new(content: V) -> Self {
+        Self {
+            content: Pod::new(content),
+            on_drop: None,
+            highlight: Styled::style("drop-target.highlight"),
+            border_radius: Styled::style("drop-target.border-radius"),
+            border_width: Styled::style("drop-target.border-width"),
+        }
+    }
+
+    /// Set the callback for when a payload is dropped on the target.
+    pub fn on_drop(mut self, on_drop: impl FnMut(&mut EventCx, &mut T, P) + 'static) -> Self {
+        self.on_drop = Some(Box::new(on_drop));
+        self
+    }
+}
+
+#[doc(hidden)]
+pub struct DropTargetState {
+    pub style: DropTargetStyle,
+    pub hovered: bool,
+}
+
+impl<T, V, P> View<T> for DropTarget<T, V, P>
+where
+    V: View<T>,
+    P: 'static,
+{
+    type State = (DropTargetState, State<T, V>);
+
+    fn build(&mut self, cx: &mut BuildCx, data: &mut T) -> Self::State {
+        cx.set_class("drop-target");
+
+        let state = DropTargetState {
+            style: DropTargetStyle::styled(self, cx.styles()),
+            hovered: false,
+        };
+
+        (state, self.content.build(cx, data))
+    }
+
+    fn rebuild(
+        &mut self,
+        (state, content): &mut Self::State,
+        cx: &mut RebuildCx,
+        data: &mut T,
+        old: &Self,
+    ) {
+        Rebuild::rebuild(self, cx, old);
+        state.style.rebuild(self, cx);
+
+        self.content.rebuild(content, cx, data, &old.content);
+    }
+
+    fn event(
+        &mut self,
+        (state, content): &mut Self::State,
+        cx: &mut EventCx,
+        data: &mut T,
+        event: &Event,
+    ) -> bool {
+        let mut handled = self.content.event(content, cx, data, event);
+
+        // the drag preview drawn on top of the target isn't hoverable, so the target stays
+        // hovered beneath it
+        let accepts = |cx: &EventCx| {
+            let drag = cx.get_context::<DragState>();
+            drag.is_some_and(|drag| drag.payload::<P>().is_some())
+        };
+
+        match event {
+            Event::PointerMoved(_) => {
+                let hovered = accepts(cx) && cx.has_hovered();
+
+                if state.hovered != hovered {
+                    state.hovered = hovered;
+                    cx.draw();
+                }
+            }
+
+            Event::PointerReleased(_) => {
+                if state.hovered {
+                    state.hovered = false;
+                    cx.draw();
+                }
+
+                if !accepts(cx) || !cx.has_hovered() {
+                    return handled;
+                }
+
+                let payload = cx.context_mut::<DragState>().take::<P>();
+
+                if let (Some(payload), Some(on_drop)) = (payload, &mut self.on_drop) {
+                    on_drop(cx, data, payload);
+                    handled = true;
+                }
+            }
+
+            _ => {}
+        }
+
+        handled
+    }
+
+    fn layout(
+        &mut self,
+        (_state, content): &mut Self::State,
+        cx: &mut LayoutCx,
+        data: &mut T,
+        space: Space,
+    ) -> Size {
+        self.content.layout(content, cx, data, space)
+    }
+
+    fn draw(&mut self, (state, content): &mut Self::State, cx: &mut DrawCx, data: &mut T) {
+        // the whole target is hoverable, not only what the content draws
+        cx.trigger(cx.rect());
+        self.content.draw(content, cx, data);
+
+        if state.hovered {
+            cx.quad(
+                cx.rect(),
+                Color::TRANSPARENT,
+                state.style.border_radius,
+                state.style.border_width,
+                state.style.highlight,
+            );
+        }
+    }
+}
+
+/// A view that follows the pointer while something is dragged, eg. a copy of the dragged
+/// item.
+///
+/// The preview takes up no space, and is drawn in an overlay with its top left corner at the
+/// pointer, so it can be placed anywhere in the tree, eg. in a [`zstack`](super::zstack) at
+/// the root. The preview is never hovered, the pointer hovers the views beneath it.
+pub struct DragPreview<V> {
+    /// The content.
+    pub content: Pod<V>,
+}
+
+impl<V> DragPreview<V> {
+    /// Create a new [`DragPreview`].
+    pub fn new(content: V) -> Self {
+        Self {
+            content: Pod::new(content),
+        }
+    }
+}
+
+impl<T, V: View<T>> View<T> for DragPreview<V> {
+    type State = (bool, State<T, V>);
+
+    fn build(&mut self, cx: &mut BuildCx, data: &mut T) -> Self::State {
+        (false, self.content.build(cx, data))
+    }
+
+    fn rebuild(
+        &mut self,
+        (_, content): &mut Self::State,
+        cx: &mut RebuildCx,
+        data: &mut T,
+        old: &Self,
+    ) {
+        self.content.rebuild(content, cx, data, &old.content);
+    }
+
+    fn event(
+        &mut self,
+        (shown, content): &mut Self::State,
+        cx: &mut EventCx,
+        data: &mut T,
+        event: &Event,
+    ) -> bool {
+        let dragging = cx
+            .get_context::<DragState>()
+            .is_some_and(DragState::is_dragging);
+
+        // the preview is redrawn whenever the pointer moves, or the drag ends
+        if dragging || *shown {
+            cx.draw();
+        }
+
+        *shown = dragging;
+
+        self.content.event(content, cx, data, event)
+    }
+
+    fn layout(
+        &mut self,
+        (_, content): &mut Self::State,
+        cx: &mut LayoutCx,
+        data: &mut T,
+        _space: Space,
+    ) -> Size {
+        let _ = self.content.layout(content, cx, data, Space::UNBOUNDED);
+        Size::ZERO
+    }
+
+    fn draw(&mut self, (_, content): &mut Self::State, cx: &mut DrawCx, data: &mut T) {
+        let Some(drag) = cx.get_context::<DragState>() else {
+            return;
+        };
+
+        if !drag.is_dragging() {
+            return;
+        }
+
+        let position = drag.position();
+
+        // the preview is under the pointer, it must not hide the views beneath it from hover
+        cx.overlay(1, |cx| {
+            cx.non_hoverable(|cx| {
+                cx.translated(position.to_vector(), |cx| {
+                    self.content.draw(content, cx, data);
+                });
+            });
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        hstack,
+        layout::Vector,
+        views::{rect, size, testing::ViewTester, trigger, with_id},
+    };
+
+    use super::*;
+
+    #[test]
+    fn dropping_on_a_target_delivers_the_payload() {
+        let source = ViewId::new();
+        let target = ViewId::new();
+
+        // the source is at 0..50, the target at 50..100
+        let mut view = hstack![
+            draggable(7u32, with_id(source, size([50.0, 50.0], ()))),
+            with_id(
+                target,
+                drop_target::<u32, _, _>(size([50.0, 50.0], ()))
+                    .on_drop(|_, dropped: &mut Vec<u32>, payload| dropped.push(payload)),
+            ),
+        ];
+
+        let mut dropped = Vec::new();
+
+        let mut tester = ViewTester::new(&mut view, &mut dropped);
+        tester.layout(&mut view, &mut dropped, Space::UNBOUNDED);
+
+        let start = Point::new(25.0, 25.0);
+        let end = Point::new(75.0, 25.0);
+        let primary = PointerButton::Primary;
+
+        tester.hover(source, start);
+
+        // the pointer moves over the source before it's pressed
+        tester.move_to(&mut view, &mut dropped, start);
+        tester.press(&mut view, &mut dropped, start, primary);
+        tester.hover(target, end);
+        tester.move_to(&mut view, &mut dropped, end);
+
+        let drag = tester.contexts.get::<DragState>().unwrap();
+        assert_eq!(drag.payload::<u32>(), Some(&7));
+        assert_eq!(drag.position(), Point::new(75.0, 25.0));
+
+        tester.release(&mut view, &mut dropped, end, primary);
+        assert_eq!(dropped, vec![7]);
+
+        tester.event(&mut view, &mut dropped, &Event::Animate(0.016));
+        assert!(!tester.contexts.get::<DragState>().unwrap().is_dragging());
+    }
+
+    #[test]
+    fn drag_preview_is_not_hovered() {
+        let source = ViewId::new();
+        let target = ViewId::new();
+
+        let mut view = hstack![
+            draggable(7u32, with_id(source, size([50.0, 50.0], ()))),
+            with_id(target, drop_target::<u32, _, _>(size([50.0, 50.0], ()))),
+            drag_preview(trigger(rect(Size::all(20.0), Color::RED))),
+        ];
+
+        let mut data = ();
+        let mut tester = ViewTester::new(&mut view, &mut data);
+        tester.layout(&mut view, &mut data, Space::UNBOUNDED);
+
+        let start = Point::new(25.0, 25.0);
+        let end = Point::new(75.0, 25.0);
+
+        tester.hover(source, start);
+        tester.move_to(&mut view, &mut data, start);
+        tester.press(&mut view, &mut data, start, PointerButton::Primary);
+        tester.move_to(&mut view, &mut data, end);
+
+        // the preview is drawn at the pointer, over the target
+        let canvas = tester.draw(&mut view, &mut data);
+        assert!(canvas
+            .primitives()
+            .any(|p| p.bounds().is_some_and(|b| b.contains(end))));
+        assert_eq!(canvas.view_at(end + Vector::all(5.0)), Some(target));
+    }
+}
@@ -0,0 +1,244 @@
+use std::{
+    any::Any,
+    ops::DerefMut,
+    panic::{self, AssertUnwindSafe},
+};
+
+use crate::{
+    context::{BaseCx, BuildCx, DrawCx, EventCx, LayoutCx, RebuildCx},
+    event::Event,
+    layout::{Affine, Size, Space},
+    log::error,
+    style::Styles,
+    view::{Pod, State, View},
+};
+
+/// Create a new [`ErrorBoundary`] view.
+pub fn error_boundary<V, F>(content: V, fallback: F) -> ErrorBoundary<V, F> {
+    ErrorBoundary::new(content, fallback)
+}
+
+/// A view that catches panics in its content, and displays a fallback view instead.
+///
+/// When the content panics, the panic is logged, the state of the content is discarded and
+/// the `fallback` is shown. The next time the view is rebuilt, the content is built fresh and
+/// shown again.
+///
+/// Note that panics are caught with [`AssertUnwindSafe`], if the content panics while
+/// modifying the data, the data might be left in an inconsistent state. Panics are also not
+/// caught at all when building with `panic = "abort"`.
+pub struct ErrorBoundary<V, F> {
+    /// The content.
+    pub content: Pod<V>,
+
+    /// The view displayed when the content has panicked.
+    pub fallback: Pod<F>,
+}
+
+impl<V, F> ErrorBoundary<V, F> {
+    /// Create a new [`ErrorBoundary`] view.
+    pub fn new(content: V, fallback: F) -> Self {
+        Self {
+            content: Pod::new(content),
+            fallback: Pod::new(fallback),
+        }
+    }
+}
+
+#[doc(hidden)]
+pub struct ErrorBoundaryState<T, V: View<T>, F: View<T>> {
+    content: Option<State<T, V>>,
+    fallback: State<T, F>,
+}
+
+impl<T, V: View<T>, F: View<T>> View<T> for ErrorBoundary<V, F> {
+    type State = ErrorBoundaryState<T, V, F>;
+
+    fn build(&mut self, cx: &mut BuildCx, data: &mut T) -> Self::State {
+        let content = catch(cx, |cx| self.content.build(cx, data));
+
+        ErrorBoundaryState {
+            content,
+            fallback: self.fallback.build(cx, data),
+        }
+    }
+
+    fn rebuild(&mut self, state: &mut Self::State, cx: &mut RebuildCx, data: &mut T, old: &Self) {
+        match state.content {
+            Some(ref mut content) => {
+                let content = catch(cx, |cx| {
+                    (self.content).rebuild(content, cx, data, &old.content);
+                });
+
+                if content.is_none() {
+                    state.content = None;
+                    cx.layout();
+                }
+            }
+            None => {
+                // the old state of the content was discarded, so we build it fresh
+                state.content = catch(cx, |cx| {
+                    let mut build_cx = cx.as_build_cx();
+                    self.content.build(&mut build_cx, data)
+                });
+
+                cx.layout();
+            }
+        }
+
+        (self.fallback).rebuild(&mut state.fallback, cx, data, &old.fallback);
+    }
+
+    fn event(
+        &mut self,
+        state: &mut Self::State,
+        cx: &mut EventCx,
+        data: &mut T,
+        event: &Event,
+    ) -> bool {
+        let Some(ref mut content) = state.content else {
+            return self.fallback.event(&mut state.fallback, cx, data, event);
+        };
+
+        match catch(cx, |cx| self.content.event(content, cx, data, event)) {
+            Some(handled) => handled,
+            None => {
+                state.content = None;
+                cx.layout();
+
+                false
+            }
+        }
+    }
+
+    fn layout(
+        &mut self,
+        state: &mut Self::State,
+        cx: &mut LayoutCx,
+        data: &mut T,
+        space: Space,
+    ) -> Size {
+        if let Some(ref mut content) = state.content {
+            let size = catch(cx, |cx| self.content.layout(content, cx, data, space));
+
+            match size {
+                Some(size) => return size,
+                None => state.content = None,
+            }
+        }
+
+        cx.view_state.request_draw();
+        self.fallback.layout(&mut state.fallback, cx, data, space)
+    }
+
+    fn draw(&mut self, state: &mut Self::State, cx: &mut DrawCx, data: &mut T) {
+        if let Some(ref mut content) = state.content {
+            // the content is drawn in a layer, if it panics the layer is never added to the
+            // canvas, so nothing it has drawn is kept
+            let drawn = catch(cx, |cx| {
+                cx.transformed(Affine::IDENTITY, |cx| {
+                    self.content.draw(content, cx, data);
+                });
+            });
+
+            if drawn.is_some() {
+                return;
+            }
+
+            state.content = None;
+
+            // the fallback has not been laid out, so we lay it out to fit our size
+            let size = cx.size();
+            let mut layout_cx = LayoutCx::new(cx.base, cx.view_state);
+            let space = Space::new(size, size);
+            let _ = (self.fallback).layout(&mut state.fallback, &mut layout_cx, data, space);
+        }
+
+        self.fallback.draw(&mut state.fallback, cx, data);
+    }
+}
+
+// call `f`, catching any panics, and logging them
+fn catch<'b, C, R>(cx: &mut C, f: impl FnOnce(&mut C) -> R) -> Option<R>
+where
+    C: DerefMut<Target = BaseCx<'b>>,
+{
+    // if the content panics, the classes it pushed are never popped
+    let depth = cx.get_context::<Styles>().map(Styles::class_depth);
+
+    match panic::catch_unwind(AssertUnwindSafe(|| f(cx))) {
+        Ok(result) => Some(result),
+        Err(payload) => {
+            if let (Some(depth), Some(styles)) = (depth, cx.get_context_mut::<Styles>()) {
+                styles.truncate_classes(depth);
+            }
+
+            error!(
+                "view panicked, showing fallback: {}",
+                panic_message(&payload)
+            );
+
+            None
+        }
+    }
+}
+
+fn panic_message(payload: &Box<dyn Any + Send>) -> &str {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        return message;
+    }
+
+    match payload.downcast_ref::<String>() {
+        Some(message) => message,
+        None => "unknown panic",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::views::{size, testing::ViewTester};
+
+    use super::*;
+
+    struct Panics;
+
+    impl View for Panics {
+        type State = ();
+
+        fn build(&mut self, _cx: &mut BuildCx, _data: &mut ()) -> Self::State {}
+
+        fn rebuild(&mut self, _: &mut (), _: &mut RebuildCx, _: &mut (), _old: &Self) {}
+
+        fn event(&mut self, _: &mut (), _: &mut EventCx, _: &mut (), _: &Event) -> bool {
+            false
+        }
+
+        fn layout(&mut self, _: &mut (), _: &mut LayoutCx, _: &mut (), _: Space) -> Size {
+            let items: Vec<f32> = Vec::new();
+            Size::all(items[3])
+        }
+
+        fn draw(&mut self, _: &mut (), _: &mut DrawCx, _: &mut ()) {}
+    }
+
+    #[test]
+    fn panic_shows_fallback() {
+        let mut view = error_boundary(Panics, size(42.0, ()));
+
+        let mut tester = ViewTester::new(&mut view, &mut ());
+        let size = tester.layout(&mut view, &mut (), Space::UNBOUNDED);
+
+        assert_eq!(size, Size::all(42.0));
+        assert!(tester.state.content.is_none());
+    }
+
+    #[test]
+    fn content_without_panic() {
+        let mut view = error_boundary(size(10.0, ()), size(42.0, ()));
+
+        let mut tester = ViewTester::new(&mut view, &mut ());
+        let size = tester.layout(&mut view, &mut (), Space::UNBOUNDED);
+
+        assert_eq!(size, Size::all(10.0));
+    }
+}
@@ -1,13 +1,176 @@
+use std::time::Duration;
+
 use crate::{
     canvas::{Color, Pattern},
     context::{BuildCx, DrawCx, EventCx, LayoutCx, RebuildCx},
     event::Event,
-    image::Image,
-    layout::{Affine, Size, Space, Vector},
+    image::{Image, ImageHandle},
+    layout::{Affine, Axis, Size, Space, Vector},
     view::View,
 };
 
+// frames this short are shown for `DEFAULT_FRAME_DELAY` instead, like browsers do, since many
+// gifs leave the delay at zero
+const MIN_FRAME_DELAY: Duration = Duration::from_millis(10);
+const DEFAULT_FRAME_DELAY: Duration = Duration::from_millis(100);
+
+#[doc(hidden)]
+pub struct ImageState {
+    frame: usize,
+    elapsed: Duration,
+    loops: u32,
+
+    // the current frame of an animated image, uploaded to a single texture
+    handle: Option<ImageHandle>,
+}
+
+impl ImageState {
+    fn new(image: &Image) -> Self {
+        Self {
+            frame: 0,
+            elapsed: Duration::ZERO,
+            loops: 0,
+            handle: image
+                .is_animated()
+                .then(|| ImageHandle::new(image.to_frame(0))),
+        }
+    }
+
+    fn is_done(&self, image: &Image) -> bool {
+        image.loop_count().is_some_and(|count| self.loops >= count)
+    }
+
+    // advance the animation by `dt`, returns whether the frame changed
+    fn advance(&mut self, image: &Image, dt: Duration) -> bool {
+        let frames = image.frames();
+        let start = self.frame;
+
+        self.elapsed += dt;
+
+        loop {
+            let delay = match frames[self.frame].delay() {
+                delay if delay < MIN_FRAME_DELAY => DEFAULT_FRAME_DELAY,
+                delay => delay,
+            };
+
+            if self.elapsed < delay {
+                break;
+            }
+
+            // the last loop ends on the last frame
+            if self.frame + 1 == frames.len() {
+                self.loops += 1;
+
+                if self.is_done(image) {
+                    self.elapsed = Duration::ZERO;
+                    break;
+                }
+            }
+
+            self.elapsed -= delay;
+            self.frame = (self.frame + 1) % frames.len();
+        }
+
+        self.frame != start
+    }
+}
+
 impl<T> View<T> for Image {
+    type State = ImageState;
+
+    fn build(&mut self, cx: &mut BuildCx, _data: &mut T) -> Self::State {
+        if self.is_animated() {
+            cx.animate();
+        }
+
+        ImageState::new(self)
+    }
+
+    fn rebuild(&mut self, state: &mut Self::State, cx: &mut RebuildCx, _data: &mut T, old: &Self) {
+        if self != old {
+            *state = ImageState::new(self);
+
+            if self.is_animated() {
+                cx.animate();
+            }
+
+            cx.layout();
+            cx.draw();
+        }
+    }
+
+    fn event(
+        &mut self,
+        state: &mut Self::State,
+        cx: &mut EventCx,
+        _data: &mut T,
+        event: &Event,
+    ) -> bool {
+        if let (Event::Animate(dt), Some(_)) = (event, &state.handle) {
+            if state.is_done(self) {
+                return false;
+            }
+
+            if state.advance(self, Duration::from_secs_f32(dt.max(0.0))) {
+                let frame = self.to_frame(state.frame);
+
+                if let Some(ref mut handle) = state.handle {
+                    handle.update(&frame);
+                }
+
+                cx.draw();
+            }
+
+            if !state.is_done(self) {
+                cx.animate();
+            }
+        }
+
+        false
+    }
+
+    fn layout(
+        &mut self,
+        _state: &mut Self::State,
+        _cx: &mut LayoutCx,
+        _data: &mut T,
+        space: Space,
+    ) -> Size {
+        space.fit(self.size())
+    }
+
+    fn intrinsic_size(
+        &mut self,
+        _state: &mut Self::State,
+        _cx: &mut LayoutCx,
+        _data: &mut T,
+        axis: Axis,
+        _cross_extent: f32,
+    ) -> f32 {
+        axis.major(self.size())
+    }
+
+    fn draw(&mut self, state: &mut Self::State, cx: &mut DrawCx, _data: &mut T) {
+        let scale = Vector::from(cx.size() / self.size());
+
+        let image = match state.handle {
+            Some(ref handle) => handle.image().clone(),
+            None => self.clone(),
+        };
+
+        cx.fill_rect(
+            cx.rect(),
+            Pattern {
+                image,
+                transform: Affine::scale(scale),
+                color: Color::WHITE,
+            },
+        );
+    }
+}
+
+#[cfg(feature = "svg")]
+impl<T> View<T> for crate::image::Svg {
     type State = ();
 
     fn build(&mut self, _cx: &mut BuildCx, _data: &mut T) -> Self::State {}
@@ -39,16 +202,117 @@ impl<T> View<T> for Image {
         space.fit(self.size())
     }
 
+    fn intrinsic_size(
+        &mut self,
+        _state: &mut Self::State,
+        _cx: &mut LayoutCx,
+        _data: &mut T,
+        axis: Axis,
+        _cross_extent: f32,
+    ) -> f32 {
+        axis.major(self.size())
+    }
+
     fn draw(&mut self, _state: &mut Self::State, cx: &mut DrawCx, _data: &mut T) {
-        let scale = Vector::from(cx.size() / self.size());
+        // rasterized at the size it's drawn at, so it stays crisp when resized or scaled
+        let image = self.rasterize(cx.size(), cx.window().render_scale());
+        let scale = Vector::from(cx.size() / image.size());
 
         cx.fill_rect(
             cx.rect(),
             Pattern {
-                image: self.clone(),
+                image,
                 transform: Affine::scale(scale),
                 color: Color::WHITE,
             },
         );
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        image::{ImageData, ImageFrame},
+        views::testing::ViewTester,
+    };
+
+    use super::*;
+
+    fn animation(loop_count: Option<u32>) -> Image {
+        let frames = (0..3)
+            .map(|i| ImageFrame::new(vec![i; 4], Duration::from_millis(100)))
+            .collect();
+
+        let mut data = ImageData::from_frames(frames, 1, 1);
+        data.set_loop_count(loop_count);
+        Image::from(data)
+    }
+
+    fn pixel(tester: &ViewTester<(), Image>) -> u8 {
+        tester
+            .state
+            .handle
+            .as_ref()
+            .unwrap()
+            .image()
+            .get_pixel(0, 0)[0]
+    }
+
+    #[test]
+    fn animated_image_advances_frames() {
+        let mut view = animation(Some(2));
+        let mut tester = ViewTester::new(&mut view, &mut ());
+        assert_eq!(pixel(&tester), 0);
+
+        // a frame boundary isn't crossed yet
+        tester.event(&mut view, &mut (), &Event::Animate(0.05));
+        assert_eq!(pixel(&tester), 0);
+
+        tester.event(&mut view, &mut (), &Event::Animate(0.1));
+        assert_eq!(pixel(&tester), 1);
+
+        // long frames skip ahead, and wrap around to the first frame
+        tester.event(&mut view, &mut (), &Event::Animate(0.2));
+        assert_eq!(pixel(&tester), 0);
+
+        // the second loop is the last, it stops on the last frame
+        tester.event(&mut view, &mut (), &Event::Animate(10.0));
+        assert_eq!(pixel(&tester), 2);
+        assert!(tester.state.is_done(&view));
+
+        let still = ImageState::new(&Image::new(vec![0; 4], 1, 1));
+        assert!(still.handle.is_none());
+    }
+
+    #[test]
+    #[cfg(feature = "svg")]
+    fn svg_is_rasterized_at_the_render_scale() {
+        use crate::{
+            canvas::{Primitive, Shader},
+            image::Svg,
+            window::Window,
+        };
+
+        let svg = br#"<svg xmlns="http://www.w3.org/2000/svg" width="16" height="12"/>"#;
+        let mut view = Svg::try_load_data(svg).unwrap();
+
+        let mut tester = ViewTester::new(&mut view, &mut ());
+        let window = tester.contexts.get_mut::<Window>().unwrap();
+        window.scale = 1.5;
+        window.set_ui_scale(2.0);
+
+        tester.layout(&mut view, &mut (), Space::UNBOUNDED);
+        let canvas = tester.draw(&mut view, &mut ());
+
+        let image = canvas.primitives().find_map(|primitive| match primitive {
+            Primitive::Fill { paint, .. } => match &paint.shader {
+                Shader::Pattern(pattern) => Some(pattern.image.clone()),
+                _ => None,
+            },
+            _ => None,
+        });
+
+        let image = image.unwrap();
+        assert_eq!((image.width(), image.height()), (48, 36));
+    }
+}
@@ -0,0 +1,127 @@
+use crate::{
+    canvas::{Color, Pattern},
+    context::{BuildCx, DrawCx, EventCx, LayoutCx, RebuildCx},
+    event::Event,
+    image::{ImageData, ImageHandle},
+    layout::{Affine, Size, Space, Vector},
+    view::View,
+};
+
+/// Create a new [`ImageStream`] view.
+pub fn image_stream<T>(
+    producer: impl FnMut(&mut T) -> Option<ImageData> + 'static,
+) -> ImageStream<T> {
+    ImageStream::new(producer)
+}
+
+/// A view that displays an image that changes every frame, eg. a video or camera feed.
+///
+/// The producer is called every animation frame, and when it returns new image data, the
+/// image is updated in place through an [`ImageHandle`]. This avoids allocating a new
+/// texture for every frame, as long as the size of the image doesn't change.
+pub struct ImageStream<T> {
+    /// The producer of the image data.
+    #[allow(clippy::type_complexity)]
+    pub producer: Box<dyn FnMut(&mut T) -> Option<ImageData>>,
+}
+
+impl<T> ImageStream<T> {
+    /// Create a new [`ImageStream`] view.
+    pub fn new(producer: impl FnMut(&mut T) -> Option<ImageData> + 'static) -> Self {
+        Self {
+            producer: Box::new(producer),
+        }
+    }
+}
+
+impl<T> View<T> for ImageStream<T> {
+    type State = ImageHandle;
+
+    fn build(&mut self, cx: &mut BuildCx, data: &mut T) -> Self::State {
+        cx.animate();
+
+        match (self.producer)(data) {
+            Some(image) => ImageHandle::new(image),
+            None => ImageHandle::default(),
+        }
+    }
+
+    fn rebuild(
+        &mut self,
+        _state: &mut Self::State,
+        cx: &mut RebuildCx,
+        _data: &mut T,
+        _old: &Self,
+    ) {
+        cx.animate();
+    }
+
+    fn event(
+        &mut self,
+        handle: &mut Self::State,
+        cx: &mut EventCx,
+        data: &mut T,
+        event: &Event,
+    ) -> bool {
+        if let Event::Animate(_) = event {
+            if let Some(image) = (self.producer)(data) {
+                if !handle.update(&image) {
+                    cx.layout();
+                }
+
+                cx.draw();
+            }
+
+            cx.animate();
+        }
+
+        false
+    }
+
+    fn layout(
+        &mut self,
+        handle: &mut Self::State,
+        _cx: &mut LayoutCx,
+        _data: &mut T,
+        space: Space,
+    ) -> Size {
+        space.fit(handle.image().size())
+    }
+
+    fn draw(&mut self, handle: &mut Self::State, cx: &mut DrawCx, _data: &mut T) {
+        // an empty image has nothing to draw, and can't be scaled to the size of the view
+        if handle.image().width() == 0 || handle.image().height() == 0 {
+            return;
+        }
+
+        let scale = Vector::from(cx.size() / handle.image().size());
+
+        cx.fill_rect(
+            cx.rect(),
+            Pattern {
+                image: handle.image().clone(),
+                transform: Affine::scale(scale),
+                color: Color::WHITE,
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{layout::Space, views::testing::ViewTester};
+
+    use super::*;
+
+    #[test]
+    fn empty_frames_are_not_drawn() {
+        let mut view = image_stream(|_: &mut ()| Some(ImageData::new(Vec::new(), 0, 0)));
+        let mut tester = ViewTester::new(&mut view, &mut ());
+
+        let space = Space::new(Size::ZERO, Size::all(100.0));
+        assert_eq!(tester.layout(&mut view, &mut (), space), Size::ZERO);
+
+        let canvas = tester.draw(&mut view, &mut ());
+        assert!(canvas.primitives().next().is_none());
+    }
+}
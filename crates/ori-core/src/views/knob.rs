@@ -0,0 +1,459 @@
+use std::{
+    f32::consts::{PI, TAU},
+    ops::RangeInclusive,
+};
+
+use ori_macro::{Build, Styled};
+
+use crate::{
+    canvas::{Color, Curve, Stroke, StrokeCap},
+    context::{BuildCx, DrawCx, EventCx, LayoutCx, RebuildCx},
+    event::{Event, Key},
+    layout::{Point, Size, Space, Vector},
+    rebuild::Rebuild,
+    style::{Styled, Theme},
+    view::View,
+};
+
+/// Create a new [`Knob`].
+pub fn knob<T>(value: f32) -> Knob<T> {
+    Knob::new(value)
+}
+
+/// How dragging a [`Knob`] changes its value.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum KnobDrag {
+    /// Dragging up increases the value, and dragging down decreases it, by the full range
+    /// over [`Knob::drag_distance`].
+    #[default]
+    Vertical,
+
+    /// The indicator follows the angle of the pointer around the center of the knob.
+    Circular,
+}
+
+/// A rotary knob, for adjusting a value in a range, eg. the parameters of audio effects.
+///
+/// The knob sweeps 270 degrees clockwise from the bottom left, the gap at the bottom
+/// separates the minimum from the maximum, and dragging never wraps around from one to
+/// the other. When focused the value can be adjusted with the arrow keys.
+///
+/// Can be styled with a [`KnobStyle`].
+#[derive(Styled, Build, Rebuild)]
+pub struct Knob<T> {
+    /// The value of the knob.
+    #[rebuild(draw)]
+    pub value: f32,
+
+    /// The range of the knob.
+    #[rebuild(draw)]
+    pub range: RangeInclusive<f32>,
+
+    /// The increments the value snaps to, counted from the start of the range.
+    ///
+    /// The arrow keys adjust the value by one step, or by a hundredth of the range when this
+    /// is `None`.
+    pub step: Option<f32>,
+
+    /// How dragging the knob changes its value.
+    pub drag: KnobDrag,
+
+    /// The distance in pixels to drag for the full range, with [`KnobDrag::Vertical`].
+    pub drag_distance: f32,
+
+    /// The callback for when the value changes.
+    #[build(ignore)]
+    #[allow(clippy::type_complexity)]
+    pub on_change: Option<Box<dyn FnMut(&mut EventCx, &mut T, f32) + 'static>>,
+
+    /// The diameter of the knob.
+    #[rebuild(layout)]
+    #[styled(default = 48.0)]
+    pub size: Styled<f32>,
+
+    /// The width of the track and the indicator.
+    #[rebuild(draw)]
+    #[styled(default = 4.0)]
+    pub width: Styled<f32>,
+
+    /// The color of the value and the indicator.
+    #[rebuild(draw)]
+    #[styled(default -> Theme::PRIMARY or Color::BLUE)]
+    pub color: Styled<Color>,
+
+    /// The color of the track.
+    #[rebuild(draw)]
+    #[styled(default -> Theme::SURFACE_HIGH or Color::grayscale(0.9))]
+    pub background: Styled<Color>,
+}
+
+impl<T> Knob<T> {
+    /// Create a new [`Knob`].
+    pub fn new(value: f32) -> Self {
+        Self {
+            value,
+            range: 0.0..=1.0,
+            step: None,
+            drag: KnobDrag::Vertical,
+            drag_distance: 200.0,
+            on_change: None,
+            size: Styled::style("knob.size"),
+            width: Styled::style("knob.width"),
+            color: Styled::style("knob.color"),
+            background: Styled::style("knob.background"),
+        }
+    }
+
+    /// Set the callback for when the value changes.
+    pub fn on_change(mut self, on_change: impl FnMut(&mut EventCx, &mut T, f32) + 'static) -> Self {
+        self.on_change = Some(Box::new(on_change));
+        self
+    }
+
+    // snap a normalized value to the steps, and map it into the range
+    fn value_at(&self, t: f32) -> f32 {
+        let (start, end) = (*self.range.start(), *self.range.end());
+        let value = start + t.clamp(0.0, 1.0) * (end - start);
+
+        match self.step {
+            Some(step) if step > 0.0 => {
+                let value = start + ((value - start) / step).round() * step;
+                value.clamp(start.min(end), start.max(end))
+            }
+            _ => value,
+        }
+    }
+
+    fn normalized(&self) -> f32 {
+        let (start, end) = (*self.range.start(), *self.range.end());
+
+        match end == start {
+            true => 0.0,
+            false => ((self.value - start) / (end - start)).clamp(0.0, 1.0),
+        }
+    }
+
+    fn set_value(&mut self, cx: &mut EventCx, data: &mut T, value: f32) {
+        if value == self.value {
+            return;
+        }
+
+        self.value = value;
+
+        if let Some(ref mut on_change) = self.on_change {
+            on_change(cx, data, value);
+        }
+
+        cx.draw();
+    }
+}
+
+// the angle of the minimum, at the bottom left, angles are clockwise since y points down
+const START: f32 = 0.75 * PI;
+
+// the angle swept from the minimum to the maximum
+const SWEEP: f32 = 1.5 * PI;
+
+/// The state of a [`Knob`] while it's dragged.
+#[derive(Clone, Copy, Debug)]
+pub struct KnobDragState {
+    // the pointer position and the normalized value when the drag started
+    origin: Point,
+    start: f32,
+
+    // the angle of the pointer, and how far it has turned from `START`, without clamping
+    // or wrapping around, so the pointer has to turn back past the end before the value
+    // changes again
+    pointer_angle: f32,
+    turned: f32,
+}
+
+impl KnobDragState {
+    fn new(drag: KnobDrag, center: Point, position: Point, start: f32) -> Self {
+        let pointer_angle = (position - center).angle();
+
+        // the gap at the bottom is split between the ends, so pressing in it picks the
+        // closest one
+        let mut turned = (pointer_angle - START).rem_euclid(TAU);
+
+        if turned > SWEEP + (TAU - SWEEP) / 2.0 {
+            turned -= TAU;
+        }
+
+        let turned = match drag {
+            KnobDrag::Vertical => start * SWEEP,
+            KnobDrag::Circular => turned,
+        };
+
+        Self {
+            origin: position,
+            start,
+            pointer_angle,
+            turned,
+        }
+    }
+
+    // the normalized value of the knob with the pointer at `position`
+    fn normalized(&mut self, drag: KnobDrag, distance: f32, center: Point, position: Point) -> f32 {
+        match drag {
+            KnobDrag::Vertical => {
+                let dragged = (self.origin.y - position.y) / distance.max(1.0);
+                (self.start + dragged).clamp(0.0, 1.0)
+            }
+            KnobDrag::Circular => {
+                let angle = (position - center).angle();
+
+                // the shortest turn from the last angle, the pointer can't turn half a circle
+                // between two events
+                let turn = (angle - self.pointer_angle + PI).rem_euclid(TAU) - PI;
+
+                self.pointer_angle = angle;
+                self.turned += turn;
+
+                (self.turned / SWEEP).clamp(0.0, 1.0)
+            }
+        }
+    }
+}
+
+impl<T> View<T> for Knob<T> {
+    type State = (KnobStyle, Option<KnobDragState>);
+
+    fn build(&mut self, cx: &mut BuildCx, _data: &mut T) -> Self::State {
+        cx.set_class("knob");
+        cx.set_focusable(true);
+
+        (KnobStyle::styled(self, cx.styles()), None)
+    }
+
+    fn rebuild(
+        &mut self,
+        (style, _): &mut Self::State,
+        cx: &mut RebuildCx,
+        _data: &mut T,
+        old: &Self,
+    ) {
+        Rebuild::rebuild(self, cx, old);
+        style.rebuild(self, cx);
+    }
+
+    fn event(
+        &mut self,
+        (_, drag): &mut Self::State,
+        cx: &mut EventCx,
+        data: &mut T,
+        event: &Event,
+    ) -> bool {
+        if cx.focused_changed() {
+            cx.draw();
+        }
+
+        let center = cx.rect().center();
+
+        match event {
+            Event::PointerPressed(e) if cx.is_hovered() => {
+                let local = cx.local(e.position);
+                let mut state = KnobDragState::new(self.drag, center, local, self.normalized());
+
+                let t = state.normalized(self.drag, self.drag_distance, center, local);
+                self.set_value(cx, data, self.value_at(t));
+
+                *drag = Some(state);
+
+                cx.set_active(true);
+                cx.capture_pointer(e.id);
+                cx.focus();
+
+                true
+            }
+            Event::PointerMoved(e) if cx.is_active() => {
+                let local = cx.local(e.position);
+
+                if let Some(ref mut state) = drag {
+                    let t = state.normalized(self.drag, self.drag_distance, center, local);
+                    self.set_value(cx, data, self.value_at(t));
+                }
+
+                true
+            }
+            Event::PointerReleased(e) if cx.is_active() => {
+                *drag = None;
+
+                cx.set_active(false);
+                cx.release_pointer_capture(e.id);
+
+                true
+            }
+            Event::KeyPressed(e) if cx.is_focused() => {
+                let (start, end) = (*self.range.start(), *self.range.end());
+                let step = self.step.unwrap_or((end - start) / 100.0);
+
+                let value = if e.is_key(Key::Up) || e.is_key(Key::Right) {
+                    self.value + step
+                } else if e.is_key(Key::Down) || e.is_key(Key::Left) {
+                    self.value - step
+                } else if e.is_key(Key::Home) {
+                    start
+                } else if e.is_key(Key::End) {
+                    end
+                } else {
+                    return false;
+                };
+
+                let value = value.clamp(start.min(end), start.max(end));
+                self.set_value(cx, data, value);
+
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn layout(
+        &mut self,
+        (style, _): &mut Self::State,
+        _cx: &mut LayoutCx,
+        _data: &mut T,
+        space: Space,
+    ) -> Size {
+        space.fit(Size::all(style.size))
+    }
+
+    fn draw(&mut self, (style, _): &mut Self::State, cx: &mut DrawCx, _data: &mut T) {
+        cx.hoverable(|cx| {
+            let center = cx.rect().center();
+            let radius = (cx.size().width.min(cx.size().height) - style.width) / 2.0;
+            let angle = START + self.normalized() * SWEEP;
+
+            let stroke = Stroke {
+                width: style.width,
+                cap: StrokeCap::Round,
+                ..Default::default()
+            };
+
+            if cx.is_focused() {
+                let ring = Curve::circle(center, radius + style.width);
+                let color = cx.styles().get_or(Color::BLUE, &Theme::INFO);
+                cx.stroke(ring, 2.0, color);
+            }
+
+            cx.stroke(
+                arc(center, radius, START, SWEEP),
+                stroke.clone(),
+                style.background,
+            );
+
+            if self.normalized() > 0.0 {
+                let value = arc(center, radius, START, angle - START);
+                cx.stroke(value, stroke.clone(), style.color);
+            }
+
+            let mut indicator = Curve::new();
+            indicator.move_to(center + Vector::from_angle(angle) * radius * 0.3);
+            indicator.line_to(center + Vector::from_angle(angle) * radius);
+            cx.stroke(indicator, stroke, style.color);
+        });
+    }
+}
+
+// an arc of a circle, from `start` sweeping `sweep` radians clockwise
+fn arc(center: Point, radius: f32, start: f32, sweep: f32) -> Curve {
+    // each cubic covers at most a quarter of the circle
+    let segments = (sweep.abs() / (PI / 2.0)).ceil().max(1.0);
+    let step = sweep / segments;
+    let handle = radius * 4.0 / 3.0 * (step / 4.0).tan();
+
+    let mut curve = Curve::new();
+    curve.move_to(center + Vector::from_angle(start) * radius);
+
+    for i in 0..segments as usize {
+        let a = start + step * i as f32;
+        let b = a + step;
+
+        let (from, to) = (Vector::from_angle(a), Vector::from_angle(b));
+        let tangent = |v: Vector| Vector::new(-v.y, v.x);
+
+        curve.cubic_to(
+            center + from * radius + tangent(from) * handle,
+            center + to * radius - tangent(to) * handle,
+            center + to * radius,
+        );
+    }
+
+    curve
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        event::PointerButton,
+        hstack,
+        view::ViewId,
+        views::{testing::ViewTester, with_id},
+    };
+
+    use super::*;
+
+    // press the knob at the first point and drag it through the rest, returning the value
+    // after each point
+    fn drag(knob: Knob<f32>, points: &[Point]) -> Vec<f32> {
+        let id = ViewId::new();
+        let mut value = knob.value;
+        let mut view = hstack![with_id(id, knob.on_change(|_, value, new| *value = new))];
+
+        let mut tester = ViewTester::new(&mut view, &mut value);
+        tester.layout(&mut view, &mut value, Space::UNBOUNDED);
+
+        tester.hover(id, points[0]);
+        tester.press(&mut view, &mut value, points[0], PointerButton::Primary);
+        let mut values = vec![value];
+
+        for &point in &points[1..] {
+            tester.move_to(&mut view, &mut value, point);
+            values.push(value);
+        }
+
+        values
+    }
+
+    fn assert_values(values: &[f32], expected: &[f32]) {
+        assert_eq!(values.len(), expected.len());
+
+        for (value, expected) in values.iter().zip(expected) {
+            assert!((value - expected).abs() < 1e-3, "{:?}", values);
+        }
+    }
+
+    #[test]
+    fn drag_maps_to_value_in_range() {
+        // the knob is 48 pixels wide, centered at 24, 24
+        let center = Point::all(24.0);
+        let at = |angle: f32| center + Vector::from_angle(angle) * 20.0;
+
+        // 200 pixels for the full range, pressing doesn't change the value
+        let vertical = knob(5.0).range(0.0..=10.0);
+        let values = drag(
+            vertical,
+            &[
+                center,
+                center - Vector::new(0.0, 40.0),
+                center + Vector::all(500.0),
+            ],
+        );
+        assert_values(&values, &[5.0, 7.0, 0.0]);
+
+        // pressing at the top jumps to the middle, then the value follows the pointer clockwise
+        // up to the end, and stays there when the pointer continues into the gap at the bottom
+        // and past the start
+        let circular = knob(0.0).range(0.0..=10.0).drag(KnobDrag::Circular);
+        let points = [at(-PI / 2.0), at(0.0), at(PI / 2.0), at(PI)];
+        assert_values(&drag(circular, &points), &[5.0, 25.0 / 3.0, 10.0, 10.0]);
+
+        // with steps the value snaps to them
+        let stepped = knob(0.0)
+            .range(0.0..=10.0)
+            .step(2.0)
+            .drag(KnobDrag::Circular);
+        assert_values(&drag(stepped, &points[..2]), &[6.0, 8.0]);
+    }
+}
@@ -0,0 +1,221 @@
+use crate::{
+    context::{BuildCx, DrawCx, EventCx, LayoutCx, RebuildCx},
+    event::Event,
+    layout::{Point, Rect, Size, Space},
+    view::{Pod, State, View, ViewId},
+};
+
+/// Create a new [`Lazy`] view.
+pub fn lazy<T, V>(build: impl FnMut(&mut T) -> V + 'static) -> Lazy<T, V> {
+    Lazy::new(build)
+}
+
+/// A command that tears down the content of every [`Lazy`] view that receives it, they are
+/// built again the next time they are shown.
+///
+/// Send it with [`BaseCx::send_to`](crate::context::BaseCx::send_to) to reset a single view.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LazyReset;
+
+// sent by a lazy view when its content was built while drawing, the view with the matching id
+// is laid out again at the size of the content
+struct LazyBuilt(ViewId);
+
+/// A view that defers building its content until it's first shown.
+///
+/// Until then, the view takes up the size of its [`placeholder`](Lazy::placeholder) and
+/// draws nothing. The first time the placeholder is drawn inside the visible region, eg. when
+/// it's scrolled into view, the content is built and drawn in its place in the same frame, and
+/// the view is laid out again at the size of the content. This saves building the rows of a
+/// long list that are never scrolled to.
+///
+/// Once built, the content is rebuilt like any other view, until a [`LazyReset`] is received.
+pub struct Lazy<T, V> {
+    #[allow(clippy::type_complexity)]
+    build: Box<dyn FnMut(&mut T) -> V>,
+
+    /// The size of the view until its content is built.
+    ///
+    /// This should be close to the size of the content, a placeholder with no size is shown
+    /// as long as it's at the edge of the visible region.
+    pub placeholder: Size,
+}
+
+impl<T, V> Lazy<T, V> {
+    /// Create a new [`Lazy`] view.
+    pub fn new(build: impl FnMut(&mut T) -> V + 'static) -> Self {
+        Self {
+            build: Box::new(build),
+            placeholder: Size::ZERO,
+        }
+    }
+
+    /// Set the size of the view until its content is built.
+    pub fn placeholder(mut self, size: impl Into<Size>) -> Self {
+        self.placeholder = size.into();
+        self
+    }
+}
+
+#[doc(hidden)]
+pub struct LazyState<T, V: View<T>> {
+    id: ViewId,
+    space: Space,
+    size: Size,
+    content: Option<(Pod<V>, State<T, V>)>,
+}
+
+impl<T, V: View<T>> LazyState<T, V> {
+    /// Check whether the content has been built.
+    pub fn is_built(&self) -> bool {
+        self.content.is_some()
+    }
+}
+
+impl<T, V: View<T>> View<T> for Lazy<T, V> {
+    type State = LazyState<T, V>;
+
+    fn build(&mut self, _cx: &mut BuildCx, _data: &mut T) -> Self::State {
+        LazyState {
+            id: ViewId::new(),
+            space: Space::UNBOUNDED,
+            size: Size::ZERO,
+            content: None,
+        }
+    }
+
+    fn rebuild(&mut self, state: &mut Self::State, cx: &mut RebuildCx, data: &mut T, old: &Self) {
+        match state.content {
+            Some((ref mut view, ref mut content)) => {
+                let mut new_view = Pod::new((self.build)(data));
+                new_view.rebuild(content, cx, data, view);
+                *view = new_view;
+            }
+            None if self.placeholder != old.placeholder => cx.layout(),
+            None => {}
+        }
+    }
+
+    fn event(
+        &mut self,
+        state: &mut Self::State,
+        cx: &mut EventCx,
+        data: &mut T,
+        event: &Event,
+    ) -> bool {
+        if let Some(LazyBuilt(id)) = event.cmd() {
+            if *id == state.id {
+                cx.layout();
+                return true;
+            }
+        }
+
+        if event.is_cmd::<LazyReset>() && state.content.is_some() {
+            state.content = None;
+            cx.layout();
+        }
+
+        match state.content {
+            Some((ref mut view, ref mut content)) => view.event(content, cx, data, event),
+            None => false,
+        }
+    }
+
+    fn layout(
+        &mut self,
+        state: &mut Self::State,
+        cx: &mut LayoutCx,
+        data: &mut T,
+        space: Space,
+    ) -> Size {
+        state.space = space;
+        state.size = match state.content {
+            Some((ref mut view, ref mut content)) => view.layout(content, cx, data, space),
+            None => space.fit(self.placeholder),
+        };
+
+        state.size
+    }
+
+    fn draw(&mut self, state: &mut Self::State, cx: &mut DrawCx, data: &mut T) {
+        match state.content {
+            Some((ref mut view, ref mut content)) => view.draw(content, cx, data),
+            None => {
+                if !cx.is_visible(Rect::min_size(Point::ZERO, state.size)) {
+                    return;
+                }
+
+                // the content is laid out in the space the placeholder was given, so it's
+                // shown right away instead of an empty placeholder for a frame
+                let mut view = Pod::new((self.build)(data));
+                let mut content = view.build(&mut cx.as_build_cx(), data);
+                let mut layout_cx = cx.as_layout_cx();
+                state.size = view.layout(&mut content, &mut layout_cx, data, state.space);
+                view.draw(&mut content, cx, data);
+
+                state.content = Some((view, content));
+                cx.cmd(LazyBuilt(state.id));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, rc::Rc};
+
+    use crate::{
+        canvas::Color,
+        command::Command,
+        views::{painter, size, testing::ViewTester},
+    };
+
+    use super::*;
+
+    #[test]
+    fn content_is_built_when_first_shown() {
+        let builds = Rc::new(Cell::new(0));
+
+        let mut view = lazy({
+            let builds = builds.clone();
+            move |_: &mut ()| {
+                builds.set(builds.get() + 1);
+                size(10.0, painter(|cx, _| cx.fill_rect(cx.rect(), Color::RED)))
+            }
+        })
+        .placeholder(Size::all(20.0));
+
+        let mut tester = ViewTester::new(&mut view, &mut ());
+
+        // nothing is built until the placeholder is drawn
+        let placeholder = tester.layout(&mut view, &mut (), Space::UNBOUNDED);
+        assert_eq!(placeholder, Size::all(20.0));
+        assert_eq!(builds.get(), 0);
+        assert!(!tester.state.is_built());
+
+        // drawing the visible placeholder builds the content, and draws it in the same frame
+        let canvas = tester.draw(&mut view, &mut ());
+        assert_eq!(builds.get(), 1);
+        assert!(tester.state.is_built());
+
+        let content = Rect::min_size(Point::ZERO, Size::all(10.0));
+        assert_eq!(canvas.content_bounds(), Some(content));
+
+        // then the view is laid out again at the size of the content
+        let command = tester.command_rx.try_recv().unwrap();
+        tester.event(&mut view, &mut (), &Event::Command(command));
+        assert!(tester.view_state.needs_layout());
+
+        let built = tester.layout(&mut view, &mut (), Space::UNBOUNDED);
+        assert_eq!(built, Size::all(10.0));
+
+        // a rebuild rebuilds the content, and a reset tears it down
+        let mut new_view = lazy(|_: &mut ()| size(10.0, painter(|_, _| {})));
+        tester.rebuild(&mut new_view, &mut (), &view);
+        assert!(tester.state.is_built());
+
+        let reset = Event::Command(Command::new(LazyReset));
+        tester.event(&mut new_view, &mut (), &reset);
+        assert!(!tester.state.is_built());
+    }
+}
@@ -0,0 +1,148 @@
+use crate::{
+    context::{BuildCx, DrawCx, EventCx, LayoutCx, RebuildCx},
+    event::Event,
+    layout::{Size, Space},
+    view::View,
+};
+
+/// Create a new [`Lifecycle`].
+pub fn lifecycle<T, V>(content: V) -> Lifecycle<T, V> {
+    Lifecycle::new(content)
+}
+
+/// A view that runs callbacks when it's mounted and unmounted.
+///
+/// The view is mounted when it's built, and unmounted when its state is dropped, eg. when it's
+/// removed from the view tree, or when its window is closed. This is useful for side effects
+/// tied to the presence of a view, like starting and stopping a subscription.
+pub struct Lifecycle<T, V> {
+    /// The content.
+    pub content: V,
+
+    /// The callback called after the content is built.
+    #[allow(clippy::type_complexity)]
+    pub on_mount: Option<Box<dyn FnOnce(&mut BuildCx, &mut T) + 'static>>,
+
+    /// The callback called when the state of the view is dropped.
+    ///
+    /// When the view is rebuilt, the callback replaces the one from the previous rebuild,
+    /// so the callback always captures the latest values.
+    pub on_unmount: Option<Box<dyn FnOnce() + 'static>>,
+}
+
+impl<T, V> Lifecycle<T, V> {
+    /// Create a new [`Lifecycle`].
+    pub fn new(content: V) -> Self {
+        Self {
+            content,
+            on_mount: None,
+            on_unmount: None,
+        }
+    }
+
+    /// Set the callback called after the content is built.
+    pub fn on_mount(mut self, on_mount: impl FnOnce(&mut BuildCx, &mut T) + 'static) -> Self {
+        self.on_mount = Some(Box::new(on_mount));
+        self
+    }
+
+    /// Set the callback called when the state of the view is dropped.
+    pub fn on_unmount(mut self, on_unmount: impl FnOnce() + 'static) -> Self {
+        self.on_unmount = Some(Box::new(on_unmount));
+        self
+    }
+}
+
+#[doc(hidden)]
+pub struct LifecycleState<S> {
+    content: S,
+    on_unmount: Option<Box<dyn FnOnce() + 'static>>,
+}
+
+impl<S> Drop for LifecycleState<S> {
+    fn drop(&mut self) {
+        if let Some(on_unmount) = self.on_unmount.take() {
+            on_unmount();
+        }
+    }
+}
+
+impl<T, V: View<T>> View<T> for Lifecycle<T, V> {
+    type State = LifecycleState<V::State>;
+
+    fn build(&mut self, cx: &mut BuildCx, data: &mut T) -> Self::State {
+        let content = self.content.build(cx, data);
+
+        if let Some(on_mount) = self.on_mount.take() {
+            on_mount(cx, data);
+        }
+
+        LifecycleState {
+            content,
+            on_unmount: self.on_unmount.take(),
+        }
+    }
+
+    fn rebuild(&mut self, state: &mut Self::State, cx: &mut RebuildCx, data: &mut T, old: &Self) {
+        if let Some(on_unmount) = self.on_unmount.take() {
+            state.on_unmount = Some(on_unmount);
+        }
+
+        (self.content).rebuild(&mut state.content, cx, data, &old.content);
+    }
+
+    fn event(
+        &mut self,
+        state: &mut Self::State,
+        cx: &mut EventCx,
+        data: &mut T,
+        event: &Event,
+    ) -> bool {
+        self.content.event(&mut state.content, cx, data, event)
+    }
+
+    fn layout(
+        &mut self,
+        state: &mut Self::State,
+        cx: &mut LayoutCx,
+        data: &mut T,
+        space: Space,
+    ) -> Size {
+        self.content.layout(&mut state.content, cx, data, space)
+    }
+
+    fn draw(&mut self, state: &mut Self::State, cx: &mut DrawCx, data: &mut T) {
+        self.content.draw(&mut state.content, cx, data);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use crate::views::testing::ViewTester;
+
+    use super::*;
+
+    #[test]
+    fn mount_then_unmount() {
+        let events = Rc::new(RefCell::new(Vec::new()));
+
+        let mut view = lifecycle(())
+            .on_mount({
+                let events = events.clone();
+                move |_, _| events.borrow_mut().push("mount")
+            })
+            .on_unmount({
+                let events = events.clone();
+                move || events.borrow_mut().push("unmount")
+            });
+
+        let mut tester = ViewTester::new(&mut view, &mut ());
+        tester.layout(&mut view, &mut (), Space::UNBOUNDED);
+        assert_eq!(*events.borrow(), ["mount"]);
+
+        drop(tester);
+        assert_eq!(*events.borrow(), ["mount", "unmount"]);
+    }
+}
@@ -0,0 +1,117 @@
+use ori_macro::Build;
+
+use crate::{
+    context::{BuildCx, DrawCx, EventCx, LayoutCx, RebuildCx},
+    event::Event,
+    layout::{Size, Space},
+    view::View,
+    window::Politeness,
+};
+
+/// Create a new [`LiveRegion`], emitting an announcement of `message` whenever it changes.
+pub fn live_region<V>(message: impl Into<String>, content: V) -> LiveRegion<V> {
+    LiveRegion::new(message, content)
+}
+
+/// A view that emits an [`Announcement`] when a message changes, eg. the number of items left
+/// in a list.
+///
+/// The message is compared with the previous one when the view is rebuilt, and announced with
+/// [`BaseCx::announce`](crate::context::BaseCx::announce) if it's different. The first
+/// message isn't announced, since it's part of the content the user is already reading.
+///
+/// The content isn't marked as a live region anywhere, there is no accessibility tree, so the
+/// announcement only reaches a screen reader through a delegate of the application.
+///
+/// [`Announcement`]: crate::window::Announcement
+#[derive(Build)]
+pub struct LiveRegion<V> {
+    /// The content.
+    #[build(ignore)]
+    pub content: V,
+
+    /// The message announced when it changes.
+    #[build(ignore)]
+    pub message: String,
+
+    /// How urgently the message is announced.
+    pub politeness: Politeness,
+}
+
+impl<V> LiveRegion<V> {
+    /// Create a new [`LiveRegion`].
+    pub fn new(message: impl Into<String>, content: V) -> Self {
+        Self {
+            content,
+            message: message.into(),
+            politeness: Politeness::Polite,
+        }
+    }
+}
+
+impl<T, V: View<T>> View<T> for LiveRegion<V> {
+    type State = V::State;
+
+    fn build(&mut self, cx: &mut BuildCx, data: &mut T) -> Self::State {
+        self.content.build(cx, data)
+    }
+
+    fn rebuild(&mut self, state: &mut Self::State, cx: &mut RebuildCx, data: &mut T, old: &Self) {
+        if self.message != old.message {
+            cx.announce(self.message.clone(), self.politeness);
+        }
+
+        self.content.rebuild(state, cx, data, &old.content);
+    }
+
+    fn event(
+        &mut self,
+        state: &mut Self::State,
+        cx: &mut EventCx,
+        data: &mut T,
+        event: &Event,
+    ) -> bool {
+        self.content.event(state, cx, data, event)
+    }
+
+    fn layout(
+        &mut self,
+        state: &mut Self::State,
+        cx: &mut LayoutCx,
+        data: &mut T,
+        space: Space,
+    ) -> Size {
+        self.content.layout(state, cx, data, space)
+    }
+
+    fn draw(&mut self, state: &mut Self::State, cx: &mut DrawCx, data: &mut T) {
+        self.content.draw(state, cx, data);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{views::testing::ViewTester, window::Announcement};
+
+    use super::*;
+
+    #[test]
+    fn changed_message_is_announced() {
+        let view = |left: usize| live_region(format!("{} items left", left), ());
+
+        let mut old = view(3);
+        let mut tester = ViewTester::new(&mut old, &mut ());
+
+        let mut new = view(3);
+        tester.rebuild(&mut new, &mut (), &old);
+        assert!(tester.command_rx.try_recv().is_none());
+
+        let mut newer = view(2).politeness(Politeness::Assertive);
+        tester.rebuild(&mut newer, &mut (), &new);
+
+        let command = tester.command_rx.try_recv().unwrap();
+        let announcement = command.get::<Announcement>().unwrap();
+        assert_eq!(announcement.message, "2 items left");
+        assert_eq!(announcement.politeness, Politeness::Assertive);
+    }
+}
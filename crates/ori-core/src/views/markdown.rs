@@ -0,0 +1,606 @@
+use std::ops::Range;
+
+use ori_macro::{Build, Styled};
+use pulldown_cmark::{Event as MdEvent, HeadingLevel, Parser, Tag, TagEnd};
+
+use crate::{
+    canvas::Color,
+    context::{BuildCx, DrawCx, EventCx, LayoutCx, RebuildCx},
+    event::{Event, PointerButton},
+    layout::{Point, Rect, Size, Space},
+    rebuild::Rebuild,
+    style::{Styled, Theme},
+    text::{
+        FontAttributes, FontFamily, FontStretch, FontStyle, FontWeight, Paragraph, TextAlign,
+        TextLayoutLine, TextWrap,
+    },
+    view::View,
+    window::Cursor,
+};
+
+/// Create a new [`Markdown`] view.
+pub fn markdown<T>(source: impl Into<String>) -> Markdown<T> {
+    Markdown::new(source)
+}
+
+/// A view that displays a subset of Markdown, eg. for help panels and changelogs.
+///
+/// Paragraphs, headings, lists, code blocks, and inline bold, italic, code and links are
+/// supported, anything else is displayed as plain text. Clicking a link calls
+/// [`Markdown::on_link`] with its url.
+///
+/// Can be styled with a [`MarkdownStyle`]. Like [`Text`](super::Text), the font size, font
+/// family and color are inherited from the closest ancestor that sets them, the color from
+/// its `text-color`.
+#[derive(Styled, Build, Rebuild)]
+pub struct Markdown<T> {
+    /// The Markdown source.
+    #[rebuild(layout)]
+    pub source: String,
+
+    /// The callback for when a link is clicked.
+    #[build(ignore)]
+    #[allow(clippy::type_complexity)]
+    pub on_link: Option<Box<dyn FnMut(&mut EventCx, &mut T, &str) + 'static>>,
+
+    /// The font size of the body text, headings are scaled from it.
+    #[styled(inherit = "font-size", default = 16.0)]
+    #[rebuild(layout)]
+    pub font_size: Styled<f32>,
+
+    /// The font family of the text, code is always monospace.
+    #[styled(inherit = "font-family", default)]
+    #[rebuild(layout)]
+    pub font_family: Styled<FontFamily>,
+
+    /// The color of the text.
+    #[styled(inherit = "text-color", default -> Theme::CONTRAST or Color::BLACK)]
+    #[rebuild(layout)]
+    pub color: Styled<Color>,
+
+    /// The color of links.
+    #[styled(default -> Theme::ACCENT or Color::BLUE)]
+    #[rebuild(layout)]
+    pub link_color: Styled<Color>,
+
+    /// The background color of code blocks.
+    #[styled(default -> Theme::SURFACE_HIGH or Color::grayscale(0.9))]
+    #[rebuild(draw)]
+    pub code_background: Styled<Color>,
+
+    /// The line height of the text.
+    #[styled(default = 1.2)]
+    #[rebuild(layout)]
+    pub line_height: Styled<f32>,
+
+    /// The space between blocks, eg. paragraphs and list items.
+    #[styled(default = 8.0)]
+    #[rebuild(layout)]
+    pub spacing: Styled<f32>,
+
+    /// The indentation of each level of lists.
+    #[styled(default = 20.0)]
+    #[rebuild(layout)]
+    pub indent: Styled<f32>,
+}
+
+impl<T> Markdown<T> {
+    /// Create a new [`Markdown`] view.
+    pub fn new(source: impl Into<String>) -> Self {
+        Self {
+            source: source.into(),
+            on_link: None,
+            font_size: Styled::style("markdown.font-size"),
+            font_family: Styled::style("markdown.font-family"),
+            color: Styled::style("markdown.color"),
+            link_color: Styled::style("markdown.link-color"),
+            code_background: Styled::style("markdown.code-background"),
+            line_height: Styled::style("markdown.line-height"),
+            spacing: Styled::style("markdown.spacing"),
+            indent: Styled::style("markdown.indent"),
+        }
+    }
+
+    /// Set the callback for when a link is clicked.
+    pub fn on_link(mut self, on_link: impl FnMut(&mut EventCx, &mut T, &str) + 'static) -> Self {
+        self.on_link = Some(Box::new(on_link));
+        self
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum BlockKind {
+    Paragraph,
+    Heading(HeadingLevel),
+    ListItem { marker: String },
+    Code,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+struct Span {
+    text: String,
+    bold: bool,
+    italic: bool,
+    code: bool,
+    link: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Block {
+    kind: BlockKind,
+    spans: Vec<Span>,
+    // the level of indentation, the number of list items the block is in
+    level: usize,
+}
+
+// parse the supported subset of markdown into blocks of styled spans
+fn parse(source: &str) -> Vec<Block> {
+    let mut blocks = Vec::new();
+    let mut current: Option<Block> = None;
+
+    let mut bold = 0;
+    let mut italic = 0;
+    let mut link = None;
+
+    // the next number of each ordered list, `None` for bullet lists
+    let mut lists: Vec<Option<u64>> = Vec::new();
+
+    // the number of list items the parser is in
+    let mut items = 0;
+
+    fn flush(blocks: &mut Vec<Block>, current: &mut Option<Block>) {
+        if let Some(block) = current.take() {
+            if !block.spans.is_empty() {
+                blocks.push(block);
+            }
+        }
+    }
+
+    fn begin(blocks: &mut Vec<Block>, current: &mut Option<Block>, kind: BlockKind, level: usize) {
+        flush(blocks, current);
+        *current = Some(Block {
+            kind,
+            spans: Vec::new(),
+            level,
+        });
+    }
+
+    for event in Parser::new(source) {
+        let in_code = matches!(current, Some(ref block) if block.kind == BlockKind::Code);
+        let is_empty = matches!(current, Some(ref block) if block.spans.is_empty());
+
+        // text after a nested list continues the outer item
+        let mut span = |text: &str, code: bool| {
+            let block = current.get_or_insert_with(|| Block {
+                kind: BlockKind::Paragraph,
+                spans: Vec::new(),
+                level: items,
+            });
+
+            block.spans.push(Span {
+                text: text.to_string(),
+                bold: bold > 0,
+                italic: italic > 0,
+                code,
+                link: link.clone(),
+            });
+        };
+
+        match event {
+            // the first paragraph of a loose list item continues the item, the rest are
+            // indented like it
+            MdEvent::Start(Tag::Paragraph) if !is_empty => {
+                begin(&mut blocks, &mut current, BlockKind::Paragraph, items);
+            }
+            MdEvent::Start(Tag::Heading { level, .. }) => {
+                begin(&mut blocks, &mut current, BlockKind::Heading(level), items);
+            }
+            MdEvent::Start(Tag::CodeBlock(_)) => {
+                begin(&mut blocks, &mut current, BlockKind::Code, items);
+            }
+            MdEvent::Start(Tag::List(start)) => {
+                flush(&mut blocks, &mut current);
+                lists.push(start);
+            }
+            MdEvent::Start(Tag::Item) => {
+                items += 1;
+
+                let marker = match lists.last_mut() {
+                    Some(Some(number)) => {
+                        *number += 1;
+                        format!("{}.", *number - 1)
+                    }
+                    _ => String::from("•"),
+                };
+
+                begin(
+                    &mut blocks,
+                    &mut current,
+                    BlockKind::ListItem { marker },
+                    items,
+                );
+            }
+            MdEvent::Start(Tag::Strong) => bold += 1,
+            MdEvent::Start(Tag::Emphasis) => italic += 1,
+            MdEvent::Start(Tag::Link { dest_url, .. }) => link = Some(dest_url.to_string()),
+            MdEvent::End(TagEnd::Strong) => bold -= 1,
+            MdEvent::End(TagEnd::Emphasis) => italic -= 1,
+            MdEvent::End(TagEnd::Link) => link = None,
+            MdEvent::End(TagEnd::List(_)) => {
+                flush(&mut blocks, &mut current);
+                lists.pop();
+            }
+            MdEvent::End(TagEnd::CodeBlock) => {
+                if let Some(span) = current.as_mut().and_then(|block| block.spans.last_mut()) {
+                    span.text.truncate(span.text.trim_end_matches('\n').len());
+                }
+
+                flush(&mut blocks, &mut current);
+            }
+            MdEvent::End(TagEnd::Item) => {
+                flush(&mut blocks, &mut current);
+                items -= 1;
+            }
+            MdEvent::End(TagEnd::Paragraph | TagEnd::Heading(_)) => {
+                flush(&mut blocks, &mut current);
+            }
+            MdEvent::Text(text) => span(&text, in_code),
+            MdEvent::Code(text) => span(&text, true),
+            MdEvent::SoftBreak => span(" ", false),
+            MdEvent::HardBreak => span("\n", false),
+            _ => {}
+        }
+    }
+
+    flush(&mut blocks, &mut current);
+    blocks
+}
+
+#[doc(hidden)]
+pub struct MarkdownState {
+    blocks: Vec<Block>,
+    laid_out: Vec<BlockLayout>,
+}
+
+struct BlockLayout {
+    paragraph: Paragraph,
+    marker: Option<Paragraph>,
+    links: Vec<(Range<usize>, String)>,
+    // the lines of the paragraph, laid out when looking for links
+    lines: Option<Vec<TextLayoutLine>>,
+    level: usize,
+    code: bool,
+    rect: Rect,
+    marker_rect: Rect,
+    background: Rect,
+}
+
+impl MarkdownState {
+    fn new(source: &str, style: &MarkdownStyle) -> Self {
+        let mut state = Self {
+            blocks: parse(source),
+            laid_out: Vec::new(),
+        };
+
+        state.build(style);
+        state
+    }
+
+    // build the paragraphs of the blocks with `style`
+    fn build(&mut self, style: &MarkdownStyle) {
+        let attributes = |size: f32, span: &Span| FontAttributes {
+            size,
+            family: match span.code {
+                true => FontFamily::Monospace,
+                false => style.font_family.clone(),
+            },
+            stretch: FontStretch::Normal,
+            weight: match span.bold {
+                true => FontWeight::BOLD,
+                false => FontWeight::NORMAL,
+            },
+            style: match span.italic {
+                true => FontStyle::Italic,
+                false => FontStyle::Normal,
+            },
+            ligatures: true,
+            color: match span.link {
+                Some(_) => style.link_color,
+                None => style.color,
+            },
+            variations: Vec::new(),
+        };
+
+        self.laid_out.clear();
+
+        for block in &self.blocks {
+            let size = match block.kind {
+                BlockKind::Heading(level) => style.font_size * heading_scale(level),
+                _ => style.font_size,
+            };
+
+            let mut paragraph = Paragraph::new(style.line_height, TextAlign::Start, TextWrap::Word);
+            let mut links = Vec::new();
+
+            for span in &block.spans {
+                let mut span = span.clone();
+                span.bold |= matches!(block.kind, BlockKind::Heading(_));
+
+                let start = paragraph.text().len();
+                paragraph.push_text(&span.text, attributes(size, &span));
+
+                if let Some(link) = span.link {
+                    links.push((start..paragraph.text().len(), link));
+                }
+            }
+
+            let marker = match block.kind {
+                BlockKind::ListItem { ref marker, .. } => {
+                    let mut paragraph =
+                        Paragraph::new(style.line_height, TextAlign::End, TextWrap::None);
+                    paragraph.set_text(marker, attributes(size, &Span::default()));
+                    Some(paragraph)
+                }
+                _ => None,
+            };
+
+            // the marker of list items goes in their indentation
+            self.laid_out.push(BlockLayout {
+                paragraph,
+                marker,
+                links,
+                lines: None,
+                level: block.level,
+                code: block.kind == BlockKind::Code,
+                rect: Rect::ZERO,
+                marker_rect: Rect::ZERO,
+                background: Rect::ZERO,
+            });
+        }
+    }
+
+    // the url of the link at `local`, if any
+    fn link_at(&mut self, cx: &mut EventCx, local: Point) -> Option<String> {
+        let block = (self.laid_out.iter_mut()).find(|block| block.rect.contains(local))?;
+
+        let lines = block
+            .lines
+            .get_or_insert_with(|| cx.fonts().layout(&block.paragraph, block.rect.width()));
+
+        let point = Point::from(local - block.rect.min);
+
+        let glyphs = lines.iter().flat_map(|line| &line.glyphs);
+        let glyph = glyphs
+            .into_iter()
+            .find(|glyph| glyph.bounds.contains(point))?;
+
+        let (_, url) =
+            (block.links.iter()).find(|(range, _)| range.contains(&glyph.range.start))?;
+        Some(url.clone())
+    }
+}
+
+fn heading_scale(level: HeadingLevel) -> f32 {
+    match level {
+        HeadingLevel::H1 => 2.0,
+        HeadingLevel::H2 => 1.5,
+        HeadingLevel::H3 => 1.25,
+        HeadingLevel::H4 => 1.1,
+        HeadingLevel::H5 => 1.0,
+        HeadingLevel::H6 => 0.9,
+    }
+}
+
+// the padding of code blocks
+const CODE_PADDING: f32 = 8.0;
+
+impl<T> View<T> for Markdown<T> {
+    type State = MarkdownState;
+
+    fn build(&mut self, cx: &mut BuildCx, _data: &mut T) -> Self::State {
+        let style = MarkdownStyle::styled(self, cx.styles());
+        MarkdownState::new(&self.source, &style)
+    }
+
+    fn rebuild(&mut self, state: &mut Self::State, cx: &mut RebuildCx, _data: &mut T, old: &Self) {
+        Rebuild::rebuild(self, cx, old);
+
+        let style = MarkdownStyle::styled(self, cx.styles());
+
+        if self.source != old.source {
+            state.blocks = parse(&self.source);
+        }
+
+        state.build(&style);
+    }
+
+    fn event(
+        &mut self,
+        state: &mut Self::State,
+        cx: &mut EventCx,
+        data: &mut T,
+        event: &Event,
+    ) -> bool {
+        match event {
+            Event::PointerMoved(e) if cx.is_hovered() => {
+                let local = cx.local(e.position);
+
+                match state.link_at(cx, local) {
+                    Some(_) => cx.set_cursor(Some(Cursor::Pointer)),
+                    None => cx.set_cursor(None),
+                }
+
+                false
+            }
+            Event::PointerPressed(e) if cx.is_hovered() && e.button == PointerButton::Primary => {
+                let local = cx.local(e.position);
+
+                let Some(url) = state.link_at(cx, local) else {
+                    return false;
+                };
+
+                if let Some(ref mut on_link) = self.on_link {
+                    on_link(cx, data, &url);
+                }
+
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn layout(
+        &mut self,
+        state: &mut Self::State,
+        cx: &mut LayoutCx,
+        _data: &mut T,
+        space: Space,
+    ) -> Size {
+        let style = MarkdownStyle::styled(self, cx.styles());
+        let width = space.max.width;
+
+        let mut y = 0.0;
+        let mut content_width = 0.0f32;
+
+        for (i, block) in state.laid_out.iter_mut().enumerate() {
+            if i > 0 {
+                y += style.spacing;
+            }
+
+            let indent = block.level as f32 * style.indent;
+            let padding = if block.code { CODE_PADDING } else { 0.0 };
+
+            let max_width = (width - indent - padding * 2.0).max(0.0);
+            let size = cx.fonts().measure(&block.paragraph, max_width);
+
+            block.rect = Rect::min_size(Point::new(indent + padding, y + padding), size);
+            block.lines = None;
+
+            // the marker is right aligned in the indentation of the item
+            let marker_width = style.indent * 0.75;
+            let marker_min = Point::new(indent - style.indent, y);
+            block.marker_rect = Rect::min_size(marker_min, Size::new(marker_width, size.height));
+
+            // code blocks are as wide as the view
+            let full_width = match width.is_finite() {
+                true => width - indent,
+                false => size.width + padding * 2.0,
+            };
+
+            block.background = Rect::min_size(
+                Point::new(indent, y),
+                Size::new(full_width, size.height + padding * 2.0),
+            );
+
+            y += size.height + padding * 2.0;
+            content_width = content_width.max(indent + size.width + padding * 2.0);
+        }
+
+        let size = Size::new(content_width, y);
+        space.fit(size)
+    }
+
+    fn draw(&mut self, state: &mut Self::State, cx: &mut DrawCx, _data: &mut T) {
+        let background = self
+            .code_background
+            .get_or(cx.styles(), Color::grayscale(0.9));
+
+        cx.hoverable(|cx| {
+            for block in &state.laid_out {
+                if block.code {
+                    cx.fill_rect(block.background, background);
+                }
+
+                if let Some(ref marker) = block.marker {
+                    cx.paragraph(marker, block.marker_rect);
+                }
+
+                cx.paragraph(&block.paragraph, block.rect);
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        hstack,
+        text::{Fonts, MonoFonts},
+        view::ViewId,
+        views::{testing::ViewTester, with_id},
+    };
+
+    use super::*;
+
+    #[test]
+    fn bold_and_links_become_spans() {
+        let blocks = parse("**bold** and [a](b)");
+
+        let span = |text: &str| Span {
+            text: text.into(),
+            ..Default::default()
+        };
+
+        let spans = vec![
+            Span {
+                bold: true,
+                ..span("bold")
+            },
+            span(" and "),
+            Span {
+                link: Some(String::from("b")),
+                ..span("a")
+            },
+        ];
+
+        assert_eq!(
+            blocks,
+            [Block {
+                kind: BlockKind::Paragraph,
+                spans,
+                level: 0,
+            }]
+        );
+    }
+
+    #[test]
+    fn list_item_continuations_keep_the_indentation() {
+        let blocks = parse("- a\n\n  b\n  - c\n\n  d\n\ne");
+        let levels: Vec<_> = blocks.iter().map(|block| block.level).collect();
+
+        // the second paragraph of `a`, and `d` after the nested list, stay in the item
+        assert_eq!(levels, [1, 1, 2, 1, 0]);
+        assert_eq!(blocks[1].kind, BlockKind::Paragraph);
+        assert_eq!(blocks[3].spans[0].text, "d");
+    }
+
+    #[test]
+    fn clicking_a_link_calls_on_link() {
+        let id = ViewId::new();
+        let view = markdown("see [a](b)").on_link(|_, urls: &mut Vec<String>, url| {
+            urls.push(url.into());
+        });
+        let mut view = hstack![with_id(id, view)];
+
+        let mut urls = Vec::new();
+        let mut tester = ViewTester::new(&mut view, &mut urls);
+        tester
+            .contexts
+            .insert::<Box<dyn Fonts>>(Box::new(MonoFonts));
+        tester.layout(&mut view, &mut urls, Space::UNBOUNDED);
+        tester.draw(&mut view, &mut urls);
+
+        let mut click = |x: f32| {
+            let position = Point::new(x, 10.0);
+
+            tester.hover(id, position);
+            tester.press(&mut view, &mut urls, position, PointerButton::Primary);
+        };
+
+        // `see ` is plain text, `a` is the fifth glyph
+        click(MonoFonts::ADVANCE * 1.5);
+        click(MonoFonts::ADVANCE * 4.5);
+
+        assert_eq!(urls, ["b"]);
+    }
+}
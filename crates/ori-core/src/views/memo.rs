@@ -13,6 +13,11 @@ pub fn memo<T, V: View<T>, D: PartialEq>(
     Memo::new(data, build)
 }
 
+/// Create a new [`WhenChanged`].
+pub fn when_changed<V, D: PartialEq>(dep: D, view: V) -> WhenChanged<V, D> {
+    WhenChanged::new(dep, view)
+}
+
 /// A view that only builds the inner view when certain data changes.
 pub struct Memo<T, V, D> {
     data: Option<D>,
@@ -87,3 +92,115 @@ impl<T, V: View<T>, D: PartialEq> View<T> for Memo<T, V, D> {
         state.view.draw(&mut state.state, cx, data);
     }
 }
+
+/// A view that only rebuilds the inner view when `dep` changes.
+///
+/// Unlike [`Memo`] the view is built every time, but when `dep` is equal to the last `dep` the
+/// new view is discarded, and the previous view and its subtree are kept without being
+/// rebuilt.
+///
+/// **Note:** the previous view is the one receiving events, so closures captured by the view,
+/// eg. by `on_click`, are also the previous ones. Anything a closure captures that isn't part
+/// of `dep` is stale until `dep` changes, prefer reading from the data passed to the closure.
+pub struct WhenChanged<V, D> {
+    dep: Option<D>,
+    view: Option<V>,
+}
+
+impl<V, D: PartialEq> WhenChanged<V, D> {
+    /// Create a new [`WhenChanged`].
+    pub fn new(dep: D, view: V) -> Self {
+        Self {
+            dep: Some(dep),
+            view: Some(view),
+        }
+    }
+}
+
+#[doc(hidden)]
+pub struct WhenChangedState<T, V: View<T>, D> {
+    view: V,
+    state: V::State,
+    dep: Option<D>,
+}
+
+impl<T, V: View<T>, D: PartialEq> View<T> for WhenChanged<V, D> {
+    type State = WhenChangedState<T, V, D>;
+
+    fn build(&mut self, cx: &mut BuildCx, data: &mut T) -> Self::State {
+        let mut view = self.view.take().expect("WhenChanged::build called twice");
+        let state = view.build(cx, data);
+        let dep = self.dep.take();
+
+        WhenChangedState { view, state, dep }
+    }
+
+    fn rebuild(&mut self, state: &mut Self::State, cx: &mut RebuildCx, data: &mut T, _old: &Self) {
+        if self.dep == state.dep {
+            return;
+        }
+
+        if let Some(mut view) = self.view.take() {
+            view.rebuild(&mut state.state, cx, data, &state.view);
+
+            state.view = view;
+            state.dep = self.dep.take();
+        }
+    }
+
+    fn event(
+        &mut self,
+        state: &mut Self::State,
+        cx: &mut EventCx,
+        data: &mut T,
+        event: &Event,
+    ) -> bool {
+        state.view.event(&mut state.state, cx, data, event)
+    }
+
+    fn layout(
+        &mut self,
+        state: &mut Self::State,
+        cx: &mut LayoutCx,
+        data: &mut T,
+        space: Space,
+    ) -> Size {
+        state.view.layout(&mut state.state, cx, data, space)
+    }
+
+    fn draw(&mut self, state: &mut Self::State, cx: &mut DrawCx, data: &mut T) {
+        state.view.draw(&mut state.state, cx, data);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, rc::Rc};
+
+    use crate::views::{on_rebuild, testing::ViewTester};
+
+    use super::*;
+
+    #[test]
+    fn unchanged_dep_skips_rebuild() {
+        let rebuilds = Rc::new(Cell::new(0));
+        let view = |dep| {
+            let rebuilds = rebuilds.clone();
+            when_changed(
+                dep,
+                on_rebuild((), move |_, _| rebuilds.set(rebuilds.get() + 1)),
+            )
+        };
+
+        let mut old = view(1);
+        let mut tester = ViewTester::new(&mut old, &mut ());
+
+        let mut new = view(1);
+        tester.rebuild(&mut new, &mut (), &old);
+        assert_eq!(rebuilds.get(), 0);
+
+        let mut newer = view(2);
+        tester.rebuild(&mut newer, &mut (), &new);
+        assert_eq!(rebuilds.get(), 1);
+    }
+}
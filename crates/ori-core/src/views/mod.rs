@@ -5,34 +5,55 @@ mod animate;
 mod aspect;
 mod build_handler;
 mod button;
+mod cache;
 mod checkbox;
 mod class;
 mod clickable;
+mod clip;
 mod collapsing;
 mod color_picker;
 mod constrain;
 mod container;
+mod context_menu;
+mod crisp;
+mod debounce;
 mod decorate;
+mod drag;
 mod draw_handler;
+mod error_boundary;
 mod event_handler;
 mod flex;
 mod focus;
 mod image;
+mod image_stream;
+mod knob;
 mod layout;
+mod lazy;
+mod lifecycle;
+mod live_region;
+#[cfg(feature = "markdown")]
+mod markdown;
 mod memo;
+mod opacity;
 mod opaque;
 mod pad;
 mod painter;
 mod rebuild_handler;
 mod scroll;
 mod slider;
+mod split;
 mod stack;
 mod suspense;
+mod tabs;
 mod text;
 mod text_input;
 mod tooltip;
 mod transform;
 mod trigger;
+mod visibility;
+mod watch;
+mod window_focus;
+mod with_id;
 mod with_state;
 mod with_style;
 mod wrap;
@@ -43,33 +64,54 @@ pub use animate::*;
 pub use aspect::*;
 pub use build_handler::*;
 pub use button::*;
+pub use cache::*;
 pub use checkbox::*;
 pub use class::*;
 pub use clickable::*;
+pub use clip::*;
 pub use collapsing::*;
 pub use color_picker::*;
 pub use constrain::*;
 pub use container::*;
+pub use context_menu::*;
+pub use crisp::*;
+pub use debounce::*;
 pub use decorate::*;
+pub use drag::*;
 pub use draw_handler::*;
+pub use error_boundary::*;
 pub use event_handler::*;
 pub use flex::*;
 pub use focus::*;
+pub use image_stream::*;
+pub use knob::*;
 pub use layout::*;
+pub use lazy::*;
+pub use lifecycle::*;
+pub use live_region::*;
+#[cfg(feature = "markdown")]
+pub use markdown::*;
 pub use memo::*;
+pub use opacity::*;
 pub use opaque::*;
 pub use pad::*;
 pub use painter::*;
 pub use rebuild_handler::*;
 pub use scroll::*;
 pub use slider::*;
+pub use split::*;
 pub use stack::*;
 pub use suspense::*;
+pub use tabs::*;
 pub use text::*;
 pub use text_input::*;
 pub use tooltip::*;
 pub use transform::*;
 pub use trigger::*;
+pub use visibility::*;
+pub use watch::*;
+pub use window_focus::*;
+pub use with_id::*;
 pub use with_state::*;
 pub use with_style::*;
 pub use wrap::*;
@@ -77,24 +119,36 @@ pub use zstack::*;
 
 #[cfg(test)]
 #[allow(dead_code)]
-mod testing {
+pub(crate) mod testing {
     use std::collections::HashMap;
 
     use crate::{
+        canvas::Canvas,
         command::{CommandProxy, CommandReceiver, CommandWaker},
         context::{BaseCx, BuildCx, Contexts, DrawCx, EventCx, LayoutCx, RebuildCx},
-        event::Event,
-        layout::{Rect, Size, Space},
-        view::{View, ViewState},
+        event::{
+            Event, Modifiers, PointerButton, PointerId, PointerMoved, PointerPressed,
+            PointerReleased, PointerScrolled, PointerTool, ScrollDelta,
+        },
+        layout::{Axis, Point, Rect, Size, Space, Vector},
+        style::Styles,
+        view::{View, ViewId, ViewState},
         window::Window,
     };
 
+    /// The pointer moved by [`ViewTester::press`], [`ViewTester::move_to`] and the other
+    /// pointer helpers.
+    pub const POINTER: PointerId = PointerId::from_u64(0);
+
     pub struct ViewTester<T, V: View<T>> {
         pub state: V::State,
         pub view_state: ViewState,
         pub contexts: Contexts,
         pub command_rx: CommandReceiver,
         pub command_proxy: CommandProxy,
+
+        /// The tool of [`POINTER`], sent with the pointer events.
+        pub tool: PointerTool,
     }
 
     impl<T, V: View<T>> ViewTester<T, V> {
@@ -105,6 +159,7 @@ mod testing {
 
             let mut contexts = Contexts::new();
             contexts.insert(window);
+            contexts.insert(Styles::new());
 
             let (mut proxy, rx) = CommandProxy::new(waker);
 
@@ -121,6 +176,7 @@ mod testing {
                 contexts,
                 command_rx: rx,
                 command_proxy: proxy,
+                tool: PointerTool::Mouse,
             }
         }
 
@@ -141,6 +197,17 @@ mod testing {
             needs_rebuild
         }
 
+        pub fn draw(&mut self, view: &mut V, data: &mut T) -> Canvas {
+            let mut canvas = Canvas::new();
+
+            let mut base_cx = BaseCx::new(&mut self.contexts, &mut self.command_proxy);
+            let mut draw_cx = DrawCx::new(&mut base_cx, &mut self.view_state, &mut canvas);
+
+            view.draw(&mut self.state, &mut draw_cx, data);
+
+            canvas
+        }
+
         pub fn layout(&mut self, view: &mut V, data: &mut T, space: Space) -> Size {
             let mut base_cx = BaseCx::new(&mut self.contexts, &mut self.command_proxy);
             let mut layout_cx = LayoutCx::new(&mut base_cx, &mut self.view_state);
@@ -150,6 +217,121 @@ mod testing {
 
             size
         }
+
+        pub fn intrinsic_size(
+            &mut self,
+            view: &mut V,
+            data: &mut T,
+            axis: Axis,
+            cross_extent: f32,
+        ) -> f32 {
+            let mut base_cx = BaseCx::new(&mut self.contexts, &mut self.command_proxy);
+            let mut layout_cx = LayoutCx::new(&mut base_cx, &mut self.view_state);
+
+            view.intrinsic_size(&mut self.state, &mut layout_cx, data, axis, cross_extent)
+        }
+
+        fn window_mut(&mut self) -> &mut Window {
+            self.contexts.get_mut::<Window>().unwrap()
+        }
+
+        /// Make [`POINTER`] hover the view with `id` at `position`, a pod updates the hovered
+        /// state of its view from the window on every event.
+        pub fn hover(&mut self, id: ViewId, position: Point) {
+            let window = self.window_mut();
+            window.move_pointer(POINTER, position);
+
+            if let Some(pointer) = window.get_pointer_mut(POINTER) {
+                pointer.hovering = Some(id);
+            }
+        }
+
+        /// Press `button` of [`POINTER`] at `position`.
+        pub fn press(
+            &mut self,
+            view: &mut V,
+            data: &mut T,
+            position: Point,
+            button: PointerButton,
+        ) -> bool {
+            let window = self.window_mut();
+            window.move_pointer(POINTER, position);
+            window.press_pointer(POINTER, button);
+
+            let event = Event::PointerPressed(PointerPressed {
+                id: POINTER,
+                position,
+                button,
+                pressure: 1.0,
+                tilt: Vector::ZERO,
+                tool: self.tool,
+                modifiers: Modifiers::default(),
+            });
+
+            self.event(view, data, &event)
+        }
+
+        /// Move [`POINTER`] to `position`, the delta is the distance from where it last was.
+        pub fn move_to(&mut self, view: &mut V, data: &mut T, position: Point) -> bool {
+            let delta = self.window_mut().move_pointer(POINTER, position);
+
+            let event = Event::PointerMoved(PointerMoved {
+                id: POINTER,
+                position,
+                delta,
+                pressure: 1.0,
+                tilt: Vector::ZERO,
+                tool: self.tool,
+                modifiers: Modifiers::default(),
+            });
+
+            self.event(view, data, &event)
+        }
+
+        /// Release `button` of [`POINTER`] at `position`, it's a click when the pointer stayed
+        /// close to where it was pressed.
+        pub fn release(
+            &mut self,
+            view: &mut V,
+            data: &mut T,
+            position: Point,
+            button: PointerButton,
+        ) -> bool {
+            let window = self.window_mut();
+            window.move_pointer(POINTER, position);
+            let clicked = window.release_pointer(POINTER, button);
+
+            let event = Event::PointerReleased(PointerReleased {
+                id: POINTER,
+                position,
+                clicked,
+                button,
+                pressure: 1.0,
+                tilt: Vector::ZERO,
+                tool: self.tool,
+                modifiers: Modifiers::default(),
+            });
+
+            self.event(view, data, &event)
+        }
+
+        /// Scroll [`POINTER`] by `delta` at `position`.
+        pub fn scroll(
+            &mut self,
+            view: &mut V,
+            data: &mut T,
+            position: Point,
+            delta: impl Into<ScrollDelta>,
+        ) -> bool {
+            let event = Event::PointerScrolled(PointerScrolled {
+                id: POINTER,
+                position,
+                delta: delta.into(),
+                modifiers: Modifiers::default(),
+            });
+
+            self.event(view, data, &event)
+        }
     }
 
     pub fn test_layout<T>(view: &mut impl View<T>, data: &mut T, space: Space) -> SavedLayouts {
@@ -0,0 +1,124 @@
+use crate::{
+    context::{BuildCx, DrawCx, EventCx, LayoutCx, RebuildCx},
+    event::Event,
+    layout::{Size, Space},
+    rebuild::Rebuild,
+    view::{Pod, State, View},
+};
+
+/// Create a new [`Opacity`] view.
+pub fn opacity<V>(opacity: f32, content: V) -> Opacity<V> {
+    Opacity::new(opacity, content)
+}
+
+/// A view that fades its content as a unit.
+///
+/// Everything the content draws is composited with the opacity at once, so overlapping parts
+/// of the content don't show through each other, see [`ViewState::set_opacity`]. Combined
+/// with [`transition`](super::transition) this fades content in and out.
+///
+/// [`ViewState::set_opacity`]: crate::view::ViewState::set_opacity
+#[derive(Rebuild)]
+pub struct Opacity<V> {
+    /// The content.
+    pub content: Pod<V>,
+
+    /// The opacity, in the range `0.0..=1.0`.
+    #[rebuild(draw)]
+    pub opacity: f32,
+}
+
+impl<V> Opacity<V> {
+    /// Create a new [`Opacity`] view.
+    pub fn new(opacity: f32, content: V) -> Self {
+        Self {
+            content: Pod::new(content),
+            opacity,
+        }
+    }
+}
+
+impl<T, V: View<T>> View<T> for Opacity<V> {
+    type State = State<T, V>;
+
+    fn build(&mut self, cx: &mut BuildCx, data: &mut T) -> Self::State {
+        let mut state = self.content.build(cx, data);
+        state.set_opacity(self.opacity);
+        state
+    }
+
+    fn rebuild(&mut self, state: &mut Self::State, cx: &mut RebuildCx, data: &mut T, old: &Self) {
+        Rebuild::rebuild(self, cx, old);
+        state.set_opacity(self.opacity);
+
+        self.content.rebuild(state, cx, data, &old.content);
+    }
+
+    fn event(
+        &mut self,
+        state: &mut Self::State,
+        cx: &mut EventCx,
+        data: &mut T,
+        event: &Event,
+    ) -> bool {
+        self.content.event(state, cx, data, event)
+    }
+
+    fn layout(
+        &mut self,
+        state: &mut Self::State,
+        cx: &mut LayoutCx,
+        data: &mut T,
+        space: Space,
+    ) -> Size {
+        self.content.layout(state, cx, data, space)
+    }
+
+    fn draw(&mut self, state: &mut Self::State, cx: &mut DrawCx, data: &mut T) {
+        self.content.draw(state, cx, data);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        canvas::{BlendMode, Color, Primitive},
+        layout::{Point, Rect},
+        views::{painter, testing::ViewTester},
+    };
+
+    use super::*;
+
+    #[test]
+    fn half_opacity_red_over_white_is_pink() {
+        let mut view = opacity(
+            0.5,
+            painter(|cx, _| {
+                let rect = Rect::min_size(Point::ZERO, Size::all(10.0));
+                cx.fill_rect(rect, Color::RED);
+            }),
+        );
+
+        let mut tester = ViewTester::new(&mut view, &mut ());
+        tester.layout(&mut view, &mut (), Space::UNBOUNDED);
+        let canvas = tester.draw(&mut view, &mut ());
+
+        // the layer of the content is filtered by the opacity
+        fn find_filter(primitives: &[Primitive]) -> Option<Color> {
+            primitives.iter().find_map(|primitive| match primitive {
+                Primitive::Layer {
+                    filter: Some(filter),
+                    ..
+                } => Some(filter.apply(Color::RED)),
+                Primitive::Layer { primitives, .. } => find_filter(primitives),
+                _ => None,
+            })
+        }
+
+        let primitives = canvas.primitives().cloned().collect::<Vec<_>>();
+        let red = find_filter(&primitives).unwrap();
+        let pixel = red.blend(Color::WHITE, BlendMode::SourceOver);
+
+        assert_eq!(pixel, Color::rgb(1.0, 0.5, 0.5));
+    }
+}
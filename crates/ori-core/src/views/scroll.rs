@@ -1,10 +1,10 @@
 use ori_macro::{example, is_mobile, Build, Styled};
 
 use crate::{
-    canvas::{BorderRadius, Color},
+    canvas::{BorderRadius, Color, Mesh, Vertex},
     context::{BuildCx, DrawCx, EventCx, LayoutCx, RebuildCx},
-    event::Event,
-    layout::{Axis, Rect, Size, Space, Vector},
+    event::{Event, Key, KeyPressed, PointerTool},
+    layout::{Axis, Point, Rect, Size, Space, Vector},
     rebuild::Rebuild,
     style::{Styled, Theme},
     transition::Transition,
@@ -33,8 +33,54 @@ pub struct Scroll<V> {
     #[rebuild(layout)]
     pub axis: Axis,
 
+    /// Whether scrolling with the keyboard should be animated.
+    pub smooth: bool,
+
+    /// Whether the content should keep scrolling after a touch drag is released.
+    ///
+    /// Defaults to `true`.
+    pub fling: bool,
+
+    /// The rate at which a fling slows down, per second.
+    ///
+    /// The velocity of a fling is multiplied by `exp(-deceleration * dt)` every frame,
+    /// so the total distance travelled is the release velocity divided by this.
+    pub deceleration: f32,
+
+    /// Whether the part of a wheel scroll this view can't use, because it reached the end of
+    /// its content, is passed on to the scroll view containing it.
+    ///
+    /// Defaults to `true`.
+    pub chain: bool,
+
+    /// Whether to fade out the edges where there is more content to scroll to.
+    #[rebuild(draw)]
+    pub edge_fade: bool,
+
+    /// The length of the edge fades, see [`Scroll::edge_fade`].
+    #[rebuild(draw)]
+    #[styled(default = 24.0)]
+    pub fade_size: Styled<f32>,
+
+    /// The color the edges fade to, usually the background behind the scroll view.
+    #[rebuild(draw)]
+    #[styled(default -> Theme::BACKGROUND or Color::WHITE)]
+    pub fade_color: Styled<Color>,
+
+    /// The overlap kept between pages, when paging with the keyboard.
+    #[styled(default = 32.0)]
+    pub page_overlap: Styled<f32>,
+
+    /// The distance scrolled per line of a wheel scroll, see
+    /// [`ScrollDelta::Lines`](crate::event::ScrollDelta::Lines).
+    #[styled(default = 10.0)]
+    pub line_height: Styled<f32>,
+
     /// The transition of the scrollbar.
-    #[styled(default = Transition::ease(0.1))]
+    ///
+    /// Can also be set for the `color` with the `transition` shorthand, eg.
+    /// `scroll { transition: color 0.2s, }`.
+    #[styled(default = Transition::ease(0.1), transition_of = color)]
     pub transition: Styled<Transition>,
 
     /// The inset of the scrollbar.
@@ -69,6 +115,15 @@ impl<V> Scroll<V> {
         Self {
             content: Pod::new(content),
             axis,
+            smooth: false,
+            fling: true,
+            deceleration: 4.0,
+            chain: true,
+            edge_fade: false,
+            fade_size: Styled::style("scroll.fade-size"),
+            fade_color: Styled::style("scroll.fade-color"),
+            page_overlap: Styled::style("scroll.page-overlap"),
+            line_height: Styled::style("scroll.line-height"),
             transition: Styled::style("scroll.transition"),
             inset: Styled::style("scroll.inset"),
             width: Styled::style("scroll.width"),
@@ -117,18 +172,96 @@ impl<V> Scroll<V> {
         )
     }
 
+    // a gradient over the edge at the start or the end of the axis, `amount` is how much of
+    // the content is hidden past the edge
+    fn edge_fade_mesh(&self, style: &ScrollStyle, rect: Rect, start: bool, amount: f32) -> Mesh {
+        let length = style.fade_size.min(self.axis.major(rect.size()) / 2.0);
+
+        // the fade grows in as the content scrolls past the edge
+        let edge_color = style.fade_color.fade(f32::min(amount / length, 1.0));
+        let inner_color = style.fade_color.fade(0.0);
+
+        let (major_min, minor_min) = self.axis.unpack(rect.min);
+        let (major_max, minor_max) = self.axis.unpack(rect.max);
+
+        let (edge, inner) = match start {
+            true => (major_min, major_min + length),
+            false => (major_max, major_max - length),
+        };
+
+        let vertex = |major: f32, minor: f32, color: Color| {
+            Vertex::new(self.axis.pack::<Point>(major, minor), color)
+        };
+
+        let a = vertex(edge, minor_min, edge_color);
+        let b = vertex(edge, minor_max, edge_color);
+        let c = vertex(inner, minor_max, inner_color);
+        let d = vertex(inner, minor_min, inner_color);
+
+        let mut mesh = Mesh::triangle(a, b, c);
+        mesh.push_triangle(a, c, d);
+        mesh
+    }
+
+    // split a wheel delta into the part along the axis, the direction of that part, and the
+    // rest, a horizontal scroll view also scrolls with a vertical wheel
+    fn split_wheel_delta(&self, delta: Vector) -> (f32, Vector, Vector) {
+        match self.axis {
+            Axis::Vertical => (delta.y, Vector::Y, Vector::new(delta.x, 0.0)),
+            Axis::Horizontal if delta.x != 0.0 => (delta.x, Vector::X, Vector::new(0.0, delta.y)),
+            Axis::Horizontal => (delta.y, Vector::Y, Vector::ZERO),
+        }
+    }
+
     fn overflow(&self, content: Size, size: Size) -> f32 {
         self.axis.major(content - size).max(0.0)
     }
+
+    // get the scroll offset a key moves to, if any
+    fn key_target(
+        &self,
+        style: &ScrollStyle,
+        event: &KeyPressed,
+        scroll: f32,
+        size: Size,
+        overflow: f32,
+    ) -> Option<f32> {
+        let page = (self.axis.major(size) - style.page_overlap).max(0.0);
+
+        let target = if event.is_key(Key::PageDown) {
+            scroll + page
+        } else if event.is_key(Key::PageUp) {
+            scroll - page
+        } else if event.is_key(Key::Home) {
+            0.0
+        } else if event.is_key(Key::End) {
+            overflow
+        } else {
+            return None;
+        };
+
+        Some(target.clamp(0.0, overflow))
+    }
 }
 
+// flings slower than this, in pixels per second, are stopped
+const MIN_FLING_VELOCITY: f32 = 10.0;
+
+// the part of a wheel delta, in pixels, left over by a nested scroll view, see `Scroll::chain`
+struct ScrollRemainder(Vector);
+
 #[doc(hidden)]
 pub struct ScrollState {
     style: ScrollStyle,
     dragging: bool,
     scrollbar_hovered: bool,
     scroll: f32,
+    target: Option<f32>,
     t: f32,
+
+    // the distance dragged since the last frame, and the velocity it was dragged with
+    drag_delta: f32,
+    velocity: f32,
 }
 
 impl<T, V: View<T>> View<T> for Scroll<V> {
@@ -142,7 +275,10 @@ impl<T, V: View<T>> View<T> for Scroll<V> {
             dragging: false,
             scrollbar_hovered: false,
             scroll: 0.0,
+            target: None,
             t: 0.0,
+            drag_delta: 0.0,
+            velocity: 0.0,
         };
 
         let content = self.content.build(cx, data);
@@ -187,6 +323,7 @@ impl<T, V: View<T>> View<T> for Scroll<V> {
                 let scroll_fract = (local_major - scroll_start) / (scroll_end - scroll_start);
                 state.scroll = overflow * scroll_fract;
                 state.scroll = state.scroll.clamp(0.0, overflow);
+                state.target = None;
 
                 content.translate(self.axis.pack(-state.scroll, 0.0));
 
@@ -194,6 +331,11 @@ impl<T, V: View<T>> View<T> for Scroll<V> {
             } else if state.dragging {
                 state.scroll -= self.axis.major(e.delta);
                 state.scroll = state.scroll.clamp(0.0, overflow);
+                state.target = None;
+
+                // the velocity is sampled every frame, from the distance dragged since the last
+                state.drag_delta -= self.axis.major(e.delta);
+                cx.animate();
                 cx.draw();
             }
         }
@@ -212,16 +354,38 @@ impl<T, V: View<T>> View<T> for Scroll<V> {
             cx.draw();
         }
 
+        // the remainder is set by nested scroll views that handle the event
+        if matches!(event, Event::PointerScrolled(_)) {
+            cx.remove_context::<ScrollRemainder>();
+        }
+
         // propagate event
         handled = self.content.event_maybe(handled, content, cx, data, event);
 
-        if is_mobile!() && !handled {
-            if matches!(event, Event::PointerPressed(_)) && cx.has_hovered() {
-                state.dragging = true;
+        // a new press interrupts a fling
+        if matches!(event, Event::PointerPressed(_)) && cx.has_hovered() {
+            state.velocity = 0.0;
+        }
+
+        if !handled {
+            // touch pointers drag the content, on mobile every pointer does
+            if let Event::PointerPressed(e) = event {
+                let drags = is_mobile!() || e.tool == PointerTool::Touch;
+
+                if drags && cx.has_hovered() {
+                    state.dragging = true;
+                    state.drag_delta = 0.0;
+                }
             }
 
             if matches!(event, Event::PointerReleased(_)) && state.dragging {
                 state.dragging = false;
+
+                if self.fling && state.velocity.abs() > MIN_FLING_VELOCITY {
+                    cx.animate();
+                } else {
+                    state.velocity = 0.0;
+                }
             }
         }
 
@@ -236,18 +400,96 @@ impl<T, V: View<T>> View<T> for Scroll<V> {
                 cx.animate();
                 cx.draw();
             }
+
+            if let Some(target) = state.target {
+                state.scroll += (target - state.scroll) * (1.0 - f32::exp(-*dt * 20.0));
+
+                if (target - state.scroll).abs() < 0.5 {
+                    state.scroll = target;
+                    state.target = None;
+                } else {
+                    cx.animate();
+                }
+
+                content.translate(self.axis.pack(-state.scroll, 0.0));
+                cx.draw();
+            }
+
+            if state.dragging && *dt > 0.0 {
+                // smooth the velocity, pointer events don't arrive at an even rate
+                let velocity = state.drag_delta / *dt;
+                state.velocity = state.velocity * 0.2 + velocity * 0.8;
+                state.drag_delta = 0.0;
+            } else if !state.dragging && state.velocity != 0.0 {
+                state.scroll += state.velocity * *dt;
+                state.velocity *= f32::exp(-self.deceleration * *dt);
+
+                // stop at the bounds of the content
+                if state.scroll <= 0.0 || state.scroll >= overflow {
+                    state.velocity = 0.0;
+                }
+
+                if state.velocity.abs() < MIN_FLING_VELOCITY {
+                    state.velocity = 0.0;
+                } else {
+                    cx.animate();
+                }
+
+                state.scroll = state.scroll.clamp(0.0, overflow);
+                content.translate(self.axis.pack(-state.scroll, 0.0));
+                cx.draw();
+            }
+        }
+
+        // handle keyboard paging, when the scroll view or a descendant is focused
+        if let Event::KeyPressed(e) = event {
+            let scroll = state.target.unwrap_or(state.scroll);
+            let target = self.key_target(&state.style, e, scroll, cx.size(), overflow);
+
+            if let (Some(target), false, true) = (target, handled, cx.has_focused()) {
+                handled = true;
+                state.velocity = 0.0;
+
+                if self.smooth {
+                    state.target = Some(target);
+                    cx.animate();
+                } else {
+                    state.scroll = target;
+                    content.translate(self.axis.pack(-state.scroll, 0.0));
+                    cx.draw();
+                }
+            }
         }
 
         if let Event::PointerScrolled(e) = event {
-            if on && !handled {
+            let remainder = cx.remove_context::<ScrollRemainder>();
+
+            // a nested scroll view may have left part of the delta for us
+            let delta = match handled {
+                true => remainder.map(|ScrollRemainder(delta)| delta),
+                false => Some(e.delta.to_pixels(state.style.line_height)),
+            };
+
+            let (major, direction, rest) = self.split_wheel_delta(delta.unwrap_or_default());
+
+            // a delta across the axis passes straight through, eg. to a horizontal parent
+            if on && major != 0.0 {
                 handled = true;
 
-                state.scroll -= e.delta.y * 10.0;
+                let scroll = state.scroll;
+                state.scroll -= major;
                 state.scroll = state.scroll.clamp(0.0, overflow);
+                state.target = None;
+                state.velocity = 0.0;
 
                 content.translate(self.axis.pack(-state.scroll, 0.0));
 
                 cx.draw();
+
+                if self.chain {
+                    let unused = major - (scroll - state.scroll);
+                    cx.insert_context(ScrollRemainder(rest + direction * unused));
+                }
             }
         }
 
@@ -296,6 +538,19 @@ impl<T, V: View<T>> View<T> for Scroll<V> {
             return;
         }
 
+        if self.edge_fade {
+            if state.scroll > 0.0 {
+                let mesh = self.edge_fade_mesh(&state.style, cx.rect(), true, state.scroll);
+                cx.mesh(mesh);
+            }
+
+            if state.scroll < overflow {
+                let amount = overflow - state.scroll;
+                let mesh = self.edge_fade_mesh(&state.style, cx.rect(), false, amount);
+                cx.mesh(mesh);
+            }
+        }
+
         let track_color = state.style.color.fade(0.7);
         let knob_color = state.style.knob_color.fade(0.9);
 
@@ -316,3 +571,175 @@ impl<T, V: View<T>> View<T> for Scroll<V> {
         );
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        canvas::{Canvas, Primitive},
+        event::{Modifiers, PointerButton, ScrollDelta},
+        view::ViewId,
+        views::{size, testing::ViewTester, vstack, with_id},
+    };
+
+    use super::*;
+
+    fn key_pressed(key: Key) -> Event {
+        Event::KeyPressed(KeyPressed {
+            key,
+            code: None,
+            text: None,
+            modifiers: Modifiers::default(),
+            repeat: false,
+        })
+    }
+
+    #[test]
+    fn page_down_moves_by_viewport() {
+        let mut view = vscroll(size(Size::new(100.0, 1000.0), ())).page_overlap(32.0);
+
+        let mut tester = ViewTester::new(&mut view, &mut ());
+        tester.layout(&mut view, &mut (), Space::new(Size::ZERO, Size::all(200.0)));
+        tester.view_state.set_focused(true);
+
+        tester.event(&mut view, &mut (), &key_pressed(Key::PageDown));
+        assert_eq!(tester.state.0.scroll, 200.0 - 32.0);
+
+        tester.event(&mut view, &mut (), &key_pressed(Key::End));
+        assert_eq!(tester.state.0.scroll, 800.0);
+
+        tester.event(&mut view, &mut (), &key_pressed(Key::PageDown));
+        assert_eq!(tester.state.0.scroll, 800.0);
+
+        tester.event(&mut view, &mut (), &key_pressed(Key::Home));
+        assert_eq!(tester.state.0.scroll, 0.0);
+    }
+
+    // the edges of the view that have a fade drawn over them
+    fn edge_fades(canvas: &Canvas) -> (bool, bool) {
+        let meshes: Vec<_> = (canvas.primitives())
+            .filter_map(|primitive| match primitive {
+                Primitive::Mesh { mesh } => Some(mesh.bounds()),
+                _ => None,
+            })
+            .collect();
+
+        let top = meshes.iter().any(|bounds| bounds.min.y == 0.0);
+        let bottom = meshes.iter().any(|bounds| bounds.max.y == 200.0);
+
+        (top, bottom)
+    }
+
+    #[test]
+    fn edge_fades_follow_the_scroll() {
+        let mut view = vscroll(size(Size::new(100.0, 1000.0), ())).edge_fade(true);
+
+        let mut tester = ViewTester::new(&mut view, &mut ());
+        tester.layout(&mut view, &mut (), Space::new(Size::ZERO, Size::all(200.0)));
+        tester.view_state.set_focused(true);
+
+        let canvas = tester.draw(&mut view, &mut ());
+        assert_eq!(edge_fades(&canvas), (false, true));
+
+        tester.event(&mut view, &mut (), &key_pressed(Key::PageDown));
+        let canvas = tester.draw(&mut view, &mut ());
+        assert_eq!(edge_fades(&canvas), (true, true));
+
+        tester.event(&mut view, &mut (), &key_pressed(Key::End));
+        let canvas = tester.draw(&mut view, &mut ());
+        assert_eq!(edge_fades(&canvas), (true, false));
+    }
+
+    #[test]
+    fn fling_decelerates_to_a_stop() {
+        let mut view = vscroll(size(Size::new(100.0, 1000.0), ()));
+
+        let mut tester = ViewTester::new(&mut view, &mut ());
+        tester.layout(&mut view, &mut (), Space::new(Size::ZERO, Size::all(200.0)));
+
+        tester.view_state.set_hovered(true);
+        tester.tool = PointerTool::Touch;
+
+        // drag upwards at 1200 pixels per second, 20 pixels every frame
+        let mut position = Point::new(50.0, 150.0);
+        tester.press(&mut view, &mut (), position, PointerButton::Primary);
+
+        for _ in 0..4 {
+            position.y -= 20.0;
+            tester.move_to(&mut view, &mut (), position);
+            tester.event(&mut view, &mut (), &Event::Animate(1.0 / 60.0));
+        }
+
+        assert_eq!(tester.state.0.scroll, 80.0);
+        tester.release(&mut view, &mut (), position, PointerButton::Primary);
+
+        let released = tester.state.0.velocity;
+        assert!((released - 1200.0).abs() < 20.0, "{}", released);
+
+        let mut offsets = Vec::new();
+
+        while tester.state.0.velocity != 0.0 && offsets.len() < 1000 {
+            let scroll = tester.state.0.scroll;
+            tester.event(&mut view, &mut (), &Event::Animate(1.0 / 60.0));
+            offsets.push(tester.state.0.scroll - scroll);
+        }
+
+        assert!(offsets.len() < 1000, "the fling never stopped");
+        assert!(offsets.windows(2).all(|w| w[1] < w[0]));
+
+        // the distance of an exponential fling is the velocity divided by the deceleration
+        let distance: f32 = offsets.iter().sum();
+        assert!((distance - released / view.deceleration).abs() < 10.0);
+    }
+
+    #[test]
+    fn wheel_lines_are_scaled_by_the_line_height() {
+        let mut view = vscroll(size(Size::new(100.0, 1000.0), ())).line_height(20.0);
+
+        let mut tester = ViewTester::new(&mut view, &mut ());
+        tester.layout(&mut view, &mut (), Space::new(Size::ZERO, Size::all(200.0)));
+        tester.view_state.set_hovered(true);
+
+        let position = Point::new(50.0, 50.0);
+
+        // a mouse wheel click scrolls a line
+        tester.scroll(&mut view, &mut (), position, Vector::NEG_Y);
+        assert_eq!(tester.state.0.scroll, 20.0);
+
+        // a trackpad scrolls exactly as far as it moved
+        let pixels = ScrollDelta::Pixels(Vector::new(0.0, -5.0));
+        tester.scroll(&mut view, &mut (), position, pixels);
+        assert_eq!(tester.state.0.scroll, 25.0);
+    }
+
+    #[test]
+    fn wheel_overflow_chains_to_the_outer_scroll() {
+        let inner = ViewId::new();
+
+        // a 200 pixel tall list with 100 pixels to scroll, inside 800 pixels more content
+        let mut view = vscroll(vstack![
+            with_id(
+                inner,
+                size([100.0, 200.0], vscroll(size([100.0, 300.0], ())))
+            ),
+            size([100.0, 800.0], ()),
+        ]);
+
+        let mut tester = ViewTester::new(&mut view, &mut ());
+        tester.layout(&mut view, &mut (), Space::new(Size::ZERO, Size::all(200.0)));
+
+        let position = Point::new(50.0, 50.0);
+        tester.hover(inner, position);
+
+        // the inner list uses the whole delta
+        tester.scroll(&mut view, &mut (), position, Vector::new(0.0, -8.0));
+        assert_eq!(tester.state.0.scroll, 0.0);
+
+        // the inner list reaches its end after 20 pixels, the outer scroll moves the rest
+        tester.scroll(&mut view, &mut (), position, Vector::new(0.0, -3.0));
+        assert_eq!(tester.state.0.scroll, 10.0);
+
+        // a horizontal delta isn't used by either
+        let horizontal = Vector::new(-3.0, 0.0);
+        assert!(!tester.scroll(&mut view, &mut (), position, horizontal));
+    }
+}
@@ -143,6 +143,7 @@ impl<T> View<T> for Slider<T> {
                 }
 
                 cx.set_active(true);
+                cx.capture_pointer(e.id);
 
                 true
             }
@@ -160,8 +161,9 @@ impl<T> View<T> for Slider<T> {
 
                 false
             }
-            Event::PointerReleased(_) if cx.is_active() => {
+            Event::PointerReleased(e) if cx.is_active() => {
                 cx.set_active(false);
+                cx.release_pointer_capture(e.id);
 
                 true
             }
@@ -207,3 +209,47 @@ impl<T> View<T> for Slider<T> {
         });
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        event::PointerButton,
+        hstack,
+        layout::Point,
+        view::ViewId,
+        views::{on_event, painter, size, testing::ViewTester, with_id},
+    };
+
+    use super::*;
+
+    #[test]
+    fn captured_slider_follows_moves_outside_its_rect() {
+        let id = ViewId::new();
+
+        // the slider is 100 pixels long, from 50 to 150
+        let mut view = hstack![
+            // handles every move, so views after it only see moves of pointers they captured
+            on_event(size(50.0, painter(|_, _| {})), |_, _, event| {
+                matches!(event, Event::PointerMoved(_))
+            }),
+            with_id(
+                id,
+                slider(0.0).on_input(|_, value: &mut f32, new| *value = new)
+            ),
+        ];
+        let mut value = 0.0;
+
+        let mut tester = ViewTester::new(&mut view, &mut value);
+        tester.layout(&mut view, &mut value, Space::UNBOUNDED);
+
+        let pressed = Point::new(60.0, 5.0);
+        tester.hover(id, pressed);
+
+        tester.press(&mut view, &mut value, pressed, PointerButton::Primary);
+        assert!((value - 0.1).abs() < 1e-6);
+
+        // past the end of the slider, and below it, over nothing
+        tester.move_to(&mut view, &mut value, Point::new(250.0, 40.0));
+        assert_eq!(value, 1.0);
+    }
+}
@@ -0,0 +1,302 @@
+use ori_macro::{Build, Styled};
+
+use crate::{
+    canvas::Color,
+    context::{BuildCx, DrawCx, EventCx, LayoutCx, RebuildCx},
+    event::Event,
+    layout::{Axis, Rect, Size, Space},
+    rebuild::Rebuild,
+    style::{Styled, Theme},
+    view::{Pod, State, View},
+    window::Cursor,
+};
+
+/// Create a new horizontal [`Split`], with `left` and `right` panes.
+pub fn hsplit<A, B>(left: A, right: B) -> Split<A, B> {
+    Split::new(Axis::Horizontal, left, right)
+}
+
+/// Create a new vertical [`Split`], with `top` and `bottom` panes.
+pub fn vsplit<A, B>(top: A, bottom: B) -> Split<A, B> {
+    Split::new(Axis::Vertical, top, bottom)
+}
+
+/// A view that splits its space between two panes, with a divider that can be dragged to
+/// resize them.
+///
+/// The position of the divider is stored in the state of the view, so it's kept when the view
+/// is rebuilt. Changing [`Split::ratio`] moves the divider back to the new ratio.
+///
+/// Can be styled with a [`SplitStyle`].
+#[derive(Styled, Build, Rebuild)]
+pub struct Split<A, B> {
+    /// The first pane, on the left or at the top.
+    #[build(ignore)]
+    pub first: Pod<A>,
+
+    /// The second pane, on the right or at the bottom.
+    #[build(ignore)]
+    pub second: Pod<B>,
+
+    /// The axis the panes are placed along.
+    #[rebuild(layout)]
+    pub axis: Axis,
+
+    /// The initial share of the space given to the first pane, in the range `0.0..=1.0`.
+    pub ratio: f32,
+
+    /// The minimum size of each pane, the divider can't be dragged further.
+    #[rebuild(layout)]
+    #[styled(default = 50.0)]
+    pub min_size: Styled<f32>,
+
+    /// The width of the divider.
+    #[rebuild(layout)]
+    #[styled(default = 6.0)]
+    pub divider_width: Styled<f32>,
+
+    /// The color of the divider.
+    #[rebuild(draw)]
+    #[styled(default -> Theme::OUTLINE or Color::grayscale(0.8))]
+    pub divider_color: Styled<Color>,
+}
+
+impl<A, B> Split<A, B> {
+    /// Create a new [`Split`].
+    pub fn new(axis: Axis, first: A, second: B) -> Self {
+        Self {
+            first: Pod::new(first),
+            second: Pod::new(second),
+            axis,
+            ratio: 0.5,
+            min_size: Styled::style("split.min-size"),
+            divider_width: Styled::style("split.divider-width"),
+            divider_color: Styled::style("split.divider-color"),
+        }
+    }
+
+    fn cursor(&self) -> Cursor {
+        match self.axis {
+            Axis::Horizontal => Cursor::ColResize,
+            Axis::Vertical => Cursor::RowResize,
+        }
+    }
+
+    // the rect of the divider, for a split of `size`
+    fn divider_rect(&self, state: &SplitState, size: Size) -> Rect {
+        let (_, cross) = self.axis.unpack(size);
+        let start = self.axis.pack(state.first_length, 0.0);
+        let size = self.axis.pack(state.style.divider_width, cross);
+
+        Rect::min_size(start, size)
+    }
+}
+
+// clamp `ratio` so neither pane is smaller than `min_size`, if there is room for both
+fn clamp_ratio(ratio: f32, available: f32, min_size: f32) -> f32 {
+    if available <= 0.0 {
+        return ratio.clamp(0.0, 1.0);
+    }
+
+    let min = f32::min(min_size / available, 0.5);
+    ratio.clamp(min, 1.0 - min)
+}
+
+#[doc(hidden)]
+pub struct SplitState {
+    style: SplitStyle,
+    ratio: f32,
+
+    // the length of the space shared by the panes, and the length of the first pane
+    available: f32,
+    first_length: f32,
+
+    // the offset from the start of the divider to the pointer while dragging
+    grab: Option<f32>,
+}
+
+impl<T, A: View<T>, B: View<T>> View<T> for Split<A, B> {
+    type State = (SplitState, State<T, A>, State<T, B>);
+
+    fn build(&mut self, cx: &mut BuildCx, data: &mut T) -> Self::State {
+        cx.set_class("split");
+
+        let state = SplitState {
+            style: SplitStyle::styled(self, cx.styles()),
+            ratio: self.ratio,
+            available: 0.0,
+            first_length: 0.0,
+            grab: None,
+        };
+
+        let first = self.first.build(cx, data);
+        let second = self.second.build(cx, data);
+
+        (state, first, second)
+    }
+
+    fn rebuild(
+        &mut self,
+        (state, first, second): &mut Self::State,
+        cx: &mut RebuildCx,
+        data: &mut T,
+        old: &Self,
+    ) {
+        Rebuild::rebuild(self, cx, old);
+        state.style.rebuild(self, cx);
+
+        if self.ratio != old.ratio {
+            state.ratio = self.ratio;
+            cx.layout();
+        }
+
+        self.first.rebuild(first, cx, data, &old.first);
+        self.second.rebuild(second, cx, data, &old.second);
+    }
+
+    fn event(
+        &mut self,
+        (state, first, second): &mut Self::State,
+        cx: &mut EventCx,
+        data: &mut T,
+        event: &Event,
+    ) -> bool {
+        let mut handled = self.first.event(first, cx, data, event);
+        handled |= self.second.event(second, cx, data, event);
+
+        // the divider is the only part of the split that is hoverable itself
+        if cx.is_hovered() || cx.is_active() {
+            cx.set_cursor(Some(self.cursor()));
+        } else {
+            cx.set_cursor(None);
+        }
+
+        match event {
+            Event::PointerPressed(e) if cx.is_hovered() && !handled => {
+                let local = self.axis.major(cx.local(e.position));
+                state.grab = Some(local - state.first_length);
+                cx.set_active(true);
+
+                true
+            }
+            Event::PointerMoved(e) => {
+                let Some(grab) = state.grab else {
+                    return handled;
+                };
+
+                let local = self.axis.major(cx.local(e.position));
+                let ratio = (local - grab) / state.available;
+                let ratio = clamp_ratio(ratio, state.available, state.style.min_size);
+
+                if ratio.is_finite() && ratio != state.ratio {
+                    state.ratio = ratio;
+                    cx.layout();
+                }
+
+                true
+            }
+            Event::PointerReleased(_) if state.grab.is_some() => {
+                state.grab = None;
+                cx.set_active(false);
+
+                true
+            }
+            _ => handled,
+        }
+    }
+
+    fn layout(
+        &mut self,
+        (state, first, second): &mut Self::State,
+        cx: &mut LayoutCx,
+        data: &mut T,
+        space: Space,
+    ) -> Size {
+        let (min_length, min_cross) = self.axis.unpack(space.min);
+        let (max_length, max_cross) = self.axis.unpack(space.max);
+
+        let length = match max_length.is_finite() {
+            true => max_length,
+            false => min_length,
+        };
+
+        let divider = state.style.divider_width;
+        let available = f32::max(length - divider, 0.0);
+        let ratio = clamp_ratio(state.ratio, available, state.style.min_size);
+
+        let first_length = (available * ratio).round();
+        let second_length = available - first_length;
+
+        state.available = available;
+        state.first_length = first_length;
+
+        let pane_space = |length| {
+            let min = self.axis.pack(length, min_cross);
+            let max = self.axis.pack(length, max_cross);
+            Space::new(min, max)
+        };
+
+        let first_size = self.first.layout(first, cx, data, pane_space(first_length));
+        let second_size = (self.second).layout(second, cx, data, pane_space(second_length));
+
+        second.translate(self.axis.pack(first_length + divider, 0.0));
+
+        let cross = f32::max(self.axis.minor(first_size), self.axis.minor(second_size));
+
+        space.fit(self.axis.pack(length, cross))
+    }
+
+    fn draw(&mut self, (state, first, second): &mut Self::State, cx: &mut DrawCx, data: &mut T) {
+        self.first.draw(first, cx, data);
+        self.second.draw(second, cx, data);
+
+        let divider = self.divider_rect(state, cx.size());
+
+        cx.hoverable(|cx| {
+            cx.fill_rect(divider, state.style.divider_color);
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        event::PointerButton,
+        layout::{Point, FILL},
+        views::{size, testing::ViewTester, Constrain},
+    };
+
+    use super::*;
+
+    #[test]
+    fn dragging_the_divider_resizes_the_panes() {
+        let mut view = hsplit(size(FILL, ()), size(FILL, ()))
+            .min_size(50.0)
+            .divider_width(6.0);
+        let space = Space::new(Size::ZERO, Size::new(406.0, 100.0));
+
+        let mut tester = ViewTester::new(&mut view, &mut ());
+        tester.layout(&mut view, &mut (), space);
+
+        let widths = |tester: &ViewTester<(), Split<Constrain<()>, Constrain<()>>>| {
+            let (_, first, second) = &tester.state;
+            (first.size().width, second.size().width)
+        };
+
+        assert_eq!(widths(&tester), (200.0, 200.0));
+
+        let pressed = Point::new(203.0, 50.0);
+
+        tester.view_state.set_hovered(true);
+        tester.press(&mut view, &mut (), pressed, PointerButton::Primary);
+
+        tester.move_to(&mut view, &mut (), Point::new(243.0, 50.0));
+        tester.layout(&mut view, &mut (), space);
+        assert_eq!(widths(&tester), (240.0, 160.0));
+
+        // the second pane can't be made smaller than the minimum size
+        tester.move_to(&mut view, &mut (), Point::new(400.0, 50.0));
+        tester.layout(&mut view, &mut (), space);
+        assert_eq!(widths(&tester), (350.0, 50.0));
+    }
+}
@@ -18,16 +18,16 @@ use super::Flex;
 /// Create a horizontal [`Stack`].
 #[macro_export]
 macro_rules! hstack {
-    ($($child:expr),* $(,)?) => {
-        $crate::views::hstack(($($child,)*))
+    ($($child:tt)*) => {
+        $crate::views::hstack($crate::children![$($child)*])
     };
 }
 
 /// Create a vertical [`Stack`].
 #[macro_export]
 macro_rules! vstack {
-    ($($child:expr),* $(,)?) => {
-        $crate::views::vstack(($($child,)*))
+    ($($child:tt)*) => {
+        $crate::views::vstack($crate::children![$($child)*])
     };
 }
 
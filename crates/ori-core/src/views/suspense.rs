@@ -15,7 +15,19 @@ where
     Suspense::new(future)
 }
 
+/// Create a new [`TrySuspense`] view, from a function creating a future that can fail.
+///
+/// The view to display when the future fails is set with [`TrySuspense::error`].
+pub fn try_suspense<F, Fut>(future: F) -> TrySuspense<(), F, ()>
+where
+    F: FnMut() -> Fut,
+{
+    TrySuspense::new(future)
+}
+
 /// A view that suspends rendering while a future is pending.
+///
+/// See [`TrySuspense`] for futures that can fail, and should be retried.
 pub struct Suspense<V, F> {
     fallback: Pod<V>,
     future: Option<F>,
@@ -57,7 +69,7 @@ where
     future_state: Option<State<T, F::Output>>,
 }
 
-#[derive(Clone, Copy, Default, PartialEq)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
 struct SuspenseId(usize);
 
 struct SuspenseCompleted<T> {
@@ -74,7 +86,8 @@ where
     type State = SuspenseState<T, F, V>;
 
     fn build(&mut self, cx: &mut BuildCx, data: &mut T) -> Self::State {
-        let id = spawn(&mut self.future, cx);
+        let future = self.future.take().expect("future not taken");
+        let id = spawn(future, cx);
 
         let fallback_state = self.fallback.build(cx, data);
 
@@ -87,7 +100,8 @@ where
     }
 
     fn rebuild(&mut self, state: &mut Self::State, cx: &mut RebuildCx, data: &mut T, old: &Self) {
-        state.id = spawn(&mut self.future, cx);
+        let future = self.future.take().expect("future not taken");
+        state.id = spawn(future, cx);
 
         if let (Some(fallback_state), None) = (&mut state.fallback_state, &mut state.future_state) {
             (self.fallback).rebuild(fallback_state, cx, data, &old.fallback);
@@ -155,13 +169,11 @@ where
     }
 }
 
-fn spawn<F>(future: &mut Option<F>, cx: &mut BaseCx) -> SuspenseId
+fn spawn<F>(future: F, cx: &mut BaseCx) -> SuspenseId
 where
     F: Future + Send + 'static,
     F::Output: Send,
 {
-    let future = future.take().expect("future not taken");
-
     let id = *cx.context_or_default::<SuspenseId>();
     cx.context_or_default::<SuspenseId>().0 += 1;
 
@@ -174,3 +186,310 @@ where
 
     id
 }
+
+/// A view that suspends rendering while a future that can fail is pending.
+///
+/// The fallback is shown while the future is pending, the view it resolves to on `Ok`, and
+/// the [`error`](TrySuspense::error) view on `Err`. The error view is given a [`Retry`], which
+/// creates the future again and shows the fallback until it resolves, eg. from a button.
+///
+/// The future is only created when the view is built and on [`Retry`], a rebuild keeps the
+/// result, and rebuilds the error view.
+///
+/// ```no_run
+/// # use ori_core::{view::View, views::*};
+/// # async fn fetch() -> Result<Text, String> { todo!() }
+/// fn ui() -> impl View {
+///     try_suspense(fetch)
+///         .fallback(text("Loading..."))
+///         .error(|err, retry| {
+///             on_click(button(text!("{}, try again", err)), move |cx, _| retry.retry(cx))
+///         })
+/// }
+/// ```
+pub struct TrySuspense<P, F, E> {
+    fallback: Pod<P>,
+    future: F,
+    error: E,
+}
+
+impl<F, Fut> TrySuspense<(), F, ()>
+where
+    F: FnMut() -> Fut,
+{
+    /// Create a new [`TrySuspense`] view.
+    pub fn new(future: F) -> Self {
+        Self {
+            fallback: Pod::new(()),
+            future,
+            error: (),
+        }
+    }
+}
+
+impl<P, F, E> TrySuspense<P, F, E> {
+    /// Set the fallback view to display while the future is pending.
+    pub fn fallback<V>(self, fallback: V) -> TrySuspense<V, F, E> {
+        TrySuspense {
+            fallback: Pod::new(fallback),
+            future: self.future,
+            error: self.error,
+        }
+    }
+
+    /// Set the view to display when the future fails.
+    pub fn error<G, Fut, V, Err, EV>(self, error: G) -> TrySuspense<P, F, G>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<V, Err>>,
+        G: FnMut(&Err, Retry) -> EV,
+    {
+        TrySuspense {
+            fallback: self.fallback,
+            future: self.future,
+            error,
+        }
+    }
+}
+
+/// Retries a [`TrySuspense`] that failed, see [`TrySuspense::error`].
+#[derive(Clone, Copy, Debug)]
+pub struct Retry {
+    id: SuspenseId,
+}
+
+impl Retry {
+    /// Create the future of the suspense again, showing the fallback until it resolves.
+    pub fn retry(self, cx: &mut BaseCx) {
+        cx.cmd(self);
+    }
+}
+
+#[doc(hidden)]
+pub struct TrySuspenseState<T, P, V, Err, E>
+where
+    P: View<T>,
+    V: View<T>,
+    E: View<T>,
+{
+    id: SuspenseId,
+    fallback_state: Option<State<T, P>>,
+    error: Option<Err>,
+    result: Option<Pod<Result<V, E>>>,
+    result_state: Option<State<T, Result<V, E>>>,
+}
+
+impl<T, P, F, Fut, V, Err, E, EV> View<T> for TrySuspense<P, F, E>
+where
+    P: View<T>,
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<V, Err>> + Send + 'static,
+    V: View<T> + Send + 'static,
+    Err: Send + 'static,
+    E: FnMut(&Err, Retry) -> EV,
+    EV: View<T>,
+{
+    type State = TrySuspenseState<T, P, V, Err, EV>;
+
+    fn build(&mut self, cx: &mut BuildCx, data: &mut T) -> Self::State {
+        let id = spawn((self.future)(), cx);
+
+        let fallback_state = self.fallback.build(cx, data);
+
+        TrySuspenseState {
+            id,
+            fallback_state: Some(fallback_state),
+            error: None,
+            result: None,
+            result_state: None,
+        }
+    }
+
+    fn rebuild(&mut self, state: &mut Self::State, cx: &mut RebuildCx, data: &mut T, old: &Self) {
+        if let (Some(fallback_state), None) = (&mut state.fallback_state, &mut state.result_state) {
+            (self.fallback).rebuild(fallback_state, cx, data, &old.fallback);
+        }
+
+        // the view of a successful result was created by the future, so only the error view
+        // can be created again
+        if let (Some(err), Some(result), Some(result_state)) =
+            (&state.error, &mut state.result, &mut state.result_state)
+        {
+            let retry = Retry { id: state.id };
+            let mut new_result = Pod::new(Err((self.error)(err, retry)));
+            new_result.rebuild(result_state, cx, data, result);
+            *result = new_result;
+        }
+    }
+
+    fn event(
+        &mut self,
+        state: &mut Self::State,
+        cx: &mut EventCx,
+        data: &mut T,
+        event: &Event,
+    ) -> bool {
+        if let Some(completed) = event.cmd::<SuspenseCompleted<Fut::Output>>() {
+            if completed.id == state.id {
+                let retry = Retry { id: state.id };
+
+                let mut result = match completed.view.borrow_mut().take() {
+                    Some(Ok(view)) => Some(Pod::new(Ok(view))),
+                    Some(Err(err)) => {
+                        let view = (self.error)(&err, retry);
+                        state.error = Some(err);
+                        Some(Pod::new(Err(view)))
+                    }
+                    None => None,
+                };
+
+                state.result_state =
+                    (result.as_mut()).map(|r| r.build(&mut cx.as_build_cx(), data));
+                state.result = result;
+
+                state.fallback_state.take();
+
+                cx.layout();
+            }
+        }
+
+        if let Some(retry) = event.cmd::<Retry>() {
+            if retry.id == state.id {
+                state.id = spawn((self.future)(), cx);
+
+                state.error.take();
+                state.result.take();
+                state.result_state.take();
+
+                let fallback_state = self.fallback.build(&mut cx.as_build_cx(), data);
+                state.fallback_state = Some(fallback_state);
+
+                cx.layout();
+                return true;
+            }
+        }
+
+        match (
+            &mut state.fallback_state,
+            &mut state.result,
+            &mut state.result_state,
+        ) {
+            (None, Some(result), Some(result_state)) => result.event(result_state, cx, data, event),
+            (Some(fallback_state), _, _) => self.fallback.event(fallback_state, cx, data, event),
+            _ => false,
+        }
+    }
+
+    fn layout(
+        &mut self,
+        state: &mut Self::State,
+        cx: &mut LayoutCx,
+        data: &mut T,
+        space: Space,
+    ) -> Size {
+        match (
+            &mut state.fallback_state,
+            &mut state.result,
+            &mut state.result_state,
+        ) {
+            (None, Some(result), Some(result_state)) => {
+                result.layout(result_state, cx, data, space)
+            }
+            (Some(fallback_state), _, _) => self.fallback.layout(fallback_state, cx, data, space),
+            _ => Size::ZERO,
+        }
+    }
+
+    fn draw(&mut self, state: &mut Self::State, cx: &mut DrawCx, data: &mut T) {
+        match (
+            &mut state.fallback_state,
+            &mut state.result,
+            &mut state.result_state,
+        ) {
+            (None, Some(result), Some(result_state)) => result.draw(result_state, cx, data),
+            (Some(fallback_state), _, _) => self.fallback.draw(fallback_state, cx, data),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    };
+
+    use crate::views::{size, testing::ViewTester};
+
+    use super::*;
+
+    #[test]
+    fn error_view_is_shown_and_retry_runs_future_again() {
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let retries = Arc::new(Mutex::new(None));
+
+        // the first attempt fails, and the second succeeds
+        let make_view = || {
+            let attempts = attempts.clone();
+            let retries = retries.clone();
+
+            try_suspense(move || {
+                let attempt = attempts.fetch_add(1, Ordering::SeqCst);
+
+                async move {
+                    match attempt {
+                        0 => Err("offline"),
+                        _ => Ok(size(30.0, ())),
+                    }
+                }
+            })
+            .fallback(size(10.0, ()))
+            .error(move |_, retry| {
+                *retries.lock().unwrap() = Some(retry);
+                size(20.0, ())
+            })
+        };
+
+        let mut view = make_view();
+        let mut tester = ViewTester::new(&mut view, &mut ());
+
+        // the futures are ready immediately, so the completion is sent when they're spawned
+        let complete = |tester: &mut ViewTester<(), _>, view: &mut _| {
+            let command = tester.command_rx.try_recv().unwrap();
+            tester.event(view, &mut (), &Event::Command(command));
+            tester.layout(view, &mut (), Space::UNBOUNDED)
+        };
+
+        assert_eq!(
+            tester.layout(&mut view, &mut (), Space::UNBOUNDED),
+            Size::all(10.0)
+        );
+
+        assert_eq!(complete(&mut tester, &mut view), Size::all(20.0));
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+
+        // a rebuild keeps the error, and creates the error view again instead of the future
+        retries.lock().unwrap().take();
+
+        let mut new_view = make_view();
+        tester.rebuild(&mut new_view, &mut (), &view);
+        let mut view = new_view;
+
+        assert!(tester.command_rx.try_recv().is_none());
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+        assert_eq!(
+            tester.layout(&mut view, &mut (), Space::UNBOUNDED),
+            Size::all(20.0)
+        );
+
+        // retrying shows the fallback until the future resolves again
+        let retry = retries.lock().unwrap().take().unwrap();
+        tester.command_proxy.cmd(retry);
+
+        assert_eq!(complete(&mut tester, &mut view), Size::all(10.0));
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+
+        assert_eq!(complete(&mut tester, &mut view), Size::all(30.0));
+    }
+}
@@ -0,0 +1,542 @@
+use ori_macro::{Build, Styled};
+use smol_str::SmolStr;
+
+use crate::{
+    canvas::{BorderRadius, BorderWidth, Color, Curve},
+    context::{BuildCx, DrawCx, EventCx, LayoutCx, RebuildCx},
+    event::{Event, Key, PointerButton},
+    layout::{pt, Padding, Point, Rect, Size, Space, Vector},
+    rebuild::Rebuild,
+    style::{Styled, Theme},
+    text::{FontAttributes, FontFamily, Paragraph, TextAlign, TextWrap},
+    view::View,
+};
+
+/// Create a new [`Tabs`] view.
+pub fn tabs<T>(items: impl IntoIterator<Item = impl Into<SmolStr>>, selected: usize) -> Tabs<T> {
+    Tabs::new(items, selected)
+}
+
+/// A strip of tabs, that can be selected, closed and reordered by dragging them.
+///
+/// The view doesn't own the tabs, the callbacks are called with the requested change, and the
+/// items and selected tab should be updated accordingly. When the tabs don't fit, the strip
+/// can be scrolled. `Ctrl+Tab` and `Ctrl+Shift+Tab` select the next and previous tab.
+///
+/// Can be styled using the [`TabsStyle`].
+#[derive(Styled, Build, Rebuild)]
+pub struct Tabs<T> {
+    /// The labels of the tabs.
+    #[build(ignore)]
+    #[rebuild(layout)]
+    pub items: Vec<SmolStr>,
+
+    /// The index of the selected tab.
+    #[build(ignore)]
+    #[rebuild(draw)]
+    pub selected: usize,
+
+    /// A callback for when a tab is selected.
+    #[build(ignore)]
+    #[allow(clippy::type_complexity)]
+    pub on_select: Option<Box<dyn FnMut(&mut EventCx, &mut T, usize)>>,
+
+    /// A callback for when the close button of a tab is clicked.
+    #[build(ignore)]
+    #[allow(clippy::type_complexity)]
+    pub on_close: Option<Box<dyn FnMut(&mut EventCx, &mut T, usize)>>,
+
+    /// A callback for when a tab is dragged to a new position.
+    ///
+    /// The callback is given the new order of the tabs, as the old index of each tab.
+    #[build(ignore)]
+    #[allow(clippy::type_complexity)]
+    pub on_reorder: Option<Box<dyn FnMut(&mut EventCx, &mut T, Vec<usize>)>>,
+
+    /// Whether the tabs have a close button.
+    #[rebuild(layout)]
+    pub closable: bool,
+
+    /// The padding of the tabs.
+    #[rebuild(layout)]
+    #[styled(default = Padding::new(6.0, 8.0, 6.0, 12.0))]
+    pub padding: Styled<Padding>,
+
+    /// The font size of the labels.
+    #[rebuild(layout)]
+    #[styled(default = pt(12.0))]
+    pub font_size: Styled<f32>,
+
+    /// The font family of the labels.
+    #[rebuild(layout)]
+    #[styled(default)]
+    pub font_family: Styled<FontFamily>,
+
+    /// The color of the labels.
+    #[rebuild(draw)]
+    #[styled(default -> Theme::CONTRAST or Color::BLACK)]
+    pub color: Styled<Color>,
+
+    /// The size of the close buttons.
+    #[rebuild(layout)]
+    #[styled(default = 8.0)]
+    pub close_size: Styled<f32>,
+
+    /// The background color of the strip.
+    #[rebuild(draw)]
+    #[styled(default -> Theme::SURFACE_LOW or Color::grayscale(0.9))]
+    pub background: Styled<Color>,
+
+    /// The background color of the selected tab.
+    #[rebuild(draw)]
+    #[styled(default -> Theme::SURFACE_HIGHER or Color::WHITE)]
+    pub selected_background: Styled<Color>,
+
+    /// The border radius of the tabs.
+    #[rebuild(draw)]
+    #[styled(default = BorderRadius::new(4.0, 4.0, 0.0, 0.0))]
+    pub border_radius: Styled<BorderRadius>,
+
+    /// The color of the indicator drawn where a dragged tab will be dropped.
+    #[rebuild(draw)]
+    #[styled(default -> Theme::PRIMARY or Color::BLUE)]
+    pub indicator_color: Styled<Color>,
+}
+
+impl<T> Tabs<T> {
+    /// Create a new [`Tabs`] view.
+    pub fn new(items: impl IntoIterator<Item = impl Into<SmolStr>>, selected: usize) -> Self {
+        Self {
+            items: items.into_iter().map(Into::into).collect(),
+            selected,
+            on_select: None,
+            on_close: None,
+            on_reorder: None,
+            closable: true,
+            padding: Styled::style("tabs.padding"),
+            font_size: Styled::style("tabs.font-size"),
+            font_family: Styled::style("tabs.font-family"),
+            color: Styled::style("tabs.color"),
+            close_size: Styled::style("tabs.close-size"),
+            background: Styled::style("tabs.background"),
+            selected_background: Styled::style("tabs.selected-background"),
+            border_radius: Styled::style("tabs.border-radius"),
+            indicator_color: Styled::style("tabs.indicator-color"),
+        }
+    }
+
+    /// Set the callback for when a tab is selected.
+    pub fn on_select(
+        mut self,
+        on_select: impl FnMut(&mut EventCx, &mut T, usize) + 'static,
+    ) -> Self {
+        self.on_select = Some(Box::new(on_select));
+        self
+    }
+
+    /// Set the callback for when the close button of a tab is clicked.
+    pub fn on_close(mut self, on_close: impl FnMut(&mut EventCx, &mut T, usize) + 'static) -> Self {
+        self.on_close = Some(Box::new(on_close));
+        self
+    }
+
+    /// Set the callback for when a tab is dragged to a new position.
+    ///
+    /// The callback is given the new order of the tabs, as the old index of each tab, eg.
+    /// dragging the first of four tabs past the third gives `[1, 2, 0, 3]`.
+    pub fn on_reorder(
+        mut self,
+        on_reorder: impl FnMut(&mut EventCx, &mut T, Vec<usize>) + 'static,
+    ) -> Self {
+        self.on_reorder = Some(Box::new(on_reorder));
+        self
+    }
+
+    fn select(&mut self, cx: &mut EventCx, data: &mut T, index: usize) {
+        if let Some(ref mut on_select) = self.on_select {
+            on_select(cx, data, index);
+        }
+    }
+
+    fn set_texts(&self, state: &mut TabsState) {
+        let attributes = FontAttributes {
+            size: state.style.font_size,
+            family: state.style.font_family.clone(),
+            color: state.style.color,
+            ..Default::default()
+        };
+
+        state.paragraphs = (self.items.iter())
+            .map(|item| {
+                let mut paragraph = Paragraph::new(1.2, TextAlign::Start, TextWrap::None);
+                paragraph.set_text(item, attributes.clone());
+                paragraph
+            })
+            .collect();
+    }
+}
+
+// the new order of `len` tabs, after moving the tab at `from` to the gap before `to`
+fn reorder(len: usize, from: usize, to: usize) -> Option<Vec<usize>> {
+    if from >= len || to == from || to == from + 1 {
+        return None;
+    }
+
+    let mut order: Vec<usize> = (0..len).collect();
+    order.remove(from);
+
+    match to < from {
+        true => order.insert(to, from),
+        false => order.insert(to - 1, from),
+    }
+
+    Some(order)
+}
+
+// a tab being dragged, positions are along the strip, including the scroll
+struct TabDrag {
+    index: usize,
+    start: f32,
+    position: f32,
+    moved: bool,
+}
+
+#[doc(hidden)]
+pub struct TabsState {
+    style: TabsStyle,
+    paragraphs: Vec<Paragraph>,
+    text_height: f32,
+    tabs: Vec<Rect>,
+    scroll: f32,
+    overflow: f32,
+    drag: Option<TabDrag>,
+}
+
+impl TabsState {
+    // the distance the pointer must move before a press becomes a drag
+    const DRAG_THRESHOLD: f32 = 4.0;
+
+    fn tab_at(&self, x: f32) -> Option<usize> {
+        (self.tabs.iter()).position(|tab| tab.min.x <= x && x < tab.max.x)
+    }
+
+    fn close_rect(&self, tab: Rect) -> Rect {
+        let size = self.style.close_size;
+        let center = Point::new(
+            tab.max.x - self.style.padding.right - size / 2.0,
+            tab.center().y,
+        );
+
+        Rect::center_size(center, Size::all(size))
+    }
+
+    // the gap a tab dropped at `x` is moved to, the gap before the first tab is 0
+    fn drop_index(&self, x: f32) -> usize {
+        self.tabs.iter().filter(|tab| tab.center().x < x).count()
+    }
+
+    fn scroll_to(&mut self, index: usize, width: f32) {
+        if let Some(tab) = self.tabs.get(index) {
+            let min = tab.max.x - width;
+            self.scroll = self.scroll.clamp(min, tab.min.x).clamp(0.0, self.overflow);
+        }
+    }
+}
+
+impl<T> View<T> for Tabs<T> {
+    type State = TabsState;
+
+    fn build(&mut self, cx: &mut BuildCx, _data: &mut T) -> Self::State {
+        cx.set_class("tabs");
+
+        let mut state = TabsState {
+            style: TabsStyle::styled(self, cx.styles()),
+            paragraphs: Vec::new(),
+            text_height: 0.0,
+            tabs: Vec::new(),
+            scroll: 0.0,
+            overflow: 0.0,
+            drag: None,
+        };
+
+        self.set_texts(&mut state);
+
+        state
+    }
+
+    fn rebuild(&mut self, state: &mut Self::State, cx: &mut RebuildCx, _data: &mut T, old: &Self) {
+        Rebuild::rebuild(self, cx, old);
+        state.style.rebuild(self, cx);
+
+        if self.items != old.items {
+            state.drag = None;
+        }
+
+        self.set_texts(state);
+    }
+
+    fn event(
+        &mut self,
+        state: &mut Self::State,
+        cx: &mut EventCx,
+        data: &mut T,
+        event: &Event,
+    ) -> bool {
+        match event {
+            Event::PointerPressed(e) if e.button == PointerButton::Primary && cx.is_hovered() => {
+                let local = cx.local(e.position) + Vector::new(state.scroll, 0.0);
+
+                let Some(index) = state.tab_at(local.x) else {
+                    return false;
+                };
+
+                if self.closable && state.close_rect(state.tabs[index]).contains(local) {
+                    if let Some(ref mut on_close) = self.on_close {
+                        on_close(cx, data, index);
+                    }
+
+                    return true;
+                }
+
+                if index != self.selected {
+                    self.select(cx, data, index);
+                }
+
+                state.drag = Some(TabDrag {
+                    index,
+                    start: local.x,
+                    position: local.x,
+                    moved: false,
+                });
+
+                cx.set_active(true);
+                cx.draw();
+
+                true
+            }
+            Event::PointerMoved(e) => {
+                let Some(ref mut drag) = state.drag else {
+                    return false;
+                };
+
+                drag.position = cx.local(e.position).x + state.scroll;
+                drag.moved |= (drag.position - drag.start).abs() > TabsState::DRAG_THRESHOLD;
+                cx.draw();
+
+                true
+            }
+            Event::PointerReleased(_) => {
+                let Some(drag) = state.drag.take() else {
+                    return false;
+                };
+
+                cx.set_active(false);
+                cx.draw();
+
+                if !drag.moved {
+                    return true;
+                }
+
+                let target = state.drop_index(drag.position);
+
+                if let Some(order) = reorder(self.items.len(), drag.index, target) {
+                    if let Some(ref mut on_reorder) = self.on_reorder {
+                        on_reorder(cx, data, order);
+                    }
+                }
+
+                true
+            }
+            Event::PointerScrolled(e) if cx.is_hovered() && state.overflow > 0.0 => {
+                let delta = e.delta.to_pixels(10.0);
+                state.scroll -= delta.x + delta.y;
+                state.scroll = state.scroll.clamp(0.0, state.overflow);
+                cx.draw();
+
+                true
+            }
+            Event::KeyPressed(e) if e.is_key(Key::Tab) && e.modifiers.ctrl => {
+                let len = self.items.len();
+
+                if len == 0 {
+                    return false;
+                }
+
+                let index = match e.modifiers.shift {
+                    true => (self.selected + len - 1) % len,
+                    false => (self.selected + 1) % len,
+                };
+
+                state.scroll_to(index, cx.size().width);
+                self.select(cx, data, index);
+                cx.draw();
+
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn layout(
+        &mut self,
+        state: &mut Self::State,
+        cx: &mut LayoutCx,
+        _data: &mut T,
+        space: Space,
+    ) -> Size {
+        let padding = state.style.padding;
+
+        let close_width = match self.closable {
+            true => state.style.close_size + padding.left,
+            false => 0.0,
+        };
+
+        let sizes: Vec<_> = (state.paragraphs.iter())
+            .map(|paragraph| cx.fonts().measure(paragraph, f32::INFINITY))
+            .collect();
+
+        let text_height = sizes.iter().map(|size| size.height).fold(0.0, f32::max);
+        let text_height = f32::max(text_height, state.style.font_size * 1.2);
+        let height = text_height + padding.size().height;
+
+        let mut x = 0.0;
+        state.tabs.clear();
+
+        for size in sizes {
+            let width = size.width + close_width + padding.size().width;
+            state
+                .tabs
+                .push(Rect::min_size(Point::new(x, 0.0), Size::new(width, height)));
+            x += width;
+        }
+
+        let size = space.fit(Size::new(x, height));
+
+        state.text_height = text_height;
+        state.overflow = f32::max(x - size.width, 0.0);
+        state.scroll = state.scroll.clamp(0.0, state.overflow);
+
+        size
+    }
+
+    fn draw(&mut self, state: &mut Self::State, cx: &mut DrawCx, _data: &mut T) {
+        let style = &state.style;
+        let size = cx.size();
+
+        let draw_tab = |cx: &mut DrawCx, index: usize, tab: Rect| {
+            if index == self.selected {
+                cx.quad(
+                    tab,
+                    style.selected_background,
+                    style.border_radius,
+                    BorderWidth::ZERO,
+                    Color::TRANSPARENT,
+                );
+            }
+
+            let text_size = Size::new(tab.width(), state.text_height);
+            let text_rect = Rect::min_size(tab.min + style.padding.offset(), text_size);
+            cx.paragraph(&state.paragraphs[index], text_rect);
+
+            if self.closable {
+                let close = state.close_rect(tab);
+
+                let mut curve = Curve::new();
+                curve.move_to(close.top_left());
+                curve.line_to(close.bottom_right());
+                curve.move_to(close.top_right());
+                curve.line_to(close.bottom_left());
+
+                cx.stroke(curve, 1.5, style.color);
+            }
+        };
+
+        cx.hoverable(|cx| {
+            cx.fill_rect(Rect::min_size(Point::ZERO, size), style.background);
+
+            cx.masked(Rect::min_size(Point::ZERO, size), |cx| {
+                cx.translated(Vector::new(-state.scroll, 0.0), |cx| {
+                    let dragged = state.drag.as_ref().filter(|drag| drag.moved);
+
+                    for (index, &tab) in state.tabs.iter().enumerate() {
+                        if dragged.is_some_and(|drag| drag.index == index) {
+                            continue;
+                        }
+
+                        draw_tab(cx, index, tab);
+                    }
+
+                    let Some(drag) = dragged else {
+                        return;
+                    };
+
+                    // the dragged tab follows the pointer, and the indicator shows the gap
+                    // it will be dropped in
+                    let tab = state.tabs[drag.index] + Vector::new(drag.position - drag.start, 0.0);
+                    draw_tab(cx, drag.index, tab);
+
+                    let target = state.drop_index(drag.position);
+
+                    let x = match state.tabs.get(target) {
+                        Some(tab) => tab.min.x,
+                        None => state.tabs.last().map_or(0.0, |tab| tab.max.x),
+                    };
+
+                    let indicator =
+                        Rect::min_size(Point::new(x - 1.0, 0.0), Size::new(2.0, size.height));
+                    cx.fill_rect(indicator, style.indicator_color);
+                });
+            });
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use crate::{
+        text::{Fonts, MonoFonts},
+        views::testing::ViewTester,
+    };
+
+    use super::*;
+
+    #[test]
+    fn reorder_moves_the_tab_into_the_gap() {
+        assert_eq!(reorder(4, 0, 3), Some(vec![1, 2, 0, 3]));
+        assert_eq!(reorder(4, 3, 1), Some(vec![0, 3, 1, 2]));
+        assert_eq!(reorder(4, 1, 1), None);
+        assert_eq!(reorder(4, 1, 2), None);
+    }
+
+    #[test]
+    fn dragging_a_tab_past_another_reorders() {
+        let order = Arc::new(Mutex::new(None));
+
+        let mut view = tabs::<()>(["one", "two", "six", "ten"], 0).on_reorder({
+            let order = order.clone();
+            move |_, _, new| *order.lock().unwrap() = Some(new)
+        });
+
+        let mut tester = ViewTester::new(&mut view, &mut ());
+        (tester.contexts).insert::<Box<dyn Fonts>>(Box::new(MonoFonts));
+        tester.layout(&mut view, &mut (), Space::UNBOUNDED);
+
+        let tabs = tester.state.tabs.clone();
+        let y = tabs[0].center().y;
+
+        let pressed = Point::new(tabs[0].min.x + 4.0, y);
+
+        // just past the center of the third tab
+        let moved = Point::new(tabs[2].center().x + 4.0, y);
+
+        let primary = PointerButton::Primary;
+        tester.view_state.set_hovered(true);
+        tester.press(&mut view, &mut (), pressed, primary);
+        tester.move_to(&mut view, &mut (), moved);
+        tester.release(&mut view, &mut (), moved, primary);
+
+        assert_eq!(*order.lock().unwrap(), Some(vec![1, 2, 0, 3]));
+    }
+}
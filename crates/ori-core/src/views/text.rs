@@ -7,12 +7,12 @@ use crate::{
     canvas::Color,
     context::{BuildCx, DrawCx, EventCx, LayoutCx, RebuildCx},
     event::Event,
-    layout::{Size, Space},
+    layout::{Axis, Size, Space},
     rebuild::Rebuild,
     style::{Styled, Theme},
     text::{
-        FontAttributes, FontFamily, FontStretch, FontStyle, FontWeight, Paragraph, TextAlign,
-        TextWrap,
+        FontAttributes, FontFamily, FontStretch, FontStyle, FontVariation, FontWeight, Fonts,
+        Paragraph, TextAlign, TextWrap, WhiteSpace,
     },
     view::View,
 };
@@ -38,7 +38,11 @@ pub fn text(text: impl Into<SmolStr>) -> Text {
 
 /// A view that displays text.
 ///
-/// Can be styled using the [`TextStyle`].
+/// Can be styled using the [`TextStyle`]. The color, font size, font family and alignment
+/// of text that doesn't set its own are inherited from the closest ancestor that does, like
+/// in CSS, eg. `container { text-color: #ff0000 }` makes all the text in containers red, see
+/// [`Styles::get_inherited`](crate::style::Styles::get_inherited). The color is inherited
+/// from `text-color` rather than `color`, which views like buttons use for their own fill.
 #[example(name = "text", width = 400, height = 300)]
 #[derive(Styled, Build, Rebuild)]
 pub struct Text {
@@ -46,17 +50,18 @@ pub struct Text {
     #[rebuild(layout)]
     pub text: SmolStr,
 
-    /// The font size of the text.
-    #[styled(default = 16.0)]
+    /// The font size of the text, inherited from the `font-size` of the ancestors.
+    #[styled(inherit = "font-size", default = 16.0)]
     #[rebuild(layout)]
     pub font_size: Styled<f32>,
 
-    /// The font family of the text.
-    #[styled(default)]
+    /// The font family of the text, inherited from the `font-family` of the ancestors.
+    #[styled(inherit = "font-family", default)]
     #[rebuild(layout)]
     pub font_family: Styled<FontFamily>,
 
-    /// The font weight of the text.
+    /// The font weight of the text, see [`Text::font_weight`].
+    #[build(ignore)]
     #[styled(default)]
     #[rebuild(layout)]
     pub font_weight: Styled<FontWeight>,
@@ -71,13 +76,14 @@ pub struct Text {
     #[rebuild(layout)]
     pub font_style: Styled<FontStyle>,
 
-    /// The color of the text.
-    #[styled(default -> Theme::CONTRAST or Color::BLACK)]
+    /// The color of the text, inherited from the `text-color` of the ancestors, not their
+    /// `color`.
+    #[styled(inherit = "text-color", default -> Theme::CONTRAST or Color::BLACK)]
     #[rebuild(draw)]
     pub color: Styled<Color>,
 
-    /// The horizontal alignment of the text.
-    #[styled(default)]
+    /// The horizontal alignment of the text, inherited from the `text-align` of the ancestors.
+    #[styled(inherit = "text-align", default)]
     #[rebuild(layout)]
     pub align: Styled<TextAlign>,
 
@@ -90,6 +96,30 @@ pub struct Text {
     #[styled(default)]
     #[rebuild(layout)]
     pub wrap: Styled<TextWrap>,
+
+    /// The handling of whitespace in the text, see [`WhiteSpace`].
+    #[styled(default)]
+    #[rebuild(layout)]
+    pub white_space: Styled<WhiteSpace>,
+
+    /// The number of columns between tab stops, see [`Text::tab_size`].
+    #[build(ignore)]
+    #[rebuild(layout)]
+    pub tab_size: usize,
+
+    /// The maximum number of lines to display, see [`Text::max_lines`].
+    #[build(ignore)]
+    #[rebuild(layout)]
+    pub max_lines: Option<usize>,
+
+    /// Whether to append an ellipsis when the text is truncated by `max_lines`.
+    #[rebuild(layout)]
+    pub ellipsis: bool,
+
+    /// The positions on the variation axes of a variable font, see [`Text::font_axis`].
+    #[build(ignore)]
+    #[rebuild(layout)]
+    pub font_variations: Vec<FontVariation>,
 }
 
 impl Text {
@@ -106,9 +136,59 @@ impl Text {
             align: Styled::style("text.align"),
             line_height: Styled::style("text.line-height"),
             wrap: Styled::style("text.wrap"),
+            white_space: Styled::style("text.white-space"),
+            tab_size: 4,
+            max_lines: None,
+            ellipsis: true,
+            font_variations: Vec::new(),
         }
     }
 
+    /// Set the maximum number of lines to display.
+    ///
+    /// Text exceeding `max_lines` is truncated, and if [`Text::ellipsis`] is enabled, the
+    /// last visible line ends with `…`.
+    pub fn max_lines(mut self, max_lines: usize) -> Self {
+        self.max_lines = Some(max_lines.max(1));
+        self
+    }
+
+    /// Set the number of columns between tab stops, tabs advance to the next multiple of it.
+    pub fn tab_size(mut self, tab_size: usize) -> Self {
+        self.tab_size = tab_size.max(1);
+        self
+    }
+
+    /// Set the value of a variation axis of a variable font, eg. `font_axis("wght", 650.0)`.
+    ///
+    /// Setting the same axis again replaces the previous value.
+    pub fn font_axis(mut self, tag: &str, value: f32) -> Self {
+        let variation = FontVariation::new(tag, value);
+
+        match (self.font_variations.iter_mut()).find(|v| v.tag == variation.tag) {
+            Some(existing) => existing.value = value,
+            None => self.font_variations.push(variation),
+        }
+
+        self
+    }
+
+    /// Set the font weight, eg. `font_weight(650.0)` or `font_weight(FontWeight::BOLD)`.
+    ///
+    /// The weight also sets the `wght` axis of variable fonts, so any weight in the range
+    /// `1.0..=1000.0` can be used, not only the named ones.
+    pub fn font_weight(mut self, weight: impl Into<f32>) -> Self {
+        let weight = weight.into().clamp(1.0, 1000.0);
+
+        self.font_weight = Styled::from(FontWeight(weight.round() as u16));
+        self.font_axis("wght", weight)
+    }
+
+    /// Set the `wdth` axis of a variable font, in percent of the normal width.
+    pub fn font_width(self, width: f32) -> Self {
+        self.font_axis("wdth", width)
+    }
+
     fn font_attributes(&self, style: &TextStyle) -> FontAttributes {
         FontAttributes {
             size: style.font_size,
@@ -118,31 +198,101 @@ impl Text {
             style: style.font_style,
             ligatures: true,
             color: style.color,
+            variations: self.font_variations.clone(),
+        }
+    }
+}
+
+#[doc(hidden)]
+pub struct TextState {
+    paragraph: Paragraph,
+    attributes: FontAttributes,
+    truncated: Option<Paragraph>,
+}
+
+impl TextState {
+    fn visible(&self) -> &Paragraph {
+        self.truncated.as_ref().unwrap_or(&self.paragraph)
+    }
+
+    // truncate the paragraph to `max_lines`, cutting the last line at a glyph boundary
+    fn truncate(
+        &self,
+        fonts: &mut dyn Fonts,
+        max_lines: usize,
+        ellipsis: bool,
+        width: f32,
+    ) -> Option<Paragraph> {
+        let lines = fonts.layout(&self.paragraph, width);
+
+        if lines.len() <= max_lines {
+            return None;
+        }
+
+        let text = self.paragraph.text();
+        let line = &lines[max_lines - 1];
+
+        let mut paragraph = self.paragraph.clone();
+
+        if !ellipsis {
+            let text = text[..line.range.end].trim_end();
+            paragraph.set_text(text, self.attributes.clone());
+            return Some(paragraph);
         }
+
+        paragraph.set_text("…", self.attributes.clone());
+        let ellipsis_width = fonts.measure(&paragraph, f32::INFINITY).width;
+
+        // find the last glyph that leaves room for the ellipsis
+        let end = (line.glyphs.iter())
+            .take_while(|glyph| glyph.bounds.max.x - line.left + ellipsis_width <= width)
+            .last()
+            .map_or(line.range.start, |glyph| glyph.range.end);
+
+        let text = text[..end].trim_end();
+        paragraph.set_text(format_args!("{}…", text), self.attributes.clone());
+        Some(paragraph)
     }
 }
 
 impl<T> View<T> for Text {
-    type State = Paragraph;
+    type State = TextState;
 
     fn build(&mut self, cx: &mut BuildCx, _data: &mut T) -> Self::State {
         let style = TextStyle::styled(self, cx.styles());
+        let attributes = self.font_attributes(&style);
 
-        let mut paragraph = Paragraph::new(style.line_height, style.align, style.wrap);
-        paragraph.push_text(&self.text, self.font_attributes(&style));
-        paragraph
+        let text = style.white_space.apply(&self.text);
+
+        let mut paragraph = Paragraph::new(
+            style.line_height,
+            style.align,
+            style.white_space.wrap(style.wrap),
+        );
+        paragraph.tab_size = Some(self.tab_size);
+        paragraph.push_text(&text, attributes.clone());
+
+        TextState {
+            paragraph,
+            attributes,
+            truncated: None,
+        }
     }
 
     fn rebuild(&mut self, state: &mut Self::State, cx: &mut RebuildCx, _data: &mut T, old: &Self) {
         Rebuild::rebuild(self, cx, old);
 
         let style = TextStyle::styled(self, cx.styles());
+        state.attributes = self.font_attributes(&style);
 
-        state.line_height = style.line_height;
-        state.align = style.align;
-        state.wrap = style.wrap;
+        let paragraph = &mut state.paragraph;
+        paragraph.line_height = style.line_height;
+        paragraph.align = style.align;
+        paragraph.wrap = style.white_space.wrap(style.wrap);
+        paragraph.tab_size = Some(self.tab_size);
 
-        state.set_text(&self.text, self.font_attributes(&style));
+        let text = style.white_space.apply(&self.text);
+        paragraph.set_text(&text, state.attributes.clone());
     }
 
     fn event(
@@ -162,11 +312,44 @@ impl<T> View<T> for Text {
         _data: &mut T,
         space: Space,
     ) -> Size {
-        cx.fonts().measure(state, space.max.width)
+        let width = space.max.width;
+
+        // truncation depends on the available width, so it's recomputed on every layout
+        state.truncated = match self.max_lines {
+            Some(max_lines) => state.truncate(cx.fonts(), max_lines, self.ellipsis, width),
+            None => None,
+        };
+
+        cx.fonts().measure(state.visible(), width)
+    }
+
+    fn intrinsic_size(
+        &mut self,
+        state: &mut Self::State,
+        cx: &mut LayoutCx,
+        _data: &mut T,
+        axis: Axis,
+        cross_extent: f32,
+    ) -> f32 {
+        // the natural width of text is its unwrapped width, and its natural height is the
+        // height when wrapped to the cross extent
+        let width = match axis {
+            Axis::Horizontal => f32::INFINITY,
+            Axis::Vertical => cross_extent,
+        };
+
+        // unlike layout, this must not replace the truncated paragraph
+        let truncated = match self.max_lines {
+            Some(max_lines) => state.truncate(cx.fonts(), max_lines, self.ellipsis, width),
+            None => None,
+        };
+
+        let paragraph = truncated.as_ref().unwrap_or(&state.paragraph);
+        axis.major(cx.fonts().measure(paragraph, width))
     }
 
     fn draw(&mut self, state: &mut Self::State, cx: &mut DrawCx, _data: &mut T) {
-        cx.paragraph(state, cx.rect());
+        cx.paragraph(state.visible(), cx.rect());
     }
 }
 
@@ -177,3 +360,144 @@ impl From<fmt::Arguments<'_>> for Text {
         Text::new(w)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        canvas::Primitive,
+        style::Styles,
+        text::MonoFonts,
+        views::{button, container, hstack, testing::ViewTester},
+    };
+
+    use super::*;
+
+    #[test]
+    fn intrinsic_width_is_unwrapped() {
+        let mut view = text("Hello world");
+
+        let mut tester = ViewTester::new(&mut view, &mut ());
+        (tester.contexts).insert::<Box<dyn Fonts>>(Box::new(MonoFonts));
+
+        let width = tester.intrinsic_size(&mut view, &mut (), Axis::Horizontal, 40.0);
+        assert_eq!(width, 11.0 * MonoFonts::ADVANCE);
+
+        let height = tester.intrinsic_size(&mut view, &mut (), Axis::Vertical, 40.0);
+        assert_eq!(height, 3.0 * MonoFonts::HEIGHT);
+    }
+
+    #[test]
+    fn max_lines_ellipsis() {
+        let mut view = text("Hello wonderful world").max_lines(1);
+
+        let mut tester = ViewTester::new(&mut view, &mut ());
+        (tester.contexts).insert::<Box<dyn Fonts>>(Box::new(MonoFonts));
+
+        let space = Space::new(Size::ZERO, Size::new(80.0, 100.0));
+        let size = tester.layout(&mut view, &mut (), space);
+
+        assert_eq!(tester.state.visible().text(), "Hello won…");
+        assert_eq!(size, Size::new(80.0, MonoFonts::HEIGHT));
+
+        // the truncation is recomputed when the width changes
+        let space = Space::new(Size::ZERO, Size::new(1000.0, 100.0));
+        tester.layout(&mut view, &mut (), space);
+
+        assert_eq!(tester.state.visible().text(), "Hello wonderful world");
+    }
+
+    #[test]
+    fn pre_expands_tabs_to_tab_stops() {
+        let mut view = text("ab\tc\n\td").white_space(WhiteSpace::Pre).tab_size(4);
+
+        let mut tester = ViewTester::new(&mut view, &mut ());
+        (tester.contexts).insert::<Box<dyn Fonts>>(Box::new(MonoFonts));
+
+        // tabs are kept in the text, so byte ranges into it stay valid
+        let paragraph = tester.state.visible().clone();
+        assert_eq!(paragraph.text(), "ab\tc\n\td");
+        assert_eq!(paragraph.tab_stops().collect::<Vec<_>>(), [(2, 2), (5, 4)]);
+
+        let lines = MonoFonts.layout(&paragraph, f32::INFINITY);
+        let c = lines[0].glyphs.iter().find(|glyph| glyph.range.start == 3);
+        assert_eq!(c.unwrap().bounds.min.x, 4.0 * MonoFonts::ADVANCE);
+    }
+
+    #[test]
+    fn normal_collapses_white_space() {
+        let mut view = text("a \t b\n\nc").white_space(WhiteSpace::Normal);
+
+        let tester = ViewTester::new(&mut view, &mut ());
+        assert_eq!(tester.state.visible().text(), "a b c");
+    }
+
+    #[test]
+    fn font_weight_sets_the_weight_axis() {
+        let attributes = |mut view: Text| {
+            let tester = ViewTester::new(&mut view, &mut ());
+            let (_, attributes) = tester.state.visible().iter().next().unwrap();
+            (attributes.weight, attributes.variations.clone())
+        };
+
+        let (weight, variations) = attributes(text("m").font_weight(650.4));
+        assert_eq!(weight, FontWeight(650));
+        assert_eq!(variations, [FontVariation::new("wght", 650.4)]);
+
+        // setting the axis again replaces it
+        let view = text("m")
+            .font_weight(FontWeight::BOLD)
+            .font_axis("wght", 300.0);
+        let (weight, variations) = attributes(view);
+        assert_eq!(weight, FontWeight::BOLD);
+        assert_eq!(variations, [FontVariation::new("wght", 300.0)]);
+    }
+
+    // the colors of the paragraphs drawn by the view with the styles, in order
+    fn drawn_text_colors<V: View<()>>(view: impl Fn() -> V, styles: &str) -> Vec<Color> {
+        fn text_colors(primitives: &[Primitive], colors: &mut Vec<Color>) {
+            for primitive in primitives {
+                match primitive {
+                    Primitive::Paragraph { paragraph, .. } => {
+                        colors.extend(paragraph.iter().map(|(_, attrs)| attrs.color));
+                    }
+                    Primitive::Layer { primitives, .. } => text_colors(primitives, colors),
+                    _ => {}
+                }
+            }
+        }
+
+        let mut old = view();
+        let mut tester = ViewTester::new(&mut old, &mut ());
+        (tester.contexts).insert::<Box<dyn Fonts>>(Box::new(MonoFonts));
+
+        let styles: Styles = styles.parse().unwrap();
+        tester.contexts.insert(styles);
+
+        let mut new = view();
+        tester.rebuild(&mut new, &mut (), &old);
+        tester.layout(&mut new, &mut (), Space::UNBOUNDED);
+        let canvas = tester.draw(&mut new, &mut ());
+
+        let primitives = canvas.primitives().cloned().collect::<Vec<_>>();
+        let mut drawn = Vec::new();
+        text_colors(&primitives, &mut drawn);
+        drawn
+    }
+
+    #[test]
+    fn color_is_inherited_from_container() {
+        let view = || container(hstack![text("inherited"), text("own").color(Color::BLUE)]);
+        let drawn = drawn_text_colors(view, "container { text-color: #ff0000, }");
+
+        assert_eq!(drawn, [Color::rgb(1.0, 0.0, 0.0), Color::BLUE]);
+    }
+
+    #[test]
+    fn button_color_is_not_inherited() {
+        // the color of a button is its face, the label has to stay readable on it
+        let view = || button(text("label"));
+        let drawn = drawn_text_colors(view, "button { color: #ff0000, }");
+
+        assert_eq!(drawn, [Color::BLACK]);
+    }
+}
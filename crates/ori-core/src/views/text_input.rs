@@ -1,4 +1,7 @@
+use std::{borrow::Cow, mem, ops::Range};
+
 use ori_macro::{example, Build, Styled};
+use unicode_segmentation::UnicodeSegmentation;
 
 use crate::{
     canvas::Color,
@@ -7,8 +10,8 @@ use crate::{
     layout::{Point, Rect, Size, Space},
     style::{Styled, Theme},
     text::{
-        FontAttributes, FontFamily, FontStretch, FontStyle, FontWeight, Paragraph, TextAlign,
-        TextLayoutLine, TextWrap,
+        FontAttributes, FontFamily, FontStretch, FontStyle, FontWeight, GlyphCluster, Paragraph,
+        TextAlign, TextDirection, TextLayoutLine, TextWrap,
     },
     view::View,
     window::Cursor,
@@ -42,11 +45,31 @@ pub struct TextInput<T> {
     /// Placeholder text to display when the input is empty.
     pub placeholder: String,
 
+    /// Whether the placeholder should be hidden while the input is focused.
+    pub hide_placeholder_on_focus: bool,
+
+    /// The maximum number of characters the input will accept.
+    pub max_length: Option<usize>,
+
+    /// A filter deciding which characters the input will accept.
+    #[build(ignore)]
+    #[allow(clippy::type_complexity)]
+    pub char_filter: Option<Box<dyn Fn(char) -> bool>>,
+
     /// Whether the input is multi-line.
     ///
     /// When disabled (the default), the input will only accept a single line of text.
     pub multiline: bool,
 
+    /// Whether the input is a password field.
+    ///
+    /// Every grapheme of the text is drawn as [`TextInput::mask_char`], and the text can't be
+    /// copied or cut to the clipboard. The text passed to the callbacks isn't masked.
+    pub password: bool,
+
+    /// The character drawn in place of every grapheme of the text of a password field.
+    pub mask_char: char,
+
     /// How the text should be capitalized.
     ///
     /// This only affects text input from IMEs, eg. on-screen keyboards like the ones on mobile
@@ -115,7 +138,12 @@ impl<T> TextInput<T> {
             on_input: None,
             on_submit: None,
             placeholder: String::from("..."),
+            hide_placeholder_on_focus: false,
+            max_length: None,
+            char_filter: None,
             multiline: false,
+            password: false,
+            mask_char: '\u{2022}',
             capitalize: Capitalize::Sentences,
             font_size: Styled::style("text-input.font-size"),
             font_family: Styled::style("text-input.font-family"),
@@ -155,6 +183,177 @@ impl<T> TextInput<T> {
         self.on_submit = Some(Box::new(on_submit));
         self
     }
+
+    /// Set the filter deciding which characters the input will accept.
+    ///
+    /// Characters for which `filter` returns `false` are dropped from typed and pasted text.
+    pub fn char_filter(mut self, filter: impl Fn(char) -> bool + 'static) -> Self {
+        self.char_filter = Some(Box::new(filter));
+        self
+    }
+
+    // the character drawn in place of the text, if it's masked
+    fn mask(&self) -> Option<char> {
+        self.password.then_some(self.mask_char)
+    }
+
+    // filter `input` and truncate it so the text doesn't exceed `max_length`
+    //
+    // `len` is the number of characters that will remain in the text
+    fn accept_input(&self, input: &str, len: usize) -> String {
+        let filter = self.char_filter.as_deref();
+        accept_input(input, len, self.max_length, filter)
+    }
+}
+
+fn accept_input(
+    input: &str,
+    len: usize,
+    max_length: Option<usize>,
+    filter: Option<&dyn Fn(char) -> bool>,
+) -> String {
+    let chars = input.chars().filter(|&c| match filter {
+        Some(filter) => filter(c),
+        None => true,
+    });
+
+    match max_length {
+        Some(max_length) => chars.take(max_length.saturating_sub(len)).collect(),
+        None => chars.collect(),
+    }
+}
+
+// the text drawn by the input, with every grapheme replaced by `mask` if it's masked
+fn display_text(text: &str, mask: Option<char>) -> Cow<'_, str> {
+    match mask {
+        Some(mask) => Cow::Owned(text.graphemes(true).map(|_| mask).collect()),
+        None => Cow::Borrowed(text),
+    }
+}
+
+// the index in the masked text of the grapheme at `index` in `text`
+fn mask_index(text: &str, index: usize, mask: char) -> usize {
+    text[..index].graphemes(true).count() * mask.len_utf8()
+}
+
+// map the ranges of lines laid out from the masked text back to ranges of `text`, so the
+// cursor and selection work on the real characters
+fn unmask_lines(lines: &mut [TextLayoutLine], text: &str, mask: char) {
+    let offsets = (text.grapheme_indices(true).map(|(index, _)| index))
+        .chain(Some(text.len()))
+        .collect::<Vec<_>>();
+
+    let unmask = |index: usize| offsets[usize::min(index / mask.len_utf8(), offsets.len() - 1)];
+
+    for line in lines {
+        line.range = unmask(line.range.start)..unmask(line.range.end);
+
+        for glyph in &mut line.glyphs {
+            glyph.range = unmask(glyph.range.start)..unmask(glyph.range.end);
+        }
+    }
+}
+
+// the maximum number of undo steps kept by a text input
+const HISTORY_LIMIT: usize = 100;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum EditKind {
+    // typing, consecutive typing is undone one word at a time
+    Typing,
+
+    // deleting with backspace, consecutive deletes are undone together
+    Deleting,
+
+    // pasting, cutting or a line break, never merged with other edits
+    Other,
+}
+
+// an edit that replaced `removed` at `start` with `inserted`
+#[derive(Clone, Debug)]
+struct Edit {
+    kind: EditKind,
+    start: usize,
+    removed: String,
+    inserted: String,
+
+    // the cursor and selection before the edit, restored when the edit is undone
+    cursor: usize,
+    selection: Option<usize>,
+}
+
+impl Edit {
+    // try to merge `next` into `self`, so they are undone as one step
+    fn merge(&mut self, next: &Edit) -> bool {
+        if self.kind != next.kind {
+            return false;
+        }
+
+        match self.kind {
+            EditKind::Typing => {
+                let is_contiguous = self.start + self.inserted.len() == next.start;
+
+                // a new word starts a new step
+                let ends_word = self.inserted.ends_with(char::is_whitespace)
+                    && !next.inserted.starts_with(char::is_whitespace);
+
+                if !is_contiguous || !next.removed.is_empty() || ends_word {
+                    return false;
+                }
+
+                self.inserted.push_str(&next.inserted);
+                true
+            }
+            EditKind::Deleting => {
+                if next.start + next.removed.len() != self.start || !next.inserted.is_empty() {
+                    return false;
+                }
+
+                self.removed.insert_str(0, &next.removed);
+                self.start = next.start;
+                true
+            }
+            EditKind::Other => false,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct History {
+    undo: Vec<Edit>,
+    redo: Vec<Edit>,
+
+    // whether the next edit starts a new step, eg. after the cursor was moved
+    sealed: bool,
+}
+
+impl History {
+    fn push(&mut self, edit: Edit) {
+        self.redo.clear();
+
+        let sealed = mem::take(&mut self.sealed);
+
+        if let (Some(last), false) = (self.undo.last_mut(), sealed) {
+            if last.merge(&edit) {
+                return;
+            }
+        }
+
+        if self.undo.len() >= HISTORY_LIMIT {
+            self.undo.remove(0);
+        }
+
+        self.undo.push(edit);
+    }
+
+    fn seal(&mut self) {
+        self.sealed = true;
+    }
+
+    fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
 }
 
 #[doc(hidden)]
@@ -164,6 +363,9 @@ pub struct TextInputState {
 
     // the current text of the input
     text: String,
+
+    // the character drawn in place of the text, if it's masked
+    mask: Option<char>,
     paragraph: Paragraph,
     lines: Vec<TextLayoutLine>,
 
@@ -173,6 +375,12 @@ pub struct TextInputState {
     blink: f32,
     cursor: usize,
     selection: Option<usize>,
+
+    // where the cursor was placed by moving it visually, a cursor at the boundary between
+    // text of different directions has two visual positions
+    visual_cursor: Option<(usize, f32)>,
+
+    history: History,
 }
 
 impl TextInputState {
@@ -186,29 +394,103 @@ impl TextInputState {
         self.cursor = cursor;
         self.blink = 0.0;
         self.move_offset = None;
+        self.visual_cursor = None;
     }
 
-    fn remove_selection(&mut self) {
-        if let Some(selection) = self.selection {
-            let start = usize::min(self.cursor, selection);
-            let end = usize::max(self.cursor, selection);
+    // the number of characters that remain when the selection is replaced
+    fn remaining_len(&self) -> usize {
+        let len = self.text.chars().count();
 
-            self.text.drain(start..end);
-            self.set_cursor(start, false);
+        match self.selection {
+            Some(selection) => {
+                let start = usize::min(self.cursor, selection);
+                let end = usize::max(self.cursor, selection);
 
-            self.selection = None;
+                len - self.text[start..end].chars().count()
+            }
+            None => len,
+        }
+    }
+
+    // the selected range, or an empty range at the cursor
+    fn selected_range(&self) -> Range<usize> {
+        let selection = self.selection.unwrap_or(self.cursor);
+        usize::min(self.cursor, selection)..usize::max(self.cursor, selection)
+    }
+
+    // replace `range` with `text`, recording the edit in the history
+    fn edit(&mut self, range: Range<usize>, text: &str, kind: EditKind) {
+        let edit = Edit {
+            kind,
+            start: range.start,
+            removed: self.text[range.clone()].to_string(),
+            inserted: text.to_string(),
+            cursor: self.cursor,
+            selection: self.selection,
+        };
+
+        self.text.replace_range(range.clone(), text);
+        self.set_cursor(range.start + text.len(), false);
+        self.history.push(edit);
+    }
+
+    fn insert_text(&mut self, text: &str, kind: EditKind) {
+        self.edit(self.selected_range(), text, kind);
+    }
+
+    fn remove_selection(&mut self, kind: EditKind) {
+        if self.selection.is_some() {
+            self.edit(self.selected_range(), "", kind);
         }
     }
 
+    fn undo(&mut self) -> bool {
+        let Some(edit) = self.history.undo.pop() else {
+            return false;
+        };
+
+        let end = edit.start + edit.inserted.len();
+        self.text.replace_range(edit.start..end, &edit.removed);
+
+        self.set_cursor(edit.cursor, false);
+        self.selection = edit.selection;
+
+        self.history.redo.push(edit);
+        self.history.seal();
+
+        true
+    }
+
+    fn redo(&mut self) -> bool {
+        let Some(edit) = self.history.redo.pop() else {
+            return false;
+        };
+
+        let end = edit.start + edit.removed.len();
+        self.text.replace_range(edit.start..end, &edit.inserted);
+
+        self.set_cursor(edit.start + edit.inserted.len(), false);
+
+        self.history.undo.push(edit);
+        self.history.seal();
+
+        true
+    }
+
     fn move_right(&mut self, select: bool) {
         if !select && self.selection.is_some() {
             // if the selection is active, clear it
 
-            if self.cursor < self.selection.unwrap() {
-                self.cursor = self.selection.unwrap();
+            let selection = self.selection.take().unwrap();
+
+            if self.visual_position(selection) > self.visual_position(self.cursor) {
+                self.cursor = selection;
             }
 
-            self.selection = None;
+            return;
+        }
+
+        if self.move_visually(true, select) {
             return;
         }
 
@@ -225,11 +507,16 @@ impl TextInputState {
         if !select && self.selection.is_some() {
             // if the selection is active, clear it
 
-            if self.cursor > self.selection.unwrap() {
-                self.cursor = self.selection.unwrap();
+            let selection = self.selection.take().unwrap();
+
+            if self.visual_position(selection) < self.visual_position(self.cursor) {
+                self.cursor = selection;
             }
 
-            self.selection = None;
+            return;
+        }
+
+        if self.move_visually(false, select) {
             return;
         }
 
@@ -295,20 +582,86 @@ impl TextInputState {
         self.blink = 0.0;
     }
 
+    // move the cursor one cluster visually, returns false if there is no cluster to move over
+    // on the current line
+    fn move_visually(&mut self, right: bool, select: bool) -> bool {
+        let Some((line_index, offset)) = self.visual_position(self.cursor) else {
+            return false;
+        };
+
+        let glyphs = self.lines[line_index].glyphs.iter();
+
+        let glyph = if right {
+            glyphs
+                .filter(|glyph| glyph.bounds.left() >= offset - 0.01)
+                .min_by(|a, b| f32::total_cmp(&a.bounds.left(), &b.bounds.left()))
+        } else {
+            glyphs
+                .filter(|glyph| glyph.bounds.right() <= offset + 0.01)
+                .max_by(|a, b| f32::total_cmp(&a.bounds.right(), &b.bounds.right()))
+        };
+
+        let Some(glyph) = glyph else {
+            return false;
+        };
+
+        let visual_cursor = match right {
+            true => (right_edge_index(glyph), glyph.bounds.right()),
+            false => (left_edge_index(glyph), glyph.bounds.left()),
+        };
+
+        self.set_cursor(visual_cursor.0, select);
+        self.visual_cursor = Some(visual_cursor);
+
+        true
+    }
+
+    // the line number and the horizontal offset of a cursor at `index`
+    fn visual_position(&self, index: usize) -> Option<(usize, f32)> {
+        if self.lines.is_empty() {
+            return None;
+        }
+
+        Some((self.line_number(index), self.cursor_offset(index)))
+    }
+
     fn get_cursor_offset(&self) -> f32 {
+        self.cursor_offset(self.cursor)
+    }
+
+    fn cursor_offset(&self, index: usize) -> f32 {
         if self.lines.is_empty() {
             return 0.0;
         }
 
-        let line_index = self.current_line_number();
+        if let Some((cursor, offset)) = self.visual_cursor {
+            if cursor == index {
+                return offset;
+            }
+        }
+
+        let line = &self.lines[self.line_number(index)];
 
-        for glyph in &self.lines[line_index].glyphs {
-            if glyph.range.start == self.cursor {
-                return glyph.bounds.left();
+        // the cursor is drawn at the leading edge of the cluster after it, which is the right
+        // edge for right-to-left text
+        for glyph in &line.glyphs {
+            if glyph.range.start == index {
+                return match glyph.direction {
+                    TextDirection::Ltr => glyph.bounds.left(),
+                    TextDirection::Rtl => glyph.bounds.right(),
+                };
             }
         }
 
-        let line = &self.lines[line_index];
+        // at the end of a run the cursor is drawn at the trailing edge of the cluster before it
+        for glyph in &line.glyphs {
+            if glyph.range.end == index {
+                return match glyph.direction {
+                    TextDirection::Ltr => glyph.bounds.right(),
+                    TextDirection::Rtl => glyph.bounds.left(),
+                };
+            }
+        }
 
         if let Some(glyph) = line.glyphs.last() {
             glyph.bounds.right()
@@ -318,8 +671,12 @@ impl TextInputState {
     }
 
     fn current_line_number(&self) -> usize {
+        self.line_number(self.cursor)
+    }
+
+    fn line_number(&self, index: usize) -> usize {
         for (i, line) in self.lines.iter().enumerate() {
-            if self.cursor < line.range.end + 1 {
+            if index < line.range.end + 1 {
                 return i;
             }
         }
@@ -330,13 +687,73 @@ impl TextInputState {
     fn select_point_in_line(&self, line_index: usize, offset: f32) -> usize {
         let line = &self.lines[line_index];
 
-        for glyph in &line.glyphs {
+        let mut glyphs = line.glyphs.iter().collect::<Vec<_>>();
+        glyphs.sort_by(|a, b| f32::total_cmp(&a.bounds.left(), &b.bounds.left()));
+
+        for glyph in &glyphs {
             if offset < glyph.bounds.center().x {
-                return glyph.range.start;
+                return left_edge_index(glyph);
+            }
+        }
+
+        match glyphs.last() {
+            Some(glyph) if glyph.direction == TextDirection::Rtl => glyph.range.start,
+            _ => line.range.end,
+        }
+    }
+
+    // the rects covering the selected clusters, the selection is a range of the text, which is
+    // not necessarily contiguous visually when it spans text of different directions
+    fn selection_rects(&self) -> Vec<Rect> {
+        let Some(selection) = self.selection else {
+            return Vec::new();
+        };
+
+        let start = usize::min(self.cursor, selection);
+        let end = usize::max(self.cursor, selection);
+
+        let mut rects = Vec::new();
+
+        for line in &self.lines {
+            let top = line.top();
+            let bottom = line.bottom();
+
+            if line.glyphs.is_empty() && line.range.start >= start && line.range.end <= end {
+                let rect = Rect::new(
+                    Point::new(line.left(), top),
+                    Point::new(line.left() + 2.0, bottom),
+                );
+
+                rects.push(rect);
+
+                continue;
+            }
+
+            let mut glyphs = line.glyphs.iter().collect::<Vec<_>>();
+            glyphs.sort_by(|a, b| f32::total_cmp(&a.bounds.left(), &b.bounds.left()));
+
+            let mut run: Option<(f32, f32)> = None;
+
+            for glyph in glyphs {
+                let selected = glyph.range.start >= start && glyph.range.end <= end;
+
+                match (selected, run) {
+                    (true, Some((left, _))) => run = Some((left, glyph.bounds.right())),
+                    (true, None) => run = Some((glyph.bounds.left(), glyph.bounds.right())),
+                    (false, Some((left, right))) => {
+                        rects.push(Rect::new(Point::new(left, top), Point::new(right, bottom)));
+                        run = None;
+                    }
+                    (false, None) => {}
+                }
+            }
+
+            if let Some((left, right)) = run {
+                rects.push(Rect::new(Point::new(left, top), Point::new(right, bottom)));
             }
         }
 
-        line.range.end
+        rects
     }
 
     fn select_point(&self, point: Point) -> usize {
@@ -359,10 +776,14 @@ impl<T> View<T> for TextInput<T> {
 
         let style = TextInputStyle::styled(self, cx.styles());
 
+        let text = self.text.clone().unwrap_or_default();
+        let cursor = text.len();
+        let mask = self.mask();
+
         let mut paragraph = Paragraph::new(style.line_height, style.align, style.wrap);
 
         paragraph.set_text(
-            self.text.as_deref().unwrap_or_default(),
+            display_text(&text, mask),
             FontAttributes {
                 size: style.font_size,
                 family: style.font_family.clone(),
@@ -371,15 +792,14 @@ impl<T> View<T> for TextInput<T> {
                 style: style.font_style,
                 ligatures: false,
                 color: style.color,
+                variations: Vec::new(),
             },
         );
 
-        let text = self.text.clone().unwrap_or_default();
-        let cursor = text.len();
-
         TextInputState {
             style,
             text,
+            mask,
             paragraph,
             lines: Vec::new(),
             dragging: false,
@@ -387,6 +807,8 @@ impl<T> View<T> for TextInput<T> {
             blink: 0.0,
             cursor,
             selection: None,
+            visual_cursor: None,
+            history: History::default(),
         }
     }
 
@@ -398,12 +820,24 @@ impl<T> View<T> for TextInput<T> {
                 state.cursor = text.len();
             }
 
+            // the text was changed from outside, so the history no longer applies to it
+            if *text != state.text {
+                state.history.clear();
+            }
+
             state.text = text.clone();
             state.lines.clear();
 
             cx.layout();
         }
 
+        if state.mask != self.mask() {
+            state.mask = self.mask();
+            state.lines.clear();
+
+            cx.layout();
+        }
+
         if state.paragraph.line_height != state.style.line_height
             || state.paragraph.align != state.style.align
             || state.paragraph.wrap != state.style.wrap
@@ -416,7 +850,7 @@ impl<T> View<T> for TextInput<T> {
         }
 
         state.paragraph.set_text(
-            &state.text,
+            display_text(&state.text, state.mask),
             FontAttributes {
                 size: state.style.font_size,
                 family: state.style.font_family.clone(),
@@ -425,6 +859,7 @@ impl<T> View<T> for TextInput<T> {
                 style: state.style.font_style,
                 ligatures: false,
                 color: state.style.color,
+                variations: Vec::new(),
             },
         );
     }
@@ -456,18 +891,28 @@ impl<T> View<T> for TextInput<T> {
         if cx.is_focused() {
             let selection = state.selection.unwrap_or(state.cursor);
 
-            let min = usize::min(state.cursor, selection);
-            let max = usize::max(state.cursor, selection);
+            let mut min = usize::min(state.cursor, selection);
+            let mut max = usize::max(state.cursor, selection);
+
+            // the ime never sees the text of a password field
+            if let Some(mask) = state.mask {
+                min = mask_index(&state.text, min, mask);
+                max = mask_index(&state.text, max, mask);
+            }
 
             cx.set_ime(Some(Ime {
-                text: state.text.clone(),
+                text: display_text(&state.text, state.mask).into_owned(),
                 selection: min..max,
                 compose: None,
                 multiline: self.multiline,
                 capitalize: self.capitalize,
+                password: self.password,
             }));
 
-            cx.animate();
+            // pause the blinking cursor while the window is unfocused
+            if cx.window().focused {
+                cx.animate();
+            }
         } else {
             cx.set_ime(None);
         }
@@ -478,6 +923,7 @@ impl<T> View<T> for TextInput<T> {
                 let cursor = state.select_point(local);
 
                 state.set_cursor(cursor, false);
+                state.history.seal();
                 state.dragging = true;
 
                 cx.focus();
@@ -515,28 +961,37 @@ impl<T> View<T> for TextInput<T> {
                 let mut text_changed = false;
                 let mut text_submitted = false;
 
+                // keys pressed with the command key, alt or meta are shortcuts, not typing
+                let is_chord = e.modifiers.command() || e.modifiers.alt || e.modifiers.meta;
+
                 if let Some(ref text) = e.text {
-                    if !text.chars().any(char::is_control) && !e.modifiers.ctrl {
-                        state.remove_selection();
-                        state.text.insert_str(state.cursor, text);
-                        state.set_cursor(state.cursor + text.len(), false);
+                    if !text.chars().any(char::is_control) && !is_chord {
+                        let text = self.accept_input(text, state.remaining_len());
 
-                        text_changed = true;
+                        if !text.is_empty() {
+                            state.insert_text(&text, EditKind::Typing);
+                            text_changed = true;
+                        }
                     }
                 }
 
-                if e.is_key('v') && e.modifiers.ctrl {
-                    state.remove_selection();
+                // shortcuts fire once when held, unlike navigation and deletion
+                let is_shortcut = e.modifiers.command() && !e.repeat;
 
+                if e.is_key('v') && is_shortcut {
                     let text = cx.clipboard().get();
+                    let text = self.accept_input(&text, state.remaining_len());
 
-                    state.text.insert_str(state.cursor, &text);
-                    state.set_cursor(state.cursor + text.len(), false);
-
-                    text_changed = true;
+                    if !text.is_empty() {
+                        state.insert_text(&text, EditKind::Other);
+                        text_changed = true;
+                    }
                 }
 
-                if e.is_key('c') && e.modifiers.ctrl {
+                // the text of a password field never leaves the input
+                let can_copy = !self.password;
+
+                if e.is_key('c') && is_shortcut && can_copy {
                     if let Some(selection) = state.selection {
                         let start = usize::min(state.cursor, selection);
                         let end = usize::max(state.cursor, selection);
@@ -546,21 +1001,26 @@ impl<T> View<T> for TextInput<T> {
                     }
                 }
 
-                if e.is_key('x') && e.modifiers.ctrl {
-                    if let Some(selection) = state.selection {
-                        let start = usize::min(state.cursor, selection);
-                        let end = usize::max(state.cursor, selection);
+                if e.is_key('x') && is_shortcut && can_copy && state.selection.is_some() {
+                    let text = state.text[state.selected_range()].to_string();
+                    cx.clipboard().set(text);
 
-                        let text = state.text.drain(start..end).collect::<String>();
-                        cx.clipboard().set(text);
+                    state.remove_selection(EditKind::Other);
+                    text_changed = true;
+                }
 
-                        state.set_cursor(start, false);
+                let is_undo = e.is_key('z') && !e.modifiers.shift;
+                let is_redo = e.is_key('y') || (e.is_key('z') && e.modifiers.shift);
 
-                        text_changed = true;
-                    }
+                if is_undo && is_shortcut {
+                    text_changed |= state.undo();
                 }
 
-                if e.is_key(Key::Escape) {
+                if is_redo && is_shortcut {
+                    text_changed |= state.redo();
+                }
+
+                if e.is_key(Key::Escape) && !e.repeat {
                     if state.selection.is_some() {
                         state.selection = None;
                     } else {
@@ -568,15 +1028,16 @@ impl<T> View<T> for TextInput<T> {
                     }
                 }
 
-                if e.is_key(Key::Enter) && self.multiline {
-                    state.remove_selection();
-                    state.text.insert(state.cursor, '\n');
-                    state.set_cursor(state.cursor + 1, false);
+                let at_max_length = self
+                    .max_length
+                    .is_some_and(|max| state.remaining_len() >= max);
 
+                if e.is_key(Key::Enter) && self.multiline && !at_max_length {
+                    state.insert_text("\n", EditKind::Other);
                     text_changed = true;
                 }
 
-                if e.is_key(Key::Enter) && !self.multiline {
+                if e.is_key(Key::Enter) && !self.multiline && !e.repeat {
                     cx.focus_next();
 
                     text_changed = true;
@@ -585,15 +1046,26 @@ impl<T> View<T> for TextInput<T> {
 
                 if e.is_key(Key::Backspace) {
                     if state.selection.is_some() {
-                        state.remove_selection();
+                        state.remove_selection(EditKind::Other);
                         text_changed = true;
                     } else if state.cursor > 0 {
-                        state.move_left(false);
-                        state.text.remove(state.cursor);
+                        let prev_char = state.text[..state.cursor].chars().next_back().unwrap();
+                        let start = state.cursor - prev_char.len_utf8();
+
+                        state.edit(start..state.cursor, "", EditKind::Deleting);
                         text_changed = true;
                     }
                 }
 
+                let is_arrow = e.is_key(Key::Right)
+                    || e.is_key(Key::Left)
+                    || e.is_key(Key::Up)
+                    || e.is_key(Key::Down);
+
+                if is_arrow {
+                    state.history.seal();
+                }
+
                 if e.is_key(Key::Right) {
                     state.move_right(e.modifiers.shift);
                     cx.draw();
@@ -620,7 +1092,7 @@ impl<T> View<T> for TextInput<T> {
                     }
 
                     state.paragraph.set_text(
-                        &state.text,
+                        display_text(&state.text, state.mask),
                         FontAttributes {
                             size: state.style.font_size,
                             family: state.style.font_family.clone(),
@@ -629,6 +1101,7 @@ impl<T> View<T> for TextInput<T> {
                             style: state.style.font_style,
                             ligatures: false,
                             color: state.style.color,
+                            variations: Vec::new(),
                         },
                     );
 
@@ -646,6 +1119,12 @@ impl<T> View<T> for TextInput<T> {
                 true
             }
 
+            Event::WindowFocusChanged(_) => {
+                state.blink = 0.0;
+                cx.draw();
+
+                false
+            }
             Event::Animate(dt) => {
                 state.blink += *dt;
 
@@ -684,6 +1163,7 @@ impl<T> View<T> for TextInput<T> {
                     style: state.style.font_style,
                     ligatures: false,
                     color: state.style.placeholder_color,
+                    variations: Vec::new(),
                 },
             );
 
@@ -696,6 +1176,11 @@ impl<T> View<T> for TextInput<T> {
         }
 
         state.lines = cx.layout_paragraph(&state.paragraph, space.max.width);
+        state.visual_cursor = None;
+
+        if let Some(mask) = state.mask {
+            unmask_lines(&mut state.lines, &state.text, mask);
+        }
 
         let mut size = cx.measure_paragraph(&state.paragraph, space.max.width);
 
@@ -710,7 +1195,7 @@ impl<T> View<T> for TextInput<T> {
 
         if !state.text.is_empty() {
             cx.paragraph(&state.paragraph, cx.rect());
-        } else {
+        } else if !(self.hide_placeholder_on_focus && cx.is_focused()) {
             let mut placeholder = Paragraph::new(
                 // please, don't make this ugly rustfmt
                 state.style.line_height,
@@ -728,6 +1213,7 @@ impl<T> View<T> for TextInput<T> {
                     style: state.style.font_style,
                     ligatures: false,
                     color: state.style.placeholder_color,
+                    variations: Vec::new(),
                 },
             );
 
@@ -751,45 +1237,24 @@ impl<T> View<T> for TextInput<T> {
 }
 
 fn draw_highlight(state: &mut TextInputState, cx: &mut DrawCx, color: Color) {
-    if let Some(selection) = state.selection {
-        let start = usize::min(state.cursor, selection);
-        let end = usize::max(state.cursor, selection);
-
-        for line in &state.lines {
-            let top = line.top();
-            let bottom = line.bottom();
-
-            if line.glyphs.is_empty() && line.range.start >= start && line.range.end <= end {
-                let rect = Rect::new(
-                    Point::new(line.left(), top),
-                    Point::new(line.left() + 2.0, bottom),
-                );
-
-                cx.fill_rect(rect, color);
-
-                continue;
-            }
-
-            let mut left = line.right();
-            let mut right = line.left();
-
-            for glyph in &line.glyphs {
-                if start <= glyph.range.start && end >= glyph.range.start {
-                    left = f32::min(left, glyph.bounds.left());
-                }
-
-                if start <= glyph.range.end && end >= glyph.range.end {
-                    right = f32::max(right, glyph.bounds.right());
-                }
-            }
+    for rect in state.selection_rects() {
+        cx.fill_rect(rect, color);
+    }
+}
 
-            if left >= right {
-                continue;
-            }
+// the index of a cursor at the left edge of a cluster
+fn left_edge_index(glyph: &GlyphCluster) -> usize {
+    match glyph.direction {
+        TextDirection::Ltr => glyph.range.start,
+        TextDirection::Rtl => glyph.range.end,
+    }
+}
 
-            let rect = Rect::new(Point::new(left, top), Point::new(right, bottom));
-            cx.fill_rect(rect, color);
-        }
+// the index of a cursor at the right edge of a cluster
+fn right_edge_index(glyph: &GlyphCluster) -> usize {
+    match glyph.direction {
+        TextDirection::Ltr => glyph.range.end,
+        TextDirection::Rtl => glyph.range.start,
     }
 }
 
@@ -813,3 +1278,390 @@ fn draw_cursor(state: &mut TextInputState, cx: &mut DrawCx, color: Color) {
 
     cx.fill_rect(rect, color);
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        canvas::Primitive,
+        clipboard::{Clipboard, ClipboardBackend},
+        event::{KeyPressed, Modifiers},
+        text::{FontSource, Fonts},
+        views::testing::ViewTester,
+    };
+
+    use super::*;
+
+    // a monospace font on a single line, where hebrew letters are right-to-left
+    struct BidiFonts;
+
+    impl BidiFonts {
+        const ADVANCE: f32 = 8.0;
+        const HEIGHT: f32 = 20.0;
+
+        fn direction(c: char) -> TextDirection {
+            match c {
+                '\u{0590}'..='\u{05ff}' => TextDirection::Rtl,
+                _ => TextDirection::Ltr,
+            }
+        }
+    }
+
+    impl Fonts for BidiFonts {
+        fn load(&mut self, _source: FontSource<'_>, _name: Option<&str>) {}
+
+        fn layout(&mut self, paragraph: &Paragraph, _width: f32) -> Vec<TextLayoutLine> {
+            let text = paragraph.text();
+
+            // split the text into runs of the same direction
+            let mut runs = Vec::<(TextDirection, Vec<(usize, char)>)>::new();
+
+            for (index, c) in text.char_indices() {
+                let direction = Self::direction(c);
+
+                match runs.last_mut() {
+                    Some((run, chars)) if *run == direction => chars.push((index, c)),
+                    _ => runs.push((direction, vec![(index, c)])),
+                }
+            }
+
+            let mut glyphs = Vec::new();
+
+            for (direction, mut chars) in runs {
+                if direction == TextDirection::Rtl {
+                    chars.reverse();
+                }
+
+                for (index, c) in chars {
+                    let left = glyphs.len() as f32 * Self::ADVANCE;
+
+                    glyphs.push(GlyphCluster {
+                        bounds: Rect::min_size(
+                            Point::new(left, 0.0),
+                            Size::new(Self::ADVANCE, Self::HEIGHT),
+                        ),
+                        range: index..index + c.len_utf8(),
+                        direction,
+                    });
+                }
+            }
+
+            vec![TextLayoutLine {
+                ascent: Self::HEIGHT,
+                descent: 0.0,
+                left: 0.0,
+                width: glyphs.len() as f32 * Self::ADVANCE,
+                height: Self::HEIGHT,
+                baseline: Self::HEIGHT,
+                range: 0..text.len(),
+                glyphs,
+            }]
+        }
+
+        fn measure(&mut self, paragraph: &Paragraph, width: f32) -> Size {
+            let lines = self.layout(paragraph, width);
+            Size::new(lines[0].width, Self::HEIGHT)
+        }
+    }
+
+    fn layout_input(text: &str) -> TextInputState {
+        let mut view = text_input::<()>().text(text);
+
+        let mut tester = ViewTester::new(&mut view, &mut ());
+        (tester.contexts).insert::<Box<dyn Fonts>>(Box::new(BidiFonts));
+        tester.layout(&mut view, &mut (), Space::UNBOUNDED);
+
+        tester.state
+    }
+
+    #[test]
+    fn rtl_cursor_moves_visually() {
+        // the letters are laid out right to left, 'ג' at the left edge and 'א' at the right
+        let mut state = layout_input("אבג");
+
+        state.set_cursor(0, false);
+        assert_eq!(state.get_cursor_offset(), 24.0);
+
+        state.move_left(false);
+        assert_eq!(state.cursor, 2);
+        assert_eq!(state.get_cursor_offset(), 16.0);
+
+        state.move_left(false);
+        assert_eq!(state.cursor, 4);
+        assert_eq!(state.get_cursor_offset(), 8.0);
+
+        state.move_right(false);
+        assert_eq!(state.cursor, 2);
+        assert_eq!(state.get_cursor_offset(), 16.0);
+    }
+
+    #[test]
+    fn bidi_selection_rects() {
+        // laid out as "abבאcd", selecting "bא" is split visually around 'ב'
+        let mut state = layout_input("abאבcd");
+
+        state.set_cursor(1, false);
+        state.set_cursor(4, true);
+
+        let rects = state.selection_rects();
+
+        assert_eq!(
+            rects,
+            vec![
+                Rect::new(Point::new(8.0, 0.0), Point::new(16.0, 20.0)),
+                Rect::new(Point::new(24.0, 0.0), Point::new(32.0, 20.0)),
+            ]
+        );
+    }
+
+    fn type_text(state: &mut TextInputState, text: &str) {
+        for c in text.chars() {
+            state.insert_text(&c.to_string(), EditKind::Typing);
+        }
+    }
+
+    #[test]
+    fn typed_words_undo_together() {
+        let mut state = layout_input("");
+
+        type_text(&mut state, "hello world");
+        assert_eq!(state.history.undo.len(), 2);
+
+        assert!(state.undo());
+        assert_eq!(state.text, "hello ");
+        assert_eq!(state.cursor, 6);
+
+        assert!(state.undo());
+        assert_eq!(state.text, "");
+        assert_eq!(state.cursor, 0);
+        assert!(!state.undo());
+    }
+
+    #[test]
+    fn redo_after_undo() {
+        let mut state = layout_input("ab");
+
+        state.set_cursor(0, false);
+        state.set_cursor(2, true);
+        state.insert_text("cd", EditKind::Other);
+
+        assert!(state.undo());
+        assert_eq!(state.text, "ab");
+        assert_eq!((state.cursor, state.selection), (2, Some(0)));
+
+        assert!(state.redo());
+        assert_eq!(state.text, "cd");
+        assert_eq!((state.cursor, state.selection), (2, None));
+
+        // a new edit clears the redo stack
+        assert!(state.undo());
+        type_text(&mut state, "x");
+        assert!(!state.redo());
+        assert_eq!(state.text, "x");
+    }
+
+    #[test]
+    fn max_length() {
+        assert_eq!(accept_input("a", 2, Some(3), None), "a");
+        assert_eq!(accept_input("a", 3, Some(3), None), "");
+        assert_eq!(accept_input("åäö", 0, Some(3), None), "åäö");
+    }
+
+    #[test]
+    fn max_length_paste() {
+        assert_eq!(accept_input("hello world", 5, Some(8), None), "hel");
+        assert_eq!(accept_input("hello world", 8, Some(8), None), "");
+    }
+
+    #[test]
+    fn char_filter() {
+        let filter = |c: char| c.is_ascii_digit();
+        assert_eq!(accept_input("a1b2c3", 0, None, Some(&filter)), "123");
+        assert_eq!(accept_input("a1b2c3", 0, Some(2), Some(&filter)), "12");
+    }
+
+    fn key_pressed(key: Key, text: Option<&str>, modifiers: Modifiers, repeat: bool) -> Event {
+        Event::KeyPressed(KeyPressed {
+            key,
+            code: None,
+            text: text.map(String::from),
+            modifiers,
+            repeat,
+        })
+    }
+
+    // a clipboard that always holds the same text
+    struct PasteClipboard(&'static str);
+
+    impl ClipboardBackend for PasteClipboard {
+        fn get_text(&mut self) -> String {
+            self.0.to_string()
+        }
+
+        fn set_text(&mut self, _text: &str) {}
+    }
+
+    #[test]
+    fn typed_committed_and_pasted_text_is_limited_and_filtered() {
+        let mut view = text_input()
+            .max_length(6)
+            .char_filter(|c| c.is_ascii_digit())
+            .on_input(|_, data: &mut String, text| {
+                *data = text;
+            });
+
+        let mut data = String::new();
+        let mut tester = ViewTester::new(&mut view, &mut data);
+        (tester.contexts).insert::<Box<dyn Fonts>>(Box::new(BidiFonts));
+        (tester.contexts).insert(Clipboard::new(Box::new(PasteClipboard("7a8b9c0"))));
+        tester.view_state.set_focused(true);
+        tester.layout(&mut view, &mut data, Space::UNBOUNDED);
+
+        let none = Modifiers::default();
+
+        // typed characters rejected by the filter never reach the text
+        for c in ["1", "x", "2"] {
+            let key = key_pressed(Key::Character('x'), Some(c), none, false);
+            tester.event(&mut view, &mut data, &key);
+        }
+
+        assert_eq!(data, "12");
+
+        // the ime commits whole words as a single key press
+        let commit = key_pressed(Key::Unidentified, Some("3four5"), none, false);
+        tester.event(&mut view, &mut data, &commit);
+        assert_eq!(data, "1235");
+
+        // a paste is filtered first, then cut off at the limit
+        let paste = key_pressed(Key::Character('v'), None, Modifiers::COMMAND, false);
+        tester.event(&mut view, &mut data, &paste);
+        assert_eq!(data, "123578");
+
+        let key = key_pressed(Key::Character('9'), Some("9"), none, false);
+        tester.event(&mut view, &mut data, &key);
+        tester.event(&mut view, &mut data, &commit);
+        assert_eq!(tester.state.text, "123578");
+
+        // deleting frees up room again
+        let backspace = key_pressed(Key::Backspace, None, none, false);
+        tester.event(&mut view, &mut data, &backspace);
+        tester.event(&mut view, &mut data, &key);
+        assert_eq!(data, "123579");
+    }
+
+    #[test]
+    fn held_arrow_repeats_and_held_shortcut_fires_once() {
+        let mut view = text_input::<()>();
+
+        let mut tester = ViewTester::new(&mut view, &mut ());
+        (tester.contexts).insert::<Box<dyn Fonts>>(Box::new(BidiFonts));
+        tester.view_state.set_focused(true);
+        tester.layout(&mut view, &mut (), Space::UNBOUNDED);
+
+        let none = Modifiers::default();
+        let command = Modifiers {
+            ctrl: true,
+            meta: true,
+            ..Modifiers::default()
+        };
+
+        // two edits, separated in the history by moving the cursor
+        let type_a = key_pressed(Key::Character('a'), Some("a"), none, false);
+        let type_b = key_pressed(Key::Character('b'), Some("b"), none, false);
+        tester.event(&mut view, &mut (), &type_a);
+        tester.event(
+            &mut view,
+            &mut (),
+            &key_pressed(Key::Left, None, none, false),
+        );
+        tester.event(
+            &mut view,
+            &mut (),
+            &key_pressed(Key::Right, None, none, false),
+        );
+        tester.event(&mut view, &mut (), &type_b);
+        assert_eq!(tester.state.text, "ab");
+
+        let undo = |repeat| key_pressed(Key::Character('z'), None, command, repeat);
+        tester.event(&mut view, &mut (), &undo(false));
+        tester.event(&mut view, &mut (), &undo(true));
+        tester.event(&mut view, &mut (), &undo(true));
+        assert_eq!(tester.state.text, "a");
+
+        // moving the cursor repeats while the key is held
+        let left = |repeat| key_pressed(Key::Left, None, none, repeat);
+        tester.event(&mut view, &mut (), &type_b);
+        tester.event(&mut view, &mut (), &type_b);
+        assert_eq!(
+            (tester.state.text.as_str(), tester.state.cursor),
+            ("abb", 3)
+        );
+
+        tester.event(&mut view, &mut (), &left(false));
+        tester.event(&mut view, &mut (), &left(true));
+        tester.event(&mut view, &mut (), &left(true));
+        assert_eq!(tester.state.cursor, 0);
+    }
+
+    #[test]
+    fn chords_do_not_type_text() {
+        let mut view = text_input::<()>();
+
+        let mut tester = ViewTester::new(&mut view, &mut ());
+        (tester.contexts).insert::<Box<dyn Fonts>>(Box::new(BidiFonts));
+        tester.view_state.set_focused(true);
+        tester.layout(&mut view, &mut (), Space::UNBOUNDED);
+
+        let chords = [Modifiers::COMMAND, Modifiers::ALT, Modifiers::META];
+
+        for modifiers in chords {
+            let event = key_pressed(Key::Character('x'), Some("x"), modifiers, false);
+            tester.event(&mut view, &mut (), &event);
+        }
+
+        assert_eq!(tester.state.text, "");
+
+        let event = key_pressed(Key::Character('X'), Some("X"), Modifiers::SHIFT, false);
+        tester.event(&mut view, &mut (), &event);
+        assert_eq!(tester.state.text, "X");
+    }
+
+    #[test]
+    fn password_draws_mask_and_keeps_text() {
+        let mut view = text_input()
+            .password(true)
+            .on_input(|_, data: &mut String, text| {
+                *data = text;
+            });
+
+        let mut data = String::new();
+        let mut tester = ViewTester::new(&mut view, &mut data);
+        (tester.contexts).insert::<Box<dyn Fonts>>(Box::new(BidiFonts));
+        tester.view_state.set_focused(true);
+
+        let none = Modifiers::default();
+
+        // the `a` and the combining accent are a single grapheme, masked by a single bullet
+        for c in ["p", "a", "\u{301}", "s"] {
+            let key = key_pressed(Key::Character('x'), Some(c), none, false);
+            tester.event(&mut view, &mut data, &key);
+        }
+
+        assert_eq!(data, "pa\u{301}s");
+
+        tester.layout(&mut view, &mut data, Space::UNBOUNDED);
+        let canvas = tester.draw(&mut view, &mut data);
+
+        let text = canvas.primitives().find_map(|primitive| match primitive {
+            Primitive::Paragraph { paragraph, .. } => Some(paragraph.text().to_string()),
+            _ => None,
+        });
+
+        assert_eq!(text.as_deref(), Some("\u{2022}\u{2022}\u{2022}"));
+
+        // the cursor moves over the real characters
+        let left = key_pressed(Key::Left, None, none, false);
+        tester.event(&mut view, &mut data, &left);
+        assert_eq!(tester.state.cursor, 4);
+        assert_eq!(tester.state.get_cursor_offset(), 16.0);
+    }
+}
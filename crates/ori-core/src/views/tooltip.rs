@@ -4,7 +4,7 @@ use smol_str::SmolStr;
 use crate::{
     canvas::{BorderRadius, BorderWidth, Color},
     context::{BuildCx, DrawCx, EventCx, LayoutCx, RebuildCx},
-    event::Event,
+    event::{Event, Key},
     layout::{pt, Padding, Point, Rect, Size, Space, Vector},
     rebuild::Rebuild,
     style::{Styled, Theme},
@@ -22,6 +22,9 @@ pub fn tooltip<V>(view: V, text: impl Into<SmolStr>) -> Tooltip<V> {
 
 /// A view that displays some text when the content is hovered.
 ///
+/// The text is also displayed while the content is focused, so it's available to keyboard
+/// users, and can be dismissed with `Escape` without moving the focus.
+///
 /// Can be styled using the [`TooltipStyle`].
 #[example(name = "tooltip", width = 400, height = 300)]
 #[derive(Styled, Rebuild)]
@@ -131,6 +134,11 @@ pub struct TooltipState {
     pub timer: f32,
     pub position: Point,
     pub style: TooltipStyle,
+
+    // whether the tooltip is shown because the content is focused, and whether it was
+    // dismissed since the content was focused
+    pub focused: bool,
+    pub dismissed: bool,
 }
 
 impl<T, V: View<T>> View<T> for Tooltip<V> {
@@ -146,6 +154,8 @@ impl<T, V: View<T>> View<T> for Tooltip<V> {
             timer: 0.0,
             position: Point::ZERO,
             style,
+            focused: false,
+            dismissed: false,
         };
 
         state.paragraph.set_text(
@@ -158,6 +168,7 @@ impl<T, V: View<T>> View<T> for Tooltip<V> {
                 style: state.style.font_style,
                 ligatures: true,
                 color: state.style.color,
+                variations: Vec::new(),
             },
         );
 
@@ -188,6 +199,7 @@ impl<T, V: View<T>> View<T> for Tooltip<V> {
                 style: state.style.font_style,
                 ligatures: true,
                 color: state.style.color,
+                variations: Vec::new(),
             },
         );
 
@@ -203,7 +215,25 @@ impl<T, V: View<T>> View<T> for Tooltip<V> {
     ) -> bool {
         let handled = self.content.event(content, cx, data, event);
 
-        if !content.has_hovered() && state.timer > 0.0 {
+        if !content.has_focused() {
+            state.dismissed = false;
+        }
+
+        let focused = content.has_focused() && !state.dismissed;
+
+        if focused != state.focused {
+            state.focused = focused;
+
+            // keyboard users don't wait for the delay
+            state.timer = match focused {
+                true => 1.0,
+                false => 0.0,
+            };
+
+            cx.draw();
+        }
+
+        if !content.has_hovered() && !state.focused && state.timer > 0.0 {
             state.timer = 0.0;
             cx.draw();
         }
@@ -214,8 +244,16 @@ impl<T, V: View<T>> View<T> for Tooltip<V> {
 
                 handled
             }
+            Event::KeyPressed(e) if e.is_key(Key::Escape) && state.timer > 0.0 => {
+                state.dismissed = content.has_focused();
+                state.focused = false;
+                state.timer = 0.0;
+                cx.draw();
+
+                handled
+            }
             Event::PointerMoved(e) => {
-                if state.timer > 0.0 {
+                if state.timer > 0.0 && !state.focused {
                     state.timer = 0.0;
                     cx.draw();
                 }
@@ -272,36 +310,84 @@ impl<T, V: View<T>> View<T> for Tooltip<V> {
             return;
         }
 
-        // we need to try to move the tooltip so it fits on the screen
-        let window_rect = Rect::min_size(Point::ZERO, cx.window().size);
-        let text_size = cx.fonts().measure(&state.paragraph, window_rect.width());
+        // the text wraps at the width of the window, and the tooltip is moved to fit where
+        // popups can be placed
+        let max_width = cx.window().content_size().width;
+        let text_size = cx.fonts().measure(&state.paragraph, max_width);
 
         let size = text_size + state.style.padding.size();
-        let mut offset = Vector::new(-size.width / 2.0, 20.0);
-
-        let rect = Rect::min_size(state.position + offset, size);
-
-        let tl_delta = window_rect.top_left() - rect.top_left();
-        let br_delta = rect.bottom_right() - window_rect.bottom_right();
-
-        offset += Vector::max(tl_delta, Vector::ZERO);
-        offset -= Vector::max(br_delta, Vector::ZERO);
-
-        cx.overlay(0, |cx| {
-            cx.translated(Vector::from(state.position + offset), |cx| {
-                cx.quad(
-                    Rect::min_size(Point::ZERO, size),
-                    state.style.background.fade(alpha),
-                    state.style.border_radius,
-                    state.style.border_width,
-                    state.style.border_color.fade(alpha),
-                );
-
-                cx.paragraph(
-                    &state.paragraph,
-                    Rect::min_size(state.style.padding.offset().to_point(), text_size),
-                );
-            });
+
+        let position = match state.focused && !content.has_hovered() {
+            // without a pointer, the tooltip is placed below the content
+            true => {
+                let bottom = cx.transform() * content.rect().bottom_center();
+                bottom + Vector::new(-size.width / 2.0, 4.0)
+            }
+            false => state.position + Vector::new(-size.width / 2.0, 20.0),
+        };
+
+        let rect = Rect::min_size(position, size).shift_inside(cx.popup_bounds());
+
+        // tooltips don't grab the pointer, they close when the content isn't hovered
+        cx.popup(rect, false, |cx| {
+            cx.quad(
+                Rect::min_size(Point::ZERO, size),
+                state.style.background.fade(alpha),
+                state.style.border_radius,
+                state.style.border_width,
+                state.style.border_color.fade(alpha),
+            );
+
+            cx.paragraph(
+                &state.paragraph,
+                Rect::min_size(state.style.padding.offset().to_point(), text_size),
+            );
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        event::{FocusTarget, KeyPressed, Modifiers},
+        views::{on_build, size, testing::ViewTester},
+    };
+
+    use super::*;
+
+    #[test]
+    fn focus_shows_and_escape_dismisses() {
+        let focusable = on_build(size(20.0, ()), |cx, _| {
+            cx.set_focusable(true);
         });
+        let mut view = tooltip(focusable, "Save the file");
+
+        let mut tester = ViewTester::new(&mut view, &mut ());
+        tester.layout(&mut view, &mut (), Space::UNBOUNDED);
+
+        let focus = Event::FocusGiven(FocusTarget::Next);
+        tester.event(&mut view, &mut (), &focus);
+
+        let (state, content) = &tester.state;
+        assert!(content.is_focused());
+        assert!(state.focused);
+        assert_eq!(state.timer, 1.0);
+
+        let escape = Event::KeyPressed(KeyPressed {
+            key: Key::Escape,
+            code: None,
+            text: None,
+            modifiers: Modifiers::default(),
+            repeat: false,
+        });
+        tester.event(&mut view, &mut (), &escape);
+
+        // the tooltip stays hidden while the focus remains
+        tester.event(&mut view, &mut (), &Event::Animate(0.1));
+
+        let (state, content) = &tester.state;
+        assert!(content.is_focused());
+        assert!(!state.focused);
+        assert_eq!(state.timer, 0.0);
     }
 }
@@ -0,0 +1,252 @@
+use crate::{
+    context::{BuildCx, DrawCx, EventCx, LayoutCx, RebuildCx},
+    event::Event,
+    layout::{Size, Space},
+    rebuild::Rebuild,
+    view::{Pod, State, View},
+};
+
+/// Create a new [`Visibility`] view.
+pub fn visibility<V>(visibility: VisibilityMode, content: V) -> Visibility<V> {
+    Visibility::new(visibility, content)
+}
+
+/// Whether a view is shown, and whether it takes up space when it isn't.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum VisibilityMode {
+    /// The view is laid out, drawn and receives events.
+    #[default]
+    Visible,
+
+    /// The view is laid out and takes up space, but isn't drawn and doesn't receive input.
+    Hidden,
+
+    /// The view isn't laid out or drawn, doesn't receive input and takes up no space.
+    Collapsed,
+}
+
+impl From<bool> for VisibilityMode {
+    fn from(visible: bool) -> Self {
+        match visible {
+            true => VisibilityMode::Visible,
+            false => VisibilityMode::Collapsed,
+        }
+    }
+}
+
+/// A view that can hide its content, see [`VisibilityMode`].
+///
+/// Unlike an `Option<V>`, the content keeps its state while it isn't visible, and with
+/// [`VisibilityMode::Hidden`] it keeps its place in the layout, so toggling eg. a spinner
+/// doesn't move the views around it.
+///
+/// Content that isn't visible still receives commands, animation frames and window events,
+/// only pointer and keyboard input is withheld, and it loses the focus and hover.
+#[derive(Rebuild)]
+pub struct Visibility<V> {
+    /// The content.
+    pub content: Pod<V>,
+
+    /// The visibility of the content.
+    #[rebuild(layout)]
+    pub visibility: VisibilityMode,
+}
+
+impl<V> Visibility<V> {
+    /// Create a new [`Visibility`] view.
+    pub fn new(visibility: VisibilityMode, content: V) -> Self {
+        Self {
+            content: Pod::new(content),
+            visibility,
+        }
+    }
+}
+
+impl<T, V: View<T>> View<T> for Visibility<V> {
+    type State = State<T, V>;
+
+    fn build(&mut self, cx: &mut BuildCx, data: &mut T) -> Self::State {
+        self.content.build(cx, data)
+    }
+
+    fn rebuild(&mut self, state: &mut Self::State, cx: &mut RebuildCx, data: &mut T, old: &Self) {
+        Rebuild::rebuild(self, cx, old);
+
+        self.content.rebuild(state, cx, data, &old.content);
+    }
+
+    fn event(
+        &mut self,
+        state: &mut Self::State,
+        cx: &mut EventCx,
+        data: &mut T,
+        event: &Event,
+    ) -> bool {
+        if self.visibility == VisibilityMode::Visible {
+            return self.content.event(state, cx, data, event);
+        }
+
+        match event {
+            Event::PointerMoved(_)
+            | Event::PointerPressed(_)
+            | Event::PointerReleased(_)
+            | Event::PointerScrolled(_)
+            | Event::KeyPressed(_)
+            | Event::KeyReleased(_)
+            | Event::FocusGiven(_) => {
+                // the focused view gives up the focus, and hovered views update their hover
+                // from the window, where the content isn't hovered since it's not drawn
+                if state.has_focused() {
+                    let _ = self.content.event(state, cx, data, &Event::FocusWanted);
+                }
+
+                if state.has_hovered() {
+                    let _ = self.content.event(state, cx, data, &Event::Notify);
+                }
+
+                false
+            }
+            _ => self.content.event(state, cx, data, event),
+        }
+    }
+
+    fn layout(
+        &mut self,
+        state: &mut Self::State,
+        cx: &mut LayoutCx,
+        data: &mut T,
+        space: Space,
+    ) -> Size {
+        match self.visibility {
+            VisibilityMode::Visible | VisibilityMode::Hidden => {
+                self.content.layout(state, cx, data, space)
+            }
+            VisibilityMode::Collapsed => space.min,
+        }
+    }
+
+    fn draw(&mut self, state: &mut Self::State, cx: &mut DrawCx, data: &mut T) {
+        if self.visibility == VisibilityMode::Visible {
+            self.content.draw(state, cx, data);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        canvas::Color,
+        command::Command,
+        event::{Key, KeyPressed, Modifiers, PointerButton},
+        layout::Point,
+        view::ViewId,
+        views::{on_event, painter, size, testing::ViewTester},
+    };
+
+    use super::*;
+
+    fn swatch<T>(visibility: VisibilityMode) -> Visibility<impl View<T>> {
+        let swatch = painter(|cx, _| cx.fill_rect(cx.rect(), Color::RED));
+        self::visibility(visibility, size(Size::new(40.0, 20.0), swatch))
+    }
+
+    #[test]
+    fn hidden_keeps_its_size_but_draws_nothing() {
+        let mut view = swatch(VisibilityMode::Hidden);
+        let mut tester = ViewTester::new(&mut view, &mut ());
+
+        let size = tester.layout(&mut view, &mut (), Space::UNBOUNDED);
+        assert_eq!(size, Size::new(40.0, 20.0));
+
+        let canvas = tester.draw(&mut view, &mut ());
+        assert_eq!(canvas.primitives().count(), 0);
+
+        // the same content draws when visible
+        let mut view = swatch(VisibilityMode::Visible);
+        let mut tester = ViewTester::new(&mut view, &mut ());
+        tester.layout(&mut view, &mut (), Space::UNBOUNDED);
+        assert!(tester.draw(&mut view, &mut ()).primitives().count() > 0);
+    }
+
+    #[test]
+    fn collapsed_takes_up_no_space() {
+        let mut view = swatch(VisibilityMode::Collapsed);
+        let mut tester = ViewTester::new(&mut view, &mut ());
+
+        let size = tester.layout(&mut view, &mut (), Space::UNBOUNDED);
+        assert_eq!(size, Size::ZERO);
+    }
+
+    fn counted(visibility: VisibilityMode) -> Visibility<impl View<usize>> {
+        let content = on_event(
+            swatch(VisibilityMode::Visible),
+            |_, count: &mut usize, _| {
+                *count += 1;
+                false
+            },
+        );
+
+        self::visibility(visibility, content)
+    }
+
+    fn key_pressed() -> Event {
+        Event::KeyPressed(KeyPressed {
+            key: Key::Enter,
+            code: None,
+            text: None,
+            modifiers: Modifiers::default(),
+            repeat: false,
+        })
+    }
+
+    #[test]
+    fn hidden_content_receives_commands_but_not_input() {
+        let mut view = counted(VisibilityMode::Hidden);
+        let mut count = 0;
+        let mut tester = ViewTester::new(&mut view, &mut count);
+        tester.layout(&mut view, &mut count, Space::UNBOUNDED);
+
+        tester.event(&mut view, &mut count, &key_pressed());
+        tester.press(
+            &mut view,
+            &mut count,
+            Point::new(10.0, 10.0),
+            PointerButton::Primary,
+        );
+        assert_eq!(count, 0);
+
+        let command = Event::Command(Command::new(()));
+        tester.event(&mut view, &mut count, &command);
+
+        // animation frames are delivered to content that asked for them
+        tester.state.request_animate();
+        tester.event(&mut view, &mut count, &Event::Animate(0.1));
+        assert_eq!(count, 2);
+    }
+
+    #[test]
+    fn hiding_clears_focus_and_hover() {
+        let mut view = counted(VisibilityMode::Visible);
+        let mut count = 0;
+        let mut tester = ViewTester::new(&mut view, &mut count);
+        tester.layout(&mut view, &mut count, Space::UNBOUNDED);
+
+        let id = tester.state.id();
+        tester.hover(id, Point::new(10.0, 10.0));
+        tester.state.set_focused(true);
+        tester.event(&mut view, &mut count, &Event::Notify);
+        assert!(tester.state.is_hovered());
+
+        let old = counted(VisibilityMode::Visible);
+        view = counted(VisibilityMode::Hidden);
+        tester.rebuild(&mut view, &mut count, &old);
+
+        tester.event(&mut view, &mut count, &key_pressed());
+        assert!(!tester.state.has_focused());
+
+        // the window no longer hovers the content, since it isn't drawn
+        tester.hover(ViewId::new(), Point::new(10.0, 10.0));
+        tester.move_to(&mut view, &mut count, Point::new(12.0, 10.0));
+        assert!(!tester.state.has_hovered());
+    }
+}
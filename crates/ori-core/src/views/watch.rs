@@ -0,0 +1,335 @@
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        Arc, Mutex, MutexGuard,
+    },
+};
+
+use crate::{
+    command::CommandProxy,
+    context::{BaseCx, BuildCx, DrawCx, EventCx, LayoutCx, RebuildCx},
+    event::Event,
+    layout::{Size, Space},
+    view::{Pod, State, View},
+};
+
+/// Create a new [`Watch`] view.
+pub fn watch<S, V>(signal: &Signal<S>, view: impl FnMut(&S) -> V + 'static) -> Watch<S, V> {
+    Watch::new(signal, view)
+}
+
+struct SignalInner<S> {
+    id: usize,
+    value: Mutex<Arc<S>>,
+    version: AtomicU64,
+}
+
+/// A shared value that rebuilds the [`Watch`] views subscribed to it when it changes.
+///
+/// Setting a signal doesn't rebuild the whole window, only the watching views are rebuilt,
+/// which is useful for state that changes often but is only displayed in a small part of a
+/// large user interface.
+///
+/// Signals can be cloned cheaply, every clone refers to the same value.
+pub struct Signal<S> {
+    inner: Arc<SignalInner<S>>,
+}
+
+impl<S> Clone for Signal<S> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<S: fmt::Debug> fmt::Debug for Signal<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Signal").field(&**self.lock()).finish()
+    }
+}
+
+impl<S: Default> Default for Signal<S> {
+    fn default() -> Self {
+        Self::new(S::default())
+    }
+}
+
+// the command sent when a signal changes
+struct SignalChanged {
+    signal: usize,
+}
+
+impl<S> Signal<S> {
+    /// Create a new [`Signal`].
+    pub fn new(value: S) -> Self {
+        // ids are never reused, unlike the address of the value after it's dropped
+        static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+        Self {
+            inner: Arc::new(SignalInner {
+                id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+                value: Mutex::new(Arc::new(value)),
+                version: AtomicU64::new(0),
+            }),
+        }
+    }
+
+    /// Get a clone of the value.
+    pub fn get(&self) -> S
+    where
+        S: Clone,
+    {
+        S::clone(&self.lock())
+    }
+
+    /// Call `f` with a reference to the value.
+    ///
+    /// The signal isn't locked while `f` runs, so `f` may get or set the signal itself.
+    pub fn with<R>(&self, f: impl FnOnce(&S) -> R) -> R {
+        let value = self.lock().clone();
+        f(&value)
+    }
+
+    /// Set the value, and rebuild the views watching the signal.
+    pub fn set(&self, cx: &mut BaseCx, value: S) {
+        self.replace(value);
+        cx.cmd(self.changed());
+    }
+
+    /// Modify the value, and rebuild the views watching the signal.
+    ///
+    /// The value is cloned first if it's being read by [`Signal::with`].
+    pub fn update(&self, cx: &mut BaseCx, f: impl FnOnce(&mut S))
+    where
+        S: Clone,
+    {
+        f(Arc::make_mut(&mut self.lock()));
+        self.inner.version.fetch_add(1, Ordering::SeqCst);
+        cx.cmd(self.changed());
+    }
+
+    /// Set the value from outside of a view, eg. from another thread.
+    pub fn set_with_proxy(&self, proxy: &CommandProxy, value: S) {
+        self.replace(value);
+        proxy.cmd(self.changed());
+    }
+
+    fn replace(&self, value: S) {
+        *self.lock() = Arc::new(value);
+        self.inner.version.fetch_add(1, Ordering::SeqCst);
+    }
+
+    fn changed(&self) -> SignalChanged {
+        SignalChanged { signal: self.id() }
+    }
+
+    fn version(&self) -> u64 {
+        self.inner.version.load(Ordering::SeqCst)
+    }
+
+    fn id(&self) -> usize {
+        self.inner.id
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Arc<S>> {
+        // a panic while the value was modified doesn't leave it in an invalid state
+        self.inner
+            .value
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+/// A view that builds its content from a [`Signal`], and rebuilds it when the signal changes.
+///
+/// Only the content is rebuilt when the signal changes, not the rest of the window. The
+/// content is also rebuilt when the window is rebuilt.
+pub struct Watch<S, V> {
+    signal: Signal<S>,
+    view: Box<dyn FnMut(&S) -> V>,
+}
+
+impl<S, V> Watch<S, V> {
+    /// Create a new [`Watch`] view.
+    pub fn new(signal: &Signal<S>, view: impl FnMut(&S) -> V + 'static) -> Self {
+        Self {
+            signal: signal.clone(),
+            view: Box::new(view),
+        }
+    }
+
+    fn view(&mut self) -> (Pod<V>, u64) {
+        let version = self.signal.version();
+        let view = self.signal.with(|value| (self.view)(value));
+
+        (Pod::new(view), version)
+    }
+}
+
+#[doc(hidden)]
+pub struct WatchState<T, V: View<T>> {
+    view: Pod<V>,
+    version: u64,
+    content: State<T, V>,
+}
+
+impl<T, S, V: View<T>> View<T> for Watch<S, V> {
+    type State = WatchState<T, V>;
+
+    fn build(&mut self, cx: &mut BuildCx, data: &mut T) -> Self::State {
+        let (mut view, version) = self.view();
+        let content = view.build(cx, data);
+
+        WatchState {
+            view,
+            version,
+            content,
+        }
+    }
+
+    fn rebuild(&mut self, state: &mut Self::State, cx: &mut RebuildCx, data: &mut T, _old: &Self) {
+        let (mut view, version) = self.view();
+        view.rebuild(&mut state.content, cx, data, &state.view);
+
+        state.view = view;
+        state.version = version;
+    }
+
+    fn event(
+        &mut self,
+        state: &mut Self::State,
+        cx: &mut EventCx,
+        data: &mut T,
+        event: &Event,
+    ) -> bool {
+        if let Some(changed) = event.cmd::<SignalChanged>() {
+            let is_current = self.signal.version() == state.version;
+
+            if changed.signal == self.signal.id() && !is_current {
+                let (mut view, version) = self.view();
+                view.rebuild(
+                    &mut state.content,
+                    &mut cx.as_rebuild_cx(),
+                    data,
+                    &state.view,
+                );
+
+                state.view = view;
+                state.version = version;
+            }
+
+            // other views may watch the same signal, including views in the content
+            let _ = (state.view).event(&mut state.content, cx, data, event);
+            return false;
+        }
+
+        (state.view).event(&mut state.content, cx, data, event)
+    }
+
+    fn layout(
+        &mut self,
+        state: &mut Self::State,
+        cx: &mut LayoutCx,
+        data: &mut T,
+        space: Space,
+    ) -> Size {
+        (state.view).layout(&mut state.content, cx, data, space)
+    }
+
+    fn draw(&mut self, state: &mut Self::State, cx: &mut DrawCx, data: &mut T) {
+        (state.view).draw(&mut state.content, cx, data);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicUsize;
+
+    use crate::views::{hstack, on_rebuild, testing::ViewTester};
+
+    use super::*;
+
+    // a view that counts how many times it's rebuilt
+    fn count_rebuilds(count: Arc<AtomicUsize>) -> impl View {
+        on_rebuild((), move |_, _| {
+            count.fetch_add(1, Ordering::SeqCst);
+        })
+    }
+
+    #[test]
+    fn only_the_watched_subtree_rebuilds() {
+        let signal = Signal::new(0);
+        let watched = Arc::new(AtomicUsize::new(0));
+        let sibling = Arc::new(AtomicUsize::new(0));
+        let values = Arc::new(Mutex::new(Vec::new()));
+
+        let mut view = hstack![
+            watch(&signal, {
+                let watched = watched.clone();
+                let values = values.clone();
+
+                move |value: &i32| {
+                    values.lock().unwrap().push(*value);
+                    count_rebuilds(watched.clone())
+                }
+            }),
+            count_rebuilds(sibling.clone()),
+        ];
+
+        let mut tester = ViewTester::new(&mut view, &mut ());
+
+        signal.set_with_proxy(&tester.command_proxy, 5);
+        let command = tester.command_rx.try_recv().unwrap();
+        tester.event(&mut view, &mut (), &Event::Command(command));
+
+        assert_eq!(*values.lock().unwrap(), [0, 5]);
+        assert_eq!(watched.load(Ordering::SeqCst), 1);
+        assert_eq!(sibling.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn nested_watches_rebuild() {
+        let outer = Signal::new(0);
+        let inner = Signal::new(0);
+        let values = Arc::new(Mutex::new(Vec::new()));
+
+        let mut view = watch(&outer, {
+            let inner = inner.clone();
+            let values = values.clone();
+
+            move |_: &i32| {
+                let values = values.clone();
+
+                watch(&inner, move |value: &i32| {
+                    values.lock().unwrap().push(*value);
+                })
+            }
+        });
+
+        let mut tester = ViewTester::new(&mut view, &mut ());
+
+        inner.set_with_proxy(&tester.command_proxy, 3);
+        let command = tester.command_rx.try_recv().unwrap();
+        tester.event(&mut view, &mut (), &Event::Command(command));
+
+        assert_eq!(*values.lock().unwrap(), [0, 3]);
+    }
+
+    #[test]
+    fn signal_can_be_read_while_watched() {
+        let signal = Signal::new(1);
+        let sum = signal.with(|value| value + signal.get());
+        assert_eq!(sum, 2);
+    }
+
+    #[test]
+    fn signal_ids_are_not_reused() {
+        let signal = Signal::new(0);
+        let id = signal.id();
+        drop(signal);
+
+        assert_ne!(Signal::new(0).id(), id);
+    }
+}
@@ -0,0 +1,76 @@
+use crate::{
+    context::{BuildCx, DrawCx, EventCx, LayoutCx, RebuildCx},
+    event::Event,
+    layout::{Size, Space},
+    view::View,
+};
+
+/// Create a new [`WindowFocusHandler`].
+pub fn on_window_focus<T, V>(
+    content: V,
+    on_focus: impl FnMut(&mut EventCx, &mut T, bool) + 'static,
+) -> WindowFocusHandler<T, V> {
+    WindowFocusHandler::new(content, on_focus)
+}
+
+/// A view that is notified when the window gains or loses focus.
+pub struct WindowFocusHandler<T, V> {
+    /// The content.
+    pub content: V,
+
+    /// The callback for when the focus of the window changes.
+    #[allow(clippy::type_complexity)]
+    pub on_focus: Box<dyn FnMut(&mut EventCx, &mut T, bool) + 'static>,
+}
+
+impl<T, V> WindowFocusHandler<T, V> {
+    /// Create a new [`WindowFocusHandler`].
+    pub fn new(content: V, on_focus: impl FnMut(&mut EventCx, &mut T, bool) + 'static) -> Self {
+        Self {
+            content,
+            on_focus: Box::new(on_focus),
+        }
+    }
+}
+
+impl<T, V: View<T>> View<T> for WindowFocusHandler<T, V> {
+    type State = V::State;
+
+    fn build(&mut self, cx: &mut BuildCx, data: &mut T) -> Self::State {
+        self.content.build(cx, data)
+    }
+
+    fn rebuild(&mut self, state: &mut Self::State, cx: &mut RebuildCx, data: &mut T, old: &Self) {
+        self.content.rebuild(state, cx, data, &old.content);
+    }
+
+    fn event(
+        &mut self,
+        state: &mut Self::State,
+        cx: &mut EventCx,
+        data: &mut T,
+        event: &Event,
+    ) -> bool {
+        let handled = self.content.event(state, cx, data, event);
+
+        if let Event::WindowFocusChanged(e) = event {
+            (self.on_focus)(cx, data, e.focused);
+        }
+
+        handled
+    }
+
+    fn layout(
+        &mut self,
+        state: &mut Self::State,
+        cx: &mut LayoutCx,
+        data: &mut T,
+        space: Space,
+    ) -> Size {
+        self.content.layout(state, cx, data, space)
+    }
+
+    fn draw(&mut self, state: &mut Self::State, cx: &mut DrawCx, data: &mut T) {
+        self.content.draw(state, cx, data);
+    }
+}
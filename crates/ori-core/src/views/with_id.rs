@@ -0,0 +1,139 @@
+use crate::{
+    context::{BuildCx, DrawCx, EventCx, LayoutCx, RebuildCx},
+    event::Event,
+    layout::{Size, Space},
+    view::{View, ViewId},
+};
+
+/// Give a view a known [`ViewId`].
+///
+/// The id can be used to send targeted commands to the view with [`BaseCx::send_to`], or
+/// to focus it with [`RequestFocus`].
+///
+/// Note that the content shares the [`ViewState`](crate::view::ViewState) of the
+/// [`WithId`] view, so `view` should be a view that can be focused or handles commands
+/// itself, eg. a text input.
+///
+/// [`BaseCx::send_to`]: crate::context::BaseCx::send_to
+/// [`RequestFocus`]: crate::event::RequestFocus
+pub fn with_id<V>(id: ViewId, view: V) -> WithId<V> {
+    WithId::new(id, view)
+}
+
+/// A view with a known [`ViewId`].
+pub struct WithId<V> {
+    /// The content.
+    pub content: V,
+
+    /// The id of the view.
+    pub id: ViewId,
+}
+
+impl<V> WithId<V> {
+    /// Create a new [`WithId`].
+    pub fn new(id: ViewId, content: V) -> Self {
+        Self { content, id }
+    }
+}
+
+impl<T, V: View<T>> View<T> for WithId<V> {
+    type State = V::State;
+
+    fn build(&mut self, cx: &mut BuildCx, data: &mut T) -> Self::State {
+        cx.set_id(self.id);
+        self.content.build(cx, data)
+    }
+
+    fn rebuild(&mut self, state: &mut Self::State, cx: &mut RebuildCx, data: &mut T, old: &Self) {
+        cx.set_id(self.id);
+        self.content.rebuild(state, cx, data, &old.content);
+    }
+
+    fn event(
+        &mut self,
+        state: &mut Self::State,
+        cx: &mut EventCx,
+        data: &mut T,
+        event: &Event,
+    ) -> bool {
+        self.content.event(state, cx, data, event)
+    }
+
+    fn layout(
+        &mut self,
+        state: &mut Self::State,
+        cx: &mut LayoutCx,
+        data: &mut T,
+        space: Space,
+    ) -> Size {
+        self.content.layout(state, cx, data, space)
+    }
+
+    fn draw(&mut self, state: &mut Self::State, cx: &mut DrawCx, data: &mut T) {
+        self.content.draw(state, cx, data);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{command::Command, views::testing::ViewTester};
+
+    use super::*;
+
+    struct Clear;
+
+    struct CountClears;
+
+    impl View<Vec<ViewId>> for CountClears {
+        type State = ();
+
+        fn build(&mut self, _: &mut BuildCx, _: &mut Vec<ViewId>) -> Self::State {}
+
+        fn rebuild(&mut self, _: &mut (), _: &mut RebuildCx, _: &mut Vec<ViewId>, _: &Self) {}
+
+        fn event(
+            &mut self,
+            _: &mut (),
+            cx: &mut EventCx,
+            data: &mut Vec<ViewId>,
+            event: &Event,
+        ) -> bool {
+            if event.is_cmd::<Clear>() {
+                data.push(cx.id());
+            }
+
+            false
+        }
+
+        fn layout(&mut self, _: &mut (), _: &mut LayoutCx, _: &mut Vec<ViewId>, _: Space) -> Size {
+            Size::ZERO
+        }
+
+        fn draw(&mut self, _: &mut (), _: &mut DrawCx, _: &mut Vec<ViewId>) {}
+    }
+
+    #[test]
+    fn targeted_command_reaches_only_target() {
+        let first = ViewId::new();
+        let second = ViewId::new();
+
+        let mut view = crate::views::vstack![
+            with_id(first, CountClears),
+            with_id(second, CountClears),
+            CountClears,
+        ];
+
+        let mut data = Vec::new();
+        let mut tester = ViewTester::new(&mut view, &mut data);
+
+        let event = Event::Command(Command::targeted(second, Clear));
+        tester.event(&mut view, &mut data, &event);
+        assert_eq!(data, vec![second]);
+
+        data.clear();
+
+        let event = Event::Command(Command::new(Clear));
+        tester.event(&mut view, &mut data, &event);
+        assert_eq!(data.len(), 3);
+    }
+}
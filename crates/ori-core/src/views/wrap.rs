@@ -16,16 +16,16 @@ pub use crate::{hwrap, vwrap};
 /// Create a horizontal [`Wrap`].
 #[macro_export]
 macro_rules! hwrap {
-    ($($child:expr),* $(,)?) => {
-        $crate::views::hwrap(($($child,)*))
+    ($($child:tt)*) => {
+        $crate::views::hwrap($crate::children![$($child)*])
     };
 }
 
 /// Create a vertical [`Wrap`].
 #[macro_export]
 macro_rules! vwrap {
-    ($($child:expr),* $(,)?) => {
-        $crate::views::vwrap(($($child,)*))
+    ($($child:tt)*) => {
+        $crate::views::vwrap($crate::children![$($child)*])
     };
 }
 
@@ -267,7 +267,21 @@ impl<T, V: ViewSeq<T>> View<T> for Wrap<V> {
         let (max_major, max_minor) = self.axis.unpack(space.max);
 
         for i in 0..self.content.len() {
-            let size = (self.content).layout_nth(i, content, cx, data, Space::UNBOUNDED);
+            // each child gets its natural size, but never more than a whole run
+            let natural =
+                (self.content).intrinsic_size_nth(i, content, cx, data, self.axis, f32::INFINITY);
+
+            // a child that fits is laid out in the space it was measured in, which reuses the
+            // layout done while measuring it
+            let space = match natural > 0.0 && natural <= max_major {
+                true => Space::UNBOUNDED,
+                false => {
+                    let max = self.axis.pack(f32::min(natural, max_major), f32::INFINITY);
+                    Space::new(Size::ZERO, max)
+                }
+            };
+
+            let size = (self.content).layout_nth(i, content, cx, data, space);
             state.majors[i] = self.axis.major(size);
         }
 
@@ -338,3 +352,36 @@ impl<T, V: ViewSeq<T>> View<T> for Wrap<V> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        canvas::Color,
+        views::{layout, rect, testing::ViewTester},
+    };
+
+    use super::*;
+
+    #[test]
+    fn children_measured_by_layout_are_laid_out_once() {
+        let square = || {
+            layout(|spaces: &mut Vec<Space>, space| {
+                spaces.push(space);
+                rect(Size::all(10.0), Color::RED)
+            })
+        };
+
+        let mut view = hwrap((square(), square()));
+
+        let mut spaces = Vec::new();
+        let mut tester = ViewTester::new(&mut view, &mut spaces);
+        let size = tester.layout(
+            &mut view,
+            &mut spaces,
+            Space::new(Size::ZERO, Size::all(15.0)),
+        );
+
+        assert_eq!(size.width, 10.0);
+        assert_eq!(spaces, [Space::UNBOUNDED, Space::UNBOUNDED]);
+    }
+}
@@ -12,8 +12,8 @@ pub use crate::zstack;
 /// Create a new [`ZStack`] view.
 #[macro_export]
 macro_rules! zstack {
-    ($($child:expr),* $(,)?) => {
-        $crate::views::zstack(($($child,)*))
+    ($($child:tt)*) => {
+        $crate::views::zstack($crate::children![$($child)*])
     };
 }
 
@@ -0,0 +1,25 @@
+/// How urgently an [`Announcement`] should be read by a screen reader.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Politeness {
+    /// Read when the screen reader is done with what it's currently reading.
+    #[default]
+    Polite,
+
+    /// Interrupt what the screen reader is currently reading.
+    Assertive,
+}
+
+/// A command carrying a message meant for screen readers, see
+/// [`BaseCx::announce`](crate::context::BaseCx::announce).
+///
+/// Ori has no accessibility tree, so nothing reads announcements on its own, and none of the
+/// platforms of `ori-shell` pass them on. The command is broadcast like any other, it only
+/// reaches a screen reader when a delegate of the application handles it and passes it on.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Announcement {
+    /// The message to announce.
+    pub message: String,
+
+    /// How urgently the message should be read.
+    pub politeness: Politeness,
+}
@@ -1,9 +1,11 @@
 //! Windowing system abstraction layer.
 
+mod announce;
 mod cursor;
 mod pointer;
 mod window;
 
+pub use announce::*;
 pub use cursor::*;
 pub use pointer::*;
 pub use window::*;
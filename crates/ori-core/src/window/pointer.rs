@@ -1,6 +1,6 @@
 use crate::{
-    event::{PointerButton, PointerId},
-    layout::Point,
+    event::{PointerButton, PointerId, PointerTool},
+    layout::{Point, Vector},
     view::ViewId,
 };
 
@@ -19,6 +19,28 @@ pub struct Pointer {
     ///
     /// You probably don't want to set this directly.
     pub hovering: Option<ViewId>,
+
+    /// The view that captured the pointer, see [`EventCx::capture_pointer`].
+    ///
+    /// You probably don't want to set this directly.
+    ///
+    /// [`EventCx::capture_pointer`]: crate::context::EventCx::capture_pointer
+    pub capture: Option<ViewId>,
+
+    /// The pressure of the pointer, in the range `0.0..=1.0`.
+    ///
+    /// You probably don't want to set this directly.
+    pub pressure: f32,
+
+    /// The tilt of the pointer in radians, along the x and y axes.
+    ///
+    /// You probably don't want to set this directly.
+    pub tilt: Vector,
+
+    /// The tool of the pointer.
+    ///
+    /// You probably don't want to set this directly.
+    pub tool: PointerTool,
 }
 
 impl Pointer {
@@ -29,6 +51,10 @@ impl Pointer {
             pressed: Vec::new(),
             position,
             hovering: None,
+            capture: None,
+            pressure: 1.0,
+            tilt: Vector::ZERO,
+            tool: PointerTool::Mouse,
         }
     }
 
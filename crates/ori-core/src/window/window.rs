@@ -4,10 +4,10 @@ use std::{
 };
 
 use crate::{
-    canvas::Color,
+    canvas::{Color, ColorSpace},
     event::{Ime, PointerButton, PointerId},
     image::Image,
-    layout::{Point, Size, Vector},
+    layout::{Point, Rect, Size, Vector},
     view::ViewId,
 };
 
@@ -57,6 +57,27 @@ impl Default for WindowSizing {
     }
 }
 
+/// How frames are presented to a window.
+///
+/// Synchronizing with the display avoids tearing and limits the frame rate to the refresh
+/// rate of the display, at the cost of a frame of latency. Presenting immediately minimizes
+/// latency, but will tear and render as fast as possible, which can waste a lot of CPU and
+/// GPU time.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum PresentMode {
+    /// Wait for the display to refresh before presenting a frame.
+    #[default]
+    AutoVsync,
+
+    /// Present frames immediately, this may cause tearing.
+    Immediate,
+
+    /// Present the latest frame at the next refresh, without blocking rendering.
+    ///
+    /// Not all backends support this, in which case [`PresentMode::AutoVsync`] is used.
+    Mailbox,
+}
+
 /// A window.
 #[derive(Clone, Debug)]
 pub struct Window {
@@ -72,6 +93,13 @@ pub struct Window {
     /// The size of the window.
     pub size: Size,
 
+    /// The position of the content of the window on the screen, or `None` to let the platform
+    /// place it.
+    ///
+    /// This is only used when the window is opened, and is set by the platform when the window
+    /// is moved. Not every platform can place windows, eg. Wayland never does.
+    pub position: Option<Point>,
+
     /// The sizing of the window.
     pub sizing: WindowSizing,
 
@@ -81,6 +109,12 @@ pub struct Window {
     /// as a rule of thumb, don't do it.
     pub scale: f32,
 
+    /// The scale of the user interface, independent of the scale of the display.
+    ///
+    /// This works like the zoom of a browser, the content is laid out in a space divided by
+    /// `ui_scale`, and rendered with the product of `scale` and `ui_scale`.
+    pub ui_scale: f32,
+
     /// Whether the window is resizable.
     pub resizable: bool,
 
@@ -93,8 +127,63 @@ pub struct Window {
     /// Whether the window is visible.
     pub visible: bool,
 
+    /// Whether the window has focus.
+    ///
+    /// This is set by the platform, modifying it has no effect.
+    pub focused: bool,
+
+    /// How frames are presented to the window.
+    pub present_mode: PresentMode,
+
+    /// The maximum number of frames drawn per second, `None` means no limit.
+    ///
+    /// This saves power when vsync is off, see [`Window::max_fps`].
+    pub max_fps: Option<u32>,
+
+    /// The progress shown in the taskbar, in the range `0.0..=1.0`.
+    ///
+    /// On X11 and Wayland this is shown on the launcher icon of the app, through the
+    /// `com.canonical.Unity.LauncherEntry` D-Bus interface, which eg. KDE Plasma and Dash to
+    /// Dock support. The icon belongs to the app rather than the window, so the window that
+    /// last sets it wins. Other platforms ignore it.
+    pub progress: Option<f32>,
+
+    /// Whether the window requests the attention of the user.
+    ///
+    /// On X11 this sets the urgency hint, which usually flashes the window in the taskbar. On
+    /// Wayland it marks the launcher icon of the app as urgent, like
+    /// [`progress`](Window::progress).
+    pub urgent: bool,
+
+    /// Whether the window is visible on all workspaces, eg. a floating notepad.
+    ///
+    /// On X11 this sets the `_NET_WM_STATE_STICKY` state, other platforms ignore it.
+    pub all_workspaces: bool,
+
     /// The color of the window.
     pub color: Option<Color>,
+
+    /// The region of the window that receives pointer input, in logical pixels, `None` means
+    /// the whole window.
+    ///
+    /// Pointer input outside the region passes through to whatever is behind the window, which
+    /// is useful for overlays drawn in a transparent window. An empty region makes the whole
+    /// window click-through. On X11 this sets the input shape of the window and on Wayland the
+    /// input region of its surface, Android ignores it.
+    pub input_region: Option<Vec<Rect>>,
+
+    /// The popup placement of the window, see [`Window::popup`].
+    pub popup: Option<Popup>,
+
+    /// Whether the platform shows the popups views draw, see
+    /// [`DrawCx::popup`](crate::context::DrawCx::popup), in windows of their own.
+    ///
+    /// Popup windows can extend beyond the window, otherwise popups are drawn over the content
+    /// and kept inside the window. This is set by the platform, only X11 supports it.
+    pub popup_windows: bool,
+
+    /// The color space the window is rendered in, see [`ColorSpace`].
+    pub color_space: ColorSpace,
 }
 
 impl Default for Window {
@@ -112,13 +201,25 @@ impl Window {
             title: String::from("Ori window"),
             icon: None,
             size: Size::new(800.0, 600.0),
+            position: None,
             sizing: WindowSizing::Fixed,
             scale: 1.0,
+            ui_scale: 1.0,
             resizable: true,
             decorated: true,
             maximized: false,
             visible: true,
+            focused: true,
+            present_mode: PresentMode::AutoVsync,
+            max_fps: None,
+            progress: None,
+            urgent: false,
+            all_workspaces: false,
             color: None,
+            input_region: None,
+            popup: None,
+            popup_windows: false,
+            color_space: ColorSpace::Srgb,
         }
     }
 
@@ -145,6 +246,12 @@ impl Window {
         self
     }
 
+    /// Set the position of the window, see [`Window::position`].
+    pub fn position(mut self, x: f32, y: f32) -> Self {
+        self.position = Some(Point::new(x, y));
+        self
+    }
+
     /// Set the sizing of the window.
     pub fn sizing(mut self, sizing: WindowSizing) -> Self {
         self.sizing = sizing;
@@ -164,6 +271,12 @@ impl Window {
         self
     }
 
+    /// Set the scale of the user interface, see [`Window::ui_scale`].
+    pub fn ui_scale(mut self, ui_scale: f32) -> Self {
+        self.set_ui_scale(ui_scale);
+        self
+    }
+
     /// Set whether the window is resizable.
     pub fn resizable(mut self, resizable: bool) -> Self {
         self.resizable = resizable;
@@ -188,12 +301,85 @@ impl Window {
         self
     }
 
+    /// Set how frames are presented to the window.
+    pub fn present_mode(mut self, present_mode: PresentMode) -> Self {
+        self.present_mode = present_mode;
+        self
+    }
+
+    /// Set the progress shown in the taskbar, see [`Window::set_progress`].
+    pub fn progress(mut self, progress: impl Into<Option<f32>>) -> Self {
+        self.set_progress(progress);
+        self
+    }
+
+    /// Set whether the window requests the attention of the user.
+    pub fn urgent(mut self, urgent: bool) -> Self {
+        self.urgent = urgent;
+        self
+    }
+
+    /// Set whether the window is visible on all workspaces.
+    pub fn all_workspaces(mut self, all_workspaces: bool) -> Self {
+        self.all_workspaces = all_workspaces;
+        self
+    }
+
     /// Set the color of the window.
     pub fn color(mut self, color: impl Into<Option<Color>>) -> Self {
         self.color = color.into();
         self
     }
 
+    /// Set the region of the window that receives pointer input, see [`Window::input_region`].
+    pub fn input_region(mut self, input_region: impl Into<Option<Vec<Rect>>>) -> Self {
+        self.input_region = input_region.into();
+        self
+    }
+
+    /// Make the whole window click-through, passing all pointer input to what is behind it.
+    ///
+    /// This is a shorthand for `self.input_region(Vec::new())`.
+    pub fn click_through(self) -> Self {
+        self.input_region(Vec::new())
+    }
+
+    /// Set the maximum number of frames drawn per second, `None` means no limit.
+    ///
+    /// Animations run at the limit, and windows that don't animate aren't redrawn at all.
+    /// This is mostly useful with [`PresentMode::Immediate`] or when the platform doesn't
+    /// support vsync, where frames would otherwise be drawn as fast as possible.
+    pub fn max_fps(mut self, max_fps: Option<u32>) -> Self {
+        self.max_fps = max_fps;
+        self
+    }
+
+    /// Set the progress shown in the taskbar, clamped to `0.0..=1.0`.
+    ///
+    /// Setting the progress to `None` hides it.
+    pub fn set_progress(&mut self, progress: impl Into<Option<f32>>) {
+        self.progress = progress.into().map(|progress| progress.clamp(0.0, 1.0));
+    }
+
+    /// Set the scale of the user interface, clamped to `0.25..=5.0`.
+    pub fn set_ui_scale(&mut self, ui_scale: f32) {
+        self.ui_scale = ui_scale.clamp(0.25, 5.0);
+    }
+
+    /// Get the size available to the content of the window.
+    ///
+    /// This is a shorthand for `self.size / self.ui_scale`.
+    pub fn content_size(&self) -> Size {
+        self.size / self.ui_scale
+    }
+
+    /// Get the scale the window is rendered with.
+    ///
+    /// This is a shorthand for `self.scale * self.ui_scale`.
+    pub fn render_scale(&self) -> f32 {
+        self.scale * self.ui_scale
+    }
+
     /// Get the size of the window in physical pixels.
     ///
     /// This is a shorthand for `self.size * self.scale`.
@@ -226,6 +412,16 @@ impl Window {
         (self.pointers.iter()).any(|pointer| pointer.hovering == Some(view_id))
     }
 
+    /// Get the view that captured the pointer with `pointer_id`.
+    pub fn pointer_capture(&self, pointer_id: PointerId) -> Option<ViewId> {
+        self.get_pointer(pointer_id)?.capture
+    }
+
+    /// Get whether a specific view has captured a pointer.
+    pub fn is_capturing(&self, view_id: ViewId) -> bool {
+        (self.pointers.iter()).any(|pointer| pointer.capture == Some(view_id))
+    }
+
     /// Get whether `button` is held down on pointer with `pointer_id`.
     pub fn is_pointer_held(&self, pointer_id: PointerId, button: PointerButton) -> bool {
         match self.get_pointer(pointer_id) {
@@ -297,14 +493,75 @@ impl Window {
             WindowUpdate::Icon(self.icon.clone()),
             WindowUpdate::Size(self.size),
             WindowUpdate::Scale(self.scale),
+            WindowUpdate::UiScale(self.ui_scale),
             WindowUpdate::Resizable(self.resizable),
             WindowUpdate::Decorated(self.decorated),
             WindowUpdate::Maximized(self.maximized),
             WindowUpdate::Visible(self.visible),
+            WindowUpdate::PresentMode(self.present_mode),
+            WindowUpdate::Progress(self.progress),
+            WindowUpdate::Urgent(self.urgent),
+            WindowUpdate::AllWorkspaces(self.all_workspaces),
             WindowUpdate::Color(self.color),
+            WindowUpdate::InputRegion(self.input_region.clone()),
         ]
     }
 
+    /// Set the color space of the window, see [`ColorSpace`] for what renderers support.
+    pub fn color_space(mut self, color_space: ColorSpace) -> Self {
+        self.color_space = color_space;
+        self
+    }
+
+    /// Make the window a popup of `parent`, placed at `position` relative to the content of
+    /// the parent, in logical pixels.
+    ///
+    /// Popups are undecorated, can extend beyond the bounds of their parent, and are closed
+    /// when the pointer is pressed outside of them, or when their parent is closed. Views that
+    /// draw popups, like tooltips and context menus, use [`DrawCx::popup`] instead, which
+    /// the application shows in popup windows when the platform supports them.
+    ///
+    /// On X11 popups are override-redirect windows that grab the pointer and follow their
+    /// parent when it moves, backends without support for popups open them as regular
+    /// undecorated windows.
+    ///
+    /// [`DrawCx::popup`]: crate::context::DrawCx::popup
+    pub fn popup(mut self, parent: WindowId, position: Point) -> Self {
+        self.popup = Some(Popup {
+            parent,
+            position,
+            grab: true,
+        });
+        self.decorated = false;
+        self.resizable = false;
+        self
+    }
+
+    /// Check if the window is a popup, see [`Window::popup`].
+    pub fn is_popup(&self) -> bool {
+        self.popup.is_some()
+    }
+
+    /// Get the [`WindowPlacement`] of the window, to be restored with [`Window::restore`].
+    pub fn placement(&self) -> WindowPlacement {
+        WindowPlacement {
+            position: self.position,
+            size: self.size,
+            maximized: self.maximized,
+        }
+    }
+
+    /// Restore a [`WindowPlacement`], eg. one saved the last time the application ran.
+    ///
+    /// The position is moved onto a connected monitor when the window is added to the
+    /// application, see [`WindowPlacement::clamp`].
+    pub fn restore(mut self, placement: WindowPlacement) -> Self {
+        self.position = placement.position;
+        self.size = placement.size;
+        self.maximized = placement.maximized;
+        self
+    }
+
     /// Get the [`WindowSnapshot`] of the window.
     pub fn snapshot(&self) -> WindowSnapshot {
         WindowSnapshot {
@@ -312,11 +569,18 @@ impl Window {
             icon: self.icon.clone(),
             size: self.size,
             scale: self.scale,
+            ui_scale: self.ui_scale,
             resizable: self.resizable,
             decorated: self.decorated,
             maximized: self.maximized,
             visible: self.visible,
+            present_mode: self.present_mode,
+            progress: self.progress,
+            urgent: self.urgent,
+            all_workspaces: self.all_workspaces,
             color: self.color,
+            input_region: self.input_region.clone(),
+            popup: self.popup,
         }
     }
 }
@@ -336,6 +600,9 @@ pub enum WindowUpdate {
     /// Set the scale of the window.
     Scale(f32),
 
+    /// Set the scale of the user interface.
+    UiScale(f32),
+
     /// Set whether the window is resizable.
     Resizable(bool),
 
@@ -348,9 +615,33 @@ pub enum WindowUpdate {
     /// Set whether the window is visible.
     Visible(bool),
 
+    /// Set how frames are presented to the window.
+    PresentMode(PresentMode),
+
+    /// Set the progress shown in the taskbar, see [`Window::progress`].
+    Progress(Option<f32>),
+
+    /// Set whether the window requests the attention of the user.
+    Urgent(bool),
+
+    /// Request the attention of the user once, eg. by flashing the window in the taskbar.
+    ///
+    /// On X11 this sets the `_NET_WM_STATE_DEMANDS_ATTENTION` state, which the window manager
+    /// clears when the window is focused. Other platforms ignore it.
+    RequestAttention,
+
+    /// Set whether the window is visible on all workspaces.
+    AllWorkspaces(bool),
+
     /// Set the color of the window.
     Color(Option<Color>),
 
+    /// Set the region of the window that receives pointer input, `None` means the whole window.
+    InputRegion(Option<Vec<Rect>>),
+
+    /// Move a popup window, see [`Window::popup`].
+    Popup(Option<Popup>),
+
     /// Set the cursor of the window.
     Cursor(Cursor),
 
@@ -358,6 +649,57 @@ pub enum WindowUpdate {
     Ime(Option<Ime>),
 }
 
+/// The placement of a popup window relative to its parent, see [`Window::popup`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Popup {
+    /// The window the popup belongs to.
+    pub parent: WindowId,
+
+    /// The position of the popup relative to the content of the parent, in logical pixels.
+    pub position: Point,
+
+    /// Whether the popup grabs the pointer while it's open, so pressing anywhere outside of it
+    /// reaches the application and closes it, like with menus. Tooltips don't grab.
+    pub grab: bool,
+}
+
+/// The placement of a window, that can be saved when it's closed, and restored the next time
+/// it's opened, see `AppDelegate::save_window_placement`.
+///
+/// With the `serde` feature enabled this can be serialized, eg. to a config file.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WindowPlacement {
+    /// The position of the window, see [`Window::position`].
+    pub position: Option<Point>,
+
+    /// The size of the window.
+    pub size: Size,
+
+    /// Whether the window is maximized.
+    pub maximized: bool,
+}
+
+impl WindowPlacement {
+    /// Move the placement onto one of `monitors`, if its position isn't on any of them.
+    ///
+    /// A monitor the window was on when the placement was saved may have been disconnected
+    /// since, in which case the window is moved onto the first monitor, keeping as much of it
+    /// visible as fits. Nothing is moved when no monitors are known.
+    pub fn clamp(&mut self, monitors: &[Rect]) {
+        let (Some(position), Some(&first)) = (self.position, monitors.first()) else {
+            return;
+        };
+
+        if monitors.iter().any(|monitor| monitor.contains(position)) {
+            return;
+        }
+
+        let max = Point::max(first.max - self.size, first.min);
+        self.position = Some(position.clamp(first.min, max));
+    }
+}
+
 /// The state of a window.
 #[derive(Clone, Debug)]
 pub struct WindowSnapshot {
@@ -373,6 +715,9 @@ pub struct WindowSnapshot {
     /// The scale of the window.
     pub scale: f32,
 
+    /// The scale of the user interface.
+    pub ui_scale: f32,
+
     /// Whether the window is resizable.
     pub resizable: bool,
 
@@ -385,8 +730,26 @@ pub struct WindowSnapshot {
     /// Whether the window is visible.
     pub visible: bool,
 
+    /// How frames are presented to the window.
+    pub present_mode: PresentMode,
+
+    /// The progress shown in the taskbar.
+    pub progress: Option<f32>,
+
+    /// Whether the window requests the attention of the user.
+    pub urgent: bool,
+
+    /// Whether the window is visible on all workspaces.
+    pub all_workspaces: bool,
+
     /// The color of the window.
     pub color: Option<Color>,
+
+    /// The region of the window that receives pointer input.
+    pub input_region: Option<Vec<Rect>>,
+
+    /// The popup placement of the window.
+    pub popup: Option<Popup>,
 }
 
 impl WindowSnapshot {
@@ -410,6 +773,10 @@ impl WindowSnapshot {
             updates.push(WindowUpdate::Scale(window.scale));
         }
 
+        if self.ui_scale != window.ui_scale {
+            updates.push(WindowUpdate::UiScale(window.ui_scale));
+        }
+
         if self.resizable != window.resizable {
             updates.push(WindowUpdate::Resizable(window.resizable));
         }
@@ -426,10 +793,34 @@ impl WindowSnapshot {
             updates.push(WindowUpdate::Visible(window.visible));
         }
 
+        if self.present_mode != window.present_mode {
+            updates.push(WindowUpdate::PresentMode(window.present_mode));
+        }
+
+        if self.progress != window.progress {
+            updates.push(WindowUpdate::Progress(window.progress));
+        }
+
+        if self.urgent != window.urgent {
+            updates.push(WindowUpdate::Urgent(window.urgent));
+        }
+
+        if self.all_workspaces != window.all_workspaces {
+            updates.push(WindowUpdate::AllWorkspaces(window.all_workspaces));
+        }
+
         if self.color != window.color {
             updates.push(WindowUpdate::Color(window.color));
         }
 
+        if self.input_region != window.input_region {
+            updates.push(WindowUpdate::InputRegion(window.input_region.clone()));
+        }
+
+        if self.popup != window.popup {
+            updates.push(WindowUpdate::Popup(window.popup));
+        }
+
         updates
     }
 }
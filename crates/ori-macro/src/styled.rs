@@ -6,6 +6,8 @@ use crate::{find_core, rebuild};
 
 syn::custom_keyword!(or);
 syn::custom_keyword!(default);
+syn::custom_keyword!(inherit);
+syn::custom_keyword!(transition_of);
 
 pub fn derive_styled(input: proc_macro::TokenStream) -> manyhow::Result<proc_macro::TokenStream> {
     let input = syn::parse::<syn::DeriveInput>(input)?;
@@ -167,36 +169,28 @@ fn style_get_field(field: &syn::Field, styled: &syn::Expr, styles: &syn::Expr) -
     let ori_core = find_core();
     let ident = field.ident.as_ref().unwrap();
     let mut default = None;
+    let mut property = None;
+    let mut animated = None;
 
     for attr in &field.attrs {
         if attr.path().is_ident("styled") {
             attr.parse_args_with(|input: syn::parse::ParseStream| {
-                if input.is_empty() {
-                    return Ok(());
-                }
-
-                input.parse::<default>()?;
-
-                if input.peek(syn::Token![=]) {
-                    input.parse::<syn::Token![=]>()?;
-                    default = Some(input.parse::<syn::Expr>()?);
-                } else if input.peek(syn::Token![->]) {
-                    input.parse::<syn::Token![->]>()?;
-                    let style = input.parse::<syn::Expr>()?;
-
-                    if input.peek(or) {
-                        input.parse::<or>()?;
-                        let or_value = input.parse::<syn::Expr>()?;
-                        default = Some(parse_quote_spanned! { style.span() =>
-                            #styles.get_or_else(|| #or_value, &#style)
-                        });
+                while !input.is_empty() {
+                    if input.peek(inherit) {
+                        input.parse::<inherit>()?;
+                        input.parse::<syn::Token![=]>()?;
+                        property = Some(input.parse::<syn::LitStr>()?);
+                    } else if input.peek(transition_of) {
+                        input.parse::<transition_of>()?;
+                        input.parse::<syn::Token![=]>()?;
+                        animated = Some(input.parse::<syn::Ident>()?);
                     } else {
-                        default = Some(parse_quote_spanned! { style.span() =>
-                            #styles.get(&#style).expect(concat!("missing style for `", #style, "`"))
-                        });
+                        default = Some(parse_default(input, styles)?);
+                    }
+
+                    if !input.is_empty() {
+                        input.parse::<syn::Token![,]>()?;
                     }
-                } else {
-                    default = Some(parse_quote!(::std::default::Default::default()));
                 }
 
                 Ok(())
@@ -205,7 +199,25 @@ fn style_get_field(field: &syn::Field, styled: &syn::Expr, styles: &syn::Expr) -
         }
     }
 
-    match default {
+    // inherited properties fall back to the closest ancestor that sets them, before the
+    // default
+    if let Some(property) = property {
+        let inherited = quote! {
+            #ori_core::style::Styles::get_inherited(
+                #styles,
+                &#ori_core::style::Style::new(#property),
+            )
+        };
+
+        default = Some(match default {
+            Some(default) => parse_quote!(#inherited.unwrap_or_else(|| #default)),
+            None => parse_quote! {
+                #inherited.expect(concat!("missing style for `", stringify!(#ident), "`"))
+            },
+        });
+    }
+
+    let value: syn::Expr = match default {
         Some(default) => parse_quote! {
             #ori_core::style::Styled::get_or_else(
                 &#styled.#ident,
@@ -219,7 +231,49 @@ fn style_get_field(field: &syn::Field, styled: &syn::Expr, styles: &syn::Expr) -
                 #styles
             ).expect(concat!("missing style for `", stringify!(#ident), "`"))
         },
+    };
+
+    // the transition of a property set by the `transition` shorthand takes precedence over
+    // the styles of the transition, but not over a transition set on the view itself
+    match animated {
+        Some(of) => parse_quote! {
+            match #styled.#ident {
+                #ori_core::style::Styled::Value(_) => #value,
+                _ => #ori_core::style::Styles::get_property_transition(#styles, &#styled.#of)
+                    .unwrap_or_else(|| #value),
+            }
+        },
+        None => value,
+    }
+}
+
+fn parse_default(input: syn::parse::ParseStream, styles: &syn::Expr) -> syn::Result<syn::Expr> {
+    input.parse::<default>()?;
+
+    if input.peek(syn::Token![=]) {
+        input.parse::<syn::Token![=]>()?;
+        return input.parse::<syn::Expr>();
+    }
+
+    if input.peek(syn::Token![->]) {
+        input.parse::<syn::Token![->]>()?;
+        let style = input.parse::<syn::Expr>()?;
+
+        if input.peek(or) {
+            input.parse::<or>()?;
+            let or_value = input.parse::<syn::Expr>()?;
+
+            return Ok(parse_quote_spanned! { style.span() =>
+                #styles.get_or_else(|| #or_value, &#style)
+            });
+        }
+
+        return Ok(parse_quote_spanned! { style.span() =>
+            #styles.get(&#style).expect(concat!("missing style for `", #style, "`"))
+        });
     }
+
+    Ok(parse_quote!(::std::default::Default::default()))
 }
 
 fn get_styled(ty: &syn::Type) -> Option<syn::Type> {
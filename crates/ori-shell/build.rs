@@ -2,6 +2,7 @@ fn main() {
     println!("cargo::rustc-check-cfg=cfg(x11_platform)");
     println!("cargo::rustc-check-cfg=cfg(wayland_platform)");
     println!("cargo::rustc-check-cfg=cfg(android_platform)");
+    println!("cargo::rustc-check-cfg=cfg(file_dialog)");
 
     let target_os = std::env::var("CARGO_CFG_TARGET_OS").unwrap();
 
@@ -14,6 +15,9 @@ fn main() {
 
         #[cfg(feature = "wayland")]
         println!("cargo:rustc-cfg=wayland_platform");
+
+        #[cfg(feature = "file-dialog")]
+        println!("cargo:rustc-cfg=file_dialog");
     }
 
     if target_os == "android" {
@@ -0,0 +1,70 @@
+//! Native file dialogs, powered by [`rfd`].
+
+use std::path::PathBuf;
+
+use ori_app::{AppDelegate, DelegateCx};
+use ori_core::{
+    command::CommandRequest,
+    dialog::{FileDialog, FileDialogKind},
+    event::Event,
+};
+
+/// A delegate answering [`FileDialog`] requests with the dialogs of the platform.
+///
+/// The dialogs are awaited on the command queue, so the event loop keeps running while they
+/// are open.
+pub(crate) struct FileDialogs;
+
+impl<T> AppDelegate<T> for FileDialogs {
+    fn event(&mut self, cx: &mut DelegateCx<T>, _data: &mut T, event: &Event) -> bool {
+        let Some(request) = event.cmd::<CommandRequest<FileDialog, Option<PathBuf>>>() else {
+            return false;
+        };
+
+        if request.is_responded() {
+            return false;
+        }
+
+        let dialog = request.request();
+        let mut rfd_dialog = rfd::AsyncFileDialog::new();
+
+        if let Some(ref title) = dialog.title {
+            rfd_dialog = rfd_dialog.set_title(title);
+        }
+
+        if let Some(ref directory) = dialog.directory {
+            rfd_dialog = rfd_dialog.set_directory(directory);
+        }
+
+        if let Some(ref file_name) = dialog.file_name {
+            rfd_dialog = rfd_dialog.set_file_name(file_name);
+        }
+
+        for filter in &dialog.filters {
+            rfd_dialog = rfd_dialog.add_filter(&filter.name, &filter.extensions[..]);
+        }
+
+        let responder = request.responder();
+
+        // the futures are created here, so the dialog builder isn't moved into the spawned
+        // futures, which must be `Send`
+        match dialog.kind {
+            FileDialogKind::Open => {
+                let file = rfd_dialog.pick_file();
+
+                cx.spawn_async(async move {
+                    responder.respond(file.await.map(|file| file.path().to_path_buf()));
+                });
+            }
+            FileDialogKind::Save => {
+                let file = rfd_dialog.save_file();
+
+                cx.spawn_async(async move {
+                    responder.respond(file.await.map(|file| file.path().to_path_buf()));
+                });
+            }
+        }
+
+        true
+    }
+}
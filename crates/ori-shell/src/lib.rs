@@ -7,6 +7,8 @@ use ori_app::{AppBuilder, IntoUiBuilder};
 use ori_core::window::Window;
 use tracing_subscriber::{layer::SubscriberExt, EnvFilter};
 
+#[cfg(file_dialog)]
+mod file_dialog;
 pub mod platform;
 
 /// Errors that can occur when running an Ori application.
@@ -133,6 +135,9 @@ pub fn install_logger() -> Result<(), LogError> {
 /// Run an Ori application.
 #[allow(unused_variables, unreachable_code)]
 pub fn run<T>(app: AppBuilder<T>, data: &mut T) -> Result<(), RunError> {
+    #[cfg(file_dialog)]
+    let app = app.delegate(file_dialog::FileDialogs);
+
     #[cfg(wayland_platform)]
     if platform::wayland::is_available() {
         return Ok(platform::wayland::run(app, data)?);
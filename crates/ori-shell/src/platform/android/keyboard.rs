@@ -129,11 +129,23 @@ impl ImeState {
             .get_static_field("android/text/InputType", "TYPE_CLASS_TEXT", "I")?
             .i()?;
 
+        // hides the text from suggestions, and shows a keyboard without them
+        let variation = match ime.password {
+            true => env
+                .get_static_field(
+                    "android/text/InputType",
+                    "TYPE_TEXT_VARIATION_PASSWORD",
+                    "I",
+                )?
+                .i()?,
+            false => 0,
+        };
+
         env.call_method(
             &activity,
             "setIMEInputType",
             "(I)V",
-            &[JValue::Int(input_type | text_flaged)],
+            &[JValue::Int(input_type | variation | text_flaged)],
         )?;
 
         Ok(())
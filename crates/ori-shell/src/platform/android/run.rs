@@ -1,15 +1,19 @@
 use android_activity::{
-    input::{InputEvent, KeyAction, KeyEvent, KeyMapChar, Keycode, MotionAction, MotionEvent},
+    input::{
+        Axis, InputEvent, KeyAction, KeyEvent, KeyMapChar, Keycode, MotionAction, MotionEvent,
+        Pointer, ToolType,
+    },
     AndroidApp, AndroidAppWaker, InputStatus, MainEvent, PollEvent,
 };
 use ori_app::{App, AppBuilder, AppRequest, UiBuilder};
 use ori_core::{
+    canvas::ColorSpace,
     clipboard::Clipboard,
     command::CommandWaker,
-    event::{Key, PointerButton, PointerId},
-    layout::{Point, Size},
+    event::{Code, Key, PointerButton, PointerId, PointerTool},
+    layout::{Point, Size, Vector},
     text::Fonts,
-    window::{Window, WindowId, WindowUpdate},
+    window::{PresentMode, Window, WindowId, WindowUpdate},
 };
 use ori_skia::{SkiaFonts, SkiaRenderer};
 use tracing::warn;
@@ -68,7 +72,9 @@ pub fn run<T>(app: AppBuilder<T>, data: &mut T) -> Result<(), AndroidError> {
                     MainEvent::Destroy => {
                         state.running = false;
                     }
-                    MainEvent::GainedFocus => {}
+                    MainEvent::GainedFocus => {
+                        window_focused(&mut state, data, true);
+                    }
                     MainEvent::InitWindow { .. } => {
                         if !init {
                             state.app.init(data);
@@ -81,13 +87,19 @@ pub fn run<T>(app: AppBuilder<T>, data: &mut T) -> Result<(), AndroidError> {
                         request_redraw(&mut state);
                     }
                     MainEvent::InsetsChanged { .. } => {}
-                    MainEvent::LostFocus => {}
+                    MainEvent::LostFocus => {
+                        window_focused(&mut state, data, false);
+                    }
                     MainEvent::LowMemory => {}
-                    MainEvent::Pause => {}
+                    MainEvent::Pause => {
+                        state.app.suspend(data);
+                    }
                     MainEvent::RedrawNeeded { .. } => {
                         request_redraw(&mut state);
                     }
-                    MainEvent::Resume { .. } => {}
+                    MainEvent::Resume { .. } => {
+                        state.app.resume(data);
+                    }
                     MainEvent::SaveState { .. } => {}
                     MainEvent::Start => {}
                     MainEvent::Stop => {}
@@ -147,6 +159,8 @@ struct WindowState {
     physical_height: u32,
     scale_factor: f32,
     needs_redraw: bool,
+    present_mode: PresentMode,
+    color_space: ColorSpace,
     egl_surface: EglSurface,
     renderer: SkiaRenderer,
 }
@@ -208,11 +222,32 @@ fn handle_request<T>(state: &mut AppState<T>, data: &mut T, request: AppRequest<
             WindowUpdate::Icon(_) => warn!("Window icon is not supported on Android"),
             WindowUpdate::Size(_) => warn!("Window size is not supported on Android"),
             WindowUpdate::Scale(_) => warn!("Window scale is not supported on Android"),
+            // the ui scale is applied when rendering
+            WindowUpdate::UiScale(_) => {}
             WindowUpdate::Resizable(_) => warn!("Window resizable is not supported on Android"),
             WindowUpdate::Decorated(_) => warn!("Window decorated is not supported on Android"),
             WindowUpdate::Maximized(_) => warn!("Window maximized is not supported on Android"),
             WindowUpdate::Visible(_) => warn!("Window visible is not supported on Android"),
+            WindowUpdate::PresentMode(present_mode) => {
+                if let Some(ref mut window) = state.window {
+                    window.present_mode = present_mode;
+                    window.egl_surface.make_current().unwrap();
+                    window.egl_surface.set_present_mode(present_mode).unwrap();
+                }
+            }
+            WindowUpdate::Progress(_) => warn!("Window progress is not supported on Android"),
+            WindowUpdate::Urgent(_) => warn!("Window urgency is not supported on Android"),
+            WindowUpdate::RequestAttention => {
+                warn!("Requesting attention is not supported on Android")
+            }
+            WindowUpdate::AllWorkspaces(_) => {
+                warn!("Window workspaces are not supported on Android")
+            }
             WindowUpdate::Color(_) => warn!("Window color is not supported on Android"),
+            WindowUpdate::InputRegion(_) => {
+                warn!("Window input regions are not supported on Android")
+            }
+            WindowUpdate::Popup(_) => warn!("Popup windows are not supported on Android"),
             WindowUpdate::Cursor(_) => warn!("Window cursor is not supported on Android"),
             WindowUpdate::Ime(ime) => match ime {
                 Some(ime) => {
@@ -249,10 +284,15 @@ fn create_window<T>(state: &mut AppState<T>, data: &mut T, mut window: Window, u
     window.scale = scale_factor;
 
     let native_window_ptr = native_window.ptr().as_ptr();
-    let egl_surface = EglSurface::new(&state.egl_context, native_window_ptr as _).unwrap();
+    let egl_surface = EglSurface::new(
+        &state.egl_context,
+        native_window_ptr as _,
+        window.color_space,
+    )
+    .unwrap();
 
     egl_surface.make_current().unwrap();
-    egl_surface.swap_interval(1).unwrap();
+    egl_surface.set_present_mode(window.present_mode).unwrap();
 
     let renderer = unsafe { SkiaRenderer::new(|name| state.egl_context.get_proc_address(name)) };
 
@@ -262,6 +302,8 @@ fn create_window<T>(state: &mut AppState<T>, data: &mut T, mut window: Window, u
         physical_height,
         scale_factor,
         needs_redraw: true,
+        present_mode: window.present_mode,
+        color_space: window.color_space,
         egl_surface,
         renderer,
     };
@@ -281,10 +323,15 @@ fn recreate_window<T>(state: &mut AppState<T>) {
         let scale_factor = scale_factor / 160.0;
 
         let native_window_ptr = native_window.ptr().as_ptr();
-        let egl_surface = EglSurface::new(&state.egl_context, native_window_ptr as _).unwrap();
+        let egl_surface = EglSurface::new(
+            &state.egl_context,
+            native_window_ptr as _,
+            window.color_space,
+        )
+        .unwrap();
 
         egl_surface.make_current().unwrap();
-        egl_surface.swap_interval(1).unwrap();
+        egl_surface.set_present_mode(window.present_mode).unwrap();
 
         let renderer = unsafe {
             // SAFETY: The EGL context is current
@@ -297,6 +344,8 @@ fn recreate_window<T>(state: &mut AppState<T>) {
             physical_height,
             scale_factor,
             needs_redraw: true,
+            present_mode: window.present_mode,
+            color_space: window.color_space,
             egl_surface,
             renderer,
         };
@@ -322,9 +371,11 @@ fn render_window<T>(state: &mut AppState<T>, data: &mut T) {
                 fonts.downcast_mut().unwrap(),
                 &draw.canvas,
                 draw.clear_color,
+                window.egl_surface.color_space(),
                 window.physical_width,
                 window.physical_height,
-                window.scale_factor,
+                window.scale_factor * draw.ui_scale,
+                None,
             );
 
             window.egl_surface.swap_buffers().unwrap();
@@ -354,6 +405,12 @@ fn window_resized<T>(state: &mut AppState<T>, data: &mut T) {
     }
 }
 
+fn window_focused<T>(state: &mut AppState<T>, data: &mut T, focused: bool) {
+    if let Some(ref window) = state.window {
+        state.app.window_focused(data, window.id, focused);
+    }
+}
+
 fn input_event<T>(state: &mut AppState<T>, data: &mut T, event: &InputEvent) -> InputStatus {
     match event {
         InputEvent::MotionEvent(event) => match motion_event(state, data, event) {
@@ -381,6 +438,7 @@ fn motion_event<T>(state: &mut AppState<T>, data: &mut T, event: &MotionEvent) -
 
     let pointer = event.pointer_at_index(event.pointer_index());
     let point = Point::new(pointer.x(), pointer.y()) / window.scale_factor;
+    let (pressure, tilt, tool) = pointer_stylus(&pointer);
 
     match event.action() {
         MotionAction::Down | MotionAction::Up => {
@@ -392,6 +450,8 @@ fn motion_event<T>(state: &mut AppState<T>, data: &mut T, event: &MotionEvent) -
                 handled |= state.app.pointer_moved(data, window.id, pointer_id, point);
             }
 
+            (state.app).pointer_stylus(window.id, pointer_id, pressure, tilt, tool);
+
             handled |= state.app.pointer_button(
                 data,
                 window.id,
@@ -406,11 +466,36 @@ fn motion_event<T>(state: &mut AppState<T>, data: &mut T, event: &MotionEvent) -
 
             handled
         }
-        MotionAction::Move => state.app.pointer_moved(data, window.id, pointer_id, point),
+        MotionAction::Move => {
+            (state.app).pointer_stylus(window.id, pointer_id, pressure, tilt, tool);
+            state.app.pointer_moved(data, window.id, pointer_id, point)
+        }
         _ => false,
     }
 }
 
+fn pointer_stylus(pointer: &Pointer) -> (f32, Vector, PointerTool) {
+    let tool = match pointer.tool_type() {
+        ToolType::Stylus => PointerTool::Pen,
+        ToolType::Eraser => PointerTool::Eraser,
+        ToolType::Finger => PointerTool::Touch,
+        _ => PointerTool::Mouse,
+    };
+
+    // android reports tilt as the angle from the perpendicular, and orientation as the
+    // direction the tool is pointing in, we convert it to a tilt along the x and y axes
+    let angle = pointer.axis_value(Axis::Tilt);
+    let orientation = pointer.axis_value(Axis::Orientation);
+    let tilt = Vector::new(orientation.sin(), -orientation.cos()) * angle;
+
+    let pressure = match tool {
+        PointerTool::Pen | PointerTool::Eraser => pointer.pressure().clamp(0.0, 1.0),
+        _ => 1.0,
+    };
+
+    (pressure, tilt, tool)
+}
+
 fn key_event<T>(state: &mut AppState<T>, data: &mut T, event: &KeyEvent) -> bool {
     let Some(ref mut window) = state.window else {
         return false;
@@ -423,7 +508,12 @@ fn key_event<T>(state: &mut AppState<T>, data: &mut T, event: &KeyEvent) -> bool
     let logical = to_logical(keychar, event.key_code());
     let text = logical.as_char().map(String::from);
 
-    (state.app).keyboard_key(data, window_id, logical, None, text, pressed)
+    // android reports the scancodes of the linux kernel, which identify the physical key
+    let code = u8::try_from(event.scan_code())
+        .ok()
+        .and_then(Code::from_linux_scancode);
+
+    (state.app).keyboard_key(data, window_id, logical, code, text, pressed)
 }
 
 fn get_key_event_keychar<T>(state: &mut AppState<T>, event: &KeyEvent) -> Option<KeyMapChar> {
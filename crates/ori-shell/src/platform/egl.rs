@@ -1,10 +1,12 @@
 use std::{
-    ffi, ptr,
+    ffi, mem, ptr,
     rc::Rc,
     sync::{Arc, LazyLock},
 };
 
 use libloading::Library;
+use ori_core::{canvas::ColorSpace, window::PresentMode};
+use tracing::warn;
 
 pub static LIB_EGL: LazyLock<Result<Library, Arc<libloading::Error>>> = LazyLock::new(|| {
     // load libEGL.so
@@ -64,6 +66,7 @@ struct EglContextInner {
     display: *mut ffi::c_void,
     config: *mut ffi::c_void,
     context: *mut ffi::c_void,
+    extensions: String,
 }
 
 pub struct EglContext {
@@ -135,11 +138,14 @@ impl EglContext {
 
         check_egl_error()?;
 
+        let extensions = unsafe { egl_query_string(display, EGL_EXTENSIONS) };
+
         let inner = Rc::new(EglContextInner {
             native: native_display,
             display,
             config,
             context,
+            extensions,
         });
 
         Ok(Self { inner })
@@ -151,6 +157,12 @@ impl EglContext {
     }
 }
 
+impl EglContextInner {
+    fn has_extension(&self, name: &str) -> bool {
+        self.extensions.split_whitespace().any(|ext| ext == name)
+    }
+}
+
 impl Drop for EglContextInner {
     fn drop(&mut self) {
         unsafe {
@@ -169,10 +181,56 @@ impl Drop for EglContextInner {
 pub struct EglSurface {
     cx: Rc<EglContextInner>,
     surface: *mut ffi::c_void,
+    color_space: ColorSpace,
 }
 
 impl EglSurface {
-    pub fn new(context: &EglContext, window: *mut ffi::c_void) -> Result<Self, EglError> {
+    /// Create a surface for `window`, in `color_space` if the driver supports it.
+    ///
+    /// The surfaces are 8 bit, so extended range sRGB is always rendered as sRGB, and Display
+    /// P3 falls back to sRGB if `EGL_EXT_gl_colorspace_display_p3` isn't supported, see
+    /// [`EglSurface::color_space`].
+    pub fn new(
+        context: &EglContext,
+        window: *mut ffi::c_void,
+        color_space: ColorSpace,
+    ) -> Result<Self, EglError> {
+        let display_p3 = color_space == ColorSpace::DisplayP3
+            && context.inner.has_extension("EGL_KHR_gl_colorspace")
+            && context
+                .inner
+                .has_extension("EGL_EXT_gl_colorspace_display_p3");
+
+        if display_p3 {
+            let surface_attribs = [
+                EGL_GL_COLORSPACE_KHR,
+                EGL_GL_COLORSPACE_DISPLAY_P3_EXT,
+                EGL_NONE,
+            ];
+
+            let surface = unsafe {
+                egl_create_window_surface(
+                    context.inner.display,
+                    context.inner.config,
+                    window,
+                    surface_attribs.as_ptr(),
+                )
+            };
+
+            match surface {
+                Ok(surface) => {
+                    return Ok(Self {
+                        cx: context.inner.clone(),
+                        surface,
+                        color_space: ColorSpace::DisplayP3,
+                    });
+                }
+                Err(err) => warn!("Display P3 surface not supported, using sRGB: {}", err),
+            }
+        } else if color_space == ColorSpace::DisplayP3 {
+            warn!("EGL_EXT_gl_colorspace_display_p3 not supported, using sRGB");
+        }
+
         let surface_attribs = [EGL_NONE];
 
         let surface = unsafe {
@@ -187,6 +245,7 @@ impl EglSurface {
         Ok(Self {
             cx: context.inner.clone(),
             surface,
+            color_space: ColorSpace::Srgb,
         })
     }
 
@@ -195,9 +254,16 @@ impl EglSurface {
         Self {
             cx: context.inner.clone(),
             surface,
+            color_space: ColorSpace::Srgb,
         }
     }
 
+    /// The color space the surface was created with, the renderer has to tag its
+    /// surface with the same.
+    pub fn color_space(&self) -> ColorSpace {
+        self.color_space
+    }
+
     pub fn swap_interval(&self, interval: i32) -> Result<(), EglError> {
         unsafe {
             egl_swap_interval(self.cx.display, interval);
@@ -206,6 +272,17 @@ impl EglSurface {
         Ok(())
     }
 
+    /// Set the swap interval matching `present_mode`, the surface must be current.
+    pub fn set_present_mode(&self, present_mode: PresentMode) -> Result<(), EglError> {
+        // egl has no mailbox mode, vsync is the closest match as it doesn't tear
+        let interval = match present_mode {
+            PresentMode::AutoVsync | PresentMode::Mailbox => 1,
+            PresentMode::Immediate => 0,
+        };
+
+        self.swap_interval(interval)
+    }
+
     pub fn make_current(&self) -> Result<(), EglError> {
         unsafe {
             egl_make_current(self.cx.display, self.surface, self.surface, self.cx.context)?;
@@ -221,6 +298,48 @@ impl EglSurface {
 
         Ok(())
     }
+
+    /// Get the number of frames since the contents of the back buffer were presented, the
+    /// surface must be current.
+    ///
+    /// Returns 0 if the contents are undefined, or `EGL_EXT_buffer_age` isn't supported.
+    #[cfg(x11_platform)]
+    pub fn buffer_age(&self) -> usize {
+        if !self.cx.has_extension("EGL_EXT_buffer_age") {
+            return 0;
+        }
+
+        let age = unsafe { egl_query_surface(self.cx.display, self.surface, EGL_BUFFER_AGE_EXT) };
+        age.map_or(0, |age| age.max(0) as usize)
+    }
+
+    /// Swap the buffers, telling the compositor that only `damage` changed since the last
+    /// frame, `damage` is `[x, y, width, height]` in pixels from the top left.
+    ///
+    /// This is the same as [`EglSurface::swap_buffers`] if
+    /// `EGL_KHR_swap_buffers_with_damage` isn't supported.
+    #[cfg(x11_platform)]
+    pub fn swap_buffers_with_damage(&self, damage: [i32; 4]) -> Result<(), EglError> {
+        if !self.cx.has_extension("EGL_KHR_swap_buffers_with_damage") {
+            return self.swap_buffers();
+        }
+
+        let height = unsafe { egl_query_surface(self.cx.display, self.surface, EGL_HEIGHT) };
+
+        // egl rects start at the bottom left
+        let [x, y, width, rect_height] = damage;
+        let y = height.unwrap_or(0) - y - rect_height;
+
+        unsafe {
+            egl_swap_buffers_with_damage(
+                self.cx.display,
+                self.surface,
+                &[x, y, width, rect_height],
+            )?;
+        }
+
+        Ok(())
+    }
 }
 
 impl Drop for EglSurface {
@@ -245,6 +364,14 @@ const EGL_GREEN_SIZE: i32 = 0x3023;
 const EGL_BLUE_SIZE: i32 = 0x3022;
 const EGL_ALPHA_SIZE: i32 = 0x3021;
 
+const EGL_EXTENSIONS: i32 = 0x3055;
+#[cfg(x11_platform)]
+const EGL_HEIGHT: i32 = 0x3056;
+#[cfg(x11_platform)]
+const EGL_BUFFER_AGE_EXT: i32 = 0x313D;
+const EGL_GL_COLORSPACE_KHR: i32 = 0x309D;
+const EGL_GL_COLORSPACE_DISPLAY_P3_EXT: i32 = 0x3363;
+
 const EGL_CONTEXT_MAJOR_VERSION: i32 = 0x3098;
 const EGL_CONTEXT_MINOR_VERSION: i32 = 0x30FB;
 
@@ -553,6 +680,72 @@ unsafe fn egl_swap_interval(display: *mut ffi::c_void, interval: i32) {
     }
 }
 
+unsafe fn egl_query_string(display: *mut ffi::c_void, name: i32) -> String {
+    let egl_query_string = lib_egl_symbol::<
+        unsafe extern "C" fn(*mut ffi::c_void, i32) -> *const ffi::c_char,
+    >(b"eglQueryString");
+
+    let Ok(egl_query_string) = egl_query_string else {
+        return String::new();
+    };
+
+    let string = egl_query_string(display, name);
+
+    match string.is_null() {
+        true => String::new(),
+        false => ffi::CStr::from_ptr(string).to_string_lossy().into_owned(),
+    }
+}
+
+#[cfg(x11_platform)]
+unsafe fn egl_query_surface(
+    display: *mut ffi::c_void,
+    surface: *mut ffi::c_void,
+    attribute: i32,
+) -> Option<i32> {
+    let egl_query_surface = lib_egl_symbol::<
+        unsafe extern "C" fn(*mut ffi::c_void, *mut ffi::c_void, i32, *mut i32) -> i32,
+    >(b"eglQuerySurface")
+    .ok()?;
+
+    let mut value = 0;
+
+    if egl_query_surface(display, surface, attribute, &mut value) == 0 {
+        // clear the error, so it isn't reported by the next call
+        let _ = egl_get_error();
+        return None;
+    }
+
+    Some(value)
+}
+
+#[cfg(x11_platform)]
+unsafe fn egl_swap_buffers_with_damage(
+    display: *mut ffi::c_void,
+    surface: *mut ffi::c_void,
+    rects: &[i32],
+) -> Result<(), EglError> {
+    type SwapBuffersWithDamage =
+        unsafe extern "C" fn(*mut ffi::c_void, *mut ffi::c_void, *const i32, i32) -> i32;
+
+    let proc = egl_get_proc_address(c"eglSwapBuffersWithDamageKHR".as_ptr());
+
+    if proc.is_null() {
+        return egl_swap_buffers(display, surface);
+    }
+
+    let egl_swap_buffers_with_damage =
+        mem::transmute::<*const ffi::c_void, SwapBuffersWithDamage>(proc);
+    let result =
+        egl_swap_buffers_with_damage(display, surface, rects.as_ptr(), rects.len() as i32 / 4);
+
+    if result == 0 {
+        check_egl_error()?;
+    }
+
+    Ok(())
+}
+
 unsafe fn egl_swap_buffers(
     display: *mut ffi::c_void,
     surface: *mut ffi::c_void,
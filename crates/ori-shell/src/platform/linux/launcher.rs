@@ -0,0 +1,303 @@
+//! Progress and urgency on the launcher icon of the app, through the
+//! `com.canonical.Unity.LauncherEntry` D-Bus interface.
+//!
+//! Neither X11 nor Wayland have a protocol for taskbar progress, docks and taskbars like KDE
+//! Plasma, Dash to Dock and Plank listen for the `Update` signal of this interface on the
+//! session bus instead. Only the few messages needed to emit the signal are implemented here.
+
+use std::{
+    env,
+    io::{self, Read, Write},
+    os::unix::net::UnixStream,
+    path::PathBuf,
+    process,
+    time::Duration,
+};
+
+use tracing::warn;
+
+const TIMEOUT: Duration = Duration::from_secs(1);
+
+const METHOD_CALL: u8 = 1;
+const SIGNAL: u8 = 4;
+
+const FIELD_PATH: u8 = 1;
+const FIELD_INTERFACE: u8 = 2;
+const FIELD_MEMBER: u8 = 3;
+const FIELD_DESTINATION: u8 = 6;
+const FIELD_SIGNATURE: u8 = 8;
+
+/// The launcher entry of the app, shared by all its windows.
+pub struct LauncherEntry {
+    app_uri: String,
+    bus: Option<UnixStream>,
+    connected: bool,
+    serial: u32,
+    progress: Option<f32>,
+    urgent: bool,
+}
+
+impl LauncherEntry {
+    /// Create the launcher entry of the app with `app_id`, which should match the name of its
+    /// `.desktop` file. The session bus is connected to on the first update.
+    pub fn new(app_id: &str) -> Self {
+        Self {
+            app_uri: format!("application://{}.desktop", app_id),
+            bus: None,
+            connected: false,
+            // the hello message of the connection is the first
+            serial: 1,
+            progress: None,
+            urgent: false,
+        }
+    }
+
+    /// Set the progress shown on the launcher icon, `None` hides it.
+    pub fn set_progress(&mut self, progress: Option<f32>) {
+        if self.progress != progress {
+            self.progress = progress;
+            self.update();
+        }
+    }
+
+    /// Set whether the launcher icon requests the attention of the user.
+    pub fn set_urgent(&mut self, urgent: bool) {
+        if self.urgent != urgent {
+            self.urgent = urgent;
+            self.update();
+        }
+    }
+
+    fn update(&mut self) {
+        if !self.connected {
+            self.connected = true;
+
+            match connect() {
+                Ok(bus) => self.bus = Some(bus),
+                Err(err) => warn!("Failed to connect to the D-Bus session bus: {}", err),
+            }
+        }
+
+        let Some(ref mut bus) = self.bus else {
+            return;
+        };
+
+        self.serial += 1;
+
+        let path = format!("/com/canonical/unity/launcherentry/{}", process::id());
+        let body = update_body(&self.app_uri, self.progress, self.urgent);
+        let message = message(
+            SIGNAL,
+            self.serial,
+            &[
+                (FIELD_PATH, "o", &path),
+                (FIELD_INTERFACE, "s", "com.canonical.Unity.LauncherEntry"),
+                (FIELD_MEMBER, "s", "Update"),
+                (FIELD_SIGNATURE, "g", "sa{sv}"),
+            ],
+            &body,
+        );
+
+        if let Err(err) = bus.write_all(&message) {
+            warn!("Failed to update the launcher entry: {}", err);
+            self.bus = None;
+        }
+    }
+}
+
+fn connect() -> io::Result<UnixStream> {
+    let mut bus = UnixStream::connect(session_bus_path()?)?;
+    bus.set_read_timeout(Some(TIMEOUT))?;
+    bus.set_write_timeout(Some(TIMEOUT))?;
+
+    // authenticate with the credentials of the socket, the bus asks for them with an empty
+    // `DATA` challenge first
+    bus.write_all(b"\0AUTH EXTERNAL\r\n")?;
+
+    let mut line = read_line(&mut bus)?;
+
+    if line == "DATA" {
+        bus.write_all(b"DATA\r\n")?;
+        line = read_line(&mut bus)?;
+    }
+
+    if !line.starts_with("OK ") {
+        return Err(io::Error::other(format!("authentication failed: {}", line)));
+    }
+
+    bus.write_all(b"BEGIN\r\n")?;
+
+    // every connection must say hello before sending anything else, the reply is never read
+    let hello = message(
+        METHOD_CALL,
+        1,
+        &[
+            (FIELD_PATH, "o", "/org/freedesktop/DBus"),
+            (FIELD_DESTINATION, "s", "org.freedesktop.DBus"),
+            (FIELD_INTERFACE, "s", "org.freedesktop.DBus"),
+            (FIELD_MEMBER, "s", "Hello"),
+        ],
+        &[],
+    );
+    bus.write_all(&hello)?;
+
+    Ok(bus)
+}
+
+// only `unix:path=` addresses are supported, with a fallback to the default path of the bus
+fn session_bus_path() -> io::Result<PathBuf> {
+    if let Ok(address) = env::var("DBUS_SESSION_BUS_ADDRESS") {
+        let path = address
+            .split(';')
+            .filter_map(|address| address.strip_prefix("unix:"))
+            .flat_map(|options| options.split(','))
+            .find_map(|option| option.strip_prefix("path="));
+
+        if let Some(path) = path {
+            return Ok(PathBuf::from(path));
+        }
+    }
+
+    match env::var_os("XDG_RUNTIME_DIR") {
+        Some(dir) => Ok(PathBuf::from(dir).join("bus")),
+        None => Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            "no session bus address",
+        )),
+    }
+}
+
+fn read_line(bus: &mut UnixStream) -> io::Result<String> {
+    let mut line = Vec::new();
+    let mut byte = [0];
+
+    while !line.ends_with(b"\r\n") {
+        bus.read_exact(&mut byte)?;
+        line.push(byte[0]);
+    }
+
+    line.truncate(line.len() - 2);
+    Ok(String::from_utf8_lossy(&line).into_owned())
+}
+
+// the body of `Update(s app_uri, a{sv} properties)`
+fn update_body(app_uri: &str, progress: Option<f32>, urgent: bool) -> Vec<u8> {
+    let mut body = Vec::new();
+    put_str(&mut body, app_uri);
+
+    let length = body.len();
+    put_u32(&mut body, 0);
+    align(&mut body, 8);
+    let start = body.len();
+
+    align(&mut body, 8);
+    put_str(&mut body, "progress");
+    put_signature(&mut body, "d");
+    align(&mut body, 8);
+    body.extend(f64::from(progress.unwrap_or(0.0)).to_le_bytes());
+
+    align(&mut body, 8);
+    put_str(&mut body, "progress-visible");
+    put_signature(&mut body, "b");
+    put_u32(&mut body, progress.is_some() as u32);
+
+    align(&mut body, 8);
+    put_str(&mut body, "urgent");
+    put_signature(&mut body, "b");
+    put_u32(&mut body, urgent as u32);
+
+    // the length of an array doesn't include the padding before its first element
+    let array_length = (body.len() - start) as u32;
+    body[length..length + 4].copy_from_slice(&array_length.to_le_bytes());
+
+    body
+}
+
+// a little endian message with string, object path or signature header `fields`
+fn message(kind: u8, serial: u32, fields: &[(u8, &str, &str)], body: &[u8]) -> Vec<u8> {
+    let mut message = vec![b'l', kind, 0, 1];
+    put_u32(&mut message, body.len() as u32);
+    put_u32(&mut message, serial);
+
+    let length = message.len();
+    put_u32(&mut message, 0);
+    align(&mut message, 8);
+    let start = message.len();
+
+    for &(code, signature, value) in fields {
+        align(&mut message, 8);
+        message.push(code);
+        put_signature(&mut message, signature);
+
+        match signature {
+            "g" => put_signature(&mut message, value),
+            _ => put_str(&mut message, value),
+        }
+    }
+
+    let fields_length = (message.len() - start) as u32;
+    message[length..length + 4].copy_from_slice(&fields_length.to_le_bytes());
+
+    // the body always starts aligned to 8 bytes
+    align(&mut message, 8);
+    message.extend_from_slice(body);
+
+    message
+}
+
+fn align(buf: &mut Vec<u8>, alignment: usize) {
+    buf.resize(buf.len().next_multiple_of(alignment), 0);
+}
+
+fn put_u32(buf: &mut Vec<u8>, value: u32) {
+    align(buf, 4);
+    buf.extend(value.to_le_bytes());
+}
+
+fn put_str(buf: &mut Vec<u8>, value: &str) {
+    put_u32(buf, value.len() as u32);
+    buf.extend(value.as_bytes());
+    buf.push(0);
+}
+
+fn put_signature(buf: &mut Vec<u8>, value: &str) {
+    buf.push(value.len() as u8);
+    buf.extend(value.as_bytes());
+    buf.push(0);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn update_body_is_marshalled() {
+        let body = update_body("application://a.desktop", Some(0.5), false);
+
+        // the app uri is 4 + 23 + 1 bytes, the properties array starts padded to 32
+        assert_eq!(&body[..4], &23u32.to_le_bytes());
+        assert_eq!(&body[28..32], &76u32.to_le_bytes());
+        assert_eq!(body.len(), 32 + 76);
+
+        // the progress double is aligned to 8 bytes, after its key and signature
+        assert_eq!(&body[45..48], &[1, b'd', 0]);
+        assert_eq!(&body[48..56], &0.5f64.to_le_bytes());
+
+        // progress-visible is the next 8 byte aligned entry, and is true
+        assert_eq!(&body[77..80], &[1, b'b', 0]);
+        assert_eq!(&body[80..84], &1u32.to_le_bytes());
+    }
+
+    #[test]
+    fn message_header_is_padded() {
+        let message = message(SIGNAL, 7, &[(FIELD_MEMBER, "s", "Update")], &[1, 2, 3]);
+
+        assert_eq!(&message[..4], &[b'l', SIGNAL, 0, 1]);
+        assert_eq!(&message[4..8], &3u32.to_le_bytes());
+        assert_eq!(&message[8..12], &7u32.to_le_bytes());
+
+        // one field of 1 + 3 + 4 + 7 bytes, padded to 32 before the body
+        assert_eq!(&message[12..16], &15u32.to_le_bytes());
+        assert_eq!(&message[32..], &[1, 2, 3]);
+    }
+}
@@ -1,2 +1,3 @@
+pub mod launcher;
 #[allow(unused)]
 pub mod xkb;
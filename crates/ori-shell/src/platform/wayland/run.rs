@@ -2,16 +2,17 @@ use std::{mem, num::NonZero, sync::Arc, time::Duration};
 
 use ori_app::{App, AppBuilder, AppRequest, UiBuilder};
 use ori_core::{
+    canvas::ColorSpace,
     clipboard::{Clipboard, ClipboardBackend},
     command::CommandWaker,
-    event::{Code, Key, PointerButton, PointerId},
-    layout::{Point, Vector},
+    event::{Code, Key, PointerButton, PointerId, ScrollDelta},
+    layout::{Point, Rect, Vector},
     text::Fonts,
-    window::{Cursor, Window, WindowId, WindowUpdate},
+    window::{Cursor, PresentMode, Window, WindowId, WindowUpdate},
 };
 use ori_skia::{SkiaFonts, SkiaRenderer};
 use smithay_client_toolkit::{
-    compositor::{CompositorHandler, CompositorState, SurfaceData},
+    compositor::{CompositorHandler, CompositorState, Region, SurfaceData},
     delegate_compositor, delegate_output, delegate_pointer, delegate_registry, delegate_seat,
     delegate_shm, delegate_subcompositor, delegate_xdg_shell, delegate_xdg_window,
     output::{OutputHandler, OutputState},
@@ -66,7 +67,10 @@ use wayland_csd_frame::DecorationsFrame;
 
 use crate::platform::{
     egl::{EglContext, EglNativeDisplay, EglSurface},
-    linux::xkb::{XkbContext, XkbKeyboard},
+    linux::{
+        launcher::LauncherEntry,
+        xkb::{XkbContext, XkbKeyboard},
+    },
 };
 
 use super::error::WaylandError;
@@ -120,6 +124,8 @@ pub fn run<T>(app: AppBuilder<T>, data: &mut T) -> Result<(), WaylandError> {
 
     app.init(data);
 
+    let launcher = LauncherEntry::new(app.app_id());
+
     let mut state = State {
         running: true,
 
@@ -144,12 +150,15 @@ pub fn run<T>(app: AppBuilder<T>, data: &mut T) -> Result<(), WaylandError> {
 
         events: Vec::new(),
         windows: Vec::new(),
+
+        launcher,
     };
 
     while state.running {
+        // windows with a frame rate limit wait for their next frame before redrawing
         let timeout = match state.needs_redraw() {
-            true => Some(Duration::from_millis(2)),
-            false => None,
+            true => Some(state.redraw_delay(&app)),
+            false => app.control_flow().timeout(),
         };
 
         event_loop.dispatch(timeout, &mut state).unwrap();
@@ -195,8 +204,11 @@ fn handle_app_request<T>(
 
         AppRequest::CloseWindow(id) => {
             if let Some(index) = window_index_by_id(&state.windows, id) {
-                state.windows.remove(index);
+                let mut window = state.windows.remove(index);
+                window.release_renderer()?;
             }
+
+            app.remove_window(data, id);
         }
 
         AppRequest::DragWindow(id) => {
@@ -261,6 +273,10 @@ fn handle_app_request<T>(
                     window.scale_factor = scale;
                     window.needs_redraw = true;
                 }
+                // the ui scale is applied when rendering
+                WindowUpdate::UiScale(_) => {
+                    window.needs_redraw = true;
+                }
                 WindowUpdate::Resizable(resizable) => {
                     set_resizable(window, resizable);
                     window.resizable = resizable;
@@ -295,9 +311,39 @@ fn handle_app_request<T>(
                 WindowUpdate::Visible(_) => {
                     warn!("Setting window visibility is not supported on Wayland");
                 }
+                WindowUpdate::PresentMode(present_mode) => {
+                    window.present_mode = present_mode;
+
+                    if let Some(ref egl_surface) = window.egl_surface {
+                        egl_surface.make_current()?;
+                        egl_surface.set_present_mode(present_mode)?;
+                    }
+                }
+                // wayland has no protocol for either, the launcher icon shows them instead
+                WindowUpdate::Progress(progress) => {
+                    state.launcher.set_progress(progress);
+                }
+                WindowUpdate::Urgent(urgent) => {
+                    state.launcher.set_urgent(urgent);
+                }
+                WindowUpdate::RequestAttention => {
+                    warn!("Requesting attention is not supported on Wayland");
+                }
+                WindowUpdate::AllWorkspaces(all_workspaces) => {
+                    if all_workspaces {
+                        warn!("Showing windows on all workspaces is not supported on Wayland");
+                    }
+                }
                 WindowUpdate::Color(_) => {
                     window.needs_redraw = true;
                 }
+                WindowUpdate::InputRegion(region) => {
+                    let surface = window.xdg_window.wl_surface();
+                    set_input_region(&state.compositor, surface, region.as_deref())?;
+                    window.xdg_window.commit();
+                }
+                // popups are opened as regular windows, which can't be placed
+                WindowUpdate::Popup(_) => {}
                 WindowUpdate::Cursor(cursor) => {
                     window.cursor_icon = cursor_icon(cursor);
                     window.set_cursor_icon = true;
@@ -372,6 +418,13 @@ fn open_window<T>(
     );
 
     xdg_window.set_title(&window.title);
+    xdg_window.set_app_id(app.app_id());
+
+    if window.input_region.is_some() {
+        let surface = xdg_window.wl_surface();
+        set_input_region(&state.compositor, surface, window.input_region.as_deref())?;
+    }
+
     xdg_window.commit();
 
     xdg_window.xdg_surface().set_window_geometry(
@@ -399,6 +452,8 @@ fn open_window<T>(
         maximized: window.maximized,
         resizable: window.resizable,
         decorated: window.decorated,
+        present_mode: window.present_mode,
+        color_space: window.color_space,
         last_configure: None,
 
         pointers: Vec::new(),
@@ -425,6 +480,36 @@ fn open_window<T>(
     Ok(())
 }
 
+fn set_input_region(
+    compositor: &CompositorState,
+    surface: &WlSurface,
+    region: Option<&[Rect]>,
+) -> Result<(), WaylandError> {
+    let Some(rects) = region else {
+        // without a region the whole surface receives input
+        surface.set_input_region(None);
+        return Ok(());
+    };
+
+    // the region is in surface coordinates, which are logical pixels, and an empty region
+    // makes every click pass through the surface
+    let wl_region = Region::new(compositor)?;
+
+    for rect in rects {
+        let min = rect.min.round();
+        let max = rect.max.round();
+
+        let width = (max.x - min.x).max(0.0);
+        let height = (max.y - min.y).max(0.0);
+        wl_region.add(min.x as i32, min.y as i32, width as i32, height as i32);
+    }
+
+    // the request copies the region, so it can be destroyed right away
+    surface.set_input_region(Some(wl_region.wl_region()));
+
+    Ok(())
+}
+
 fn set_resizable(window: &WindowState, resizable: bool) {
     if resizable {
         window.xdg_window.set_min_size(None);
@@ -455,6 +540,11 @@ fn render_windows<T>(
             continue;
         }
 
+        // the redraw stays pending until the next frame is due, see `App::frame_delay`
+        if !app.frame_delay(window.id).is_zero() {
+            continue;
+        }
+
         window.needs_redraw = false;
 
         if let Some(draw_state) = app.draw_window(data, window.id) {
@@ -469,9 +559,11 @@ fn render_windows<T>(
                 fonts.downcast_mut().unwrap(),
                 &draw_state.canvas,
                 draw_state.clear_color,
+                egl_surface.color_space(),
                 window.physical_width,
                 window.physical_height,
-                window.scale_factor,
+                window.scale_factor * draw_state.ui_scale,
+                None,
             );
 
             egl_surface.swap_buffers()?;
@@ -550,11 +642,16 @@ fn handle_event<T>(
         Event::CloseRequested { id } => {
             if let Some(index) = window_index_by_id(&state.windows, id) {
                 if app.close_requested(data, id) {
-                    state.windows.remove(index);
+                    let mut window = state.windows.remove(index);
+                    window.release_renderer()?;
                 }
             }
         }
 
+        Event::Focused { id, focused } => {
+            app.window_focused(data, id, focused);
+        }
+
         Event::PointerMoved {
             id,
             object_id,
@@ -629,12 +726,24 @@ struct State {
 
     events: Vec<Event>,
     windows: Vec<WindowState>,
+
+    launcher: LauncherEntry,
 }
 
 impl State {
     fn needs_redraw(&self) -> bool {
         self.windows.iter().any(|w| w.needs_redraw)
     }
+
+    fn redraw_delay<T>(&self, app: &App<T>) -> Duration {
+        let delay = (self.windows.iter())
+            .filter(|w| w.needs_redraw)
+            .map(|w| app.frame_delay(w.id))
+            .min()
+            .unwrap_or_default();
+
+        delay.max(Duration::from_millis(2))
+    }
 }
 
 struct PointerState {
@@ -682,6 +791,11 @@ enum Event {
         id: WindowId,
     },
 
+    Focused {
+        id: WindowId,
+        focused: bool,
+    },
+
     PointerMoved {
         id: WindowId,
         object_id: ObjectId,
@@ -698,7 +812,7 @@ enum Event {
     PointerScroll {
         id: WindowId,
         object_id: ObjectId,
-        delta: Vector,
+        delta: ScrollDelta,
     },
 
     Keyboard {
@@ -729,6 +843,8 @@ struct WindowState {
     maximized: bool,
     resizable: bool,
     decorated: bool,
+    present_mode: PresentMode,
+    color_space: ColorSpace,
     last_configure: Option<WindowConfigure>,
 
     pointers: Vec<ObjectId>,
@@ -744,6 +860,16 @@ struct WindowState {
 }
 
 impl WindowState {
+    // the gpu resources must be freed while the context of the window is current
+    fn release_renderer(&mut self) -> Result<(), WaylandError> {
+        if let (Some(egl_surface), Some(renderer)) = (&self.egl_surface, &mut self.renderer) {
+            egl_surface.make_current()?;
+            renderer.release();
+        }
+
+        Ok(())
+    }
+
     fn resize(&mut self) -> Option<Event> {
         let Some(ref configure) = self.last_configure else {
             warn!("No last configure event for window {}", self.id);
@@ -981,10 +1107,11 @@ impl WindowHandler for State {
                 .unwrap();
 
                 let wl_egl_ptr = wl_egl_surface.ptr() as *mut _;
-                let egl_surface = EglSurface::new(&self.egl_context, wl_egl_ptr).unwrap();
+                let egl_surface =
+                    EglSurface::new(&self.egl_context, wl_egl_ptr, window.color_space).unwrap();
 
                 egl_surface.make_current().unwrap();
-                egl_surface.swap_interval(1).unwrap();
+                egl_surface.set_present_mode(window.present_mode).unwrap();
 
                 let renderer = unsafe {
                     SkiaRenderer::new(|symbol| self.egl_context.get_proc_address(symbol))
@@ -1290,7 +1417,18 @@ impl PointerHandler for State {
                     vertical,
                     ..
                 } => {
-                    let delta = Vector::new(-horizontal.discrete as f32, -vertical.discrete as f32);
+                    // wheels report discrete steps, trackpads only report a distance
+                    let delta = if horizontal.discrete != 0 || vertical.discrete != 0 {
+                        ScrollDelta::Lines(Vector::new(
+                            -horizontal.discrete as f32,
+                            -vertical.discrete as f32,
+                        ))
+                    } else {
+                        ScrollDelta::Pixels(Vector::new(
+                            -horizontal.absolute as f32,
+                            -vertical.absolute as f32,
+                        ))
+                    };
 
                     self.events.push(Event::PointerScroll {
                         id: window.id,
@@ -1338,12 +1476,26 @@ impl Dispatch<WlKeyboard, ()> for State {
             }
             KeyboardEvent::Enter { surface, .. } => {
                 if let Some(window) = window_by_surface(&mut state.windows, &surface) {
+                    if window.keyboards.is_empty() {
+                        (state.events).push(Event::Focused {
+                            id: window.id,
+                            focused: true,
+                        });
+                    }
+
                     window.keyboards.push(keyboard.keyboard.id());
                 }
             }
             KeyboardEvent::Leave { surface, .. } => {
                 if let Some(window) = window_by_surface(&mut state.windows, &surface) {
                     window.keyboards.retain(|id| *id != keyboard.keyboard.id());
+
+                    if window.keyboards.is_empty() {
+                        (state.events).push(Event::Focused {
+                            id: window.id,
+                            focused: false,
+                        });
+                    }
                 }
             }
             KeyboardEvent::Key {
@@ -1362,7 +1514,9 @@ impl Dispatch<WlKeyboard, ()> for State {
                 let layout = xkb_state.layout();
 
                 let keycode = scancode + 8;
-                let code = Code::from_linux_scancode(scancode as u8);
+                let code = u8::try_from(scancode)
+                    .ok()
+                    .and_then(Code::from_linux_scancode);
                 let keysym_raw = keymap.first_keysym(layout, keycode).unwrap();
                 let keysym = xkb_state.get_one_sym(keycode);
                 let key = keyboard.xkb_keyboard.keysym_to_key(keysym_raw);
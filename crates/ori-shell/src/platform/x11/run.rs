@@ -1,5 +1,5 @@
 use std::{
-    collections::{hash_map::Entry, HashMap},
+    collections::{hash_map::Entry, HashMap, VecDeque},
     sync::{
         mpsc::{Receiver, RecvTimeoutError, Sender},
         Arc,
@@ -11,13 +11,14 @@ use std::{
 use as_raw_xcb_connection::AsRawXcbConnection;
 use ori_app::{App, AppBuilder, AppRequest, UiBuilder};
 use ori_core::{
+    canvas::Damage,
     clipboard::Clipboard,
     command::CommandWaker,
-    event::{Code, Modifiers, PointerButton, PointerId},
+    event::{Code, Modifiers, PointerButton, PointerId, PointerTool, ScrollDelta},
     image::Image,
-    layout::{Point, Vector},
+    layout::{Affine, Point, Rect, Size, Vector},
     text::Fonts,
-    window::{Cursor, Window, WindowId, WindowUpdate},
+    window::{Cursor, Popup, Window, WindowId, WindowUpdate},
 };
 use ori_skia::{SkiaFonts, SkiaRenderer};
 
@@ -26,19 +27,22 @@ use x11rb::{
     atom_manager,
     connection::{Connection, RequestConnection},
     cursor::Handle as CursorHandle,
-    properties::WmSizeHints,
+    properties::{WmHints, WmSizeHints, WmSizeHintsSpecification},
     protocol::{
         render::{ConnectionExt as _, PictType},
+        shape::{self, ConnectionExt as _, SK, SO},
         sync::{ConnectionExt as _, Int64},
+        xinput::{self, ConnectionExt as _, DeviceClassData, DeviceType, Fp3232, XIEventMask},
         xkb::{
-            ConnectionExt as _, EventType as XkbEventType, MapPart as XkbMapPart,
+            BoolCtrl as XkbBoolCtrl, ConnectionExt as _, EventType as XkbEventType,
+            MapPart as XkbMapPart, PerClientFlag as XkbPerClientFlag,
             SelectEventsAux as XkbSelectEventsAux, ID as XkbID,
         },
         xproto::{
             AtomEnum, ChangeWindowAttributesAux, ClientMessageData, ClientMessageEvent,
-            ColormapAlloc, ConfigureWindowAux, ConnectionExt as _, CreateWindowAux,
-            Cursor as XCursor, EventMask, ModMask, PropMode, VisualClass, Visualid, WindowClass,
-            CLIENT_MESSAGE_EVENT,
+            ClipOrdering, ColormapAlloc, ConfigureWindowAux, ConnectionExt as _, CreateWindowAux,
+            Cursor as XCursor, EventMask, GrabMode, GrabStatus, Gravity, ModMask, NotifyDetail,
+            PropMode, Rectangle, VisualClass, Visualid, WindowClass, CLIENT_MESSAGE_EVENT,
         },
         Event as XEvent,
     },
@@ -50,7 +54,10 @@ use x11rb::{
 
 use crate::platform::{
     egl::{EglContext, EglNativeDisplay, EglSurface},
-    linux::xkb::{XkbContext, XkbKeyboard},
+    linux::{
+        launcher::LauncherEntry,
+        xkb::{XkbContext, XkbKeyboard},
+    },
 };
 
 use super::{clipboard::X11ClipboardServer, X11Error};
@@ -93,10 +100,15 @@ atom_manager! {
         _NET_WM_STATE,
         _NET_WM_STATE_MAXIMIZED_VERT,
         _NET_WM_STATE_MAXIMIZED_HORZ,
+        _NET_WM_STATE_DEMANDS_ATTENTION,
+        _NET_WM_STATE_STICKY,
         _NET_WM_WINDOW_TYPE,
         _NET_WM_WINDOW_TYPE_NORMAL,
         _NET_WM_WINDOW_TYPE_DIALOG,
         _NET_WM_WINDOW_TYPE_DOCK,
+        ABS_PRESSURE: b"Abs Pressure",
+        ABS_TILT_X: b"Abs Tilt X",
+        ABS_TILT_Y: b"Abs Tilt Y",
     }
 }
 
@@ -110,9 +122,57 @@ struct X11Window {
     renderer: SkiaRenderer,
     needs_redraw: bool,
     sync_counter: Option<u32>,
+
+    // the damaged region of the most recent frames in pixels, `None` if the whole window was
+    // damaged, the most recent frame is first
+    damage_history: VecDeque<Option<Rect>>,
 }
 
 impl X11Window {
+    // buffers older than this are rendered completely
+    const MAX_BUFFER_AGE: usize = 4;
+
+    // get the region of the window that must be rendered in pixels, or `None` to render the
+    // whole window
+    //
+    // the back buffer contains the frame presented `buffer_age` frames ago, so everything
+    // damaged since then must be rendered, or the whole window if the age is unknown
+    fn render_region(&mut self, damage: Damage, scale: f32) -> Option<Rect> {
+        let size = Size::new(self.physical_width as f32, self.physical_height as f32);
+        let bounds = Rect::min_size(Point::ZERO, size);
+
+        let region = match damage {
+            Damage::Rect(rect) => {
+                let rect = rect.transform(Affine::scale(Vector::all(scale)));
+
+                // round out to whole pixels, with a pixel of margin for antialiasing
+                let min = Point::new(rect.min.x.floor() - 1.0, rect.min.y.floor() - 1.0);
+                let max = Point::new(rect.max.x.ceil() + 1.0, rect.max.y.ceil() + 1.0);
+
+                Some(Rect::new(min, max).intersection(bounds))
+            }
+            Damage::None | Damage::Full => None,
+        };
+
+        self.damage_history.push_front(region);
+        self.damage_history.truncate(Self::MAX_BUFFER_AGE);
+
+        let age = self.egl_surface.buffer_age();
+
+        if age == 0 || age > self.damage_history.len() {
+            return None;
+        }
+
+        let mut history = self.damage_history.iter().take(age);
+        let mut region = (*history.next()?)?;
+
+        for damage in history {
+            region = region.union((*damage)?);
+        }
+
+        Some(region)
+    }
+
     fn set_title(
         window: u32,
         conn: &XCBConnection,
@@ -157,6 +217,26 @@ impl X11Window {
         Ok(())
     }
 
+    // without the hints most window managers ignore the position the window was created at,
+    // the static gravity places the content rather than the frame at the position
+    fn set_position_hints(
+        window: u32,
+        conn: &XCBConnection,
+        x: i32,
+        y: i32,
+    ) -> Result<(), X11Error> {
+        let size_hints = WmSizeHints {
+            position: Some((WmSizeHintsSpecification::UserSpecified, x, y)),
+            win_gravity: Some(Gravity::STATIC),
+            ..Default::default()
+        };
+
+        size_hints.set_normal_hints(conn, window)?;
+        conn.flush()?;
+
+        Ok(())
+    }
+
     fn set_icon(
         window: u32,
         conn: &XCBConnection,
@@ -312,18 +392,84 @@ impl X11Window {
         Ok(())
     }
 
+    fn set_urgent(window: u32, conn: &XCBConnection, urgent: bool) -> Result<(), X11Error> {
+        let mut hints = WmHints::get(conn, window)?.reply()?.unwrap_or_default();
+        hints.urgent = urgent;
+        hints.set(conn, window)?;
+
+        Ok(())
+    }
+
+    fn set_input_region(
+        window: u32,
+        conn: &XCBConnection,
+        scale_factor: f32,
+        region: Option<&[Rect]>,
+    ) -> Result<(), X11Error> {
+        let has_shape = conn
+            .extension_information(shape::X11_EXTENSION_NAME)?
+            .is_some();
+
+        let Some(region) = region else {
+            // removing the input shape makes the whole window receive input again, without the
+            // extension the window never had one
+            if has_shape {
+                conn.shape_mask(SO::SET, SK::INPUT, window, 0, 0, x11rb::NONE)?;
+            }
+
+            return Ok(());
+        };
+
+        if !has_shape {
+            warn!("Window input regions require the X11 SHAPE extension");
+            return Ok(());
+        }
+
+        let rectangles = input_rectangles(region, scale_factor);
+
+        // an empty list of rectangles is an empty shape, so every click passes through
+        conn.shape_rectangles(
+            SO::SET,
+            SK::INPUT,
+            ClipOrdering::UNSORTED,
+            window,
+            0,
+            0,
+            &rectangles,
+        )?;
+
+        Ok(())
+    }
+
     fn set_maximized(
         window: u32,
         screen: usize,
         conn: &XCBConnection,
         atoms: &Atoms,
         maximized: bool,
+    ) -> Result<(), X11Error> {
+        let states = [
+            atoms._NET_WM_STATE_MAXIMIZED_HORZ,
+            atoms._NET_WM_STATE_MAXIMIZED_VERT,
+        ];
+
+        Self::set_state(window, screen, conn, atoms, maximized, states)
+    }
+
+    // ask the window manager to add or remove up to two `_NET_WM_STATE` atoms
+    fn set_state(
+        window: u32,
+        screen: usize,
+        conn: &XCBConnection,
+        atoms: &Atoms,
+        enabled: bool,
+        states: [u32; 2],
     ) -> Result<(), X11Error> {
         let mut data = [0u32; 5];
 
-        data[0] = maximized as u32;
-        data[1] = atoms._NET_WM_STATE_MAXIMIZED_HORZ;
-        data[2] = atoms._NET_WM_STATE_MAXIMIZED_VERT;
+        data[0] = enabled as u32;
+        data[1] = states[0];
+        data[2] = states[1];
 
         let screen = conn.setup().roots[screen].root;
 
@@ -348,6 +494,13 @@ impl X11Window {
     }
 
     fn is_maximized(window: u32, conn: &XCBConnection, atoms: &Atoms) -> Result<bool, X11Error> {
+        let states = Self::get_states(window, conn, atoms)?;
+
+        Ok(states.contains(&atoms._NET_WM_STATE_MAXIMIZED_HORZ)
+            && states.contains(&atoms._NET_WM_STATE_MAXIMIZED_VERT))
+    }
+
+    fn get_states(window: u32, conn: &XCBConnection, atoms: &Atoms) -> Result<Vec<u32>, X11Error> {
         let reply = conn.get_property(
             false,
             window,
@@ -364,8 +517,54 @@ impl X11Window {
             .flatten()
             .collect::<Vec<_>>();
 
-        Ok(states.contains(&atoms._NET_WM_STATE_MAXIMIZED_HORZ)
-            && states.contains(&atoms._NET_WM_STATE_MAXIMIZED_VERT))
+        Ok(states)
+    }
+}
+
+// a valuator of an xinput device, eg. the pressure of a stylus
+#[derive(Clone, Copy, Debug)]
+struct X11Valuator {
+    number: u16,
+    min: f64,
+    max: f64,
+}
+
+impl X11Valuator {
+    // map a value of the valuator to `0.0..=1.0`
+    fn normalize(&self, value: f64) -> f64 {
+        if self.max <= self.min {
+            return 1.0;
+        }
+
+        ((value - self.min) / (self.max - self.min)).clamp(0.0, 1.0)
+    }
+}
+
+// a slave pointer device, and the stylus state it last reported
+#[derive(Clone, Debug)]
+struct X11PointerDevice {
+    tool: PointerTool,
+    pressure_valuator: Option<X11Valuator>,
+    tilt_x_valuator: Option<X11Valuator>,
+    tilt_y_valuator: Option<X11Valuator>,
+    pressure: f32,
+    tilt: Vector,
+}
+
+impl X11PointerDevice {
+    fn set_valuator(&mut self, number: u16, value: f64) {
+        if let Some(valuator) = self.pressure_valuator.filter(|v| v.number == number) {
+            self.pressure = valuator.normalize(value) as f32;
+        }
+
+        // both the wacom and the libinput drivers report tilt in degrees
+        if self.tilt_x_valuator.is_some_and(|v| v.number == number) {
+            self.tilt.x = (value as f32).to_radians();
+        }
+
+        if self.tilt_y_valuator.is_some_and(|v| v.number == number) {
+            self.tilt.y = (value as f32).to_radians();
+        }
     }
 }
 
@@ -375,6 +574,7 @@ pub fn run<T>(app: AppBuilder<T>, data: &mut T, options: X11RunOptions) -> Resul
     let conn = Arc::new(conn);
 
     X11App::<T>::init_xkb(&conn)?;
+    let xinput = X11App::<T>::init_xinput(&conn, conn.setup().roots[screen_num].root)?;
 
     let atoms = Atoms::new(&conn)?.reply()?;
     let (clipboard_server, clipboard) = X11ClipboardServer::new(&conn, atoms)?;
@@ -429,6 +629,16 @@ pub fn run<T>(app: AppBuilder<T>, data: &mut T, options: X11RunOptions) -> Resul
     let mut app = app.build(waker, fonts);
     app.add_context(Clipboard::new(Box::new(clipboard)));
 
+    // without randr the root window is the only monitor we know of, it covers all of them
+    let screen = &conn.setup().roots[screen_num];
+    let root_size = Size::new(
+        screen.width_in_pixels as f32,
+        screen.height_in_pixels as f32,
+    );
+    app.set_monitors(vec![Rect::min_size(Point::ZERO, root_size)]);
+
+    let launcher = LauncherEntry::new(app.app_id());
+
     let mut state = X11App {
         options,
         app,
@@ -447,24 +657,41 @@ pub fn run<T>(app: AppBuilder<T>, data: &mut T, options: X11RunOptions) -> Resul
         egl_context,
         xkb_context,
         core_keyboard,
+
+        xinput,
+        pointer_devices: HashMap::new(),
+
+        launcher,
     };
 
+    state.query_pointer_devices()?;
+
     state.app.init(data);
     state.handle_app_requests(data)?;
 
     while state.running {
         state.conn.flush()?;
 
-        let mut event_option = if state.needs_redraw() {
-            state.event_rx.try_recv().ok()
-        } else {
-            match state.event_rx.recv_timeout(Duration::from_millis(2)) {
+        // windows with a frame rate limit wait for their next frame before redrawing
+        let timeout = match state.needs_redraw() {
+            true => Some(state.redraw_delay()),
+            false => state.app.control_flow().timeout(),
+        };
+
+        // when nothing is scheduled, we block until an event arrives or the waker is called
+        let mut event_option = match timeout {
+            Some(Duration::ZERO) => state.event_rx.try_recv().ok(),
+            Some(timeout) => match state.event_rx.recv_timeout(timeout) {
                 Ok(event) => Some(event),
                 Err(err) => match err {
                     RecvTimeoutError::Timeout => None,
                     RecvTimeoutError::Disconnected => break,
                 },
-            }
+            },
+            None => match state.event_rx.recv() {
+                Ok(event) => Some(event),
+                Err(_) => break,
+            },
         };
 
         while let Some(event) = event_option {
@@ -506,6 +733,11 @@ struct X11App<T> {
     egl_context: EglContext,
     xkb_context: XkbContext,
     core_keyboard: XkbKeyboard,
+
+    xinput: bool,
+    pointer_devices: HashMap<u16, X11PointerDevice>,
+
+    launcher: LauncherEntry,
 }
 
 impl<T> X11App<T> {
@@ -521,6 +753,15 @@ impl<T> X11App<T> {
         self.windows.iter().any(|w| w.needs_redraw)
     }
 
+    fn redraw_delay(&self) -> Duration {
+        self.windows
+            .iter()
+            .filter(|w| w.needs_redraw)
+            .map(|w| self.app.frame_delay(w.ori_id))
+            .min()
+            .unwrap_or_default()
+    }
+
     fn handle_commands(&mut self, data: &mut T) -> Result<(), X11Error> {
         self.app.handle_commands(data);
 
@@ -530,7 +771,7 @@ impl<T> X11App<T> {
     fn open_window(
         &mut self,
         data: &mut T,
-        window: Window,
+        mut window: Window,
         ui: UiBuilder<T>,
     ) -> Result<(), X11Error> {
         let win_id = self.conn.generate_id()?;
@@ -552,11 +793,14 @@ impl<T> X11App<T> {
                     | EventMask::BUTTON_PRESS
                     | EventMask::BUTTON_RELEASE
                     | EventMask::KEY_PRESS
-                    | EventMask::KEY_RELEASE,
+                    | EventMask::KEY_RELEASE
+                    | EventMask::FOCUS_CHANGE,
             )
             .background_pixel(0)
             .border_pixel(screen.black_pixel)
-            .colormap(colormap_id);
+            .colormap(colormap_id)
+            // popups are placed by us, and must not be managed by the window manager
+            .override_redirect(u32::from(window.is_popup()));
 
         let scale_factor = 1.0;
         let physical_width = (window.size.width * scale_factor) as u32;
@@ -567,12 +811,23 @@ impl<T> X11App<T> {
             None => screen.root,
         };
 
+        // windows restored at a position are placed there, the window manager may still move them
+        let position = window.position.filter(|_| !window.is_popup());
+
+        let (x, y) = match position {
+            Some(position) => (
+                (position.x * scale_factor).round() as i16,
+                (position.y * scale_factor).round() as i16,
+            ),
+            None => self.popup_position(window.popup, parent)?,
+        };
+
         self.conn.create_window(
             depth,
             win_id,
             parent,
-            0,
-            0,
+            x,
+            y,
             physical_width as u16,
             physical_height as u16,
             0,
@@ -594,7 +849,7 @@ impl<T> X11App<T> {
             win_id,
             AtomEnum::WM_CLASS,
             AtomEnum::STRING,
-            b"ori\0",
+            &wm_class(self.app.app_id()),
         )?;
 
         let sync_counter = if self
@@ -622,6 +877,10 @@ impl<T> X11App<T> {
         X11Window::set_title(win_id, &self.conn, &self.atoms, &window.title)?;
         X11Window::set_decorated(win_id, &self.conn, &self.atoms, window.decorated)?;
 
+        if position.is_some() {
+            X11Window::set_position_hints(win_id, &self.conn, x as i32, y as i32)?;
+        }
+
         if !window.resizable {
             X11Window::set_resizable(win_id, &self.conn, &self.atoms, window.resizable)?;
             X11Window::set_size_hints(
@@ -637,11 +896,20 @@ impl<T> X11App<T> {
             X11Window::set_icon(win_id, &self.conn, &self.atoms, icon)?;
         }
 
+        if window.input_region.is_some() {
+            X11Window::set_input_region(
+                win_id,
+                &self.conn,
+                scale_factor,
+                window.input_region.as_deref(),
+            )?;
+        }
+
         self.conn.flush()?;
 
-        let egl_surface = EglSurface::new(&self.egl_context, win_id as _)?;
+        let egl_surface = EglSurface::new(&self.egl_context, win_id as _, window.color_space)?;
         egl_surface.make_current()?;
-        egl_surface.swap_interval(0)?;
+        egl_surface.set_present_mode(window.present_mode)?;
 
         let renderer = unsafe {
             SkiaRenderer::new(|name| {
@@ -660,26 +928,120 @@ impl<T> X11App<T> {
             renderer,
             needs_redraw: true,
             sync_counter,
+            damage_history: VecDeque::new(),
         };
 
+        self.select_pointer_devices(win_id)?;
+
         if window.visible {
             self.conn.map_window(win_id)?;
+
+            // override-redirect windows are viewable as soon as they're mapped
+            if window.popup.is_some_and(|popup| popup.grab) {
+                self.grab_pointer(win_id)?;
+            }
         }
 
         self.conn.flush()?;
 
+        // popups drawn by views are shown in override-redirect windows of their own
+        window.popup_windows = true;
+
         self.windows.push(x11_window);
         self.app.add_window(data, ui, window);
 
         Ok(())
     }
 
-    fn close_window(&mut self, id: WindowId) -> Result<(), X11Error> {
+    // the position of a popup in the coordinates of `root`, other windows are placed at the origin
+    fn popup_position(&self, popup: Option<Popup>, root: u32) -> Result<(i16, i16), X11Error> {
+        let Some(popup) = popup else {
+            return Ok((0, 0));
+        };
+
+        let Some(index) = self.get_window_ori(popup.parent) else {
+            return Ok((0, 0));
+        };
+
+        let parent = &self.windows[index];
+        let ui_scale = (self.app.get_window(popup.parent)).map_or(1.0, |w| w.ui_scale);
+        let scale = parent.scale_factor * ui_scale;
+        let x = (popup.position.x * scale).round() as i16;
+        let y = (popup.position.y * scale).round() as i16;
+
+        let reply = (self.conn)
+            .translate_coordinates(parent.x11_id, root, x, y)?
+            .reply()?;
+
+        Ok((reply.dst_x, reply.dst_y))
+    }
+
+    // move a popup to follow its parent, or to its new position
+    fn move_popup(&self, x11_id: u32, popup: Option<Popup>) -> Result<(), X11Error> {
+        let root = self.conn.setup().roots[self.screen].root;
+        let (x, y) = self.popup_position(popup, root)?;
+
+        let aux = ConfigureWindowAux::new().x(x as i32).y(y as i32);
+        self.conn.configure_window(x11_id, &aux)?;
+
+        Ok(())
+    }
+
+    fn move_popups(&self, parent: WindowId) -> Result<(), X11Error> {
+        for window in &self.windows {
+            let popup = (self.app.get_window(window.ori_id)).and_then(|w| w.popup);
+
+            if popup.is_some_and(|popup| popup.parent == parent) {
+                self.move_popup(window.x11_id, popup)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    // grab the pointer for a popup, so presses outside of it are delivered to it and close it,
+    // with `owner_events` the windows of the app still receive their own events
+    fn grab_pointer(&self, x11_id: u32) -> Result<(), X11Error> {
+        let reply = (self.conn)
+            .grab_pointer(
+                true,
+                x11_id,
+                EventMask::BUTTON_PRESS | EventMask::BUTTON_RELEASE | EventMask::POINTER_MOTION,
+                GrabMode::ASYNC,
+                GrabMode::ASYNC,
+                x11rb::NONE,
+                x11rb::NONE,
+                x11rb::CURRENT_TIME,
+            )?
+            .reply()?;
+
+        if reply.status != GrabStatus::SUCCESS {
+            warn!("Failed to grab the pointer for a popup: {:?}", reply.status);
+        }
+
+        Ok(())
+    }
+
+    fn close_window(&mut self, data: &mut T, id: WindowId) -> Result<(), X11Error> {
         if let Some(index) = self.windows.iter().position(|w| w.ori_id == id) {
-            let window = self.windows.remove(index);
+            let mut window = self.windows.remove(index);
+
+            // the gpu resources must be freed while the context of the window is current
+            window.egl_surface.make_current()?;
+            window.renderer.release();
 
             self.conn.destroy_window(window.x11_id)?;
-            self.app.remove_window(id);
+            self.app.remove_window(data, id);
+
+            // the grab ends with the popup that held it, the last grabbing popup takes it over
+            let grabbing = self.windows.iter().rev().find(|window| {
+                let popup = (self.app.get_window(window.ori_id)).and_then(|w| w.popup);
+                popup.is_some_and(|popup| popup.grab)
+            });
+
+            if let Some(window) = grabbing {
+                self.grab_pointer(window.x11_id)?;
+            }
         }
 
         Ok(())
@@ -693,7 +1055,7 @@ impl<T> X11App<T> {
 
     fn render_windows(&mut self, data: &mut T) -> Result<(), X11Error> {
         for window in &mut self.windows {
-            if !window.needs_redraw {
+            if !window.needs_redraw || !self.app.frame_delay(window.ori_id).is_zero() {
                 continue;
             }
 
@@ -702,18 +1064,35 @@ impl<T> X11App<T> {
             if let Some(state) = self.app.draw_window(data, window.ori_id) {
                 window.egl_surface.make_current()?;
 
+                let scale = window.scale_factor * state.ui_scale;
+                let region = window.render_region(state.damage, scale);
+
                 let fonts = self.app.contexts.get_mut::<Box<dyn Fonts>>().unwrap();
 
                 window.renderer.render(
                     fonts.downcast_mut().unwrap(),
                     &state.canvas,
                     state.clear_color,
+                    window.egl_surface.color_space(),
                     window.physical_width,
                     window.physical_height,
-                    window.scale_factor,
+                    scale,
+                    region,
                 );
 
-                window.egl_surface.swap_buffers()?;
+                match region {
+                    Some(region) => {
+                        let damage = [
+                            region.min.x as i32,
+                            region.min.y as i32,
+                            region.width() as i32,
+                            region.height() as i32,
+                        ];
+
+                        window.egl_surface.swap_buffers_with_damage(damage)?;
+                    }
+                    None => window.egl_surface.swap_buffers()?,
+                }
             }
         }
 
@@ -746,7 +1125,7 @@ impl<T> X11App<T> {
     fn handle_app_request(&mut self, data: &mut T, request: AppRequest<T>) -> Result<(), X11Error> {
         match request {
             AppRequest::OpenWindow(window, ui) => self.open_window(data, window, ui)?,
-            AppRequest::CloseWindow(id) => self.close_window(id)?,
+            AppRequest::CloseWindow(id) => self.close_window(data, id)?,
             AppRequest::DragWindow(_id) => {
                 warn!("DragWindow is not supported on X11");
             }
@@ -792,6 +1171,8 @@ impl<T> X11App<T> {
                         self.conn.configure_window(window.x11_id, &aux)?;
                     }
                     WindowUpdate::Scale(_) => {}
+                    // the ui scale is applied when rendering
+                    WindowUpdate::UiScale(_) => {}
                     WindowUpdate::Resizable(resizable) => {
                         X11Window::set_resizable(
                             window.x11_id,
@@ -831,9 +1212,51 @@ impl<T> X11App<T> {
                             self.conn.unmap_window(window.x11_id)?;
                         }
                     }
+                    WindowUpdate::PresentMode(present_mode) => {
+                        window.egl_surface.make_current()?;
+                        window.egl_surface.set_present_mode(present_mode)?;
+                    }
+                    WindowUpdate::Progress(progress) => {
+                        self.launcher.set_progress(progress);
+                    }
+                    WindowUpdate::Urgent(urgent) => {
+                        X11Window::set_urgent(window.x11_id, &self.conn, urgent)?;
+                    }
+                    WindowUpdate::RequestAttention => {
+                        X11Window::set_state(
+                            window.x11_id,
+                            self.screen,
+                            &self.conn,
+                            &self.atoms,
+                            true,
+                            [self.atoms._NET_WM_STATE_DEMANDS_ATTENTION, 0],
+                        )?;
+                    }
+                    WindowUpdate::AllWorkspaces(all_workspaces) => {
+                        X11Window::set_state(
+                            window.x11_id,
+                            self.screen,
+                            &self.conn,
+                            &self.atoms,
+                            all_workspaces,
+                            [self.atoms._NET_WM_STATE_STICKY, 0],
+                        )?;
+                    }
                     WindowUpdate::Color(_) => {
                         self.request_redraw(id);
                     }
+                    WindowUpdate::InputRegion(region) => {
+                        X11Window::set_input_region(
+                            window.x11_id,
+                            &self.conn,
+                            window.scale_factor,
+                            region.as_deref(),
+                        )?;
+                    }
+                    WindowUpdate::Popup(popup) => {
+                        let x11_id = window.x11_id;
+                        self.move_popup(x11_id, popup)?;
+                    }
                     WindowUpdate::Cursor(cursor) => {
                         let x_window = window.x11_id;
                         self.set_cursor(x_window, cursor)?;
@@ -841,6 +1264,8 @@ impl<T> X11App<T> {
                     WindowUpdate::Ime(_) => {}
                 }
             }
+            // the app only requests to quit once the delegates have cleaned up, the cleanup
+            // futures wake the loop through the command waker while it keeps running
             AppRequest::Quit => self.running = false,
         }
 
@@ -883,6 +1308,28 @@ impl<T> X11App<T> {
                         (self.app).window_resized(data, id, logical_width, logical_height);
                         window.needs_redraw = true;
                     }
+
+                    // remember where the window is, for its placement
+                    let window = &self.windows[index];
+                    let id = window.ori_id;
+
+                    if (self.app.get_window(id)).is_some_and(|w| !w.is_popup()) {
+                        let root = self.conn.setup().roots[self.screen].root;
+                        let reply = (self.conn)
+                            .translate_coordinates(window.x11_id, root, 0, 0)?
+                            .reply()?;
+
+                        let scale_factor = window.scale_factor;
+                        let x = reply.dst_x as f32 / scale_factor;
+                        let y = reply.dst_y as f32 / scale_factor;
+
+                        if let Some(app_window) = self.app.get_window_mut(id) {
+                            app_window.position = Some(Point::new(x, y));
+                        }
+                    }
+
+                    // popups follow their parent when it moves
+                    self.move_popups(id)?;
                 }
             }
             XEvent::ClientMessage(event) => {
@@ -943,6 +1390,38 @@ impl<T> X11App<T> {
                     self.pointer_button(data, self.windows[index].ori_id, event.detail, false);
                 }
             }
+            XEvent::XinputMotion(event)
+            | XEvent::XinputButtonPress(event)
+            | XEvent::XinputButtonRelease(event) => {
+                self.pointer_valuators(&event);
+            }
+            XEvent::XinputHierarchy(_) => {
+                self.query_pointer_devices()?;
+
+                for window in &self.windows {
+                    self.select_pointer_devices(window.x11_id)?;
+                }
+            }
+            XEvent::FocusIn(event) => {
+                if event.detail == NotifyDetail::POINTER {
+                    return Ok(());
+                }
+
+                if let Some(index) = self.get_window_x11(event.event) {
+                    let id = self.windows[index].ori_id;
+                    self.app.window_focused(data, id, true);
+                }
+            }
+            XEvent::FocusOut(event) => {
+                if event.detail == NotifyDetail::POINTER {
+                    return Ok(());
+                }
+
+                if let Some(index) = self.get_window_x11(event.event) {
+                    let id = self.windows[index].ori_id;
+                    self.app.window_focused(data, id, false);
+                }
+            }
             XEvent::XkbStateNotify(event) => {
                 let state = self.core_keyboard.state().unwrap();
 
@@ -1009,11 +1488,12 @@ impl<T> X11App<T> {
 
         match code {
             4..=7 => {
+                // the core protocol only reports wheel clicks, one line each
                 let delta = match code {
-                    4 => Vector::Y,
-                    5 => Vector::NEG_Y,
-                    6 => Vector::X,
-                    7 => Vector::NEG_X,
+                    4 => ScrollDelta::Lines(Vector::Y),
+                    5 => ScrollDelta::Lines(Vector::NEG_Y),
+                    6 => ScrollDelta::Lines(Vector::X),
+                    7 => ScrollDelta::Lines(Vector::NEG_X),
                     _ => unreachable!(),
                 };
 
@@ -1027,6 +1507,125 @@ impl<T> X11App<T> {
         }
     }
 
+    // xinput events are delivered before the core events of the same input, so the stylus
+    // state is up to date when the app receives the core event
+    fn pointer_valuators(&mut self, event: &xinput::ButtonPressEvent) {
+        let Some(index) = self.get_window_x11(event.event) else {
+            return;
+        };
+
+        let Some(device) = self.pointer_devices.get_mut(&event.sourceid) else {
+            return;
+        };
+
+        // only the valuators that changed are reported, in the order of their numbers
+        let mut values = event.axisvalues.iter();
+
+        for number in 0..event.valuator_mask.len() * 32 {
+            if event.valuator_mask[number / 32] & (1 << (number % 32)) == 0 {
+                continue;
+            }
+
+            let Some(&value) = values.next() else {
+                break;
+            };
+
+            device.set_valuator(number as u16, fp3232(value));
+        }
+
+        let pointer_id = PointerId::from_hash(&event.child);
+        let (pressure, tilt, tool) = (device.pressure, device.tilt, device.tool);
+
+        let id = self.windows[index].ori_id;
+        (self.app).pointer_stylus(id, pointer_id, pressure, tilt, tool);
+    }
+
+    fn query_pointer_devices(&mut self) -> Result<(), X11Error> {
+        self.pointer_devices.clear();
+
+        if !self.xinput {
+            return Ok(());
+        }
+
+        let reply = self
+            .conn
+            .xinput_xi_query_device(xinput::Device::ALL)?
+            .reply()?;
+
+        for info in reply.infos {
+            if info.type_ != DeviceType::SLAVE_POINTER {
+                continue;
+            }
+
+            let mut device = X11PointerDevice {
+                tool: PointerTool::Mouse,
+                pressure_valuator: None,
+                tilt_x_valuator: None,
+                tilt_y_valuator: None,
+                pressure: 1.0,
+                tilt: Vector::ZERO,
+            };
+
+            for class in info.classes {
+                let DeviceClassData::Valuator(data) = class.data else {
+                    continue;
+                };
+
+                let valuator = X11Valuator {
+                    number: data.number,
+                    min: fp3232(data.min),
+                    max: fp3232(data.max),
+                };
+
+                if data.label == self.atoms.ABS_PRESSURE {
+                    device.pressure_valuator = Some(valuator);
+                } else if data.label == self.atoms.ABS_TILT_X {
+                    device.tilt_x_valuator = Some(valuator);
+                } else if data.label == self.atoms.ABS_TILT_Y {
+                    device.tilt_y_valuator = Some(valuator);
+                } else {
+                    continue;
+                }
+
+                device.set_valuator(data.number, fp3232(data.value));
+            }
+
+            // the wacom driver exposes the eraser end of a stylus as a device of its own
+            let name = String::from_utf8_lossy(&info.name).to_lowercase();
+
+            device.tool = if name.contains("eraser") {
+                PointerTool::Eraser
+            } else if device.pressure_valuator.is_some() {
+                PointerTool::Pen
+            } else {
+                PointerTool::Mouse
+            };
+
+            self.pointer_devices.insert(info.deviceid, device);
+        }
+
+        Ok(())
+    }
+
+    fn select_pointer_devices(&self, window: u32) -> Result<(), X11Error> {
+        // only the slave devices are selected, selecting the master pointer would replace the
+        // core events of the window
+        let mask = XIEventMask::MOTION | XIEventMask::BUTTON_PRESS | XIEventMask::BUTTON_RELEASE;
+
+        let masks = (self.pointer_devices.keys())
+            .map(|&deviceid| xinput::EventMask {
+                deviceid,
+                mask: vec![mask],
+            })
+            .collect::<Vec<_>>();
+
+        if !masks.is_empty() {
+            self.conn.xinput_xi_select_events(window, &masks)?;
+        }
+
+        Ok(())
+    }
+
     /// Choose a direct bgra8888 visual with 32-bit depth.
     fn choose_visual(&self) -> Result<(u8, Visualid), X11Error> {
         let screen = &self.conn.setup().roots[self.screen];
@@ -1088,6 +1687,32 @@ impl<T> X11App<T> {
         Ok((screen.root_depth, screen.root_visual))
     }
 
+    // the core protocol only reports the position of a pointer, the pressure and tilt of a
+    // stylus are reported by xinput 2 as valuators of its device
+    fn init_xinput(conn: &XCBConnection, root: u32) -> Result<bool, X11Error> {
+        if conn
+            .extension_information(xinput::X11_EXTENSION_NAME)?
+            .is_none()
+        {
+            return Ok(false);
+        }
+
+        let version = conn.xinput_xi_query_version(2, 0)?.reply()?;
+
+        if version.major_version < 2 {
+            return Ok(false);
+        }
+
+        // tablets can be plugged in while the app is running
+        let mask = xinput::EventMask {
+            deviceid: xinput::Device::ALL.into(),
+            mask: vec![XIEventMask::HIERARCHY],
+        };
+        conn.xinput_xi_select_events(root, &[mask])?;
+
+        Ok(true)
+    }
+
     fn init_xkb(conn: &XCBConnection) -> Result<(), X11Error> {
         conn.xkb_use_extension(1, 0)?;
 
@@ -1102,6 +1727,121 @@ impl<T> X11App<T> {
             &XkbSelectEventsAux::new(),
         )?;
 
+        // without detectable auto repeat, held keys are reported as release and press pairs,
+        // with it only the presses are repeated, which the app recognizes as repeats
+        let auto_repeat = XkbPerClientFlag::DETECTABLE_AUTO_REPEAT;
+        conn.xkb_per_client_flags(
+            XkbID::USE_CORE_KBD.into(),
+            auto_repeat,
+            auto_repeat,
+            XkbBoolCtrl::default(),
+            XkbBoolCtrl::default(),
+            XkbBoolCtrl::default(),
+        )?;
+
         Ok(())
     }
 }
+
+fn fp3232(value: Fp3232) -> f64 {
+    value.integral as f64 + value.frac as f64 / (1u64 << 32) as f64
+}
+
+// `WM_CLASS` is the instance name followed by the class name, both null terminated,
+// by convention the class name is capitalized, eg. `todos\0Todos\0`
+fn wm_class(app_id: &str) -> Vec<u8> {
+    let mut chars = app_id.chars();
+
+    let class = match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    };
+
+    let mut bytes = Vec::with_capacity(app_id.len() + class.len() + 2);
+    bytes.extend_from_slice(app_id.as_bytes());
+    bytes.push(0);
+    bytes.extend_from_slice(class.as_bytes());
+    bytes.push(0);
+    bytes
+}
+
+// the rectangles of an input shape in physical pixels
+fn input_rectangles(region: &[Rect], scale_factor: f32) -> Vec<Rectangle> {
+    (region.iter())
+        .map(|rect| {
+            let min = rect.min * scale_factor;
+            let size = rect.size() * scale_factor;
+
+            Rectangle {
+                x: min.x.round() as i16,
+                y: min.y.round() as i16,
+                width: size.width.round().max(0.0) as u16,
+                height: size.height.round().max(0.0) as u16,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wm_class_contains_app_id() {
+        assert_eq!(wm_class("todos"), b"todos\0Todos\0");
+        assert_eq!(wm_class("org.ori.Demo"), b"org.ori.Demo\0Org.ori.Demo\0");
+    }
+
+    #[test]
+    fn input_shape_is_scaled_to_physical_pixels() {
+        let region = [Rect::new(Point::new(1.0, 2.5), Point::new(11.0, 7.5))];
+
+        let rectangles = input_rectangles(&region, 2.0);
+        let expected = Rectangle {
+            x: 2,
+            y: 5,
+            width: 20,
+            height: 10,
+        };
+
+        assert_eq!(rectangles, [expected]);
+
+        // a click-through window sets an empty shape, rather than removing it
+        assert!(input_rectangles(&[], 2.0).is_empty());
+    }
+
+    #[test]
+    fn stylus_valuators_are_normalized() {
+        let half = Fp3232 {
+            integral: 512,
+            frac: 1 << 31,
+        };
+        assert_eq!(fp3232(half), 512.5);
+
+        let mut device = X11PointerDevice {
+            tool: PointerTool::Pen,
+            pressure_valuator: Some(X11Valuator {
+                number: 2,
+                min: 0.0,
+                max: 2048.0,
+            }),
+            tilt_x_valuator: Some(X11Valuator {
+                number: 3,
+                min: -64.0,
+                max: 63.0,
+            }),
+            tilt_y_valuator: None,
+            pressure: 1.0,
+            tilt: Vector::ZERO,
+        };
+
+        device.set_valuator(2, 512.0);
+        device.set_valuator(3, 45.0);
+        assert_eq!(device.pressure, 0.25);
+        assert_eq!(device.tilt, Vector::new(45f32.to_radians(), 0.0));
+
+        // values outside the range of the valuator are clamped
+        device.set_valuator(2, 4096.0);
+        assert_eq!(device.pressure, 1.0);
+    }
+}
@@ -0,0 +1,71 @@
+//! Compares rendering 10k quads as individual rectangles against a single instanced mesh,
+//! drawn by the renderer onto a raster surface.
+//!
+//! Run with `cargo bench -p ori-skia --bench instanced_quads`.
+
+use std::{hint::black_box, time::Instant};
+
+use ori_core::{
+    canvas::{Canvas, Color, QuadInstance},
+    layout::{Point, Rect, Size},
+};
+use ori_skia::{SkiaFonts, SkiaRenderer};
+use skia_safe::{surfaces, Surface};
+
+const QUADS: usize = 10_000;
+const FRAMES: u32 = 100;
+
+fn quads() -> Vec<QuadInstance> {
+    (0..QUADS)
+        .map(|i| {
+            let x = (i % 100) as f32 * 12.0;
+            let y = (i / 100) as f32 * 12.0;
+
+            let rect = Rect::min_size(Point::new(x, y), Size::all(10.0));
+            let color = Color::hsl(i as f32 * 0.036, 0.8, 0.5);
+
+            QuadInstance::rect(rect, color)
+        })
+        .collect()
+}
+
+fn surface() -> Surface {
+    surfaces::raster_n32_premul((1200, 1200)).unwrap()
+}
+
+fn bench(name: &str, fonts: &mut SkiaFonts, canvas: &Canvas) {
+    let mut surface = surface();
+    let start = Instant::now();
+
+    for _ in 0..FRAMES {
+        surface.canvas().clear(skia_safe::Color::WHITE);
+        SkiaRenderer::draw_to(fonts, surface.canvas(), canvas, 1.0);
+        black_box(surface.canvas());
+    }
+
+    let elapsed = start.elapsed() / FRAMES;
+    let primitives = canvas.primitives().count();
+
+    println!(
+        "{name:>12}: {:>10.3?} per frame, {primitives} primitives",
+        elapsed
+    );
+}
+
+fn main() {
+    let mut fonts = SkiaFonts::new(None);
+    let instances = quads();
+
+    let mut individual = Canvas::new();
+
+    for instance in &instances {
+        let [min, _, max, _] = instance.corners();
+        individual.rect(Rect::new(min, max), instance.color);
+    }
+
+    let mut instanced = Canvas::new();
+    instanced.draw_quads_instanced(&instances);
+
+    bench("individual", &mut fonts, &individual);
+    bench("instanced", &mut fonts, &instanced);
+}
@@ -0,0 +1,74 @@
+//! Compares drawing a static label with the recorded paragraph picture against laying out and
+//! painting the paragraph every frame.
+//!
+//! Run with `cargo bench -p ori-skia --bench text_draw`.
+
+use std::{hint::black_box, time::Instant};
+
+use ori_core::text::{include_font, FontAttributes, Fonts, Paragraph, TextAlign, TextWrap};
+use ori_skia::SkiaFonts;
+use skia_safe::{surfaces, Color, Surface};
+
+const FRAMES: u32 = 1000;
+const WIDTH: f32 = 300.0;
+
+fn label() -> Paragraph {
+    let mut paragraph = Paragraph::new(1.2, TextAlign::Start, TextWrap::Word);
+    paragraph.set_text(
+        "The quick brown fox jumps over the lazy dog",
+        FontAttributes::default(),
+    );
+    paragraph
+}
+
+fn surface() -> Surface {
+    surfaces::raster_n32_premul((WIDTH as i32, 40)).unwrap()
+}
+
+fn bench(name: &str, surface: &mut Surface, mut f: impl FnMut(&mut Surface)) {
+    let start = Instant::now();
+
+    for _ in 0..FRAMES {
+        surface.canvas().clear(Color::WHITE);
+        f(surface);
+        black_box(surface.canvas());
+    }
+
+    let elapsed = start.elapsed() / FRAMES;
+    println!("{name:>8}: {:>10.3?} per frame", elapsed);
+}
+
+fn pixels(surface: &mut Surface) -> Vec<u8> {
+    surface.peek_pixels().unwrap().bytes().unwrap().to_vec()
+}
+
+fn main() {
+    let mut fonts = SkiaFonts::new(None);
+    fonts.load(include_font!("../ori-app/font"), None);
+
+    let paragraph = label();
+
+    let mut direct = surface();
+    bench("direct", &mut direct, |surface| {
+        let skia_paragraph = fonts.build_skia_paragraph(&paragraph);
+        skia_paragraph.layout(WIDTH);
+        skia_paragraph.paint(surface.canvas(), (0.0, 0.0));
+    });
+
+    let mut cached = surface();
+    bench("cached", &mut cached, |surface| {
+        let picture = fonts.paragraph_picture(&paragraph, WIDTH);
+        surface.canvas().draw_picture(&picture, None, None);
+    });
+
+    let recorded = fonts.pictures_recorded();
+    println!("recorded {recorded} picture(s) over {FRAMES} frames");
+    assert_eq!(recorded, 1, "a static label should only be recorded once");
+
+    // the cached picture must draw exactly what a freshly built paragraph does
+    assert_eq!(
+        pixels(&mut cached),
+        pixels(&mut direct),
+        "cached picture differs from a fresh paint",
+    );
+}
@@ -1,22 +1,29 @@
-use std::{hash::BuildHasherDefault, num::NonZeroUsize};
+use std::{
+    hash::{BuildHasherDefault, Hash, Hasher},
+    num::NonZeroUsize,
+};
 
 use lru::LruCache;
 use ori_core::{
+    canvas::{AntiAlias, Color},
     layout::{Point, Rect, Size},
     text::{
-        FontFamily, FontSource, FontStretch, FontStyle, Fonts, GlyphCluster, Paragraph, TextAlign,
-        TextDirection, TextLayoutLine, TextWrap,
+        FontFamily, FontSource, FontStretch, FontStyle, Fonts, GlyphAtlasConfig, GlyphCacheStats,
+        GlyphCluster, GlyphRenderConfig, Paragraph, TextAlign, TextDirection, TextLayoutCache,
+        TextLayoutLine, TextWrap,
     },
 };
 use seahash::SeaHasher;
 use skia_safe::{
+    font::Edging,
+    font_arguments::{variation_position::Coordinate, VariationPosition},
     font_style::{FontStyle as SkiaFontStyle, Slant, Weight, Width},
     textlayout::{
         FontCollection, Paragraph as SkiaParagraph, ParagraphBuilder, ParagraphStyle,
         TextAlign as SkiaTextAlign, TextDirection as SkiaTextDirection, TextStyle,
         TypefaceFontProvider,
     },
-    FontMgr,
+    FontArguments, FontMgr, FourByteTag, Picture, PictureRecorder, TextBlob,
 };
 
 use crate::SkiaRenderer;
@@ -27,6 +34,11 @@ pub struct SkiaFonts {
     provider: TypefaceFontProvider,
     manager: FontMgr,
     paragraph_cache: LruCache<Paragraph, SkiaParagraph, BuildHasherDefault<SeaHasher>>,
+    picture_cache: LruCache<u64, Picture, BuildHasherDefault<SeaHasher>>,
+    pictures_recorded: usize,
+    layout_cache: TextLayoutCache,
+    atlas_config: GlyphAtlasConfig,
+    render_config: GlyphRenderConfig,
 }
 
 impl SkiaFonts {
@@ -40,12 +52,21 @@ impl SkiaFonts {
 
         let cache_size = NonZeroUsize::new(128).unwrap();
         let paragraph_cache = LruCache::with_hasher(cache_size, Default::default());
+        let picture_cache = LruCache::with_hasher(cache_size, Default::default());
+
+        let atlas_config = GlyphAtlasConfig::default();
+        skia_safe::graphics::set_font_cache_limit(atlas_config.cache_size);
 
         Self {
             collection,
             provider,
             manager,
             paragraph_cache,
+            picture_cache,
+            pictures_recorded: 0,
+            layout_cache: TextLayoutCache::default(),
+            atlas_config,
+            render_config: GlyphRenderConfig::default(),
         }
     }
 
@@ -65,12 +86,24 @@ impl SkiaFonts {
         style.set_height(paragraph.line_height);
         style.set_text_align(align);
 
+        if !self.render_config.hinting {
+            style.turn_hinting_off();
+        }
+
         if let TextWrap::None = paragraph.wrap {
             style.set_max_lines(1);
         }
 
+        if paragraph.tab_size.is_some() {
+            // tabs are shaped as spaces, which are widened to their tab stops below
+            style.set_replace_tab_characters(true);
+        }
+
         let mut builder = ParagraphBuilder::new(&style, &self.collection);
 
+        let mut tab_stops = paragraph.tab_stops().peekable();
+        let mut start = 0;
+
         for (text, attributes) in paragraph.iter() {
             let mut style = TextStyle::new();
 
@@ -108,7 +141,8 @@ impl SkiaFonts {
             style.set_font_size(attributes.size);
             style.set_font_families(&[family]);
             style.set_font_style(font_style);
-            style.set_color(SkiaRenderer::skia_color(attributes.color));
+            let color = SkiaRenderer::skia_color_4f(attributes.color);
+            style.set_foreground_paint(&skia_safe::Paint::new(color, None));
 
             if !attributes.ligatures {
                 // disable ligatures
@@ -116,28 +150,162 @@ impl SkiaFonts {
                 style.add_font_feature("clig", 0);
             }
 
+            // the arguments are copied into the style, so they only have to outlive the call
+            let coordinates: Vec<_> = (attributes.variations.iter())
+                .map(|variation| Coordinate {
+                    axis: FourByteTag::new(u32::from_be_bytes(variation.tag)),
+                    value: variation.value,
+                })
+                .collect();
+
+            if !coordinates.is_empty() {
+                let position = VariationPosition {
+                    coordinates: &coordinates,
+                };
+
+                let arguments = FontArguments::new().set_variation_design_position(position);
+                style.set_font_arguments(&arguments);
+            }
+
             builder.push_style(&style);
-            builder.add_text(text);
+
+            // the text is added as is, so the byte indices of the glyphs match the paragraph
+            let end = start + text.len();
+            let mut last = start;
+
+            while let Some((tab, columns)) = tab_stops.next_if(|&(tab, _)| tab < end) {
+                builder.add_text(&paragraph.text()[last..tab]);
+
+                // a tab advances a whole number of spaces, the first is its own glyph
+                let space = Self::space_advance(&self.collection, &style);
+
+                let mut tab_style = style.clone();
+                tab_style.set_letter_spacing((columns - 1) as f32 * space);
+
+                builder.push_style(&tab_style);
+                builder.add_text("\t");
+                builder.pop();
+
+                last = tab + 1;
+            }
+
+            builder.add_text(&paragraph.text()[last..end]);
             builder.pop();
+
+            start = end;
         }
 
         self.paragraph_cache.put(paragraph.clone(), builder.build());
         self.paragraph_cache.get_mut(paragraph).unwrap()
     }
-}
 
-impl Fonts for SkiaFonts {
-    fn load(&mut self, source: FontSource<'_>, name: Option<&str>) {
-        let fonts = source.data().unwrap();
+    // the advance of a space in the font of `style`
+    fn space_advance(collection: &FontCollection, style: &TextStyle) -> f32 {
+        let mut builder = ParagraphBuilder::new(&ParagraphStyle::new(), collection);
+        builder.push_style(style);
+        builder.add_text(" ");
 
-        for data in fonts {
-            if let Some(typeface) = self.manager.new_from_data(&data, None) {
-                self.provider.register_typeface(typeface, name);
-            }
+        let mut paragraph = builder.build();
+        paragraph.layout(f32::INFINITY);
+
+        // the intrinsic width of a paragraph leaves out trailing whitespace, the cluster doesn't
+        (paragraph.get_glyph_cluster_at(0)).map_or(0.0, |cluster| cluster.bounds.width())
+    }
+
+    /// Get `paragraph` laid out with `width`, recorded into a picture.
+    ///
+    /// Drawing the picture replays the recorded glyph runs, so unchanged text isn't laid out
+    /// again every frame. The picture is keyed on a hash of the paragraph, which includes the
+    /// text and its colors, and of the width, so changing any of them records a new picture.
+    /// The picture is drawn at the origin, and is translated to the position of the text.
+    pub fn paragraph_picture(&mut self, paragraph: &Paragraph, width: f32) -> Picture {
+        let anti_alias = self.render_config.anti_alias;
+
+        // hashed rather than cloned, so drawing cached text doesn't copy it every frame,
+        // -0.0 and 0.0 lay out the same
+        let mut hasher = SeaHasher::new();
+        paragraph.hash(&mut hasher);
+        (width + 0.0).to_bits().hash(&mut hasher);
+        let key = hasher.finish();
+
+        if let Some(picture) = self.picture_cache.get(&key) {
+            return picture.clone();
+        }
+
+        let skia_paragraph = self.build_skia_paragraph(paragraph);
+        skia_paragraph.layout(width);
+
+        // glyphs can overhang the laid out box, the bounds are only a hint for culling
+        let overhang = skia_paragraph.height();
+        let bounds = skia_safe::Rect::from_xywh(
+            -overhang,
+            -overhang,
+            skia_paragraph.max_intrinsic_width().max(width) + overhang * 2.0,
+            skia_paragraph.height() + overhang * 2.0,
+        );
+
+        let mut recorder = PictureRecorder::new();
+        let canvas = recorder.begin_recording(bounds, None);
+
+        match anti_alias {
+            AntiAlias::None => Self::paint_aliased(skia_paragraph, paragraph, canvas),
+            AntiAlias::Fast | AntiAlias::Full => skia_paragraph.paint(canvas, (0.0, 0.0)),
         }
+
+        let picture = recorder.finish_recording_as_picture(None).unwrap();
+        self.pictures_recorded += 1;
+
+        self.picture_cache.put(key, picture.clone());
+        picture
     }
 
-    fn layout(&mut self, paragraph: &Paragraph, width: f32) -> Vec<TextLayoutLine> {
+    // skia's paragraphs always anti-alias their glyphs, so aliased text is drawn run by run,
+    // with fonts that don't
+    fn paint_aliased(
+        skia_paragraph: &mut SkiaParagraph,
+        paragraph: &Paragraph,
+        canvas: &skia_safe::Canvas,
+    ) {
+        // the colors of the text by byte range, the runs only know where their text starts
+        let mut colors = Vec::new();
+        let mut start = 0;
+
+        for (text, attributes) in paragraph.iter() {
+            colors.push((start..start + text.len(), attributes.color));
+            start += text.len();
+        }
+
+        skia_paragraph.visit(|_, info| {
+            let Some(info) = info else {
+                return;
+            };
+
+            let start = info
+                .utf8_starts()
+                .first()
+                .map_or(0, |&start| start as usize);
+            let color = (colors.iter())
+                .find(|(range, _)| range.contains(&start))
+                .map_or(Color::BLACK, |(_, color)| *color);
+
+            let mut font = info.font().clone();
+            font.set_edging(Edging::Alias);
+
+            let mut paint = skia_safe::Paint::new(SkiaRenderer::skia_color_4f(color), None);
+            paint.set_anti_alias(false);
+
+            if let Some(blob) = TextBlob::from_pos_text(info.glyphs(), info.positions(), &font) {
+                canvas.draw_text_blob(&blob, info.origin(), &paint);
+            }
+        });
+    }
+
+    /// Get the number of pictures recorded by [`SkiaFonts::paragraph_picture`].
+    pub fn pictures_recorded(&self) -> usize {
+        self.pictures_recorded
+    }
+
+    fn layout_lines(&mut self, paragraph: &Paragraph, width: f32) -> Vec<TextLayoutLine> {
         let skia_paragraph = self.build_skia_paragraph(paragraph);
         skia_paragraph.layout(width);
 
@@ -220,14 +388,84 @@ impl Fonts for SkiaFonts {
 
         lines
     }
+}
+
+impl Fonts for SkiaFonts {
+    fn set_atlas_config(&mut self, config: GlyphAtlasConfig) {
+        // skia evicts the least recently used glyphs when the cache is full,
+        // so shrinking the limit will purge glyphs until it fits
+        skia_safe::graphics::set_font_cache_limit(config.cache_size);
+        self.atlas_config = config;
+    }
+
+    fn atlas_config(&self) -> GlyphAtlasConfig {
+        self.atlas_config
+    }
+
+    // skia doesn't expose the occupancy of its atlas textures, only of the glyph cache
+    fn glyph_cache_stats(&self) -> GlyphCacheStats {
+        GlyphCacheStats {
+            used: skia_safe::graphics::font_cache_used(),
+            capacity: skia_safe::graphics::font_cache_limit(),
+            entries: skia_safe::graphics::font_cache_count_used().max(0) as usize,
+        }
+    }
+
+    fn set_render_config(&mut self, config: GlyphRenderConfig) {
+        // the paragraphs are rasterized with the config they were built with
+        if self.render_config != config {
+            self.paragraph_cache.clear();
+            self.picture_cache.clear();
+            self.layout_cache.clear();
+        }
+
+        self.render_config = config;
+    }
+
+    fn render_config(&self) -> GlyphRenderConfig {
+        self.render_config
+    }
+
+    fn load(&mut self, source: FontSource<'_>, name: Option<&str>) {
+        let fonts = source.data().unwrap();
+
+        for data in fonts {
+            if let Some(typeface) = self.manager.new_from_data(&data, None) {
+                self.provider.register_typeface(typeface, name);
+            }
+        }
+
+        // text shaped before may resolve to the new fonts
+        self.paragraph_cache.clear();
+        self.picture_cache.clear();
+        self.layout_cache.clear();
+    }
+
+    fn layout(&mut self, paragraph: &Paragraph, width: f32) -> Vec<TextLayoutLine> {
+        if let Some(lines) = self.layout_cache.lines(paragraph, width) {
+            return lines;
+        }
+
+        let lines = self.layout_lines(paragraph, width);
+        self.layout_cache
+            .insert_lines(paragraph, width, lines.clone());
+        lines
+    }
 
     fn measure(&mut self, paragraph: &Paragraph, width: f32) -> Size {
+        if let Some(size) = self.layout_cache.size(paragraph, width) {
+            return size;
+        }
+
         let skia_paragraph = self.build_skia_paragraph(paragraph);
         skia_paragraph.layout(width);
 
-        let width = skia_paragraph.max_intrinsic_width();
-        let height = skia_paragraph.height();
+        let size = Size::new(
+            skia_paragraph.max_intrinsic_width(),
+            skia_paragraph.height(),
+        );
 
-        Size::new(width, height)
+        self.layout_cache.insert_size(paragraph, width, size);
+        size
     }
 }
@@ -2,23 +2,30 @@ use core::ffi;
 use std::{collections::HashMap, mem};
 
 use ori_core::{
-    canvas::{BlendMode, Canvas, Color, Curve, CurveSegment, FillRule, Paint, Primitive, Shader},
-    image::WeakImage,
-    layout::{Affine, Vector},
+    canvas::{
+        BlendMode, BorderRadius, Canvas, Color, ColorSpace, ConicGradient, Curve, CurveSegment,
+        FillRule, Mask, Mesh, Paint, Primitive, Shader,
+    },
+    image::{Image, ImageId, TextureId, WeakImage},
+    layout::{Affine, Rect, Vector},
+    text::{Fonts, GlyphAtlasConfig},
 };
 
 use crate::SkiaFonts;
 
-type Images = HashMap<WeakImage, skia_safe::Image>;
 type GlGetIntegerv = unsafe extern "C" fn(u32, *mut i32);
 
 pub struct SkiaRenderer {
     gl_get_integerv: GlGetIntegerv,
+    interface: skia_safe::gpu::gl::Interface,
+    atlas_config: GlyphAtlasConfig,
     skia: skia_safe::gpu::DirectContext,
     surface: Option<skia_safe::Surface>,
-    images: HashMap<WeakImage, skia_safe::Image>,
+    blend_surface: Option<skia_safe::Surface>,
+    images: Images,
     width: u32,
     height: u32,
+    color_space: ColorSpace,
 }
 
 impl SkiaRenderer {
@@ -27,41 +34,163 @@ impl SkiaRenderer {
     /// - `loader` must be a function that returns a valid pointer to a GL function.
     pub unsafe fn new(mut loader: impl FnMut(&str) -> *const ffi::c_void) -> Self {
         let interface = skia_safe::gpu::gl::Interface::new_load_with(&mut loader).unwrap();
-        let skia = skia_safe::gpu::direct_contexts::make_gl(interface, None).unwrap();
+        let atlas_config = GlyphAtlasConfig::default();
+        let skia = Self::make_context(&interface, atlas_config);
 
         let gl_get_integerv =
             mem::transmute::<*const std::ffi::c_void, GlGetIntegerv>(loader("glGetIntegerv"));
 
         Self {
             gl_get_integerv,
+            interface,
+            atlas_config,
             skia,
             surface: None,
-            images: HashMap::new(),
+            blend_surface: None,
+            images: Images::default(),
             width: 0,
             height: 0,
+            color_space: ColorSpace::Srgb,
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn render(
         &mut self,
         fonts: &mut SkiaFonts,
         canvas: &Canvas,
         color: Color,
+        color_space: ColorSpace,
         width: u32,
         height: u32,
         scale_factor: f32,
+        damage: Option<Rect>,
     ) {
-        self.update_surface(width, height);
+        self.update_atlas(fonts.atlas_config());
+        self.update_surface(width, height, color_space);
+
+        // only the damaged region is rendered, the rest of the surfaces are expected to still
+        // contain the previous frame
+        let clip = damage.map(|damage| {
+            skia_safe::Rect::new(damage.min.x, damage.min.y, damage.max.x, damage.max.y)
+        });
+
+        let blend_surface = self.blend_surface.as_mut().unwrap();
+        let skia_canvas = blend_surface.canvas();
+        skia_canvas.save();
+
+        if let Some(clip) = clip {
+            skia_canvas.clip_rect(clip, skia_safe::ClipOp::Intersect, false);
+        }
+
+        skia_canvas.clear(Self::skia_color_4f(color));
 
-        let skia_canvas = self.surface.as_mut().unwrap().canvas();
-        skia_canvas.clear(Self::skia_color(color));
+        let transform = Affine::scale(Vector::all(scale_factor));
+        let images = &mut self.images;
+        Self::render_offscreen(fonts, images, skia_canvas, canvas.primitives(), transform);
 
         for primitive in canvas.primitives() {
-            let transform = Affine::scale(Vector::all(scale_factor));
             Self::draw_primitive(fonts, &mut self.images, skia_canvas, primitive, transform);
         }
 
+        skia_canvas.restore();
+
+        // the frame is copied onto the window, which converts it to the color space of the
+        // surface of the window
+        let window_canvas = self.surface.as_mut().unwrap().canvas();
+        window_canvas.save();
+
+        if let Some(clip) = clip {
+            window_canvas.clip_rect(clip, skia_safe::ClipOp::Intersect, false);
+        }
+
+        let mut paint = skia_safe::Paint::default();
+        paint.set_blend_mode(skia_safe::BlendMode::Src);
+
+        let sampling = skia_safe::SamplingOptions::default();
+        blend_surface.draw(window_canvas, (0, 0), sampling, Some(&paint));
+
+        window_canvas.restore();
+
         self.skia.flush_and_submit();
+        self.images.clean();
+    }
+
+    /// Draw a `canvas` onto any skia canvas, eg. a raster surface, without an OpenGL context.
+    ///
+    /// Images are uploaded again on every call, so this is meant for tests and benchmarks,
+    /// windows are drawn with [`SkiaRenderer::render`]. Colors are blended in the color space
+    /// of `target`, create it with [`SkiaRenderer::blend_info`] to blend like windows are.
+    pub fn draw_to(
+        fonts: &mut SkiaFonts,
+        target: &skia_safe::Canvas,
+        canvas: &Canvas,
+        scale_factor: f32,
+    ) {
+        let mut images = Images::default();
+        let transform = Affine::scale(Vector::all(scale_factor));
+
+        Self::render_offscreen(fonts, &mut images, target, canvas.primitives(), transform);
+
+        for primitive in canvas.primitives() {
+            Self::draw_primitive(fonts, &mut images, target, primitive, transform);
+        }
+    }
+
+    // off-screen textures are rendered before anything is drawn, so they're ready wherever
+    // they're sampled, even before the primitive that renders them
+    fn render_offscreen<'a>(
+        fonts: &mut SkiaFonts,
+        images: &mut Images,
+        canvas: &skia_safe::Canvas,
+        primitives: impl Iterator<Item = &'a Primitive>,
+        transform: Affine,
+    ) {
+        for primitive in primitives {
+            match primitive {
+                Primitive::Layer {
+                    primitives,
+                    transform: layer_transform,
+                    ..
+                } => {
+                    let transform = transform * *layer_transform;
+                    Self::render_offscreen(fonts, images, canvas, primitives.iter(), transform);
+                }
+                Primitive::Offscreen {
+                    primitives,
+                    texture,
+                    width,
+                    height,
+                } => {
+                    // the texture is rendered at the scale it's drawn at, in physical pixels
+                    let scale =
+                        Vector::new(transform.matrix.x.length(), transform.matrix.y.length());
+                    let transform = Affine::scale(scale);
+
+                    // textures drawn inside the texture are rendered first
+                    Self::render_offscreen(fonts, images, canvas, primitives.iter(), transform);
+
+                    let width = f32::max((*width as f32 * scale.x).ceil(), 1.0) as i32;
+                    let height = f32::max((*height as f32 * scale.y).ceil(), 1.0) as i32;
+
+                    let Some(mut surface) = images.take_target(canvas, *texture, width, height)
+                    else {
+                        continue;
+                    };
+
+                    let target = surface.canvas();
+                    target.clear(skia_safe::Color::TRANSPARENT);
+                    target.set_matrix(&Self::skia_matrix(transform).into());
+
+                    for primitive in primitives.iter() {
+                        Self::draw_primitive(fonts, images, target, primitive, transform);
+                    }
+
+                    images.insert_target(*texture, surface);
+                }
+                _ => {}
+            }
+        }
     }
 
     fn draw_primitive(
@@ -81,30 +210,65 @@ impl SkiaRenderer {
                 paint,
             } => {
                 let mut stroked = Curve::new();
-                stroked.stroke_curve(curve, *stroke);
+                stroked.stroke_curve(curve, stroke);
                 Self::fill_curve(images, canvas, &stroked, &FillRule::NonZero, paint);
             }
+            Primitive::Mesh { mesh } => Self::draw_mesh(canvas, mesh),
+            Primitive::RoundedRect {
+                rect,
+                radius,
+                fill,
+                stroke_width,
+                stroke,
+            } => Self::draw_rounded_rect(
+                images,
+                canvas,
+                *rect,
+                *radius,
+                fill,
+                *stroke_width,
+                *stroke,
+            ),
             Primitive::Paragraph {
                 paragraph, rect, ..
             } => {
-                let skia_paragraph = fonts.build_skia_paragraph(paragraph);
-                skia_paragraph.layout(rect.width() + 1.0);
+                // unchanged text replays the glyph runs recorded when it was first drawn
+                let picture = fonts.paragraph_picture(paragraph, rect.width() + 1.0);
+                let matrix = skia_safe::Matrix::translate((rect.min.x, rect.min.y));
 
-                skia_paragraph.paint(canvas, (rect.min.x, rect.min.y));
+                canvas.draw_picture(&picture, Some(&matrix), None);
             }
             Primitive::Layer {
                 primitives,
                 transform: layer_transform,
                 mask,
+                blend,
+                filter,
                 ..
             } => {
-                canvas.save();
+                match (blend, filter) {
+                    (BlendMode::SourceOver, None) => {
+                        canvas.save();
+                    }
+                    (blend, filter) => {
+                        let mut paint = skia_safe::Paint::default();
+                        paint.set_blend_mode(Self::skia_blend_mode(*blend));
+
+                        if let Some(filter) = filter {
+                            let color_filter =
+                                skia_safe::color_filters::matrix_row_major(&filter.matrix, None);
+                            paint.set_color_filter(color_filter);
+                        }
+
+                        let layer = skia_safe::canvas::SaveLayerRec::default().paint(&paint);
+                        canvas.save_layer(&layer);
+                    }
+                }
 
                 let transform = transform * *layer_transform;
 
                 if let Some(mask) = mask {
-                    let skia_path = Self::skia_path(&mask.curve);
-                    canvas.clip_path(&skia_path, None, true);
+                    Self::clip_mask(canvas, mask);
                 }
 
                 canvas.set_matrix(&Self::skia_matrix(transform).into());
@@ -115,6 +279,8 @@ impl SkiaRenderer {
 
                 canvas.restore();
             }
+            // rendered before anything is drawn, see `render_offscreen`
+            Primitive::Offscreen { .. } => {}
         }
     }
 
@@ -127,58 +293,116 @@ impl SkiaRenderer {
     ) {
         let mut skia_path = Self::skia_path(curve);
 
-        skia_path.set_fill_type(match fill {
-            FillRule::NonZero => skia_safe::PathFillType::Winding,
-            FillRule::EvenOdd => skia_safe::PathFillType::EvenOdd,
-        });
+        skia_path.set_fill_type(Self::skia_fill_type(*fill));
+
+        let skia_paint = Self::skia_paint(images, canvas, paint);
+        canvas.draw_path(&skia_path, &skia_paint);
+    }
 
+    fn draw_rounded_rect(
+        images: &mut Images,
+        canvas: &skia_safe::Canvas,
+        rect: Rect,
+        radius: BorderRadius,
+        fill: &Paint,
+        stroke_width: f32,
+        stroke: Color,
+    ) {
+        // skia draws rounded rectangles with analytic coverage, instead of rasterizing a path
+        let rrect = Self::skia_rrect(rect, radius);
+        canvas.draw_rrect(rrect, &Self::skia_paint(images, canvas, fill));
+
+        if stroke_width <= 0.0 || stroke.a <= 0.0 {
+            return;
+        }
+
+        // the stroke is centered on its path, so the path is inset by half the width to keep
+        // the stroke inside the edges
+        let inset = stroke_width / 2.0;
+        let rect = rect.shrink(inset);
+        let shrink = |radius: f32| f32::max(radius - inset, 0.0);
+        let radius = BorderRadius::new(
+            shrink(radius.top_left),
+            shrink(radius.top_right),
+            shrink(radius.bottom_right),
+            shrink(radius.bottom_left),
+        );
+
+        let mut skia_paint = skia_safe::Paint::new(Self::skia_color_4f(stroke), None);
+        skia_paint.set_anti_alias(true);
+        skia_paint.set_style(skia_safe::PaintStyle::Stroke);
+        skia_paint.set_stroke_width(stroke_width);
+
+        canvas.draw_rrect(Self::skia_rrect(rect, radius), &skia_paint);
+    }
+
+    // the path is built from plain segments, so skia can't tell a rectangle or an oval from
+    // any other curve, those are recognized here and clipped without rasterizing the path
+    fn clip_mask(canvas: &skia_safe::Canvas, mask: &Mask) {
+        let bounds = mask.curve.bounds();
+        let rect = skia_safe::Rect::new(bounds.min.x, bounds.min.y, bounds.max.x, bounds.max.y);
+
+        if mask.curve.is_rect() {
+            canvas.clip_rect(rect, None, true);
+        } else if mask.curve.is_oval() {
+            canvas.clip_rrect(skia_safe::RRect::new_oval(rect), None, true);
+        } else {
+            let mut skia_path = Self::skia_path(&mask.curve);
+            skia_path.set_fill_type(Self::skia_fill_type(mask.fill));
+            canvas.clip_path(&skia_path, None, true);
+        }
+    }
+
+    fn skia_rrect(rect: Rect, radius: BorderRadius) -> skia_safe::RRect {
+        let rect = skia_safe::Rect::new(rect.min.x, rect.min.y, rect.max.x, rect.max.y);
+
+        let radii = [
+            skia_safe::Vector::new(radius.top_left, radius.top_left),
+            skia_safe::Vector::new(radius.top_right, radius.top_right),
+            skia_safe::Vector::new(radius.bottom_right, radius.bottom_right),
+            skia_safe::Vector::new(radius.bottom_left, radius.bottom_left),
+        ];
+
+        skia_safe::RRect::new_rect_radii(rect, &radii)
+    }
+
+    fn skia_paint(
+        images: &mut Images,
+        canvas: &skia_safe::Canvas,
+        paint: &Paint,
+    ) -> skia_safe::Paint {
         let color = match paint.shader {
             Shader::Solid(color) => color,
             Shader::Pattern(ref pattern) => pattern.color,
-        };
-
-        let blend_mode = match paint.blend {
-            BlendMode::Clear => skia_safe::BlendMode::Clear,
-            BlendMode::Source => skia_safe::BlendMode::Src,
-            BlendMode::Destination => skia_safe::BlendMode::Dst,
-            BlendMode::SourceOver => skia_safe::BlendMode::SrcOver,
-            BlendMode::DestinationOver => skia_safe::BlendMode::DstOver,
+            Shader::ConicGradient(_) => Color::WHITE,
         };
 
         let mut skia_paint = skia_safe::Paint::new(Self::skia_color_4f(color), None);
         skia_paint.set_anti_alias(true);
-        skia_paint.set_blend_mode(blend_mode);
+        skia_paint.set_blend_mode(Self::skia_blend_mode(paint.blend));
 
         match paint.shader {
             Shader::Pattern(ref pattern) => {
-                let weak_image = pattern.image.downgrade();
-                let image = images.entry(weak_image).or_insert_with(|| {
-                    let image = skia_safe::images::raster_from_data(
-                        &skia_safe::ImageInfo::new(
-                            skia_safe::ISize::new(
-                                pattern.image.width() as i32,
-                                pattern.image.height() as i32,
-                            ),
-                            skia_safe::ColorType::RGBA8888,
-                            skia_safe::AlphaType::Unpremul,
-                            None,
-                        ),
-                        skia_safe::Data::new_copy(pattern.image.data()),
-                        pattern.image.width() as usize * 4,
-                    )
-                    .unwrap();
+                let image = images.get(canvas, &pattern.image);
 
-                    image
-                });
+                // rendered textures have more pixels than the image on high dpi displays
+                let mut matrix = Self::skia_matrix(pattern.transform);
+                matrix.pre_scale(
+                    (
+                        pattern.image.width() as f32 / image.width() as f32,
+                        pattern.image.height() as f32 / image.height() as f32,
+                    ),
+                    None,
+                );
 
                 let shader = skia_safe::shaders::image(
-                    image.clone(),
+                    image,
                     (
                         skia_safe::TileMode::default(),
                         skia_safe::TileMode::default(),
                     ),
                     &skia_safe::SamplingOptions::default(),
-                    &Self::skia_matrix(pattern.transform),
+                    &matrix,
                 )
                 .unwrap()
                 .with_color_filter(
@@ -191,10 +415,74 @@ impl SkiaRenderer {
 
                 skia_paint.set_shader(shader);
             }
+            Shader::ConicGradient(ref gradient) => {
+                if let Some(shader) = Self::skia_conic_gradient(gradient) {
+                    skia_paint.set_shader(shader);
+                }
+            }
             Shader::Solid(_) => {}
         }
 
-        canvas.draw_path(&skia_path, &skia_paint);
+        skia_paint
+    }
+
+    fn skia_conic_gradient(gradient: &ConicGradient) -> Option<skia_safe::Shader> {
+        let colors: Vec<_> = (gradient.stops.iter())
+            .map(|stop| Self::skia_color_4f(stop.color))
+            .collect();
+        let offsets: Vec<_> = gradient.stops.iter().map(|stop| stop.offset).collect();
+
+        // skia sweeps clockwise from the positive x axis, so we rotate it to start at `angle`
+        let center = gradient.center;
+        let matrix =
+            skia_safe::Matrix::rotate_deg_pivot(gradient.angle.to_degrees(), (center.x, center.y));
+
+        skia_safe::Shader::sweep_gradient(
+            (center.x, center.y),
+            skia_safe::gradient_shader::GradientShaderColors::ColorsInSpace(
+                colors.as_slice(),
+                None,
+            ),
+            Some(offsets.as_slice()),
+            skia_safe::TileMode::Clamp,
+            None,
+            None,
+            Some(&matrix),
+        )
+    }
+
+    fn draw_mesh(canvas: &skia_safe::Canvas, mesh: &Mesh) {
+        // skia only supports 16 bit indices, so the triangles are drawn without indices
+        let mut positions = Vec::with_capacity(mesh.indices.len());
+        let mut colors = Vec::with_capacity(mesh.indices.len());
+
+        for triangle in mesh.triangles() {
+            for vertex in triangle {
+                positions.push(skia_safe::Point::new(vertex.position.x, vertex.position.y));
+                colors.push(Self::skia_color(vertex.color));
+            }
+        }
+
+        let vertices = skia_safe::Vertices::new_copy(
+            skia_safe::vertices::VertexMode::Triangles,
+            &positions,
+            &[],
+            &colors,
+            None,
+        );
+
+        let mut skia_paint = skia_safe::Paint::default();
+        skia_paint.set_anti_alias(true);
+
+        // without a shader, the colors of the vertices are used as is
+        canvas.draw_vertices(&vertices, skia_safe::BlendMode::Dst, &skia_paint);
+    }
+
+    fn skia_fill_type(fill: FillRule) -> skia_safe::PathFillType {
+        match fill {
+            FillRule::NonZero => skia_safe::PathFillType::Winding,
+            FillRule::EvenOdd => skia_safe::PathFillType::EvenOdd,
+        }
     }
 
     fn skia_path(curve: &Curve) -> skia_safe::Path {
@@ -234,21 +522,128 @@ impl SkiaRenderer {
         matrix
     }
 
-    fn skia_color_4f(color: Color) -> skia_safe::Color4f {
+    fn skia_blend_mode(blend: BlendMode) -> skia_safe::BlendMode {
+        match blend {
+            BlendMode::Clear => skia_safe::BlendMode::Clear,
+            BlendMode::Source => skia_safe::BlendMode::Src,
+            BlendMode::Destination => skia_safe::BlendMode::Dst,
+            BlendMode::SourceOver => skia_safe::BlendMode::SrcOver,
+            BlendMode::DestinationOver => skia_safe::BlendMode::DstOver,
+            BlendMode::Multiply => skia_safe::BlendMode::Multiply,
+            BlendMode::Screen => skia_safe::BlendMode::Screen,
+            BlendMode::Overlay => skia_safe::BlendMode::Overlay,
+            BlendMode::Darken => skia_safe::BlendMode::Darken,
+            BlendMode::Lighten => skia_safe::BlendMode::Lighten,
+        }
+    }
+
+    // colors outside the sRGB gamut have components outside 0..=1, they are kept as floats
+    // so skia can convert them to the color space of the surface
+    pub(crate) fn skia_color_4f(color: Color) -> skia_safe::Color4f {
         skia_safe::Color4f::new(color.r, color.g, color.b, color.a)
     }
 
+    // only for the apis of skia that take 8 bit colors, wide gamut colors are clamped to sRGB
     pub(crate) fn skia_color(color: Color) -> skia_safe::Color {
+        // round to the nearest value, truncating darkens every color slightly,
+        // which is especially visible on the anti-aliased edges of text
+        let channel = |x: f32| f32::round(x.clamp(0.0, 1.0) * 255.0) as u8;
+
         skia_safe::Color::from_argb(
-            (color.a * 255.0) as u8,
-            (color.r * 255.0) as u8,
-            (color.g * 255.0) as u8,
-            (color.b * 255.0) as u8,
+            channel(color.a),
+            channel(color.r),
+            channel(color.g),
+            channel(color.b),
+        )
+    }
+
+    fn make_context(
+        interface: &skia_safe::gpu::gl::Interface,
+        config: GlyphAtlasConfig,
+    ) -> skia_safe::gpu::DirectContext {
+        let mut options = skia_safe::gpu::ContextOptions::new();
+
+        // glyphs are stored in A8 textures, so one byte per pixel
+        let size = config.max_texture_size as usize;
+        options.glyph_cache_texture_maximum_bytes = size * size;
+        options.allow_multiple_glyph_cache_textures = match config.multiple_pages {
+            true => skia_safe::gpu::context_options::Enable::Yes,
+            false => skia_safe::gpu::context_options::Enable::No,
+        };
+
+        skia_safe::gpu::direct_contexts::make_gl(interface.clone(), &options).unwrap()
+    }
+
+    /// Release the GPU resources of the renderer.
+    ///
+    /// This frees the surface, the textures of all images and every resource cached by skia.
+    /// The renderer can still be used afterwards, everything is recreated on demand. This is
+    /// also called when the renderer is dropped, the OpenGL context of the renderer should be
+    /// current when either happens.
+    pub fn release(&mut self) {
+        self.images.clear();
+
+        self.surface = None;
+        self.blend_surface = None;
+        self.width = 0;
+        self.height = 0;
+
+        self.skia.free_gpu_resources();
+    }
+
+    fn update_atlas(&mut self, config: GlyphAtlasConfig) {
+        if self.atlas_config == config {
+            return;
+        }
+
+        // the atlas size is fixed when the context is created, so the context
+        // has to be recreated, glyphs will be re-rasterized on demand
+        self.surface = None;
+        self.blend_surface = None;
+        self.width = 0;
+        self.height = 0;
+
+        self.skia.free_gpu_resources();
+        self.skia = Self::make_context(&self.interface, config);
+        self.atlas_config = config;
+    }
+
+    // the surfaces are 8 bit, so extended range sRGB is rendered as sRGB
+    fn skia_color_space(color_space: ColorSpace) -> Option<skia_safe::ColorSpace> {
+        match color_space {
+            ColorSpace::Srgb | ColorSpace::ExtendedSrgb => None,
+            ColorSpace::DisplayP3 => skia_safe::ColorSpace::new_rgb(
+                &skia_safe::named_transfer_fn::SRGB,
+                &skia_safe::named_gamut::DISPLAY_P3,
+            )
+            .into(),
+        }
+    }
+
+    /// The image info of the surface frames are drawn into, before they are copied onto the
+    /// surface of the window.
+    ///
+    /// The surface is linear, so the coverage of anti-aliased edges and the alpha of colors
+    /// are blended in linear light, like [`Color::blend_coverage`]. Blending the sRGB encoded
+    /// components makes the edges of dark text on light backgrounds too heavy, and those of
+    /// light text on dark backgrounds too thin. Half floats keep the precision of dark colors.
+    pub fn blend_info(width: u32, height: u32, color_space: ColorSpace) -> skia_safe::ImageInfo {
+        let skia_color_space = Self::skia_color_space(color_space)
+            .unwrap_or_else(skia_safe::ColorSpace::new_srgb)
+            .with_linear_gamma();
+
+        skia_safe::ImageInfo::new(
+            (width as i32, height as i32),
+            skia_safe::ColorType::RGBAF16,
+            skia_safe::AlphaType::Premul,
+            skia_color_space,
         )
     }
 
-    fn update_surface(&mut self, width: u32, height: u32) {
-        if self.width != width || self.height != height {
+    fn update_surface(&mut self, width: u32, height: u32, color_space: ColorSpace) {
+        let resized = self.width != width || self.height != height;
+
+        if resized || self.color_space != color_space {
             let mut fboid = 0;
             unsafe { (self.gl_get_integerv)(0x8D40, &mut fboid) };
 
@@ -273,12 +668,906 @@ impl SkiaRenderer {
                 &backend_render_target,
                 skia_safe::gpu::SurfaceOrigin::BottomLeft,
                 skia_safe::ColorType::RGBA8888,
+                Self::skia_color_space(color_space),
+                None,
+            )
+            .unwrap();
+
+            let blend_surface = skia_safe::gpu::surfaces::render_target(
+                &mut self.skia,
+                skia_safe::gpu::Budgeted::Yes,
+                &Self::blend_info(width, height, color_space),
+                sample_count,
+                skia_safe::gpu::SurfaceOrigin::BottomLeft,
                 None,
+                false,
                 None,
             )
             .unwrap();
 
             self.surface = Some(surface);
+            self.blend_surface = Some(blend_surface);
+            self.width = width;
+            self.height = height;
+            self.color_space = color_space;
         }
     }
 }
+
+impl Drop for SkiaRenderer {
+    fn drop(&mut self) {
+        self.release();
+    }
+}
+
+#[derive(Default)]
+struct Images {
+    images: HashMap<WeakImage, skia_safe::Image>,
+    textures: HashMap<TextureId, HandleTexture>,
+    targets: HashMap<TextureId, RenderTarget>,
+}
+
+// an image managed by an `ImageHandle`, its pixels are written into the same surface, which
+// lives on the gpu when rendering with gl
+struct HandleTexture {
+    id: ImageId,
+    surface: skia_safe::Surface,
+    used: bool,
+}
+
+// an off-screen texture drawn by a `Primitive::Offscreen`, the surface is kept while the
+// texture is rendered every frame
+struct RenderTarget {
+    surface: skia_safe::Surface,
+    used: bool,
+}
+
+impl Images {
+    fn get(&mut self, canvas: &skia_safe::Canvas, image: &Image) -> skia_safe::Image {
+        if let Some(texture) = image.texture() {
+            if let Some(target) = self.targets.get_mut(&texture) {
+                target.used = true;
+                return target.surface.image_snapshot();
+            }
+
+            if let Some(image) = self.get_texture(canvas, texture, image) {
+                return image;
+            }
+        }
+
+        let weak_image = image.downgrade();
+        let skia_image = self.images.entry(weak_image).or_insert_with(|| {
+            skia_safe::images::raster_from_data(
+                &Self::image_info(image),
+                skia_safe::Data::new_copy(image.data()),
+                image.width() as usize * 4,
+            )
+            .unwrap()
+        });
+
+        skia_image.clone()
+    }
+
+    fn get_texture(
+        &mut self,
+        canvas: &skia_safe::Canvas,
+        texture: TextureId,
+        image: &Image,
+    ) -> Option<skia_safe::Image> {
+        let info = Self::image_info(image);
+
+        // the handle allocates a new texture when the size changes, but we check anyway
+        let needs_alloc = match self.textures.get(&texture) {
+            Some(entry) => {
+                entry.surface.width() != info.width() || entry.surface.height() != info.height()
+            }
+            None => true,
+        };
+
+        if needs_alloc {
+            // the surface is created like the canvas, on the gpu when rendering with gl and
+            // in memory when rendering with a raster canvas
+            let info = info.with_alpha_type(skia_safe::AlphaType::Premul);
+            let mut surface = canvas.new_surface(&info, None)?;
+            Self::write_pixels(&mut surface, image);
+
+            let entry = HandleTexture {
+                id: image.id(),
+                surface,
+                used: false,
+            };
+
+            self.textures.insert(texture, entry);
+        }
+
+        let texture = self.textures.get_mut(&texture).unwrap();
+
+        if texture.id != image.id() {
+            texture.id = image.id();
+            Self::write_pixels(&mut texture.surface, image);
+        }
+
+        // the snapshot shares the texture of the surface, it is not kept around so the next
+        // write doesn't have to copy the texture to preserve the snapshot
+        texture.used = true;
+        Some(texture.surface.image_snapshot())
+    }
+
+    // take the surface of an off-screen texture out to draw to it, creating it the first time
+    // the texture is rendered, or when its size changes
+    fn take_target(
+        &mut self,
+        canvas: &skia_safe::Canvas,
+        texture: TextureId,
+        width: i32,
+        height: i32,
+    ) -> Option<skia_safe::Surface> {
+        if let Some(target) = self.targets.remove(&texture) {
+            if target.surface.width() == width && target.surface.height() == height {
+                return Some(target.surface);
+            }
+        }
+
+        // the surface is created like the canvas, on the gpu when rendering with gl and in
+        // memory when rendering with a raster canvas
+        let info = skia_safe::ImageInfo::new_n32_premul((width, height), None);
+        canvas.new_surface(&info, None)
+    }
+
+    fn insert_target(&mut self, texture: TextureId, surface: skia_safe::Surface) {
+        let target = RenderTarget {
+            surface,
+            used: true,
+        };
+
+        self.targets.insert(texture, target);
+    }
+
+    fn write_pixels(surface: &mut skia_safe::Surface, image: &Image) {
+        // a texture that wasn't rendered this frame has no pixels, it's drawn as transparent
+        if image.data().is_empty() {
+            surface.canvas().clear(skia_safe::Color::TRANSPARENT);
+            return;
+        }
+
+        let info = Self::image_info(image);
+        let row_bytes = image.width() as usize * 4;
+
+        surface
+            .canvas()
+            .write_pixels(&info, image.data(), row_bytes, (0, 0));
+    }
+
+    fn image_info(image: &Image) -> skia_safe::ImageInfo {
+        skia_safe::ImageInfo::new(
+            skia_safe::ISize::new(image.width() as i32, image.height() as i32),
+            skia_safe::ColorType::RGBA8888,
+            skia_safe::AlphaType::Unpremul,
+            None,
+        )
+    }
+
+    // remove images that are no longer referenced, and the textures of handles that weren't
+    // drawn since the last clean
+    fn clean(&mut self) {
+        self.images.retain(|image, _| image.strong_count() > 0);
+
+        self.textures
+            .retain(|_, texture| mem::take(&mut texture.used));
+
+        self.targets.retain(|_, target| mem::take(&mut target.used));
+    }
+
+    fn clear(&mut self) {
+        self.images.clear();
+        self.textures.clear();
+        self.targets.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ori_core::{
+        canvas::{AntiAlias, BorderEdge, BorderWidth, ColorMatrix, Pattern, Vertex},
+        image::{ImageData, ImageHandle},
+        layout::{Point, Size},
+        text::{
+            FontAttributes, FontFamily, FontSource, FontVariation, FontWeight, GlyphRenderConfig,
+            Paragraph, TextAlign, TextWrap,
+        },
+    };
+
+    use super::*;
+
+    #[test]
+    fn sample_texture_rendered_offscreen() {
+        let rect = Rect::min_size(Point::ZERO, Size::all(4.0));
+
+        let mut canvas = Canvas::new();
+        let handle = canvas.render_to_texture(rect.size(), |canvas| {
+            canvas.rect(rect, Color::RED);
+        });
+        canvas.rect(rect, Pattern::from(handle.image().clone()));
+
+        // the handle has no pixels, the texture is only created when rendering
+        assert!(handle.image().data().is_empty());
+
+        // a raster surface, so the test doesn't need a gl context
+        let mut surface = skia_safe::surfaces::raster_n32_premul((4, 4)).unwrap();
+        let mut fonts = SkiaFonts::new(None);
+        SkiaRenderer::draw_to(&mut fonts, surface.canvas(), &canvas, 1.0);
+
+        let pixels = surface.peek_pixels().unwrap();
+        assert_eq!(pixels.get_color((2, 2)), skia_safe::Color::RED);
+    }
+
+    #[test]
+    fn rect_and_oval_masks_clip_the_layer() {
+        let rect = Rect::min_size(Point::ZERO, Size::all(20.0));
+
+        let masked = |mask: Curve| {
+            let mut canvas = Canvas::new();
+            canvas.masked(Mask::new(mask, FillRule::NonZero), |canvas| {
+                canvas.rect(rect, Color::RED);
+            });
+
+            let mut surface = skia_safe::surfaces::raster_n32_premul((20, 20)).unwrap();
+            surface.canvas().clear(skia_safe::Color::WHITE);
+            let mut fonts = SkiaFonts::new(None);
+            SkiaRenderer::draw_to(&mut fonts, surface.canvas(), &canvas, 1.0);
+
+            let pixels = surface.peek_pixels().unwrap();
+            let red = |x, y| pixels.get_color((x, y)) == skia_safe::Color::RED;
+            [red(5, 5), red(15, 5), red(10, 10), red(1, 1)]
+        };
+
+        // the left half of the layer
+        let half = Curve::rect(Rect::min_size(Point::ZERO, Size::new(10.0, 20.0)));
+        assert_eq!(masked(half), [true, false, false, true]);
+
+        // the corners of the layer are outside of the circle
+        let circle = Curve::circle(Point::all(10.0), 10.0);
+        assert_eq!(masked(circle), [true, true, true, false]);
+    }
+
+    #[test]
+    fn border_edges_are_filled_with_their_color() {
+        let rect = Rect::min_size(Point::ZERO, Size::all(20.0));
+        let width = BorderWidth::all(4.0);
+        let colors = [Color::RED, Color::GREEN, Color::BLUE, Color::BLACK];
+
+        let mut canvas = Canvas::new();
+        for (edge, color) in BorderEdge::ALL.into_iter().zip(colors) {
+            let mut curve = Curve::new();
+            curve.push_rect_border_edge(rect, BorderRadius::all(0.0), width, edge);
+            canvas.fill(curve, FillRule::NonZero, color);
+        }
+
+        let mut surface = skia_safe::surfaces::raster_n32_premul((20, 20)).unwrap();
+        surface.canvas().clear(skia_safe::Color::WHITE);
+        let mut fonts = SkiaFonts::new(None);
+        SkiaRenderer::draw_to(&mut fonts, surface.canvas(), &canvas, 1.0);
+
+        let pixels = surface.peek_pixels().unwrap();
+        assert_eq!(pixels.get_color((10, 1)), skia_safe::Color::RED);
+        assert_eq!(pixels.get_color((18, 10)), skia_safe::Color::GREEN);
+        assert_eq!(pixels.get_color((10, 18)), skia_safe::Color::BLUE);
+        assert_eq!(pixels.get_color((1, 10)), skia_safe::Color::BLACK);
+        assert_eq!(pixels.get_color((10, 10)), skia_safe::Color::WHITE);
+    }
+
+    #[test]
+    fn texture_is_rendered_before_it_is_sampled() {
+        let rect = Rect::min_size(Point::ZERO, Size::all(4.0));
+
+        // the texture is rendered in an overlay, which is drawn after the content sampling it
+        let mut canvas = Canvas::new();
+        let handle = canvas.overlay(0, |canvas| {
+            canvas.render_to_texture(rect.size(), |canvas| {
+                canvas.rect(rect, Color::RED);
+            })
+        });
+        canvas.rect(rect, Pattern::from(handle.image().clone()));
+
+        let mut surface = skia_safe::surfaces::raster_n32_premul((4, 4)).unwrap();
+        let mut fonts = SkiaFonts::new(None);
+        SkiaRenderer::draw_to(&mut fonts, surface.canvas(), &canvas, 1.0);
+
+        let pixels = surface.peek_pixels().unwrap();
+        assert_eq!(pixels.get_color((2, 2)), skia_safe::Color::RED);
+    }
+
+    #[test]
+    fn texture_is_rendered_in_physical_pixels() {
+        let rect = Rect::min_size(Point::ZERO, Size::all(2.0));
+
+        // the red rect ends in the middle of the second logical pixel
+        let mut canvas = Canvas::new();
+        let handle = canvas.render_to_texture(rect.size(), |canvas| {
+            canvas.rect(Rect::min_size(Point::ZERO, Size::new(1.5, 2.0)), Color::RED);
+        });
+        canvas.rect(rect, Pattern::from(handle.image().clone()));
+
+        let mut surface = skia_safe::surfaces::raster_n32_premul((4, 4)).unwrap();
+        surface.canvas().clear(skia_safe::Color::WHITE);
+        let mut fonts = SkiaFonts::new(None);
+        SkiaRenderer::draw_to(&mut fonts, surface.canvas(), &canvas, 2.0);
+
+        // at twice the scale the edge falls between two pixels, a texture of logical pixels
+        // would cover both of them by half
+        let pixels = surface.peek_pixels().unwrap();
+        assert_eq!(pixels.get_color((2, 1)), skia_safe::Color::RED);
+        assert_eq!(pixels.get_color((3, 1)), skia_safe::Color::WHITE);
+    }
+
+    #[test]
+    fn antialiased_edges_are_blended_in_linear_light() {
+        let mut canvas = Canvas::new();
+        canvas.rect(Rect::min_size(Point::ZERO, Size::all(4.0)), Color::WHITE);
+
+        // the black rect covers half of every pixel in the third column
+        canvas.rect(
+            Rect::min_size(Point::ZERO, Size::new(2.5, 4.0)),
+            Color::BLACK,
+        );
+
+        let info = SkiaRenderer::blend_info(4, 4, ColorSpace::Srgb);
+        let mut surface = skia_safe::surfaces::raster(&info, None, None).unwrap();
+        let mut fonts = SkiaFonts::new(None);
+        SkiaRenderer::draw_to(&mut fonts, surface.canvas(), &canvas, 1.0);
+
+        // read back as sRGB, like the frame is copied onto the window
+        let srgb = info
+            .with_color_type(skia_safe::ColorType::RGBA8888)
+            .with_color_space(skia_safe::ColorSpace::new_srgb());
+
+        let mut pixels = vec![0; 4 * 4 * 4];
+        assert!(surface.read_pixels(&srgb, &mut pixels, 4 * 4, (0, 0)));
+
+        let red = |x: usize, y: usize| pixels[(y * 4 + x) * 4];
+        assert_eq!(red(1, 1), 0);
+        assert_eq!(red(3, 1), 255);
+
+        // the edge reflects half the light of white, which is sRGB 188, not the 128 of
+        // blending the encoded components
+        let expected = Color::BLACK.blend_coverage(Color::WHITE, 0.5).r8();
+        assert!(red(2, 1).abs_diff(expected) <= 2, "{}", red(2, 1));
+    }
+
+    #[test]
+    fn image_handle_updates_write_into_same_texture() {
+        let rect = Rect::min_size(Point::ZERO, Size::all(2.0));
+        let red = ImageData::new([255, 0, 0, 255].repeat(4), 2, 2);
+        let blue = ImageData::new([0, 0, 255, 255].repeat(4), 2, 2);
+
+        let mut handle = ImageHandle::new(red);
+
+        let mut surface = skia_safe::surfaces::raster_n32_premul((2, 2)).unwrap();
+        let mut fonts = SkiaFonts::new(None);
+        let mut images = Images::default();
+
+        let mut draw = |images: &mut Images, handle: &ImageHandle| {
+            let mut canvas = Canvas::new();
+            canvas.rect(rect, Pattern::from(handle.image().clone()));
+
+            for primitive in canvas.primitives() {
+                let target = surface.canvas();
+                SkiaRenderer::draw_primitive(
+                    &mut fonts,
+                    images,
+                    target,
+                    primitive,
+                    Affine::IDENTITY,
+                );
+            }
+
+            let pixels = surface.peek_pixels().unwrap();
+            pixels.get_color((1, 1))
+        };
+
+        let texture_pixels = |images: &mut Images, handle: &ImageHandle| {
+            let texture = images.textures.get_mut(&handle.texture_id()).unwrap();
+            texture.surface.peek_pixels().unwrap().addr()
+        };
+
+        assert_eq!(draw(&mut images, &handle), skia_safe::Color::RED);
+        let before = texture_pixels(&mut images, &handle);
+
+        assert!(handle.update(&blue));
+        assert_eq!(draw(&mut images, &handle), skia_safe::Color::BLUE);
+
+        // the new pixels were written into the texture of the first frame
+        assert_eq!(images.textures.len(), 1);
+        assert_eq!(texture_pixels(&mut images, &handle), before);
+    }
+
+    #[test]
+    fn display_p3_gradient_is_not_clamped() {
+        let rect = Rect::min_size(Point::ZERO, Size::all(4.0));
+        let red = Color::display_p3(1.0, 0.0, 0.0);
+
+        let gradient = ConicGradient::new(rect.center(), [(0.0, red), (1.0, red)]);
+
+        let mut canvas = Canvas::new();
+        canvas.rect(rect, gradient);
+
+        let info = skia_safe::ImageInfo::new(
+            (4, 4),
+            skia_safe::ColorType::RGBA8888,
+            skia_safe::AlphaType::Premul,
+            SkiaRenderer::skia_color_space(ColorSpace::DisplayP3),
+        );
+
+        let mut surface = skia_safe::surfaces::raster(&info, None, None).unwrap();
+        let mut fonts = SkiaFonts::new(None);
+        let mut images = Images::default();
+
+        for primitive in canvas.primitives() {
+            let target = surface.canvas();
+            SkiaRenderer::draw_primitive(
+                &mut fonts,
+                &mut images,
+                target,
+                primitive,
+                Affine::IDENTITY,
+            );
+        }
+
+        // clamped to sRGB the red would be about (234, 51, 35) in Display P3
+        let pixels = surface.peek_pixels().unwrap();
+        let color = pixels.get_color((2, 2));
+        assert!(color.r() >= 253, "{:?}", color);
+        assert!(color.g() <= 2 && color.b() <= 2, "{:?}", color);
+    }
+
+    #[test]
+    fn grayscale_filter_keeps_luminance() {
+        let rect = Rect::min_size(Point::ZERO, Size::all(4.0));
+
+        let mut canvas = Canvas::new();
+        canvas.filtered(ColorMatrix::grayscale(), |canvas| {
+            canvas.rect(rect, Color::RED);
+        });
+
+        let mut surface = skia_safe::surfaces::raster_n32_premul((4, 4)).unwrap();
+        let mut fonts = SkiaFonts::new(None);
+        let mut images = Images::default();
+
+        for primitive in canvas.primitives() {
+            let target = surface.canvas();
+            SkiaRenderer::draw_primitive(
+                &mut fonts,
+                &mut images,
+                target,
+                primitive,
+                Affine::IDENTITY,
+            );
+        }
+
+        // the luminance of red is 0.2126, which is 54 out of 255
+        let pixels = surface.peek_pixels().unwrap();
+        let color = pixels.get_color((2, 2));
+        assert_eq!((color.r(), color.g(), color.b()), (54, 54, 54));
+    }
+
+    #[test]
+    fn half_opacity_layer_over_white() {
+        let rect = Rect::min_size(Point::ZERO, Size::all(4.0));
+
+        let mut canvas = Canvas::new();
+        canvas.rect(rect, Color::WHITE);
+
+        // the overlapping rects are faded as a unit, so they don't show through each other
+        canvas.filtered(ColorMatrix::opacity(0.5), |canvas| {
+            canvas.rect(rect, Color::RED);
+            canvas.rect(rect, Color::RED);
+        });
+
+        let mut surface = skia_safe::surfaces::raster_n32_premul((4, 4)).unwrap();
+        let mut fonts = SkiaFonts::new(None);
+        let mut images = Images::default();
+
+        for primitive in canvas.primitives() {
+            let target = surface.canvas();
+            SkiaRenderer::draw_primitive(
+                &mut fonts,
+                &mut images,
+                target,
+                primitive,
+                Affine::IDENTITY,
+            );
+        }
+
+        let pixels = surface.peek_pixels().unwrap();
+        let color = pixels.get_color((2, 2));
+        assert_eq!(color.r(), 255);
+        assert!(color.g().abs_diff(128) <= 1, "{:?}", color);
+        assert!(color.b().abs_diff(128) <= 1, "{:?}", color);
+    }
+
+    #[test]
+    fn multiply_blend_matches_color_blend() {
+        let rect = Rect::min_size(Point::ZERO, Size::all(4.0));
+        let gray = Color::rgb(0.5, 0.5, 0.5);
+        let orange = Color::rgba(1.0, 0.5, 0.0, 0.75);
+
+        let mut canvas = Canvas::new();
+        canvas.rect(rect, gray);
+        canvas.rect(
+            rect,
+            Paint {
+                shader: Shader::Solid(orange),
+                blend: BlendMode::Multiply,
+                ..Default::default()
+            },
+        );
+
+        let mut surface = skia_safe::surfaces::raster_n32_premul((4, 4)).unwrap();
+        let mut fonts = SkiaFonts::new(None);
+        let mut images = Images::default();
+
+        for primitive in canvas.primitives() {
+            let target = surface.canvas();
+            SkiaRenderer::draw_primitive(
+                &mut fonts,
+                &mut images,
+                target,
+                primitive,
+                Affine::IDENTITY,
+            );
+        }
+
+        // the renderer blends like `Color::blend` predicts
+        let [r, g, b, _] = orange.blend(gray, BlendMode::Multiply).to_rgba8();
+
+        let pixels = surface.peek_pixels().unwrap();
+        let color = pixels.get_color((2, 2));
+        assert!(color.r().abs_diff(r) <= 1, "{:?}", color);
+        assert!(color.g().abs_diff(g) <= 1, "{:?}", color);
+        assert!(color.b().abs_diff(b) <= 1, "{:?}", color);
+    }
+
+    #[test]
+    fn blended_layer_is_composited_over_the_backdrop() {
+        let rect = Rect::min_size(Point::ZERO, Size::all(4.0));
+        let left = Rect::min_size(Point::ZERO, Size::new(2.0, 4.0));
+        let gray = Color::rgb(0.5, 0.5, 0.5);
+        let orange = Color::rgba(1.0, 0.5, 0.0, 0.5);
+
+        let mut canvas = Canvas::new();
+        canvas.rect(rect, gray);
+
+        // the overlapping rects are drawn normally in the layer, which is then multiplied
+        canvas.blended(BlendMode::Multiply, |canvas| {
+            canvas.rect(left, orange);
+            canvas.rect(left, orange);
+        });
+
+        let mut surface = skia_safe::surfaces::raster_n32_premul((4, 4)).unwrap();
+        let mut fonts = SkiaFonts::new(None);
+        let mut images = Images::default();
+
+        for primitive in canvas.primitives() {
+            let target = surface.canvas();
+            SkiaRenderer::draw_primitive(
+                &mut fonts,
+                &mut images,
+                target,
+                primitive,
+                Affine::IDENTITY,
+            );
+        }
+
+        let layer = orange.blend(orange, BlendMode::SourceOver);
+        let [r, g, b, _] = layer.blend(gray, BlendMode::Multiply).to_rgba8();
+
+        let pixels = surface.peek_pixels().unwrap();
+        let color = pixels.get_color((1, 2));
+        assert!(color.r().abs_diff(r) <= 1, "{:?}", color);
+        assert!(color.g().abs_diff(g) <= 1, "{:?}", color);
+        assert!(color.b().abs_diff(b) <= 1, "{:?}", color);
+
+        // the backdrop is left alone where the layer is empty
+        let [r, g, b, _] = gray.to_rgba8();
+
+        let color = pixels.get_color((3, 2));
+        assert!(color.r().abs_diff(r) <= 1, "{:?}", color);
+        assert!(color.g().abs_diff(g) <= 1, "{:?}", color);
+        assert!(color.b().abs_diff(b) <= 1, "{:?}", color);
+    }
+
+    #[test]
+    fn mesh_colors_match_mesh_sample() {
+        let mesh = Mesh::triangle(
+            Vertex::new(Point::new(0.0, 0.0), Color::RED),
+            Vertex::new(Point::new(32.0, 0.0), Color::GREEN),
+            Vertex::new(Point::new(0.0, 32.0), Color::BLUE),
+        );
+
+        let mut canvas = Canvas::new();
+        canvas.mesh(mesh.clone());
+
+        let mut surface = skia_safe::surfaces::raster_n32_premul((32, 32)).unwrap();
+        let mut fonts = SkiaFonts::new(None);
+        let mut images = Images::default();
+
+        for primitive in canvas.primitives() {
+            let target = surface.canvas();
+            SkiaRenderer::draw_primitive(
+                &mut fonts,
+                &mut images,
+                target,
+                primitive,
+                Affine::IDENTITY,
+            );
+        }
+
+        let pixels = surface.peek_pixels().unwrap();
+
+        // the colors are interpolated across the triangle like `Mesh::sample` does
+        for (x, y) in [(2, 2), (8, 8), (20, 4), (4, 20), (12, 12)] {
+            let center = Point::new(x as f32 + 0.5, y as f32 + 0.5);
+            let [r, g, b, _] = mesh.sample(center).unwrap().to_rgba8();
+
+            let color = pixels.get_color((x, y));
+            assert!(color.r().abs_diff(r) <= 2, "{:?} at {x}, {y}", color);
+            assert!(color.g().abs_diff(g) <= 2, "{:?} at {x}, {y}", color);
+            assert!(color.b().abs_diff(b) <= 2, "{:?} at {x}, {y}", color);
+        }
+
+        // and nothing is drawn outside of it
+        assert_eq!(mesh.sample(Point::new(28.5, 28.5)), None);
+        assert_eq!(pixels.get_color((28, 28)), skia_safe::Color::TRANSPARENT);
+    }
+
+    #[test]
+    fn rounded_rect_corners_are_antialiased() {
+        let rect = Rect::min_size(Point::ZERO, Size::all(20.0));
+
+        let mut canvas = Canvas::new();
+        canvas.draw_rounded_rect(rect, 8.0, Color::RED, 0.0, Color::TRANSPARENT);
+
+        let mut surface = skia_safe::surfaces::raster_n32_premul((20, 20)).unwrap();
+        surface.canvas().clear(skia_safe::Color::TRANSPARENT);
+
+        let mut fonts = SkiaFonts::new(None);
+        let mut images = Images::default();
+
+        for primitive in canvas.primitives() {
+            let target = surface.canvas();
+            SkiaRenderer::draw_primitive(
+                &mut fonts,
+                &mut images,
+                target,
+                primitive,
+                Affine::IDENTITY,
+            );
+        }
+
+        let pixels = surface.peek_pixels().unwrap();
+        let alpha = |x, y| pixels.get_color((x, y)).a();
+
+        // the arc of the corner crosses the diagonal at 8 - 8 / sqrt(2), about 2.3, so the
+        // pixel there is partially covered, while the corner itself is empty
+        assert_eq!(alpha(0, 0), 0);
+        assert!(alpha(2, 2) > 0 && alpha(2, 2) < 255, "{}", alpha(2, 2));
+        assert_eq!(alpha(10, 10), 255);
+    }
+
+    #[test]
+    fn evicted_glyphs_render_identically() {
+        let rect = Rect::min_size(Point::ZERO, Size::new(128.0, 32.0));
+
+        let mut fonts = SkiaFonts::new(None);
+        let roboto = include_bytes!("../../ori-app/font/Roboto-Regular.ttf");
+        fonts.load(FontSource::Data(roboto.as_slice().into()), None);
+
+        let mut paragraph = Paragraph::new(1.0, TextAlign::Start, TextWrap::None);
+        paragraph.set_text(
+            "Glyphs",
+            FontAttributes {
+                size: 20.0,
+                ..Default::default()
+            },
+        );
+
+        let mut canvas = Canvas::new();
+        canvas.paragraph(paragraph, rect, rect);
+
+        let render = |fonts: &mut SkiaFonts| {
+            let mut surface = skia_safe::surfaces::raster_n32_premul((128, 32)).unwrap();
+            let mut images = Images::default();
+
+            for primitive in canvas.primitives() {
+                let target = surface.canvas();
+                SkiaRenderer::draw_primitive(
+                    fonts,
+                    &mut images,
+                    target,
+                    primitive,
+                    Affine::IDENTITY,
+                );
+            }
+
+            let pixels = surface.peek_pixels().unwrap();
+            pixels.bytes().unwrap().to_vec()
+        };
+
+        let before = render(&mut fonts);
+        assert!(before.iter().any(|&byte| byte != 0));
+        assert!(fonts.glyph_cache_stats().entries > 0);
+
+        // an empty cache evicts every glyph, they are rasterized again when drawn
+        let config = fonts.atlas_config();
+        fonts.set_atlas_config(GlyphAtlasConfig {
+            cache_size: 0,
+            ..config
+        });
+        skia_safe::graphics::purge_font_cache();
+        fonts.set_atlas_config(config);
+
+        assert_eq!(render(&mut fonts), before);
+    }
+
+    #[test]
+    fn font_weight_draws_heavier_glyphs() {
+        let rect = Rect::min_size(Point::ZERO, Size::new(64.0, 32.0));
+
+        let mut fonts = SkiaFonts::new(None);
+        let roboto = include_bytes!("../../ori-app/font/Roboto-Regular.ttf");
+        fonts.load(FontSource::Data(roboto.as_slice().into()), None);
+
+        // the attributes set by `Text::font_weight`, and the total coverage of the drawn text
+        let mut ink = |weight: FontWeight| {
+            let mut paragraph = Paragraph::new(1.0, TextAlign::Start, TextWrap::None);
+            paragraph.set_text(
+                "mmm",
+                FontAttributes {
+                    size: 20.0,
+                    weight,
+                    variations: vec![FontVariation::new("wght", weight.into())],
+                    ..Default::default()
+                },
+            );
+
+            let mut canvas = Canvas::new();
+            canvas.paragraph(paragraph, rect, rect);
+
+            let mut surface = skia_safe::surfaces::raster_n32_premul((64, 32)).unwrap();
+            let mut images = Images::default();
+
+            for primitive in canvas.primitives() {
+                let target = surface.canvas();
+                SkiaRenderer::draw_primitive(
+                    &mut fonts,
+                    &mut images,
+                    target,
+                    primitive,
+                    Affine::IDENTITY,
+                );
+            }
+
+            let pixels = surface.peek_pixels().unwrap();
+            let bytes = pixels.bytes().unwrap();
+            bytes.iter().map(|&byte| byte as u64).sum::<u64>()
+        };
+
+        // the bundled Roboto has a single weight, the `wght` axis is ignored and the bold
+        // weight is synthesized from the regular outlines
+        let regular = ink(FontWeight::NORMAL);
+        assert!(regular > 0);
+        assert!(ink(FontWeight::BOLD) > regular);
+    }
+
+    // draws `text` in black with `fonts` on a transparent surface
+    fn draw_text(fonts: &mut SkiaFonts, text: &str, family: FontFamily) -> skia_safe::Surface {
+        let rect = Rect::min_size(Point::ZERO, Size::new(64.0, 32.0));
+
+        let mut paragraph = Paragraph::new(1.0, TextAlign::Start, TextWrap::None);
+        paragraph.set_text(
+            text,
+            FontAttributes {
+                size: 24.0,
+                family,
+                color: Color::BLACK,
+                ..Default::default()
+            },
+        );
+
+        let mut canvas = Canvas::new();
+        canvas.paragraph(paragraph, rect, rect);
+
+        let mut surface = skia_safe::surfaces::raster_n32_premul((64, 32)).unwrap();
+        let mut images = Images::default();
+
+        for primitive in canvas.primitives() {
+            let target = surface.canvas();
+            SkiaRenderer::draw_primitive(fonts, &mut images, target, primitive, Affine::IDENTITY);
+        }
+
+        surface
+    }
+
+    #[test]
+    fn color_glyphs_are_drawn_in_color() {
+        // a COLR font, where `■` is a square drawn with a single red layer
+        let font = include_bytes!("../test-fonts/ColorSquare.ttf");
+
+        let mut fonts = SkiaFonts::new(None);
+        fonts.load(FontSource::Data(font.as_slice().into()), None);
+
+        let family = FontFamily::Name(String::from("Color Square"));
+        let mut surface = draw_text(&mut fonts, "\u{25a0}", family);
+
+        // the glyph ignores the black text color
+        let pixels = surface.peek_pixels().unwrap();
+        let color = pixels.get_color((8, 12));
+        assert_eq!(
+            (color.r(), color.g(), color.b(), color.a()),
+            (255, 0, 0, 255)
+        );
+    }
+
+    #[test]
+    fn aliased_glyphs_have_no_partial_coverage() {
+        let roboto = include_bytes!("../../ori-app/font/Roboto-Regular.ttf");
+
+        let mut fonts = SkiaFonts::new(None);
+        fonts.load(FontSource::Data(roboto.as_slice().into()), None);
+
+        let mut alphas = |anti_alias: AntiAlias| {
+            fonts.set_render_config(GlyphRenderConfig {
+                anti_alias,
+                ..Default::default()
+            });
+
+            let mut surface = draw_text(&mut fonts, "Aa", FontFamily::SansSerif);
+            let pixels = surface.peek_pixels().unwrap();
+            let bytes = pixels.bytes().unwrap().to_vec();
+
+            // the alpha of every pixel, n32 is either rgba or bgra
+            bytes.chunks(4).map(|pixel| pixel[3]).collect::<Vec<_>>()
+        };
+
+        let smooth = alphas(AntiAlias::Full);
+        assert!(smooth.iter().any(|&alpha| alpha > 0 && alpha < 255));
+
+        let aliased = alphas(AntiAlias::None);
+        assert!(aliased.contains(&255));
+        assert!(aliased.iter().all(|&alpha| alpha == 0 || alpha == 255));
+    }
+
+    #[test]
+    fn tabs_advance_to_tab_stops() {
+        let roboto_mono = include_bytes!("../../ori-app/font/RobotoMono-Regular.ttf");
+
+        let mut fonts = SkiaFonts::new(None);
+        fonts.load(FontSource::Data(roboto_mono.as_slice().into()), None);
+
+        // the left edge of the glyph of the byte at `index` in `text`
+        let mut left = |text: &str, index: usize| {
+            let mut paragraph = Paragraph::new(1.0, TextAlign::Start, TextWrap::None);
+            paragraph.tab_size = Some(4);
+            paragraph.set_text(
+                text,
+                FontAttributes {
+                    size: 20.0,
+                    family: FontFamily::Monospace,
+                    ..Default::default()
+                },
+            );
+
+            let lines = fonts.layout(&paragraph, f32::INFINITY);
+            let glyphs = &lines[0].glyphs;
+            let glyph = glyphs.iter().find(|glyph| glyph.range.start == index);
+            glyph.unwrap().bounds.min.x
+        };
+
+        let space = left(" x", 1);
+        assert!(space > 0.0);
+
+        // the tab keeps its single byte, so the index of the `x` is unchanged
+        assert!((left("a\tx", 2) - 4.0 * space).abs() < 0.01);
+        assert!((left("abcd\tx", 5) - 8.0 * space).abs() < 0.01);
+    }
+}
@@ -57,11 +57,12 @@ fn ui(data: &mut Data) -> impl View<Data> {
 
         stroke.stroke_curve(
             &curve,
-            Stroke {
+            &Stroke {
                 width: data.offset,
                 cap: data.cap,
                 join: data.join,
                 miter: 4.0,
+                ..Default::default()
             },
         );
 
@@ -0,0 +1,82 @@
+use std::{fs, path::PathBuf};
+
+use ori::prelude::*;
+
+#[derive(Default)]
+struct Data {
+    path: Option<PathBuf>,
+    contents: String,
+}
+
+// sent when a file picked in the dialog has been read
+struct FileLoaded {
+    path: PathBuf,
+    contents: String,
+}
+
+fn open_button() -> impl View<Data> {
+    on_click(button(text("Open file")).fancy(4.0), |cx, _| {
+        let dialog = FileDialog::new()
+            .title("Open a text file")
+            .filter("Text", &["txt", "md", "rs", "toml"]);
+
+        let picked = cx.open_file_dialog(dialog);
+
+        cx.spawn_async({
+            let proxy = cx.proxy();
+
+            async move {
+                // the path is `None` when the dialog is cancelled
+                let Some(path) = picked.await else {
+                    return;
+                };
+
+                match fs::read_to_string(&path) {
+                    Ok(contents) => proxy.cmd(FileLoaded { path, contents }),
+                    Err(err) => error!("failed to read {}: {}", path.display(), err),
+                }
+            }
+        });
+    })
+}
+
+fn ui(data: &mut Data) -> impl View<Data> {
+    let path = match data.path {
+        Some(ref path) => path.display().to_string(),
+        None => String::from("No file opened"),
+    };
+
+    let contents = text(&data.contents).font_family(FontFamily::Monospace);
+
+    vstack![
+        hstack![open_button(), text(path)],
+        expand(vscroll(pad(8.0, contents)))
+    ]
+    .align(Align::Start)
+}
+
+struct Delegate;
+
+impl AppDelegate<Data> for Delegate {
+    fn event(&mut self, cx: &mut DelegateCx<Data>, data: &mut Data, event: &Event) -> bool {
+        if let Some(loaded) = event.cmd::<FileLoaded>() {
+            data.path = Some(loaded.path.clone());
+            data.contents = loaded.contents.clone();
+            cx.rebuild();
+
+            return true;
+        }
+
+        false
+    }
+}
+
+fn main() {
+    ori::log::install().unwrap();
+
+    let window = Window::new().title("File Dialog (examples/file_dialog.rs)");
+
+    let app = App::build().window(window, ui).delegate(Delegate);
+
+    ori::run(app, &mut Data::default()).unwrap();
+}
@@ -0,0 +1,55 @@
+use std::{
+    fs::{self, File},
+    io::{BufWriter, Write},
+    path::PathBuf,
+};
+
+use ori::prelude::*;
+
+// Every input is written to this file as a line of json when the example is run,
+// running the example with `replay` plays the recorded inputs back instead.
+fn recording_path() -> PathBuf {
+    std::env::temp_dir().join("ori-input-recording.jsonl")
+}
+
+fn load_recording() -> Vec<RecordedInput> {
+    let Ok(recording) = fs::read_to_string(recording_path()) else {
+        return Vec::new();
+    };
+
+    let inputs = recording.lines().map(serde_json::from_str);
+    inputs.filter_map(Result::ok).collect()
+}
+
+fn ui(count: &mut u32) -> impl View<u32> {
+    let counter = button(text!("Clicked {} times", count)).fancy(4.0);
+    let counter = on_click(counter, |_, count: &mut u32| *count += 1);
+
+    center(vstack![counter, text_input().placeholder("Type something")])
+}
+
+fn main() {
+    ori::log::install().unwrap();
+
+    let window = Window::new()
+        .title("Record Input (examples/record_input.rs)")
+        .size(400, 300);
+
+    let mut app = App::build().window(window, ui);
+
+    if std::env::args().any(|arg| arg == "replay") {
+        app = app.replay(load_recording(), ReplayTiming::Recorded);
+    } else {
+        let mut file = BufWriter::new(File::create(recording_path()).unwrap());
+
+        // each input is written and flushed as it arrives, so a crash doesn't lose it
+        app = app.record_input(move |input| {
+            if let Ok(json) = serde_json::to_string(input) {
+                let _ = writeln!(file, "{}", json);
+                let _ = file.flush();
+            }
+        });
+    }
+
+    ori::run(app, &mut 0).unwrap();
+}
@@ -0,0 +1,92 @@
+use ori::prelude::*;
+
+// All windows of an application share the same data.
+//
+// When a view in any window calls `cx.rebuild()`, every window is rebuilt,
+// so a change made in one window is immediately visible in all the others.
+struct Data {
+    counter: i32,
+    step: i32,
+}
+
+// A command that resets the counter.
+//
+// Commands are sent to the delegates first, and if they aren't handled,
+// they are broadcast to every window.
+struct Reset;
+
+// The main window shows the counter and a button to increment it.
+fn main_window(data: &mut Data) -> impl View<Data> {
+    let increment = button(text!("Add {}", data.step)).fancy(4.0);
+
+    let increment = on_click(increment, |cx, data: &mut Data| {
+        data.counter += data.step;
+        cx.rebuild();
+    });
+
+    let content = vstack![text!("Counter: {}", data.counter), increment].gap(8.0);
+
+    center(content)
+}
+
+// The settings window changes the step of the counter and can reset it.
+fn settings_window(data: &mut Data) -> impl View<Data> {
+    let decrease = on_click(button(text("-")).fancy(4.0), |cx, data: &mut Data| {
+        data.step = i32::max(data.step - 1, 1);
+        cx.rebuild();
+    });
+
+    let increase = on_click(button(text("+")).fancy(4.0), |cx, data: &mut Data| {
+        data.step += 1;
+        cx.rebuild();
+    });
+
+    let reset = button(text("Reset")).color(Theme::DANGER).fancy(4.0);
+    let reset = on_click(reset, |cx, _: &mut Data| {
+        cx.cmd(Reset);
+    });
+
+    let step = hstack![decrease, text!("Step: {}", data.step), increase].gap(8.0);
+    let content = vstack![step, reset].gap(8.0);
+
+    center(content)
+}
+
+struct Delegate;
+
+impl AppDelegate<Data> for Delegate {
+    fn event(&mut self, cx: &mut DelegateCx<Data>, data: &mut Data, event: &Event) -> bool {
+        if event.is_cmd::<Reset>() {
+            data.counter = 0;
+
+            // this rebuilds both windows
+            cx.rebuild();
+
+            return true;
+        }
+
+        false
+    }
+}
+
+fn main() {
+    ori::log::install().unwrap();
+
+    let main = Window::new()
+        .title("Shared Counter (examples/shared_counter.rs)")
+        .size(400, 300);
+
+    let settings = Window::new().title("Settings").size(300, 200);
+
+    let app = App::build()
+        .window(main, main_window)
+        .window(settings, settings_window)
+        .delegate(Delegate);
+
+    let mut data = Data {
+        counter: 0,
+        step: 1,
+    };
+
+    ori::run(app, &mut data).unwrap();
+}
@@ -23,13 +23,15 @@ fn image(url: &str) -> impl View<String> {
     memo(String::from(url), |url: &mut String| {
         let url = url.clone();
 
-        suspense(async move {
-            match load_image(&url).await {
-                Ok(image) => Ok(image),
-                Err(err) => Err(text!("Error: {}", err)),
-            }
+        try_suspense(move || {
+            let url = url.clone();
+            async move { load_image(&url).await.map_err(|err| err.to_string()) }
         })
         .fallback(text!("Loading..."))
+        .error(|err, retry| {
+            let retry = on_click(button(text("Retry")), move |cx, _| retry.retry(cx));
+            vstack![text!("Error: {}", err), retry].gap(8.0)
+        })
     })
 }
 
@@ -18,6 +18,9 @@ enum Selection {
 // here we define a custom command we can send to the delegate
 struct RemoveTodo(usize);
 
+// a command that adds a todo from the text in the clipboard
+struct PasteTodo;
+
 // a todo
 #[derive(Debug)]
 struct Todo {
@@ -101,6 +104,17 @@ fn theme_button(data: &mut Data) -> impl View<Data> {
     })
 }
 
+fn paste_button() -> impl View<Data> {
+    let button = button(fa::icon("paste")).fancy(4.0);
+
+    let button = on_click(button, |cx, _: &mut Data| {
+        // the clipboard is read by the delegate
+        cx.cmd(PasteTodo);
+    });
+
+    tooltip(button, "Add a todo from the clipboard")
+}
+
 fn todo(index: usize, todo: &mut Todo) -> impl View<Todo> {
     let completed = checkbox(todo.completed).border_radius(12.0);
     let completed = on_click(completed, |cx, data: &mut Todo| {
@@ -170,7 +184,7 @@ fn active_count(data: &mut Data) -> impl View<Data> {
         format!("{} items left", active)
     };
 
-    text(active_text).font_size(16.0)
+    live_region(active_text.clone(), text(active_text).font_size(16.0))
 }
 
 fn selection_button(data: &Data, selection: Selection) -> impl View<Data> {
@@ -217,7 +231,11 @@ fn ui(data: &mut Data) -> impl View<Data> {
     .gap(0.0);
 
     let stack = vstack![title(), expand(rows)].gap(16.0);
-    let content = zstack![align((0.5, 0.2), stack), top_right(theme_button(data))];
+    let content = zstack![
+        align((0.5, 0.2), stack),
+        top_left(paste_button()),
+        top_right(theme_button(data)),
+    ];
 
     let style = if data.dark_mode {
         Theme::dark()
@@ -240,6 +258,18 @@ impl AppDelegate<Data> for Delegate {
             return true;
         }
 
+        if event.is_cmd::<PasteTodo>() {
+            let text = cx.clipboard().get();
+            let text = text.trim();
+
+            if !text.is_empty() {
+                data.input(text.to_string());
+                cx.rebuild();
+            }
+
+            return true;
+        }
+
         false
     }
 }
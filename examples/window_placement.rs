@@ -0,0 +1,61 @@
+use std::{fs, path::PathBuf};
+
+use ori::prelude::*;
+
+// The placement of the window is saved to this file when the window is closed,
+// and restored the next time the example is run.
+fn placement_path() -> PathBuf {
+    std::env::temp_dir().join("ori-window-placement.json")
+}
+
+fn load_placement() -> Option<WindowPlacement> {
+    let json = fs::read_to_string(placement_path()).ok()?;
+    serde_json::from_str(&json).ok()
+}
+
+fn ui(_data: &mut ()) -> impl View<()> {
+    center(text(
+        "Resize the window, close it, and run the example again",
+    ))
+}
+
+struct Delegate;
+
+impl AppDelegate<()> for Delegate {
+    fn event(&mut self, _cx: &mut DelegateCx<()>, _data: &mut (), _event: &Event) -> bool {
+        false
+    }
+
+    fn save_window_placement(
+        &mut self,
+        _cx: &mut DelegateCx<()>,
+        _data: &mut (),
+        _window_id: WindowId,
+        placement: &WindowPlacement,
+    ) {
+        match serde_json::to_string_pretty(placement) {
+            Ok(json) => {
+                if let Err(err) = fs::write(placement_path(), json) {
+                    error!("failed to save window placement: {}", err);
+                }
+            }
+            Err(err) => error!("failed to serialize window placement: {}", err),
+        }
+    }
+}
+
+fn main() {
+    ori::log::install().unwrap();
+
+    let mut window = Window::new()
+        .title("Window Placement (examples/window_placement.rs)")
+        .size(400, 300);
+
+    if let Some(placement) = load_placement() {
+        window = window.restore(placement);
+    }
+
+    let app = App::build().window(window, ui).delegate(Delegate);
+
+    ori::run(app, &mut ()).unwrap();
+}
@@ -37,22 +37,27 @@ pub mod log {
 pub mod prelude {
     //! Convenient imports for Ori.
 
-    pub use ori_app::{App, AppBuilder, AppCommand, AppDelegate, DelegateCx};
+    pub use ori_app::{
+        App, AppBuilder, AppCommand, AppDelegate, DelegateCx, RecordedInput, ReplayTiming,
+    };
 
     pub use ori_core::{
         canvas::{
             hex, hsl, hsla, hsv, hsva, okhsl, okhsla, okhsv, okhsva, oklab, oklaba, oklch, oklcha,
-            rgb, rgba, BlendMode, BorderRadius, BorderWidth, Canvas, Color, Curve, FillRule, Paint,
-            Pattern, Shader, Stroke, StrokeCap, StrokeJoin,
+            rgb, rgba, BlendMode, BorderColor, BorderPaint, BorderRadius, BorderWidth, Canvas,
+            Color, ColorSpace, Curve, FillRule, Paint, Pattern, Shader, Stroke, StrokeCap,
+            StrokeJoin,
         },
         clipboard::Clipboard,
         command::CommandProxy,
         context::{BaseCx, BuildCx, DrawCx, EventCx, LayoutCx, RebuildCx},
+        dialog::{FileDialog, FileFilter},
         event::{
-            Code, Event, Key, KeyPressed, Modifiers, PointerButton, PointerId, PointerMoved,
-            PointerPressed, PointerReleased, PointerScrolled, WindowCloseRequested,
+            Accelerator, Code, Event, Key, KeyPressed, Modifiers, PointerButton, PointerId,
+            PointerMoved, PointerPressed, PointerReleased, PointerScrolled, ScrollDelta, Shortcut,
+            ShortcutKey, WindowCloseRequested,
         },
-        image::{Image, ImageData, ImageId},
+        image::{Image, ImageData, ImageHandle, ImageId},
         layout::{
             pt, Affine, Align, Alignment, Axis, Justify, Matrix, Padding, Point, Rect, Size, Space,
             Vector, FILL,
@@ -70,11 +75,17 @@ pub mod prelude {
             any, pod, AnyView, BoxedView, Pod, PodSeq, SeqState, State, View, ViewSeq, ViewState,
         },
         views::*,
-        window::{Cursor, Pointer, Window, WindowId, WindowSizing},
+        window::{
+            Announcement, Cursor, Pointer, Politeness, Popup, Window, WindowId, WindowPlacement,
+            WindowSizing,
+        },
     };
 
     pub use ori_macro::{desktop, is_desktop, is_mobile, is_web, mobile, web, Build, Styled};
 
     #[cfg(feature = "image")]
     pub use ori_core::include_image;
+
+    #[cfg(feature = "svg")]
+    pub use ori_core::image::Svg;
 }